@@ -0,0 +1,50 @@
+use automata::prelude::*;
+use automata_learning::{
+    passive::{
+        sprout::{
+            finite_consistency_conflicts, sprout_parallel, sprout_with, PrefixRepresentation,
+            SproutStrategy,
+        },
+        FiniteSample,
+    },
+    random::random_words,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Builds a sample of `count` random words that are positive iff the number of a's is
+/// divisible by three.
+fn modulo_sample(count: usize) -> FiniteSample {
+    let alphabet = alphabet!(simple 'a', 'b', 'c');
+    let words = random_words(&alphabet, count, 12, 42).into_iter().map(|w| {
+        let positive = w.iter().filter(|sym| **sym == 'a').count() % 3 == 0;
+        (w, positive)
+    });
+    FiniteSample::new_finite(alphabet, words)
+}
+
+fn parallel_sprout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sprout");
+    group.sample_size(10);
+    for count in [1000, 3000] {
+        let sample = modulo_sample(count);
+        let conflicts = finite_consistency_conflicts(&sample, PrefixRepresentation::Dawg);
+        group.bench_with_input(
+            BenchmarkId::new("sequential", count),
+            &conflicts,
+            |b, conflicts| {
+                b.iter(|| sprout_with(conflicts, vec![], true, &SproutStrategy::default()))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parallel", count),
+            &conflicts,
+            |b, conflicts| {
+                b.iter(|| sprout_parallel(conflicts, vec![], true, &SproutStrategy::default()))
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parallel_sprout);
+criterion_main!(benches);
@@ -0,0 +1,218 @@
+use std::cell::{Cell, RefCell};
+
+use automata::{prelude::*, Map};
+use tracing::trace;
+
+use super::{LStarHypothesis, LStarOracle, OutputQueryOracle};
+
+/// Wraps an oracle and caches the answers to its membership queries, so that every word is
+/// posed to the oracle at most once. Counterexamples returned by equivalence queries are cached
+/// along with their output.
+///
+/// If the wrapped oracle answers output queries for Mealy machines, the answer on a word
+/// determines the outputs of all of its prefixes. The cache then stores the last output of
+/// every prefix, so later queries on prefixes and output queries on words whose prefixes are
+/// all known do not reach the oracle.
+///
+/// With the `persist` feature, the contents of the cache can be saved to disk and restored in a
+/// later run, which pays off when queries to the system under learning are slow.
+pub struct CachedOracle<O, C> {
+    oracle: O,
+    cache: RefCell<Map<Vec<char>, C>>,
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+}
+
+impl<O, C: Color> CachedOracle<O, C> {
+    /// Wraps `oracle` with an empty cache.
+    pub fn new(oracle: O) -> Self {
+        Self::with_entries(oracle, [])
+    }
+
+    /// Wraps `oracle` with a cache that contains the given answers.
+    pub fn with_entries<I: IntoIterator<Item = (Vec<char>, C)>>(oracle: O, entries: I) -> Self {
+        Self {
+            oracle,
+            cache: RefCell::new(entries.into_iter().collect()),
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+
+    /// Returns the wrapped oracle.
+    pub fn oracle(&self) -> &O {
+        &self.oracle
+    }
+
+    /// Returns the number of cached words.
+    pub fn len(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Returns `true` if no word is cached.
+    pub fn is_empty(&self) -> bool {
+        self.cache.borrow().is_empty()
+    }
+
+    /// Returns the number of queries that were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// Returns the number of queries that were passed on to the wrapped oracle.
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+
+    /// Returns all cached words with their outputs.
+    pub fn entries(&self) -> Vec<(Vec<char>, C)> {
+        self.cache
+            .borrow()
+            .iter()
+            .map(|(w, c)| (w.clone(), c.clone()))
+            .collect()
+    }
+
+    /// Returns the cached output for `word` or obtains it with `query` on a miss.
+    fn lookup(&self, word: Vec<char>, query: impl FnOnce(&[char]) -> C) -> C {
+        if let Some(output) = self.cache.borrow().get(&word) {
+            self.hits.set(self.hits.get() + 1);
+            return output.clone();
+        }
+        self.misses.set(self.misses.get() + 1);
+        let output = query(&word);
+        trace!("Caching output {} for {}", output.show(), word.as_string());
+        self.cache.borrow_mut().insert(word, output.clone());
+        output
+    }
+}
+
+#[cfg(feature = "persist")]
+impl<O, C> CachedOracle<O, C>
+where
+    C: Color + serde::Serialize + serde::de::DeserializeOwned,
+{
+    /// Saves the contents of the cache to the file at `path`.
+    pub fn save<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> Result<(), crate::formats::PersistError> {
+        crate::formats::save(&self.entries(), path)
+    }
+
+    /// Wraps `oracle` with a cache that is restored from the file at `path`, which must have
+    /// been written by [`CachedOracle::save`].
+    pub fn load<P: AsRef<std::path::Path>>(
+        oracle: O,
+        path: P,
+    ) -> Result<Self, crate::formats::PersistError> {
+        let entries: Vec<(Vec<char>, C)> = crate::formats::load(path)?;
+        Ok(Self::with_entries(oracle, entries))
+    }
+}
+
+impl<H, O> LStarOracle<H> for CachedOracle<O, H::Color>
+where
+    H: LStarHypothesis<Alphabet = CharAlphabet>,
+    O: LStarOracle<H>,
+{
+    fn output<W: FiniteWord<SymbolOf<H>>>(&self, word: W) -> H::Color {
+        self.lookup(word.to_vec(), |w| self.oracle.output(w))
+    }
+
+    fn alphabet(&self) -> H::Alphabet {
+        self.oracle.alphabet()
+    }
+
+    fn equivalence(&self, hypothesis: &H) -> Result<(), (Vec<SymbolOf<H>>, H::Color)> {
+        self.oracle.equivalence(hypothesis).map_err(|(w, c)| {
+            self.cache.borrow_mut().insert(w.clone(), c.clone());
+            (w, c)
+        })
+    }
+}
+
+impl<O, C> OutputQueryOracle<C> for CachedOracle<O, C>
+where
+    O: OutputQueryOracle<C>,
+    C: Color,
+{
+    fn alphabet(&self) -> CharAlphabet {
+        self.oracle.alphabet()
+    }
+
+    fn output_word(&self, input: &[char]) -> Vec<C> {
+        let known: Option<Vec<C>> = {
+            let cache = self.cache.borrow();
+            (1..=input.len())
+                .map(|i| cache.get(&input[..i]).cloned())
+                .collect()
+        };
+        if let Some(outputs) = known {
+            self.hits.set(self.hits.get() + 1);
+            return outputs;
+        }
+
+        self.misses.set(self.misses.get() + 1);
+        let outputs = self.oracle.output_word(input);
+        assert_eq!(
+            outputs.len(),
+            input.len(),
+            "Output word has the wrong length"
+        );
+        let mut cache = self.cache.borrow_mut();
+        for (i, output) in outputs.iter().enumerate() {
+            cache.insert(input[..=i].to_vec(), output.clone());
+        }
+        outputs
+    }
+
+    fn equivalence(&self, hypothesis: &MealyMachine<CharAlphabet, C>) -> Result<(), Vec<char>> {
+        self.oracle.equivalence(hypothesis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::CachedOracle;
+    use crate::active::{LStar, LStarOracle, MealyOracle, OutputQueryOracle};
+
+    #[test]
+    fn cached_oracle() {
+        let target = NTS::builder()
+            .with_transitions([
+                (0, 'a', 1, 1),
+                (0, 'b', 0, 0),
+                (1, 'a', 0, 0),
+                (1, 'b', 1, 1),
+            ])
+            .into_mealy_machine(0);
+        let alphabet = target.alphabet().clone();
+        let cached = CachedOracle::new(MealyOracle::new(target, None));
+
+        // the output word of abab determines the outputs of all of its prefixes
+        assert_eq!(cached.output_word(&['a', 'b', 'a', 'b']), vec![1, 1, 0, 0]);
+        assert_eq!(cached.len(), 4);
+        assert_eq!(cached.output_word(&['a', 'b']), vec![1, 1]);
+        assert_eq!(LStarOracle::<MealyMachine<_, _>>::output(&cached, "aba"), 0);
+        assert_eq!((cached.hits(), cached.misses()), (2, 1));
+
+        let mut learner = LStar::for_mealy(alphabet, cached);
+        assert_eq!(learner.infer().size(), 2);
+        assert!(learner.oracle().len() > 4);
+
+        #[cfg(feature = "persist")]
+        {
+            let cached = learner.oracle();
+            let path = std::env::temp_dir().join(format!("lama-cache-{}.bin", std::process::id()));
+            cached.save(&path).unwrap();
+            let restored = CachedOracle::load(cached.oracle().clone(), &path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(restored.len(), cached.len());
+            assert_eq!(restored.output_word(&['b', 'a']), vec![0, 1]);
+            assert_eq!(restored.misses(), 0);
+        }
+    }
+}
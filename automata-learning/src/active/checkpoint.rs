@@ -0,0 +1,167 @@
+//! Checkpoints of L* runs, so that a long run against a slow or flaky target can be resumed
+//! after a crash. With [`LStar::checkpoint_to`], a [`Checkpoint`] is written to a directory
+//! after each equivalence query, and [`LStar::resume_from`] continues from the latest one in a
+//! directory. Checkpoints are stored with [`crate::formats::save`].
+
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use automata::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use super::{LStar, LStarHypothesis, LStarOracle};
+use crate::{
+    budget::QueryCounts,
+    formats::{load, save, PersistError, TsRepr},
+};
+
+/// The state of an L* run after an equivalence query. The hypothesis is only stored for
+/// inspection, with its colors rendered as strings, as it is rebuilt from the table when the
+/// run is resumed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint<C> {
+    /// The round after which the checkpoint was taken.
+    pub round: usize,
+    /// The access words of the observation table.
+    pub rows: Vec<Vec<char>>,
+    /// The suffixes of the observation table.
+    pub suffixes: Vec<Vec<char>>,
+    /// All membership queries with their answers.
+    pub queries: Vec<(Vec<char>, C)>,
+    /// The queries that were posed so far.
+    pub counts: QueryCounts,
+    /// The hypothesis that was tested in the equivalence query.
+    pub hypothesis: TsRepr<String, String>,
+}
+
+impl<C: Color> Checkpoint<C> {
+    /// Takes a checkpoint of `lstar` after the given round, in which `hypothesis` was tested.
+    pub fn of<D, T>(lstar: &LStar<D, T>, round: usize, hypothesis: &D) -> Self
+    where
+        D: LStarHypothesis<Alphabet = CharAlphabet, Color = C>,
+        T: LStarOracle<D>,
+    {
+        Self {
+            round,
+            rows: lstar.table().rows().to_vec(),
+            suffixes: lstar.table().suffixes().to_vec(),
+            queries: lstar
+                .queries()
+                .entries()
+                .map(|(w, c)| (w.clone(), c.clone()))
+                .collect(),
+            counts: lstar.query_counts(),
+            hypothesis: TsRepr::from_ts_mapped(hypothesis, |q| q.show(), |c| c.show()),
+        }
+    }
+}
+
+fn checkpoint_path(directory: &Path, round: usize) -> PathBuf {
+    directory.join(format!("checkpoint-{round:06}.bin"))
+}
+
+/// Loads the checkpoint with the highest round from `directory`, if there is one.
+pub fn latest_checkpoint<C: DeserializeOwned>(
+    directory: impl AsRef<Path>,
+) -> Result<Option<Checkpoint<C>>, PersistError> {
+    let mut latest: Option<(usize, PathBuf)> = None;
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        let round = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("checkpoint-")?.strip_suffix(".bin"))
+            .and_then(|round| round.parse().ok());
+        if let Some(round) = round {
+            if latest.as_ref().map_or(true, |(r, _)| round > *r) {
+                latest = Some((round, path));
+            }
+        }
+    }
+    latest.map(|(_, path)| load(path)).transpose()
+}
+
+impl<D, T> LStar<D, T>
+where
+    D: LStarHypothesis<Alphabet = CharAlphabet> + 'static,
+    D::Color: Serialize + DeserializeOwned,
+    T: LStarOracle<D> + 'static,
+{
+    /// Writes a [`Checkpoint`] into `directory`, which must exist, after each equivalence
+    /// query. Failing to write a checkpoint is logged, but does not stop the run.
+    pub fn checkpoint_to<P: Into<PathBuf>>(self, directory: P) -> Self {
+        let directory = directory.into();
+        self.on_round(Rc::new(
+            move |lstar: &Self, round: usize, hypothesis: &D| {
+                let path = checkpoint_path(&directory, round);
+                match save(&Checkpoint::of(lstar, round, hypothesis), &path) {
+                    Ok(()) => debug!("Wrote checkpoint {}", path.display()),
+                    Err(err) => warn!("Could not write checkpoint {}: {err}", path.display()),
+                }
+            },
+        ))
+    }
+
+    /// Continues from the latest checkpoint in `directory`, if there is one. The oracle is not
+    /// asked again for the membership queries that are stored in the checkpoint.
+    pub fn resume_from<P: AsRef<Path>>(mut self, directory: P) -> Result<Self, PersistError> {
+        if let Some(checkpoint) = latest_checkpoint::<D::Color>(directory)? {
+            debug!("Resuming from checkpoint after round {}", checkpoint.round);
+            self.restore(
+                checkpoint.rows,
+                checkpoint.suffixes,
+                checkpoint.queries,
+                checkpoint.counts,
+            );
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::latest_checkpoint;
+    use crate::active::{DFAOracle, LStar};
+
+    #[test]
+    fn checkpoint_and_resume() {
+        // words whose number of a's is divisible by three
+        let mut target = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let states = [true, false, false].map(|accepting| target.add_state(accepting));
+        for i in 0..3 {
+            target.add_edge(states[i], 'a', states[(i + 1) % 3], Void);
+            target.add_edge(states[i], 'b', states[i], Void);
+        }
+        let alphabet = target.alphabet().clone();
+        let directory =
+            std::env::temp_dir().join(format!("lama-checkpoints-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let mut lstar = LStar::for_dfa(alphabet.clone(), DFAOracle::new(target.clone()))
+            .checkpoint_to(&directory);
+        let learned = lstar.infer();
+        let checkpoint = latest_checkpoint::<bool>(&directory)
+            .unwrap()
+            .expect("A checkpoint must have been written");
+        assert_eq!(checkpoint.counts, lstar.query_counts());
+        assert_eq!(checkpoint.rows, lstar.table().rows());
+        assert_eq!(checkpoint.hypothesis.states.len(), learned.size());
+
+        let mut resumed = LStar::for_dfa(alphabet, DFAOracle::new(target))
+            .resume_from(&directory)
+            .unwrap();
+        let relearned = resumed.infer();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(relearned.moore_witness_non_bisimilarity(learned).is_none());
+        // all membership queries are known from the checkpoint
+        assert_eq!(
+            resumed.query_counts().membership,
+            checkpoint.counts.membership
+        );
+    }
+}
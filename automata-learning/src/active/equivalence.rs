@@ -0,0 +1,556 @@
+//! Ready-made equivalence oracles. An [`EquivalenceOracle`] only searches for counterexamples
+//! and obtains the output of the target from a membership oracle, so it can be combined with
+//! any source of membership queries through a [`Teacher`]. The following oracles are provided:
+//! - [`PerfectOracle`] knows the target and returns a shortest word from the symmetric
+//!   difference of target and hypothesis,
+//! - [`RandomSampling`] tests a number of random words drawn from a [`WordDistribution`],
+//! - [`WMethod`] and [`WpMethod`] run conformance tests that find a counterexample whenever
+//!   the target has at most `depth` states more than the hypothesis.
+//!
+//! The conformance tests can also be exported as a test suite for a Mealy machine with
+//! [`WMethod::test_suite`] and [`WpMethod::test_suite`], which pairs every input word with the
+//! outputs that the machine expects.
+
+use std::{cell::RefCell, collections::VecDeque};
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+
+use crate::{
+    analysis::Separation,
+    random::{random_word, Rng},
+};
+
+use super::{LStarHypothesis, LStarOracle};
+
+type Word = Vec<char>;
+
+/// Searches for counterexamples to a hypothesis, see the module documentation.
+pub trait EquivalenceOracle<H: LStarHypothesis<Alphabet = CharAlphabet>> {
+    /// Returns a word on which `hypothesis` and the target disagree, if one is found. The output
+    /// of the target on a word is given by `output`.
+    fn counterexample(&self, hypothesis: &H, output: &dyn Fn(&[char]) -> H::Color) -> Option<Word>;
+}
+
+/// A minimally adequate teacher that answers membership queries with a function and
+/// equivalence queries with an [`EquivalenceOracle`]. If the equivalence oracle finds no
+/// counterexample, the hypothesis is accepted.
+#[derive(Clone)]
+pub struct Teacher<M, E> {
+    alphabet: CharAlphabet,
+    membership: M,
+    equivalence: E,
+}
+
+impl<M, E> Teacher<M, E> {
+    /// Creates a teacher over `alphabet` from a membership function and an equivalence oracle.
+    pub fn new(alphabet: CharAlphabet, membership: M, equivalence: E) -> Self {
+        Self {
+            alphabet,
+            membership,
+            equivalence,
+        }
+    }
+}
+
+impl<H, M, E> LStarOracle<H> for Teacher<M, E>
+where
+    H: LStarHypothesis<Alphabet = CharAlphabet>,
+    M: Fn(&[char]) -> H::Color,
+    E: EquivalenceOracle<H>,
+{
+    fn output<W: FiniteWord<SymbolOf<H>>>(&self, word: W) -> H::Color {
+        (self.membership)(&word.to_vec())
+    }
+
+    fn alphabet(&self) -> H::Alphabet {
+        self.alphabet.clone()
+    }
+
+    fn equivalence(&self, hypothesis: &H) -> Result<(), (Vec<SymbolOf<H>>, H::Color)> {
+        match self
+            .equivalence
+            .counterexample(hypothesis, &self.membership)
+        {
+            Some(word) => {
+                let color = (self.membership)(&word);
+                Err((word, color))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks whether hypotheses of type `H` assign an output to the empty word, which is not the
+/// case for Mealy machines.
+fn defines_empty<H: LStarHypothesis>(alphabet: &H::Alphabet) -> bool {
+    H::mandatory_experiments(alphabet)
+        .into_iter()
+        .any(|e| e.is_empty())
+}
+
+/// Returns the first of `words` on which `hypothesis` and the target disagree.
+fn first_disagreement<H, I>(
+    hypothesis: &H,
+    output: &dyn Fn(&[char]) -> H::Color,
+    words: I,
+) -> Option<Word>
+where
+    H: LStarHypothesis<Alphabet = CharAlphabet>,
+    I: IntoIterator<Item = Word>,
+{
+    let empty = defines_empty::<H>(hypothesis.alphabet());
+    words
+        .into_iter()
+        .filter(|w| empty || !w.is_empty())
+        .find(|w| hypothesis.transform(w) != output(w))
+}
+
+/// An equivalence oracle that knows the target. Can also be used as an [`LStarOracle`] on its
+/// own, in which case membership queries are answered by the target as well.
+#[derive(Clone)]
+pub struct PerfectOracle<H> {
+    target: H,
+}
+
+impl<H: LStarHypothesis<Alphabet = CharAlphabet>> PerfectOracle<H> {
+    /// Creates an oracle for the given target, which must be complete.
+    pub fn new(target: H) -> Self {
+        Self { target }
+    }
+
+    /// Returns a shortest word on which `hypothesis` and the target disagree. It is found with a
+    /// breadth-first search through the product of both, so every pair of states is visited
+    /// at most once.
+    pub fn witness(&self, hypothesis: &H) -> Option<Word> {
+        let alphabet = self.target.alphabet();
+        if defines_empty::<H>(alphabet) && self.target.transform(&[]) != hypothesis.transform(&[]) {
+            return Some(vec![]);
+        }
+
+        let start = (self.target.initial(), hypothesis.initial());
+        let mut seen = Set::from_iter([start]);
+        let mut queue = VecDeque::from([(start, vec![])]);
+        while let Some(((p, q), word)) = queue.pop_front() {
+            for sym in alphabet.universe() {
+                let mut next = word.clone();
+                next.push(sym);
+                if self.target.transform(&next) != hypothesis.transform(&next) {
+                    return Some(next);
+                }
+                let successors = (
+                    self.target
+                        .successor_index(p, sym)
+                        .expect("Target must be complete"),
+                    hypothesis
+                        .successor_index(q, sym)
+                        .expect("Hypothesis must be complete"),
+                );
+                if seen.insert(successors) {
+                    queue.push_back((successors, next));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<H: LStarHypothesis<Alphabet = CharAlphabet>> EquivalenceOracle<H> for PerfectOracle<H> {
+    fn counterexample(
+        &self,
+        hypothesis: &H,
+        _output: &dyn Fn(&[char]) -> H::Color,
+    ) -> Option<Word> {
+        self.witness(hypothesis)
+    }
+}
+
+impl<H: LStarHypothesis<Alphabet = CharAlphabet>> LStarOracle<H> for PerfectOracle<H> {
+    fn output<W: FiniteWord<SymbolOf<H>>>(&self, word: W) -> H::Color {
+        self.target.transform(&word.to_vec())
+    }
+
+    fn alphabet(&self) -> H::Alphabet {
+        self.target.alphabet().clone()
+    }
+
+    fn equivalence(&self, hypothesis: &H) -> Result<(), (Vec<SymbolOf<H>>, H::Color)> {
+        match self.witness(hypothesis) {
+            Some(word) => {
+                let color = self.target.transform(&word);
+                Err((word, color))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// The distribution of the words that are tested by [`RandomSampling`]. In both cases, the
+/// symbols are drawn uniformly from the alphabet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WordDistribution {
+    /// The length is drawn uniformly from `min..=max`.
+    Uniform { min: usize, max: usize },
+    /// The length follows a geometric distribution with the given mean, i.e. after each symbol
+    /// the word ends with probability `1 / (mean + 1)`.
+    Geometric { mean: f64 },
+}
+
+impl WordDistribution {
+    /// Draws a random word over `alphabet`.
+    pub fn sample(&self, alphabet: &CharAlphabet, rng: &mut Rng) -> Word {
+        let length = match *self {
+            WordDistribution::Uniform { min, max } => {
+                assert!(min <= max, "Empty range of lengths");
+                min + rng.below(max - min + 1)
+            }
+            WordDistribution::Geometric { mean } => {
+                let stop = 1.0 / (mean.max(0.0) + 1.0);
+                let mut length = 0;
+                while rng.unit() >= stop {
+                    length += 1;
+                }
+                length
+            }
+        };
+        random_word(alphabet, length, rng)
+    }
+}
+
+/// An equivalence oracle that tests a fixed number of random words per equivalence query. As
+/// it may miss counterexamples, the learned automaton is only correct with high probability.
+/// Successive queries draw different words, but the sequence only depends on the seed.
+#[derive(Debug, Clone)]
+pub struct RandomSampling {
+    samples: usize,
+    distribution: WordDistribution,
+    rng: RefCell<Rng>,
+}
+
+impl RandomSampling {
+    /// Creates an oracle that tests `samples` words drawn from `distribution`, the seed is 0.
+    pub fn new(samples: usize, distribution: WordDistribution) -> Self {
+        Self {
+            samples,
+            distribution,
+            rng: RefCell::new(Rng::seeded(0)),
+        }
+    }
+
+    /// Sets the seed of the random number generator.
+    pub fn seed(self, seed: u64) -> Self {
+        Self {
+            rng: RefCell::new(Rng::seeded(seed)),
+            ..self
+        }
+    }
+}
+
+impl<H: LStarHypothesis<Alphabet = CharAlphabet>> EquivalenceOracle<H> for RandomSampling {
+    fn counterexample(&self, hypothesis: &H, output: &dyn Fn(&[char]) -> H::Color) -> Option<Word> {
+        let mut rng = self.rng.borrow_mut();
+        let words = (0..self.samples)
+            .map(|_| self.distribution.sample(hypothesis.alphabet(), &mut rng))
+            .collect_vec();
+        first_disagreement(hypothesis, output, words)
+    }
+}
+
+/// Returns the access words of all states of `hypothesis` in length-lexicographic order, each
+/// together with the state it reaches.
+fn state_cover<H>(hypothesis: &H) -> Vec<(H::StateIndex, Word)>
+where
+    H: LStarHypothesis<Alphabet = CharAlphabet>,
+{
+    let initial = hypothesis.initial();
+    let mut seen = Set::from_iter([initial]);
+    let mut queue = VecDeque::from([(initial, vec![])]);
+    let mut out = vec![];
+    while let Some((q, word)) = queue.pop_front() {
+        for sym in hypothesis.alphabet().universe() {
+            let p = hypothesis
+                .successor_index(q, sym)
+                .expect("Hypothesis must be complete");
+            if seen.insert(p) {
+                let mut next = word.clone();
+                next.push(sym);
+                queue.push_back((p, next));
+            }
+        }
+        out.push((q, word));
+    }
+    out
+}
+
+/// Computes the identification set of each state, which consists of the mandatory experiments
+/// and one suffix that separates the state from each other state.
+fn identification_sets<H>(
+    hypothesis: &H,
+    cover: &[(H::StateIndex, Word)],
+) -> Map<H::StateIndex, Vec<Word>>
+where
+    H: LStarHypothesis<Alphabet = CharAlphabet>,
+{
+    let mandatory: Set<Word> = H::mandatory_experiments(hypothesis.alphabet())
+        .into_iter()
+        .collect();
+    let mut sets: Map<_, _> = cover.iter().map(|(q, _)| (*q, mandatory.clone())).collect();
+    for (i, (p, _)) in cover.iter().enumerate() {
+        for (q, _) in &cover[i + 1..] {
+            if let Some(suffix) = hypothesis.separating_word(*p, *q) {
+                sets.get_mut(p).unwrap().insert(suffix.clone());
+                sets.get_mut(q).unwrap().insert(suffix);
+            }
+        }
+    }
+    sets.into_iter()
+        .map(|(q, set)| (q, set.into_iter().sorted().collect()))
+        .collect()
+}
+
+/// Returns all words over `alphabet` of length at most `depth`.
+fn words_up_to(alphabet: &CharAlphabet, depth: usize) -> Vec<Word> {
+    let mut out = vec![vec![]];
+    let mut layer = vec![vec![]];
+    for _ in 0..depth {
+        layer = layer
+            .iter()
+            .flat_map(|w: &Word| {
+                alphabet.universe().map(move |sym| {
+                    let mut next = w.clone();
+                    next.push(sym);
+                    next
+                })
+            })
+            .collect();
+        out.extend(layer.iter().cloned());
+    }
+    out
+}
+
+/// Returns the one letter extensions of the access words that are not access words
+/// themselves.
+fn proper_extensions(alphabet: &CharAlphabet, cover: &[(impl Sized, Word)]) -> Vec<Word> {
+    let access: Set<&Word> = cover.iter().map(|(_, w)| w).collect();
+    cover
+        .iter()
+        .flat_map(|(_, w)| {
+            alphabet.universe().map(move |sym| {
+                let mut next = w.clone();
+                next.push(sym);
+                next
+            })
+        })
+        .filter(|w| !access.contains(w))
+        .collect()
+}
+
+/// The W-method of Chow and Vasilevskii. It tests all words `p m w`, where `p` is an access
+/// word or a one letter extension of one, `m` has length at most `depth` and `w` is taken from
+/// a characterizing set of the hypothesis. Finds a counterexample whenever there is one and the
+/// target has at most `depth` states more than the hypothesis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WMethod {
+    depth: usize,
+}
+
+impl WMethod {
+    /// Creates a W-method oracle with the given depth bound.
+    pub fn new(depth: usize) -> Self {
+        Self { depth }
+    }
+
+    /// Returns the words that are tested for `hypothesis`, in the order in which they are
+    /// tested.
+    pub fn tests<'a, H>(&self, hypothesis: &'a H) -> impl Iterator<Item = Word> + 'a
+    where
+        H: LStarHypothesis<Alphabet = CharAlphabet>,
+    {
+        let alphabet = hypothesis.alphabet();
+        let cover = state_cover(hypothesis);
+        let characterizing = identification_sets(hypothesis, &cover)
+            .into_values()
+            .flatten()
+            .collect::<Set<_>>()
+            .into_iter()
+            .sorted()
+            .collect_vec();
+        let middle = words_up_to(alphabet, self.depth);
+
+        let prefixes = cover
+            .iter()
+            .map(|(_, w)| w.clone())
+            .chain(proper_extensions(alphabet, &cover))
+            .collect_vec();
+        prefixes
+            .into_iter()
+            .cartesian_product(middle)
+            .cartesian_product(characterizing)
+            .map(|((p, m), w)| [p, m, w].concat())
+    }
+
+    /// Generates a conformance test suite from a Mealy machine, e.g. a learned model, which
+    /// consists of the tested words together with the outputs that the machine produces on
+    /// them. An implementation that passes all tests and has at most `depth` states more than
+    /// the machine is equivalent to it.
+    pub fn test_suite<'a, C: Color + Default>(
+        &self,
+        machine: &'a MealyMachine<CharAlphabet, C>,
+    ) -> impl Iterator<Item = (Word, Vec<C>)> + 'a {
+        self.tests(machine)
+            .map(move |word| (word.clone(), mealy_outputs(machine, &word)))
+    }
+}
+
+/// Returns the outputs that a complete Mealy machine produces on `word`.
+fn mealy_outputs<C: Color>(machine: &MealyMachine<CharAlphabet, C>, word: &[char]) -> Vec<C> {
+    (1..=word.len())
+        .map(|i| {
+            machine
+                .last_edge_color(&word[..i])
+                .expect("Mealy machine must be complete")
+        })
+        .collect()
+}
+
+impl<H: LStarHypothesis<Alphabet = CharAlphabet>> EquivalenceOracle<H> for WMethod {
+    fn counterexample(&self, hypothesis: &H, output: &dyn Fn(&[char]) -> H::Color) -> Option<Word> {
+        first_disagreement(hypothesis, output, self.tests(hypothesis))
+    }
+}
+
+/// The partial W-method of Fujiwara et al., which needs fewer tests than the [`WMethod`] with
+/// the same guarantee. Access words are followed by the full characterizing set, but the one
+/// letter extensions are only followed by the identification set of the state they reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WpMethod {
+    depth: usize,
+}
+
+impl WpMethod {
+    /// Creates a Wp-method oracle with the given depth bound.
+    pub fn new(depth: usize) -> Self {
+        Self { depth }
+    }
+
+    /// Returns the words that are tested for `hypothesis`, in the order in which they are
+    /// tested.
+    pub fn tests<'a, H>(&self, hypothesis: &'a H) -> impl Iterator<Item = Word> + 'a
+    where
+        H: LStarHypothesis<Alphabet = CharAlphabet>,
+    {
+        let alphabet = hypothesis.alphabet();
+        let cover = state_cover(hypothesis);
+        let identification = identification_sets(hypothesis, &cover);
+        let characterizing = identification
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<Set<_>>()
+            .into_iter()
+            .sorted()
+            .collect_vec();
+        let middle = words_up_to(alphabet, self.depth);
+
+        let first = cover
+            .iter()
+            .cartesian_product(&middle)
+            .cartesian_product(&characterizing)
+            .map(|(((_, p), m), w)| [p.as_slice(), m.as_slice(), w.as_slice()].concat())
+            .collect_vec();
+        let second = proper_extensions(alphabet, &cover)
+            .into_iter()
+            .cartesian_product(middle)
+            .flat_map(move |(p, m)| {
+                let prefix = [p, m].concat();
+                let state = hypothesis
+                    .reached_state_index(&prefix)
+                    .expect("Hypothesis must be complete");
+                identification[&state]
+                    .iter()
+                    .map(|w| [prefix.as_slice(), w.as_slice()].concat())
+                    .collect_vec()
+            });
+        first.into_iter().chain(second)
+    }
+
+    /// Generates a conformance test suite from a Mealy machine like
+    /// [`WMethod::test_suite`], but with the tests of the Wp-method.
+    pub fn test_suite<'a, C: Color + Default>(
+        &self,
+        machine: &'a MealyMachine<CharAlphabet, C>,
+    ) -> impl Iterator<Item = (Word, Vec<C>)> + 'a {
+        self.tests(machine)
+            .map(move |word| (word.clone(), mealy_outputs(machine, &word)))
+    }
+}
+
+impl<H: LStarHypothesis<Alphabet = CharAlphabet>> EquivalenceOracle<H> for WpMethod {
+    fn counterexample(&self, hypothesis: &H, output: &dyn Fn(&[char]) -> H::Color) -> Option<Word> {
+        first_disagreement(hypothesis, output, self.tests(hypothesis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{PerfectOracle, RandomSampling, Teacher, WMethod, WordDistribution, WpMethod};
+    use crate::active::LStar;
+
+    #[test]
+    fn equivalence_oracles() {
+        let alphabet = CharAlphabet::from_iter(['a', 'b']);
+        // an even number of a's and an even number of b's
+        let membership = |w: &[char]| {
+            let count = |sym| w.iter().filter(|x| **x == sym).count();
+            usize::from(count('a') % 2 == 0 && count('b') % 2 == 0)
+        };
+
+        let teacher = Teacher::new(alphabet.clone(), membership, WMethod::new(2));
+        let target = LStar::moore(alphabet.clone(), teacher);
+        assert_eq!(target.size(), 4);
+
+        let teacher = Teacher::new(alphabet.clone(), membership, WpMethod::new(2));
+        assert_eq!(LStar::moore(alphabet.clone(), teacher).size(), 4);
+
+        let distribution = WordDistribution::Uniform { min: 0, max: 8 };
+        let sampling = RandomSampling::new(200, distribution).seed(7);
+        let teacher = Teacher::new(alphabet.clone(), membership, sampling);
+        assert_eq!(LStar::moore(alphabet.clone(), teacher).size(), 4);
+
+        let oracle = PerfectOracle::new(target.clone());
+        assert_eq!(oracle.witness(&target), None);
+        let learned = LStar::moore(alphabet, oracle.clone());
+        assert_eq!(oracle.witness(&learned), None);
+        assert_eq!(learned.size(), 4);
+    }
+
+    #[test]
+    fn conformance_test_suites() {
+        // outputs whether the number of a's read so far is odd
+        let mm = NTS::builder()
+            .default_color(())
+            .with_transitions([
+                (0, 'a', 1, 1),
+                (0, 'b', 0, 0),
+                (1, 'a', 0, 0),
+                (1, 'b', 1, 1),
+            ])
+            .deterministic()
+            .with_initial(0)
+            .into_mealy();
+        let count = |w: &[char]| w.iter().filter(|x| **x == 'a').count();
+
+        let w_suite = WMethod::new(1).test_suite(&mm).collect::<Vec<_>>();
+        let wp_suite = WpMethod::new(1).test_suite(&mm).collect::<Vec<_>>();
+        assert!(wp_suite.len() <= w_suite.len());
+        for (word, outputs) in w_suite.iter().chain(&wp_suite) {
+            assert_eq!(outputs.len(), word.len());
+            for (i, output) in outputs.iter().enumerate() {
+                assert_eq!(*output, count(&word[..=i]) % 2);
+            }
+        }
+        assert!(w_suite.iter().any(|(word, _)| word.len() >= 3));
+    }
+}
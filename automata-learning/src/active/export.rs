@@ -0,0 +1,241 @@
+//! Renders the intermediate data structures of active learners, so that they can be inspected
+//! while teaching or debugging. Observation tables can be exported as markdown or CSV, and the
+//! [`DiscriminationTree`] that a table induces can be exported in the DOT language. Learners
+//! export their structures after each round if the observer asks for it, see
+//! [`crate::observer::LearnerObserver::export_formats`].
+
+use std::fmt::Write;
+
+use automata::prelude::*;
+use itertools::Itertools;
+
+use super::{LStarHypothesis, ObservationTable};
+use crate::observer::ExportFormat;
+
+type Word<D> = Vec<SymbolOf<D>>;
+
+fn label<S: Symbol>(word: &[S]) -> String {
+    if word.is_empty() {
+        "ε".to_string()
+    } else {
+        word.as_string()
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\\\""))
+}
+
+impl<D: LStarHypothesis> ObservationTable<D> {
+    /// Returns the rows of the table, i.e. the access words followed by their one letter
+    /// extensions, with a flag that indicates whether the row is an access word.
+    fn labelled_rows(&self) -> Vec<(Word<D>, bool, Vec<String>)> {
+        self.one_letter_extensions()
+            .map(|word| {
+                let outputs = self
+                    .row(&word)
+                    .unwrap_or_else(|| panic!("No table entry for {}", word.as_string()))
+                    .iter()
+                    .map(|c| c.show())
+                    .collect();
+                let access = self.rows().contains(&word);
+                (word, access, outputs)
+            })
+            .collect()
+    }
+
+    /// Renders the table as a markdown table. There is a row for every access word and every
+    /// one letter extension of one, where access words are marked in the second column, and
+    /// the remaining columns are indexed by the suffixes. Panics if the table is not filled.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let header = self.suffixes().iter().map(|e| label(e)).join(" | ");
+        writeln!(out, "| Word | Access | {header} |").unwrap();
+        writeln!(out, "|---|---|{}", "---|".repeat(self.suffixes().len())).unwrap();
+        for (word, access, outputs) in self.labelled_rows() {
+            let marker = if access { "✓" } else { "" };
+            writeln!(
+                out,
+                "| {} | {marker} | {} |",
+                label(&word),
+                outputs.join(" | ")
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    /// Renders the table as CSV with a header row, in the same layout as
+    /// [`Self::to_markdown`]. Panics if the table is not filled.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        let header = self
+            .suffixes()
+            .iter()
+            .map(|e| csv_field(&label(e)))
+            .join(",");
+        writeln!(out, "word,access,{header}").unwrap();
+        for (word, access, outputs) in self.labelled_rows() {
+            let outputs = outputs.iter().map(|o| csv_field(o)).join(",");
+            writeln!(out, "{},{access},{outputs}", csv_field(&label(&word))).unwrap();
+        }
+        out
+    }
+
+    /// Builds the discrimination tree that separates the access words by the suffixes. Every
+    /// inner node splits the access words that reach it by their output on the first suffix
+    /// that distinguishes some of them. Panics if the table is not filled.
+    pub fn discrimination_tree(&self) -> DiscriminationTree<SymbolOf<D>, D::Color> {
+        let rows = self.rows().iter().collect_vec();
+        self.split(&rows, 0)
+    }
+
+    fn split(&self, words: &[&Word<D>], from: usize) -> DiscriminationTree<SymbolOf<D>, D::Color> {
+        let outputs = |w: &Word<D>| self.row(w).expect("Table must be filled");
+        let distinguishing = (from..self.suffixes().len())
+            .find(|i| words.iter().map(|w| &outputs(w)[*i]).unique().count() > 1);
+        let Some(i) = distinguishing else {
+            return DiscriminationTree::Leaf(words.iter().map(|w| (*w).clone()).collect());
+        };
+
+        let groups = words
+            .iter()
+            .map(|w| (outputs(w)[i].clone(), *w))
+            .into_group_map_by(|(c, _)| c.clone());
+        let children = words
+            .iter()
+            .map(|w| outputs(w)[i].clone())
+            .unique()
+            .map(|c| {
+                let group = groups[&c].iter().map(|(_, w)| *w).collect_vec();
+                let child = self.split(&group, i + 1);
+                (c, child)
+            })
+            .collect();
+        DiscriminationTree::Inner {
+            suffix: self.suffixes()[i].clone(),
+            children,
+        }
+    }
+
+    /// Exports the table in the given format, where the DOT format gives the discrimination
+    /// tree.
+    pub fn export(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Markdown => self.to_markdown(),
+            ExportFormat::Csv => self.to_csv(),
+            ExportFormat::Dot => self.discrimination_tree().to_dot(),
+        }
+    }
+}
+
+/// A tree whose inner nodes are labelled with suffixes and whose leaves contain access words.
+/// The children of an inner node are indexed by the output on the suffix, so the path to a
+/// leaf gives the outputs of its access words on the suffixes along the path. Leaves only
+/// contain more than one word if the words can not be distinguished by any suffix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscriminationTree<S, C> {
+    /// A leaf with the access words that reach it.
+    Leaf(Vec<Vec<S>>),
+    /// An inner node that distinguishes words by `suffix`.
+    Inner {
+        /// The suffix that is used to split.
+        suffix: Vec<S>,
+        /// The subtree for each output.
+        children: Vec<(C, DiscriminationTree<S, C>)>,
+    },
+}
+
+impl<S: Symbol, C: Color> DiscriminationTree<S, C> {
+    /// Returns the number of leaves.
+    pub fn leaves(&self) -> usize {
+        match self {
+            DiscriminationTree::Leaf(_) => 1,
+            DiscriminationTree::Inner { children, .. } => {
+                children.iter().map(|(_, child)| child.leaves()).sum()
+            }
+        }
+    }
+
+    /// Renders the tree in the DOT language, inner nodes are drawn as ellipses and leaves as
+    /// boxes.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph {\n");
+        self.write_dot(&mut out, &mut 0);
+        out.push_str("}\n");
+        out
+    }
+
+    /// Writes the nodes and edges of the subtree, returns the name of its root.
+    fn write_dot(&self, out: &mut String, next: &mut usize) -> String {
+        let name = format!("n{next}");
+        *next += 1;
+        match self {
+            DiscriminationTree::Leaf(words) => {
+                let words = words.iter().map(|w| label(w)).join(", ");
+                writeln!(out, "  {name} [shape=box, label={}];", quote(&words)).unwrap();
+            }
+            DiscriminationTree::Inner { suffix, children } => {
+                writeln!(
+                    out,
+                    "  {name} [shape=ellipse, label={}];",
+                    quote(&label(suffix))
+                )
+                .unwrap();
+                for (color, child) in children {
+                    let target = child.write_dot(out, next);
+                    writeln!(
+                        out,
+                        "  {name} -> {target} [label={}];",
+                        quote(&color.show())
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use crate::active::ObservationTable;
+
+    #[test]
+    fn export_table() {
+        let alphabet = CharAlphabet::from_iter(['a', 'b']);
+        // words whose number of a's is divisible by three
+        let query = |w: &Vec<char>| w.iter().filter(|sym| **sym == 'a').count() % 3 == 0;
+        let mut table: ObservationTable<DFA> = ObservationTable::new(alphabet);
+        table.add_row(vec!['a']);
+        table.add_row(vec!['a', 'a']);
+        table.add_suffix(vec!['a']);
+        table.fill(query);
+
+        let markdown = table.to_markdown();
+        // the three access words and their four extensions that are not access words
+        assert_eq!(markdown.lines().count(), 2 + 3 + 4);
+        assert!(markdown.starts_with("| Word | Access | ε | a |"));
+        assert!(markdown.contains("| aa | ✓ | "));
+
+        let csv = table.to_csv();
+        assert_eq!(csv.lines().next(), Some("word,access,ε,a"));
+        assert!(csv.lines().any(|line| line.starts_with("ab,false,")));
+
+        let tree = table.discrimination_tree();
+        assert_eq!(tree.leaves(), 3);
+        let dot = tree.to_dot();
+        assert!(dot.contains("label=\"ε\""));
+        assert_eq!(dot.matches("->").count(), 4);
+    }
+}
@@ -0,0 +1,236 @@
+//! Recording and replaying the queries of a learning session. A [`RecordingOracle`] wraps any
+//! oracle and logs every query together with its answer into a [`QueryLog`], which can be
+//! serialized with the `serde` feature. A [`ReplayOracle`] answers from such a log, so a
+//! session can be reproduced without the original teacher, e.g. to debug the learner or to
+//! run regression tests against slow or unavailable systems.
+
+use std::cell::{Cell, RefCell};
+
+use automata::{prelude::*, Map};
+
+use super::{LStarHypothesis, LStarOracle};
+
+/// A query that was posed during a learning session, together with its answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LoggedQuery<C> {
+    /// A membership query for `word`, which was answered with `output`.
+    Membership { word: Vec<char>, output: C },
+    /// An equivalence query for a hypothesis with `states` states, which was either accepted or
+    /// answered with a counterexample and its output.
+    Equivalence {
+        states: usize,
+        counterexample: Option<(Vec<char>, C)>,
+    },
+}
+
+/// All queries of a learning session in the order in which they were posed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryLog<C> {
+    /// The symbols of the alphabet.
+    pub alphabet: Vec<char>,
+    /// The logged queries.
+    pub queries: Vec<LoggedQuery<C>>,
+}
+
+impl<C> QueryLog<C> {
+    /// Creates an empty log for the given alphabet.
+    pub fn new(alphabet: &CharAlphabet) -> Self {
+        Self {
+            alphabet: alphabet.universe().collect(),
+            queries: vec![],
+        }
+    }
+
+    /// Returns the number of logged membership queries.
+    pub fn membership_queries(&self) -> usize {
+        self.queries
+            .iter()
+            .filter(|q| matches!(q, LoggedQuery::Membership { .. }))
+            .count()
+    }
+
+    /// Returns the number of logged equivalence queries.
+    pub fn equivalence_queries(&self) -> usize {
+        self.queries.len() - self.membership_queries()
+    }
+}
+
+/// Wraps an oracle and logs all queries that are posed to it, see the module documentation.
+pub struct RecordingOracle<O, C> {
+    oracle: O,
+    log: RefCell<QueryLog<C>>,
+}
+
+impl<O, C: Clone> RecordingOracle<O, C> {
+    /// Wraps `oracle` with an empty log for the given alphabet.
+    pub fn new(oracle: O, alphabet: &CharAlphabet) -> Self {
+        Self {
+            oracle,
+            log: RefCell::new(QueryLog::new(alphabet)),
+        }
+    }
+
+    /// Returns the wrapped oracle.
+    pub fn oracle(&self) -> &O {
+        &self.oracle
+    }
+
+    /// Returns a copy of the log.
+    pub fn log(&self) -> QueryLog<C> {
+        self.log.borrow().clone()
+    }
+
+    /// Consumes the oracle and returns the log.
+    pub fn into_log(self) -> QueryLog<C> {
+        self.log.into_inner()
+    }
+}
+
+impl<H, O> LStarOracle<H> for RecordingOracle<O, H::Color>
+where
+    H: LStarHypothesis<Alphabet = CharAlphabet>,
+    O: LStarOracle<H>,
+{
+    fn output<W: FiniteWord<SymbolOf<H>>>(&self, word: W) -> H::Color {
+        let word = word.to_vec();
+        let output = self.oracle.output(&word);
+        self.log.borrow_mut().queries.push(LoggedQuery::Membership {
+            word,
+            output: output.clone(),
+        });
+        output
+    }
+
+    fn alphabet(&self) -> H::Alphabet {
+        self.oracle.alphabet()
+    }
+
+    fn equivalence(&self, hypothesis: &H) -> Result<(), (Vec<SymbolOf<H>>, H::Color)> {
+        let answer = self.oracle.equivalence(hypothesis);
+        self.log
+            .borrow_mut()
+            .queries
+            .push(LoggedQuery::Equivalence {
+                states: hypothesis.size(),
+                counterexample: answer.clone().err(),
+            });
+        answer
+    }
+}
+
+/// Answers queries from a [`QueryLog`]. Membership queries may be posed in any order, but every
+/// word must have been logged. Equivalence queries are answered with the logged answers in
+/// the order in which they were recorded. Panics if a query can not be answered from the log or
+/// a hypothesis does not have the logged size, as the learner then deviates from the session.
+pub struct ReplayOracle<C> {
+    alphabet: CharAlphabet,
+    outputs: Map<Vec<char>, C>,
+    equivalences: Vec<(usize, Option<(Vec<char>, C)>)>,
+    position: Cell<usize>,
+}
+
+impl<C: Clone> ReplayOracle<C> {
+    /// Creates an oracle that replays `log`.
+    pub fn new(log: QueryLog<C>) -> Self {
+        let mut outputs = Map::default();
+        let mut equivalences = vec![];
+        for query in log.queries {
+            match query {
+                LoggedQuery::Membership { word, output } => {
+                    outputs.insert(word, output);
+                }
+                LoggedQuery::Equivalence {
+                    states,
+                    counterexample,
+                } => equivalences.push((states, counterexample)),
+            }
+        }
+        Self {
+            alphabet: CharAlphabet::from_iter(log.alphabet),
+            outputs,
+            equivalences,
+            position: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of logged equivalence queries that have not been replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.equivalences.len() - self.position.get()
+    }
+}
+
+impl<H> LStarOracle<H> for ReplayOracle<H::Color>
+where
+    H: LStarHypothesis<Alphabet = CharAlphabet>,
+{
+    fn output<W: FiniteWord<SymbolOf<H>>>(&self, word: W) -> H::Color {
+        let word = word.to_vec();
+        self.outputs
+            .get(&word)
+            .unwrap_or_else(|| panic!("Query {} was not recorded", word.as_string()))
+            .clone()
+    }
+
+    fn alphabet(&self) -> H::Alphabet {
+        self.alphabet.clone()
+    }
+
+    fn equivalence(&self, hypothesis: &H) -> Result<(), (Vec<SymbolOf<H>>, H::Color)> {
+        let position = self.position.get();
+        let Some((states, counterexample)) = self.equivalences.get(position) else {
+            panic!("No more equivalence queries were recorded");
+        };
+        assert_eq!(
+            *states,
+            hypothesis.size(),
+            "Hypothesis of equivalence query {position} has the wrong size"
+        );
+        self.position.set(position + 1);
+        match counterexample {
+            Some(answer) => Err(answer.clone()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{RecordingOracle, ReplayOracle};
+    use crate::active::{DFAOracle, LStar};
+
+    #[test]
+    fn record_and_replay() {
+        let mut target = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = target.add_state(true);
+        let q1 = target.add_state(false);
+        let q2 = target.add_state(false);
+        for (source, sym, sink) in [
+            (q0, 'a', q1),
+            (q0, 'b', q0),
+            (q1, 'a', q2),
+            (q1, 'b', q1),
+            (q2, 'a', q0),
+            (q2, 'b', q2),
+        ] {
+            target.add_edge(source, sym, sink, Void);
+        }
+        let alphabet = target.alphabet().clone();
+
+        let recording = RecordingOracle::new(DFAOracle::new(target), &alphabet);
+        let mut lstar = LStar::for_dfa(alphabet.clone(), recording);
+        let recorded = lstar.infer();
+        let log = lstar.oracle().log();
+        assert_eq!(log.membership_queries(), lstar.query_counts().membership);
+        assert_eq!(log.equivalence_queries(), lstar.query_counts().equivalence);
+
+        let replay = ReplayOracle::new(log);
+        let mut lstar = LStar::for_dfa(alphabet, replay);
+        let replayed = lstar.infer();
+        assert_eq!(lstar.oracle().remaining(), 0);
+        assert!(replayed.moore_witness_non_bisimilarity(recorded).is_none());
+    }
+}
@@ -1,11 +1,21 @@
-use std::{cell::RefCell, fmt::Debug};
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    rc::Rc,
+};
 
-use automata::{prelude::*, word::Concat, Map, Set};
+use automata::{prelude::*, Map, Set};
 use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use tracing::{debug, info, trace};
 
-use super::oracle::LStarOracle;
+use crate::{
+    budget::{Budget, BudgetExceeded, Exhausted, QueryCounts},
+    observer::{LearnerEvent, LearnerObserver, TracingObserver},
+    passive::FiniteSample,
+};
+
+use super::{oracle::LStarOracle, ObservationTable};
 
 const ITERATION_THRESHOLD: usize = if cfg!(debug_assertions) { 300 } else { 200000 };
 
@@ -39,21 +49,35 @@ pub trait LStarHypothesis: Deterministic + Sproutable + Pointed {
 
 type Word<D> = Vec<SymbolOf<D>>;
 pub type Experiments<D> = Vec<Word<D>>;
+type RoundHook<D, T> = Rc<dyn Fn(&LStar<D, T>, usize, &D)>;
+
+/// Determines how L* incorporates a counterexample into the observation table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CounterexampleAnalysis {
+    /// All non-empty suffixes of the counterexample are added as experiments.
+    #[default]
+    AllSuffixes,
+    /// A single distinguishing suffix is found with a binary search over the counterexample,
+    /// as proposed by Rivest and Schapire. This takes a logarithmic number of membership
+    /// queries in the length of the counterexample and keeps the table small.
+    RivestSchapire,
+}
 
 /// An implementation of the L* algorithm.
 #[derive(Clone)]
 pub struct LStar<D: LStarHypothesis, T: LStarOracle<D>> {
-    // the alphabet of what we are learning
-    alphabet: D::Alphabet,
     // a mapping containing all queries that have been posed so far, together with their output
     queries: RefCell<Map<Word<D>, D::Color>>,
-    // the minimal access words forming the base states
-    base: Vec<Word<D>>,
-    // all known experiments
-    experiments: Vec<Word<D>>,
-    // mapping from input word to a bitset, where the i-th entry gives the value for
-    // the output of concatenating input word and i-th experiment
-    table: Map<Word<D>, Vec<D::Color>>,
+    // the observation table
+    table: ObservationTable<D>,
+    // labelled words that are checked before the oracle is asked for equivalence
+    sample: Vec<(Word<D>, D::Color)>,
+    // how counterexamples are processed
+    analysis: CounterexampleAnalysis,
+    // the queries that were posed to the oracle
+    counts: Cell<QueryCounts>,
+    // called with the round and hypothesis after each equivalence query
+    on_round: Option<RoundHook<D, T>>,
     // the oracle
     oracle: T,
 }
@@ -67,11 +91,13 @@ impl<T: LStarOracle<DFA>> LStar<DFA, T> {
     }
 }
 
-impl<T: LStarOracle<MealyMachine<CharAlphabet>>> LStar<MealyMachine<CharAlphabet>, T> {
-    pub fn mealy(oracle: T) -> MealyMachine<CharAlphabet> {
+impl<C: Color + Default, T: LStarOracle<MealyMachine<CharAlphabet, C>>>
+    LStar<MealyMachine<CharAlphabet, C>, T>
+{
+    pub fn mealy(oracle: T) -> MealyMachine<CharAlphabet, C> {
         Self::new(oracle.alphabet(), oracle).infer()
     }
-    pub fn for_mealy(alphabet: CharAlphabet, oracle: T) -> LStar<MealyMachine<CharAlphabet>, T> {
+    pub fn for_mealy(alphabet: CharAlphabet, oracle: T) -> LStar<MealyMachine<CharAlphabet, C>, T> {
         Self::new(alphabet, oracle)
     }
 }
@@ -88,88 +114,208 @@ impl<T: LStarOracle<MooreMachine<CharAlphabet>>> LStar<MooreMachine<CharAlphabet
 impl<D: LStarHypothesis, T: LStarOracle<D>> LStar<D, T> {
     pub fn new(alphabet: D::Alphabet, oracle: T) -> Self {
         Self {
-            experiments: D::mandatory_experiments(&alphabet).into_iter().collect(),
-            alphabet,
             queries: RefCell::new(Map::default()),
-            base: vec![vec![]],
-            table: Map::default(),
+            table: ObservationTable::new(alphabet),
+            sample: vec![],
+            analysis: CounterexampleAnalysis::default(),
+            counts: Cell::new(QueryCounts::default()),
+            on_round: None,
             oracle,
         }
     }
 
-    fn output(&self, w: &Word<D>) -> D::Color {
-        if !self.queries.borrow().contains_key(w) {
-            let c = self.oracle.output(w);
-            assert!(self.queries.borrow_mut().insert(w.to_owned(), c).is_none());
+    /// Returns the oracle.
+    pub fn oracle(&self) -> &T {
+        &self.oracle
+    }
+
+    /// Returns the number of membership and equivalence queries that were posed to the oracle
+    /// so far, as well as the number of symbols that were sent. Membership queries are only
+    /// posed once per word and not for the words given with [`Self::with_sample`].
+    pub fn query_counts(&self) -> QueryCounts {
+        self.counts.get()
+    }
+
+    /// Returns the observation table in its current state.
+    pub fn table(&self) -> &ObservationTable<D> {
+        &self.table
+    }
+
+    /// Returns all membership queries that have been posed so far together with their answers,
+    /// including the words that were given with [`Self::with_sample`], ordered by word.
+    pub fn queries(&self) -> FiniteSample<D::Alphabet, D::Color> {
+        FiniteSample::new_finite(
+            self.oracle.alphabet(),
+            self.queries
+                .borrow()
+                .iter()
+                .map(|(w, c)| (w.clone(), c.clone()))
+                .sorted_by(|(u, _), (v, _)| u.cmp(v)),
+        )
+    }
+
+    /// Restores the state of an earlier run from its access words, suffixes, membership
+    /// queries and query counts. The table is filled from the queries in the next round.
+    pub(super) fn restore(
+        &mut self,
+        rows: Vec<Word<D>>,
+        suffixes: Vec<Word<D>>,
+        queries: Vec<(Word<D>, D::Color)>,
+        counts: QueryCounts,
+    ) {
+        for row in rows {
+            self.table.add_row(row);
         }
-        self.queries.borrow().get(w).unwrap().clone()
+        for suffix in suffixes {
+            self.table.add_suffix(suffix);
+        }
+        self.queries.borrow_mut().extend(queries);
+        self.counts.set(counts);
     }
 
-    fn update_table(&mut self) {
-        let mut updates = vec![];
-        let experiment_count = self.experiments.len();
-
-        for (n, mr) in self.one_letter_extensions().enumerate() {
-            let stored_experiment_count = self.table.get(&mr).map(|r| r.len()).unwrap_or(0);
-            trace!("Have stored {stored_experiment_count} out of {experiment_count} experiments for {}", mr.as_string());
-            if stored_experiment_count < experiment_count {
-                for i in stored_experiment_count..experiment_count {
-                    let concat = Concat(&mr, &self.experiments[i]).to_vec();
-                    let output = self.output(&concat).clone();
-                    trace!(
-                        "Adding update that {} maps to {}",
-                        concat.as_string(),
-                        output.show()
-                    );
-                    updates.push((mr.clone(), i, output));
-                }
-            } else {
-                assert_eq!(
-                    stored_experiment_count,
-                    experiment_count,
-                    "Too many experiments present for {}",
-                    mr.as_string()
-                );
+    /// Sets a function that is called after each equivalence query with the learner, the
+    /// current round and the hypothesis that was tested, e.g. to write checkpoints.
+    pub(super) fn on_round(mut self, hook: RoundHook<D, T>) -> Self {
+        self.on_round = Some(hook);
+        self
+    }
+
+    /// Sets how counterexamples are processed, see [`CounterexampleAnalysis`].
+    pub fn counterexample_analysis(mut self, analysis: CounterexampleAnalysis) -> Self {
+        self.analysis = analysis;
+        self
+    }
+
+    /// Bootstraps the learner with the labelled words of `sample`, which must agree with the
+    /// oracle. The labels are used in place of membership queries and all suffixes of the
+    /// sample words are added as experiments, so the initial observation table already
+    /// contains what is known from the sample. Moreover, every hypothesis is first checked
+    /// against the sample, and the oracle is only asked for equivalence if it is consistent.
+    pub fn with_sample(mut self, sample: &FiniteSample<D::Alphabet, D::Color>) -> Self {
+        for (word, color) in sample.entries() {
+            self.queries
+                .borrow_mut()
+                .insert(word.clone(), color.clone());
+            for i in 0..word.len() {
+                self.table.add_suffix(word[i..].to_vec());
             }
+            self.sample.push((word.clone(), color.clone()));
         }
+        debug!(
+            "Bootstrapped with {} words, have {} experiments",
+            self.sample.len(),
+            self.table.suffixes().len()
+        );
+        self
+    }
 
-        for (mr, i, output) in updates {
-            assert!(i < self.experiments.len());
-            let mut row = self.table.entry(mr).or_default();
-            row.push(output);
-        }
+    /// Returns a word of the sample that `hypothesis` classifies wrongly, with its label.
+    fn sample_counterexample(&self, hypothesis: &D) -> Option<(Word<D>, D::Color)> {
+        self.sample
+            .iter()
+            .find(|(word, color)| hypothesis.transform(word) != *color)
+            .cloned()
+    }
 
-        if cfg!(debug_assertions) {
-            for mr in self.one_letter_extensions() {
-                let Some(val) = self.table.get(&mr) else {
-                    panic!("No table entry for {}", mr.as_string());
-                };
-                if val.len() != experiment_count {
-                    panic!(
-                        "Row for {} does not have enough entries, has {} of {experiment_count}",
-                        mr.show(),
-                        val.len()
-                    )
-                }
+    fn update_table(&mut self) {
+        let Self {
+            table,
+            queries,
+            counts,
+            oracle,
+            ..
+        } = self;
+
+        // all missing outputs are independent, so they are posed to the oracle as one batch
+        let missing = {
+            let known = queries.borrow();
+            table
+                .missing()
+                .into_iter()
+                .filter(|w| !known.contains_key(w))
+                .unique()
+                .collect_vec()
+        };
+        if !missing.is_empty() {
+            let mut updated = counts.get();
+            for w in &missing {
+                updated.record_membership(w.len());
             }
+            counts.set(updated);
+            let outputs = oracle.outputs(&missing);
+            assert_eq!(
+                outputs.len(),
+                missing.len(),
+                "Oracle must answer every query"
+            );
+            queries
+                .borrow_mut()
+                .extend(missing.into_iter().zip(outputs));
         }
 
-        trace!("After update the table is\n{:?}", self);
+        table.fill(|w| {
+            queries
+                .borrow_mut()
+                .entry(w.clone())
+                .or_insert_with(|| membership_query(oracle, counts, w))
+                .clone()
+        });
     }
 
     pub fn infer(&mut self) -> D {
+        self.infer_observed(&mut TracingObserver)
+    }
+
+    /// Runs the algorithm like [`Self::infer`] and reports its progress to `observer`.
+    pub fn infer_observed(&mut self, observer: &mut dyn LearnerObserver) -> D {
+        self.run(observer, None)
+            .unwrap_or_else(|_| unreachable!("Run without budget cannot exhaust it"))
+    }
+
+    /// Runs the algorithm like [`Self::infer`], but stops once `budget` is exhausted. The number
+    /// of rows in the base counts as states. As only closed tables yield a hypothesis, the budget
+    /// is checked whenever a hypothesis has been constructed and, from then on, at the start of
+    /// every iteration. If it is exhausted, the most recent hypothesis is returned together with
+    /// the reason. The check after constructing a hypothesis already includes the equivalence
+    /// query that is about to be posed, so a limit on equivalence queries is never exceeded.
+    pub fn infer_budgeted(&mut self, budget: &Budget) -> Result<D, BudgetExceeded<D>> {
+        self.run(&mut TracingObserver, Some(budget))
+    }
+
+    fn check_budget(&self, budget: Option<&Budget>, pending: usize) -> Result<(), Exhausted> {
+        let Some(budget) = budget else {
+            return Ok(());
+        };
+        let mut counts = self.counts.get();
+        counts.equivalence += pending;
+        budget.check_queries(self.table.rows().len(), &counts)
+    }
+
+    fn run(
+        &mut self,
+        observer: &mut dyn LearnerObserver,
+        budget: Option<&Budget>,
+    ) -> Result<D, BudgetExceeded<D>> {
         let start = std::time::Instant::now();
         let mut iteration = 0;
         let threshold = std::env::var("MAX_ITERATIONS")
             .unwrap_or(format!("{ITERATION_THRESHOLD}"))
             .parse()
             .unwrap();
+        let mut best = None;
 
         'outer: while iteration < threshold {
+            if let Err(reason) = self.check_budget(budget, 0) {
+                if let Some(best) = best.take() {
+                    info!("LStar stopped after {iteration} iterations: {reason}");
+                    return Err(BudgetExceeded { best, reason });
+                }
+            }
             self.update_table();
             iteration += 1;
             trace!("LStar iteration {iteration} with table\n{:?}", self);
-            let todo = self.rows_to_promote();
+            let todo = self.table.unclosed_rows();
+            observer.observe(LearnerEvent::Pending(todo.len()));
             trace!(
                 "Have to promote rows: {}",
                 todo.iter().map(FiniteWord::as_string).join(", ")
@@ -178,7 +324,7 @@ impl<D: LStarHypothesis, T: LStarOracle<D>> LStar<D, T> {
             if !todo.is_empty() {
                 let mut queries = Set::default();
                 for r in todo {
-                    self.base.push(r.clone());
+                    self.table.add_row(r.clone());
                     queries.insert(r.clone());
                 }
                 // TODO optimize this call!
@@ -186,202 +332,158 @@ impl<D: LStarHypothesis, T: LStarOracle<D>> LStar<D, T> {
                 continue 'outer;
             }
 
-            let hypothesis = self.hypothesis();
+            let hypothesis = self.table.hypothesis();
+            observer.observe(LearnerEvent::Hypothesis(hypothesis.size()));
+            for format in observer.export_formats().to_vec() {
+                observer.export(iteration, format, &self.table.export(format));
+            }
+            if let Err(reason) = self.check_budget(budget, 1) {
+                info!("LStar stopped after {iteration} iterations: {reason}");
+                return Err(BudgetExceeded {
+                    best: hypothesis,
+                    reason,
+                });
+            }
+
+            if let Some((counterexample, color)) = self.sample_counterexample(&hypothesis) {
+                trace!(
+                    "Sample contains counterexample {}",
+                    counterexample.as_string()
+                );
+                observer.observe(LearnerEvent::Conflict(counterexample.len()));
+                self.process_counterexample(counterexample, color, &hypothesis);
+                best = Some(hypothesis);
+                continue 'outer;
+            }
 
-            if let Err((counterexample, color)) = self.oracle.equivalence(&hypothesis) {
+            self.counts.set({
+                let mut counts = self.counts.get();
+                counts.record_equivalence();
+                counts
+            });
+            let answer = self.oracle.equivalence(&hypothesis);
+            if let Err((counterexample, color)) = answer {
                 assert!(hypothesis.transform(&counterexample) != color);
-                self.process_counterexample(counterexample, color);
+                observer.observe(LearnerEvent::Conflict(counterexample.len()));
+                self.process_counterexample(counterexample, color, &hypothesis);
+                if let Some(hook) = self.on_round.clone() {
+                    hook(self, iteration, &hypothesis);
+                }
+                best = Some(hypothesis);
                 continue 'outer;
             }
+            if let Some(hook) = self.on_round.clone() {
+                hook(self, iteration, &hypothesis);
+            }
 
+            let counts = self.counts.get();
+            observer.observe(LearnerEvent::Queries(counts.membership));
+            observer.observe(LearnerEvent::EquivalenceQueries(counts.equivalence));
+            observer.observe(LearnerEvent::Symbols(counts.symbols));
+            observer.observe(LearnerEvent::Phase("lstar", start.elapsed()));
             let duration = start.elapsed().as_millis();
             info!("Execution of LStar took {duration}ms");
-            return hypothesis;
+            return Ok(hypothesis);
         }
 
         panic!("Iteration threshold exceeded!")
     }
 
-    fn process_counterexample(&mut self, word: Word<D>, color: D::Color) {
-        for i in 0..(word.len()) {
-            let suffix = word[i..].to_vec();
-            assert!(!suffix.is_empty());
-            if !self.experiments.contains(&suffix) {
-                trace!("Adding counterexample {}", suffix.as_string());
-                self.experiments.push(suffix)
-            }
-        }
-    }
-
-    fn state_color(&self, mr: &Word<D>) -> D::StateColor {
-        D::give_state_color(
-            mr,
-            &self.experiments,
-            self.table
-                .get(mr)
-                .expect("Cannot give color for non-existent word"),
-        )
-    }
-
-    fn edge_color(&self, mr: &Word<D>, sym: SymbolOf<D>) -> D::EdgeColor {
-        D::give_transition_color(mr, sym, &self.experiments, self.table.get(mr).unwrap())
-    }
-
-    fn hypothesis(&self) -> D {
-        let start = std::time::Instant::now();
-
-        let mut ts: DTS<_, _, _> = DTS::new_for_alphabet(self.alphabet.clone());
-        let mut state_map = Map::default();
-        let mut observations = Map::default();
-
-        for mr in &self.base {
-            let color = self.state_color(mr);
-            let id = ts.add_state(color);
-            state_map.insert(mr, id);
-
-            let observed = self.table.get(mr).unwrap();
-            observations.insert(observed, mr);
-        }
-
-        for (mr, i) in &state_map {
-            for a in self.alphabet.universe() {
-                let color = self.edge_color(mr, a);
-                let obs = self
-                    .table
-                    .get(&Concat(mr, [a]).to_vec())
-                    .expect("Can only work if table is closed!");
-                let target = observations.get(obs).unwrap();
-
-                let added = ts.add_edge(
-                    *i,
-                    <D::Alphabet as Alphabet>::expression(a),
-                    *state_map.get(target).unwrap(),
-                    color,
-                );
-                assert!(added.is_none());
-            }
-        }
-
-        let duration = start.elapsed().as_micros();
-        debug!("Building hypothesis took {duration} microseconds");
-
-        D::from_transition_system(ts, *state_map.get(&vec![]).unwrap())
+    fn output(&self, w: &Word<D>) -> D::Color {
+        self.queries
+            .borrow_mut()
+            .entry(w.clone())
+            .or_insert_with(|| membership_query(&self.oracle, &self.counts, w))
+            .clone()
     }
 
-    fn inconsistent_words(&self) -> Option<Word<D>> {
-        for (i, left) in self.base.iter().enumerate() {
-            'middle: for right in &self.base[i..] {
-                if self.table.get(left) != self.table.get(right) {
-                    continue 'middle;
-                }
-
-                for sym in self.alphabet.universe() {
-                    let left_ext = left
-                        .iter()
-                        .chain(std::iter::once(&sym))
-                        .cloned()
-                        .collect_vec();
-                    let right_ext = right
-                        .iter()
-                        .chain(std::iter::once(&sym))
-                        .cloned()
-                        .collect_vec();
-
-                    let l = self.table.get(&left_ext).expect("Should be present!");
-                    let r = self.table.get(&right_ext).expect("Should be present!");
-
-                    if l == r {
-                        continue 'middle;
-                    }
-                    'inner: for (j, e) in self.experiments.iter().enumerate() {
-                        assert!(j < std::cmp::min(l.len(), r.len()));
-                        if l[j] == r[j] {
-                            continue 'inner;
-                        }
-                        return Some(Concat(vec![sym], e).to_vec());
-                    }
+    fn process_counterexample(&mut self, word: Word<D>, color: D::Color, hypothesis: &D) {
+        match self.analysis {
+            CounterexampleAnalysis::AllSuffixes => {
+                for i in 0..(word.len()) {
+                    let suffix = word[i..].to_vec();
+                    assert!(!suffix.is_empty());
+                    self.table.add_suffix(suffix);
                 }
             }
+            CounterexampleAnalysis::RivestSchapire => {
+                let suffix = self.distinguishing_suffix(&word, hypothesis);
+                trace!(
+                    "Counterexample {} yields suffix {}",
+                    word.as_string(),
+                    suffix.as_string()
+                );
+                assert!(self.table.add_suffix(suffix));
+            }
         }
-        None
     }
 
-    fn one_letter_extensions(&self) -> impl Iterator<Item = Word<D>> + '_ {
-        self.base
+    /// Finds a suffix of the counterexample `word` that distinguishes two rows which the
+    /// hypothesis identifies. For a position `i`, let `α(i)` be the output on the access word of
+    /// the state reached by the first `i` symbols, followed by the remaining symbols. Then `α(0)`
+    /// is the output on `word`, which differs from the hypothesis, while `α(n-1)` agrees with
+    /// it, where `n` is the length of `word`. A binary search yields `i` such that `α(i)` and
+    /// `α(i+1)` differ, so the suffix after position `i+1` is distinguishing.
+    fn distinguishing_suffix(&self, word: &Word<D>, hypothesis: &D) -> Word<D> {
+        assert!(!word.is_empty(), "Counterexample must not be empty");
+        let access: Map<usize, &Word<D>> = self
+            .table
+            .rows()
             .iter()
-            .flat_map(|w| {
-                std::iter::once(w.clone()).chain(self.alphabet.universe().filter_map(|a| {
-                    let mut x = w.clone();
-                    x.push(a);
-                    if !self.base.contains(&x) {
-                        Some(x)
-                    } else {
-                        None
-                    }
-                }))
-            })
-            .unique()
-    }
-
-    fn rows_to_promote(&self) -> Set<Word<D>> {
-        let start = std::time::Instant::now();
-
-        let known = automata::Set::from_iter(self.base.iter().map(|b| {
-            self.table.get(b).unwrap_or_else(|| {
-                panic!(
-                    "Experiment {} must be present",
-                    owo_colors::OwoColorize::blue(&b.as_string())
-                )
+            .map(|mr| {
+                let state = hypothesis
+                    .reached_state_index(mr)
+                    .expect("Hypothesis must be complete");
+                (state, mr)
             })
-        }));
-        let mut seen = automata::Set::default();
-        let mut out = Set::default();
-
-        for word in self.one_letter_extensions() {
-            trace!("Considering one letter extension {}", word.as_string());
-            let seq = self.table.get(&word).unwrap();
-            if !known.contains(seq) && seen.insert(seq) {
-                out.insert(word);
+            .collect();
+        let alpha = |i: usize| {
+            let state = hypothesis
+                .reached_state_index(&word[..i])
+                .expect("Hypothesis must be complete");
+            let mut query = access[&state].clone();
+            query.extend_from_slice(&word[i..]);
+            self.output(&query)
+        };
+
+        let expected = hypothesis.transform(word);
+        let (mut lo, mut hi) = (0, word.len() - 1);
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            if alpha(mid) == expected {
+                hi = mid;
+            } else {
+                lo = mid;
             }
         }
-
-        debug!(
-            "Finding rows to promote took {} microseconds",
-            start.elapsed().as_micros()
-        );
-        out
+        word[hi..].to_vec()
     }
 }
 
+/// Poses a membership query for `word` to `oracle` and records it in `counts`.
+fn membership_query<D: LStarHypothesis, T: LStarOracle<D>>(
+    oracle: &T,
+    counts: &Cell<QueryCounts>,
+    word: &Word<D>,
+) -> D::Color {
+    let mut updated = counts.get();
+    updated.record_membership(word.len());
+    counts.set(updated);
+    oracle.output(word)
+}
+
 impl<D: LStarHypothesis, T: LStarOracle<D>> std::fmt::Debug for LStar<D, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut builder = tabled::builder::Builder::default();
-        let mut header = vec!["MR".to_string()];
-
-        for e in &self.experiments {
-            header.push(e.as_string());
-        }
-        builder.push_record(header);
-
-        for (i, mr) in self.base.iter().enumerate() {
-            let mut row = vec![mr.as_string()];
-            for color in self
-                .table
-                .get(mr)
-                .unwrap_or_else(|| panic!("No table entry for {}", mr.as_string()))
-            {
-                row.push(color.show());
-            }
-            builder.push_record(row);
-        }
-
-        write!(f, "{}", builder.build())
+        write!(f, "{:?}", self.table)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+
     use automata::prelude::*;
+    use itertools::Itertools;
     use oracle::MealyOracle;
     use owo_colors::OwoColorize;
 
@@ -393,7 +495,12 @@ mod tests {
         passive::FiniteSample,
     };
 
-    use super::LStar;
+    use super::{CounterexampleAnalysis, LStar};
+    use crate::{
+        budget::{Budget, CancellationToken, Exhausted},
+        formats::ToDot,
+        observer::{ExportFormat, LearnerEvent, LearnerObserver, LearnerStatistics},
+    };
 
     struct ModkAmodlB(CharAlphabet);
     struct WordLenModk(CharAlphabet, usize);
@@ -478,6 +585,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lstar_is_deterministic() {
+        let learn = || {
+            let alphabet = CharAlphabet::from_iter(['b', 'a']);
+            let mut lstar = super::LStar::for_moore(alphabet.clone(), ModkAmodlB(alphabet));
+            lstar.infer().to_dot()
+        };
+        assert_eq!(learn(), learn());
+    }
+
+    #[test]
+    fn lstar_budgeted() {
+        let alphabet = CharAlphabet::from_iter(vec!['a', 'b']);
+        let mut lstar = super::LStar::for_moore(alphabet.clone(), ModkAmodlB(alphabet.clone()));
+        let mm = lstar.infer_budgeted(&Budget::unlimited()).unwrap();
+        assert_eq!(mm.size(), 4);
+
+        // the first hypothesis only distinguishes by the output
+        let budget = Budget::unlimited().max_states(1);
+        let mut lstar = super::LStar::for_moore(alphabet.clone(), ModkAmodlB(alphabet.clone()));
+        let exceeded = lstar.infer_budgeted(&budget).unwrap_err();
+        assert_eq!(exceeded.reason, Exhausted::States(1));
+        assert_eq!(exceeded.best.size(), 2);
+
+        let mut statistics = LearnerStatistics::default();
+        let mut lstar = super::LStar::for_moore(alphabet.clone(), ModkAmodlB(alphabet.clone()));
+        lstar.infer_observed(&mut statistics);
+        let counts = lstar.query_counts();
+        assert_eq!(counts.equivalence, statistics.hypotheses.len());
+        assert_eq!(counts.membership, lstar.queries().words.len());
+        assert_eq!(counts.symbols, statistics.symbols);
+
+        // the limit is checked before the equivalence query is posed
+        let budget = Budget::unlimited().max_equivalence_queries(1);
+        let mut lstar = super::LStar::for_moore(alphabet.clone(), ModkAmodlB(alphabet.clone()));
+        let exceeded = lstar.infer_budgeted(&budget).unwrap_err();
+        assert_eq!(exceeded.reason, Exhausted::EquivalenceQueries(1));
+        assert_eq!(lstar.query_counts().equivalence, 1);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let budget = Budget::unlimited().cancellation(token);
+        let mut lstar = super::LStar::for_moore(alphabet.clone(), ModkAmodlB(alphabet));
+        let exceeded = lstar.infer_budgeted(&budget).unwrap_err();
+        assert_eq!(exceeded.reason, Exhausted::Cancelled);
+        assert_eq!(exceeded.best.size(), 2);
+    }
+
     #[test]
     fn lstar_even_a_even_b() {
         let alphabet = CharAlphabet::from_iter(vec!['a', 'b']);
@@ -520,11 +675,137 @@ mod tests {
 
         assert_eq!(dfa.size(), 4);
 
+        let mut statistics = LearnerStatistics::default();
+        let observed = LStar::for_dfa(test_dfa().alphabet().clone(), DFAOracle::new(test_dfa()))
+            .infer_observed(&mut statistics);
+        assert_eq!(observed.size(), 4);
+        assert_eq!(statistics.hypotheses.last(), Some(&4));
+        assert_eq!(statistics.conflicts + 1, statistics.hypotheses.len());
+        assert!(statistics.queries > 0);
+
         if let Some(witness) = dfa.moore_witness_non_bisimilarity(test_dfa()) {
             panic!("DFAs do not treat {} the same way", witness.as_string());
         }
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn lstar_parallel_queries() {
+        let alphabet = test_dfa().alphabet().clone();
+        let mut sequential = LStar::for_dfa(alphabet.clone(), DFAOracle::new(test_dfa()));
+        let expected = sequential.infer();
+        let oracle = oracle::ParallelOracle(DFAOracle::new(test_dfa()));
+        let mut parallel = LStar::for_dfa(alphabet, oracle);
+        let dfa = parallel.infer();
+
+        assert!(dfa.moore_witness_non_bisimilarity(expected).is_none());
+        assert_eq!(parallel.table().rows(), sequential.table().rows());
+        assert_eq!(parallel.table().suffixes(), sequential.table().suffixes());
+        assert_eq!(parallel.query_counts(), sequential.query_counts());
+    }
+
+    struct CountingOracle(DFAOracle<DFA>, Cell<usize>);
+
+    impl LStarOracle<DFA> for CountingOracle {
+        fn output<W: FiniteWord<char>>(&self, word: W) -> bool {
+            LStarOracle::<DFA>::output(&self.0, word)
+        }
+
+        fn equivalence(&self, hypothesis: &DFA) -> Result<(), (Vec<char>, bool)> {
+            self.1.set(self.1.get() + 1);
+            LStarOracle::<DFA>::equivalence(&self.0, hypothesis)
+        }
+
+        fn alphabet(&self) -> CharAlphabet {
+            LStarOracle::<DFA>::alphabet(&self.0)
+        }
+    }
+
+    #[derive(Default)]
+    struct Exports {
+        hypotheses: usize,
+        rounds: Vec<(usize, ExportFormat)>,
+    }
+
+    impl LearnerObserver for Exports {
+        fn observe(&mut self, event: LearnerEvent) {
+            if let LearnerEvent::Hypothesis(_) = event {
+                self.hypotheses += 1;
+            }
+        }
+
+        fn export_formats(&self) -> &[ExportFormat] {
+            &[ExportFormat::Markdown, ExportFormat::Dot]
+        }
+
+        fn export(&mut self, round: usize, format: ExportFormat, content: &str) {
+            assert!(!content.is_empty());
+            self.rounds.push((round, format));
+        }
+    }
+
+    #[test]
+    fn lstar_exports() {
+        let mut exports = Exports::default();
+        LStar::for_dfa(test_dfa().alphabet().clone(), DFAOracle::new(test_dfa()))
+            .infer_observed(&mut exports);
+        assert_eq!(exports.rounds.len(), 2 * exports.hypotheses);
+        assert!(exports.rounds.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn lstar_rivest_schapire() {
+        let mut statistics = LearnerStatistics::default();
+        let dfa = LStar::for_dfa(test_dfa().alphabet().clone(), DFAOracle::new(test_dfa()))
+            .counterexample_analysis(CounterexampleAnalysis::RivestSchapire)
+            .infer_observed(&mut statistics);
+        assert!(dfa.moore_witness_non_bisimilarity(test_dfa()).is_none());
+
+        let mut statistics = LearnerStatistics::default();
+        let alphabet = CharAlphabet::from_iter(vec!['a', 'b']);
+        let mut lstar = super::LStar::for_moore(alphabet.clone(), WordLenModk(alphabet, 5))
+            .counterexample_analysis(CounterexampleAnalysis::RivestSchapire);
+        let mm = lstar.infer_observed(&mut statistics);
+        assert_eq!(mm.size(), 5);
+        // every counterexample adds exactly one experiment
+        let conflicts = statistics.conflicts;
+        assert!(lstar.table().suffixes().len() <= 1 + conflicts);
+    }
+
+    #[test]
+    fn lstar_bootstrapped() {
+        let target = test_dfa();
+        // all words up to length three, classified by the target
+        let words = (0..=3)
+            .flat_map(|n| {
+                (0..n)
+                    .map(|_| ['a', 'b', 'c'])
+                    .multi_cartesian_product()
+                    .collect_vec()
+            })
+            .chain([vec![]])
+            .unique()
+            .map(|w| {
+                let c = target.accepts(&w);
+                (w, c)
+            })
+            .collect_vec();
+        let sample = FiniteSample::new_finite(target.alphabet().clone(), words);
+
+        let oracle = CountingOracle(DFAOracle::new(test_dfa()), Cell::new(0));
+        let mut lstar = LStar::for_dfa(target.alphabet().clone(), oracle).with_sample(&sample);
+        let dfa = lstar.infer();
+
+        assert_eq!(dfa.size(), 4);
+        assert!(dfa.moore_witness_non_bisimilarity(test_dfa()).is_none());
+        // the sample separates all states, so the oracle only confirms the hypothesis
+        assert_eq!(lstar.oracle.1.get(), 1);
+        assert_eq!(lstar.query_counts().equivalence, 1);
+        assert_eq!(lstar.table().rows().len(), 4);
+        assert!(lstar.table().is_closed() && lstar.table().is_consistent());
+        assert!(lstar.queries().words.len() >= sample.words.len());
+    }
+
     #[test]
     fn lstar_mealy() {
         let mm = NTS::builder()
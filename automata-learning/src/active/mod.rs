@@ -1,6 +1,32 @@
 mod lstar;
 pub use lstar::*;
 
+mod table;
+pub use table::ObservationTable;
+
+mod export;
+pub use export::DiscriminationTree;
+
+mod equivalence;
+pub use equivalence::*;
+
+mod sequences;
+pub use sequences::{AdaptiveSequence, TestingSequences};
+
+mod cache;
+pub use cache::CachedOracle;
+
+pub mod sul;
+pub use sul::{SulOracle, SystemUnderLearning};
+
+pub mod log;
+pub use log::{QueryLog, RecordingOracle, ReplayOracle};
+
+#[cfg(feature = "persist")]
+mod checkpoint;
+#[cfg(feature = "persist")]
+pub use checkpoint::{latest_checkpoint, Checkpoint};
+
 pub(crate) mod oracle;
 pub use oracle::*;
 
@@ -1,4 +1,6 @@
-use automata::{prelude::*, transition_system::operations::MapStateColor, word::LinearWord};
+use std::cell::{Cell, RefCell};
+
+use automata::{prelude::*, transition_system::operations::MapStateColor, word::LinearWord, Map};
 
 use crate::passive::Sample;
 
@@ -16,6 +18,13 @@ pub trait LStarOracle<H: LStarHypothesis> {
     /// Query the desired output for the given word.
     fn output<W: FiniteWord<SymbolOf<H>>>(&self, word: W) -> H::Color;
 
+    /// Queries the outputs for a batch of independent words, returned in the same order. By
+    /// default, the words are queried one after the other, but oracles may override this to
+    /// dispatch them concurrently, see [`ParallelOracle`].
+    fn outputs(&self, words: &[Vec<SymbolOf<H>>]) -> Vec<H::Color> {
+        words.iter().map(|w| self.output(w)).collect()
+    }
+
     fn alphabet(&self) -> H::Alphabet;
 
     /// Test the given hypothesis for equivalence, returning `Ok(())` if it is equivalent and `Err((word, color))` otherwise.
@@ -266,6 +275,158 @@ where
     }
 }
 
+impl<D> OutputQueryOracle<D::EdgeColor> for MealyOracle<D>
+where
+    D: Congruence<Alphabet = CharAlphabet>,
+    EdgeColor<D>: Color + Default,
+{
+    fn alphabet(&self) -> CharAlphabet {
+        self.automaton.alphabet().clone()
+    }
+
+    fn output_word(&self, input: &[char]) -> Vec<D::EdgeColor> {
+        (1..=input.len())
+            .map(|i| <Self as LStarOracle<MealyMachine<_, _>>>::output(self, &input[..i]))
+            .collect()
+    }
+
+    fn equivalence(
+        &self,
+        hypothesis: &MealyMachine<CharAlphabet, D::EdgeColor>,
+    ) -> Result<(), Vec<char>> {
+        LStarOracle::equivalence(self, hypothesis).map_err(|(w, _)| w)
+    }
+}
+
+/// A teacher for Mealy machines that answers output queries. Such a query returns the output
+/// word that the target produces on an input word, which consists of one output for each
+/// input symbol.
+pub trait OutputQueryOracle<O: Color> {
+    /// Returns the alphabet of input symbols.
+    fn alphabet(&self) -> CharAlphabet;
+
+    /// Returns the output word for the given input word.
+    fn output_word(&self, input: &[char]) -> Vec<O>;
+
+    /// Tests the hypothesis for equivalence, returning an input word on which the output words
+    /// of the target and the hypothesis differ if they are not equivalent.
+    fn equivalence(&self, hypothesis: &MealyMachine<CharAlphabet, O>) -> Result<(), Vec<char>>;
+}
+
+/// Lets L* learn from an [`OutputQueryOracle`]. The cells of the observation table of a Mealy
+/// machine hold the last output that is produced on the concatenation of a row and a suffix,
+/// and as the answer to an output query contains the outputs on all prefixes of the input, all
+/// of them are cached. Hence, a single output query fills the cells of all prefixes of a word.
+pub struct OutputQueries<T, O> {
+    teacher: T,
+    cache: RefCell<Map<Vec<char>, O>>,
+    queries: Cell<usize>,
+}
+
+impl<T: OutputQueryOracle<O>, O: Color> OutputQueries<T, O> {
+    /// Wraps the given teacher.
+    pub fn new(teacher: T) -> Self {
+        Self {
+            teacher,
+            cache: RefCell::new(Map::default()),
+            queries: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of output queries that were posed to the teacher.
+    pub fn queries(&self) -> usize {
+        self.queries.get()
+    }
+
+    /// Returns the underlying teacher.
+    pub fn teacher(&self) -> &T {
+        &self.teacher
+    }
+}
+
+impl<T: OutputQueryOracle<O>, O: Color + Default> LStarOracle<MealyMachine<CharAlphabet, O>>
+    for OutputQueries<T, O>
+{
+    fn output<W: FiniteWord<char>>(&self, word: W) -> O {
+        let word = word.to_vec();
+        if let Some(output) = self.cache.borrow().get(&word) {
+            return output.clone();
+        }
+        self.queries.set(self.queries.get() + 1);
+        let outputs = self.teacher.output_word(&word);
+        assert_eq!(
+            outputs.len(),
+            word.len(),
+            "Output word has the wrong length"
+        );
+        let mut cache = self.cache.borrow_mut();
+        for (i, output) in outputs.into_iter().enumerate() {
+            cache.insert(word[..=i].to_vec(), output);
+        }
+        cache
+            .get(&word)
+            .expect("Mealy machines produce no output on the empty word")
+            .clone()
+    }
+
+    fn equivalence(
+        &self,
+        hypothesis: &MealyMachine<CharAlphabet, O>,
+    ) -> Result<(), (Vec<char>, O)> {
+        let Err(counterexample) = self.teacher.equivalence(hypothesis) else {
+            return Ok(());
+        };
+        // the shortest prefix on which the last outputs differ is a counterexample for L*
+        for i in 1..=counterexample.len() {
+            let prefix = counterexample[..i].to_vec();
+            let expected = self.output(&prefix);
+            if hypothesis.try_mealy_map(&prefix).as_ref() != Some(&expected) {
+                return Err((prefix, expected));
+            }
+        }
+        panic!("Output words of hypothesis and target agree on the counterexample")
+    }
+
+    fn alphabet(&self) -> CharAlphabet {
+        self.teacher.alphabet()
+    }
+}
+
+/// Wraps an oracle and answers batches of membership queries in parallel on the rayon thread
+/// pool, which pays off if queries are slow, e.g. because they are sent over the network. As
+/// the answers are returned in the order of the queries, the observation table that L* builds
+/// does not depend on the order in which the queries complete.
+#[cfg(feature = "parallel")]
+#[derive(Debug, Clone)]
+pub struct ParallelOracle<O>(pub O);
+
+#[cfg(feature = "parallel")]
+impl<H, O> LStarOracle<H> for ParallelOracle<O>
+where
+    H: LStarHypothesis,
+    O: LStarOracle<H> + Sync,
+    H::Color: Send,
+    SymbolOf<H>: Sync,
+{
+    fn output<W: FiniteWord<SymbolOf<H>>>(&self, word: W) -> H::Color {
+        self.0.output(word)
+    }
+
+    fn outputs(&self, words: &[Vec<SymbolOf<H>>]) -> Vec<H::Color> {
+        use rayon::prelude::*;
+
+        words.par_iter().map(|w| self.0.output(w)).collect()
+    }
+
+    fn alphabet(&self) -> H::Alphabet {
+        self.0.alphabet()
+    }
+
+    fn equivalence(&self, hypothesis: &H) -> Result<(), (Vec<SymbolOf<H>>, H::Color)> {
+        self.0.equivalence(hypothesis)
+    }
+}
+
 /// An oracle based on a [`MooreMachine`].
 #[derive(Debug, Clone)]
 pub struct MooreOracle<D> {
@@ -315,7 +476,7 @@ mod tests {
 
     use crate::active::LStar;
 
-    use super::MealyOracle;
+    use super::{MealyOracle, OutputQueries};
 
     #[test]
     fn mealy_al() {
@@ -335,4 +496,25 @@ mod tests {
         let mm = learner.infer();
         assert_eq!(mm.size(), 2);
     }
+
+    #[test]
+    fn mealy_output_queries() {
+        let target = NTS::builder()
+            .with_transitions([
+                (0, 'a', 1, 1),
+                (0, 'b', 0, 2),
+                (1, 'a', 1, 2),
+                (1, 'b', 0, 0),
+                (2, 'a', 0, 0),
+                (2, 'b', 1, 1),
+            ])
+            .into_mealy_machine(0);
+        let teacher = OutputQueries::new(MealyOracle::new(target, None));
+        let mut learner = LStar::for_mealy(teacher.teacher().alphabet().clone(), teacher);
+        let mm = learner.infer();
+        assert_eq!(mm.size(), 3);
+        // every output query answers the queries for all prefixes of the word
+        let oracle = learner.oracle();
+        assert!(oracle.queries() < learner.queries().words.len());
+    }
 }
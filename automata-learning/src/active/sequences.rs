@@ -0,0 +1,342 @@
+//! Classic experiments from the testing theory of Mealy machines, which complement the
+//! conformance tests of [`WMethod`](super::WMethod) and [`WpMethod`](super::WpMethod). The
+//! output of an input is the color of the edge that is taken, state colors are ignored. All
+//! experiments assume that the machine is complete and panic otherwise. Whether one of them
+//! exists depends on the machine, so they are returned as an [`Option`] that is `None` if no
+//! such experiment exists.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+
+/// Returns the successor of `q` on `sym` together with the output, i.e. the color of the edge.
+fn step<D: Deterministic<Alphabet = CharAlphabet>>(
+    ts: &D,
+    q: D::StateIndex,
+    sym: char,
+) -> (D::StateIndex, D::EdgeColor) {
+    let e = ts
+        .edges_from(q)
+        .expect("State must exist")
+        .find(|e| *e.expression() == sym)
+        .expect("Transition system must be complete");
+    (e.target(), e.color())
+}
+
+/// Returns the state that is reached from `q` on `word` together with the produced outputs.
+fn run<D: Deterministic<Alphabet = CharAlphabet>>(
+    ts: &D,
+    q: D::StateIndex,
+    word: &[char],
+) -> (D::StateIndex, Vec<D::EdgeColor>) {
+    word.iter().fold((q, vec![]), |(q, mut outputs), sym| {
+        let (target, output) = step(ts, q, *sym);
+        outputs.push(output);
+        (target, outputs)
+    })
+}
+
+/// Groups `items` by their output, keeping the order in which outputs first occur.
+fn group_by_output<O: PartialEq, T>(items: impl IntoIterator<Item = (O, T)>) -> Vec<(O, Vec<T>)> {
+    let mut groups: Vec<(O, Vec<T>)> = vec![];
+    for (output, item) in items {
+        match groups.iter_mut().find(|(o, _)| *o == output) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((output, vec![item])),
+        }
+    }
+    groups
+}
+
+/// Returns a shortest word after which the runs from the distinct states `p` and `q` reach the
+/// same state or, if `separate` is set, have produced different outputs.
+fn shortest_resolving_word<D: Deterministic<Alphabet = CharAlphabet>>(
+    ts: &D,
+    p: D::StateIndex,
+    q: D::StateIndex,
+    separate: bool,
+) -> Option<Vec<char>> {
+    let symbols = ts.alphabet().universe().sorted().collect_vec();
+    let mut seen: Set<_> = [(p.min(q), p.max(q))].into_iter().collect();
+    let mut queue = VecDeque::from([((p, q), vec![])]);
+    while let Some(((p, q), word)) = queue.pop_front() {
+        for sym in &symbols {
+            let ((p, p_out), (q, q_out)) = (step(ts, p, *sym), step(ts, q, *sym));
+            let mut next = word.clone();
+            next.push(*sym);
+            if p == q || (separate && p_out != q_out) {
+                return Some(next);
+            }
+            if seen.insert((p.min(q), p.max(q))) {
+                queue.push_back(((p, q), next));
+            }
+        }
+    }
+    None
+}
+
+/// Searches an adaptive distinguishing sequence for the given pairs of an initial and a
+/// current state, where the current states are distinct. Sets of current states that already
+/// occur on `path` are not expanded again, as a shorter experiment would do the same.
+#[allow(clippy::type_complexity)]
+fn adaptive<D: Deterministic<Alphabet = CharAlphabet>>(
+    ts: &D,
+    symbols: &[char],
+    pairs: Vec<(D::StateIndex, D::StateIndex)>,
+    path: &mut Vec<BTreeSet<D::StateIndex>>,
+) -> Option<AdaptiveSequence<D::StateIndex, D::EdgeColor>> {
+    match pairs[..] {
+        [] => return None,
+        [(initial, _)] => return Some(AdaptiveSequence::Identified(initial)),
+        _ => {}
+    }
+    let current: BTreeSet<_> = pairs.iter().map(|(_, q)| *q).collect();
+    if path.contains(&current) {
+        return None;
+    }
+    path.push(current);
+    let found = symbols.iter().find_map(|sym| {
+        let groups = group_by_output(pairs.iter().map(|(initial, q)| {
+            let (target, output) = step(ts, *q, *sym);
+            (output, (*initial, target))
+        }));
+        // states that produce the same output and merge can never be told apart
+        if groups
+            .iter()
+            .any(|(_, group)| !group.iter().map(|(_, q)| q).all_unique())
+        {
+            return None;
+        }
+        let next = groups
+            .into_iter()
+            .map(|(output, group)| Some((output, adaptive(ts, symbols, group, path)?)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(AdaptiveSequence::Apply { input: *sym, next })
+    });
+    path.pop();
+    found
+}
+
+/// An adaptive distinguishing sequence, i.e. a decision tree that chooses the next input
+/// based on the outputs observed so far and identifies the state in which it was started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdaptiveSequence<Idx, O> {
+    /// The experiment is finished and was started in the given state.
+    Identified(Idx),
+    /// Apply `input` and continue with the subtree for the observed output.
+    Apply {
+        /// The input that is applied.
+        input: char,
+        /// The subtree for each output that can be observed.
+        next: Vec<(O, AdaptiveSequence<Idx, O>)>,
+    },
+}
+
+impl<Idx, O: PartialEq> AdaptiveSequence<Idx, O> {
+    /// Runs the experiment, where `output` applies an input to the system under test and
+    /// returns its output. Returns the identified state, or `None` if an output is observed
+    /// that no state of the machine produces.
+    pub fn run<F: FnMut(char) -> O>(&self, mut output: F) -> Option<&Idx> {
+        let mut node = self;
+        loop {
+            match node {
+                Self::Identified(q) => return Some(q),
+                Self::Apply { input, next } => {
+                    let observed = output(*input);
+                    node = &next.iter().find(|(o, _)| *o == observed)?.1;
+                }
+            }
+        }
+    }
+
+    /// Returns the maximal number of inputs that the experiment applies.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Identified(_) => 0,
+            Self::Apply { next, .. } => 1 + next.iter().map(|(_, t)| t.depth()).max().unwrap_or(0),
+        }
+    }
+}
+
+/// Homing, synchronizing, adaptive distinguishing and UIO sequences of complete deterministic
+/// transition systems whose outputs are the colors of their edges, see the module
+/// documentation.
+pub trait TestingSequences: Deterministic<Alphabet = CharAlphabet> {
+    /// Returns a homing sequence, i.e. a word such that the outputs it produces determine the
+    /// state that is reached, no matter in which state it is applied. It is built greedily by
+    /// appending shortest words that separate or merge two states which are not yet told apart,
+    /// which finds one whenever one exists. Minimal machines always have one.
+    fn homing_sequence(&self) -> Option<Vec<char>> {
+        let mut blocks: Vec<Vec<Self::StateIndex>> = vec![self.state_indices().sorted().collect()];
+        let mut word = vec![];
+        while let Some((p, q)) = blocks
+            .iter()
+            .find(|block| block.len() > 1)
+            .map(|block| (block[0], block[1]))
+        {
+            let extension = shortest_resolving_word(self, p, q, true)?;
+            blocks = blocks
+                .into_iter()
+                .flat_map(|block| {
+                    group_by_output(block.into_iter().map(|q| {
+                        let (target, outputs) = run(self, q, &extension);
+                        (outputs, target)
+                    }))
+                })
+                .map(|(_, targets)| targets.into_iter().sorted().dedup().collect())
+                .collect();
+            word.extend(extension);
+        }
+        Some(word)
+    }
+
+    /// Returns a synchronizing word, i.e. a word that leads every state to the same state.
+    /// Like [`Self::homing_sequence`], it is built greedily by merging two of the states that
+    /// are still possible, which finds one whenever one exists, but not necessarily a shortest
+    /// one.
+    fn synchronizing_word(&self) -> Option<Vec<char>> {
+        let mut current = self.state_indices().sorted().collect_vec();
+        let mut word = vec![];
+        while let [p, q, ..] = current[..] {
+            let extension = shortest_resolving_word(self, p, q, false)?;
+            current = current
+                .into_iter()
+                .map(|q| run(self, q, &extension).0)
+                .sorted()
+                .dedup()
+                .collect();
+            word.extend(extension);
+        }
+        Some(word)
+    }
+
+    /// Returns an adaptive distinguishing sequence, which identifies the state in which it is
+    /// started, or `None` if there is none. This is the case if two states are equivalent, but
+    /// may also happen for minimal machines. The search is exhaustive and thus takes
+    /// exponential time in the worst case.
+    fn adaptive_distinguishing_sequence(
+        &self,
+    ) -> Option<AdaptiveSequence<Self::StateIndex, Self::EdgeColor>> {
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        let pairs = self.state_indices().sorted().map(|q| (q, q)).collect();
+        adaptive(self, &symbols, pairs, &mut vec![])
+    }
+
+    /// Returns a shortest unique input output sequence for `state`, i.e. a word on which the
+    /// outputs from `state` differ from the outputs from every other state, or `None` if there
+    /// is none. This searches the subsets of states that agree with `state` on the outputs so
+    /// far, so it takes exponential time in the worst case.
+    fn uio_sequence(&self, state: Self::StateIndex) -> Option<Vec<char>> {
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        let others: BTreeSet<_> = self.state_indices().filter(|q| *q != state).collect();
+        if others.is_empty() {
+            return Some(vec![]);
+        }
+        let mut seen: Set<_> = [(state, others.clone())].into_iter().collect();
+        let mut queue = VecDeque::from([((state, others), vec![])]);
+        while let Some(((q, others), word)) = queue.pop_front() {
+            for sym in &symbols {
+                let (target, output) = step(self, q, *sym);
+                let agreeing: BTreeSet<_> = others
+                    .iter()
+                    .filter_map(|p| {
+                        let (p, p_output) = step(self, *p, *sym);
+                        (p_output == output).then_some(p)
+                    })
+                    .collect();
+                let mut next = word.clone();
+                next.push(*sym);
+                if agreeing.is_empty() {
+                    return Some(next);
+                }
+                // a state that agrees so far and merges with the run from `state` never differs
+                if !agreeing.contains(&target) && seen.insert((target, agreeing.clone())) {
+                    queue.push_back(((target, agreeing), next));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns a UIO sequence, as given by [`Self::uio_sequence`], for every state that has
+    /// one.
+    fn uio_sequences(&self) -> Map<Self::StateIndex, Vec<char>> {
+        self.state_indices()
+            .filter_map(|q| Some((q, self.uio_sequence(q)?)))
+            .collect()
+    }
+}
+
+impl<D: Deterministic<Alphabet = CharAlphabet>> TestingSequences for D {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::TestingSequences;
+
+    #[test]
+    fn testing_sequences() {
+        let mm = NTS::builder()
+            .with_transitions([
+                (0, 'a', 0, 1),
+                (0, 'b', 0, 0),
+                (1, 'a', 0, 2),
+                (1, 'b', 1, 0),
+                (2, 'a', 1, 0),
+                (2, 'b', 0, 2),
+            ])
+            .deterministic()
+            .with_initial(0)
+            .into_mealy();
+        let step = |q: usize, sym: char| {
+            let e = mm.edges_from(q).unwrap().find(|e| *e.expression() == sym);
+            e.map(|e| (e.target(), e.color())).unwrap()
+        };
+        let outputs_from = |mut q: usize, word: &[char]| {
+            word.iter()
+                .map(|sym| {
+                    let (target, output) = step(q, *sym);
+                    q = target;
+                    output
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let homing = mm.homing_sequence().expect("The machine is minimal");
+        assert_eq!(homing, vec!['b', 'a']);
+        assert_eq!(mm.synchronizing_word(), Some(vec!['b', 'a', 'b']));
+
+        let ads = mm
+            .adaptive_distinguishing_sequence()
+            .expect("An adaptive distinguishing sequence exists");
+        assert_eq!(ads.depth(), 2);
+        for q in 0..3 {
+            let mut current = q;
+            let identified = ads.run(|sym| {
+                let (target, output) = step(current, sym);
+                current = target;
+                output
+            });
+            assert_eq!(identified, Some(&q));
+        }
+
+        assert_eq!(mm.uio_sequence(1), Some(vec!['b']));
+        let uio = mm.uio_sequence(0).unwrap();
+        assert_eq!(uio, vec!['a', 'a']);
+        assert!((1..3).all(|q| outputs_from(q, &uio) != outputs_from(0, &uio)));
+        assert_eq!(mm.uio_sequences().len(), 3);
+
+        // two equivalent states that are swapped by every input
+        let swap = NTS::builder()
+            .with_transitions([(0, 'a', 0, 1), (1, 'a', 0, 0)])
+            .deterministic()
+            .with_initial(0)
+            .into_mealy();
+        assert_eq!(swap.homing_sequence(), None);
+        assert_eq!(swap.synchronizing_word(), None);
+        assert_eq!(swap.adaptive_distinguishing_sequence(), None);
+        assert_eq!(swap.uio_sequence(0), None);
+    }
+}
@@ -0,0 +1,279 @@
+//! Adapters for learning models of black-box systems. A [`SystemUnderLearning`] can be reset
+//! and then fed one input symbol at a time, producing an output for each. Adapters are provided
+//! for closures ([`FnSul`]), Mealy machines ([`MealySul`]) and external processes
+//! ([`ProcessSul`]). A [`SulOracle`] answers membership and output queries by running the
+//! system and combines it with an [`EquivalenceOracle`] to obtain a teacher for L*.
+
+use std::{
+    cell::RefCell,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use automata::prelude::*;
+
+use super::{EquivalenceOracle, LStarOracle, OutputQueryOracle};
+
+/// A reactive system that is learned as a Mealy machine.
+pub trait SystemUnderLearning {
+    /// The type of outputs that the system produces.
+    type Output: Color;
+
+    /// Brings the system back to its initial state.
+    fn reset(&mut self);
+
+    /// Feeds `symbol` into the system and returns the output it produces.
+    fn step(&mut self, symbol: char) -> Self::Output;
+
+    /// Resets the system and returns the outputs it produces on `input`.
+    fn run(&mut self, input: &[char]) -> Vec<Self::Output> {
+        self.reset();
+        input.iter().map(|sym| self.step(*sym)).collect()
+    }
+}
+
+/// A system whose state of type `S` is updated by a closure, which also gives the output.
+#[derive(Clone)]
+pub struct FnSul<S, F> {
+    initial: S,
+    state: S,
+    step: F,
+}
+
+impl<S: Clone, F> FnSul<S, F> {
+    /// Creates a system that starts in `initial` and processes symbols with `step`.
+    pub fn new(initial: S, step: F) -> Self {
+        Self {
+            state: initial.clone(),
+            initial,
+            step,
+        }
+    }
+}
+
+impl<S: Clone, O: Color, F: FnMut(&mut S, char) -> O> SystemUnderLearning for FnSul<S, F> {
+    type Output = O;
+
+    fn reset(&mut self) {
+        self.state = self.initial.clone();
+    }
+
+    fn step(&mut self, symbol: char) -> O {
+        (self.step)(&mut self.state, symbol)
+    }
+}
+
+/// A system that is simulated by a complete Mealy machine.
+#[derive(Clone)]
+pub struct MealySul<C: Color> {
+    machine: MealyMachine<CharAlphabet, C>,
+    input: Vec<char>,
+}
+
+impl<C: Color> MealySul<C> {
+    /// Creates a system that behaves like `machine`.
+    pub fn new(machine: MealyMachine<CharAlphabet, C>) -> Self {
+        Self {
+            machine,
+            input: vec![],
+        }
+    }
+}
+
+impl<C: Color> SystemUnderLearning for MealySul<C> {
+    type Output = C;
+
+    fn reset(&mut self) {
+        self.input.clear();
+    }
+
+    fn step(&mut self, symbol: char) -> C {
+        self.input.push(symbol);
+        self.machine
+            .last_edge_color(&self.input)
+            .expect("Mealy machine must be complete")
+    }
+}
+
+/// An external process that communicates over its standard input and output. It has to follow
+/// a line based protocol: a line `reset` resets the system without an answer, any other line
+/// contains a single input symbol and has to be answered with a line that contains the output.
+/// The process is killed when this is dropped.
+pub struct ProcessSul {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ProcessSul {
+    /// Spawns `command` with piped standard input and output.
+    pub fn spawn(command: &mut Command) -> std::io::Result<Self> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("Standard input is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("Standard output is piped"));
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.stdin, "{line}")
+            .and_then(|_| self.stdin.flush())
+            .expect("Could not write to the process");
+    }
+}
+
+impl SystemUnderLearning for ProcessSul {
+    type Output = String;
+
+    fn reset(&mut self) {
+        self.send("reset");
+    }
+
+    fn step(&mut self, symbol: char) -> String {
+        self.send(&symbol.to_string());
+        let mut line = String::new();
+        let read = self
+            .stdout
+            .read_line(&mut line)
+            .expect("Could not read from the process");
+        assert!(read > 0, "Process closed its output");
+        line.trim_end().to_string()
+    }
+}
+
+impl Drop for ProcessSul {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Answers queries by running a [`SystemUnderLearning`] and equivalence queries with an
+/// [`EquivalenceOracle`], so it can be used to learn the system as a Mealy machine with L*.
+pub struct SulOracle<S, E> {
+    alphabet: CharAlphabet,
+    sul: RefCell<S>,
+    equivalence: E,
+}
+
+impl<S: SystemUnderLearning, E> SulOracle<S, E> {
+    /// Creates an oracle for `sul` with inputs from `alphabet`.
+    pub fn new(alphabet: CharAlphabet, sul: S, equivalence: E) -> Self {
+        Self {
+            alphabet,
+            sul: RefCell::new(sul),
+            equivalence,
+        }
+    }
+
+    /// Returns the last output that the system produces on the non-empty word `input`.
+    pub fn membership(&self, input: &[char]) -> S::Output {
+        self.sul
+            .borrow_mut()
+            .run(input)
+            .pop()
+            .expect("Mealy machines produce no output on the empty word")
+    }
+}
+
+impl<S, E> LStarOracle<MealyMachine<CharAlphabet, S::Output>> for SulOracle<S, E>
+where
+    S: SystemUnderLearning,
+    S::Output: Default,
+    E: EquivalenceOracle<MealyMachine<CharAlphabet, S::Output>>,
+{
+    fn output<W: FiniteWord<char>>(&self, word: W) -> S::Output {
+        self.membership(&word.to_vec())
+    }
+
+    fn alphabet(&self) -> CharAlphabet {
+        self.alphabet.clone()
+    }
+
+    fn equivalence(
+        &self,
+        hypothesis: &MealyMachine<CharAlphabet, S::Output>,
+    ) -> Result<(), (Vec<char>, S::Output)> {
+        match self
+            .equivalence
+            .counterexample(hypothesis, &|w: &[char]| self.membership(w))
+        {
+            Some(word) => {
+                let output = self.membership(&word);
+                Err((word, output))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<S, E> OutputQueryOracle<S::Output> for SulOracle<S, E>
+where
+    S: SystemUnderLearning,
+    S::Output: Default,
+    E: EquivalenceOracle<MealyMachine<CharAlphabet, S::Output>>,
+{
+    fn alphabet(&self) -> CharAlphabet {
+        self.alphabet.clone()
+    }
+
+    fn output_word(&self, input: &[char]) -> Vec<S::Output> {
+        self.sul.borrow_mut().run(input)
+    }
+
+    fn equivalence(
+        &self,
+        hypothesis: &MealyMachine<CharAlphabet, S::Output>,
+    ) -> Result<(), Vec<char>> {
+        LStarOracle::equivalence(self, hypothesis).map_err(|(w, _)| w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{FnSul, MealySul, SulOracle, SystemUnderLearning};
+    use crate::active::{LStar, OutputQueries, WMethod};
+
+    #[test]
+    fn learn_system_under_learning() {
+        let alphabet = CharAlphabet::from_iter(['a', 'b']);
+        // counts the a's modulo three and signals whenever the count wraps around
+        let sul = FnSul::new(0usize, |count: &mut usize, sym: char| {
+            if sym == 'a' {
+                *count = (*count + 1) % 3;
+            }
+            usize::from(*count == 0)
+        });
+        let oracle = SulOracle::new(alphabet.clone(), sul, WMethod::new(1));
+        let learned = LStar::mealy(oracle);
+        assert_eq!(learned.size(), 3);
+
+        let mut simulated = MealySul::new(learned.clone());
+        assert_eq!(simulated.run(&['a', 'b', 'a', 'a']), vec![0, 0, 0, 1]);
+        let teacher = OutputQueries::new(SulOracle::new(alphabet, simulated, WMethod::new(1)));
+        assert_eq!(LStar::mealy(teacher).size(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn process_sul() {
+        use super::ProcessSul;
+
+        // outputs the parity of the number of inputs
+        let script = r#"n=0; while read line; do
+            if [ "$line" = reset ]; then n=0; else n=$(( (n + 1) % 2 )); echo $n; fi
+        done"#;
+        let mut sul = ProcessSul::spawn(std::process::Command::new("sh").args(["-c", script]))
+            .expect("sh must be available");
+        assert_eq!(sul.run(&['a', 'b', 'a']), vec!["1", "0", "1"]);
+        assert_eq!(sul.run(&['b']), vec!["1"]);
+    }
+}
@@ -0,0 +1,364 @@
+use automata::{prelude::*, word::Concat, Map, Set};
+use itertools::Itertools;
+use tracing::{debug, trace};
+
+use super::LStarHypothesis;
+
+type Word<D> = Vec<SymbolOf<D>>;
+
+/// The observation table of L*. Its rows are indexed by the access words of the states of the
+/// hypothesis and their one letter extensions, its columns by suffixes (also called
+/// experiments). The entry for a row `u` and a suffix `e` is the output of the target on `ue`.
+///
+/// The table is closed if every one letter extension has the same row as some access word,
+/// and consistent if access words with equal rows have equal rows for every one letter
+/// extension. A hypothesis can be constructed from a table that is closed and consistent.
+#[derive(Clone)]
+pub struct ObservationTable<D: LStarHypothesis> {
+    alphabet: D::Alphabet,
+    // the minimal access words forming the base states
+    base: Vec<Word<D>>,
+    // all known experiments
+    experiments: Vec<Word<D>>,
+    // mapping from input word to its row, where the i-th entry gives the output of
+    // concatenating input word and i-th experiment
+    table: Map<Word<D>, Vec<D::Color>>,
+}
+
+impl<D: LStarHypothesis> ObservationTable<D> {
+    /// Creates a table whose only access word is the empty word, with the experiments that are
+    /// mandatory for the type of hypothesis.
+    pub fn new(alphabet: D::Alphabet) -> Self {
+        Self {
+            experiments: D::mandatory_experiments(&alphabet).into_iter().collect(),
+            alphabet,
+            base: vec![vec![]],
+            table: Map::default(),
+        }
+    }
+
+    /// Returns the access words of the states, in the order in which they were added.
+    pub fn rows(&self) -> &[Word<D>] {
+        &self.base
+    }
+
+    /// Returns the suffixes that index the columns, in the order in which they were added.
+    pub fn suffixes(&self) -> &[Word<D>] {
+        &self.experiments
+    }
+
+    /// Returns the row of `word`, if it is an access word or a one letter extension of one
+    /// and the table has been filled.
+    pub fn row(&self, word: &[SymbolOf<D>]) -> Option<&[D::Color]> {
+        self.table.get(word).map(|row| row.as_slice())
+    }
+
+    /// Adds an access word, returns `false` if it is already present.
+    pub fn add_row(&mut self, word: Word<D>) -> bool {
+        if self.base.contains(&word) {
+            return false;
+        }
+        self.base.push(word);
+        true
+    }
+
+    /// Adds a suffix, returns `false` if it is already present.
+    pub fn add_suffix(&mut self, suffix: Word<D>) -> bool {
+        if self.experiments.contains(&suffix) {
+            return false;
+        }
+        trace!("Adding experiment {}", suffix.as_string());
+        self.experiments.push(suffix);
+        true
+    }
+
+    /// Returns the access words followed by all of their one letter extensions that are not
+    /// access words themselves. Extensions are ordered by their last symbol.
+    pub fn one_letter_extensions(&self) -> impl Iterator<Item = Word<D>> + '_ {
+        self.base
+            .iter()
+            .flat_map(|w| {
+                std::iter::once(w.clone()).chain(self.alphabet.universe().sorted().filter_map(
+                    |a| {
+                        let mut x = w.clone();
+                        x.push(a);
+                        if !self.base.contains(&x) {
+                            Some(x)
+                        } else {
+                            None
+                        }
+                    },
+                ))
+            })
+            .unique()
+    }
+
+    /// Returns the words whose outputs are needed to fill the missing entries of the table, in
+    /// the order in which [`Self::fill`] queries them. A word may occur more than once.
+    pub fn missing(&self) -> Vec<Word<D>> {
+        self.one_letter_extensions()
+            .flat_map(|mr| {
+                let stored = self.table.get(&mr).map(|r| r.len()).unwrap_or(0);
+                self.experiments[stored..]
+                    .iter()
+                    .map(move |e| Concat(&mr, e).to_vec())
+                    .collect_vec()
+            })
+            .collect()
+    }
+
+    /// Fills in all missing entries of the table, using `query` to obtain the output of a word.
+    pub fn fill(&mut self, mut query: impl FnMut(&Word<D>) -> D::Color) {
+        let mut updates = vec![];
+        let experiment_count = self.experiments.len();
+
+        for mr in self.one_letter_extensions() {
+            let stored_experiment_count = self.table.get(&mr).map(|r| r.len()).unwrap_or(0);
+            trace!("Have stored {stored_experiment_count} out of {experiment_count} experiments for {}", mr.as_string());
+            if stored_experiment_count < experiment_count {
+                for i in stored_experiment_count..experiment_count {
+                    let concat = Concat(&mr, &self.experiments[i]).to_vec();
+                    let output = query(&concat);
+                    trace!(
+                        "Adding update that {} maps to {}",
+                        concat.as_string(),
+                        output.show()
+                    );
+                    updates.push((mr.clone(), i, output));
+                }
+            } else {
+                assert_eq!(
+                    stored_experiment_count,
+                    experiment_count,
+                    "Too many experiments present for {}",
+                    mr.as_string()
+                );
+            }
+        }
+
+        for (mr, i, output) in updates {
+            assert!(i < self.experiments.len());
+            let mut row = self.table.entry(mr).or_default();
+            row.push(output);
+        }
+
+        if cfg!(debug_assertions) {
+            for mr in self.one_letter_extensions() {
+                let Some(val) = self.table.get(&mr) else {
+                    panic!("No table entry for {}", mr.as_string());
+                };
+                if val.len() != experiment_count {
+                    panic!(
+                        "Row for {} does not have enough entries, has {} of {experiment_count}",
+                        mr.show(),
+                        val.len()
+                    )
+                }
+            }
+        }
+
+        trace!("After update the table is\n{:?}", self);
+    }
+
+    /// Returns one extension for each row that is missing among the access words, which are
+    /// exactly the words that have to be promoted to close the table. They are returned in the
+    /// order of [`Self::one_letter_extensions`], so the rows are promoted in the same order in
+    /// every run.
+    pub fn unclosed_rows(&self) -> Vec<Word<D>> {
+        let start = std::time::Instant::now();
+
+        let known = Set::from_iter(self.base.iter().map(|b| {
+            self.table.get(b).unwrap_or_else(|| {
+                panic!(
+                    "Experiment {} must be present",
+                    owo_colors::OwoColorize::blue(&b.as_string())
+                )
+            })
+        }));
+        let mut seen = Set::default();
+        let mut out = vec![];
+
+        for word in self.one_letter_extensions() {
+            trace!("Considering one letter extension {}", word.as_string());
+            let seq = self.table.get(&word).unwrap();
+            if !known.contains(seq) && seen.insert(seq) {
+                out.push(word);
+            }
+        }
+
+        debug!(
+            "Finding rows to promote took {} microseconds",
+            start.elapsed().as_micros()
+        );
+        out
+    }
+
+    /// Checks whether the table is closed.
+    pub fn is_closed(&self) -> bool {
+        self.unclosed_rows().is_empty()
+    }
+
+    /// Returns a suffix that separates two access words with equal rows, if there is one. As
+    /// L* only promotes rows that differ from all access words, its tables are always
+    /// consistent.
+    pub fn inconsistency(&self) -> Option<Word<D>> {
+        for (i, left) in self.base.iter().enumerate() {
+            'middle: for right in &self.base[i + 1..] {
+                if self.table.get(left) != self.table.get(right) {
+                    continue 'middle;
+                }
+
+                for sym in self.alphabet.universe().sorted() {
+                    let left_ext = left
+                        .iter()
+                        .chain(std::iter::once(&sym))
+                        .cloned()
+                        .collect_vec();
+                    let right_ext = right
+                        .iter()
+                        .chain(std::iter::once(&sym))
+                        .cloned()
+                        .collect_vec();
+
+                    let l = self.table.get(&left_ext).expect("Should be present!");
+                    let r = self.table.get(&right_ext).expect("Should be present!");
+
+                    if l == r {
+                        continue;
+                    }
+                    'inner: for (j, e) in self.experiments.iter().enumerate() {
+                        assert!(j < std::cmp::min(l.len(), r.len()));
+                        if l[j] == r[j] {
+                            continue 'inner;
+                        }
+                        return Some(Concat(vec![sym], e).to_vec());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Checks whether the table is consistent.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistency().is_none()
+    }
+
+    fn state_color(&self, mr: &Word<D>) -> D::StateColor {
+        D::give_state_color(
+            mr,
+            &self.experiments,
+            self.table
+                .get(mr)
+                .expect("Cannot give color for non-existent word"),
+        )
+    }
+
+    fn edge_color(&self, mr: &Word<D>, sym: SymbolOf<D>) -> D::EdgeColor {
+        D::give_transition_color(mr, sym, &self.experiments, self.table.get(mr).unwrap())
+    }
+
+    /// Constructs the hypothesis, which has one state for each access word. States are added in
+    /// the order of the access words and their edges in the order of the symbols, so the
+    /// result does not depend on the order of hash maps. Panics if the table is not closed and
+    /// filled.
+    pub fn hypothesis(&self) -> D {
+        let start = std::time::Instant::now();
+
+        let mut ts: DTS<_, _, _> = DTS::new_for_alphabet(self.alphabet.clone());
+        let mut state_map = Map::default();
+        let mut observations = Map::default();
+
+        for mr in &self.base {
+            let color = self.state_color(mr);
+            let id = ts.add_state(color);
+            state_map.insert(mr, id);
+
+            let observed = self.table.get(mr).unwrap();
+            observations.insert(observed, mr);
+        }
+
+        for mr in &self.base {
+            let i = &state_map[mr];
+            for a in self.alphabet.universe().sorted() {
+                let color = self.edge_color(mr, a);
+                let obs = self
+                    .table
+                    .get(&Concat(mr, [a]).to_vec())
+                    .expect("Can only work if table is closed!");
+                let target = observations.get(obs).unwrap();
+
+                let added = ts.add_edge(
+                    *i,
+                    <D::Alphabet as Alphabet>::expression(a),
+                    *state_map.get(target).unwrap(),
+                    color,
+                );
+                assert!(added.is_none());
+            }
+        }
+
+        let duration = start.elapsed().as_micros();
+        debug!("Building hypothesis took {duration} microseconds");
+
+        D::from_transition_system(ts, *state_map.get(&vec![]).unwrap())
+    }
+}
+
+impl<D: LStarHypothesis> std::fmt::Debug for ObservationTable<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut builder = tabled::builder::Builder::default();
+        let mut header = vec!["MR".to_string()];
+
+        for e in &self.experiments {
+            header.push(e.as_string());
+        }
+        builder.push_record(header);
+
+        for (i, mr) in self.base.iter().enumerate() {
+            let mut row = vec![mr.as_string()];
+            for color in self
+                .table
+                .get(mr)
+                .unwrap_or_else(|| panic!("No table entry for {}", mr.as_string()))
+            {
+                row.push(color.show());
+            }
+            builder.push_record(row);
+        }
+
+        write!(f, "{}", builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::ObservationTable;
+
+    #[test]
+    fn observation_table() {
+        let alphabet = CharAlphabet::from_iter(['a', 'b']);
+        // words with an even number of a's
+        let query = |w: &Vec<char>| w.iter().filter(|sym| **sym == 'a').count() % 2 == 0;
+        let mut table: ObservationTable<DFA> = ObservationTable::new(alphabet);
+        table.fill(query);
+        assert_eq!(table.row(&['b']), Some([true].as_slice()));
+
+        let unclosed = table.unclosed_rows();
+        assert_eq!(unclosed.len(), 1);
+        for word in unclosed {
+            assert!(table.add_row(word));
+        }
+        table.fill(query);
+        assert!(table.is_closed());
+        assert!(table.is_consistent());
+        assert_eq!(table.rows(), &[vec![], vec!['a']]);
+
+        let hypothesis = table.hypothesis();
+        assert_eq!(hypothesis.size(), 2);
+        assert!(hypothesis.accepts("abba"));
+        assert!(!hypothesis.accepts("ab"));
+    }
+}
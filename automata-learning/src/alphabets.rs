@@ -0,0 +1,738 @@
+//! Products and sums of alphabets, so that automata over different alphabets can be combined.
+//! As for valuations in the [`propositional`](crate::propositional) module, the pairs of a
+//! [`ProductAlphabet`] and the tagged symbols of a [`SumAlphabet`] are encoded as symbols in a
+//! private use area of unicode, so the combined automata are ordinary [`CharAlphabet`] based
+//! transition systems. Both alphabets are limited to 65534 symbols. An [`InternalAlphabet`]
+//! designates one symbol as an internal move that does not consume input, which gives
+//! epsilon-NFAs and products in which components move on their own.
+
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, Set};
+use either::Either;
+use itertools::Itertools;
+
+/// The first symbol that is used for encoding pairs of a [`ProductAlphabet`].
+const FIRST_PAIR_SYMBOL: u32 = 0xF0000;
+/// The first symbol that is used for encoding the symbols of a [`SumAlphabet`].
+const FIRST_SUM_SYMBOL: u32 = 0x100000;
+/// The number of symbols in each of the two supplementary private use areas.
+const MAX_SYMBOLS: usize = 0xFFFE;
+/// The first symbol that is used for encoding the variants of an [`EnumSymbol`], which lies in
+/// the part of the basic private use area that is not used for valuations.
+const FIRST_VARIANT_SYMBOL: u32 = 0xF000;
+/// The maximal number of variants of an [`EnumSymbol`].
+const MAX_VARIANTS: usize = 0x900;
+
+/// Returns the position of `sym` in `symbols`, panicking if it is unknown.
+fn position(symbols: &[char], sym: char) -> u32 {
+    symbols
+        .iter()
+        .position(|s| *s == sym)
+        .unwrap_or_else(|| panic!("Unknown symbol {sym}")) as u32
+}
+
+/// Copies the states and edges of `ts` into a new transition system over `alphabet`, where
+/// every symbol is replaced according to `encode`. Edges that become equal are only added once.
+/// Returns the copy together with the index of each state of `ts` in it.
+#[allow(clippy::type_complexity)]
+fn relabel<Ts, F>(
+    ts: &Ts,
+    alphabet: CharAlphabet,
+    encode: F,
+) -> (
+    NTS<CharAlphabet, Ts::StateColor, Ts::EdgeColor>,
+    Map<Ts::StateIndex, usize>,
+)
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet>,
+    F: Fn(char) -> char,
+{
+    let mut out = NTS::new_for_alphabet(alphabet);
+    let index: Map<_, _> = ts
+        .state_indices()
+        .sorted()
+        .map(|q| {
+            let color = ts.state_color(q).expect("State must exist");
+            (q, out.add_state(color))
+        })
+        .collect();
+    for q in ts.state_indices() {
+        let mut added = vec![];
+        for e in ts.edges_from(q).expect("State must exist") {
+            let edge = (encode(*e.expression()), index[&e.target()], e.color());
+            if !added.contains(&edge) {
+                out.add_edge(index[&q], edge.0, edge.1, edge.2.clone());
+                added.push(edge);
+            }
+        }
+    }
+    (out, index)
+}
+
+/// A fieldless enum whose variants are used as symbols, e.g. the events of a system. The
+/// `i`-th variant is encoded as the symbol with code point `0xF000 + i`, so that automata over
+/// the variants are ordinary [`CharAlphabet`] based transition systems. Use
+/// [`enum_symbols!`](crate::enum_symbols) to implement it.
+pub trait EnumSymbol: Copy + Eq + 'static {
+    /// All variants in the order of their declaration.
+    const VARIANTS: &'static [Self];
+
+    /// Returns the symbol that encodes the variant.
+    fn symbol(self) -> char {
+        let index = Self::VARIANTS
+            .iter()
+            .position(|v| *v == self)
+            .expect("Variant must be listed");
+        char::from_u32(FIRST_VARIANT_SYMBOL + index as u32).expect("Symbol must be valid")
+    }
+
+    /// Returns the variant that is encoded by `symbol`, if there is one.
+    fn from_symbol(symbol: char) -> Option<Self> {
+        let index = u32::from(symbol).checked_sub(FIRST_VARIANT_SYMBOL)?;
+        Self::VARIANTS.get(index as usize).cloned()
+    }
+
+    /// Returns the alphabet of the symbols of all variants. Panics if there are more than
+    /// 2304 variants.
+    fn alphabet() -> CharAlphabet {
+        assert!(
+            Self::VARIANTS.len() <= MAX_VARIANTS,
+            "At most {MAX_VARIANTS} variants are supported"
+        );
+        CharAlphabet::from_iter(Self::VARIANTS.iter().map(|v| v.symbol()))
+    }
+
+    /// Encodes a word of variants as a word of symbols.
+    fn encode<I: IntoIterator<Item = Self>>(word: I) -> Vec<char> {
+        word.into_iter().map(Self::symbol).collect()
+    }
+
+    /// Decodes a word of symbols, or returns `None` if one of them encodes no variant.
+    fn decode(word: &[char]) -> Option<Vec<Self>> {
+        word.iter().map(|sym| Self::from_symbol(*sym)).collect()
+    }
+}
+
+/// Declares a fieldless enum and implements [`EnumSymbol`] for it, so that its variants can be
+/// used as symbols. `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq` and `Hash` are derived, other
+/// attributes are kept.
+///
+/// ```
+/// use automata_learning::{alphabets::EnumSymbol, enum_symbols};
+///
+/// enum_symbols! {
+///     pub enum Event { Open, Close }
+/// }
+/// assert_eq!(Event::from_symbol(Event::Close.symbol()), Some(Event::Close));
+/// ```
+#[macro_export]
+macro_rules! enum_symbols {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($variant:ident),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        $vis enum $name {
+            $($variant),*
+        }
+
+        impl $crate::alphabets::EnumSymbol for $name {
+            const VARIANTS: &'static [Self] = &[$($name::$variant),*];
+        }
+    };
+}
+
+/// Abstracts the types of errors that can occur when relabelling the alphabet of a transition
+/// system, see [`RelabelAlphabet::relabel_alphabet`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum RelabelError<Idx> {
+    MissingSymbol(char),
+    Conflict(Idx, char),
+}
+
+impl<Idx: std::fmt::Debug> std::fmt::Display for RelabelError<Idx> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelabelError::MissingSymbol(sym) => write!(f, "Symbol {sym} is not relabelled"),
+            RelabelError::Conflict(state, sym) => write!(
+                f,
+                "State {state:?} has different transitions that are merged into {sym}"
+            ),
+        }
+    }
+}
+
+impl<Idx: std::fmt::Debug> std::error::Error for RelabelError<Idx> {}
+
+/// Renames the symbols of a transition system while keeping its states and edges.
+pub trait RelabelAlphabet: TransitionSystem<Alphabet = CharAlphabet> {
+    /// Returns a copy in which every symbol `a` is replaced by `map[a]`, over the alphabet of
+    /// the new symbols, together with the index of each state in the copy. If `map` is not
+    /// injective, the edges whose symbols are merged are merged as well, which is only allowed
+    /// if they lead to the same target with the same color, so that no nondeterminism is
+    /// introduced. Otherwise, or if a symbol is not mapped, an error is returned.
+    #[allow(clippy::type_complexity)]
+    fn relabel_alphabet(
+        &self,
+        map: &Map<char, char>,
+    ) -> Result<
+        (
+            NTS<CharAlphabet, Self::StateColor, Self::EdgeColor>,
+            Map<Self::StateIndex, usize>,
+        ),
+        RelabelError<Self::StateIndex>,
+    > {
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        if let Some(sym) = symbols.iter().find(|sym| !map.contains_key(sym)) {
+            return Err(RelabelError::MissingSymbol(*sym));
+        }
+        for q in self.state_indices().sorted() {
+            let edges = self
+                .edges_from(q)
+                .expect("State must exist")
+                .map(|e| (*e.expression(), e.target(), e.color()))
+                .collect_vec();
+            for ((a, p, c), (b, r, d)) in edges.iter().tuple_combinations() {
+                if a != b && map[a] == map[b] && (p != r || c != d) {
+                    return Err(RelabelError::Conflict(q, map[a]));
+                }
+            }
+        }
+        let alphabet = CharAlphabet::from_iter(symbols.iter().map(|sym| map[sym]).unique());
+        Ok(relabel(self, alphabet, |sym| map[&sym]))
+    }
+}
+
+impl<Ts: TransitionSystem<Alphabet = CharAlphabet>> RelabelAlphabet for Ts {}
+
+/// The alphabet of pairs of a symbol from a left and a symbol from a right alphabet, which is
+/// read by the synchronous product of automata over the two alphabets, see
+/// [`ProductAlphabet::product`]. The pair of the `i`-th left and `j`-th right symbol in sorted
+/// order is encoded as the symbol with code point `0xF0000 + i * m + j`, where `m` is the size
+/// of the right alphabet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductAlphabet {
+    left: Vec<char>,
+    right: Vec<char>,
+}
+
+impl ProductAlphabet {
+    /// Creates the product of the given alphabets. Panics if it has more than 65534 symbols.
+    pub fn new(left: &CharAlphabet, right: &CharAlphabet) -> Self {
+        let left = left.universe().sorted().collect_vec();
+        let right = right.universe().sorted().collect_vec();
+        assert!(
+            left.len() * right.len() <= MAX_SYMBOLS,
+            "At most {MAX_SYMBOLS} pairs are supported"
+        );
+        Self { left, right }
+    }
+
+    /// Returns the symbol that encodes the pair of `left` and `right`. Panics if one of them
+    /// is unknown.
+    pub fn symbol(&self, left: char, right: char) -> char {
+        let code =
+            position(&self.left, left) * self.right.len() as u32 + position(&self.right, right);
+        char::from_u32(FIRST_PAIR_SYMBOL + code).expect("Symbol must be valid")
+    }
+
+    /// Returns the pair that is encoded by `symbol`.
+    pub fn pair(&self, symbol: char) -> (char, char) {
+        let code = (u32::from(symbol) - FIRST_PAIR_SYMBOL) as usize;
+        (
+            self.left[code / self.right.len()],
+            self.right[code % self.right.len()],
+        )
+    }
+
+    /// Returns the symbols of all pairs.
+    pub fn symbols(&self) -> impl Iterator<Item = char> + '_ {
+        self.left
+            .iter()
+            .cartesian_product(&self.right)
+            .map(|(a, b)| self.symbol(*a, *b))
+    }
+
+    /// Returns the symbols of all pairs that satisfy `predicate`, which is useful for labelling
+    /// edges with a constraint on both components.
+    pub fn symbols_where<F: Fn(char, char) -> bool>(&self, predicate: F) -> Vec<char> {
+        self.symbols()
+            .filter(|sym| {
+                let (a, b) = self.pair(*sym);
+                predicate(a, b)
+            })
+            .collect()
+    }
+
+    /// Returns the alphabet that consists of the symbols of all pairs.
+    pub fn alphabet(&self) -> CharAlphabet {
+        CharAlphabet::from_iter(self.symbols())
+    }
+
+    /// Builds the synchronous product of `left` and `right`, which reads a pair `(a, b)` by
+    /// reading `a` in `left` and `b` in `right` at the same time. The states and edges of the
+    /// product are colored with the pairs of colors of the components. Only the part that is
+    /// reachable from the pair of initial states is built.
+    pub fn product<L, R>(&self, left: &L, right: &R) -> AlphabetProduct<L, R>
+    where
+        L: TransitionSystem<Alphabet = CharAlphabet> + Pointed,
+        R: TransitionSystem<Alphabet = CharAlphabet> + Pointed,
+    {
+        let mut ts = NTS::new_for_alphabet(self.alphabet());
+        let mut states = vec![];
+        let mut index: Map<(L::StateIndex, R::StateIndex), usize> = Map::default();
+        let color = |(p, q): (L::StateIndex, R::StateIndex)| {
+            (
+                left.state_color(p).expect("State must exist"),
+                right.state_color(q).expect("State must exist"),
+            )
+        };
+        let pair = (left.initial(), right.initial());
+        let initial = ts.add_state(color(pair));
+        index.insert(pair, initial);
+        states.push(pair);
+        let mut queue = VecDeque::from([pair]);
+        while let Some((p, q)) = queue.pop_front() {
+            let source = index[&(p, q)];
+            for e in left.edges_from(p).expect("State must exist") {
+                for f in right.edges_from(q).expect("State must exist") {
+                    let pair = (e.target(), f.target());
+                    let target = *index.entry(pair).or_insert_with(|| {
+                        states.push(pair);
+                        queue.push_back(pair);
+                        ts.add_state(color(pair))
+                    });
+                    let sym = self.symbol(*e.expression(), *f.expression());
+                    ts.add_edge(source, sym, target, (e.color(), f.color()));
+                }
+            }
+        }
+        AlphabetProduct {
+            ts,
+            initial,
+            states,
+        }
+    }
+}
+
+/// The synchronous product of two transition systems over a [`ProductAlphabet`], see
+/// [`ProductAlphabet::product`].
+#[derive(Debug, Clone)]
+#[allow(clippy::type_complexity)]
+pub struct AlphabetProduct<L: TransitionSystem, R: TransitionSystem> {
+    /// The transition system of the product.
+    pub ts: NTS<CharAlphabet, (L::StateColor, R::StateColor), (L::EdgeColor, R::EdgeColor)>,
+    /// The initial state of the product.
+    pub initial: usize,
+    /// The pair of states of the components for each state of the product.
+    pub states: Vec<(L::StateIndex, R::StateIndex)>,
+}
+
+/// The disjoint union of a left and a right alphabet, whose symbols are tagged with the side
+/// they come from. The `i`-th left symbol in sorted order is encoded as the symbol with code
+/// point `0x100000 + i` and the `j`-th right symbol as `0x100000 + n + j`, where `n` is the
+/// size of the left alphabet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumAlphabet {
+    left: Vec<char>,
+    right: Vec<char>,
+}
+
+impl SumAlphabet {
+    /// Creates the sum of the given alphabets. Panics if it has more than 65534 symbols.
+    pub fn new(left: &CharAlphabet, right: &CharAlphabet) -> Self {
+        let left = left.universe().sorted().collect_vec();
+        let right = right.universe().sorted().collect_vec();
+        assert!(
+            left.len() + right.len() <= MAX_SYMBOLS,
+            "At most {MAX_SYMBOLS} symbols are supported"
+        );
+        Self { left, right }
+    }
+
+    /// Returns the symbol that encodes the left symbol `sym`. Panics if it is unknown.
+    pub fn left(&self, sym: char) -> char {
+        char::from_u32(FIRST_SUM_SYMBOL + position(&self.left, sym)).expect("Symbol must be valid")
+    }
+
+    /// Returns the symbol that encodes the right symbol `sym`. Panics if it is unknown.
+    pub fn right(&self, sym: char) -> char {
+        let code = self.left.len() as u32 + position(&self.right, sym);
+        char::from_u32(FIRST_SUM_SYMBOL + code).expect("Symbol must be valid")
+    }
+
+    /// Returns the symbol of the left or right alphabet that is encoded by `symbol`.
+    pub fn split(&self, symbol: char) -> Either<char, char> {
+        let code = (u32::from(symbol) - FIRST_SUM_SYMBOL) as usize;
+        match code.checked_sub(self.left.len()) {
+            None => Either::Left(self.left[code]),
+            Some(code) => Either::Right(self.right[code]),
+        }
+    }
+
+    /// Returns the symbols of all left symbols followed by those of all right symbols.
+    pub fn symbols(&self) -> impl Iterator<Item = char> + '_ {
+        self.left
+            .iter()
+            .map(|sym| self.left(*sym))
+            .chain(self.right.iter().map(|sym| self.right(*sym)))
+    }
+
+    /// Returns the alphabet that consists of all symbols.
+    pub fn alphabet(&self) -> CharAlphabet {
+        CharAlphabet::from_iter(self.symbols())
+    }
+
+    /// Copies a transition system over the left alphabet into one over the sum, which has no
+    /// edges for right symbols. Returns the copy together with the index of each state in it.
+    #[allow(clippy::type_complexity)]
+    pub fn embed_left<Ts: TransitionSystem<Alphabet = CharAlphabet>>(
+        &self,
+        ts: &Ts,
+    ) -> (
+        NTS<CharAlphabet, Ts::StateColor, Ts::EdgeColor>,
+        Map<Ts::StateIndex, usize>,
+    ) {
+        relabel(ts, self.alphabet(), |sym| self.left(sym))
+    }
+
+    /// Copies a transition system over the right alphabet into one over the sum, see
+    /// [`Self::embed_left`].
+    #[allow(clippy::type_complexity)]
+    pub fn embed_right<Ts: TransitionSystem<Alphabet = CharAlphabet>>(
+        &self,
+        ts: &Ts,
+    ) -> (
+        NTS<CharAlphabet, Ts::StateColor, Ts::EdgeColor>,
+        Map<Ts::StateIndex, usize>,
+    ) {
+        relabel(ts, self.alphabet(), |sym| self.right(sym))
+    }
+}
+
+/// An alphabet in which one symbol is internal, such as epsilon or an unobservable action.
+/// Internal moves do not consume input, so runs of a word may take any number of internal
+/// edges before and after each visible symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternalAlphabet {
+    visible: Vec<char>,
+    internal: char,
+}
+
+impl InternalAlphabet {
+    /// Designates `internal` as the internal symbol of `alphabet`, which may or may not
+    /// contain it already.
+    pub fn new(alphabet: &CharAlphabet, internal: char) -> Self {
+        let visible = alphabet
+            .universe()
+            .filter(|sym| *sym != internal)
+            .sorted()
+            .collect();
+        Self { visible, internal }
+    }
+
+    /// Returns the internal symbol.
+    pub fn internal(&self) -> char {
+        self.internal
+    }
+
+    /// Returns true if `sym` is the internal symbol.
+    pub fn is_internal(&self, sym: char) -> bool {
+        sym == self.internal
+    }
+
+    /// Returns the visible symbols in sorted order.
+    pub fn visible(&self) -> &[char] {
+        &self.visible
+    }
+
+    /// Returns the alphabet that consists of the visible symbols and the internal symbol.
+    pub fn alphabet(&self) -> CharAlphabet {
+        CharAlphabet::from_iter(self.visible.iter().cloned().chain([self.internal]))
+    }
+
+    /// Returns the states that are reachable from one of `states` with internal edges only,
+    /// which includes `states` themselves.
+    pub fn closure<Ts, I>(&self, ts: &Ts, states: I) -> Set<Ts::StateIndex>
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet>,
+        I: IntoIterator<Item = Ts::StateIndex>,
+    {
+        let mut closure: Set<_> = states.into_iter().collect();
+        let mut queue: VecDeque<_> = closure.iter().cloned().collect();
+        while let Some(q) = queue.pop_front() {
+            for e in ts.edges_from(q).expect("State must exist") {
+                if self.is_internal(*e.expression()) && closure.insert(e.target()) {
+                    queue.push_back(e.target());
+                }
+            }
+        }
+        closure
+    }
+
+    /// Returns the states that are reached by runs on `word` from the initial state, where
+    /// internal edges are taken without consuming input. The word consists of visible symbols
+    /// and the result is empty if no run reads all of it.
+    pub fn run<Ts>(&self, ts: &Ts, word: &[char]) -> Set<Ts::StateIndex>
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet> + Pointed,
+    {
+        let mut current = self.closure(ts, [ts.initial()]);
+        for sym in word {
+            let successors = current
+                .iter()
+                .flat_map(|q| ts.edges_from(*q).expect("State must exist"))
+                .filter(|e| e.expression() == sym)
+                .map(|e| e.target())
+                .collect_vec();
+            current = self.closure(ts, successors);
+        }
+        current
+    }
+
+    /// Returns true if a run on `word` ends in an accepting state, see [`Self::run`].
+    pub fn accepts<Ts>(&self, ts: &Ts, word: &[char]) -> bool
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet, StateColor = bool> + Pointed,
+    {
+        self.run(ts, word)
+            .into_iter()
+            .any(|q| ts.state_color(q).expect("State must exist"))
+    }
+
+    /// Builds the product of `left` and `right` in which visible symbols are read by both
+    /// components at the same time, while an internal edge moves one component and leaves the
+    /// other where it is. Edges are colored with the colors of the edges of the components
+    /// that move. Only the part that is reachable from the pair of initial states is built.
+    pub fn product<L, R>(&self, left: &L, right: &R) -> InternalProduct<L, R>
+    where
+        L: TransitionSystem<Alphabet = CharAlphabet> + Pointed,
+        R: TransitionSystem<Alphabet = CharAlphabet> + Pointed,
+    {
+        let mut ts = NTS::new_for_alphabet(self.alphabet());
+        let mut states = vec![];
+        let mut index: Map<(L::StateIndex, R::StateIndex), usize> = Map::default();
+        let color = |(p, q): (L::StateIndex, R::StateIndex)| {
+            (
+                left.state_color(p).expect("State must exist"),
+                right.state_color(q).expect("State must exist"),
+            )
+        };
+        let pair = (left.initial(), right.initial());
+        let initial = ts.add_state(color(pair));
+        index.insert(pair, initial);
+        states.push(pair);
+        let mut queue = VecDeque::from([pair]);
+        while let Some((p, q)) = queue.pop_front() {
+            let source = index[&(p, q)];
+            let mut moves = vec![];
+            for e in left.edges_from(p).expect("State must exist") {
+                let sym = *e.expression();
+                if self.is_internal(sym) {
+                    moves.push((sym, (e.target(), q), (Some(e.color()), None)));
+                    continue;
+                }
+                for f in right.edges_from(q).expect("State must exist") {
+                    if *f.expression() == sym {
+                        let colors = (Some(e.color()), Some(f.color()));
+                        moves.push((sym, (e.target(), f.target()), colors));
+                    }
+                }
+            }
+            for f in right.edges_from(q).expect("State must exist") {
+                if self.is_internal(*f.expression()) {
+                    moves.push((self.internal, (p, f.target()), (None, Some(f.color()))));
+                }
+            }
+            for (sym, pair, colors) in moves {
+                let target = *index.entry(pair).or_insert_with(|| {
+                    states.push(pair);
+                    queue.push_back(pair);
+                    ts.add_state(color(pair))
+                });
+                ts.add_edge(source, sym, target, colors);
+            }
+        }
+        InternalProduct {
+            ts,
+            initial,
+            states,
+        }
+    }
+}
+
+/// The product of two transition systems over an [`InternalAlphabet`], see
+/// [`InternalAlphabet::product`]. An edge is colored with `None` for a component that does not
+/// move along it.
+#[derive(Debug, Clone)]
+#[allow(clippy::type_complexity)]
+pub struct InternalProduct<L: TransitionSystem, R: TransitionSystem> {
+    /// The transition system of the product.
+    pub ts: NTS<
+        CharAlphabet,
+        (L::StateColor, R::StateColor),
+        (Option<L::EdgeColor>, Option<R::EdgeColor>),
+    >,
+    /// The initial state of the product.
+    pub initial: usize,
+    /// The pair of states of the components for each state of the product.
+    pub states: Vec<(L::StateIndex, R::StateIndex)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, Map};
+    use either::Either;
+
+    use super::{
+        EnumSymbol, InternalAlphabet, ProductAlphabet, RelabelAlphabet, RelabelError, SumAlphabet,
+    };
+
+    #[test]
+    fn product_and_sum() {
+        // an even number of a's
+        let mut left = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let p0 = left.add_state(true);
+        let p1 = left.add_state(false);
+        left.add_edge(p0, 'a', p1, Void);
+        left.add_edge(p0, 'b', p0, Void);
+        left.add_edge(p1, 'a', p0, Void);
+        left.add_edge(p1, 'b', p1, Void);
+        // the last symbol is x
+        let mut right = DFA::new_for_alphabet(alphabet!(simple 'x', 'y'));
+        let q0 = right.add_state(false);
+        let q1 = right.add_state(true);
+        for q in [q0, q1] {
+            right.add_edge(q, 'x', q1, Void);
+            right.add_edge(q, 'y', q0, Void);
+        }
+
+        let pairs = ProductAlphabet::new(left.alphabet(), right.alphabet());
+        assert_eq!(pairs.symbols().count(), 4);
+        assert_eq!(pairs.pair(pairs.symbol('b', 'x')), ('b', 'x'));
+        assert_eq!(pairs.symbols_where(|a, _| a == 'a').len(), 2);
+
+        let product = pairs.product(&left, &right);
+        assert_eq!(product.states.len(), 3);
+        let accepting = product
+            .states
+            .iter()
+            .filter(|(p, q)| *p == p0 && *q == q1)
+            .count();
+        assert_eq!(accepting, 1);
+        assert_eq!(product.ts.state_color(product.initial), Some((true, false)));
+
+        let sum = SumAlphabet::new(left.alphabet(), right.alphabet());
+        assert_eq!(sum.split(sum.left('a')), Either::Left('a'));
+        assert_eq!(sum.split(sum.right('x')), Either::Right('x'));
+        assert_ne!(sum.left('b'), sum.right('x'));
+        let (embedded, index) = sum.embed_right(&right);
+        assert_eq!(index.len(), 2);
+        assert!(embedded
+            .edges_from(index[&q0])
+            .unwrap()
+            .all(|e| sum.split(*e.expression()).is_right()));
+    }
+
+    #[test]
+    fn relabel_alphabet() {
+        // an even number of a's, where b does not change the state
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b', 'c'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        for sym in ['b', 'c'] {
+            dfa.add_edge(q0, sym, q0, Void);
+            dfa.add_edge(q1, sym, q1, Void);
+        }
+
+        let rename = Map::from_iter([('a', 'x'), ('b', 'y'), ('c', 'z')]);
+        let (renamed, index) = dfa.relabel_alphabet(&rename).unwrap();
+        assert_eq!(renamed.alphabet().universe().count(), 3);
+        assert!(renamed
+            .edges_from(index[&q0])
+            .unwrap()
+            .any(|e| *e.expression() == 'x' && e.target() == index[&q1]));
+
+        // b and c behave in the same way, so they may be merged
+        let merge = Map::from_iter([('a', 'a'), ('b', 'b'), ('c', 'b')]);
+        let (merged, index) = dfa.relabel_alphabet(&merge).unwrap();
+        assert_eq!(merged.alphabet().universe().count(), 2);
+        assert_eq!(merged.edges_from(index[&q1]).unwrap().count(), 2);
+
+        let conflict = Map::from_iter([('a', 'a'), ('b', 'a'), ('c', 'c')]);
+        assert_eq!(
+            dfa.relabel_alphabet(&conflict).map(|_| ()),
+            Err(RelabelError::Conflict(q0, 'a'))
+        );
+        let missing = Map::from_iter([('a', 'a')]);
+        assert_eq!(
+            dfa.relabel_alphabet(&missing).map(|_| ()),
+            Err(RelabelError::MissingSymbol('b'))
+        );
+    }
+
+    #[test]
+    fn internal_moves() {
+        // a*b* with an epsilon edge between the two loops
+        let internal = InternalAlphabet::new(&alphabet!(simple 'a', 'b'), 'e');
+        assert_eq!(internal.visible(), &['a', 'b']);
+        let mut nfa = NTS::new_for_alphabet(internal.alphabet());
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_edge(q0, 'a', q0, Void);
+        nfa.add_edge(q0, 'e', q1, Void);
+        nfa.add_edge(q1, 'b', q1, Void);
+        let nfa = nfa.with_initial(q0);
+        assert_eq!(internal.closure(&nfa, [q0]).len(), 2);
+        for (word, accepted) in [("", true), ("aabb", true), ("b", true), ("ba", false)] {
+            let word = word.chars().collect::<Vec<_>>();
+            assert_eq!(internal.accepts(&nfa, &word), accepted);
+        }
+
+        // an even number of b's, which has no internal edges
+        let mut dfa = NTS::new_for_alphabet(internal.alphabet());
+        let p0 = dfa.add_state(true);
+        let p1 = dfa.add_state(false);
+        dfa.add_edge(p0, 'a', p0, Void);
+        dfa.add_edge(p0, 'b', p1, Void);
+        dfa.add_edge(p1, 'a', p1, Void);
+        dfa.add_edge(p1, 'b', p0, Void);
+        let product = internal.product(&nfa, &dfa.with_initial(p0));
+        assert_eq!(product.states.len(), 3);
+        let initial = product.ts.with_initial(product.initial);
+        let reached = internal.run(&initial, &['b']);
+        assert_eq!(reached.len(), 1);
+        assert!(reached.iter().all(|q| product.states[*q] == (q1, p1)));
+        assert!(initial
+            .edges_from(product.initial)
+            .unwrap()
+            .any(|e| *e.expression() == 'e' && e.color().1.is_none()));
+    }
+
+    crate::enum_symbols! {
+        /// The events of a door.
+        enum Door { Open, Close, Lock }
+    }
+
+    #[test]
+    fn enum_symbols() {
+        assert_eq!(Door::VARIANTS.len(), 3);
+        assert_eq!(Door::alphabet().universe().count(), 3);
+        assert_eq!(Door::from_symbol(Door::Lock.symbol()), Some(Door::Lock));
+        assert_eq!(Door::from_symbol('a'), None);
+
+        // a door may only be locked while it is closed
+        let mut dfa = DFA::new_for_alphabet(Door::alphabet());
+        let open = dfa.add_state(true);
+        let closed = dfa.add_state(true);
+        let locked = dfa.add_state(true);
+        dfa.add_edge(open, Door::Close.symbol(), closed, Void);
+        dfa.add_edge(closed, Door::Open.symbol(), open, Void);
+        dfa.add_edge(closed, Door::Lock.symbol(), locked, Void);
+        let word = Door::encode([Door::Close, Door::Lock]);
+        assert_eq!(dfa.reached_state_index(&word), Some(locked));
+        assert_eq!(Door::decode(&word), Some(vec![Door::Close, Door::Lock]));
+    }
+}
@@ -0,0 +1,287 @@
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+use tracing::trace;
+
+/// A partition of the states of a transition system into disjoint classes. Each class is
+/// sorted and the classes are ordered by their least element, which makes the indices of
+/// the classes stable across runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition<Idx: IndexType> {
+    classes: Vec<Vec<Idx>>,
+    lookup: Map<Idx, usize>,
+}
+
+impl<Idx: IndexType> Partition<Idx> {
+    /// Builds a partition from an iterator over classes.
+    pub fn new<I: IntoIterator<Item = Vec<Idx>>>(classes: I) -> Self {
+        let classes = classes
+            .into_iter()
+            .map(|class| class.into_iter().sorted().collect_vec())
+            .filter(|class| !class.is_empty())
+            .sorted_by(|x, y| x[0].cmp(&y[0]))
+            .collect_vec();
+        let lookup = classes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, class)| class.iter().map(move |q| (*q, i)))
+            .collect();
+        Self { classes, lookup }
+    }
+
+    /// Returns the index of the class that contains `q`, if it exists.
+    pub fn class_of(&self, q: Idx) -> Option<usize> {
+        self.lookup.get(&q).cloned()
+    }
+
+    /// Returns a reference to the class with the given index.
+    pub fn class(&self, index: usize) -> Option<&[Idx]> {
+        self.classes.get(index).map(|class| class.as_slice())
+    }
+
+    /// Gives an iterator over all classes of the partition.
+    pub fn classes(&self) -> impl Iterator<Item = &[Idx]> + '_ {
+        self.classes.iter().map(|class| class.as_slice())
+    }
+
+    /// Returns the number of classes.
+    pub fn size(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Returns `true` if `p` and `q` belong to the same class.
+    pub fn same_class(&self, p: Idx, q: Idx) -> bool {
+        match (self.class_of(p), self.class_of(q)) {
+            (Some(i), Some(j)) => i == j,
+            _ => false,
+        }
+    }
+}
+
+/// Bisimulation minimization for arbitrary state- and edge-colored transition systems. In
+/// contrast to the minimization of Moore machines, this works for nondeterministic transition
+/// systems and takes both kinds of colors into account, so it can be used for Mealy machines
+/// and automata with multiple colors alike.
+pub trait Bisimulation: TransitionSystem {
+    /// Computes the coarsest partition of the states which is stable with respect to all
+    /// edges. Two states end up in the same class if and only if they are bisimilar.
+    ///
+    /// This uses signature refinement rather than the splitter based algorithm of Paige and
+    /// Tarjan. In each round, every state is assigned its block together with the sorted set
+    /// of (label, target block) pairs of its edges, and states are grouped by hashing these
+    /// keys. A round takes O(m log m) time for m edges and at most n rounds are needed for n
+    /// states, so the worst case is O(n m log m) instead of O(m log n). In practice, few
+    /// rounds suffice for the automata we deal with.
+    fn bisimulation_partition(&self) -> Partition<Self::StateIndex>;
+
+    /// Computes the quotient with respect to bisimilarity, returning the quotient together
+    /// with the partition. The state `i` of the quotient corresponds to class `i` of the
+    /// partition, and the initial state is the class of the initial state of `self`.
+    #[allow(clippy::type_complexity)]
+    fn bisimulation_quotient(
+        &self,
+    ) -> (
+        impl TransitionSystem<
+                Alphabet = Self::Alphabet,
+                StateIndex = usize,
+                StateColor = Self::StateColor,
+                EdgeColor = Self::EdgeColor,
+            > + Pointed,
+        Partition<Self::StateIndex>,
+    )
+    where
+        Self: Pointed;
+
+    /// Returns `true` if `p` and `q` are bisimilar.
+    fn bisimilar(&self, p: Self::StateIndex, q: Self::StateIndex) -> bool {
+        self.bisimulation_partition().same_class(p, q)
+    }
+}
+
+impl<Ts: TransitionSystem> Bisimulation for Ts {
+    fn bisimulation_partition(&self) -> Partition<Self::StateIndex> {
+        let states = self.state_indices().sorted().collect_vec();
+        // expressions are not required to be ordered, so we replace each pair of expression
+        // and edge color by a number, which makes signatures cheap to sort and hash
+        let mut labels: Map<_, usize> = Map::default();
+        let mut edges: Map<Self::StateIndex, Vec<(usize, Self::StateIndex)>> = Map::default();
+        for q in &states {
+            let outgoing = self
+                .edges_from(*q)
+                .expect("State must exist")
+                .map(|e| {
+                    let next = labels.len();
+                    let label = *labels
+                        .entry((e.expression().clone(), e.color()))
+                        .or_insert(next);
+                    (label, e.target())
+                })
+                .collect();
+            edges.insert(*q, outgoing);
+        }
+
+        // initially, we group by state color
+        let mut blocks: Vec<Vec<Self::StateIndex>> = vec![];
+        for q in &states {
+            let color = self.state_color(*q);
+            match blocks
+                .iter_mut()
+                .find(|block| self.state_color(block[0]) == color)
+            {
+                Some(block) => block.push(*q),
+                None => blocks.push(vec![*q]),
+            }
+        }
+
+        let mut iteration = 0;
+        loop {
+            iteration += 1;
+            let lookup: Map<_, _> = blocks
+                .iter()
+                .enumerate()
+                .flat_map(|(i, block)| block.iter().map(move |q| (*q, i)))
+                .collect();
+            let signature = |q: &Self::StateIndex| {
+                edges[q]
+                    .iter()
+                    .map(|(label, p)| (*label, lookup[p]))
+                    .sorted()
+                    .dedup()
+                    .collect_vec()
+            };
+
+            // states are visited in order, so the new blocks are sorted and ordered by their
+            // least element
+            let mut refined: Vec<Vec<Self::StateIndex>> = vec![];
+            let mut keys: Map<(usize, Vec<(usize, usize)>), usize> = Map::default();
+            for q in &states {
+                let block = *keys.entry((lookup[q], signature(q))).or_insert_with(|| {
+                    refined.push(vec![]);
+                    refined.len() - 1
+                });
+                refined[block].push(*q);
+            }
+
+            if refined.len() == blocks.len() {
+                trace!("Bisimulation partition stabilized after {iteration} iterations");
+                break;
+            }
+            blocks = refined;
+        }
+
+        Partition::new(blocks)
+    }
+
+    fn bisimulation_quotient(
+        &self,
+    ) -> (
+        impl TransitionSystem<
+                Alphabet = Self::Alphabet,
+                StateIndex = usize,
+                StateColor = Self::StateColor,
+                EdgeColor = Self::EdgeColor,
+            > + Pointed,
+        Partition<Self::StateIndex>,
+    )
+    where
+        Self: Pointed,
+    {
+        let partition = self.bisimulation_partition();
+
+        let mut ts = NTS::new_for_alphabet(self.alphabet().clone());
+        for class in partition.classes() {
+            ts.add_state(
+                self.state_color(class[0])
+                    .expect("Every state must have a color"),
+            );
+        }
+
+        for (i, class) in partition.classes().enumerate() {
+            let mut added = Set::default();
+            for e in self.edges_from(class[0]).expect("State must exist") {
+                let target = partition
+                    .class_of(e.target())
+                    .expect("Every state is in some class");
+                if added.insert((e.expression().clone(), e.color(), target)) {
+                    ts.add_edge(i, e.expression().clone(), target, e.color());
+                }
+            }
+        }
+
+        let initial = partition
+            .class_of(self.initial())
+            .expect("Every state is in some class");
+        (ts.with_initial(initial), partition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::Bisimulation;
+
+    #[test]
+    fn bisimulation_mealy() {
+        // states 0 and 2 behave the same, state 1 produces different outputs
+        let mm = NTS::builder()
+            .default_color(())
+            .with_transitions([
+                (0, 'a', 0, 1),
+                (0, 'b', 1, 0),
+                (1, 'a', 1, 2),
+                (1, 'b', 1, 1),
+                (2, 'a', 0, 1),
+                (2, 'b', 1, 2),
+            ])
+            .deterministic()
+            .with_initial(0)
+            .into_mealy();
+
+        let partition = mm.bisimulation_partition();
+        assert_eq!(partition.size(), 2);
+        assert!(partition.same_class(0, 2));
+        assert!(!partition.same_class(0, 1));
+
+        let (quotient, partition) = mm.bisimulation_quotient();
+        assert_eq!(quotient.size(), 2);
+        assert_eq!(Some(quotient.initial()), partition.class_of(0));
+    }
+
+    #[test]
+    fn bisimulation_nondeterministic() {
+        let mut ts = NTS::new_for_alphabet(alphabet!(simple 'a'));
+        let q0 = ts.add_state(false);
+        let q1 = ts.add_state(true);
+        let q2 = ts.add_state(true);
+        ts.add_edge(q0, 'a', q1, Void);
+        ts.add_edge(q0, 'a', q2, Void);
+        ts.add_edge(q1, 'a', q1, Void);
+        ts.add_edge(q2, 'a', q1, Void);
+
+        assert!(ts.bisimilar(q1, q2));
+        assert!(!ts.bisimilar(q0, q1));
+    }
+
+    #[test]
+    fn bisimulation_needs_several_rounds() {
+        // a chain that ends in an accepting sink is refined one state per round, whereas the
+        // states on a cycle of rejecting states are all bisimilar
+        let mut ts = NTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let chain = (0..5).map(|i| ts.add_state(i == 4)).collect::<Vec<_>>();
+        for (p, q) in chain.iter().zip(chain.iter().skip(1)) {
+            ts.add_edge(*p, 'a', *q, Void);
+        }
+        ts.add_edge(chain[4], 'a', chain[4], Void);
+        let cycle = (0..6).map(|_| ts.add_state(false)).collect::<Vec<_>>();
+        for (i, q) in cycle.iter().enumerate() {
+            ts.add_edge(*q, 'a', cycle[(i + 1) % 6], Void);
+            ts.add_edge(*q, 'b', cycle[(i + 2) % 6], Void);
+        }
+
+        let partition = ts.bisimulation_partition();
+        assert_eq!(partition.size(), 6);
+        assert!(cycle.iter().all(|q| partition.same_class(cycle[0], *q)));
+        assert!(!partition.same_class(chain[0], cycle[0]));
+        assert_eq!(partition.class_of(chain[0]), Some(0));
+    }
+}
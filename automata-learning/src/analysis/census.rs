@@ -0,0 +1,586 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+use num_bigint::BigUint;
+
+/// The number of iterations that are used for approximating the dominant eigenvalue.
+const POWER_ITERATIONS: usize = 1000;
+/// Once two consecutive estimates differ by less than this value, power iteration stops.
+const POWER_ITERATION_PRECISION: f64 = 1e-12;
+
+/// Performs one step of the dynamic program that counts the number of runs reaching each state,
+/// i.e. from a map that stores how many words of length `n` reach each state, it computes the
+/// number of words of length `n + 1` reaching each state.
+fn count_step<D: DFALike>(
+    dfa: &D,
+    counts: &Map<D::StateIndex, BigUint>,
+) -> Map<D::StateIndex, BigUint> {
+    let mut next: Map<D::StateIndex, BigUint> = Map::default();
+    for (q, count) in counts {
+        for sym in dfa.alphabet().universe() {
+            if let Some(p) = dfa.successor_index(*q, sym) {
+                *next.entry(p).or_default() += count;
+            }
+        }
+    }
+    next
+}
+
+fn accepted_count<D: DFALike>(dfa: &D, counts: &Map<D::StateIndex, BigUint>) -> BigUint {
+    counts
+        .iter()
+        .filter(|(q, _)| dfa.state_color(**q) == Some(true))
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// Estimates the spectral radius of the adjacency matrix given as a map from each state to
+/// the list of its successors (with multiplicity). Successors that are not keys of the map are
+/// ignored. The radius is zero if the graph is acyclic, otherwise we use power iteration on
+/// `A + I`, normalizing in the maximum norm after each step, and subtract one. Shifting by the
+/// identity makes the dominant eigenvalue the only one of maximal modulus, so the iteration
+/// also converges on periodic components, where the estimates for `A` itself would oscillate.
+pub(crate) fn spectral_radius<Idx: IndexType>(adjacency: &Map<Idx, Vec<Idx>>) -> f64 {
+    let states = adjacency.keys().cloned().sorted().collect_vec();
+    let position: Map<_, _> = states.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+    let successors = states
+        .iter()
+        .map(|q| {
+            adjacency[q]
+                .iter()
+                .filter_map(|p| position.get(p).cloned())
+                .collect_vec()
+        })
+        .collect_vec();
+
+    // a graph without cycles has a nilpotent adjacency matrix, we detect this by repeatedly
+    // removing states without incoming edges
+    let mut in_degree = vec![0usize; states.len()];
+    for j in successors.iter().flatten() {
+        in_degree[*j] += 1;
+    }
+    let mut sources = (0..states.len())
+        .filter(|i| in_degree[*i] == 0)
+        .collect_vec();
+    let mut removed = 0;
+    while let Some(i) = sources.pop() {
+        removed += 1;
+        for j in &successors[i] {
+            in_degree[*j] -= 1;
+            if in_degree[*j] == 0 {
+                sources.push(*j);
+            }
+        }
+    }
+    if removed == states.len() {
+        return 0.0;
+    }
+
+    let mut vector = vec![1.0f64; states.len()];
+    let mut estimate = 0.0;
+    for _ in 0..POWER_ITERATIONS {
+        // we sum up the values of the successors, which amounts to multiplying with the
+        // adjacency matrix from the left, and add the value of the state itself
+        let mut next = successors
+            .iter()
+            .enumerate()
+            .map(|(i, succ)| vector[i] + succ.iter().map(|j| vector[*j]).sum::<f64>())
+            .collect_vec();
+        let norm = next.iter().cloned().fold(0.0, f64::max);
+        next.iter_mut().for_each(|x| *x /= norm);
+        let converged = (norm - estimate).abs() < POWER_ITERATION_PRECISION;
+        estimate = norm;
+        vector = next;
+        if converged {
+            break;
+        }
+    }
+    estimate - 1.0
+}
+
+/// The number of words in the language of a DFA, see [`WordCount::language_cardinality`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cardinality {
+    /// No word is accepted.
+    Empty,
+    /// The given positive number of words is accepted.
+    Finite(BigUint),
+    /// Infinitely many words are accepted.
+    Infinite,
+}
+
+/// Returns the states that are reachable and from which an accepting state is reachable,
+/// i.e. the states that occur on accepting runs.
+fn useful_states<D: DFALike>(dfa: &D) -> Set<D::StateIndex> {
+    let reachable: Set<_> = dfa.reachable_state_indices().collect();
+    let mut predecessors: Map<D::StateIndex, Vec<D::StateIndex>> = Map::default();
+    for q in &reachable {
+        for sym in dfa.alphabet().universe() {
+            if let Some(p) = dfa.successor_index(*q, sym) {
+                predecessors.entry(p).or_default().push(*q);
+            }
+        }
+    }
+    let mut useful: Set<_> = reachable
+        .iter()
+        .filter(|q| dfa.state_color(**q) == Some(true))
+        .cloned()
+        .collect();
+    let mut queue: VecDeque<_> = useful.iter().cloned().collect();
+    while let Some(q) = queue.pop_front() {
+        for p in predecessors.get(&q).into_iter().flatten() {
+            if useful.insert(*p) {
+                queue.push_back(*p);
+            }
+        }
+    }
+    useful
+}
+
+/// Sorts the useful states topologically with respect to the transitions between them, or
+/// returns `None` if they lie on a cycle, which means that the language is infinite.
+fn topological_order<D: DFALike>(
+    dfa: &D,
+    useful: &Set<D::StateIndex>,
+) -> Option<Vec<D::StateIndex>> {
+    let successors = |q: D::StateIndex| {
+        dfa.alphabet()
+            .universe()
+            .filter_map(move |sym| dfa.successor_index(q, sym))
+            .filter(|p| useful.contains(p))
+    };
+    let mut incoming: Map<D::StateIndex, usize> = useful.iter().map(|q| (*q, 0)).collect();
+    for q in useful {
+        for p in successors(*q) {
+            *incoming.get_mut(&p).expect("State is useful") += 1;
+        }
+    }
+    let mut queue: VecDeque<_> = useful
+        .iter()
+        .filter(|q| incoming[*q] == 0)
+        .cloned()
+        .sorted()
+        .collect();
+    let mut order = vec![];
+    while let Some(q) = queue.pop_front() {
+        order.push(q);
+        for p in successors(q) {
+            let count = incoming.get_mut(&p).expect("State is useful");
+            *count -= 1;
+            if *count == 0 {
+                queue.push_back(p);
+            }
+        }
+    }
+    (order.len() == useful.len()).then_some(order)
+}
+
+/// A lazy iterator over the words accepted by a DFA in length-lexicographic order, see
+/// [`WordCount::accepted_words`]. It keeps all words of the current length that lead to a
+/// useful state, so their number may grow exponentially with the length.
+pub struct AcceptedWords<'a, D: DFALike> {
+    dfa: &'a D,
+    symbols: Vec<SymbolOf<D>>,
+    useful: Set<D::StateIndex>,
+    frontier: Vec<(Vec<SymbolOf<D>>, D::StateIndex)>,
+    position: usize,
+}
+
+impl<'a, D: DFALike> AcceptedWords<'a, D> {
+    fn new(dfa: &'a D) -> Self {
+        let useful = useful_states(dfa);
+        let frontier = if useful.contains(&dfa.initial()) {
+            vec![(vec![], dfa.initial())]
+        } else {
+            vec![]
+        };
+        Self {
+            dfa,
+            symbols: dfa.alphabet().universe().sorted().collect(),
+            useful,
+            frontier,
+            position: 0,
+        }
+    }
+
+    /// Replaces the frontier by the one letter extensions of its words that lead to useful
+    /// states, which keeps it sorted lexicographically.
+    fn advance(&mut self) {
+        self.frontier = self
+            .frontier
+            .iter()
+            .flat_map(|(word, q)| {
+                self.symbols.iter().filter_map(|sym| {
+                    let p = self.dfa.successor_index(*q, sym.clone())?;
+                    self.useful.contains(&p).then(|| {
+                        let mut extended = word.clone();
+                        extended.push(sym.clone());
+                        (extended, p)
+                    })
+                })
+            })
+            .collect();
+        self.position = 0;
+    }
+}
+
+impl<D: DFALike> Iterator for AcceptedWords<'_, D> {
+    type Item = Vec<SymbolOf<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.frontier.is_empty() {
+            while let Some((word, q)) = self.frontier.get(self.position) {
+                self.position += 1;
+                if self.dfa.state_color(*q) == Some(true) {
+                    return Some(word.clone());
+                }
+            }
+            self.advance();
+        }
+        None
+    }
+}
+
+/// Counts words in the language of a DFA. Counting is done by dynamic programming over the
+/// transitions of the automaton, so the running time is linear in the length of the words.
+pub trait WordCount: DFALike {
+    /// Returns the number of words of length exactly `n` that are accepted.
+    fn count_words_of_length(&self, n: usize) -> BigUint {
+        let mut counts: Map<Self::StateIndex, BigUint> =
+            [(self.initial(), BigUint::from(1u8))].into_iter().collect();
+        for _ in 0..n {
+            counts = count_step(self, &counts);
+        }
+        accepted_count(self, &counts)
+    }
+
+    /// Returns the number of accepted words of length at most `n`.
+    fn count_words_up_to(&self, n: usize) -> BigUint {
+        let mut counts: Map<Self::StateIndex, BigUint> =
+            [(self.initial(), BigUint::from(1u8))].into_iter().collect();
+        let mut total = accepted_count(self, &counts);
+        for _ in 0..n {
+            counts = count_step(self, &counts);
+            total += accepted_count(self, &counts);
+        }
+        total
+    }
+
+    /// Estimates the growth rate of the language, which is the dominant eigenvalue of the
+    /// adjacency matrix restricted to the states that occur on accepting runs. The number of
+    /// accepted words of length `n` grows at most like `growth_rate()^n`, and the rate is zero
+    /// if and only if the language is finite.
+    fn growth_rate(&self) -> f64 {
+        let adjacency: Map<_, _> = useful_states(self)
+            .into_iter()
+            .map(|q| {
+                (
+                    q,
+                    self.alphabet()
+                        .universe()
+                        .filter_map(|sym| self.successor_index(q, sym))
+                        .collect_vec(),
+                )
+            })
+            .collect();
+        spectral_radius(&adjacency)
+    }
+
+    /// Returns the topological entropy of the language in bits, i.e. the binary logarithm of
+    /// the [growth rate](Self::growth_rate), which is the number of bits of information per
+    /// symbol in long accepted words. It is zero for finite languages and at most the binary
+    /// logarithm of the alphabet size.
+    fn entropy(&self) -> f64 {
+        let rate = self.growth_rate();
+        if rate < 1.0 {
+            0.0
+        } else {
+            rate.log2()
+        }
+    }
+
+    /// Returns the growth rate of every non-trivial strongly connected component of the states
+    /// that occur on accepting runs, ordered by decreasing rate. The growth rate of the
+    /// language is the largest of them, so this shows which part of the automaton is
+    /// responsible for it.
+    fn scc_growth_rates(&self) -> Vec<(Vec<Self::StateIndex>, f64)>
+    where
+        Self: Sized,
+    {
+        let useful = useful_states(self);
+        self.sccs()
+            .iter()
+            .filter(|scc| !scc.is_transient())
+            .map(|scc| scc.clone().into_iter().sorted().collect_vec())
+            .filter(|states| useful.contains(&states[0]))
+            .map(|states| {
+                let adjacency: Map<_, _> = states
+                    .iter()
+                    .map(|q| {
+                        (
+                            *q,
+                            self.alphabet()
+                                .universe()
+                                .filter_map(|sym| self.successor_index(*q, sym))
+                                .collect_vec(),
+                        )
+                    })
+                    .collect();
+                (states, spectral_radius(&adjacency))
+            })
+            .sorted_by(|(_, r), (_, s)| s.total_cmp(r))
+            .collect()
+    }
+
+    /// Returns an iterator over all accepted words in length-lexicographic order, where
+    /// symbols are ordered as by [`Ord`]. The iterator ends if the language is finite.
+    fn accepted_words(&self) -> AcceptedWords<'_, Self>
+    where
+        Self: Sized,
+    {
+        AcceptedWords::new(self)
+    }
+
+    /// Returns all accepted words of length exactly `n` in lexicographic order.
+    fn accepted_words_of_length(&self, n: usize) -> Vec<Vec<SymbolOf<Self>>>
+    where
+        Self: Sized,
+    {
+        let mut words = AcceptedWords::new(self);
+        for _ in 0..n {
+            words.advance();
+        }
+        words
+            .frontier
+            .into_iter()
+            .filter(|(_, q)| self.state_color(*q) == Some(true))
+            .map(|(word, _)| word)
+            .collect()
+    }
+
+    /// Returns the length-lexicographically least accepted word, which is in particular a
+    /// shortest one, or `None` if the language is empty.
+    fn shortest_accepted(&self) -> Option<Vec<SymbolOf<Self>>>
+    where
+        Self: Sized,
+    {
+        self.accepted_words().next()
+    }
+
+    /// Returns the length-lexicographically least rejected word, or `None` if every word is
+    /// accepted. Words on which the DFA has no run are rejected.
+    fn shortest_rejected(&self) -> Option<Vec<SymbolOf<Self>>> {
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        // a missing transition leads to a rejecting sink, which is represented by `None`
+        let mut visited: Set<Option<Self::StateIndex>> =
+            [Some(self.initial())].into_iter().collect();
+        let mut queue = VecDeque::from([(Some(self.initial()), vec![])]);
+        while let Some((q, word)) = queue.pop_front() {
+            let Some(q) = q.filter(|q| self.state_color(*q) == Some(true)) else {
+                return Some(word);
+            };
+            for sym in &symbols {
+                let p = self.successor_index(q, sym.clone());
+                if visited.insert(p) {
+                    let mut extended = word.clone();
+                    extended.push(sym.clone());
+                    queue.push_back((p, extended));
+                }
+            }
+        }
+        None
+    }
+
+    /// Determines whether the language is empty, finite or infinite, and counts its words if
+    /// it is finite. The language is infinite if and only if a cycle lies on an accepting run.
+    /// Otherwise no accepted word is longer than the number of states, so the words up to
+    /// that length are counted.
+    fn language_cardinality(&self) -> Cardinality {
+        let useful = useful_states(self);
+        if useful.is_empty() {
+            Cardinality::Empty
+        } else if topological_order(self, &useful).is_none() {
+            Cardinality::Infinite
+        } else {
+            Cardinality::Finite(self.count_words_up_to(useful.len()))
+        }
+    }
+
+    /// Returns a longest accepted word if the language is finite and not empty, which is found
+    /// by computing longest paths over the acyclic graph of useful states.
+    fn longest_word(&self) -> Option<Vec<SymbolOf<Self>>> {
+        let useful = useful_states(self);
+        let order = topological_order(self, &useful)?;
+        let mut longest: Map<Self::StateIndex, Vec<SymbolOf<Self>>> = Map::default();
+        if useful.contains(&self.initial()) {
+            longest.insert(self.initial(), vec![]);
+        }
+        for q in order {
+            let Some(word) = longest.get(&q).cloned() else {
+                continue;
+            };
+            for sym in self.alphabet().universe() {
+                let Some(p) = self.successor_index(q, sym.clone()) else {
+                    continue;
+                };
+                if useful.contains(&p) && !longest.get(&p).is_some_and(|w| w.len() > word.len()) {
+                    let mut extended = word.clone();
+                    extended.push(sym);
+                    longest.insert(p, extended);
+                }
+            }
+        }
+        longest
+            .into_iter()
+            .filter(|(q, _)| self.state_color(*q) == Some(true))
+            .map(|(_, word)| word)
+            .max_by_key(|word| word.len())
+    }
+}
+
+impl<D: DFALike> WordCount for D {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+    use itertools::Itertools;
+    use num_bigint::BigUint;
+
+    use super::{Cardinality, WordCount};
+
+    #[test]
+    fn language_cardinality() {
+        // the words ab, b and bb, where q3 is a rejecting sink
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        let q3 = dfa.add_state(false);
+        let q4 = dfa.add_state(true);
+        for (source, sym, target) in [
+            (q0, 'a', q1),
+            (q0, 'b', q2),
+            (q1, 'a', q3),
+            (q1, 'b', q4),
+            (q2, 'a', q3),
+            (q2, 'b', q4),
+            (q3, 'a', q3),
+            (q3, 'b', q3),
+            (q4, 'a', q3),
+            (q4, 'b', q3),
+        ] {
+            dfa.add_edge(source, sym, target, Void);
+        }
+        assert_eq!(
+            dfa.language_cardinality(),
+            Cardinality::Finite(BigUint::from(3u8))
+        );
+        assert_eq!(dfa.longest_word().map(|w| w.len()), Some(2));
+        // the rejecting sink does not contribute to the growth
+        assert_eq!(dfa.growth_rate(), 0.0);
+        assert_eq!(dfa.entropy(), 0.0);
+        assert!(dfa.scc_growth_rates().is_empty());
+
+        let mut empty = DFA::new_for_alphabet(alphabet!(simple 'a'));
+        let q = empty.add_state(false);
+        empty.add_edge(q, 'a', q, Void);
+        assert_eq!(empty.language_cardinality(), Cardinality::Empty);
+        assert_eq!(empty.longest_word(), None);
+    }
+
+    #[test]
+    fn enumerate_accepted_words() {
+        // words with an even number of a's
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+        let words = dfa
+            .accepted_words()
+            .take(6)
+            .map(|w| w.into_iter().collect::<String>())
+            .collect_vec();
+        assert_eq!(words, vec!["", "b", "aa", "bb", "aab", "aba"]);
+        assert_eq!(dfa.accepted_words_of_length(3).len(), 4);
+        assert_eq!(
+            dfa.accepted_words_of_length(2),
+            vec![vec!['a', 'a'], vec!['b', 'b']]
+        );
+
+        // only the word a is accepted, so the iterator ends
+        let mut single = DFA::new_for_alphabet(alphabet!(simple 'a'));
+        let q0 = single.add_state(false);
+        let q1 = single.add_state(true);
+        let q2 = single.add_state(false);
+        single.add_edge(q0, 'a', q1, Void);
+        single.add_edge(q1, 'a', q2, Void);
+        single.add_edge(q2, 'a', q2, Void);
+        assert_eq!(single.accepted_words().collect_vec(), vec![vec!['a']]);
+        assert_eq!(single.shortest_accepted(), Some(vec!['a']));
+        assert_eq!(single.shortest_rejected(), Some(vec![]));
+        assert_eq!(dfa.shortest_accepted(), Some(vec![]));
+        assert_eq!(dfa.shortest_rejected(), Some(vec!['a']));
+    }
+
+    #[test]
+    fn count_even_a() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+        assert_eq!(dfa.language_cardinality(), Cardinality::Infinite);
+        assert_eq!(dfa.longest_word(), None);
+        // half of all words of positive length contain an even number of a's
+        assert_eq!(dfa.count_words_of_length(0), BigUint::from(1u8));
+        assert_eq!(dfa.count_words_of_length(3), BigUint::from(4u8));
+        assert_eq!(dfa.count_words_of_length(100), BigUint::from(2u8).pow(99));
+        assert_eq!(dfa.count_words_up_to(3), BigUint::from(1u8 + 1 + 2 + 4));
+        assert!((dfa.growth_rate() - 2.0).abs() < 1e-6);
+        assert!((dfa.entropy() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn growth_of_components() {
+        // a* b (a | b)*, where the loop on q0 grows linearly and q1 exponentially
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_edge(q0, 'a', q0, Void);
+        dfa.add_edge(q0, 'b', q1, Void);
+        dfa.add_edge(q1, 'a', q1, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+        let rates = dfa.scc_growth_rates();
+        assert_eq!(
+            rates.iter().map(|(s, _)| s.clone()).collect_vec(),
+            vec![vec![q1], vec![q0]]
+        );
+        assert!((rates[0].1 - 2.0).abs() < 1e-6);
+        assert!((rates[1].1 - 1.0).abs() < 1e-6);
+        assert!((dfa.entropy() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn growth_of_periodic_component() {
+        // q0 reaches q1 with a or b and q1 goes back with a, so the component has period two
+        // and there are 2^n accepted words of length 2n
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q1, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        assert_eq!(dfa.count_words_of_length(10), BigUint::from(32u8));
+        assert!((dfa.growth_rate() - 2f64.sqrt()).abs() < 1e-6);
+        assert!((dfa.entropy() - 0.5).abs() < 1e-6);
+        let rates = dfa.scc_growth_rates();
+        assert_eq!(rates.len(), 1);
+        assert!((rates[0].1 - 2f64.sqrt()).abs() < 1e-6);
+    }
+}
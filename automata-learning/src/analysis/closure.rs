@@ -0,0 +1,193 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+
+/// Witnesses that a language is not closed under taking prefixes, suffixes or factors: `word`
+/// is accepted, but its infix `part` is not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosureViolation<S> {
+    /// An accepted word.
+    pub word: Vec<S>,
+    /// A rejected prefix, suffix or factor of `word`.
+    pub part: Vec<S>,
+}
+
+/// Checks whether the language of a DFA is closed under prefixes, suffixes or factors, which
+/// is expected e.g. of prefix acceptors built from samples. Words on which the DFA has no run
+/// are rejected. All checks run in time polynomial in the size of the DFA.
+pub trait ClosureProperties: DFALike {
+    /// Checks whether every prefix of an accepted word is accepted. This fails if some
+    /// reachable rejecting state can reach an accepting one.
+    fn is_prefix_closed(&self) -> Result<(), ClosureViolation<SymbolOf<Self>>> {
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        let shortest_words = |source: Self::StateIndex| {
+            let mut words: Map<Self::StateIndex, Vec<SymbolOf<Self>>> =
+                [(source, vec![])].into_iter().collect();
+            let mut queue = VecDeque::from([source]);
+            while let Some(q) = queue.pop_front() {
+                for sym in &symbols {
+                    let Some(p) = self.successor_index(q, sym.clone()) else {
+                        continue;
+                    };
+                    if !words.contains_key(&p) {
+                        let mut word = words[&q].clone();
+                        word.push(sym.clone());
+                        words.insert(p, word);
+                        queue.push_back(p);
+                    }
+                }
+            }
+            words
+        };
+
+        let access = shortest_words(self.initial());
+        for (q, prefix) in access
+            .iter()
+            .filter(|(q, _)| self.state_color(**q) != Some(true))
+            .sorted_by_key(|(_, word)| (word.len(), (*word).clone()))
+        {
+            let accepting = shortest_words(*q)
+                .into_iter()
+                .filter(|(p, _)| self.state_color(*p) == Some(true))
+                .min_by_key(|(_, word)| (word.len(), word.clone()));
+            if let Some((_, suffix)) = accepting {
+                return Err(ClosureViolation {
+                    word: prefix.iter().cloned().chain(suffix).collect(),
+                    part: prefix.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether every suffix of an accepted word is accepted. We explore the runs on a
+    /// word together with the run on a suffix of it, which starts at a position that is
+    /// guessed, and search for a pair of an accepting and a rejecting state.
+    fn is_suffix_closed(&self) -> Result<(), ClosureViolation<SymbolOf<Self>>> {
+        // the second component is `None` before the suffix starts, and otherwise the state
+        // reached on the suffix, which is `None` if the run on the suffix got stuck
+        type Node<Idx> = (Idx, Option<Option<Idx>>);
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        let start: Node<Self::StateIndex> = (self.initial(), None);
+        let mut visited: Set<Node<Self::StateIndex>> = [start].into_iter().collect();
+        let mut queue = VecDeque::from([(start, vec![], 0)]);
+        while let Some(((p, suffix), word, position)) = queue.pop_front() {
+            let mut successors = vec![];
+            match suffix {
+                None => {
+                    // start the suffix here without reading a symbol
+                    successors.push(((p, Some(Some(self.initial()))), word.clone(), word.len()));
+                    for sym in &symbols {
+                        if let Some(next) = self.successor_index(p, sym.clone()) {
+                            let mut extended = word.clone();
+                            extended.push(sym.clone());
+                            successors.push(((next, None), extended, 0));
+                        }
+                    }
+                }
+                Some(q) => {
+                    if self.state_color(p) == Some(true)
+                        && !q.is_some_and(|q| self.state_color(q) == Some(true))
+                    {
+                        return Err(ClosureViolation {
+                            part: word[position..].to_vec(),
+                            word,
+                        });
+                    }
+                    for sym in &symbols {
+                        if let Some(next) = self.successor_index(p, sym.clone()) {
+                            let q = q.and_then(|q| self.successor_index(q, sym.clone()));
+                            let mut extended = word.clone();
+                            extended.push(sym.clone());
+                            successors.push(((next, Some(q)), extended, position));
+                        }
+                    }
+                }
+            }
+            for (node, word, position) in successors {
+                if visited.insert(node) {
+                    queue.push_back((node, word, position));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether every factor, i.e. infix, of an accepted word is accepted. A language
+    /// is factor closed if and only if it is prefix and suffix closed, as every factor is a
+    /// prefix of a suffix.
+    fn is_factor_closed(&self) -> Result<(), ClosureViolation<SymbolOf<Self>>> {
+        self.is_prefix_closed()?;
+        self.is_suffix_closed()
+    }
+}
+
+impl<D: DFALike> ClosureProperties for D {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{ClosureProperties, ClosureViolation};
+
+    /// Builds a complete DFA over a and b from its acceptance and transitions.
+    fn dfa(accepting: &[bool], transitions: &[(usize, char, usize)]) -> DFA {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let states: Vec<_> = accepting.iter().map(|a| dfa.add_state(*a)).collect();
+        for (source, sym, target) in transitions {
+            dfa.add_edge(states[*source], *sym, states[*target], Void);
+        }
+        dfa
+    }
+
+    #[test]
+    fn closure_properties() {
+        // words without aa are closed under factors
+        let no_aa = dfa(
+            &[true, true, false],
+            &[
+                (0, 'a', 1),
+                (0, 'b', 0),
+                (1, 'a', 2),
+                (1, 'b', 0),
+                (2, 'a', 2),
+                (2, 'b', 2),
+            ],
+        );
+        assert_eq!(no_aa.is_factor_closed(), Ok(()));
+
+        // words starting with a are neither prefix nor suffix closed, as the empty word is
+        // rejected
+        let starts_with_a = dfa(
+            &[false, true, false],
+            &[
+                (0, 'a', 1),
+                (0, 'b', 2),
+                (1, 'a', 1),
+                (1, 'b', 1),
+                (2, 'a', 2),
+                (2, 'b', 2),
+            ],
+        );
+        assert_eq!(
+            starts_with_a.is_prefix_closed(),
+            Err(ClosureViolation {
+                word: vec!['a'],
+                part: vec![]
+            })
+        );
+        let violation = starts_with_a.is_suffix_closed().unwrap_err();
+        assert!(violation.word.starts_with(&['a']));
+        assert!(violation.word.ends_with(&violation.part));
+        assert_ne!(violation.part.first(), Some(&'a'));
+
+        // words ending with a are not prefix closed either
+        let ends_with_a = dfa(
+            &[false, true],
+            &[(0, 'a', 1), (0, 'b', 0), (1, 'a', 1), (1, 'b', 0)],
+        );
+        assert!(ends_with_a.is_prefix_closed().is_err());
+        assert!(ends_with_a.is_factor_closed().is_err());
+    }
+}
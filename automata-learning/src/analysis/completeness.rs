@@ -0,0 +1,74 @@
+use automata::prelude::*;
+use itertools::Itertools;
+
+/// Reports where a transition system is incomplete, i.e. where a run can get stuck, without
+/// running any words through it.
+pub trait Completeness: TransitionSystem<Alphabet = CharAlphabet> {
+    /// Returns the pairs of a state and a symbol of the alphabet for which the state has no
+    /// outgoing edge, with states in ascending order and symbols in the order of the alphabet.
+    fn missing_transitions(&self) -> impl Iterator<Item = (Self::StateIndex, char)> + '_ {
+        let symbols = self.alphabet().universe().collect_vec();
+        self.state_indices().sorted().flat_map(move |q| {
+            let present: Vec<char> = self
+                .edges_from(q)
+                .expect("State must exist")
+                .map(|e| *e.expression())
+                .collect();
+            symbols
+                .iter()
+                .filter(move |sym| !present.contains(sym))
+                .map(move |sym| (q, *sym))
+                .collect_vec()
+        })
+    }
+
+    /// Returns true if every state has an outgoing edge for every symbol of the alphabet.
+    fn is_complete(&self) -> bool {
+        self.missing_transitions().next().is_none()
+    }
+
+    /// Returns the states without any outgoing edges in ascending order.
+    fn deadlock_states(&self) -> Vec<Self::StateIndex> {
+        self.state_indices()
+            .filter(|q| {
+                self.edges_from(*q)
+                    .expect("State must exist")
+                    .next()
+                    .is_none()
+            })
+            .sorted()
+            .collect()
+    }
+}
+
+impl<Ts: TransitionSystem<Alphabet = CharAlphabet>> Completeness for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::Completeness;
+
+    #[test]
+    fn missing_transitions_and_deadlocks() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let q2 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'b', q2, Void);
+        assert!(!dfa.is_complete());
+        assert_eq!(
+            dfa.missing_transitions().collect::<Vec<_>>(),
+            vec![(q1, 'a'), (q2, 'a'), (q2, 'b')]
+        );
+        assert_eq!(dfa.deadlock_states(), vec![q2]);
+
+        dfa.add_edge(q1, 'a', q1, Void);
+        dfa.add_edge(q2, 'a', q2, Void);
+        dfa.add_edge(q2, 'b', q2, Void);
+        assert!(dfa.is_complete());
+        assert!(dfa.deadlock_states().is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+
+/// Checks whether a transition system is deterministic or, for automata that accept finite
+/// words with their state colors, unambiguous, and produces witnesses if it is not. These
+/// checks should be done before a transition system is converted into a [`DFA`] or [`DPA`].
+pub trait Determinism: TransitionSystem<Alphabet = CharAlphabet> {
+    /// Returns a state `q`, a symbol `a` and two distinct states `p` and `p'` such that there
+    /// are edges from `q` to both `p` and `p'` on `a`, or `None` if there is no such state. The
+    /// states are searched in ascending order.
+    #[allow(clippy::type_complexity)]
+    fn nondeterminism_witness(
+        &self,
+    ) -> Option<(Self::StateIndex, char, Self::StateIndex, Self::StateIndex)> {
+        for q in self.state_indices().sorted() {
+            let mut targets: Map<char, Self::StateIndex> = Map::default();
+            for e in self.edges_from(q).expect("State must exist") {
+                let (sym, p) = (*e.expression(), e.target());
+                match targets.get(&sym) {
+                    Some(other) if *other != p => return Some((q, sym, *other, p)),
+                    _ => {
+                        targets.insert(sym, p);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns true if every state has at most one successor for each symbol.
+    fn is_deterministic(&self) -> bool {
+        self.nondeterminism_witness().is_none()
+    }
+
+    /// Returns a shortest word that has two distinct accepting runs from the initial state,
+    /// where a run is accepting if it ends in a state colored with `true`. Two runs are
+    /// distinct if they visit a different state at some position. Returns `None` if the
+    /// automaton is unambiguous.
+    fn ambiguity_witness(&self) -> Option<Vec<char>>
+    where
+        Self: TransitionSystem<StateColor = bool> + Pointed,
+    {
+        // we explore pairs of runs together with a flag that indicates whether they diverged
+        type Node<Idx> = (Idx, Idx, bool);
+        let start = (self.initial(), self.initial(), false);
+        let mut parent: Map<Node<Self::StateIndex>, Option<(Node<Self::StateIndex>, char)>> =
+            [(start, None)].into_iter().collect();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node @ (p, q, diverged)) = queue.pop_front() {
+            if diverged && self.state_color(p) == Some(true) && self.state_color(q) == Some(true) {
+                let mut word = vec![];
+                let mut current = node;
+                while let Some((previous, sym)) = parent[&current] {
+                    word.push(sym);
+                    current = previous;
+                }
+                word.reverse();
+                return Some(word);
+            }
+            let left = self.edges_from(p).expect("State must exist").collect_vec();
+            let right = self.edges_from(q).expect("State must exist").collect_vec();
+            for (e, f) in left.iter().cartesian_product(&right) {
+                let sym = *e.expression();
+                if sym != *f.expression() {
+                    continue;
+                }
+                let next = (e.target(), f.target(), diverged || e.target() != f.target());
+                if !parent.contains_key(&next) {
+                    parent.insert(next, Some((node, sym)));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<Ts: TransitionSystem<Alphabet = CharAlphabet>> Determinism for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::Determinism;
+
+    #[test]
+    fn determinism_and_ambiguity() {
+        // words ending in a, where the automaton guesses the last a
+        let mut nfa = NTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_edge(q0, 'a', q0, Void);
+        nfa.add_edge(q0, 'b', q0, Void);
+        nfa.add_edge(q0, 'a', q1, Void);
+        assert!(!nfa.is_deterministic());
+        let (state, sym, first, second) = nfa.nondeterminism_witness().unwrap();
+        assert_eq!((state, sym), (q0, 'a'));
+        assert_ne!(first, second);
+        // the guess is unique as q1 has no successors
+        assert_eq!(nfa.clone().with_initial(q0).ambiguity_witness(), None);
+
+        // q1 now also loops, so aa is accepted by the runs q0 q0 q1 and q0 q1 q1
+        nfa.add_edge(q1, 'a', q1, Void);
+        assert_eq!(
+            nfa.with_initial(q0).ambiguity_witness(),
+            Some(vec!['a', 'a'])
+        );
+
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let p = dfa.add_state(true);
+        dfa.add_edge(p, 'a', p, Void);
+        assert!(dfa.is_deterministic());
+        assert_eq!(dfa.ambiguity_witness(), None);
+    }
+}
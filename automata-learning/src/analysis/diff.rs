@@ -0,0 +1,412 @@
+use std::{collections::VecDeque, fmt::Write};
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+
+use crate::formats::dot_writer::quote;
+
+/// A difference between the edges of an aligned pair of states on a symbol. The two sides are
+/// the targets of the edges in the left and in the right transition system, or `None` if there
+/// is no edge. Either one side is missing, the edges have different colors or the targets are
+/// not aligned with each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeDifference<P, Q> {
+    /// The aligned pair of source states.
+    pub source: (P, Q),
+    /// The symbol of the edges.
+    pub symbol: char,
+    /// The target in the left transition system.
+    pub left: Option<P>,
+    /// The target in the right transition system.
+    pub right: Option<Q>,
+}
+
+/// The result of [`StructuralDifference::diff`]. States of the two transition systems are
+/// aligned by running both in lockstep from their initial states in breadth-first order, where
+/// a pair of targets is aligned when it is met first and neither target is aligned yet. The
+/// report consists of the aligned pairs whose colors differ, the edges of aligned pairs that
+/// differ and the reachable states that could not be aligned.
+///
+/// The report is rendered as text by its [`Display`](std::fmt::Display) implementation, where
+/// lines starting with `-` refer to the left and lines starting with `+` refer to the right
+/// transition system, and as a DOT graph with highlights by [`StructuralDiff::to_dot`].
+pub struct StructuralDiff<'a, L: TransitionSystem, R: TransitionSystem> {
+    left: &'a L,
+    right: &'a R,
+    /// The aligned pairs of states in the order in which they were aligned.
+    pub aligned: Vec<(L::StateIndex, R::StateIndex)>,
+    /// The aligned pairs of states that have different colors.
+    pub recolored: Vec<(L::StateIndex, R::StateIndex)>,
+    /// The differences between edges of aligned pairs.
+    pub edges: Vec<EdgeDifference<L::StateIndex, R::StateIndex>>,
+    /// The reachable states of the left transition system that are not aligned, in ascending
+    /// order.
+    pub only_left: Vec<L::StateIndex>,
+    /// The reachable states of the right transition system that are not aligned, in ascending
+    /// order.
+    pub only_right: Vec<R::StateIndex>,
+}
+
+/// Returns the color and target of the edge from `q` on `sym`, if there is one.
+fn edge_on<D: Deterministic<Alphabet = CharAlphabet>>(
+    ts: &D,
+    q: D::StateIndex,
+    sym: char,
+) -> Option<(D::EdgeColor, D::StateIndex)> {
+    ts.edges_from(q)
+        .expect("State must exist")
+        .find(|e| *e.expression() == sym)
+        .map(|e| (e.color(), e.target()))
+}
+
+/// Returns the label of an edge, which includes the color unless it shows as empty.
+fn edge_label<C: Show>(sym: char, color: &C) -> String {
+    let shown = color.show();
+    if shown.is_empty() {
+        sym.to_string()
+    } else {
+        format!("{sym} | {shown}")
+    }
+}
+
+/// Computes a [`StructuralDiff`] between deterministic transition systems with initial states,
+/// which is useful for finding out why two learning runs produced different hypotheses.
+pub trait StructuralDifference: Deterministic<Alphabet = CharAlphabet> + Pointed {
+    /// Aligns the states of `self` and `other` and reports their differences. Symbols that
+    /// occur in only one of the alphabets lead to edges that are missing on the other side.
+    fn diff<'a, O>(&'a self, other: &'a O) -> StructuralDiff<'a, Self, O>
+    where
+        O: Deterministic<
+                Alphabet = CharAlphabet,
+                StateColor = Self::StateColor,
+                EdgeColor = Self::EdgeColor,
+            > + Pointed,
+    {
+        let symbols = self
+            .alphabet()
+            .universe()
+            .chain(other.alphabet().universe())
+            .sorted()
+            .dedup()
+            .collect_vec();
+        let mut diff = StructuralDiff {
+            left: self,
+            right: other,
+            aligned: vec![(self.initial(), other.initial())],
+            recolored: vec![],
+            edges: vec![],
+            only_left: vec![],
+            only_right: vec![],
+        };
+        let mut forward: Map<Self::StateIndex, O::StateIndex> = Map::default();
+        let mut backward: Map<O::StateIndex, Self::StateIndex> = Map::default();
+        forward.insert(self.initial(), other.initial());
+        backward.insert(other.initial(), self.initial());
+        let mut queue = VecDeque::from([(self.initial(), other.initial())]);
+        while let Some((p, q)) = queue.pop_front() {
+            if self.state_color(p) != other.state_color(q) {
+                diff.recolored.push((p, q));
+            }
+            for sym in &symbols {
+                let (left, right) = (edge_on(self, p, *sym), edge_on(other, q, *sym));
+                let same = match (&left, &right) {
+                    (None, None) => true,
+                    (Some((c, p)), Some((d, q))) => {
+                        let consistent = match (forward.get(p), backward.get(q)) {
+                            (None, None) => {
+                                forward.insert(*p, *q);
+                                backward.insert(*q, *p);
+                                diff.aligned.push((*p, *q));
+                                queue.push_back((*p, *q));
+                                true
+                            }
+                            (Some(image), Some(preimage)) => image == q && preimage == p,
+                            _ => false,
+                        };
+                        consistent && c == d
+                    }
+                    _ => false,
+                };
+                if !same {
+                    diff.edges.push(EdgeDifference {
+                        source: (p, q),
+                        symbol: *sym,
+                        left: left.map(|(_, p)| p),
+                        right: right.map(|(_, q)| q),
+                    });
+                }
+            }
+        }
+        diff.only_left = self
+            .reachable_state_indices_from(self.initial())
+            .filter(|p| !forward.contains_key(p))
+            .sorted()
+            .collect();
+        diff.only_right = other
+            .reachable_state_indices_from(other.initial())
+            .filter(|q| !backward.contains_key(q))
+            .sorted()
+            .collect();
+        diff
+    }
+}
+
+impl<D: Deterministic<Alphabet = CharAlphabet> + Pointed> StructuralDifference for D {}
+
+impl<'a, L, R> StructuralDiff<'a, L, R>
+where
+    L: Deterministic<Alphabet = CharAlphabet>,
+    R: Deterministic<Alphabet = CharAlphabet, StateColor = L::StateColor, EdgeColor = L::EdgeColor>,
+    L::StateIndex: Show,
+    R::StateIndex: Show,
+    L::StateColor: Show,
+    L::EdgeColor: Show,
+{
+    /// Returns true if the transition systems are isomorphic, i.e. there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.recolored.is_empty()
+            && self.edges.is_empty()
+            && self.only_left.is_empty()
+            && self.only_right.is_empty()
+    }
+
+    /// Produces a DOT graph that overlays both transition systems. Aligned pairs are merged
+    /// into a single node and drawn orange if their colors differ. States and edges that occur
+    /// only in the left transition system are drawn red, those that occur only in the right
+    /// one are drawn green.
+    pub fn to_dot(&self) -> String {
+        let position: Map<L::StateIndex, usize> = self
+            .aligned
+            .iter()
+            .enumerate()
+            .map(|(i, (p, _))| (*p, i))
+            .collect();
+        let right_position: Map<R::StateIndex, usize> = self
+            .aligned
+            .iter()
+            .enumerate()
+            .map(|(i, (_, q))| (*q, i))
+            .collect();
+        let left_node = |p: L::StateIndex| match position.get(&p) {
+            Some(i) => format!("a{i}"),
+            None => format!("l{}", p.show()),
+        };
+        let right_node = |q: R::StateIndex| match right_position.get(&q) {
+            Some(i) => format!("a{i}"),
+            None => format!("r{}", q.show()),
+        };
+
+        let mut out = String::new();
+        writeln!(out, "digraph \"diff\" {{").unwrap();
+        writeln!(out, "  rankdir=LR;").unwrap();
+        writeln!(out, "  node [shape=circle];").unwrap();
+        for (i, (p, q)) in self.aligned.iter().enumerate() {
+            let mut label = format!("q{}/q{}", p.show(), q.show());
+            let (c, d) = (
+                self.left.state_color(*p).expect("State must exist"),
+                self.right.state_color(*q).expect("State must exist"),
+            );
+            if c != d {
+                label = format!("{label} | {} -> {}", c.show(), d.show());
+                writeln!(out, "  a{i} [label={}, color=\"orange\"];", quote(&label)).unwrap();
+            } else {
+                if !c.show().is_empty() {
+                    label = format!("{label} | {}", c.show());
+                }
+                writeln!(out, "  a{i} [label={}];", quote(&label)).unwrap();
+            }
+        }
+        for p in &self.only_left {
+            let label = format!("q{}", p.show());
+            writeln!(
+                out,
+                "  {} [label={}, color=\"red\"];",
+                left_node(*p),
+                quote(&label)
+            )
+            .unwrap();
+        }
+        for q in &self.only_right {
+            let label = format!("q{}", q.show());
+            writeln!(
+                out,
+                "  {} [label={}, color=\"green\"];",
+                right_node(*q),
+                quote(&label)
+            )
+            .unwrap();
+        }
+        writeln!(out, "  init [label=\"\", shape=point];").unwrap();
+        writeln!(out, "  init -> a0;").unwrap();
+
+        let symbols = self
+            .left
+            .alphabet()
+            .universe()
+            .chain(self.right.alphabet().universe())
+            .sorted()
+            .dedup()
+            .collect_vec();
+        let mut edge = |source: &str, target: String, label: String, color: Option<&str>| {
+            let highlight = color
+                .map(|color| format!(", color=\"{color}\", fontcolor=\"{color}\""))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "  {source} -> {target} [label={}{highlight}];",
+                quote(&label)
+            )
+            .unwrap();
+        };
+        for (i, (p, q)) in self.aligned.iter().enumerate() {
+            let source = format!("a{i}");
+            for sym in &symbols {
+                let differs = self
+                    .edges
+                    .iter()
+                    .any(|e| e.source == (*p, *q) && e.symbol == *sym);
+                let left = edge_on(self.left, *p, *sym);
+                if !differs {
+                    if let Some((c, p)) = left {
+                        edge(&source, left_node(p), edge_label(*sym, &c), None);
+                    }
+                    continue;
+                }
+                if let Some((c, p)) = left {
+                    edge(&source, left_node(p), edge_label(*sym, &c), Some("red"));
+                }
+                if let Some((d, q)) = edge_on(self.right, *q, *sym) {
+                    edge(&source, right_node(q), edge_label(*sym, &d), Some("green"));
+                }
+            }
+        }
+        for p in &self.only_left {
+            for sym in &symbols {
+                if let Some((c, target)) = edge_on(self.left, *p, *sym) {
+                    let label = edge_label(*sym, &c);
+                    edge(&left_node(*p), left_node(target), label, Some("red"));
+                }
+            }
+        }
+        for q in &self.only_right {
+            for sym in &symbols {
+                if let Some((d, target)) = edge_on(self.right, *q, *sym) {
+                    let label = edge_label(*sym, &d);
+                    edge(&right_node(*q), right_node(target), label, Some("green"));
+                }
+            }
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+impl<'a, L, R> std::fmt::Display for StructuralDiff<'a, L, R>
+where
+    L: Deterministic<Alphabet = CharAlphabet>,
+    R: Deterministic<Alphabet = CharAlphabet, StateColor = L::StateColor, EdgeColor = L::EdgeColor>,
+    L::StateIndex: Show,
+    R::StateIndex: Show,
+    L::StateColor: Show,
+    L::EdgeColor: Show,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no differences");
+        }
+        for (p, q) in &self.recolored {
+            writeln!(
+                f,
+                "~ q{}/q{}: {} -> {}",
+                p.show(),
+                q.show(),
+                self.left.state_color(*p).expect("State must exist").show(),
+                self.right.state_color(*q).expect("State must exist").show()
+            )?;
+        }
+        for e in &self.edges {
+            let (p, q) = e.source;
+            if let Some((c, target)) = edge_on(self.left, p, e.symbol) {
+                let label = edge_label(e.symbol, &c);
+                let (p, q, target) = (p.show(), q.show(), target.show());
+                writeln!(f, "- q{p}/q{q} --{label}--> q{target}")?;
+            }
+            if let Some((d, target)) = edge_on(self.right, q, e.symbol) {
+                let label = edge_label(e.symbol, &d);
+                let (p, q, target) = (p.show(), q.show(), target.show());
+                writeln!(f, "+ q{p}/q{q} --{label}--> q{target}")?;
+            }
+        }
+        for p in &self.only_left {
+            writeln!(f, "- q{}", p.show())?;
+        }
+        for q in &self.only_right {
+            writeln!(f, "+ q{}", q.show())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{EdgeDifference, StructuralDifference};
+
+    /// Counts a's modulo `modulus`, where the initial state is accepting if `accepting` is set
+    /// and all other states are rejecting.
+    fn count_a(modulus: usize, accepting: bool) -> DFA {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let states = (0..modulus)
+            .map(|i| dfa.add_state(i == 0 && accepting))
+            .collect::<Vec<_>>();
+        for (i, q) in states.iter().enumerate() {
+            dfa.add_edge(*q, 'a', states[(i + 1) % modulus], Void);
+            dfa.add_edge(*q, 'b', *q, Void);
+        }
+        dfa
+    }
+
+    #[test]
+    fn structural_diff() {
+        let (two, three) = (count_a(2, true), count_a(3, true));
+        let same = two.diff(&two);
+        assert!(same.is_empty());
+        assert_eq!(same.aligned, vec![(0, 0), (1, 1)]);
+        assert_eq!(same.to_string(), "no differences\n");
+
+        let diff = two.diff(&three);
+        assert!(diff.recolored.is_empty());
+        assert_eq!(
+            diff.edges,
+            vec![EdgeDifference {
+                source: (1, 1),
+                symbol: 'a',
+                left: Some(0),
+                right: Some(2),
+            }]
+        );
+        assert!(diff.only_left.is_empty());
+        assert_eq!(diff.only_right, vec![2]);
+        assert_eq!(
+            diff.to_string(),
+            "- q1/q1 --a--> q0\n+ q1/q1 --a--> q2\n+ q2\n"
+        );
+
+        let dot = diff.to_dot();
+        assert!(dot.contains("a1 -> a0 [label=\"a\", color=\"red\", fontcolor=\"red\"];"));
+        assert!(dot.contains("a1 -> r2 [label=\"a\", color=\"green\", fontcolor=\"green\"];"));
+        assert!(dot.contains("r2 -> a0 [label=\"a\", color=\"green\", fontcolor=\"green\"];"));
+        assert!(dot.contains("a0 -> a1 [label=\"a\"];"));
+    }
+
+    #[test]
+    fn recolored_states() {
+        let (accepting, rejecting) = (count_a(2, true), count_a(2, false));
+        let diff = accepting.diff(&rejecting);
+        assert_eq!(diff.recolored, vec![(0, 0)]);
+        assert!(diff.edges.is_empty());
+        assert_eq!(diff.to_string(), "~ q0/q0: true -> false\n");
+        assert!(diff.to_dot().contains("color=\"orange\""));
+    }
+}
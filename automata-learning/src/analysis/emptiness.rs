@@ -0,0 +1,208 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+
+use super::reachability::{shortest_run, Run};
+
+/// Searches for an accepting lasso, i.e. a run from the initial state followed by a non-empty
+/// cycle that takes an accepting edge, see [`BuchiEmptiness::is_empty_buchi`].
+pub(crate) fn accepting_lasso<Ts>(ts: &Ts) -> Option<(Run<Ts::StateIndex>, Run<Ts::StateIndex>)>
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool> + Pointed,
+{
+    let initial = ts.initial();
+    let mut access: Map<Ts::StateIndex, Run<Ts::StateIndex>> =
+        [(initial, vec![])].into_iter().collect();
+    let mut queue = VecDeque::from([initial]);
+    while let Some(q) = queue.pop_front() {
+        for e in ts.edges_from(q).expect("State must exist") {
+            let (sym, p) = (*e.expression(), e.target());
+            if e.color() {
+                if let Some(back) = shortest_run(ts, p, q) {
+                    let cycle = std::iter::once((q, sym)).chain(back).collect();
+                    return Some((access[&q].clone(), cycle));
+                }
+            }
+            if !access.contains_key(&p) {
+                let mut run = access[&q].clone();
+                run.push((q, sym));
+                access.insert(p, run);
+                queue.push_back(p);
+            }
+        }
+    }
+    None
+}
+
+/// Returns a shortest word from `source` to every state that is reachable using only edges
+/// whose priority satisfies `allowed`.
+fn shortest_words<Ts, F>(
+    ts: &Ts,
+    source: Ts::StateIndex,
+    priority: &F,
+    allowed: impl Fn(usize) -> bool,
+) -> Map<Ts::StateIndex, Vec<char>>
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet>,
+    F: Fn(Ts::EdgeColor) -> usize,
+{
+    let mut words: Map<_, Vec<char>> = [(source, vec![])].into_iter().collect();
+    let mut queue = VecDeque::from([source]);
+    while let Some(q) = queue.pop_front() {
+        for e in ts.edges_from(q).expect("State must exist") {
+            if allowed(priority(e.color())) && !words.contains_key(&e.target()) {
+                let mut word = words[&q].clone();
+                word.push(*e.expression());
+                words.insert(e.target(), word);
+                queue.push_back(e.target());
+            }
+        }
+    }
+    words
+}
+
+/// Finds a lasso `(u, v)` that minimizes the length of `u v` among all lassos whose run
+/// returns to the same state after reading `v` once and visits a least priority that is
+/// even on the cycle. The priorities of edges are given by `priority`.
+///
+/// A cycle through `q` with least priority `k` consists of a shortest path from `q` to the
+/// source of an edge with priority `k`, that edge and a shortest path back to `q`, where only
+/// edges with priority at least `k` are used.
+pub(crate) fn shortest_lasso<Ts, F>(ts: &Ts, priority: F) -> Option<(Vec<char>, Vec<char>)>
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet> + Pointed,
+    F: Fn(Ts::EdgeColor) -> usize,
+{
+    let access = shortest_words(ts, ts.initial(), &priority, |_| true);
+    let edges = access
+        .keys()
+        .sorted()
+        .flat_map(|q| {
+            ts.edges_from(*q)
+                .expect("State must exist")
+                .map(|e| (*q, *e.expression(), priority(e.color()), e.target()))
+                .collect_vec()
+        })
+        .collect_vec();
+    let even = edges
+        .iter()
+        .map(|(_, _, k, _)| *k)
+        .filter(|k| k % 2 == 0)
+        .unique()
+        .sorted();
+
+    let mut best: Option<(Vec<char>, Vec<char>)> = None;
+    for k in even {
+        let mut within: Map<Ts::StateIndex, Map<Ts::StateIndex, Vec<char>>> = Map::default();
+        let mut from = |q: Ts::StateIndex| {
+            within
+                .entry(q)
+                .or_insert_with(|| shortest_words(ts, q, &priority, |c| c >= k))
+                .clone()
+        };
+        for q in access.keys().sorted() {
+            let reach = from(*q);
+            for (p, sym, _, target) in edges.iter().filter(|(_, _, c, _)| *c == k) {
+                let (Some(there), Some(back)) = (reach.get(p), from(*target).get(q).cloned())
+                else {
+                    continue;
+                };
+                let cycle = there
+                    .iter()
+                    .cloned()
+                    .chain(std::iter::once(*sym))
+                    .chain(back)
+                    .collect_vec();
+                let length = access[q].len() + cycle.len();
+                if !best
+                    .as_ref()
+                    .is_some_and(|(u, v)| u.len() + v.len() <= length)
+                {
+                    best = Some((access[q].clone(), cycle));
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Decides emptiness of transition systems with Büchi acceptance on the edges, i.e. an
+/// infinite run is accepting if it takes an edge colored with `true` infinitely often. This is
+/// the acceptance condition of [`DBA`], but the transition system need not be deterministic.
+pub trait BuchiEmptiness:
+    TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool> + Pointed
+{
+    /// Returns `None` if no infinite word is accepted. Otherwise returns a lasso `(u, v)` with
+    /// non-empty `v` such that the ultimately periodic word `u v^ω` is accepted.
+    ///
+    /// For every accepting edge that is reachable from the initial state, in breadth-first
+    /// order, we search for a path back to its source, so the running time is quadratic in the
+    /// number of edges in the worst case. The prefix `u` is a shortest word reaching the source
+    /// of the first accepting edge that lies on a cycle.
+    fn is_empty_buchi(&self) -> Option<(Vec<char>, Vec<char>)> {
+        let symbols = |run: Run<Self::StateIndex>| run.into_iter().map(|(_, sym)| sym).collect();
+        accepting_lasso(self).map(|(prefix, cycle)| (symbols(prefix), symbols(cycle)))
+    }
+
+    /// Returns an accepted lasso `(u, v)` for which `u v` is as short as possible, among the
+    /// lassos whose run returns to the same state after reading `v` once. For deterministic
+    /// automata, this is usually the most readable witness, but it is more expensive to find
+    /// than the one given by [`Self::is_empty_buchi`].
+    fn shortest_accepted_lasso(&self) -> Option<(Vec<char>, Vec<char>)> {
+        shortest_lasso(self, |accepting| usize::from(!accepting))
+    }
+}
+
+impl<Ts: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool> + Pointed> BuchiEmptiness
+    for Ts
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::BuchiEmptiness;
+
+    #[test]
+    fn buchi_lasso() {
+        // infinitely many b's, but only after the first a
+        let mut dba = DBA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dba.add_state(Void);
+        let q1 = dba.add_state(Void);
+        dba.add_edge(q0, 'a', q1, false);
+        dba.add_edge(q0, 'b', q0, false);
+        dba.add_edge(q1, 'a', q1, false);
+        dba.add_edge(q1, 'b', q1, true);
+        let (prefix, cycle) = dba.is_empty_buchi().expect("Language is not empty");
+        assert_eq!(prefix, vec!['a']);
+        assert_eq!(cycle, vec!['b']);
+
+        // the accepting edge is not on a cycle
+        let mut dba = DBA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dba.add_state(Void);
+        let q1 = dba.add_state(Void);
+        dba.add_edge(q0, 'a', q1, true);
+        dba.add_edge(q0, 'b', q0, false);
+        dba.add_edge(q1, 'a', q1, false);
+        dba.add_edge(q1, 'b', q1, false);
+        assert_eq!(dba.is_empty_buchi(), None);
+        assert_eq!(dba.shortest_accepted_lasso(), None);
+    }
+
+    #[test]
+    fn shortest_buchi_lasso() {
+        // the accepting cycle through q1 is found first, but the loop on q2 is shorter
+        let mut dba = DBA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let [q0, q1, q2, q3, q4] = [(); 5].map(|_| dba.add_state(Void));
+        dba.add_edge(q0, 'a', q1, false);
+        dba.add_edge(q0, 'b', q2, false);
+        dba.add_edge(q1, 'a', q3, true);
+        dba.add_edge(q3, 'a', q4, false);
+        dba.add_edge(q4, 'a', q1, false);
+        dba.add_edge(q2, 'a', q2, true);
+        assert_eq!(dba.is_empty_buchi(), Some((vec!['a'], vec!['a', 'a', 'a'])));
+        assert_eq!(dba.shortest_accepted_lasso(), Some((vec!['b'], vec!['a'])));
+    }
+}
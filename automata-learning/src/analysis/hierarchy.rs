@@ -0,0 +1,421 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+
+use super::{emptiness::shortest_lasso, Productive, Reachability};
+
+/// An edge given by its source, priority and target.
+type Edge<Idx> = (Idx, usize, Idx);
+
+/// Returns the states that are reachable from `source` via `edges`.
+fn reach<Idx: IndexType>(edges: &[Edge<Idx>], source: Idx) -> Set<Idx> {
+    let mut reached: Set<_> = [source].into_iter().collect();
+    let mut queue = VecDeque::from([source]);
+    while let Some(q) = queue.pop_front() {
+        for (_, _, p) in edges.iter().filter(|(s, _, _)| *s == q) {
+            if reached.insert(*p) {
+                queue.push_back(*p);
+            }
+        }
+    }
+    reached
+}
+
+/// Returns true if `edges` contain a cycle whose least priority is `k`.
+fn has_cycle_with_least<Idx: IndexType>(edges: &[Edge<Idx>], k: usize) -> bool {
+    let above = edges
+        .iter()
+        .filter(|(_, c, _)| *c >= k)
+        .cloned()
+        .collect_vec();
+    above
+        .iter()
+        .filter(|(_, c, _)| *c == k)
+        .any(|(p, _, q)| reach(&above, *q).contains(p))
+}
+
+/// Returns true if `edges` contain a cycle whose least priority is odd, i.e. a rejecting cycle.
+fn has_rejecting_cycle<Idx: IndexType>(edges: &[Edge<Idx>]) -> bool {
+    edges
+        .iter()
+        .map(|(_, c, _)| *c)
+        .filter(|c| c % 2 == 1)
+        .unique()
+        .any(|k| has_cycle_with_least(edges, k))
+}
+
+/// A chain of loops, each given by its states and whether it is accepting, where every loop is
+/// contained in the next one and the acceptance alternates.
+pub type AlternatingChain<Idx> = Vec<(Set<Idx>, bool)>;
+
+/// The least number of priorities with which the language of a DPA can be recognized, see
+/// [`TemporalHierarchy::parity_index`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParityIndex<Idx> {
+    /// The least number of priorities that is needed.
+    pub priorities: usize,
+    /// A longest alternating chain of loops, starting with the smallest loop, which witnesses
+    /// that fewer priorities do not suffice, up to the additional priority that is needed if
+    /// longest chains end with loops of both parities. Such a chain is also called a flower.
+    pub flower: AlternatingChain<Idx>,
+}
+
+/// Splits `edges` into the edge sets of the strongly connected components that contain at
+/// least one edge.
+fn components<Idx: IndexType>(edges: &[Edge<Idx>]) -> Vec<Vec<Edge<Idx>>> {
+    let reversed = edges.iter().map(|(p, c, q)| (*q, *c, *p)).collect_vec();
+    let mut assigned: Set<Idx> = Set::default();
+    let mut components = vec![];
+    for (source, _, _) in edges {
+        if assigned.contains(source) {
+            continue;
+        }
+        let component: Set<_> = reach(edges, *source)
+            .intersection(&reach(&reversed, *source))
+            .cloned()
+            .collect();
+        assigned.extend(component.iter().cloned());
+        let inner = edges
+            .iter()
+            .filter(|(p, _, q)| component.contains(p) && component.contains(q))
+            .cloned()
+            .collect_vec();
+        if !inner.is_empty() {
+            components.push(inner);
+        }
+    }
+    components
+}
+
+/// Returns a longest alternating chain in the strongly connected `component` whose largest
+/// loop is accepting and one whose largest loop is rejecting, if they exist. Every loop that
+/// contains an edge with the least priority `m` of the component has the parity of `m`, and
+/// the whole component is such a loop. All other loops lie in a component of the edges with
+/// priority above `m`, so the chains are computed recursively from those.
+fn longest_chains<Idx: IndexType>(
+    component: &[Edge<Idx>],
+) -> (Option<AlternatingChain<Idx>>, Option<AlternatingChain<Idx>>) {
+    let least = component
+        .iter()
+        .map(|(_, c, _)| *c)
+        .min()
+        .expect("Component has edges");
+    let accepting = least % 2 == 0;
+    let above = component
+        .iter()
+        .filter(|(_, c, _)| *c > least)
+        .cloned()
+        .collect_vec();
+    // the longest chain below whose largest loop has the other parity
+    let other = components(&above)
+        .iter()
+        .filter_map(|inner| {
+            let (acc, rej) = longest_chains(inner);
+            if accepting {
+                rej
+            } else {
+                acc
+            }
+        })
+        .max_by_key(|chain| chain.len());
+    let states = component.iter().map(|(p, _, _)| *p).collect();
+    let mut extended = other.clone().unwrap_or_default();
+    extended.push((states, accepting));
+    if accepting {
+        (Some(extended), other)
+    } else {
+        (other, Some(extended))
+    }
+}
+
+/// Copies `ts`, where the color of each edge is computed from its priority and its target, and
+/// returns the copy together with the index of the initial state in it.
+fn recolor<Ts, C, F>(ts: &Ts, color: F) -> (DTS<CharAlphabet, Void, C>, usize)
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = usize> + Pointed,
+    C: Color,
+    F: Fn(usize, Ts::StateIndex) -> C,
+{
+    let mut copy = DTS::new_for_alphabet(ts.alphabet().clone());
+    let states = ts.state_indices().sorted().collect_vec();
+    let index: Map<_, _> = states.iter().map(|q| (*q, copy.add_state(Void))).collect();
+    for q in &states {
+        for e in ts.edges_from(*q).expect("State must exist") {
+            let target = e.target();
+            copy.add_edge(
+                index[q],
+                *e.expression(),
+                index[&target],
+                color(e.color(), target),
+            );
+        }
+    }
+    (copy, index[&ts.initial()])
+}
+
+/// Classifies the language of a deterministic parity automaton in the lower levels of the
+/// temporal hierarchy of Manna and Pnueli and decomposes it into a safety and a liveness
+/// language. As elsewhere, a run is accepting if the least priority that occurs infinitely
+/// often is even, so a DBA can be viewed as a DPA with priorities 0 and 1. The automaton must
+/// be deterministic and complete.
+pub trait TemporalHierarchy:
+    TransitionSystem<Alphabet = CharAlphabet, EdgeColor = usize> + Pointed
+{
+    /// Returns the edges between states that are reachable from the initial state.
+    fn reachable_edges(&self) -> Vec<Edge<Self::StateIndex>> {
+        self.reachable_from([self.initial()])
+            .into_iter()
+            .sorted()
+            .flat_map(|q| {
+                self.edges_from(q)
+                    .expect("State must exist")
+                    .map(|e| (q, e.color(), e.target()))
+                    .collect_vec()
+            })
+            .collect()
+    }
+
+    /// Returns the safety closure of the language, i.e. the set of words all of whose prefixes
+    /// can be extended to an accepted word. It is recognized by a DBA with the same states,
+    /// where the edges that stay in productive states are accepting.
+    fn safety_closure(&self) -> DBA {
+        let productive = self.productive_states_parity();
+        let (ts, initial) = recolor(self, |_, target| productive.contains(&target));
+        ts.with_initial(initial).into_dba()
+    }
+
+    /// Returns the liveness part of the language, which is the union of the language with the
+    /// complement of its safety closure, so the language is the intersection of the safety
+    /// closure and the liveness part. It is recognized by a DPA with the same states, where
+    /// edges into dead states get priority 0.
+    fn liveness_part(&self) -> DPA {
+        let productive = self.productive_states_parity();
+        let (ts, initial) = recolor(self, |priority, target| {
+            if productive.contains(&target) {
+                priority
+            } else {
+                0
+            }
+        });
+        ts.with_initial(initial).into_dpa()
+    }
+
+    /// Returns an accepted lasso `(u, v)` for which `u v` is as short as possible, among the
+    /// lassos whose run returns to the same state after reading `v` once, or `None` if the
+    /// language is empty.
+    fn shortest_accepted_lasso(&self) -> Option<(Vec<char>, Vec<char>)> {
+        shortest_lasso(self, |priority| priority)
+    }
+
+    /// Computes the least number of priorities with which a DPA can recognize the language,
+    /// i.e. its parity index. By a result of Wagner, this is determined by the longest chains of
+    /// nested loops with alternating acceptance, which do not depend on the automaton. With
+    /// `n` consecutive priorities, the largest loop of a chain of length `n` has the parity of
+    /// the least priority, so one more priority is needed if there are longest chains whose
+    /// largest loops have different parities. The chains are computed by recursively
+    /// decomposing the strongly connected components along their least priorities, as
+    /// proposed by Carton and Maceiras.
+    fn parity_index(&self) -> ParityIndex<Self::StateIndex> {
+        let (accepting, rejecting): (Vec<_>, Vec<_>) = components(&self.reachable_edges())
+            .iter()
+            .map(|component| longest_chains(component))
+            .unzip();
+        let longest = |chains: Vec<Option<AlternatingChain<Self::StateIndex>>>| {
+            chains
+                .into_iter()
+                .flatten()
+                .max_by_key(|chain| chain.len())
+                .unwrap_or_default()
+        };
+        let (accepting, rejecting) = (longest(accepting), longest(rejecting));
+        let priorities = if accepting.len() == rejecting.len() {
+            accepting.len() + 1
+        } else {
+            accepting.len().max(rejecting.len())
+        };
+        let flower = if accepting.len() >= rejecting.len() {
+            accepting
+        } else {
+            rejecting
+        };
+        ParityIndex { priorities, flower }
+    }
+
+    /// Decides whether the language can be recognized by a DPA with the given number of
+    /// priorities. Otherwise returns an alternating chain of loops that is too long, see
+    /// [`Self::parity_index`].
+    fn recognizable_with(
+        &self,
+        priorities: usize,
+    ) -> Result<(), AlternatingChain<Self::StateIndex>> {
+        let index = self.parity_index();
+        if index.priorities <= priorities {
+            Ok(())
+        } else {
+            Err(index.flower)
+        }
+    }
+
+    /// Returns true if the language is a safety language, i.e. it equals its safety closure.
+    /// This is the case if no cycle of reachable productive states is rejecting.
+    fn is_safety(&self) -> bool {
+        let productive = self.productive_states_parity();
+        let edges = self
+            .reachable_edges()
+            .into_iter()
+            .filter(|(p, _, q)| productive.contains(p) && productive.contains(q))
+            .collect_vec();
+        !has_rejecting_cycle(&edges)
+    }
+
+    /// Returns true if the language is a liveness language, i.e. every finite word can be
+    /// extended to an accepted word, which means that all reachable states are productive.
+    fn is_liveness(&self) -> bool {
+        let productive = self.productive_states_parity();
+        self.reachable_from([self.initial()])
+            .iter()
+            .all(|q| productive.contains(q))
+    }
+
+    /// Returns true if the language is a persistence language, i.e. it can be recognized by a
+    /// deterministic co-Büchi automaton. By the characterization of Landweber, this is the
+    /// case if no accepting cycle contains a rejecting cycle. We search for an accepting cycle
+    /// with least priority `k` whose strongly connected component in the edges with priority
+    /// at least `k` has a rejecting cycle above `k`.
+    fn is_persistence(&self) -> bool {
+        let edges = self.reachable_edges();
+        let even = edges
+            .iter()
+            .map(|(_, c, _)| *c)
+            .filter(|c| c % 2 == 0)
+            .unique()
+            .collect_vec();
+        for k in even {
+            let above = edges
+                .iter()
+                .filter(|(_, c, _)| *c >= k)
+                .cloned()
+                .collect_vec();
+            let reversed = above.iter().map(|(p, c, q)| (*q, *c, *p)).collect_vec();
+            for (p, _, q) in above.iter().filter(|(_, c, _)| *c == k) {
+                if !reach(&above, *q).contains(p) {
+                    continue;
+                }
+                let component: Set<_> = reach(&above, *p)
+                    .intersection(&reach(&reversed, *p))
+                    .cloned()
+                    .collect();
+                let inner = above
+                    .iter()
+                    .filter(|(s, c, t)| *c > k && component.contains(s) && component.contains(t))
+                    .cloned()
+                    .collect_vec();
+                if has_rejecting_cycle(&inner) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+impl<Ts> TemporalHierarchy for Ts where
+    Ts: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = usize> + Pointed
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::TemporalHierarchy;
+
+    #[test]
+    fn classify_temporal_hierarchy() {
+        // never b, a safety language
+        let never_b: DPA = NTS::builder()
+            .with_transitions([
+                (0, 'a', 0, 0),
+                (0, 'b', 1, 1),
+                (1, 'a', 1, 1),
+                (1, 'b', 1, 1),
+            ])
+            .into_dpa(0);
+        assert!(never_b.is_safety());
+        assert!(!never_b.is_liveness());
+        assert!(never_b.is_persistence());
+
+        // infinitely many a's, a liveness language that is not a persistence language
+        let infinitely_a: DPA = NTS::builder()
+            .with_transitions([(0, 'a', 0, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        assert!(!infinitely_a.is_safety());
+        assert!(infinitely_a.is_liveness());
+        assert!(!infinitely_a.is_persistence());
+        assert_eq!(
+            infinitely_a.shortest_accepted_lasso(),
+            Some((vec![], vec!['a']))
+        );
+
+        // eventually always a, a persistence language
+        let eventually_a: DPA = NTS::builder()
+            .with_transitions([(0, 'a', 2, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        assert!(!eventually_a.is_safety());
+        assert!(eventually_a.is_persistence());
+
+        // a b^ω or infinitely many a's after starting with b, where reading aa leads into a
+        // dead state, so the safety closure forbids it and the liveness part allows it
+        let mixed: DPA = NTS::builder()
+            .with_transitions([
+                (0, 'a', 1, 1),
+                (0, 'b', 1, 2),
+                (1, 'a', 1, 3),
+                (1, 'b', 0, 1),
+                (2, 'a', 0, 2),
+                (2, 'b', 1, 2),
+                (3, 'a', 1, 3),
+                (3, 'b', 1, 3),
+            ])
+            .into_dpa(0);
+        assert!(!mixed.is_liveness());
+        let closure = mixed.safety_closure();
+        assert!(closure.accepts(upw!("a", "b")));
+        assert!(!closure.accepts(upw!("aa", "b")));
+        assert!(closure.accepts(upw!("b", "a")));
+        let liveness = mixed.liveness_part();
+        assert!(liveness.accepts(upw!("aa", "b")));
+        assert!(!liveness.accepts(upw!("b", "b")));
+    }
+
+    #[test]
+    fn parity_index() {
+        // a flower with three petals: the loop on c is accepting, adding b makes it rejecting
+        // and adding a makes it accepting again
+        let flower: DPA = NTS::builder()
+            .with_transitions([(0, 'a', 0, 0), (0, 'b', 1, 0), (0, 'c', 2, 0)])
+            .into_dpa(0);
+        let index = flower.parity_index();
+        assert_eq!(index.priorities, 3);
+        assert_eq!(
+            index.flower.iter().map(|(_, acc)| *acc).collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
+        assert!(flower.recognizable_with(3).is_ok());
+        assert_eq!(flower.recognizable_with(2).unwrap_err().len(), 3);
+
+        // never b, where the accepting and the rejecting loop are not nested, but both
+        // parities are needed
+        let never_b: DPA = NTS::builder()
+            .with_transitions([
+                (0, 'a', 0, 0),
+                (0, 'b', 1, 1),
+                (1, 'a', 3, 1),
+                (1, 'b', 1, 1),
+            ])
+            .into_dpa(0);
+        assert_eq!(never_b.parity_index().priorities, 2);
+        assert_eq!(never_b.parity_index().flower.len(), 1);
+    }
+}
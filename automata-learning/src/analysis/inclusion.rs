@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+
+/// Summarizes the runs of a Büchi automaton on a finite word: the entry for `(p, q)` is `None`
+/// if there is no run from `p` to `q`, and otherwise whether some such run takes an accepting
+/// edge. States are identified by their position in a fixed order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RunGraph {
+    states: usize,
+    cells: Vec<Option<bool>>,
+}
+
+impl RunGraph {
+    fn get(&self, p: usize, q: usize) -> Option<bool> {
+        self.cells[p * self.states + q]
+    }
+
+    /// The graph of a single symbol.
+    fn of_symbol<Ts>(ts: &Ts, position: &Map<Ts::StateIndex, usize>, sym: char) -> Self
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool>,
+    {
+        let states = position.len();
+        let mut cells = vec![None; states * states];
+        for (q, p) in position {
+            for e in ts.edges_from(*q).expect("State must exist") {
+                if *e.expression() == sym {
+                    let cell = &mut cells[p * states + position[&e.target()]];
+                    *cell = Some(cell.unwrap_or(false) || e.color());
+                }
+            }
+        }
+        Self { states, cells }
+    }
+
+    /// The graph of the concatenation of the words summarized by `self` and `other`.
+    fn compose(&self, other: &Self) -> Self {
+        let n = self.states;
+        let mut cells = vec![None; n * n];
+        for (p, q) in (0..n).cartesian_product(0..n) {
+            let Some(first) = self.get(p, q) else {
+                continue;
+            };
+            for r in 0..n {
+                if let Some(second) = other.get(q, r) {
+                    let cell = &mut cells[p * n + r];
+                    *cell = Some(cell.unwrap_or(false) || first || second);
+                }
+            }
+        }
+        Self { states: n, cells }
+    }
+
+    /// Returns true if, for a graph `self` of `u` and an idempotent graph `period` of `v`, the
+    /// word `u v^ω` is accepted from the state at position `initial`.
+    fn accepts_lasso(&self, period: &Self, initial: usize) -> bool {
+        (0..self.states).any(|q| self.get(initial, q).is_some() && period.get(q, q) == Some(true))
+    }
+}
+
+/// Assigns consecutive positions to the states of `ts` and returns the position of the initial
+/// state.
+fn positions<Ts: TransitionSystem + Pointed>(ts: &Ts) -> (Map<Ts::StateIndex, usize>, usize) {
+    let position: Map<_, _> = ts
+        .state_indices()
+        .sorted()
+        .enumerate()
+        .map(|(i, q)| (q, i))
+        .collect();
+    let initial = position[&ts.initial()];
+    (position, initial)
+}
+
+/// Language inclusion for transition systems with Büchi acceptance on the edges, which may be
+/// nondeterministic. See [`super::BuchiEmptiness`] for the acceptance condition.
+pub trait BuchiInclusion:
+    TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool> + Pointed
+{
+    /// Decides whether every infinite word accepted by `self` is also accepted by `other`.
+    /// Otherwise returns a lasso `(u, v)` such that `u v^ω` is accepted by `self`, but not by
+    /// `other`.
+    ///
+    /// This uses the Ramsey-based approach, which avoids complementing `other`. For every word
+    /// we consider the pair of graphs that summarize the runs of both automata on it, and we
+    /// explore all such pairs that are induced by some non-empty word. By Ramsey's theorem,
+    /// every counterexample can be split as `u v^ω`, where the pair of `v` is idempotent and
+    /// absorbed by the pair of `u`, so it suffices to check the explored pairs. Their number
+    /// can be exponential in the number of states of `other`, but it is usually far smaller
+    /// than a determinization.
+    fn buchi_inclusion<B>(&self, other: &B) -> Result<(), (Vec<char>, Vec<char>)>
+    where
+        B: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool> + Pointed,
+    {
+        let (left, left_initial) = positions(self);
+        let (right, right_initial) = positions(other);
+        let letters = self
+            .alphabet()
+            .universe()
+            .map(|sym| {
+                let graphs = (
+                    RunGraph::of_symbol(self, &left, sym),
+                    RunGraph::of_symbol(other, &right, sym),
+                );
+                (sym, graphs)
+            })
+            .collect_vec();
+
+        // the pairs of graphs that are induced by non-empty words, each with a shortest word
+        let mut words: Map<(RunGraph, RunGraph), Vec<char>> = Map::default();
+        let mut queue = VecDeque::new();
+        for (sym, graphs) in &letters {
+            if !words.contains_key(graphs) {
+                words.insert(graphs.clone(), vec![*sym]);
+                queue.push_back(graphs.clone());
+            }
+        }
+        while let Some((first, second)) = queue.pop_front() {
+            for (sym, (left_letter, right_letter)) in &letters {
+                let next = (first.compose(left_letter), second.compose(right_letter));
+                if !words.contains_key(&next) {
+                    let mut word = words[&(first.clone(), second.clone())].clone();
+                    word.push(*sym);
+                    words.insert(next.clone(), word);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let graphs = words
+            .iter()
+            .sorted_by_key(|(_, word)| (word.len(), (*word).clone()))
+            .collect_vec();
+        let idempotent = graphs
+            .iter()
+            .filter(|((l, r), _)| l.compose(l) == *l && r.compose(r) == *r);
+        for ((period_left, period_right), period) in idempotent {
+            for ((prefix_left, prefix_right), prefix) in &graphs {
+                if prefix_left.compose(period_left) == *prefix_left
+                    && prefix_right.compose(period_right) == *prefix_right
+                    && prefix_left.accepts_lasso(period_left, left_initial)
+                    && !prefix_right.accepts_lasso(period_right, right_initial)
+                {
+                    return Err(((*prefix).clone(), (*period).clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Ts> BuchiInclusion for Ts where
+    Ts: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool> + Pointed
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::BuchiInclusion;
+
+    #[test]
+    fn ramsey_inclusion() {
+        // infinitely many a's
+        let mut infinitely_a = NTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = infinitely_a.add_state(Void);
+        infinitely_a.add_edge(q0, 'a', q0, true);
+        infinitely_a.add_edge(q0, 'b', q0, false);
+        let infinitely_a = infinitely_a.with_initial(q0);
+
+        // finitely many b's, the automaton guesses when the last b was read
+        let mut finitely_b = NTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let p0 = finitely_b.add_state(Void);
+        let p1 = finitely_b.add_state(Void);
+        finitely_b.add_edge(p0, 'a', p0, false);
+        finitely_b.add_edge(p0, 'b', p0, false);
+        finitely_b.add_edge(p0, 'a', p1, false);
+        finitely_b.add_edge(p1, 'a', p1, true);
+        let finitely_b = finitely_b.with_initial(p0);
+
+        assert_eq!(finitely_b.buchi_inclusion(&infinitely_a), Ok(()));
+        let (prefix, period) = infinitely_a
+            .buchi_inclusion(&finitely_b)
+            .expect_err("(ab)^ω is a counterexample");
+        assert!(!prefix.is_empty());
+        // the period must contain both an a and a b
+        assert!(period.contains(&'a') && period.contains(&'b'));
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    collections::VecDeque,
+    hash::{Hash, Hasher},
+};
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+
+/// Isomorphism of deterministic transition systems with initial states. Two transition systems
+/// are isomorphic if there is a bijection between their reachable states that maps the initial
+/// state to the initial state and preserves state colors, edge colors and transitions. Since
+/// both are deterministic, the bijection is unique if it exists, and it is found by running
+/// both transition systems in lockstep from their initial states in breadth-first order.
+/// States that are not reachable from the initial state are ignored.
+///
+/// The same lockstep exploration of a single transition system yields a canonical numbering
+/// of its states, which is the basis of [`Isomorphism::canonical_hash`].
+pub trait Isomorphism: Deterministic<Alphabet = CharAlphabet> + Pointed {
+    /// Returns the bijection between the reachable states of `self` and `other` if they are
+    /// isomorphic, and `None` otherwise. The alphabets must consist of the same symbols.
+    fn is_isomorphic<O>(&self, other: &O) -> Option<Map<Self::StateIndex, O::StateIndex>>
+    where
+        O: Deterministic<
+                Alphabet = CharAlphabet,
+                StateColor = Self::StateColor,
+                EdgeColor = Self::EdgeColor,
+            > + Pointed,
+    {
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        if symbols != other.alphabet().universe().sorted().collect_vec() {
+            return None;
+        }
+        let mut forward: Map<Self::StateIndex, O::StateIndex> = Map::default();
+        let mut backward: Map<O::StateIndex, Self::StateIndex> = Map::default();
+        let mut queue = VecDeque::from([(self.initial(), other.initial())]);
+        forward.insert(self.initial(), other.initial());
+        backward.insert(other.initial(), self.initial());
+        while let Some((p, q)) = queue.pop_front() {
+            if self.state_color(p) != other.state_color(q) {
+                return None;
+            }
+            for sym in &symbols {
+                let left = self
+                    .edges_from(p)
+                    .expect("State must exist")
+                    .find(|e| e.expression() == sym)
+                    .map(|e| (e.color(), e.target()));
+                let right = other
+                    .edges_from(q)
+                    .expect("State must exist")
+                    .find(|e| e.expression() == sym)
+                    .map(|e| (e.color(), e.target()));
+                match (left, right) {
+                    (None, None) => {}
+                    (Some((c, p)), Some((d, q))) if c == d => {
+                        match (forward.get(&p), backward.get(&q)) {
+                            (None, None) => {
+                                forward.insert(p, q);
+                                backward.insert(q, p);
+                                queue.push_back((p, q));
+                            }
+                            (Some(image), Some(preimage)) if *image == q && *preimage == p => {}
+                            _ => return None,
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+        }
+        Some(forward)
+    }
+
+    /// Numbers the reachable states in the order in which a breadth-first search from the
+    /// initial state discovers them, where successors are visited in the order of their
+    /// symbols. Isomorphic transition systems get the same numbering up to the bijection.
+    fn canonical_numbering(&self) -> Map<Self::StateIndex, usize> {
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        let mut numbering: Map<Self::StateIndex, usize> = Map::default();
+        numbering.insert(self.initial(), 0);
+        let mut queue = VecDeque::from([self.initial()]);
+        while let Some(q) = queue.pop_front() {
+            for sym in &symbols {
+                if let Some(p) = self.successor_index(q, *sym) {
+                    if !numbering.contains_key(&p) {
+                        numbering.insert(p, numbering.len());
+                        queue.push_back(p);
+                    }
+                }
+            }
+        }
+        numbering
+    }
+
+    /// Returns a hash of the reachable part that does not depend on the indices of the
+    /// states, so isomorphic transition systems have the same hash. It is computed from the
+    /// symbols and the colors and transitions of the states in the canonical numbering, using
+    /// the FNV-1a function, so it is stable across runs and can be used as a cache key.
+    fn canonical_hash(&self) -> u64
+    where
+        Self::StateColor: Hash,
+        Self::EdgeColor: Hash,
+    {
+        let numbering = self.canonical_numbering();
+        let states = numbering
+            .iter()
+            .sorted_by_key(|(_, i)| **i)
+            .map(|(q, _)| *q)
+            .collect_vec();
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        let mut hasher = Fnv1a::default();
+        symbols.hash(&mut hasher);
+        for q in states {
+            self.state_color(q)
+                .expect("State must exist")
+                .hash(&mut hasher);
+            for sym in &symbols {
+                self.edges_from(q)
+                    .expect("State must exist")
+                    .find(|e| e.expression() == sym)
+                    .map(|e| (e.color(), numbering[&e.target()]))
+                    .hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+impl<D: Deterministic<Alphabet = CharAlphabet> + Pointed> Isomorphism for D {}
+
+/// The 64-bit FNV-1a hash function, whose output unlike that of the hasher of the standard
+/// library is fixed and does not change between releases.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, Set};
+
+    use super::Isomorphism;
+
+    /// An even number of a's, where the odd state is added first if `odd_first` is set and the
+    /// initial state is the even one if `start_even` is set.
+    fn parity_of_a(odd_first: bool, start_even: bool) -> DFA {
+        let mut ts = DTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let (even, odd) = if odd_first {
+            let odd = ts.add_state(false);
+            (ts.add_state(true), odd)
+        } else {
+            (ts.add_state(true), ts.add_state(false))
+        };
+        ts.add_edge(even, 'a', odd, Void);
+        ts.add_edge(even, 'b', even, Void);
+        ts.add_edge(odd, 'a', even, Void);
+        ts.add_edge(odd, 'b', odd, Void);
+        ts.with_initial(if start_even { even } else { odd })
+            .into_dfa()
+    }
+
+    #[test]
+    fn isomorphic_dfas() {
+        let dfa = parity_of_a(false, true);
+        let bijection = dfa.is_isomorphic(&dfa).unwrap();
+        assert!(bijection.iter().all(|(p, q)| p == q));
+
+        // the same automaton with the states numbered the other way round
+        let renumbered = parity_of_a(true, true);
+        let bijection = dfa.is_isomorphic(&renumbered).unwrap();
+        assert_eq!(bijection[&0], 1);
+        assert_eq!(bijection[&1], 0);
+
+        // starting in the odd state changes the colors that are met in lockstep
+        assert!(dfa.is_isomorphic(&parity_of_a(false, false)).is_none());
+    }
+
+    #[test]
+    fn canonical_hashes() {
+        let dfa = parity_of_a(false, true);
+        let renumbered = parity_of_a(true, true);
+        assert_eq!(renumbered.canonical_numbering()[&1], 0);
+        assert_eq!(dfa.canonical_hash(), renumbered.canonical_hash());
+        assert_ne!(
+            dfa.canonical_hash(),
+            parity_of_a(false, false).canonical_hash()
+        );
+
+        // deduplication of a list of hypotheses
+        let hypotheses = [dfa, renumbered, parity_of_a(true, false)];
+        let distinct: Set<_> = hypotheses.iter().map(|h| h.canonical_hash()).collect();
+        assert_eq!(distinct.len(), 2);
+    }
+}
@@ -0,0 +1,71 @@
+//! Structural analyses and reductions of transition systems, which are used for
+//! comparing and shrinking hypotheses produced by the learning algorithms.
+
+/// Computation of forward and backward simulation relations as well as the
+/// reduction of a transition system by simulation equivalence.
+pub mod simulation;
+pub use simulation::{Simulation, SimulationRelation};
+
+/// Partition refinement for computing the bisimulation quotient of arbitrary colored
+/// transition systems.
+pub mod bisimulation;
+pub use bisimulation::{Bisimulation, Partition};
+
+/// Counting and enumerating the words accepted by a DFA, deciding whether there are finitely
+/// many and estimating the growth of its language.
+pub mod census;
+pub use census::{AcceptedWords, Cardinality, WordCount};
+
+/// Reachability between arbitrary states with shortest witness words.
+pub mod reachability;
+pub use reachability::Reachability;
+
+/// Emptiness checks for Büchi acceptance that produce ultimately periodic witnesses.
+pub mod emptiness;
+pub use emptiness::BuchiEmptiness;
+
+/// Language inclusion of Büchi automata without determinization.
+pub mod inclusion;
+pub use inclusion::BuchiInclusion;
+
+/// Reports of missing transitions and deadlock states.
+pub mod completeness;
+pub use completeness::Completeness;
+
+/// Determinism and ambiguity checks that produce witnesses.
+pub mod determinism;
+pub use determinism::Determinism;
+
+/// Productive states with respect to finite-word, Büchi and parity acceptance, and removal of
+/// dead states.
+pub mod productive;
+pub use productive::Productive;
+
+/// Classification of states into recurrent and transient ones.
+pub mod recurrence;
+pub use recurrence::{Recurrence, StateClass};
+
+/// Classification of omega languages into safety, liveness and persistence languages, the
+/// decomposition into a safety closure and a liveness part, and the computation of parity
+/// indices.
+pub mod hierarchy;
+pub use hierarchy::{AlternatingChain, ParityIndex, TemporalHierarchy};
+
+/// Checks whether languages of DFAs are closed under prefixes, suffixes or factors.
+pub mod closure;
+pub use closure::{ClosureProperties, ClosureViolation};
+
+/// Separating words between states and characterizing sets of deterministic transition
+/// systems.
+pub mod separation;
+pub use separation::Separation;
+
+/// Isomorphism of deterministic transition systems with the witnessing bijection of states, and
+/// hashes that are invariant under renumbering states.
+pub mod isomorphism;
+pub use isomorphism::Isomorphism;
+
+/// Structural differences between deterministic transition systems after aligning their states
+/// from the initial states, as text or as a DOT graph with highlights.
+pub mod diff;
+pub use diff::{EdgeDifference, StructuralDiff, StructuralDifference};
@@ -0,0 +1,214 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+
+/// Returns the states that are reachable from `source` using only edges whose color satisfies
+/// `allowed`.
+fn reachable_within<Ts, F>(ts: &Ts, source: Ts::StateIndex, allowed: &F) -> Set<Ts::StateIndex>
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet>,
+    F: Fn(&Ts::EdgeColor) -> bool,
+{
+    let mut reached: Set<_> = [source].into_iter().collect();
+    let mut queue = VecDeque::from([source]);
+    while let Some(q) = queue.pop_front() {
+        for e in ts.edges_from(q).expect("State must exist") {
+            if allowed(&e.color()) && reached.insert(e.target()) {
+                queue.push_back(e.target());
+            }
+        }
+    }
+    reached
+}
+
+/// Returns the sources of the edges that are marked as `good` and lie on a cycle that only
+/// uses edges whose color satisfies `allowed`.
+fn sources_of_good_cycles<Ts, F, G>(ts: &Ts, allowed: F, good: G) -> Set<Ts::StateIndex>
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet>,
+    F: Fn(&Ts::EdgeColor) -> bool,
+    G: Fn(&Ts::EdgeColor) -> bool,
+{
+    let mut sources = Set::default();
+    for q in ts.state_indices() {
+        for e in ts.edges_from(q).expect("State must exist") {
+            let color = e.color();
+            if good(&color)
+                && allowed(&color)
+                && !sources.contains(&q)
+                && reachable_within(ts, e.target(), &allowed).contains(&q)
+            {
+                sources.insert(q);
+            }
+        }
+    }
+    sources
+}
+
+/// Finds the states that can still lead to acceptance, which are called productive, and
+/// removes all others. The remaining states are dead, i.e. every run that visits them is
+/// rejecting, so removing them preserves the language and avoids clutter in renderings.
+pub trait Productive: TransitionSystem<Alphabet = CharAlphabet> {
+    /// Returns the states from which some state in `targets` is reachable, including the
+    /// targets themselves.
+    fn coreachable(&self, targets: &Set<Self::StateIndex>) -> Set<Self::StateIndex> {
+        let mut predecessors: Map<Self::StateIndex, Vec<Self::StateIndex>> = Map::default();
+        for q in self.state_indices() {
+            for e in self.edges_from(q).expect("State must exist") {
+                predecessors.entry(e.target()).or_default().push(q);
+            }
+        }
+        let mut reached = targets.clone();
+        let mut queue: VecDeque<_> = targets.iter().cloned().collect();
+        while let Some(q) = queue.pop_front() {
+            for p in predecessors.get(&q).into_iter().flatten() {
+                if reached.insert(*p) {
+                    queue.push_back(*p);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Returns the states from which an accepting state is reachable, where states of a DFA
+    /// are accepting if they are colored with `true`.
+    fn productive_states(&self) -> Set<Self::StateIndex>
+    where
+        Self: TransitionSystem<StateColor = bool>,
+    {
+        let accepting = self
+            .state_indices()
+            .filter(|q| self.state_color(*q) == Some(true))
+            .collect();
+        self.coreachable(&accepting)
+    }
+
+    /// Returns the states from which a cycle with an accepting edge is reachable, where edges
+    /// are accepting if they are colored with `true` as in a DBA.
+    fn productive_states_buchi(&self) -> Set<Self::StateIndex>
+    where
+        Self: TransitionSystem<EdgeColor = bool>,
+    {
+        self.coreachable(&sources_of_good_cycles(self, |_| true, |c| *c))
+    }
+
+    /// Returns the states from which a cycle is reachable whose least priority is even, i.e.
+    /// an accepting cycle of a DPA.
+    fn productive_states_parity(&self) -> Set<Self::StateIndex>
+    where
+        Self: TransitionSystem<EdgeColor = usize>,
+    {
+        // a cycle with least priority k exists if an edge with priority k lies on a cycle
+        // that only uses edges with priority at least k
+        let priorities = self
+            .state_indices()
+            .flat_map(|q| {
+                self.edges_from(q)
+                    .expect("State must exist")
+                    .map(|e| e.color())
+                    .collect_vec()
+            })
+            .filter(|k| k % 2 == 0)
+            .unique()
+            .collect_vec();
+        let mut sources = Set::default();
+        for k in priorities {
+            sources.extend(sources_of_good_cycles(self, |c| *c >= k, |c| *c == k));
+        }
+        self.coreachable(&sources)
+    }
+
+    /// Returns a copy that only contains the states in `productive`, which is usually
+    /// computed by one of the methods above, together with the new index of each kept state.
+    /// States keep their order and colors, and only edges between kept states remain.
+    #[allow(clippy::type_complexity)]
+    fn remove_dead(
+        &self,
+        productive: &Set<Self::StateIndex>,
+    ) -> (
+        NTS<CharAlphabet, Self::StateColor, Self::EdgeColor>,
+        Map<Self::StateIndex, usize>,
+    ) {
+        let mut ts = NTS::new_for_alphabet(self.alphabet().clone());
+        let index: Map<_, _> = self
+            .state_indices()
+            .filter(|q| productive.contains(q))
+            .sorted()
+            .map(|q| {
+                let color = self.state_color(q).expect("State must exist");
+                (q, ts.add_state(color))
+            })
+            .collect();
+        for (q, source) in index.iter().sorted_by_key(|(_, source)| **source) {
+            for e in self.edges_from(*q).expect("State must exist") {
+                if let Some(target) = index.get(&e.target()) {
+                    ts.add_edge(*source, *e.expression(), *target, e.color());
+                }
+            }
+        }
+        (ts, index)
+    }
+}
+
+impl<Ts: TransitionSystem<Alphabet = CharAlphabet>> Productive for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, Set};
+
+    use super::Productive;
+
+    #[test]
+    fn productive_and_dead_states() {
+        // q2 is a rejecting sink, so it is dead
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let q2 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q2, Void);
+        dfa.add_edge(q1, 'a', q1, Void);
+        dfa.add_edge(q1, 'b', q2, Void);
+        dfa.add_edge(q2, 'a', q2, Void);
+        dfa.add_edge(q2, 'b', q2, Void);
+        let productive = dfa.productive_states();
+        assert_eq!(productive, Set::from_iter([q0, q1]));
+        let (trimmed, index) = dfa.remove_dead(&productive);
+        assert_eq!(trimmed.size(), 2);
+        assert!(!index.contains_key(&q2));
+        assert_eq!(trimmed.edges_from(index[&q1]).unwrap().count(), 1);
+
+        // infinitely many a's, but only before the first b
+        let mut dba = DBA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let p0 = dba.add_state(Void);
+        let p1 = dba.add_state(Void);
+        dba.add_edge(p0, 'a', p0, true);
+        dba.add_edge(p0, 'b', p1, true);
+        dba.add_edge(p1, 'a', p1, false);
+        dba.add_edge(p1, 'b', p1, false);
+        assert_eq!(dba.productive_states_buchi(), Set::from_iter([p0]));
+
+        // the cycle through both states has least priority 1, only the loop on r1 is accepting
+        let dpa: DPA = NTS::builder()
+            .with_transitions([
+                (0, 'a', 1, 1),
+                (0, 'b', 3, 0),
+                (1, 'a', 2, 1),
+                (1, 'b', 3, 0),
+            ])
+            .into_dpa(0);
+        assert_eq!(dpa.productive_states_parity(), Set::from_iter([0, 1]));
+
+        // all priorities are odd
+        let dpa: DPA = NTS::builder()
+            .with_transitions([
+                (0, 'a', 1, 1),
+                (0, 'b', 3, 0),
+                (1, 'a', 3, 1),
+                (1, 'b', 1, 0),
+            ])
+            .into_dpa(0);
+        assert_eq!(dpa.productive_states_parity(), Set::default());
+    }
+}
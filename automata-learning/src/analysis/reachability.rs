@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, Set};
+
+/// A finite run given by the source state and the symbol of each edge it takes.
+pub(crate) type Run<Idx> = Vec<(Idx, char)>;
+
+/// Finds a shortest run leading from `source` to `target` by breadth-first search, where the
+/// empty run is only returned if `source` and `target` coincide.
+pub(crate) fn shortest_run<Ts: TransitionSystem<Alphabet = CharAlphabet>>(
+    ts: &Ts,
+    source: Ts::StateIndex,
+    target: Ts::StateIndex,
+) -> Option<Run<Ts::StateIndex>> {
+    let mut parent: Map<Ts::StateIndex, (Ts::StateIndex, char)> = Map::default();
+    let mut queue = VecDeque::from([source]);
+    while let Some(q) = queue.pop_front() {
+        if q == target {
+            let mut run = vec![];
+            let mut current = q;
+            while current != source {
+                let (p, sym) = parent[&current];
+                run.push((p, sym));
+                current = p;
+            }
+            run.reverse();
+            return Some(run);
+        }
+        for e in ts.edges_from(q).expect("State must exist") {
+            let p = e.target();
+            if p != source && !parent.contains_key(&p) {
+                parent.insert(p, (q, *e.expression()));
+                queue.push_back(p);
+            }
+        }
+    }
+    None
+}
+
+/// Reachability between arbitrary states, in contrast to `reachable_state_indices`, which only
+/// considers the initial state and gives no witnesses.
+pub trait Reachability: TransitionSystem<Alphabet = CharAlphabet> {
+    /// Returns a shortest word that leads from `from` to `to`, which is empty if the two
+    /// coincide, or `None` if `to` is not reachable.
+    fn reach_witness(&self, from: Self::StateIndex, to: Self::StateIndex) -> Option<Vec<char>> {
+        shortest_run(self, from, to).map(|run| run.into_iter().map(|(_, sym)| sym).collect())
+    }
+
+    /// Returns true if `to` is reachable from `from`.
+    fn is_reachable(&self, from: Self::StateIndex, to: Self::StateIndex) -> bool {
+        shortest_run(self, from, to).is_some()
+    }
+
+    /// Returns the states that are reachable from some state in `sources`, including the
+    /// sources themselves.
+    fn reachable_from<I: IntoIterator<Item = Self::StateIndex>>(
+        &self,
+        sources: I,
+    ) -> Set<Self::StateIndex> {
+        let mut reached: Set<_> = sources.into_iter().collect();
+        let mut queue: VecDeque<_> = reached.iter().cloned().collect();
+        while let Some(q) = queue.pop_front() {
+            for e in self.edges_from(q).expect("State must exist") {
+                if reached.insert(e.target()) {
+                    queue.push_back(e.target());
+                }
+            }
+        }
+        reached
+    }
+}
+
+impl<Ts: TransitionSystem<Alphabet = CharAlphabet>> Reachability for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, Set};
+
+    use super::Reachability;
+
+    #[test]
+    fn reachability_witnesses() {
+        // a chain q0 -a-> q1 -b-> q2 with a loop on q2 and an isolated state q3
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        let q3 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'b', q2, Void);
+        dfa.add_edge(q2, 'a', q2, Void);
+
+        assert_eq!(dfa.reach_witness(q0, q2), Some(vec!['a', 'b']));
+        assert_eq!(dfa.reach_witness(q1, q1), Some(vec![]));
+        assert!(dfa.is_reachable(q1, q2));
+        assert!(!dfa.is_reachable(q2, q0));
+        assert!(!dfa.is_reachable(q0, q3));
+        assert_eq!(dfa.reachable_from([q1]), Set::from_iter([q1, q2]));
+        assert_eq!(dfa.reachable_from([q2, q3]), Set::from_iter([q2, q3]));
+    }
+}
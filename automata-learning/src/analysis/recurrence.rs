@@ -0,0 +1,75 @@
+use automata::{prelude::*, Map, Set};
+
+/// Whether a state can be visited infinitely often by a run, see
+/// [`Recurrence::classify_states`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StateClass {
+    /// The state lies on no cycle, so every run visits it at most once.
+    Transient,
+    /// The state lies in a non-trivial strongly connected component, i.e. on some cycle.
+    Recurrent,
+}
+
+/// Classification of states into recurrent and transient ones based on the decomposition into
+/// strongly connected components. A component is trivial if it consists of a single state
+/// without a self-loop. Only the states in non-trivial components can occur in the infinity
+/// set of a run, so most acceptance conditions only need to look at those.
+pub trait Recurrence: TransitionSystem + Sized {
+    /// Returns the states that lie in a non-trivial strongly connected component.
+    fn recurrent_states(&self) -> Set<Self::StateIndex> {
+        self.sccs()
+            .iter()
+            .filter(|scc| !scc.is_transient())
+            .flat_map(|scc| scc.clone().into_iter())
+            .collect()
+    }
+
+    /// Returns the states that do not lie on any cycle.
+    fn transient_states(&self) -> Set<Self::StateIndex> {
+        let recurrent = self.recurrent_states();
+        self.state_indices()
+            .filter(|q| !recurrent.contains(q))
+            .collect()
+    }
+
+    /// Returns the [`StateClass`] of every state.
+    fn classify_states(&self) -> Map<Self::StateIndex, StateClass> {
+        let recurrent = self.recurrent_states();
+        self.state_indices()
+            .map(|q| {
+                let class = if recurrent.contains(&q) {
+                    StateClass::Recurrent
+                } else {
+                    StateClass::Transient
+                };
+                (q, class)
+            })
+            .collect()
+    }
+}
+
+impl<Ts: TransitionSystem + Sized> Recurrence for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, Set};
+
+    use super::{Recurrence, StateClass};
+
+    #[test]
+    fn recurrent_and_transient() {
+        // q0 -> q1 <-> q2 -> q3, where only q3 has a self-loop
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let [q0, q1, q2, q3] = [(); 4].map(|_| dfa.add_state(false));
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q1, 'a', q2, Void);
+        dfa.add_edge(q2, 'a', q1, Void);
+        dfa.add_edge(q2, 'b', q3, Void);
+        dfa.add_edge(q3, 'a', q3, Void);
+        assert_eq!(dfa.recurrent_states(), Set::from_iter([q1, q2, q3]));
+        assert_eq!(dfa.transient_states(), Set::from_iter([q0]));
+        let classes = dfa.classify_states();
+        assert_eq!(classes[&q0], StateClass::Transient);
+        assert_eq!(classes[&q3], StateClass::Recurrent);
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+
+/// Returns the successor of `q` on `sym` together with its color and the color of the edge,
+/// if there is an edge.
+#[allow(clippy::type_complexity)]
+fn output_step<D: Deterministic<Alphabet = CharAlphabet>>(
+    ts: &D,
+    q: D::StateIndex,
+    sym: char,
+) -> Option<(D::StateIndex, D::StateColor, D::EdgeColor)> {
+    let e = ts
+        .edges_from(q)
+        .expect("State must exist")
+        .find(|e| *e.expression() == sym)?;
+    let target = e.target();
+    Some((target, ts.state_color(target)?, e.color()))
+}
+
+/// Distinguishing experiments between the states of deterministic transition systems. The
+/// output of a word from a state is the color of the state that is reached together with the
+/// color of the last edge, so this covers DFAs and Moore machines, where states are colored,
+/// as well as Mealy machines, where edges are colored. A word on which the run from one state
+/// gets stuck but the run from the other does not separates the two states as well.
+pub trait Separation: Deterministic<Alphabet = CharAlphabet> {
+    /// Returns a length-lexicographically least word that separates `p` and `q`, or `None` if
+    /// the two states are equivalent. The empty word separates states of different colors.
+    fn separating_word(&self, p: Self::StateIndex, q: Self::StateIndex) -> Option<Vec<char>> {
+        if self.state_color(p) != self.state_color(q) {
+            return Some(vec![]);
+        }
+        let symbols = self.alphabet().universe().sorted().collect_vec();
+        let mut seen: Set<_> = [(p, q)].into_iter().collect();
+        let mut queue = VecDeque::from([((p, q), vec![])]);
+        while let Some(((p, q), word)) = queue.pop_front() {
+            for sym in &symbols {
+                let mut next = word.clone();
+                next.push(*sym);
+                match (output_step(self, p, *sym), output_step(self, q, *sym)) {
+                    (None, None) => {}
+                    (Some((p, pc, pe)), Some((q, qc, qe))) if pc == qc && pe == qe => {
+                        if seen.insert((p, q)) {
+                            queue.push_back(((p, q), next));
+                        }
+                    }
+                    _ => return Some(next),
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns a separating word, as given by [`Self::separating_word`], for every pair of
+    /// distinct states that can be separated. Pairs are ordered, i.e. the first state is the
+    /// smaller one.
+    fn pairwise_separators(&self) -> Map<(Self::StateIndex, Self::StateIndex), Vec<char>> {
+        let states = self.state_indices().sorted().collect_vec();
+        states
+            .iter()
+            .tuple_combinations()
+            .filter_map(|(p, q)| Some(((*p, *q), self.separating_word(*p, *q)?)))
+            .collect()
+    }
+
+    /// Returns a characterizing set, also called W-set, i.e. a set of words such that any two
+    /// states that are not equivalent produce different outputs on one of them. It consists
+    /// of the pairwise separators and is sorted length-lexicographically.
+    fn characterizing_set(&self) -> Vec<Vec<char>> {
+        self.pairwise_separators()
+            .into_values()
+            .unique()
+            .sorted_by(|u, v| u.len().cmp(&v.len()).then_with(|| u.cmp(v)))
+            .collect()
+    }
+}
+
+impl<D: Deterministic<Alphabet = CharAlphabet>> Separation for D {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::Separation;
+
+    #[test]
+    fn separate_states() {
+        // counts a's modulo three, where only state 0 is accepting
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let states = [true, false, false].map(|accepting| dfa.add_state(accepting));
+        for i in 0..3 {
+            dfa.add_edge(states[i], 'a', states[(i + 1) % 3], Void);
+            dfa.add_edge(states[i], 'b', states[i], Void);
+        }
+        assert_eq!(dfa.separating_word(states[0], states[1]), Some(vec![]));
+        assert_eq!(dfa.separating_word(states[1], states[2]), Some(vec!['a']));
+        assert_eq!(dfa.separating_word(states[1], states[1]), None);
+        assert_eq!(dfa.pairwise_separators().len(), 3);
+        assert_eq!(dfa.characterizing_set(), vec![vec![], vec!['a']]);
+
+        // a Mealy machine whose states only differ in the output on a
+        let mm = NTS::builder()
+            .with_transitions([
+                (0, 'a', 0, 1),
+                (0, 'b', 0, 0),
+                (1, 'a', 1, 0),
+                (1, 'b', 0, 1),
+            ])
+            .deterministic()
+            .with_initial(0)
+            .into_mealy();
+        assert_eq!(mm.separating_word(0, 1), Some(vec!['a']));
+        assert_eq!(mm.characterizing_set(), vec![vec!['a']]);
+    }
+}
@@ -0,0 +1,401 @@
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+use tracing::trace;
+
+/// Represents a simulation preorder on the states of a transition system. A pair `(p, q)`
+/// is contained in the relation if `q` simulates `p`, meaning every move that `p` can make
+/// can be matched by `q` (reading the same expression and emitting the same edge color)
+/// such that the reached pair is again in the relation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationRelation<Idx: IndexType> {
+    pairs: Set<(Idx, Idx)>,
+}
+
+impl<Idx: IndexType> SimulationRelation<Idx> {
+    /// Returns `true` if `p` is simulated by `q`, i.e. if `(p, q)` is in the relation.
+    pub fn simulates(&self, p: Idx, q: Idx) -> bool {
+        self.pairs.contains(&(p, q))
+    }
+
+    /// Returns `true` if `p` and `q` simulate each other.
+    pub fn equivalent(&self, p: Idx, q: Idx) -> bool {
+        self.simulates(p, q) && self.simulates(q, p)
+    }
+
+    /// Gives an iterator over all pairs `(p, q)` such that `q` simulates `p`.
+    pub fn pairs(&self) -> impl Iterator<Item = &(Idx, Idx)> + '_ {
+        self.pairs.iter()
+    }
+
+    /// Returns the number of pairs in the relation.
+    pub fn size(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Partitions the given states into classes of simulation equivalent states. The classes
+    /// are ordered by their least element and each class is sorted.
+    pub fn equivalence_classes<I: IntoIterator<Item = Idx>>(&self, states: I) -> Vec<Vec<Idx>> {
+        let mut classes: Vec<Vec<Idx>> = vec![];
+        for q in states.into_iter().sorted() {
+            match classes
+                .iter_mut()
+                .find(|class| self.equivalent(class[0], q))
+            {
+                Some(class) => class.push(q),
+                None => classes.push(vec![q]),
+            }
+        }
+        classes
+    }
+}
+
+/// Computes the greatest fixpoint of the simulation refinement. We start with all pairs of
+/// states that have the same color and remove pairs `(p, q)` for which some edge leaving `p`
+/// cannot be matched by an edge leaving `q`. The edges are given as a map from each state to a
+/// list of (expression, color, target) triples, which allows us to use the same routine for
+/// forward and backward simulation.
+///
+/// For every pair `(p, q)` and every edge of `p`, we count the edges of `q` that match it. When
+/// a pair is removed, only the counters of the edges that enter it are decremented, and a pair
+/// whose counter drops to zero is put on the worklist. Hence pairs are never rescanned.
+fn refine_simulation<Idx, E, C, Q>(
+    states: &[Idx],
+    state_colors: &Map<Idx, Q>,
+    edges: &Map<Idx, Vec<(E, C, Idx)>>,
+) -> Set<(Idx, Idx)>
+where
+    Idx: IndexType,
+    E: Eq,
+    C: Eq,
+    Q: Eq,
+{
+    let mut relation: Set<(Idx, Idx)> = states
+        .iter()
+        .cartesian_product(states.iter())
+        .filter(|(p, q)| state_colors.get(p) == state_colors.get(q))
+        .map(|(p, q)| (*p, *q))
+        .collect();
+
+    let empty = vec![];
+    let edges_of = |q: &Idx| edges.get(q).unwrap_or(&empty);
+    // the edges entering each state, given by their source and position in its list of edges
+    let mut incoming: Map<Idx, Vec<(Idx, usize)>> = Map::default();
+    for (q, outgoing) in edges {
+        for (i, (_, _, target)) in outgoing.iter().enumerate() {
+            incoming.entry(*target).or_default().push((*q, i));
+        }
+    }
+
+    // maps (p, i, q) to the number of edges of q that match the i-th edge of p
+    let mut counts: Map<(Idx, usize, Idx), usize> = Map::default();
+    for (p, q) in &relation {
+        for (i, (e, c, p_target)) in edges_of(p).iter().enumerate() {
+            let count = edges_of(q)
+                .iter()
+                .filter(|(f, d, q_target)| {
+                    e == f && c == d && relation.contains(&(*p_target, *q_target))
+                })
+                .count();
+            counts.insert((*p, i, *q), count);
+        }
+    }
+    let mut worklist = vec![];
+    for ((p, _, q), count) in &counts {
+        if *count == 0 && relation.remove(&(*p, *q)) {
+            worklist.push((*p, *q));
+        }
+    }
+
+    let no_incoming = vec![];
+    while let Some((p_target, q_target)) = worklist.pop() {
+        trace!(
+            "Removing pair {:?} from simulation relation",
+            (p_target, q_target)
+        );
+        for (p, i) in incoming.get(&p_target).unwrap_or(&no_incoming) {
+            let (e, c, _) = &edges_of(p)[*i];
+            for (q, j) in incoming.get(&q_target).unwrap_or(&no_incoming) {
+                let (f, d, _) = &edges_of(q)[*j];
+                if e != f || c != d || !relation.contains(&(*p, *q)) {
+                    continue;
+                }
+                let count = counts
+                    .get_mut(&(*p, *i, *q))
+                    .expect("Every pair in the relation has counters");
+                *count -= 1;
+                if *count == 0 {
+                    relation.remove(&(*p, *q));
+                    worklist.push((*p, *q));
+                }
+            }
+        }
+    }
+    relation
+}
+
+/// Computes simulation relations on transition systems and allows reducing a transition system
+/// by quotienting with simulation equivalence. Both state and edge colors are taken into
+/// account, i.e. a state can only simulate another state with the same color and edges can
+/// only be matched by edges with the same expression and color. This is mainly useful for
+/// shrinking nondeterministic automata before they are determinized.
+pub trait Simulation: TransitionSystem {
+    /// Computes the forward simulation preorder, where `q` simulates `p` if every edge
+    /// leaving `p` can be matched by an edge leaving `q`.
+    fn forward_simulation(&self) -> SimulationRelation<Self::StateIndex>;
+
+    /// Computes the backward simulation preorder, where `q` simulates `p` if every edge
+    /// entering `p` can be matched by an edge entering `q`. If the transition system has
+    /// an initial state, then only the initial state can simulate the initial state.
+    fn backward_simulation(&self) -> SimulationRelation<Self::StateIndex>;
+
+    /// Returns `true` if `p` is (forward) simulated by `q`. Note that this recomputes the
+    /// simulation relation, if multiple queries are made, [`Simulation::forward_simulation`]
+    /// should be used.
+    fn simulates(&self, p: Self::StateIndex, q: Self::StateIndex) -> bool {
+        self.forward_simulation().simulates(p, q)
+    }
+
+    /// Quotients `self` by forward simulation equivalence. Returns the reduced transition system,
+    /// whose initial state is the class of the initial state of `self`, together with a map that
+    /// associates each state of `self` with the state of the quotient that it was merged into.
+    #[allow(clippy::type_complexity)]
+    fn simulation_reduce(
+        &self,
+    ) -> (
+        impl TransitionSystem<
+                Alphabet = Self::Alphabet,
+                StateIndex = usize,
+                StateColor = Self::StateColor,
+                EdgeColor = Self::EdgeColor,
+            > + Pointed,
+        Map<Self::StateIndex, usize>,
+    )
+    where
+        Self: Pointed;
+}
+
+impl<Ts: TransitionSystem> Simulation for Ts {
+    fn forward_simulation(&self) -> SimulationRelation<Self::StateIndex> {
+        let states = self.state_indices().collect_vec();
+        let state_colors: Map<_, _> = states.iter().map(|q| (*q, self.state_color(*q))).collect();
+        let edges: Map<_, Vec<_>> = states
+            .iter()
+            .map(|q| {
+                (
+                    *q,
+                    self.edges_from(*q)
+                        .expect("State must exist")
+                        .map(|e| (e.expression().clone(), e.color(), e.target()))
+                        .collect(),
+                )
+            })
+            .collect();
+
+        SimulationRelation {
+            pairs: refine_simulation(&states, &state_colors, &edges),
+        }
+    }
+
+    fn backward_simulation(&self) -> SimulationRelation<Self::StateIndex> {
+        let states = self.state_indices().collect_vec();
+        let initial = self.maybe_initial_state();
+        // we mix the information whether a state is initial into the color, which ensures
+        // that the initial state can only be simulated by itself
+        let state_colors: Map<_, _> = states
+            .iter()
+            .map(|q| (*q, (Some(*q) == initial, self.state_color(*q))))
+            .collect();
+        let mut edges: Map<_, Vec<_>> = states.iter().map(|q| (*q, vec![])).collect();
+        for q in &states {
+            for e in self.edges_from(*q).expect("State must exist") {
+                edges.entry(e.target()).or_default().push((
+                    e.expression().clone(),
+                    e.color(),
+                    e.source(),
+                ));
+            }
+        }
+
+        SimulationRelation {
+            pairs: refine_simulation(&states, &state_colors, &edges),
+        }
+    }
+
+    fn simulation_reduce(
+        &self,
+    ) -> (
+        impl TransitionSystem<
+                Alphabet = Self::Alphabet,
+                StateIndex = usize,
+                StateColor = Self::StateColor,
+                EdgeColor = Self::EdgeColor,
+            > + Pointed,
+        Map<Self::StateIndex, usize>,
+    )
+    where
+        Self: Pointed,
+    {
+        let relation = self.forward_simulation();
+        let classes = relation.equivalence_classes(self.state_indices());
+
+        let mut ts = NTS::new_for_alphabet(self.alphabet().clone());
+        let mut map = Map::default();
+        for class in &classes {
+            let color = self
+                .state_color(class[0])
+                .expect("Every state must have a color");
+            let idx = ts.add_state(color);
+            map.extend(class.iter().map(|q| (*q, idx)));
+        }
+
+        let mut added = Set::default();
+        for class in &classes {
+            let source = map[&class[0]];
+            for e in self.edges_from(class[0]).expect("State must exist") {
+                let target = map[&e.target()];
+                if added.insert((source, e.expression().clone(), e.color(), target)) {
+                    ts.add_edge(source, e.expression().clone(), target, e.color());
+                }
+            }
+        }
+
+        trace!(
+            "Simulation reduction shrunk {} states to {}",
+            self.size(),
+            classes.len()
+        );
+        let initial = map[&self.initial()];
+        (ts.with_initial(initial), map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+    use itertools::Itertools;
+
+    use super::Simulation;
+
+    #[test]
+    fn forward_simulation_nba() {
+        // state 1 and 2 both loop on a and b forever, they are thus simulation equivalent
+        let mut nba = NTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = nba.add_state(false);
+        let q1 = nba.add_state(true);
+        let q2 = nba.add_state(true);
+        let q3 = nba.add_state(false);
+        let q4 = nba.add_state(true);
+        nba.add_edge(q0, 'a', q1, Void);
+        nba.add_edge(q0, 'a', q2, Void);
+        nba.add_edge(q0, 'b', q3, Void);
+        nba.add_edge(q1, 'a', q1, Void);
+        nba.add_edge(q1, 'b', q1, Void);
+        nba.add_edge(q2, 'a', q2, Void);
+        nba.add_edge(q2, 'b', q2, Void);
+        nba.add_edge(q3, 'b', q3, Void);
+        nba.add_edge(q4, 'a', q4, Void);
+
+        let nba = nba.with_initial(q0);
+        let sim = nba.forward_simulation();
+        assert!(sim.equivalent(q1, q2));
+        // states of the same color that only differ in their successors: q3 cannot match the
+        // a-edges of q0 and q4 cannot match the b-loop of q1, but not vice versa
+        assert!(!sim.simulates(q0, q3));
+        assert!(sim.simulates(q3, q0));
+        assert!(!sim.simulates(q1, q4));
+        assert!(sim.simulates(q4, q1));
+        assert!(nba.simulates(q3, q3));
+
+        let (reduced, map) = nba.simulation_reduce();
+        assert_eq!(reduced.size(), 4);
+        assert_eq!(map[&q1], map[&q2]);
+        assert_eq!(reduced.initial(), map[&q0]);
+    }
+
+    #[test]
+    fn backward_simulation() {
+        let mut ts = NTS::new_for_alphabet(alphabet!(simple 'a'));
+        let q0 = ts.add_state(false);
+        let q1 = ts.add_state(false);
+        ts.add_edge(q0, 'a', q1, Void);
+        ts.add_edge(q1, 'a', q1, Void);
+
+        let sim = ts.backward_simulation();
+        assert!(sim.simulates(q1, q1));
+        assert!(!sim.simulates(q1, q0));
+    }
+
+    /// Returns `true` if the state colored NFA `ts` accepts `word` when started in `q`.
+    fn accepts<Ts>(ts: &Ts, q: Ts::StateIndex, word: &[char]) -> bool
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet, StateColor = bool>,
+    {
+        let mut current = vec![q];
+        for sym in word {
+            current = current
+                .into_iter()
+                .flat_map(|p| {
+                    ts.edges_from(p)
+                        .expect("State must exist")
+                        .filter(|e| e.expression() == sym)
+                        .map(|e| e.target())
+                        .collect_vec()
+                })
+                .unique()
+                .collect();
+        }
+        current.into_iter().any(|p| ts.state_color(p) == Some(true))
+    }
+
+    #[test]
+    fn simulation_reduce_preserves_language() {
+        // q1 and q2 simulate each other, but only q2 has an a-edge to y. As x simulates y, the
+        // edge can be dropped when q1 is chosen as representative of the class.
+        let mut nfa = NTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let x = nfa.add_state(true);
+        let y = nfa.add_state(true);
+        nfa.add_edge(q0, 'a', q1, Void);
+        nfa.add_edge(q0, 'b', q2, Void);
+        nfa.add_edge(q1, 'a', x, Void);
+        nfa.add_edge(q2, 'a', x, Void);
+        nfa.add_edge(q2, 'a', y, Void);
+        nfa.add_edge(x, 'a', x, Void);
+        nfa.add_edge(x, 'b', x, Void);
+        nfa.add_edge(y, 'a', y, Void);
+
+        let nfa = nfa.with_initial(q0);
+        let sim = nfa.forward_simulation();
+        assert!(sim.equivalent(q1, q2));
+        assert!(sim.simulates(y, x));
+        assert!(!sim.simulates(x, y));
+
+        let (reduced, map) = nfa.simulation_reduce();
+        assert_eq!(reduced.size(), 4);
+        assert_eq!(map[&q1], map[&q2]);
+        assert_eq!(reduced.edges_from(map[&q2]).unwrap().count(), 1);
+        assert_eq!(reduced.initial(), map[&q0]);
+
+        let mut words: Vec<Vec<char>> = vec![vec![]];
+        for len in 0..4 {
+            let longer = words
+                .iter()
+                .filter(|w| w.len() == len)
+                .flat_map(|w| ['a', 'b'].map(|sym| [w.as_slice(), &[sym]].concat()))
+                .collect_vec();
+            words.extend(longer);
+        }
+        for q in [q0, q1, q2, x, y] {
+            for word in &words {
+                assert_eq!(
+                    accepts(&nfa, q, word),
+                    accepts(&reduced, map[&q], word),
+                    "language of {q:?} differs on {}",
+                    word.iter().collect::<String>()
+                );
+            }
+        }
+    }
+}
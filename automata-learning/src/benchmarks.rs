@@ -0,0 +1,291 @@
+//! Loading of benchmark corpora, such as the HOA sets used for evaluating `autfilt` and Owl or
+//! the samples of the Abbadingo and StaMinA competitions. The corpora are not shipped with this
+//! crate and nothing is downloaded, instead they are expected to be fetched once and stored
+//! locally. A [`Corpus`] points to a directory containing such files, which is searched
+//! recursively. If no directory is given, the environment variable `LAMA_BENCHMARKS` is used.
+//!
+//! Files are always visited in lexicographic order of their paths, so iterating over a corpus
+//! gives the same sequence of instances on every machine.
+
+use std::path::{Path, PathBuf};
+
+use automata::{automaton::DeterministicOmegaAutomaton, hoa::HoaAlphabet, prelude::*};
+use itertools::Itertools;
+use tracing::{debug, warn};
+
+use crate::{formats::BaAutomaton, passive::FiniteSample};
+
+/// The environment variable that is used as the root directory of the default corpus.
+pub const BENCHMARKS_VAR: &str = "LAMA_BENCHMARKS";
+
+/// Represents the errors that can occur while loading benchmarks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BenchmarkError {
+    /// No directory was given and `LAMA_BENCHMARKS` is not set.
+    NoRoot,
+    /// A file or directory could not be read.
+    Io(PathBuf, String),
+    /// A file could not be parsed.
+    Parse(PathBuf, String),
+}
+
+impl std::fmt::Display for BenchmarkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BenchmarkError::NoRoot => {
+                write!(
+                    f,
+                    "No benchmark directory given and {BENCHMARKS_VAR} is not set"
+                )
+            }
+            BenchmarkError::Io(path, err) => write!(f, "Could not read {}: {err}", path.display()),
+            BenchmarkError::Parse(path, err) => {
+                write!(f, "Could not parse {}: {err}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BenchmarkError {}
+
+/// A single instance of a benchmark corpus.
+#[derive(Debug, Clone)]
+pub struct BenchmarkInstance<T> {
+    /// The name of the instance, which is the path relative to the root of the corpus. If a
+    /// file contains multiple instances, the position in the file is appended as `#i`.
+    pub name: String,
+    /// The file from which the instance was loaded.
+    pub path: PathBuf,
+    /// The loaded instance.
+    pub instance: T,
+}
+
+/// A directory containing benchmark files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corpus {
+    root: PathBuf,
+}
+
+impl Corpus {
+    /// Creates a corpus for the files below `root`.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Creates a corpus for the directory given by the `LAMA_BENCHMARKS` environment variable.
+    pub fn from_env() -> Result<Self, BenchmarkError> {
+        std::env::var_os(BENCHMARKS_VAR)
+            .map(Self::new)
+            .ok_or(BenchmarkError::NoRoot)
+    }
+
+    /// Returns the root directory of the corpus.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns a corpus for the subdirectory `dir` of this corpus, which is useful if multiple
+    /// suites are stored below the same root.
+    pub fn suite<P: AsRef<Path>>(&self, dir: P) -> Self {
+        Self::new(self.root.join(dir))
+    }
+
+    /// Collects all files below the root whose extension is one of `extensions`, sorted by
+    /// their path.
+    pub fn files(&self, extensions: &[&str]) -> Result<Vec<PathBuf>, BenchmarkError> {
+        let mut files = vec![];
+        let mut queue = vec![self.root.clone()];
+        while let Some(dir) = queue.pop() {
+            let entries = std::fs::read_dir(&dir)
+                .map_err(|err| BenchmarkError::Io(dir.clone(), err.to_string()))?;
+            for entry in entries {
+                let path = entry
+                    .map_err(|err| BenchmarkError::Io(dir.clone(), err.to_string()))?
+                    .path();
+                if path.is_dir() {
+                    queue.push(path);
+                } else if path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| extensions.contains(&ext))
+                {
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+        debug!(
+            "Found {} benchmark files with extensions {} in {}",
+            files.len(),
+            extensions.join(", "),
+            self.root.display()
+        );
+        Ok(files)
+    }
+
+    fn name(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
+    /// Loads every file with one of the given extensions using `load`, lazily and in order.
+    fn load<'a, T: 'a, F>(
+        &'a self,
+        extensions: &[&str],
+        load: F,
+    ) -> Result<
+        impl Iterator<Item = Result<BenchmarkInstance<T>, BenchmarkError>> + 'a,
+        BenchmarkError,
+    >
+    where
+        F: Fn(&str) -> Result<T, String> + 'a,
+    {
+        Ok(self.files(extensions)?.into_iter().map(move |path| {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|err| BenchmarkError::Io(path.clone(), err.to_string()))?;
+            let instance =
+                load(&content).map_err(|err| BenchmarkError::Parse(path.clone(), err))?;
+            Ok(BenchmarkInstance {
+                name: self.name(&path),
+                path,
+                instance,
+            })
+        }))
+    }
+
+    /// Iterates over the deterministic automata in all `.hoa` files. Files may contain
+    /// multiple automata, nondeterministic ones are skipped with a warning.
+    pub fn hoa_automata(
+        &self,
+    ) -> Result<
+        impl Iterator<
+                Item = Result<
+                    BenchmarkInstance<DeterministicOmegaAutomaton<HoaAlphabet>>,
+                    BenchmarkError,
+                >,
+            > + '_,
+        BenchmarkError,
+    > {
+        Ok(self.files(&["hoa"])?.into_iter().flat_map(move |path| {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(err) => return vec![Err(BenchmarkError::Io(path.clone(), err.to_string()))],
+            };
+            let parsed = automata::hoa::input::hoa_to_ts(&content);
+            let multiple = parsed.len() > 1;
+            parsed
+                .iter()
+                .enumerate()
+                .filter_map(|(i, aut)| {
+                    let name = if multiple {
+                        format!("{}#{i}", self.name(&path))
+                    } else {
+                        self.name(&path)
+                    };
+                    match aut.to_deterministic() {
+                        Some(instance) => Some(Ok(BenchmarkInstance {
+                            name,
+                            path: path.clone(),
+                            instance,
+                        })),
+                        None => {
+                            warn!("Skipping nondeterministic automaton {name}");
+                            None
+                        }
+                    }
+                })
+                .collect_vec()
+        }))
+    }
+
+    /// Iterates over the samples in all `.a` and `.abbadingo` files, which are expected to be
+    /// in the Abbadingo format.
+    pub fn abbadingo_samples(
+        &self,
+    ) -> Result<
+        impl Iterator<
+                Item = Result<BenchmarkInstance<FiniteSample<CharAlphabet, bool>>, BenchmarkError>,
+            > + '_,
+        BenchmarkError,
+    > {
+        self.load(&["a", "abbadingo"], |content| {
+            FiniteSample::from_abbadingo(content).map_err(|err| err.to_string())
+        })
+    }
+
+    /// Iterates over the samples in all `.stamina` files, which are expected to be in the
+    /// StaMinA format.
+    pub fn stamina_samples(
+        &self,
+    ) -> Result<
+        impl Iterator<
+                Item = Result<BenchmarkInstance<FiniteSample<CharAlphabet, bool>>, BenchmarkError>,
+            > + '_,
+        BenchmarkError,
+    > {
+        self.load(&["stamina"], |content| {
+            FiniteSample::from_stamina(content).map_err(|err| err.to_string())
+        })
+    }
+
+    /// Iterates over the automata in all `.ba` files, which are converted into NFAs.
+    pub fn ba_automata(
+        &self,
+    ) -> Result<
+        impl Iterator<
+                Item = Result<BenchmarkInstance<NTS<CharAlphabet, bool, Void>>, BenchmarkError>,
+            > + '_,
+        BenchmarkError,
+    > {
+        self.load(&["ba"], |content| {
+            BaAutomaton::try_from(content)
+                .map(|aut| aut.into_nfa())
+                .map_err(|err| err.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BenchmarkError, Corpus};
+
+    #[test]
+    fn load_benchmark_corpus() {
+        let root = std::env::temp_dir().join(format!("lama-benchmarks-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("suite")).unwrap();
+        std::fs::write(root.join("suite/b.a"), "2 2\n1 2 0 1\n0 1 1\n").unwrap();
+        std::fs::write(root.join("a.abbadingo"), "1 2\n1 1 0\n").unwrap();
+        std::fs::write(root.join("broken.a"), "not a sample").unwrap();
+        std::fs::write(root.join("ignored.txt"), "").unwrap();
+        std::fs::write(root.join("c.ba"), "[p]\na,[p]->[q]\n[q]\n").unwrap();
+
+        let corpus = Corpus::new(&root);
+        let samples = corpus.abbadingo_samples().unwrap().collect::<Vec<_>>();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].as_ref().unwrap().name, "a.abbadingo");
+        assert!(matches!(samples[1], Err(BenchmarkError::Parse(_, _))));
+        let suite = samples[2].as_ref().unwrap();
+        assert_eq!(suite.name, format!("suite{}b.a", std::path::MAIN_SEPARATOR));
+        assert_eq!(suite.instance.words.len(), 2);
+
+        assert_eq!(
+            corpus
+                .suite("suite")
+                .abbadingo_samples()
+                .unwrap()
+                .map(|instance| instance.unwrap().name)
+                .collect::<Vec<_>>(),
+            vec!["b.a"]
+        );
+        assert_eq!(corpus.ba_automata().unwrap().count(), 1);
+        assert!(matches!(
+            Corpus::new(root.join("missing")).files(&["hoa"]),
+            Err(BenchmarkError::Io(_, _))
+        ));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// A flag that can be shared with a running learner to stop it cooperatively. Clones refer to
+/// the same flag, so a token can be cancelled from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels all runs that use this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if [`Self::cancel`] was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The reason why a learner stopped before it finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Exhausted {
+    /// The deadline has passed.
+    Deadline,
+    /// The hypothesis exceeded the given number of states.
+    States(usize),
+    /// More than the given number of membership queries were posed.
+    Queries(usize),
+    /// More than the given number of equivalence queries were posed.
+    EquivalenceQueries(usize),
+    /// More than the given number of symbols were sent in queries.
+    Symbols(usize),
+    /// The run was cancelled via a [`CancellationToken`].
+    Cancelled,
+}
+
+impl std::fmt::Display for Exhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Exhausted::Deadline => write!(f, "Deadline has passed"),
+            Exhausted::States(max) => write!(f, "Hypothesis has more than {max} states"),
+            Exhausted::Queries(max) => write!(f, "More than {max} queries were posed"),
+            Exhausted::EquivalenceQueries(max) => {
+                write!(f, "More than {max} equivalence queries were posed")
+            }
+            Exhausted::Symbols(max) => write!(f, "More than {max} symbols were sent"),
+            Exhausted::Cancelled => write!(f, "Run was cancelled"),
+        }
+    }
+}
+
+/// Counts the queries that an active learner poses to its oracle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryCounts {
+    /// The number of membership or output queries.
+    pub membership: usize,
+    /// The number of equivalence queries.
+    pub equivalence: usize,
+    /// The total length of the words in membership queries.
+    pub symbols: usize,
+}
+
+impl QueryCounts {
+    /// Records a membership query for a word of the given length.
+    pub fn record_membership(&mut self, length: usize) {
+        self.membership += 1;
+        self.symbols += length;
+    }
+
+    /// Records an equivalence query.
+    pub fn record_equivalence(&mut self) {
+        self.equivalence += 1;
+    }
+}
+
+/// Returned by a learner whose [`Budget`] was exhausted, contains the best hypothesis that was
+/// found until then. For passive learners, this is usually a partial automaton.
+#[derive(Debug, Clone)]
+pub struct BudgetExceeded<H> {
+    /// The best hypothesis found so far.
+    pub best: H,
+    /// Why the learner stopped.
+    pub reason: Exhausted,
+}
+
+/// Limits the resources that a learning run may use. All limits are optional and the learners
+/// check them cooperatively at regular points, so a run may slightly overshoot a limit.
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    deadline: Option<Instant>,
+    max_states: Option<usize>,
+    max_queries: Option<usize>,
+    max_equivalence_queries: Option<usize>,
+    max_symbols: Option<usize>,
+    token: Option<CancellationToken>,
+}
+
+impl Budget {
+    /// Creates a budget without any limits.
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Stops the run at the given point in time.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stops the run once the given time has elapsed from now.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.deadline(Instant::now() + timeout)
+    }
+
+    /// Stops the run once the hypothesis has more than `max` states.
+    pub fn max_states(mut self, max: usize) -> Self {
+        self.max_states = Some(max);
+        self
+    }
+
+    /// Stops the run once more than `max` membership queries were posed.
+    pub fn max_queries(mut self, max: usize) -> Self {
+        self.max_queries = Some(max);
+        self
+    }
+
+    /// Stops the run once more than `max` equivalence queries were posed.
+    pub fn max_equivalence_queries(mut self, max: usize) -> Self {
+        self.max_equivalence_queries = Some(max);
+        self
+    }
+
+    /// Stops the run once more than `max` symbols were sent in membership queries.
+    pub fn max_symbols(mut self, max: usize) -> Self {
+        self.max_symbols = Some(max);
+        self
+    }
+
+    /// Stops the run once `token` is cancelled.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Checks whether a run with a hypothesis of size `states` that posed `queries` membership
+    /// queries may continue.
+    pub fn check(&self, states: usize, queries: usize) -> Result<(), Exhausted> {
+        if self.token.as_ref().is_some_and(|t| t.is_cancelled()) {
+            return Err(Exhausted::Cancelled);
+        }
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(Exhausted::Deadline);
+        }
+        if let Some(max) = self.max_states.filter(|max| states > *max) {
+            return Err(Exhausted::States(max));
+        }
+        if let Some(max) = self.max_queries.filter(|max| queries > *max) {
+            return Err(Exhausted::Queries(max));
+        }
+        Ok(())
+    }
+
+    /// Like [`Self::check`], but also checks the limits on equivalence queries and symbols.
+    pub fn check_queries(&self, states: usize, counts: &QueryCounts) -> Result<(), Exhausted> {
+        self.check(states, counts.membership)?;
+        if let Some(max) = self
+            .max_equivalence_queries
+            .filter(|max| counts.equivalence > *max)
+        {
+            return Err(Exhausted::EquivalenceQueries(max));
+        }
+        if let Some(max) = self.max_symbols.filter(|max| counts.symbols > *max) {
+            return Err(Exhausted::Symbols(max));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Budget, CancellationToken, Exhausted, QueryCounts};
+
+    #[test]
+    fn budget_limits() {
+        assert_eq!(Budget::unlimited().check(1000, 1000), Ok(()));
+
+        let budget = Budget::unlimited().max_states(3).max_queries(10);
+        assert_eq!(budget.check(3, 10), Ok(()));
+        assert_eq!(budget.check(4, 10), Err(Exhausted::States(3)));
+        assert_eq!(budget.check(3, 11), Err(Exhausted::Queries(10)));
+
+        let budget = Budget::unlimited()
+            .max_equivalence_queries(2)
+            .max_symbols(20);
+        let mut counts = QueryCounts::default();
+        counts.record_membership(20);
+        counts.record_equivalence();
+        counts.record_equivalence();
+        assert_eq!(budget.check_queries(0, &counts), Ok(()));
+        counts.record_equivalence();
+        assert_eq!(
+            budget.check_queries(0, &counts),
+            Err(Exhausted::EquivalenceQueries(2))
+        );
+        counts.equivalence = 0;
+        counts.record_membership(1);
+        assert_eq!(
+            budget.check_queries(0, &counts),
+            Err(Exhausted::Symbols(20))
+        );
+
+        let expired = Budget::unlimited().timeout(Duration::ZERO);
+        assert_eq!(expired.check(0, 0), Err(Exhausted::Deadline));
+
+        let token = CancellationToken::new();
+        let budget = Budget::unlimited().cancellation(token.clone());
+        assert_eq!(budget.check(0, 0), Ok(()));
+        token.cancel();
+        assert_eq!(budget.check(0, 0), Err(Exhausted::Cancelled));
+    }
+}
@@ -0,0 +1,466 @@
+//! A builder for deterministic automata whose states are referred to by name, so that small
+//! automata can be written down declaratively instead of keeping track of state indices. The
+//! builder collects states and transitions and only validates them in the final `build_*`
+//! call, which reports the first problem as a [`BuilderError`] that mentions the names of the
+//! states involved.
+//!
+//! ```ignore
+//! let even = AutomatonBuilder::dfa()
+//!     .state("even", true)
+//!     .state("odd", false)
+//!     .transition("even", 'a', "odd", Void)
+//!     .transition("odd", 'a', "even", Void)
+//!     .require_complete()
+//!     .build_dfa()?;
+//! ```
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+
+/// Abstracts the types of errors that can occur when building an automaton with an
+/// [`AutomatonBuilder`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum BuilderError {
+    NoStates,
+    DuplicateState(String),
+    UnknownState(String),
+    UnknownSymbol(char),
+    Nondeterministic(String, char),
+    Incomplete(String, char),
+}
+
+impl std::fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderError::NoStates => write!(f, "The automaton has no states"),
+            BuilderError::DuplicateState(name) => {
+                write!(f, "State \"{name}\" is declared more than once")
+            }
+            BuilderError::UnknownState(name) => {
+                write!(f, "State \"{name}\" is used but never declared")
+            }
+            BuilderError::UnknownSymbol(sym) => write!(f, "Symbol {sym} is not in the alphabet"),
+            BuilderError::Nondeterministic(name, sym) => {
+                write!(f, "State \"{name}\" has more than one transition on {sym}")
+            }
+            BuilderError::Incomplete(name, sym) => {
+                write!(f, "State \"{name}\" has no transition on {sym}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+/// Collects named states and transitions between them, see the module documentation. States
+/// are colored with `Q` and edges with `C`, and there are entry points for the common
+/// combinations, e.g. [`AutomatonBuilder::dfa`] and [`AutomatonBuilder::mealy`]. Unless
+/// [`Self::initial`] is called, the first declared state is initial.
+#[derive(Debug, Clone)]
+pub struct AutomatonBuilder<Q, C> {
+    alphabet: Option<CharAlphabet>,
+    states: Vec<(String, Q)>,
+    transitions: Vec<(String, char, String, C)>,
+    initial: Option<String>,
+    complete: bool,
+}
+
+impl<Q, C> Default for AutomatonBuilder<Q, C> {
+    fn default() -> Self {
+        Self {
+            alphabet: None,
+            states: vec![],
+            transitions: vec![],
+            initial: None,
+            complete: false,
+        }
+    }
+}
+
+impl<Q: Color, C: Color> AutomatonBuilder<Q, C> {
+    /// Creates a builder without states and transitions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fixes the alphabet, so that transitions on other symbols are rejected. Otherwise the
+    /// alphabet consists of the symbols that occur on transitions.
+    pub fn with_alphabet(mut self, alphabet: CharAlphabet) -> Self {
+        self.alphabet = Some(alphabet);
+        self
+    }
+
+    /// Fixes the alphabet to consist of the given symbols, see [`Self::with_alphabet`].
+    pub fn with_symbols<I: IntoIterator<Item = char>>(self, symbols: I) -> Self {
+        self.with_alphabet(CharAlphabet::from_iter(symbols))
+    }
+
+    /// Declares a state with the given name and color.
+    pub fn state<S: Into<String>>(mut self, name: S, color: Q) -> Self {
+        self.states.push((name.into(), color));
+        self
+    }
+
+    /// Makes the state with the given name initial.
+    pub fn initial<S: Into<String>>(mut self, name: S) -> Self {
+        self.initial = Some(name.into());
+        self
+    }
+
+    /// Adds a transition from `source` to `target` on `sym` with the given color.
+    pub fn transition<S: Into<String>, T: Into<String>>(
+        mut self,
+        source: S,
+        sym: char,
+        target: T,
+        color: C,
+    ) -> Self {
+        self.transitions
+            .push((source.into(), sym, target.into(), color));
+        self
+    }
+
+    /// Requires every state to have a transition on every symbol when building.
+    pub fn require_complete(mut self) -> Self {
+        self.complete = true;
+        self
+    }
+
+    /// Validates the declarations and builds the transition system, which has the states in
+    /// the order of their declaration. Returns it together with the initial state.
+    pub fn build(self) -> Result<NamedAutomaton<(DTS<CharAlphabet, Q, C>, usize)>, BuilderError> {
+        if self.states.is_empty() {
+            return Err(BuilderError::NoStates);
+        }
+        let mut index: Map<String, usize> = Map::default();
+        for (i, (name, _)) in self.states.iter().enumerate() {
+            if index.insert(name.clone(), i).is_some() {
+                return Err(BuilderError::DuplicateState(name.clone()));
+            }
+        }
+        let lookup = |name: &String| {
+            index
+                .get(name)
+                .cloned()
+                .ok_or_else(|| BuilderError::UnknownState(name.clone()))
+        };
+        let initial = match &self.initial {
+            Some(name) => lookup(name)?,
+            None => 0,
+        };
+
+        let alphabet = match self.alphabet {
+            Some(alphabet) => alphabet,
+            None => CharAlphabet::from_iter(
+                self.transitions
+                    .iter()
+                    .map(|(_, sym, _, _)| *sym)
+                    .sorted()
+                    .dedup(),
+            ),
+        };
+        let symbols = alphabet.universe().collect_vec();
+        let mut edges: Map<(usize, char), usize> = Map::default();
+        for (source, sym, target, _) in &self.transitions {
+            if !symbols.contains(sym) {
+                return Err(BuilderError::UnknownSymbol(*sym));
+            }
+            let (p, q) = (lookup(source)?, lookup(target)?);
+            if edges.insert((p, *sym), q).is_some() {
+                return Err(BuilderError::Nondeterministic(source.clone(), *sym));
+            }
+        }
+        if self.complete {
+            for ((name, _), q) in self.states.iter().zip(0..) {
+                if let Some(sym) = symbols.iter().find(|sym| !edges.contains_key(&(q, **sym))) {
+                    return Err(BuilderError::Incomplete(name.clone(), *sym));
+                }
+            }
+        }
+
+        let mut ts = DTS::new_for_alphabet(alphabet);
+        let mut names = vec![];
+        for (name, color) in self.states {
+            ts.add_state(color);
+            names.push(name);
+        }
+        for (source, sym, target, color) in self.transitions {
+            ts.add_edge(index[&source], sym, index[&target], color);
+        }
+        Ok(NamedAutomaton {
+            automaton: (ts, initial),
+            names,
+        })
+    }
+}
+
+impl<Q: Color> AutomatonBuilder<Q, Void> {
+    /// Adds an uncolored transition from `source` to `target` on `sym`.
+    pub fn edge<S: Into<String>, T: Into<String>>(self, source: S, sym: char, target: T) -> Self {
+        self.transition(source, sym, target, Void)
+    }
+}
+
+impl AutomatonBuilder<bool, Void> {
+    /// Creates a builder for a [`DFA`], whose states are colored with whether they accept.
+    pub fn dfa() -> Self {
+        Self::new()
+    }
+
+    /// Builds a [`DFA`], see [`Self::build`].
+    pub fn build_dfa(self) -> Result<NamedAutomaton<DFA>, BuilderError> {
+        Ok(self
+            .build()?
+            .map(|(ts, initial)| ts.with_initial(initial).into_dfa()))
+    }
+}
+
+impl<C: Color> AutomatonBuilder<Void, C> {
+    /// Creates a builder for a [`MealyMachine`], whose edges are colored with outputs.
+    pub fn mealy() -> Self {
+        Self::new()
+    }
+
+    /// Builds a [`MealyMachine`], see [`Self::build`].
+    pub fn build_mealy(
+        self,
+    ) -> Result<NamedAutomaton<MealyMachine<CharAlphabet, C>>, BuilderError> {
+        Ok(self
+            .build()?
+            .map(|(ts, initial)| ts.with_initial(initial).into_mealy()))
+    }
+}
+
+impl<Q: Color> AutomatonBuilder<Q, Void> {
+    /// Creates a builder for a [`MooreMachine`], whose states are colored with outputs.
+    pub fn moore() -> Self {
+        Self::new()
+    }
+
+    /// Builds a [`MooreMachine`], see [`Self::build`].
+    pub fn build_moore(
+        self,
+    ) -> Result<NamedAutomaton<MooreMachine<CharAlphabet, Q>>, BuilderError> {
+        Ok(self
+            .build()?
+            .map(|(ts, initial)| ts.with_initial(initial).into_moore()))
+    }
+}
+
+impl AutomatonBuilder<Void, bool> {
+    /// Creates a builder for a [`DBA`], whose edges are colored with whether they accept.
+    pub fn dba() -> Self {
+        Self::new()
+    }
+
+    /// Builds a [`DBA`], see [`Self::build`].
+    pub fn build_dba(self) -> Result<NamedAutomaton<DBA>, BuilderError> {
+        Ok(self
+            .build()?
+            .map(|(ts, initial)| ts.with_initial(initial).into_dba()))
+    }
+}
+
+/// An automaton built by an [`AutomatonBuilder`] together with the names of its states, where
+/// the state with index `i` has the `i`-th name.
+#[derive(Debug, Clone)]
+pub struct NamedAutomaton<A> {
+    /// The automaton.
+    pub automaton: A,
+    /// The name of each state.
+    pub names: Vec<String>,
+}
+
+impl<A> NamedAutomaton<A> {
+    /// Returns the index of the state with the given name.
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    /// Returns the name of the state with the given index.
+    pub fn name(&self, state: usize) -> Option<&str> {
+        self.names.get(state).map(|n| n.as_str())
+    }
+
+    fn map<B, F: FnOnce(A) -> B>(self, f: F) -> NamedAutomaton<B> {
+        NamedAutomaton {
+            automaton: f(self.automaton),
+            names: self.names,
+        }
+    }
+}
+
+/// Converts a token of the [`ts!`](crate::ts) macro, which is either a character literal or
+/// an identifier of length one, into the symbol it denotes.
+#[doc(hidden)]
+pub fn __symbol(token: &str) -> char {
+    let mut chars = token.trim_matches('\'').chars();
+    match (chars.next(), chars.next()) {
+        (Some(sym), None) => sym,
+        _ => panic!("Symbol {token} must be a single character"),
+    }
+}
+
+/// Declares a [`DFA`] state by state, which expands to calls of an [`AutomatonBuilder`]. Every
+/// state is followed by its color in brackets, which is prefixed with `init` for the initial
+/// state, and by its transitions. Symbols may be written as identifiers or character
+/// literals. The alphabet is optional and otherwise consists of the symbols that occur on
+/// transitions. Panics with the [`BuilderError`] if the declaration is not a valid DFA.
+///
+/// ```
+/// use automata::prelude::*;
+/// use automata_learning::ts;
+///
+/// let even = ts! {
+///     alphabet: ['a', 'b'],
+///     q0 [init, true]: a -> q1, b -> q0;
+///     q1 [false]: a -> q0, b -> q1;
+/// };
+/// assert!(even.accepts("abab"));
+/// ```
+#[macro_export]
+macro_rules! ts {
+    (@state $builder:expr, $name:ident [init, $color:expr]) => {
+        $builder
+            .state(stringify!($name), $color)
+            .initial(stringify!($name))
+    };
+    (@state $builder:expr, $name:ident [$color:expr]) => {
+        $builder.state(stringify!($name), $color)
+    };
+    (@build $builder:expr;) => {
+        $builder
+            .build_dfa()
+            .unwrap_or_else(|err| panic!("Invalid automaton: {err}"))
+            .automaton
+    };
+    (@build $builder:expr;
+        $name:ident $color:tt : $($sym:tt -> $target:ident),* ; $($rest:tt)*
+    ) => {
+        $crate::ts!(
+            @build $crate::ts!(@state $builder, $name $color)
+                $(.edge(
+                    stringify!($name),
+                    $crate::builder::__symbol(stringify!($sym)),
+                    stringify!($target),
+                ))*;
+            $($rest)*
+        )
+    };
+    (alphabet: [$($sym:tt),* $(,)?], $($rest:tt)*) => {
+        $crate::ts!(
+            @build $crate::builder::AutomatonBuilder::dfa()
+                .with_symbols([$($crate::builder::__symbol(stringify!($sym))),*]);
+            $($rest)*
+        )
+    };
+    ($($rest:tt)*) => {
+        $crate::ts!(@build $crate::builder::AutomatonBuilder::dfa(); $($rest)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{AutomatonBuilder, BuilderError};
+
+    #[test]
+    fn build_automata() {
+        let even = AutomatonBuilder::dfa()
+            .state("even", true)
+            .state("odd", false)
+            .transition("even", 'a', "odd", Void)
+            .transition("odd", 'a', "even", Void)
+            .require_complete()
+            .build_dfa()
+            .unwrap();
+        assert_eq!(even.index("odd"), Some(1));
+        assert_eq!(even.name(0), Some("even"));
+        assert!(even.automaton.accepts("aa"));
+        assert!(!even.automaton.accepts("aaa"));
+
+        let toggle = AutomatonBuilder::mealy()
+            .state("off", Void)
+            .state("on", Void)
+            .initial("on")
+            .transition("off", 'p', "on", 1)
+            .transition("on", 'p', "off", 0)
+            .build_mealy()
+            .unwrap();
+        assert_eq!(toggle.automaton.last_edge_color("pp"), Some(1));
+
+        let infinitely_many_b = AutomatonBuilder::dba()
+            .state("q", Void)
+            .transition("q", 'a', "q", false)
+            .transition("q", 'b', "q", true)
+            .build_dba()
+            .unwrap();
+        assert_eq!(infinitely_many_b.automaton.size(), 1);
+    }
+
+    #[test]
+    fn ts_macro() {
+        let dfa = crate::ts! {
+            alphabet: ['a', 'b'],
+            q0 [init, true]: a -> q1, b -> q0;
+            q1 [false]: a -> q0, b -> q1;
+        };
+        assert_eq!(dfa.size(), 2);
+        assert!(dfa.accepts("abba"));
+        assert!(!dfa.accepts("ab"));
+
+        // the initial state need not come first and the alphabet is inferred
+        let dfa = crate::ts! {
+            sink [false]: 'x' -> sink;
+            start [init, true]: 'x' -> sink;
+        };
+        assert!(dfa.accepts(""));
+        assert!(!dfa.accepts("x"));
+    }
+
+    #[test]
+    fn builder_errors() {
+        let builder = AutomatonBuilder::dfa()
+            .state("q0", false)
+            .state("q1", true)
+            .transition("q0", 'a', "q1", Void);
+        assert_eq!(
+            builder.clone().require_complete().build_dfa().err(),
+            Some(BuilderError::Incomplete("q1".to_string(), 'a'))
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .transition("q0", 'a', "q0", Void)
+                .build_dfa()
+                .err(),
+            Some(BuilderError::Nondeterministic("q0".to_string(), 'a'))
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .transition("q1", 'a', "q2", Void)
+                .build_dfa()
+                .err(),
+            Some(BuilderError::UnknownState("q2".to_string()))
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .with_alphabet(alphabet!(simple 'b'))
+                .build_dfa()
+                .err(),
+            Some(BuilderError::UnknownSymbol('a'))
+        );
+        assert_eq!(
+            builder.state("q0", true).build_dfa().err(),
+            Some(BuilderError::DuplicateState("q0".to_string()))
+        );
+        assert_eq!(
+            AutomatonBuilder::dfa().build_dfa().err(),
+            Some(BuilderError::NoStates)
+        );
+    }
+}
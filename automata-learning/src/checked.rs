@@ -0,0 +1,176 @@
+//! Fallible variants of accessors and constructors of transition systems, which return a
+//! [`TransitionSystemError`] for malformed inputs where the plain methods panic or silently do
+//! nothing. This is useful when automata are built from untrusted input, e.g. from a file, and
+//! the caller wants to report which state or symbol was at fault.
+
+use std::fmt::Debug;
+
+use automata::{prelude::*, transition_system::Sproutable};
+
+/// Abstracts the types of errors that can occur when accessing or modifying a transition
+/// system with the methods of [`CheckedTransitionSystem`] and [`CheckedSproutable`], where
+/// `Idx` is the type of state indices and `S` the type of symbols.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum TransitionSystemError<Idx, S> {
+    MissingState(Idx),
+    UnknownSymbol(S),
+    MissingTransition(Idx, S),
+}
+
+/// The error type of the checked methods of the transition system `Ts`.
+pub type CheckedError<Ts> =
+    TransitionSystemError<<Ts as TransitionSystem>::StateIndex, SymbolOf<Ts>>;
+
+impl<Idx: Debug, S: Debug> std::fmt::Display for TransitionSystemError<Idx, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransitionSystemError::MissingState(q) => write!(f, "State {q:?} does not exist"),
+            TransitionSystemError::UnknownSymbol(sym) => {
+                write!(f, "Symbol {sym:?} is not in the alphabet")
+            }
+            TransitionSystemError::MissingTransition(q, sym) => {
+                write!(f, "State {q:?} has no transition on {sym:?}")
+            }
+        }
+    }
+}
+
+impl<Idx: Debug, S: Debug> std::error::Error for TransitionSystemError<Idx, S> {}
+
+/// Fallible accessors of transition systems.
+pub trait CheckedTransitionSystem: TransitionSystem {
+    /// Returns the color of `state`, or an error if it does not exist.
+    fn try_state_color(
+        &self,
+        state: Self::StateIndex,
+    ) -> Result<Self::StateColor, CheckedError<Self>> {
+        self.state_color(state)
+            .ok_or(TransitionSystemError::MissingState(state))
+    }
+
+    /// Returns the initial state, or an error if it does not exist in the transition system.
+    fn try_initial(&self) -> Result<Self::StateIndex, CheckedError<Self>>
+    where
+        Self: Pointed,
+    {
+        let initial = self.initial();
+        self.try_state_color(initial).map(|_| initial)
+    }
+
+    /// Returns the color of the initial state, see [`Self::try_initial`].
+    fn try_initial_color(&self) -> Result<Self::StateColor, CheckedError<Self>>
+    where
+        Self: Pointed,
+    {
+        self.try_initial().and_then(|q| self.try_state_color(q))
+    }
+
+    /// Returns the successor of `state` on `sym`. Fails if the state does not exist, the
+    /// symbol is not in the alphabet or the state has no transition on it.
+    fn try_successor(
+        &self,
+        state: Self::StateIndex,
+        sym: SymbolOf<Self>,
+    ) -> Result<Self::StateIndex, CheckedError<Self>>
+    where
+        Self: Deterministic,
+    {
+        self.try_state_color(state)?;
+        self.check_symbol(sym)?;
+        self.successor_index(state, sym)
+            .ok_or(TransitionSystemError::MissingTransition(state, sym))
+    }
+
+    /// Returns the state that is reached by reading `word` from the initial state. Fails with
+    /// the first state and symbol for which no transition exists.
+    fn try_reached_state_index(
+        &self,
+        word: &[SymbolOf<Self>],
+    ) -> Result<Self::StateIndex, CheckedError<Self>>
+    where
+        Self: Deterministic + Pointed,
+    {
+        word.iter()
+            .try_fold(self.try_initial()?, |q, sym| self.try_successor(q, *sym))
+    }
+
+    /// Returns an error if `sym` is not in the alphabet of the transition system.
+    fn check_symbol(&self, sym: SymbolOf<Self>) -> Result<(), CheckedError<Self>> {
+        if self.alphabet().universe().any(|a| a == sym) {
+            Ok(())
+        } else {
+            Err(TransitionSystemError::UnknownSymbol(sym))
+        }
+    }
+}
+
+impl<Ts: TransitionSystem> CheckedTransitionSystem for Ts {}
+
+/// Fallible construction of transition systems.
+pub trait CheckedSproutable: Sproutable {
+    /// Adds an edge from `source` to `target` on `sym`, but unlike
+    /// [`Sproutable::add_edge`] first checks that both states exist and that the symbol is in
+    /// the alphabet. Nothing is added if one of the checks fails.
+    fn try_add_edge(
+        &mut self,
+        source: Self::StateIndex,
+        sym: SymbolOf<Self>,
+        target: Self::StateIndex,
+        color: Self::EdgeColor,
+    ) -> Result<(), CheckedError<Self>> {
+        self.try_state_color(source)?;
+        self.try_state_color(target)?;
+        self.check_symbol(sym)?;
+        self.add_edge(
+            source,
+            <Self::Alphabet as Alphabet>::expression(sym),
+            target,
+            color,
+        );
+        Ok(())
+    }
+}
+
+impl<Ts: Sproutable> CheckedSproutable for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{CheckedSproutable, CheckedTransitionSystem, TransitionSystemError};
+
+    #[test]
+    fn checked_access() {
+        // a partial DFA that accepts exactly the word a
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        assert_eq!(dfa.try_add_edge(q0, 'a', q1, Void), Ok(()));
+        assert_eq!(
+            dfa.try_add_edge(q0, 'c', q1, Void),
+            Err(TransitionSystemError::UnknownSymbol('c'))
+        );
+        assert_eq!(
+            dfa.try_add_edge(q0, 'b', 7, Void),
+            Err(TransitionSystemError::MissingState(7))
+        );
+        assert_eq!(dfa.try_state_color(q1), Ok(true));
+        assert_eq!(
+            dfa.try_state_color(7),
+            Err(TransitionSystemError::MissingState(7))
+        );
+
+        assert_eq!(dfa.try_initial(), Ok(q0));
+        assert_eq!(dfa.try_initial_color(), Ok(false));
+        assert_eq!(dfa.try_reached_state_index(&['a']), Ok(q1));
+        assert_eq!(
+            dfa.try_reached_state_index(&['a', 'b']),
+            Err(TransitionSystemError::MissingTransition(q1, 'b'))
+        );
+        assert_eq!(
+            dfa.try_reached_state_index(&['c']),
+            Err(TransitionSystemError::UnknownSymbol('c'))
+        );
+    }
+}
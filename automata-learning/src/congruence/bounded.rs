@@ -0,0 +1,131 @@
+use std::{collections::VecDeque, hash::Hash};
+
+use automata::{prelude::*, Map, RightCongruence};
+use itertools::Itertools;
+use tracing::debug;
+
+/// The outcome of a bounded exploration of a congruence that is defined on the fly, see
+/// [`explore_bounded`].
+#[derive(Clone)]
+pub enum BoundedCongruence<A: Alphabet> {
+    /// All classes were found within the budget, so the congruence is complete and has
+    /// finite index.
+    Finite(RightCongruence<A>),
+    /// The budget was exhausted before the exploration terminated, which indicates that the
+    /// congruence has unbounded index. The contained congruence consists of the classes found
+    /// so far, where transitions of classes that were not fully explored are missing.
+    Unbounded(RightCongruence<A>),
+}
+
+impl<A: Alphabet> BoundedCongruence<A> {
+    /// Returns `true` if the exploration terminated within the budget.
+    pub fn is_finite(&self) -> bool {
+        matches!(self, BoundedCongruence::Finite(_))
+    }
+
+    /// Returns the congruence, which is partial if the budget was exhausted.
+    pub fn congruence(&self) -> &RightCongruence<A> {
+        match self {
+            BoundedCongruence::Finite(cong) | BoundedCongruence::Unbounded(cong) => cong,
+        }
+    }
+
+    /// Consumes `self` and returns the congruence, which is partial if the budget was
+    /// exhausted.
+    pub fn into_congruence(self) -> RightCongruence<A> {
+        match self {
+            BoundedCongruence::Finite(cong) | BoundedCongruence::Unbounded(cong) => cong,
+        }
+    }
+}
+
+/// Builds the right congruence that is defined by `successor`, which maps the class of a word
+/// `u` and a symbol `a` to the class of `ua`, starting from the class `initial` of the empty
+/// word. Classes are identified by equality of the nodes, so `successor` must produce the same
+/// node for equivalent words, as is the case e.g. for residuals in a canonical form.
+///
+/// At most `budget` classes are created. If a further class is discovered, the exploration
+/// stops and the partial congruence is returned as [`BoundedCongruence::Unbounded`]. Classes
+/// are explored breadth-first and labelled with their minimal representatives in the
+/// length-lexicographic order, so the partial congruence contains the classes of the shortest
+/// words.
+pub fn explore_bounded<A, N, F>(
+    alphabet: A,
+    initial: N,
+    successor: F,
+    budget: usize,
+) -> BoundedCongruence<A>
+where
+    A: Alphabet,
+    N: Clone + Eq + Hash,
+    F: Fn(&N, A::Symbol) -> N,
+{
+    assert!(budget > 0, "The budget must allow at least one class");
+    let symbols = alphabet.universe().collect_vec();
+    let mut cong = RightCongruence::new(alphabet);
+    let mut index: Map<N, usize> = Map::default();
+    index.insert(initial.clone(), cong.add_state((vec![], Void)));
+    let mut queue = VecDeque::from([(initial, vec![])]);
+
+    while let Some((node, representative)) = queue.pop_front() {
+        let source = index[&node];
+        for sym in &symbols {
+            let target = successor(&node, *sym);
+            let target = match index.get(&target) {
+                Some(q) => *q,
+                None if index.len() == budget => {
+                    debug!("Exploration exceeded the budget of {budget} classes");
+                    return BoundedCongruence::Unbounded(cong);
+                }
+                None => {
+                    let mut word: Vec<A::Symbol> = representative.clone();
+                    word.push(*sym);
+                    let q = cong.add_state((word.clone(), Void));
+                    index.insert(target.clone(), q);
+                    queue.push_back((target, word));
+                    q
+                }
+            };
+            cong.add_edge(source, A::expression(*sym), target, Void);
+        }
+    }
+    debug!("Exploration terminated with {} classes", cong.size());
+    BoundedCongruence::Finite(cong)
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::explore_bounded;
+
+    #[test]
+    fn bounded_exploration() {
+        let alphabet = alphabet!(simple 'a', 'b');
+
+        // the number of a's modulo three has index three
+        let modulo = explore_bounded(
+            alphabet.clone(),
+            0usize,
+            |n, sym| if sym == 'a' { (n + 1) % 3 } else { *n },
+            3,
+        );
+        assert!(modulo.is_finite());
+        let cong = modulo.into_congruence();
+        assert_eq!(cong.size(), 3);
+        assert_eq!(cong.reached_state_index(&['a', 'b', 'a', 'a']), Some(0));
+
+        // comparing the number of a's and b's needs a counter, so no budget suffices
+        let counter = explore_bounded(
+            alphabet,
+            0i64,
+            |n, sym| if sym == 'a' { n + 1 } else { n - 1 },
+            5,
+        );
+        assert!(!counter.is_finite());
+        let partial = counter.congruence();
+        assert_eq!(partial.size(), 5);
+        assert!(partial.reached_state_index(&['a', 'a']).is_some());
+        assert!(partial.reached_state_index(&['a', 'a', 'a']).is_none());
+    }
+}
@@ -0,0 +1,316 @@
+use automata::{congruence::FORC, prelude::*, word::Reduced, Map, RightCongruence, Set};
+use itertools::Itertools;
+use tracing::debug;
+
+use super::{Exploration, ForcMembership, ForcSemantics, OmegaCongruence};
+
+/// The canonical families of DFAs of an omega-regular language `L`. All of them share the
+/// leading congruence `~` of `L` and differ in the progress congruence of each class `u`:
+/// - periodic: `x ≈ y` iff for all `v`, `u(xv)^ω ∈ L` iff `u(yv)^ω ∈ L`,
+/// - syntactic: `x ≈ y` iff `ux ~ uy` and for all `v` with `uxv ~ u`, `u(xv)^ω ∈ L` iff
+///   `u(yv)^ω ∈ L`,
+/// - recurrent: `x ≈ y` iff for all `v`, `uxv ~ u` and `u(xv)^ω ∈ L` iff `uyv ~ u` and
+///   `u(yv)^ω ∈ L`.
+///
+/// In the periodic FDFA, the class of `x` is accepting if `ux^ω ∈ L`, in the other two
+/// additionally `ux ~ u` must hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FdfaKind {
+    /// The periodic FDFA.
+    Periodic,
+    /// The syntactic FDFA.
+    Syntactic,
+    /// The recurrent FDFA.
+    Recurrent,
+}
+
+/// A family of DFAs, consisting of a [`FORC`] and a set of accepting states for each of its
+/// progress congruences. Words are accepted according to the normalized decompositions, see
+/// [`ForcMembership`]. The canonical FDFAs are saturated, so it does not matter whether some or
+/// all decompositions are checked.
+#[derive(Clone)]
+pub struct FDFA<A: Alphabet = CharAlphabet> {
+    kind: FdfaKind,
+    forc: FORC<A>,
+    accepting: Map<usize, Set<usize>>,
+}
+
+/// Builds the progress congruence of kind `kind` for the leading class `class` from the
+/// exploration of all words read from that class. Here `leading` gives the leading class that
+/// is reached by a node and `accepted` must be `true` for a node reached on `z` if `uz^ω ∈ L`,
+/// where it may be wrong if `uz ~ u` does not hold, unless the kind is periodic. Returns the
+/// progress congruence and its accepting states.
+fn progress_congruence<N, A, L, P>(
+    exploration: Exploration<N, A::Symbol>,
+    alphabet: A,
+    kind: FdfaKind,
+    class: usize,
+    leading: L,
+    accepted: P,
+) -> (RightCongruence<A>, Set<usize>)
+where
+    N: Clone + Eq + std::hash::Hash,
+    A: Alphabet,
+    L: Fn(&N) -> usize,
+    P: Fn(&N) -> bool,
+{
+    let looping = |n: &N| leading(n) == class;
+    let partition = exploration.refine(|n| match kind {
+        FdfaKind::Periodic => (0, Some(accepted(n))),
+        FdfaKind::Syntactic => (leading(n), looping(n).then(|| accepted(n))),
+        FdfaKind::Recurrent => (0, Some(looping(n) && accepted(n))),
+    });
+    let finals = exploration
+        .order
+        .iter()
+        .filter(|n| match kind {
+            FdfaKind::Periodic => accepted(n),
+            _ => looping(n) && accepted(n),
+        })
+        .map(|n| partition[n])
+        .collect_vec();
+    let (cong, index) = exploration.into_congruence(alphabet, &partition);
+    (cong, finals.into_iter().map(|c| index[&c]).collect())
+}
+
+impl<A: Alphabet> FDFA<A> {
+    /// Creates a new FDFA from a FORC and the accepting states of each progress congruence.
+    pub fn new(kind: FdfaKind, forc: FORC<A>, accepting: Map<usize, Set<usize>>) -> Self {
+        Self {
+            kind,
+            forc,
+            accepting,
+        }
+    }
+
+    /// Returns the kind of the FDFA.
+    pub fn kind(&self) -> FdfaKind {
+        self.kind
+    }
+
+    /// Returns the underlying [`FORC`].
+    pub fn forc(&self) -> &FORC<A> {
+        &self.forc
+    }
+
+    /// Returns `true` if `state` of the progress congruence of the leading class `class` is
+    /// accepting.
+    pub fn is_accepting(&self, class: usize, state: usize) -> bool {
+        self.accepting
+            .get(&class)
+            .is_some_and(|states| states.contains(&state))
+    }
+
+    /// Returns the progress DFA of the leading class `class`, i.e. its progress congruence
+    /// with the accepting states marked.
+    pub fn progress_dfa(&self, class: usize) -> Option<DFA<A>> {
+        let prc = self.forc.prc(class)?;
+        let mut dfa = DFA::new_for_alphabet(prc.alphabet().clone());
+        let states = prc.state_indices().sorted().collect_vec();
+        let index: Map<_, _> = states
+            .iter()
+            .map(|q| (*q, dfa.add_state(self.is_accepting(class, *q))))
+            .collect();
+        for q in &states {
+            for e in prc.edges_from(*q).expect("State must exist") {
+                dfa.add_edge(index[q], e.expression().clone(), index[&e.target()], Void);
+            }
+        }
+        Some(dfa)
+    }
+
+    /// Decides whether the ultimately periodic `word` is accepted.
+    pub fn accepts(&self, word: &Reduced<A::Symbol>) -> bool {
+        self.forc.accepts_with(
+            word,
+            |class, state| self.is_accepting(class, state),
+            ForcSemantics::Exists,
+        )
+    }
+
+    /// Converts the FDFA into the syntactic FDFA of the same language.
+    pub fn to_syntactic(&self) -> Self {
+        self.derive(FdfaKind::Syntactic)
+    }
+
+    /// Converts the FDFA into the recurrent FDFA of the same language.
+    pub fn to_recurrent(&self) -> Self {
+        self.derive(FdfaKind::Recurrent)
+    }
+
+    /// Computes the syntactic or recurrent FDFA. For each class `u`, both only depend on the
+    /// set of words `z` with `uz ~ u` and `uz^ω ∈ L`, which is recognized by the product of
+    /// the leading congruence and the progress DFA of `u` in all three kinds of FDFAs. Note that
+    /// the periodic FDFA can not be obtained this way.
+    fn derive(&self, kind: FdfaKind) -> Self {
+        assert_ne!(kind, FdfaKind::Periodic);
+        let leading = self.forc.leading();
+        let symbols = leading.alphabet().universe().collect_vec();
+        let mut progress = vec![];
+        let mut accepting = Map::default();
+        for class in leading.state_indices().sorted() {
+            let prc = self
+                .forc
+                .prc(class)
+                .expect("Every class must have a progress congruence");
+            let exploration =
+                Exploration::from_fn((class, prc.initial()), &symbols, |(l, p), sym| {
+                    (
+                        leading
+                            .successor_index(*l, sym)
+                            .expect("Leading congruence must be complete"),
+                        prc.successor_index(*p, sym)
+                            .expect("Progress congruence must be complete"),
+                    )
+                });
+            let (cong, finals) = progress_congruence(
+                exploration,
+                leading.alphabet().clone(),
+                kind,
+                class,
+                |(l, _)| *l,
+                |(l, p)| *l == class && self.is_accepting(class, *p),
+            );
+            progress.push((class, cong));
+            accepting.insert(class, finals);
+        }
+        Self::new(kind, FORC::from_iter(leading.clone(), progress), accepting)
+    }
+}
+
+/// Constructs the canonical FDFAs of the language of a deterministic parity automaton.
+pub trait FdfaConstruction: DPALike {
+    /// Builds the canonical FDFA of the given kind. The DPA must be complete.
+    ///
+    /// For each class `u` of the leading congruence, we explore the transition profiles of
+    /// words read from `u`, i.e. the functions mapping each state of the DPA to the state that
+    /// is reached on the word together with the least priority along the way. Whether
+    /// `u(xv)^ω ∈ L` holds only depends on the profile of `xv`, so the progress congruence is
+    /// obtained by partition refinement on the profiles.
+    fn fdfa(&self, kind: FdfaKind) -> FDFA<Self::Alphabet> {
+        let leading = self.omega_right_congruence();
+        let states = self.state_indices().sorted().collect_vec();
+        let position: Map<_, _> = states.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+        let symbols = self.alphabet().universe().collect_vec();
+        // step[i][k] is the position of the successor of the i-th state on the k-th symbol
+        // together with the priority of the transition
+        let step = states
+            .iter()
+            .map(|q| {
+                symbols
+                    .iter()
+                    .map(|sym| {
+                        let t = self
+                            .transition(*q, *sym)
+                            .expect("DPA must be deterministic and complete");
+                        (position[&t.target()], t.color())
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+        let identity = (0..states.len()).map(|i| (i, usize::MAX)).collect_vec();
+
+        let mut progress = vec![];
+        let mut accepting = Map::default();
+        for class in leading.state_indices().sorted() {
+            let representative = leading
+                .class_name(class)
+                .expect("Class must exist")
+                .to_vec();
+            let start = position[&self
+                .reached_state_index(&representative)
+                .expect("DPA must be complete")];
+            let exploration =
+                Exploration::from_fn((class, identity.clone()), &symbols, |(l, profile), sym| {
+                    let k = symbols.iter().position(|s| *s == sym).unwrap();
+                    (
+                        leading
+                            .successor_index(*l, sym)
+                            .expect("Leading congruence is complete"),
+                        profile
+                            .iter()
+                            .map(|(p, m)| (step[*p][k].0, std::cmp::min(*m, step[*p][k].1)))
+                            .collect_vec(),
+                    )
+                });
+            // u z^ω is accepted if the least priority on the cycle that is eventually
+            // reached by iterating the profile of z from the state of u is even
+            let periodic = |(_, profile): &(usize, Vec<(usize, usize)>)| {
+                let mut visited = vec![start];
+                let mut priorities = vec![];
+                loop {
+                    let (next, priority) = profile[*visited.last().unwrap()];
+                    priorities.push(priority);
+                    if let Some(i) = visited.iter().position(|p| *p == next) {
+                        let least = priorities[i..].iter().min().unwrap();
+                        return *least != usize::MAX && least % 2 == 0;
+                    }
+                    visited.push(next);
+                }
+            };
+            let (cong, finals) = progress_congruence(
+                exploration,
+                self.alphabet().clone(),
+                kind,
+                class,
+                |(l, _)| *l,
+                periodic,
+            );
+            debug!(
+                "Progress congruence of class {class} has {} states",
+                cong.size()
+            );
+            progress.push((class, cong));
+            accepting.insert(class, finals);
+        }
+        FDFA::new(kind, FORC::from_iter(leading, progress), accepting)
+    }
+}
+
+impl<D: DPALike> FdfaConstruction for D {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{FdfaConstruction, FdfaKind};
+
+    #[test]
+    fn canonical_fdfas() {
+        // eventually always a
+        let dpa: DPA = NTS::builder()
+            .with_transitions([(0, 'a', 2, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        for kind in [FdfaKind::Periodic, FdfaKind::Syntactic, FdfaKind::Recurrent] {
+            let fdfa = dpa.fdfa(kind);
+            assert_eq!(fdfa.forc().leading().size(), 1);
+            assert_eq!(fdfa.forc().prc(0).unwrap().size(), 3);
+            assert!(fdfa.accepts(&upw!("b", "a")));
+            assert!(fdfa.accepts(&upw!("a")));
+            assert!(!fdfa.accepts(&upw!("ab")));
+            assert!(!fdfa.accepts(&upw!("ba", "b")));
+        }
+
+        // converting into another kind gives the same result as the direct construction
+        let syntactic = dpa.fdfa(FdfaKind::Syntactic);
+        for fdfa in [dpa.fdfa(FdfaKind::Periodic), dpa.fdfa(FdfaKind::Recurrent)] {
+            let converted = fdfa.to_syntactic();
+            assert_eq!(converted.kind(), FdfaKind::Syntactic);
+            let (expected, found) = (
+                syntactic.progress_dfa(0).unwrap(),
+                converted.progress_dfa(0).unwrap(),
+            );
+            assert_eq!(expected.size(), found.size());
+            for word in ["", "a", "b", "ab", "aab", "ba", "baa"] {
+                assert_eq!(expected.accepts(word), found.accepts(word));
+            }
+        }
+        assert_eq!(
+            syntactic.to_recurrent().progress_dfa(0).unwrap().size(),
+            dpa.fdfa(FdfaKind::Recurrent)
+                .progress_dfa(0)
+                .unwrap()
+                .size()
+        );
+    }
+}
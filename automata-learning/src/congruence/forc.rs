@@ -0,0 +1,217 @@
+use automata::{congruence::FORC, prelude::*, word::Reduced, Map, RightCongruence};
+use tracing::trace;
+
+/// Determines which decompositions of an ultimately periodic word are taken into account when
+/// deciding membership with a family of right congruences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ForcSemantics {
+    /// The word is accepted if the periodic part of some normalized decomposition is accepted.
+    Exists,
+    /// The word is accepted if the periodic parts of all normalized decompositions are accepted.
+    Forall,
+}
+
+/// Follows `word` from `state` in a deterministic congruence, returns `None` if a transition
+/// is missing.
+fn run<A: Alphabet>(cong: &RightCongruence<A>, state: usize, word: &[A::Symbol]) -> Option<usize> {
+    word.iter()
+        .try_fold(state, |q, sym| cong.successor_index(q, *sym))
+}
+
+/// Membership semantics for a [`FORC`], i.e. a leading congruence together with a progress
+/// congruence for each of its classes. A FORC on its own does not determine a language, so
+/// acceptance is given by a predicate on pairs of a leading class and a class of the
+/// corresponding progress congruence, which is what an FDFA stores in its accepting states.
+///
+/// An ultimately periodic word `w` is decided by its normalized decompositions `(u, v)` with
+/// `w = uv^ω` and `uv ~ u` in the leading congruence. For each such decomposition, the class
+/// of `v` in the progress congruence of the class of `u` is checked for acceptance.
+pub trait ForcMembership {
+    /// The alphabet of the FORC.
+    type Alphabet: Alphabet;
+
+    /// Computes the minimal normalized decompositions of `word`, each given as the leading
+    /// class that is reached by the prefix and the period that loops on that class. For a word
+    /// `uv^ω`, the prefixes are of the form `uv^i v'` for every proper prefix `v'` of `v`, and
+    /// the periods are the corresponding rotations of a power of `v`. Any other normalized
+    /// decomposition is obtained from these by taking a power of the period. Returns `None` if
+    /// the leading congruence is not complete.
+    #[allow(clippy::type_complexity)]
+    fn normalized_decompositions(
+        &self,
+        word: &Reduced<SymbolOf<Self::Alphabet>>,
+    ) -> Option<Vec<(usize, Vec<SymbolOf<Self::Alphabet>>)>>;
+
+    /// Decides membership of `word`, where `accepting(c, p)` determines whether class `p` of
+    /// the progress congruence of the leading class `c` is accepting. Decompositions for
+    /// which a transition is missing count as rejecting.
+    fn accepts_with<F>(
+        &self,
+        word: &Reduced<SymbolOf<Self::Alphabet>>,
+        accepting: F,
+        semantics: ForcSemantics,
+    ) -> bool
+    where
+        F: Fn(usize, usize) -> bool;
+}
+
+impl<A: Alphabet> ForcMembership for FORC<A> {
+    type Alphabet = A;
+
+    fn normalized_decompositions(
+        &self,
+        word: &Reduced<A::Symbol>,
+    ) -> Option<Vec<(usize, Vec<A::Symbol>)>> {
+        let raw = word.raw_word();
+        let (spoke, cycle) = raw.split_at(word.loop_index());
+        let leading = self.leading();
+
+        let mut decompositions = vec![];
+        for offset in 0..cycle.len() {
+            // the prefix reads the spoke and the first `offset` symbols of the cycle, so the
+            // period is the cycle rotated by `offset`
+            let rotated = [&cycle[offset..], &cycle[..offset]].concat();
+            let mut prefix = spoke.to_vec();
+            prefix.extend_from_slice(&cycle[..offset]);
+
+            // we read the period until a leading class repeats, the classes from the first
+            // occurrence on form the loop of the word in the leading congruence
+            let mut states = vec![run(leading, leading.initial(), &prefix)?];
+            let (start, length) = loop {
+                let next = run(leading, *states.last().unwrap(), &rotated)?;
+                if let Some(position) = states.iter().position(|q| *q == next) {
+                    break (position, states.len() - position);
+                }
+                states.push(next);
+            };
+            trace!(
+                "Word loops on {length} leading classes after {start} iterations of its cycle \
+                 rotated by {offset}"
+            );
+
+            let period = rotated.repeat(length);
+            for class in &states[start..] {
+                if !decompositions.contains(&(*class, period.clone())) {
+                    decompositions.push((*class, period.clone()));
+                }
+            }
+        }
+        Some(decompositions)
+    }
+
+    fn accepts_with<F>(
+        &self,
+        word: &Reduced<A::Symbol>,
+        accepting: F,
+        semantics: ForcSemantics,
+    ) -> bool
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let Some(decompositions) = self.normalized_decompositions(word) else {
+            return false;
+        };
+        // each decomposition (u, v) yields the decompositions (u, v^m), whose classes in the
+        // progress congruence eventually repeat
+        let mut verdicts = decompositions.into_iter().flat_map(|(class, period)| {
+            let Some(progress) = self.prc(class) else {
+                return vec![false];
+            };
+            let mut seen: Map<usize, bool> = Map::default();
+            let mut state = progress.initial();
+            loop {
+                match run(progress, state, &period) {
+                    Some(next) if seen.contains_key(&next) => break,
+                    Some(next) => {
+                        seen.insert(next, accepting(class, next));
+                        state = next;
+                    }
+                    None => return vec![false],
+                }
+            }
+            seen.into_values().collect()
+        });
+        match semantics {
+            ForcSemantics::Exists => verdicts.any(|accepted| accepted),
+            ForcSemantics::Forall => verdicts.all(|accepted| accepted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::{congruence::FORC, prelude::*, RightCongruence};
+
+    use super::{ForcMembership, ForcSemantics};
+
+    #[test]
+    fn forc_membership() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        // the leading congruence distinguishes whether an a was seen
+        let mut leading = RightCongruence::new(alphabet.clone());
+        let eps = leading.add_state((vec![], Void));
+        let seen = leading.add_state((vec!['a'], Void));
+        leading.add_edge(eps, 'a', seen, Void);
+        leading.add_edge(eps, 'b', eps, Void);
+        leading.add_edge(seen, 'a', seen, Void);
+        leading.add_edge(seen, 'b', seen, Void);
+
+        // both progress congruences track the parity of the length
+        let parity = || {
+            let mut prc = RightCongruence::new(alphabet.clone());
+            let even = prc.add_state((vec![], Void));
+            let odd = prc.add_state((vec!['a'], Void));
+            for sym in ['a', 'b'] {
+                prc.add_edge(even, sym, odd, Void);
+                prc.add_edge(odd, sym, even, Void);
+            }
+            prc
+        };
+        let forc = FORC::from_iter(leading, [(eps, parity()), (seen, parity())]);
+        let even_after_a = |c: usize, p: usize| c == seen && p == 0;
+
+        let word = upw!("b", "a");
+        assert_eq!(
+            forc.normalized_decompositions(&word),
+            Some(vec![(seen, vec!['a'])])
+        );
+        // a^m has even length for some, but not for all m
+        assert!(forc.accepts_with(&word, even_after_a, ForcSemantics::Exists));
+        assert!(!forc.accepts_with(&word, even_after_a, ForcSemantics::Forall));
+        let word = upw!("ab");
+        assert!(forc.accepts_with(&word, even_after_a, ForcSemantics::Forall));
+        assert!(!forc.accepts_with(&upw!("b"), even_after_a, ForcSemantics::Exists));
+    }
+
+    #[test]
+    fn rotated_decompositions() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        let mut leading = RightCongruence::new(alphabet.clone());
+        let eps = leading.add_state((vec![], Void));
+        leading.add_edge(eps, 'a', eps, Void);
+        leading.add_edge(eps, 'b', eps, Void);
+
+        // the progress congruence remembers the first symbol
+        let mut prc = RightCongruence::new(alphabet);
+        let init = prc.add_state((vec![], Void));
+        let first_a = prc.add_state((vec!['a'], Void));
+        let first_b = prc.add_state((vec!['b'], Void));
+        prc.add_edge(init, 'a', first_a, Void);
+        prc.add_edge(init, 'b', first_b, Void);
+        for sym in ['a', 'b'] {
+            prc.add_edge(first_a, sym, first_a, Void);
+            prc.add_edge(first_b, sym, first_b, Void);
+        }
+        let forc = FORC::from_iter(leading, [(eps, prc)]);
+        let starts_with_b = |_: usize, p: usize| p == first_b;
+
+        // (ab)^ω = a(ba)^ω, so only the decomposition with the rotated period is accepted
+        let word = upw!("ab");
+        assert_eq!(
+            forc.normalized_decompositions(&word),
+            Some(vec![(eps, vec!['a', 'b']), (eps, vec!['b', 'a'])])
+        );
+        assert!(forc.accepts_with(&word, starts_with_b, ForcSemantics::Exists));
+        assert!(!forc.accepts_with(&word, starts_with_b, ForcSemantics::Forall));
+    }
+}
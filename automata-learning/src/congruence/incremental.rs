@@ -0,0 +1,142 @@
+use std::{cmp::Ordering, collections::VecDeque};
+
+use automata::{prelude::*, Map, RightCongruence};
+use tracing::trace;
+
+use super::{llex_cmp, ClassNavigation};
+
+/// A right congruence that maintains the minimal representative of each reachable class in
+/// the length-lexicographic order while it is being built. Adding an edge only updates the
+/// classes whose representative becomes smaller, so the representatives are available at any
+/// time without exploring the whole congruence as [`ClassNavigation::classes_llex`] does.
+///
+/// Edges can only be added, replacing or removing an edge may make representatives larger,
+/// which requires a full recomputation with [`Self::recompute`].
+#[derive(Clone)]
+pub struct IncrementalCongruence<A: Alphabet> {
+    cong: RightCongruence<A>,
+    representative: Map<usize, Vec<A::Symbol>>,
+}
+
+impl<A: Alphabet> IncrementalCongruence<A> {
+    /// Wraps the given congruence and computes the representatives of its reachable classes.
+    pub fn new(cong: RightCongruence<A>) -> Self {
+        let mut out = Self {
+            cong,
+            representative: Map::default(),
+        };
+        out.recompute();
+        out
+    }
+
+    /// Returns the underlying congruence.
+    pub fn cong(&self) -> &RightCongruence<A> {
+        &self.cong
+    }
+
+    /// Consumes `self` and returns the underlying congruence.
+    pub fn into_inner(self) -> RightCongruence<A> {
+        self.cong
+    }
+
+    /// Adds a class with the given label, which is unreachable until an edge leads to it.
+    /// The first class that is added is the class of the empty word.
+    pub fn add_state(&mut self, label: Vec<A::Symbol>) -> usize {
+        let q = self.cong.add_state((label, Void));
+        if self.cong.size() == 1 {
+            self.representative.insert(q, vec![]);
+        }
+        q
+    }
+
+    /// Adds an edge and updates the representatives of all classes that are now reached on a
+    /// smaller word. Panics if `source` already has a transition on `symbol`.
+    pub fn add_edge(&mut self, source: usize, symbol: A::Symbol, target: usize) {
+        assert!(
+            self.cong.successor_index(source, symbol).is_none(),
+            "Class {source} already has a transition on the given symbol"
+        );
+        self.cong
+            .add_edge(source, A::expression(symbol), target, Void);
+
+        let Some(rep) = self.representative.get(&source) else {
+            return;
+        };
+        let mut candidate = rep.clone();
+        candidate.push(symbol);
+        let mut queue = VecDeque::from([(target, candidate)]);
+        // appending a symbol preserves the length-lexicographic order, so relaxing the
+        // representatives along the edges until nothing changes yields the minimal ones
+        while let Some((q, word)) = queue.pop_front() {
+            let improves = self
+                .representative
+                .get(&q)
+                .map_or(true, |current| llex_cmp(&word, current) == Ordering::Less);
+            if !improves {
+                continue;
+            }
+            trace!("Representative of class {q} decreases to {word:?}");
+            for sym in self.cong.alphabet().universe() {
+                if let Some(p) = self.cong.successor_index(q, sym) {
+                    let mut next = word.clone();
+                    next.push(sym);
+                    queue.push_back((p, next));
+                }
+            }
+            self.representative.insert(q, word);
+        }
+    }
+
+    /// Returns the minimal representative of `class`, or `None` if it is not reachable.
+    pub fn minimal_representative(&self, class: usize) -> Option<&[A::Symbol]> {
+        self.representative.get(&class).map(|rep| rep.as_slice())
+    }
+
+    /// Recomputes all representatives from scratch.
+    pub fn recompute(&mut self) {
+        self.representative = if self.cong.size() == 0 {
+            Map::default()
+        } else {
+            self.cong.classes_llex().into_iter().collect()
+        };
+    }
+}
+
+impl<A: Alphabet> From<RightCongruence<A>> for IncrementalCongruence<A> {
+    fn from(cong: RightCongruence<A>) -> Self {
+        Self::new(cong)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, RightCongruence};
+
+    use super::IncrementalCongruence;
+    use crate::congruence::ClassNavigation;
+
+    #[test]
+    fn incremental_representatives() {
+        let mut cong = IncrementalCongruence::new(RightCongruence::new(alphabet!(simple 'a', 'b')));
+        let eps = cong.add_state(vec![]);
+        let x = cong.add_state(vec!['x']);
+        let y = cong.add_state(vec!['y']);
+        assert_eq!(cong.minimal_representative(eps), Some(&[][..]));
+        assert_eq!(cong.minimal_representative(x), None);
+
+        // edges are added such that representatives shrink several times
+        cong.add_edge(x, 'a', y);
+        assert_eq!(cong.minimal_representative(y), None);
+        cong.add_edge(eps, 'b', x);
+        assert_eq!(cong.minimal_representative(y), Some(&['b', 'a'][..]));
+        cong.add_edge(eps, 'a', y);
+        assert_eq!(cong.minimal_representative(y), Some(&['a'][..]));
+        cong.add_edge(y, 'a', x);
+        assert_eq!(cong.minimal_representative(x), Some(&['b'][..]));
+
+        let expected = cong.cong().classes_llex();
+        for (q, rep) in expected {
+            assert_eq!(cong.minimal_representative(q), Some(rep.as_slice()));
+        }
+    }
+}
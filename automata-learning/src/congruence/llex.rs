@@ -0,0 +1,123 @@
+use std::cmp::Ordering;
+
+use automata::{prelude::*, RightCongruence};
+
+use super::Exploration;
+
+/// Compares two words in the length-lexicographic order, i.e. shorter words come first and
+/// words of the same length are compared lexicographically.
+pub fn llex_cmp<S: Ord>(left: &[S], right: &[S]) -> Ordering {
+    left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+}
+
+/// Sorts words in the length-lexicographic order.
+pub fn llex_sort<S: Ord>(words: &mut [Vec<S>]) {
+    words.sort_by(|left, right| llex_cmp(left, right));
+}
+
+/// Navigation of a right congruence by the canonical representatives of its classes. The
+/// minimal representatives are computed from the transitions, so in contrast to the labels
+/// of the classes they are always up to date, even if the congruence was modified after
+/// `recompute_labels` was last called.
+pub trait ClassNavigation {
+    /// The alphabet of the congruence.
+    type Alphabet: Alphabet;
+
+    /// Returns the classes that are reachable from the class of the empty word together with
+    /// their minimal representative in the length-lexicographic order, ordered by these
+    /// representatives.
+    fn classes_llex(&self) -> Vec<(usize, Vec<SymbolOf<Self::Alphabet>>)>;
+
+    /// Returns the minimal representative of `class`, or `None` if it is not reachable.
+    fn minimal_representative(&self, class: usize) -> Option<Vec<SymbolOf<Self::Alphabet>>> {
+        self.classes_llex()
+            .into_iter()
+            .find(|(c, _)| *c == class)
+            .map(|(_, rep)| rep)
+    }
+
+    /// Returns the class of `uw` where `u` is a word in `class`, i.e. the class that is
+    /// reached by appending `symbol`.
+    fn class_append(&self, class: usize, symbol: SymbolOf<Self::Alphabet>) -> Option<usize> {
+        self.class_append_word(class, &[symbol])
+    }
+
+    /// Returns the class that is reached by appending `word` to the words in `class`.
+    fn class_append_word(&self, class: usize, word: &[SymbolOf<Self::Alphabet>]) -> Option<usize>;
+
+    /// Compares two classes by their minimal representatives, unreachable classes come last.
+    fn class_cmp(&self, left: usize, right: usize) -> Ordering;
+}
+
+impl<A: Alphabet> ClassNavigation for RightCongruence<A> {
+    type Alphabet = A;
+
+    fn classes_llex(&self) -> Vec<(usize, Vec<A::Symbol>)> {
+        let exploration = Exploration::new(self);
+        exploration
+            .order
+            .iter()
+            .filter_map(|q| q.map(|q| (q, exploration.representative[&Some(q)].clone())))
+            .collect()
+    }
+
+    fn class_append_word(&self, class: usize, word: &[A::Symbol]) -> Option<usize> {
+        word.iter()
+            .try_fold(class, |q, sym| self.successor_index(q, *sym))
+    }
+
+    fn class_cmp(&self, left: usize, right: usize) -> Ordering {
+        let order = self.classes_llex();
+        let position = |class: usize| {
+            order
+                .iter()
+                .position(|(c, _)| *c == class)
+                .unwrap_or(usize::MAX)
+        };
+        position(left).cmp(&position(right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use automata::{prelude::*, RightCongruence};
+
+    use super::{llex_cmp, llex_sort, ClassNavigation};
+
+    #[test]
+    fn llex_navigation() {
+        // classes are added in a different order than their representatives suggest
+        let mut cong = RightCongruence::new(alphabet!(simple 'a', 'b'));
+        let eps = cong.add_state((vec![], Void));
+        let bb = cong.add_state((vec!['b', 'b'], Void));
+        let b = cong.add_state((vec!['b'], Void));
+        let unreachable = cong.add_state((vec!['a', 'a', 'a'], Void));
+        cong.add_edge(eps, 'a', eps, Void);
+        cong.add_edge(eps, 'b', b, Void);
+        cong.add_edge(b, 'a', eps, Void);
+        cong.add_edge(b, 'b', bb, Void);
+        cong.add_edge(bb, 'a', bb, Void);
+        cong.add_edge(bb, 'b', bb, Void);
+        cong.add_edge(unreachable, 'a', eps, Void);
+
+        assert_eq!(
+            cong.classes_llex(),
+            vec![(eps, vec![]), (b, vec!['b']), (bb, vec!['b', 'b'])]
+        );
+        assert_eq!(cong.minimal_representative(unreachable), None);
+        assert_eq!(cong.class_append(b, 'b'), Some(bb));
+        assert_eq!(cong.class_append_word(eps, &['b', 'a', 'b']), Some(b));
+        assert_eq!(cong.class_cmp(bb, b), Ordering::Greater);
+        assert_eq!(cong.class_cmp(unreachable, bb), Ordering::Greater);
+
+        assert_eq!(llex_cmp(&['b'], &['a', 'a']), Ordering::Less);
+        let mut words = vec![vec!['b', 'a'], vec!['b'], vec!['a', 'b'], vec![]];
+        llex_sort(&mut words);
+        assert_eq!(
+            words,
+            vec![vec![], vec!['b'], vec!['a', 'b'], vec!['b', 'a']]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Constructions and analyses of right congruences, such as the canonical right congruence of
+//! a regular or omega-regular language.
+
+use std::{collections::VecDeque, hash::Hash};
+
+use automata::{prelude::*, Map, RightCongruence, Set};
+use itertools::Itertools;
+use tracing::trace;
+
+/// Computation of the Myhill–Nerode right congruence of a DFA.
+pub mod nerode;
+pub use nerode::NerodeCongruence;
+
+/// Computation of the canonical right congruence of an omega-regular language.
+pub mod omega;
+pub use omega::OmegaCongruence;
+
+/// Membership semantics for families of right congruences.
+pub mod forc;
+pub use forc::{ForcMembership, ForcSemantics};
+
+/// Construction of the canonical families of DFAs of an omega-regular language.
+pub mod fdfa;
+pub use fdfa::{FdfaConstruction, FdfaKind, FDFA};
+
+/// Common refinements and structural comparison of right congruences.
+pub mod refine;
+pub use refine::CongruenceRefinement;
+
+/// Navigating right congruences by the minimal representatives of their classes.
+pub mod llex;
+pub use llex::{llex_cmp, llex_sort, ClassNavigation};
+
+/// Transition and syntactic monoids together with aperiodicity checks.
+pub mod monoid;
+pub use monoid::{GroupWitness, SyntacticMonoid, TransitionMonoid};
+
+/// Caching of the looping words of the classes of a right congruence.
+pub mod progress;
+pub use progress::ProgressCache;
+
+/// Bounded exploration of right congruences that are defined on the fly.
+pub mod bounded;
+pub use bounded::{explore_bounded, BoundedCongruence};
+
+/// Incremental maintenance of the minimal representatives of classes.
+pub mod incremental;
+pub use incremental::IncrementalCongruence;
+
+/// Transactional modification of right congruences with rollback.
+pub mod transaction;
+pub use transaction::{Mutation, SproutableExt, Transaction};
+
+/// The nodes of a deterministic graph that are reachable from an initial node, explored in
+/// breadth-first order. Symbols are taken in the order of the alphabet, so the word with which a
+/// node is first reached is its minimal representative in the length-lexicographic order. When
+/// exploring a transition system, missing transitions lead to a virtual sink, which is
+/// represented by `None`.
+pub(crate) struct Exploration<N, S> {
+    /// The nodes in the order in which they were reached.
+    pub order: Vec<N>,
+    /// The minimal representative of each node.
+    pub representative: Map<N, Vec<S>>,
+    /// The successors of each node, in the order of the alphabet.
+    pub successors: Map<N, Vec<N>>,
+}
+
+impl<Idx: IndexType, S: Symbol> Exploration<Option<Idx>, S> {
+    /// Explores `ts` starting from its initial state.
+    pub fn new<D>(ts: &D) -> Self
+    where
+        D: Deterministic<StateIndex = Idx> + Pointed,
+        D::Alphabet: Alphabet<Symbol = S>,
+    {
+        let symbols = ts.alphabet().universe().collect_vec();
+        Self::from_fn(Some(ts.initial()), &symbols, |q, sym| {
+            q.and_then(|q| ts.successor_index(q, sym))
+        })
+    }
+
+    /// Returns `true` if some reachable state is missing a transition.
+    pub fn is_incomplete(&self) -> bool {
+        self.order.contains(&None)
+    }
+}
+
+impl<N: Clone + Eq + Hash, S: Symbol> Exploration<N, S> {
+    /// Explores the graph given by `successor` starting from `initial`.
+    pub fn from_fn<F>(initial: N, symbols: &[S], successor: F) -> Self
+    where
+        F: Fn(&N, S) -> N,
+    {
+        let mut order = vec![initial.clone()];
+        let mut representative: Map<N, Vec<S>> = Map::default();
+        representative.insert(initial.clone(), vec![]);
+        let mut successors: Map<N, Vec<N>> = Map::default();
+        let mut queue = VecDeque::from([initial]);
+        while let Some(q) = queue.pop_front() {
+            let succs = symbols.iter().map(|sym| successor(&q, *sym)).collect_vec();
+            for (sym, p) in symbols.iter().zip(&succs) {
+                if !representative.contains_key(p) {
+                    let mut rep = representative[&q].clone();
+                    rep.push(*sym);
+                    representative.insert(p.clone(), rep);
+                    order.push(p.clone());
+                    queue.push_back(p.clone());
+                }
+            }
+            successors.insert(q, succs);
+        }
+        Self {
+            order,
+            representative,
+            successors,
+        }
+    }
+
+    /// Moore-style partition refinement, starting from the partition given by `key`. Two nodes
+    /// end up in the same class if and only if the keys of all pairs of nodes reached on the
+    /// same words agree. Classes are numbered in the order of their first node.
+    pub fn refine<K, F>(&self, key: F) -> Map<N, usize>
+    where
+        K: Eq + Hash,
+        F: Fn(&N) -> K,
+    {
+        let mut keys: Map<K, usize> = Map::default();
+        let mut class: Map<N, usize> = Map::default();
+        for q in &self.order {
+            let next = keys.len();
+            class.insert(q.clone(), *keys.entry(key(q)).or_insert(next));
+        }
+        let mut count = keys.len();
+        let mut iteration = 0;
+        loop {
+            iteration += 1;
+            let mut signatures: Map<(usize, Vec<usize>), usize> = Map::default();
+            let refined: Map<_, _> = self
+                .order
+                .iter()
+                .map(|q| {
+                    let signature = (
+                        class[q],
+                        self.successors[q].iter().map(|p| class[p]).collect_vec(),
+                    );
+                    let next = signatures.len();
+                    (q.clone(), *signatures.entry(signature).or_insert(next))
+                })
+                .collect();
+            class = refined;
+            if signatures.len() == count {
+                break;
+            }
+            count = signatures.len();
+        }
+        trace!("Partition with {count} classes stabilized after {iteration} iterations");
+        class
+    }
+
+    /// Builds the right congruence whose classes are given by `class`, which must be
+    /// compatible with the transitions. Each class is labelled with the minimal representative
+    /// of its nodes and classes are numbered in the order of these representatives. Returns
+    /// the congruence together with a map from the given class numbers to its states.
+    pub fn into_congruence<A: Alphabet<Symbol = S>>(
+        self,
+        alphabet: A,
+        class: &Map<N, usize>,
+    ) -> (RightCongruence<A>, Map<usize, usize>) {
+        let symbols = alphabet.universe().collect_vec();
+        let mut cong = RightCongruence::new(alphabet);
+        // the first node of a class in the exploration order carries the minimal
+        // representative of the class
+        let mut index: Map<usize, usize> = Map::default();
+        for q in &self.order {
+            if !index.contains_key(&class[q]) {
+                index.insert(
+                    class[q],
+                    cong.add_state((self.representative[q].clone(), Void)),
+                );
+            }
+        }
+        let mut done = Set::default();
+        for q in &self.order {
+            if !done.insert(class[q]) {
+                continue;
+            }
+            for (sym, p) in symbols.iter().zip(&self.successors[q]) {
+                cong.add_edge(
+                    index[&class[q]],
+                    A::expression(*sym),
+                    index[&class[p]],
+                    Void,
+                );
+            }
+        }
+        (cong, index)
+    }
+}
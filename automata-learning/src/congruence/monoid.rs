@@ -0,0 +1,227 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, RightCongruence};
+use itertools::Itertools;
+use tracing::debug;
+
+use super::NerodeCongruence;
+
+/// A transformation of the classes of a right congruence, where the `i`-th entry is the class
+/// that is reached from class `i`.
+pub type Transformation = Vec<usize>;
+
+fn compose(left: &Transformation, right: &Transformation) -> Transformation {
+    left.iter().map(|q| right[*q]).collect()
+}
+
+/// The transition monoid of a complete right congruence, which consists of the
+/// transformations induced by all words. Each element is stored together with its minimal
+/// representative in the length-lexicographic order, the first element is the identity,
+/// which is induced by the empty word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitionMonoid<S> {
+    elements: Vec<(Vec<S>, Transformation)>,
+    index: Map<Transformation, usize>,
+    generators: Vec<(S, usize)>,
+}
+
+/// Witnesses that a monoid contains a non-trivial group, and hence is not aperiodic. The
+/// powers `w^index, ..., w^(index + period - 1)` of `word` are pairwise distinct and form a
+/// cyclic group of order `period > 1`, in particular `w^index = w^(index + period)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupWitness<S> {
+    /// The word that generates the group.
+    pub word: Vec<S>,
+    /// The least exponent that lies in the group.
+    pub index: usize,
+    /// The order of the group.
+    pub period: usize,
+}
+
+impl<S: Symbol> TransitionMonoid<S> {
+    /// Computes the transition monoid of a right congruence, which must be complete. Elements
+    /// are enumerated breadth-first, so the number of elements is bounded by `n^n` for `n`
+    /// classes, but in practice it is usually much smaller.
+    pub fn new<A: Alphabet<Symbol = S>>(cong: &RightCongruence<A>) -> Self {
+        let states = cong.state_indices().sorted().collect_vec();
+        let position: Map<_, _> = states.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+        let generators = cong
+            .alphabet()
+            .universe()
+            .map(|sym| {
+                let transformation = states
+                    .iter()
+                    .map(|q| {
+                        position[&cong
+                            .successor_index(*q, sym)
+                            .expect("Congruence must be complete")]
+                    })
+                    .collect_vec();
+                (sym, transformation)
+            })
+            .collect_vec();
+
+        let identity = (0..states.len()).collect_vec();
+        let mut elements = vec![(vec![], identity.clone())];
+        let mut index = Map::default();
+        index.insert(identity, 0);
+        let mut queue = VecDeque::from([0]);
+        while let Some(i) = queue.pop_front() {
+            for (sym, generator) in &generators {
+                let product = compose(&elements[i].1, generator);
+                if !index.contains_key(&product) {
+                    let mut word = elements[i].0.clone();
+                    word.push(*sym);
+                    index.insert(product.clone(), elements.len());
+                    queue.push_back(elements.len());
+                    elements.push((word, product));
+                }
+            }
+        }
+        let generators = generators
+            .into_iter()
+            .map(|(sym, generator)| (sym, index[&generator]))
+            .collect();
+        debug!(
+            "Transition monoid of congruence with {} classes has {} elements",
+            states.len(),
+            elements.len()
+        );
+        Self {
+            elements,
+            index,
+            generators,
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn size(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns the elements together with their minimal representatives.
+    pub fn elements(&self) -> impl Iterator<Item = &(Vec<S>, Transformation)> + '_ {
+        self.elements.iter()
+    }
+
+    /// Returns the element that is induced by `word`, if all symbols of the word are known.
+    pub fn element_of(&self, word: &[S]) -> Option<usize> {
+        word.iter().try_fold(0, |element, sym| {
+            let (_, generator) = self.generators.iter().find(|(s, _)| s == sym)?;
+            Some(self.multiply(element, *generator))
+        })
+    }
+
+    /// Multiplies two elements, where `multiply(x, y)` corresponds to reading `x` first.
+    pub fn multiply(&self, left: usize, right: usize) -> usize {
+        self.index[&compose(&self.elements[left].1, &self.elements[right].1)]
+    }
+
+    /// Returns `true` if `element` is idempotent, i.e. equal to its square.
+    pub fn is_idempotent(&self, element: usize) -> bool {
+        self.multiply(element, element) == element
+    }
+
+    /// Computes the index and the period of `element`, i.e. the least `i` and `p > 0` with
+    /// `x^i = x^(i + p)`.
+    pub fn index_and_period(&self, element: usize) -> (usize, usize) {
+        let mut exponent: Map<usize, usize> = Map::default();
+        let mut power = element;
+        for k in 1.. {
+            if let Some(i) = exponent.get(&power) {
+                return (*i, k - i);
+            }
+            exponent.insert(power, k);
+            power = self.multiply(power, element);
+        }
+        unreachable!("The monoid is finite")
+    }
+
+    /// Searches for a non-trivial group, which exists if and only if some element has a
+    /// period greater than one.
+    pub fn nontrivial_group(&self) -> Option<GroupWitness<S>> {
+        (0..self.size()).find_map(|element| {
+            let (index, period) = self.index_and_period(element);
+            (period > 1).then(|| GroupWitness {
+                word: self.elements[element].0.clone(),
+                index,
+                period,
+            })
+        })
+    }
+
+    /// Returns `true` if the monoid is aperiodic, i.e. group-free.
+    pub fn is_aperiodic(&self) -> bool {
+        self.nontrivial_group().is_none()
+    }
+}
+
+/// Structural analysis of the language of a DFA through its syntactic monoid, which is the
+/// transition monoid of the minimal DFA.
+pub trait SyntacticMonoid: DFALike {
+    /// Computes the syntactic monoid of the accepted language.
+    fn syntactic_monoid(&self) -> TransitionMonoid<SymbolOf<Self>> {
+        TransitionMonoid::new(&self.right_congruence())
+    }
+
+    /// Returns `true` if the accepted language is star-free, which by the theorem of
+    /// Schützenberger holds if and only if its syntactic monoid is aperiodic.
+    fn is_aperiodic(&self) -> bool {
+        self.syntactic_monoid().is_aperiodic()
+    }
+
+    /// Returns a witness for a non-trivial group in the syntactic monoid, if it exists.
+    fn nontrivial_group(&self) -> Option<GroupWitness<SymbolOf<Self>>> {
+        self.syntactic_monoid().nontrivial_group()
+    }
+}
+
+impl<D: DFALike> SyntacticMonoid for D {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{GroupWitness, SyntacticMonoid};
+
+    #[test]
+    fn aperiodicity() {
+        // even number of a's, which is the prototypical language that is not star-free
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+        let monoid = dfa.syntactic_monoid();
+        assert_eq!(monoid.size(), 2);
+        assert_eq!(monoid.element_of(&['b', 'b']), Some(0));
+        assert!(!dfa.is_aperiodic());
+        assert_eq!(
+            dfa.nontrivial_group(),
+            Some(GroupWitness {
+                word: vec!['a'],
+                index: 1,
+                period: 2
+            })
+        );
+
+        // words containing ab
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q1, Void);
+        dfa.add_edge(q1, 'b', q2, Void);
+        dfa.add_edge(q2, 'a', q2, Void);
+        dfa.add_edge(q2, 'b', q2, Void);
+        let monoid = dfa.syntactic_monoid();
+        assert!(monoid.is_aperiodic());
+        let ab = monoid.element_of(&['a', 'b']).unwrap();
+        assert!(monoid.is_idempotent(ab));
+        assert_eq!(monoid.index_and_period(ab), (1, 1));
+    }
+}
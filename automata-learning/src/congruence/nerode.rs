@@ -0,0 +1,71 @@
+use automata::{prelude::*, RightCongruence};
+
+use super::Exploration;
+
+/// Computes the canonical right congruence of the language accepted by a DFA, i.e. the
+/// Myhill–Nerode congruence. In contrast to `build_right_congruence`, which merely copies the
+/// structure of a transition system, equivalent states are merged, so the result is the same
+/// for any two DFAs accepting the same language.
+pub trait NerodeCongruence: DFALike {
+    /// Computes the Myhill–Nerode right congruence of the accepted language. Only states that
+    /// are reachable from the initial state are taken into account and missing transitions are
+    /// treated as leading to a rejecting sink, so the result is always complete. Equivalent
+    /// states are found with Moore-style partition refinement.
+    ///
+    /// Each class is labelled with its minimal representative in the length-lexicographic
+    /// order, where symbols are ordered as in the alphabet, and the classes are numbered in
+    /// the order of these representatives. In particular, class 0 is the class of the empty
+    /// word.
+    fn right_congruence(&self) -> RightCongruence<Self::Alphabet> {
+        let exploration = Exploration::new(self);
+        let class = exploration.refine(|q| q.is_some_and(|q| self.state_color(q) == Some(true)));
+        exploration
+            .into_congruence(self.alphabet().clone(), &class)
+            .0
+    }
+}
+
+impl<D: DFALike> NerodeCongruence for D {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+    use itertools::Itertools;
+
+    use super::NerodeCongruence;
+
+    fn class_names<A: Alphabet<Symbol = char>>(cong: &automata::RightCongruence<A>) -> Vec<String> {
+        cong.state_indices()
+            .sorted()
+            .map(|q| cong.class_name(q).unwrap().to_vec().into_iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn nerode_merges_equivalent_states() {
+        // even number of a's, but with four states
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let states = [true, false, true, false].map(|c| dfa.add_state(c));
+        for i in 0..4 {
+            dfa.add_edge(states[i], 'a', states[(i + 1) % 4], Void);
+            dfa.add_edge(states[i], 'b', states[i], Void);
+        }
+        let cong = dfa.right_congruence();
+        assert_eq!(cong.size(), 2);
+        assert_eq!(class_names(&cong), vec!["", "a"]);
+    }
+
+    #[test]
+    fn nerode_completes_with_sink() {
+        // accepts exactly the word a
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_edge(q0, 'a', q1, Void);
+        let cong = dfa.right_congruence();
+        assert_eq!(class_names(&cong), vec!["", "a", "b"]);
+        for q in cong.state_indices() {
+            assert_eq!(cong.edges_from(q).unwrap().count(), 2);
+        }
+    }
+}
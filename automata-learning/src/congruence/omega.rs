@@ -0,0 +1,93 @@
+use automata::{prelude::*, Map, RightCongruence};
+use tracing::trace;
+
+use super::Exploration;
+
+/// Computes the canonical right congruence of an omega-regular language from a deterministic
+/// parity automaton recognizing it. Two words are congruent if and only if they have the same
+/// residual language, which is the leading congruence that FORC and FDFA based learners start
+/// from. A DBA can be used by viewing it as a DPA with priorities 0 and 1.
+pub trait OmegaCongruence: DPALike {
+    /// Computes the right congruence of the recognized omega language, where two reachable
+    /// states are merged if they recognize the same residual language. Equivalence is decided
+    /// with [`separate`](automata::automaton::IntoDPA::separate), i.e. the equivalence test
+    /// for DPAs.
+    ///
+    /// As for the [`NerodeCongruence`](super::NerodeCongruence), classes are labelled with
+    /// their length-lexicographically minimal representatives and numbered in that order.
+    /// Panics if the automaton is not complete.
+    fn omega_right_congruence(&self) -> RightCongruence<Self::Alphabet> {
+        let exploration = Exploration::new(self);
+        assert!(
+            !exploration.is_incomplete(),
+            "The DPA must be complete for computing its right congruence"
+        );
+        let dpa = self.into_dpa();
+
+        // we compare each state with one state of each class found so far, which is enough
+        // as language equivalence is transitive
+        let mut leaders: Vec<Self::StateIndex> = vec![];
+        let mut class: Map<_, usize> = Map::default();
+        for q in exploration.order.iter().flatten() {
+            let found = leaders.iter().position(|p| dpa.separate(*p, *q).is_none());
+            let index = found.unwrap_or_else(|| {
+                leaders.push(*q);
+                leaders.len() - 1
+            });
+            class.insert(Some(*q), index);
+        }
+        trace!(
+            "Merged {} reachable states into {} classes",
+            exploration.order.len(),
+            leaders.len()
+        );
+
+        exploration
+            .into_congruence(self.alphabet().clone(), &class)
+            .0
+    }
+}
+
+impl<D: DPALike> OmegaCongruence for D {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+    use itertools::Itertools;
+
+    use super::OmegaCongruence;
+
+    #[test]
+    fn omega_congruence_merges_residuals() {
+        // infinitely many a's, recognized by two identical copies
+        let dpa: DPA = NTS::builder()
+            .with_transitions([
+                (0, 'a', 0, 1),
+                (0, 'b', 1, 0),
+                (1, 'a', 0, 0),
+                (1, 'b', 1, 1),
+            ])
+            .into_dpa(0);
+        assert_eq!(dpa.omega_right_congruence().size(), 1);
+
+        // only a^ω is accepted, state 2 is unreachable
+        let dpa: DPA = NTS::builder()
+            .with_transitions([
+                (0, 'a', 0, 0),
+                (0, 'b', 1, 1),
+                (1, 'a', 3, 1),
+                (1, 'b', 1, 1),
+                (2, 'a', 0, 2),
+                (2, 'b', 1, 2),
+            ])
+            .into_dpa(0);
+        let cong = dpa.omega_right_congruence();
+        assert_eq!(
+            cong.state_indices()
+                .sorted()
+                .map(|q| cong.class_name(q).unwrap().to_vec())
+                .collect_vec(),
+            vec![vec![], vec!['b']]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::cell::OnceCell;
+
+use automata::{prelude::*, Map, RightCongruence};
+use tracing::trace;
+
+/// A right congruence together with a lazily filled cache of the DFAs that accept the looping
+/// words of each class, i.e. the words `v` with `uv ~ u` for the class `u`. Building such a DFA
+/// requires a copy of the whole congruence, so it is computed at most once per class. The
+/// congruence can only be modified through [`Self::cong_mut`], which invalidates the cache,
+/// so the cached DFAs never get out of sync with the transitions.
+#[derive(Clone)]
+pub struct ProgressCache<A: Alphabet> {
+    cong: RightCongruence<A>,
+    // the outer cell is filled with one cell per class on first access after a modification
+    looping: OnceCell<Map<usize, OnceCell<DFA<A>>>>,
+}
+
+impl<A: Alphabet> ProgressCache<A> {
+    /// Wraps the given congruence, no DFAs are built until they are requested.
+    pub fn new(cong: RightCongruence<A>) -> Self {
+        Self {
+            cong,
+            looping: OnceCell::new(),
+        }
+    }
+
+    /// Returns the underlying congruence.
+    pub fn cong(&self) -> &RightCongruence<A> {
+        &self.cong
+    }
+
+    /// Gives mutable access to the underlying congruence, e.g. for adding states and edges with
+    /// [`Sproutable`](automata::transition_system::Sproutable). As the looping words of every
+    /// class may change, all cached DFAs are dropped.
+    pub fn cong_mut(&mut self) -> &mut RightCongruence<A> {
+        trace!("Invalidating {} cached looping word DFAs", self.cached());
+        self.looping = OnceCell::new();
+        &mut self.cong
+    }
+
+    /// Consumes the cache and returns the underlying congruence.
+    pub fn into_inner(self) -> RightCongruence<A> {
+        self.cong
+    }
+
+    /// Returns the DFA accepting the looping words of `class`, which is built on the first
+    /// call. Returns `None` if the class does not exist.
+    pub fn looping_words(&self, class: usize) -> Option<&DFA<A>> {
+        let name = self.cong.class_name(class)?;
+        let cell = self
+            .looping
+            .get_or_init(|| {
+                self.cong
+                    .state_indices()
+                    .map(|q| (q, OnceCell::new()))
+                    .collect()
+            })
+            .get(&class)?;
+        Some(cell.get_or_init(|| {
+            trace!("Building looping word DFA for class {class}");
+            self.cong.looping_words(name)
+        }))
+    }
+
+    /// Returns the DFAs accepting the looping words of all classes, indexed by class.
+    pub fn all_looping_words(&self) -> Map<usize, DFA<A>> {
+        self.cong
+            .state_indices()
+            .filter_map(|q| self.looping_words(q).map(|dfa| (q, dfa.clone())))
+            .collect()
+    }
+
+    /// Returns the number of classes whose DFA is currently cached.
+    pub fn cached(&self) -> usize {
+        self.looping.get().map_or(0, |cells| {
+            cells.values().filter(|cell| cell.get().is_some()).count()
+        })
+    }
+}
+
+impl<A: Alphabet> From<RightCongruence<A>> for ProgressCache<A> {
+    fn from(cong: RightCongruence<A>) -> Self {
+        Self::new(cong)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, RightCongruence};
+
+    use super::ProgressCache;
+
+    #[test]
+    fn cached_looping_words() {
+        // counts the a's modulo two
+        let mut cong = RightCongruence::new(alphabet!(simple 'a', 'b'));
+        let even = cong.add_state((vec![], Void));
+        let odd = cong.add_state((vec!['a'], Void));
+        cong.add_edge(even, 'a', odd, Void);
+        cong.add_edge(odd, 'a', even, Void);
+        cong.add_edge(even, 'b', even, Void);
+
+        let mut cache = ProgressCache::new(cong);
+        assert_eq!(cache.cached(), 0);
+        let dfa = cache.looping_words(odd).unwrap();
+        assert!(dfa.accepts("aa"));
+        assert!(!dfa.accepts("b"));
+        assert_eq!(cache.cached(), 1);
+        assert!(cache.looping_words(42).is_none());
+
+        let all = cache.all_looping_words();
+        assert_eq!(all.len(), 2);
+        assert!(all[&even].accepts("baab"));
+        assert_eq!(cache.cached(), 2);
+
+        // adding the missing transition changes the looping words of the odd class
+        cache.cong_mut().add_edge(odd, 'b', odd, Void);
+        assert_eq!(cache.cached(), 0);
+        assert!(cache.looping_words(odd).unwrap().accepts("b"));
+    }
+}
@@ -0,0 +1,111 @@
+use automata::{prelude::*, Map, RightCongruence};
+use itertools::Itertools;
+
+use super::Exploration;
+
+/// Structural comparison of right congruences over the same alphabet. Congruences are
+/// compared by the words they put into the same class, so the indices of their classes do
+/// not matter. Missing transitions are treated as leading to an additional sink class.
+pub trait CongruenceRefinement {
+    /// The alphabet of the congruence.
+    type Alphabet: Alphabet;
+
+    /// Computes the coarsest common refinement, i.e. the congruence in which two words are
+    /// equivalent if and only if they are equivalent in both congruences. Its classes are
+    /// labelled with their minimal representatives, see
+    /// [`NerodeCongruence`](super::NerodeCongruence).
+    fn refine_with(
+        &self,
+        other: &RightCongruence<Self::Alphabet>,
+    ) -> RightCongruence<Self::Alphabet>;
+
+    /// Returns `true` if every class of `self` is contained in a class of `other`.
+    fn is_refinement_of(&self, other: &RightCongruence<Self::Alphabet>) -> bool;
+
+    /// Returns `true` if both congruences induce the same partition of all words.
+    fn same_partition(&self, other: &RightCongruence<Self::Alphabet>) -> bool;
+}
+
+/// Explores the product of two congruences, starting from the pair of their initial classes.
+fn product<A: Alphabet>(
+    left: &RightCongruence<A>,
+    right: &RightCongruence<A>,
+) -> Exploration<(Option<usize>, Option<usize>), A::Symbol> {
+    let symbols = left.alphabet().universe().collect_vec();
+    Exploration::from_fn(
+        (Some(left.initial()), Some(right.initial())),
+        &symbols,
+        |(p, q), sym| {
+            (
+                p.and_then(|p| left.successor_index(p, sym)),
+                q.and_then(|q| right.successor_index(q, sym)),
+            )
+        },
+    )
+}
+
+impl<A: Alphabet> CongruenceRefinement for RightCongruence<A> {
+    type Alphabet = A;
+
+    fn refine_with(&self, other: &RightCongruence<A>) -> RightCongruence<A> {
+        let exploration = product(self, other);
+        let class: Map<_, _> = exploration
+            .order
+            .iter()
+            .enumerate()
+            .map(|(i, pair)| (*pair, i))
+            .collect();
+        exploration
+            .into_congruence(self.alphabet().clone(), &class)
+            .0
+    }
+
+    fn is_refinement_of(&self, other: &RightCongruence<A>) -> bool {
+        let mut image: Map<Option<usize>, Option<usize>> = Map::default();
+        product(self, other)
+            .order
+            .into_iter()
+            .all(|(p, q)| *image.entry(p).or_insert(q) == q)
+    }
+
+    fn same_partition(&self, other: &RightCongruence<A>) -> bool {
+        self.is_refinement_of(other) && other.is_refinement_of(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, RightCongruence};
+
+    use super::CongruenceRefinement;
+
+    /// The congruence that counts the occurrences of `sym` modulo `n`.
+    fn modulo(sym: char, n: usize) -> RightCongruence<CharAlphabet> {
+        let mut cong = RightCongruence::new(alphabet!(simple 'a', 'b'));
+        let mut word = vec![];
+        for _ in 0..n {
+            cong.add_state((word.clone(), Void));
+            word.push(sym);
+        }
+        for i in 0..n {
+            cong.add_edge(i, sym, (i + 1) % n, Void);
+            cong.add_edge(i, if sym == 'a' { 'b' } else { 'a' }, i, Void);
+        }
+        cong
+    }
+
+    #[test]
+    fn common_refinement() {
+        let (a2, b3, a4) = (modulo('a', 2), modulo('b', 3), modulo('a', 4));
+        let refined = a2.refine_with(&b3);
+        assert_eq!(refined.size(), 6);
+        assert!(refined.is_refinement_of(&a2));
+        assert!(refined.is_refinement_of(&b3));
+        assert!(!a2.is_refinement_of(&b3));
+
+        assert!(a4.is_refinement_of(&a2));
+        assert!(!a2.is_refinement_of(&a4));
+        assert!(a4.refine_with(&a2).same_partition(&a4));
+        assert!(!a4.same_partition(&a2));
+    }
+}
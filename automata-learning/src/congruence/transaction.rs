@@ -0,0 +1,296 @@
+use automata::{prelude::*, transition_system::Sproutable};
+use tracing::trace;
+
+/// A single modification that was made within a [`Transaction`], where `Idx` is the type of
+/// state indices and `C` the type of edge colors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mutation<S, Idx, C> {
+    /// A state with the given index was added.
+    AddState(Idx),
+    /// An edge was added, replacing the edge to `previous` if there was one.
+    AddEdge {
+        /// The source of the edge.
+        source: Idx,
+        /// The symbol of the edge.
+        symbol: S,
+        /// The target of the edge.
+        target: Idx,
+        /// The color of the edge.
+        color: C,
+        /// The target and color of the replaced edge.
+        previous: Option<(Idx, C)>,
+    },
+    /// The edge leaving `source` on `symbol` was removed, it led to `previous` if it existed.
+    RemoveEdge {
+        /// The source of the edge.
+        source: Idx,
+        /// The symbol of the edge.
+        symbol: S,
+        /// The target and color of the removed edge.
+        previous: Option<(Idx, C)>,
+    },
+}
+
+/// The mutations that are recorded for the transition system `Ts`.
+type MutationOf<Ts> = Mutation<
+    SymbolOf<Ts>,
+    <Ts as TransitionSystem>::StateIndex,
+    <Ts as TransitionSystem>::EdgeColor,
+>;
+
+/// An edit session on a deterministic transition system such as a right congruence, obtained
+/// from [`SproutableExt::transaction`]. All modifications are recorded and can be undone at
+/// once with [`Self::rollback`], which also happens when the transaction is dropped without
+/// calling [`Self::commit`]. This makes it safe to tentatively extend a congruence in search
+/// algorithms like sprout, even if the code in between returns early.
+///
+/// Edge modifications are undone by applying their inverses. As states can not be removed
+/// from a transition system, a copy is taken before the first state is added, which is
+/// restored on rollback.
+pub struct Transaction<'a, Ts: Sproutable + Deterministic + Clone> {
+    ts: &'a mut Ts,
+    log: Vec<MutationOf<Ts>>,
+    // the transition system before the first state was added, with the length of the log at
+    // that time
+    snapshot: Option<(usize, Ts)>,
+    finished: bool,
+}
+
+impl<'a, Ts: Sproutable + Deterministic + Clone> Transaction<'a, Ts> {
+    fn new(ts: &'a mut Ts) -> Self {
+        Self {
+            ts,
+            log: vec![],
+            snapshot: None,
+            finished: false,
+        }
+    }
+
+    /// Returns the transition system in its current state.
+    pub fn ts(&self) -> &Ts {
+        self.ts
+    }
+
+    /// Returns the modifications made so far, in the order in which they were made.
+    pub fn mutations(&self) -> &[MutationOf<Ts>] {
+        &self.log
+    }
+
+    /// Returns the target and color of the edge leaving `source` on `symbol`.
+    fn edge_on(
+        &self,
+        source: Ts::StateIndex,
+        symbol: SymbolOf<Ts>,
+    ) -> Option<(Ts::StateIndex, Ts::EdgeColor)> {
+        let expression = <Ts::Alphabet as Alphabet>::expression(symbol);
+        self.ts
+            .edges_from(source)?
+            .find(|e| *e.expression() == expression)
+            .map(|e| (e.target(), e.color()))
+    }
+
+    /// Adds a state with the given color and returns its index.
+    pub fn add_state<X: Into<StateColor<Ts>>>(&mut self, color: X) -> Ts::StateIndex {
+        if self.snapshot.is_none() {
+            self.snapshot = Some((self.log.len(), self.ts.clone()));
+        }
+        let q = self.ts.add_state(color.into());
+        self.log.push(Mutation::AddState(q));
+        q
+    }
+
+    /// Adds an edge, an existing edge from `source` on `symbol` is replaced.
+    pub fn add_edge(
+        &mut self,
+        source: Ts::StateIndex,
+        symbol: SymbolOf<Ts>,
+        target: Ts::StateIndex,
+        color: Ts::EdgeColor,
+    ) {
+        let previous = self.edge_on(source, symbol);
+        let expression = <Ts::Alphabet as Alphabet>::expression(symbol);
+        if previous.is_some() {
+            self.ts.remove_edges(source, expression.clone());
+        }
+        self.ts.add_edge(source, expression, target, color.clone());
+        self.log.push(Mutation::AddEdge {
+            source,
+            symbol,
+            target,
+            color,
+            previous,
+        });
+    }
+
+    /// Removes the edge leaving `source` on `symbol`, if it exists.
+    pub fn remove_edge(&mut self, source: Ts::StateIndex, symbol: SymbolOf<Ts>) {
+        let previous = self.edge_on(source, symbol);
+        if previous.is_some() {
+            self.ts
+                .remove_edges(source, <Ts::Alphabet as Alphabet>::expression(symbol));
+        }
+        self.log.push(Mutation::RemoveEdge {
+            source,
+            symbol,
+            previous,
+        });
+    }
+
+    /// Keeps all modifications.
+    pub fn commit(mut self) {
+        trace!("Committing {} mutations", self.log.len());
+        self.finished = true;
+    }
+
+    /// Undoes all modifications.
+    pub fn rollback(mut self) {
+        self.undo();
+    }
+
+    fn undo(&mut self) {
+        trace!("Rolling back {} mutations", self.log.len());
+        let mut log = std::mem::take(&mut self.log);
+        if let Some((length, snapshot)) = self.snapshot.take() {
+            *self.ts = snapshot;
+            log.truncate(length);
+        }
+        for mutation in log.into_iter().rev() {
+            match mutation {
+                Mutation::AddState(_) => unreachable!("Added states are undone by the snapshot"),
+                Mutation::AddEdge {
+                    source,
+                    symbol,
+                    previous,
+                    ..
+                } => {
+                    let expression = <Ts::Alphabet as Alphabet>::expression(symbol);
+                    self.ts.remove_edges(source, expression.clone());
+                    if let Some((p, color)) = previous {
+                        self.ts.add_edge(source, expression, p, color);
+                    }
+                }
+                Mutation::RemoveEdge {
+                    source,
+                    symbol,
+                    previous: Some((p, color)),
+                } => {
+                    self.ts.add_edge(
+                        source,
+                        <Ts::Alphabet as Alphabet>::expression(symbol),
+                        p,
+                        color,
+                    );
+                }
+                Mutation::RemoveEdge { previous: None, .. } => {}
+            }
+        }
+        self.finished = true;
+    }
+}
+
+impl<'a, Ts: Sproutable + Deterministic + Clone> Drop for Transaction<'a, Ts> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.undo();
+        }
+    }
+}
+
+/// Extends deterministic sproutable transition systems, e.g. right congruences and DFAs, with
+/// transactional edit sessions.
+pub trait SproutableExt: Sproutable + Deterministic + Clone {
+    /// Starts a transaction, see [`Transaction`].
+    fn transaction(&mut self) -> Transaction<'_, Self> {
+        Transaction::new(self)
+    }
+}
+
+impl<Ts: Sproutable + Deterministic + Clone> SproutableExt for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, RightCongruence};
+
+    use super::{Mutation, SproutableExt};
+
+    #[test]
+    fn transactions() {
+        let mut cong = RightCongruence::new(alphabet!(simple 'a', 'b'));
+        let eps = cong.add_state((vec![], Void));
+        cong.add_edge(eps, 'a', eps, Void);
+
+        // replacing and removing edges is undone
+        let mut tx = cong.transaction();
+        tx.add_edge(eps, 'b', eps, Void);
+        tx.remove_edge(eps, 'a');
+        assert_eq!(tx.ts().successor_index(eps, 'a'), None);
+        assert_eq!(tx.mutations().len(), 2);
+        tx.rollback();
+        assert_eq!(cong.successor_index(eps, 'a'), Some(eps));
+        assert_eq!(cong.successor_index(eps, 'b'), None);
+
+        // dropping a transaction also rolls it back, including added classes
+        {
+            let mut tx = cong.transaction();
+            tx.add_edge(eps, 'a', eps, Void);
+            let q = tx.add_state((vec!['b'], Void));
+            tx.add_edge(eps, 'b', q, Void);
+            assert_eq!(tx.ts().size(), 2);
+        }
+        assert_eq!(cong.size(), 1);
+        assert_eq!(cong.successor_index(eps, 'b'), None);
+
+        let mut tx = cong.transaction();
+        let q = tx.add_state((vec!['b'], Void));
+        tx.add_edge(eps, 'b', q, Void);
+        assert_eq!(
+            tx.mutations()[1],
+            Mutation::AddEdge {
+                source: eps,
+                symbol: 'b',
+                target: q,
+                color: Void,
+                previous: None
+            }
+        );
+        tx.commit();
+        assert_eq!(cong.size(), 2);
+        assert_eq!(cong.successor_index(eps, 'b'), Some(q));
+    }
+
+    #[test]
+    fn transactions_on_colored_edges() {
+        let mut dba = DBA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dba.add_state(Void);
+        dba.add_edge(q0, 'a', q0, true);
+
+        // the color of a replaced edge is restored on rollback
+        let mut tx = dba.transaction();
+        tx.add_edge(q0, 'a', q0, false);
+        let q1 = tx.add_state(Void);
+        tx.add_edge(q0, 'b', q1, true);
+        assert_eq!(
+            tx.mutations()[0],
+            Mutation::AddEdge {
+                source: q0,
+                symbol: 'a',
+                target: q0,
+                color: false,
+                previous: Some((q0, true))
+            }
+        );
+        tx.rollback();
+        assert_eq!(dba.size(), 1);
+        assert_eq!(dba.successor_index(q0, 'b'), None);
+        assert!(dba
+            .edges_from(q0)
+            .unwrap()
+            .all(|e| *e.expression() == 'a' && e.color()));
+
+        let mut tx = dba.transaction();
+        tx.remove_edge(q0, 'a');
+        assert_eq!(tx.ts().successor_index(q0, 'a'), None);
+        drop(tx);
+        assert_eq!(dba.successor_index(q0, 'a'), Some(q0));
+    }
+}
@@ -0,0 +1,134 @@
+//! A canonical form of alphabets, so that automata which are exchanged between processes agree
+//! on the order of their symbols. The canonical form lists the symbols in ascending order
+//! without repetitions, and symbol indices refer to this order. It is written as the string of
+//! all symbols, e.g. `abc`, and exporters list symbols in this order.
+
+use std::{fmt::Display, str::FromStr};
+
+use automata::prelude::*;
+use itertools::Itertools;
+
+/// Abstracts the types of errors that can occur when parsing a [`CanonicalAlphabet`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum CanonicalAlphabetError {
+    Duplicate(char),
+    Unsorted(char),
+}
+
+impl Display for CanonicalAlphabetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CanonicalAlphabetError::Duplicate(sym) => write!(f, "Symbol {sym} occurs twice"),
+            CanonicalAlphabetError::Unsorted(sym) => {
+                write!(f, "Symbol {sym} is smaller than its predecessor")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CanonicalAlphabetError {}
+
+/// The symbols of an alphabet in ascending order without repetitions, see the module
+/// documentation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CanonicalAlphabet {
+    symbols: Vec<char>,
+}
+
+impl CanonicalAlphabet {
+    /// Creates the canonical form of the given symbols, which may be in any order and contain
+    /// repetitions.
+    pub fn new<I: IntoIterator<Item = char>>(symbols: I) -> Self {
+        Self {
+            symbols: symbols.into_iter().sorted().dedup().collect(),
+        }
+    }
+
+    /// Returns the canonical form of `alphabet`.
+    pub fn of(alphabet: &CharAlphabet) -> Self {
+        Self::new(alphabet.universe())
+    }
+
+    /// Returns the symbols in ascending order.
+    pub fn symbols(&self) -> &[char] {
+        &self.symbols
+    }
+
+    /// Returns the number of symbols.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Returns true if there are no symbols.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Returns the index of `sym`, or `None` if it is not a symbol of the alphabet.
+    pub fn index(&self, sym: char) -> Option<usize> {
+        self.symbols.binary_search(&sym).ok()
+    }
+
+    /// Returns the symbol with the given index.
+    pub fn symbol(&self, index: usize) -> Option<char> {
+        self.symbols.get(index).cloned()
+    }
+
+    /// Returns the alphabet that consists of the symbols.
+    pub fn alphabet(&self) -> CharAlphabet {
+        CharAlphabet::from_iter(self.symbols.iter().cloned())
+    }
+}
+
+impl Display for CanonicalAlphabet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbols.iter().collect::<String>())
+    }
+}
+
+impl FromStr for CanonicalAlphabet {
+    type Err = CanonicalAlphabetError;
+
+    /// Parses the string of all symbols, which must be in ascending order, so that every
+    /// alphabet has exactly one string representation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let symbols = s.chars().collect_vec();
+        for (a, b) in symbols.iter().tuple_windows() {
+            if a == b {
+                return Err(CanonicalAlphabetError::Duplicate(*b));
+            }
+            if a > b {
+                return Err(CanonicalAlphabetError::Unsorted(*b));
+            }
+        }
+        Ok(Self { symbols })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{CanonicalAlphabet, CanonicalAlphabetError};
+
+    #[test]
+    fn canonical_alphabet() {
+        let alphabet = CanonicalAlphabet::of(&alphabet!(simple 'c', 'a', 'b'));
+        assert_eq!(alphabet.symbols(), &['a', 'b', 'c']);
+        assert_eq!(alphabet.index('c'), Some(2));
+        assert_eq!(alphabet.index('d'), None);
+        assert_eq!(alphabet.to_string(), "abc");
+        assert_eq!("abc".parse(), Ok(alphabet.clone()));
+        assert_eq!(CanonicalAlphabet::new("cabba".chars()), alphabet);
+        assert_eq!(
+            "acb".parse::<CanonicalAlphabet>(),
+            Err(CanonicalAlphabetError::Unsorted('b'))
+        );
+        assert_eq!(
+            "abb".parse::<CanonicalAlphabet>(),
+            Err(CanonicalAlphabetError::Duplicate('b'))
+        );
+    }
+}
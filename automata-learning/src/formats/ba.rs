@@ -0,0 +1,279 @@
+//! Reading and writing of the BA format used by RABIT and GOAL. A file consists of
+//! - optionally the initial state on its own line, e.g. `[q0]`,
+//! - one transition per line of the form `a,[q0]->[q1]`, and
+//! - the accepting states, each on its own line.
+//!
+//! If no initial state is given, the source of the first transition is initial. If no accepting
+//! states are given, all states are accepting. Symbols must be single characters.
+
+use std::fmt::Write;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+
+use crate::ordered::SortedTransitionSystem;
+
+/// Abstracts the types of errors that can occur when reading a BA file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum BaError {
+    MalformedLine(usize),
+    MalformedSymbol(String),
+    MissingInitial,
+    Nondeterministic(String, char),
+}
+
+impl std::fmt::Display for BaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BaError::MalformedLine(line) => write!(f, "Malformed line {line}"),
+            BaError::MalformedSymbol(sym) => {
+                write!(
+                    f,
+                    "Malformed symbol {sym}, symbols must be single characters"
+                )
+            }
+            BaError::MissingInitial => write!(f, "No initial state is given"),
+            BaError::Nondeterministic(state, sym) => {
+                write!(f, "State {state} has multiple transitions on {sym}")
+            }
+        }
+    }
+}
+
+/// A transition of a [`BaAutomaton`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaTransition {
+    /// The name of the source state.
+    pub source: String,
+    /// The symbol that is read.
+    pub symbol: char,
+    /// The name of the target state.
+    pub target: String,
+}
+
+/// An automaton as it is stored in a BA file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaAutomaton {
+    /// The name of the initial state.
+    pub initial: String,
+    /// The transitions in the order in which they appear.
+    pub transitions: Vec<BaTransition>,
+    /// The names of the accepting states.
+    pub accepting: Vec<String>,
+}
+
+/// Parses a state of the form `[name]`.
+fn parse_state(state: &str) -> Option<String> {
+    state
+        .trim()
+        .strip_prefix('[')?
+        .strip_suffix(']')
+        .map(|name| name.trim().to_string())
+}
+
+impl TryFrom<&str> for BaAutomaton {
+    type Error = BaError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut initial = None;
+        let mut transitions = vec![];
+        let mut accepting = vec![];
+        for (number, line) in value
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+        {
+            if let Some((symbol, states)) = line.split_once(',') {
+                let (source, target) = states
+                    .split_once("->")
+                    .and_then(|(source, target)| Some((parse_state(source)?, parse_state(target)?)))
+                    .ok_or(BaError::MalformedLine(number))?;
+                let mut chars = symbol.trim().chars();
+                let symbol = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err(BaError::MalformedSymbol(symbol.trim().to_string())),
+                };
+                transitions.push(BaTransition {
+                    source,
+                    symbol,
+                    target,
+                });
+            } else {
+                let state = parse_state(line).ok_or(BaError::MalformedLine(number))?;
+                if transitions.is_empty() && initial.is_none() {
+                    initial = Some(state);
+                } else {
+                    accepting.push(state);
+                }
+            }
+        }
+
+        let initial = initial
+            .or_else(|| transitions.first().map(|t| t.source.clone()))
+            .ok_or(BaError::MissingInitial)?;
+        let mut automaton = Self {
+            initial,
+            transitions,
+            accepting,
+        };
+        if automaton.accepting.is_empty() {
+            automaton.accepting = automaton.states();
+        }
+        Ok(automaton)
+    }
+}
+
+impl BaAutomaton {
+    /// Builds the representation of a transition system with boolean state colors, i.e. a
+    /// DFA, NFA or state-based Büchi automaton. States that are colored `true` are accepting.
+    pub fn from_acceptor<Ts>(ts: &Ts) -> Option<Self>
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet, StateColor = bool>,
+    {
+        let indices = ts.sorted_state_indices();
+        let name: Map<_, _> = indices
+            .iter()
+            .enumerate()
+            .map(|(i, q)| (*q, format!("q{i}")))
+            .collect();
+        let initial = name[&ts.maybe_initial_state()?].clone();
+        let transitions = ts
+            .sorted_edges()
+            .into_iter()
+            .map(|(source, (symbol, _, target))| BaTransition {
+                source: name[&source].clone(),
+                symbol,
+                target: name[&target].clone(),
+            })
+            .collect();
+        let accepting = indices
+            .iter()
+            .filter(|q| ts.state_color(**q) == Some(true))
+            .map(|q| name[q].clone())
+            .collect();
+        Some(Self {
+            initial,
+            transitions,
+            accepting,
+        })
+    }
+
+    /// Writes the automaton in the BA format. Note that an automaton without accepting states
+    /// can not be represented faithfully, as reading it back makes all states accepting.
+    pub fn to_ba(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "[{}]", self.initial).unwrap();
+        for t in &self.transitions {
+            writeln!(out, "{},[{}]->[{}]", t.symbol, t.source, t.target).unwrap();
+        }
+        for q in &self.accepting {
+            writeln!(out, "[{q}]").unwrap();
+        }
+        out
+    }
+
+    /// Returns the names of all states, the initial state comes first and the remaining ones
+    /// follow in the order of their first appearance.
+    pub fn states(&self) -> Vec<String> {
+        std::iter::once(&self.initial)
+            .chain(self.transitions.iter().flat_map(|t| [&t.source, &t.target]))
+            .chain(self.accepting.iter())
+            .unique()
+            .cloned()
+            .collect()
+    }
+
+    /// Collects the symbols that appear on transitions into an alphabet.
+    pub fn alphabet(&self) -> CharAlphabet {
+        self.transitions
+            .iter()
+            .map(|t| t.symbol)
+            .sorted()
+            .dedup()
+            .collect_vec()
+            .into()
+    }
+
+    /// Converts the automaton into an [`NTS`] whose states are colored with `true` if they are
+    /// accepting. The state with index `i` corresponds to the `i`-th element of
+    /// [`BaAutomaton::states`], so the initial state has index 0.
+    pub fn into_nfa(&self) -> NTS<CharAlphabet, bool, Void> {
+        let mut ts = NTS::new_for_alphabet(self.alphabet());
+        let mut indices = Map::default();
+        for q in self.states() {
+            let idx = ts.add_state(self.accepting.contains(&q));
+            indices.insert(q, idx);
+        }
+        for t in &self.transitions {
+            ts.add_edge(indices[&t.source], t.symbol, indices[&t.target], Void);
+        }
+        ts
+    }
+
+    /// Converts the automaton into a [`DFA`], which fails if some state has more than one
+    /// transition on the same symbol.
+    pub fn into_dfa(&self) -> Result<DFA, BaError> {
+        let mut dfa = DFA::new_for_alphabet(self.alphabet());
+        let mut indices = Map::default();
+        for q in self.states() {
+            let idx = dfa.add_state(self.accepting.contains(&q));
+            indices.insert(q, idx);
+        }
+        let mut seen = Set::default();
+        for t in &self.transitions {
+            if !seen.insert((t.source.clone(), t.symbol)) {
+                return Err(BaError::Nondeterministic(t.source.clone(), t.symbol));
+            }
+            dfa.add_edge(indices[&t.source], t.symbol, indices[&t.target], Void);
+        }
+        Ok(dfa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{BaAutomaton, BaError};
+
+    #[test]
+    fn ba_round_trip() {
+        let parsed = BaAutomaton::try_from(
+            "[init]\na,[init]->[odd]\nb,[init]->[init]\na,[odd]->[init]\nb,[odd]->[odd]\n[init]\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.states(), vec!["init", "odd"]);
+        assert_eq!(parsed.accepting, vec!["init"]);
+        let dfa = parsed.into_dfa().unwrap();
+        assert!(dfa.accepts("abab"));
+        assert!(!dfa.accepts("ab"));
+
+        let written = BaAutomaton::from_acceptor(&dfa).unwrap().to_ba();
+        let restored = BaAutomaton::try_from(written.as_str())
+            .unwrap()
+            .into_dfa()
+            .unwrap();
+        for word in ["", "a", "ab", "aba", "bbaab"] {
+            assert_eq!(restored.accepts(word), dfa.accepts(word));
+        }
+    }
+
+    #[test]
+    fn ba_defaults() {
+        // without an initial state and accepting states, the first source is initial and all
+        // states are accepting
+        let parsed = BaAutomaton::try_from("a,[p]->[q]\na,[p]->[p]").unwrap();
+        assert_eq!(parsed.initial, "p");
+        assert_eq!(parsed.accepting, vec!["p", "q"]);
+        assert_eq!(
+            parsed.into_dfa().map(|_| ()),
+            Err(BaError::Nondeterministic("p".to_string(), 'a'))
+        );
+        assert_eq!(
+            BaAutomaton::try_from("ab,[p]->[q]"),
+            Err(BaError::MalformedSymbol("ab".to_string()))
+        );
+    }
+}
@@ -0,0 +1,656 @@
+//! Parsing of transition systems from the DOT language used by graphviz.
+//!
+//! Only the subset of DOT that is needed for describing transition systems is supported:
+//! (strict) digraphs containing node, edge and attribute statements, which may be nested
+//! in subgraphs. Ports, undirected edges and HTML labels are not supported. Automata are
+//! read using the following conventions:
+//! - Every node is a state, its name is the node identifier. If a node has a `label` of
+//!   the form `name | color`, then the part after the last `|` is the color of the state.
+//! - The initial state is the target of an edge that leaves a node with `shape=point`,
+//!   `shape=none` or `shape=plaintext`. Such pseudo nodes are not states. Alternatively,
+//!   a node can be marked with `initial=true`.
+//! - A state is accepting if it has `shape=doublecircle` or `accepting=true`.
+//! - Edge labels are a comma separated list of single character symbols, optionally followed
+//!   by `| color` to give the color of the edges.
+//!
+//! Default attributes that are given with `node [...]` statements are applied to all nodes
+//! that are subsequently declared in the same (sub)graph.
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+use tracing::trace;
+
+/// Abstracts the types of errors that can occur when parsing a DOT graph or converting it into
+/// a transition system.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum DotParseError {
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    UndirectedGraph,
+    UnterminatedString,
+    MissingInitial,
+    MultipleInitial(String, String),
+    UnknownNode(String),
+    MalformedSymbol(String),
+    Nondeterministic(String, char),
+}
+
+impl std::fmt::Display for DotParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DotParseError::UnexpectedEnd => write!(f, "Unexpected end of input"),
+            DotParseError::UnexpectedToken(token) => write!(f, "Unexpected token {token}"),
+            DotParseError::UndirectedGraph => write!(f, "Only directed graphs are supported"),
+            DotParseError::UnterminatedString => write!(f, "Unterminated string"),
+            DotParseError::MissingInitial => write!(f, "No initial state is marked"),
+            DotParseError::MultipleInitial(p, q) => {
+                write!(f, "Both {p} and {q} are marked as initial")
+            }
+            DotParseError::UnknownNode(id) => write!(f, "Node {id} is not a state"),
+            DotParseError::MalformedSymbol(sym) => {
+                write!(
+                    f,
+                    "Malformed symbol {sym}, symbols must be single characters"
+                )
+            }
+            DotParseError::Nondeterministic(state, sym) => {
+                write!(f, "State {state} has multiple transitions on {sym}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Id(String),
+    Arrow,
+    Line,
+    Punctuation(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Id(id) => write!(f, "\"{id}\""),
+            Token::Arrow => write!(f, "->"),
+            Token::Line => write!(f, "--"),
+            Token::Punctuation(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+/// Splits the input into tokens, skipping comments. Quoted strings are unescaped and
+/// returned as identifiers, which is how DOT treats them.
+fn tokenize(input: &str) -> Result<Vec<Token>, DotParseError> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line_start = true,
+            c if c.is_whitespace() => {}
+            // lines starting with # are preprocessor output and are ignored
+            '#' if line_start => {
+                chars.by_ref().find(|c| *c == '\n');
+                continue;
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().find(|c| *c == '\n');
+                line_start = true;
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push(Token::Line);
+            }
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some('"') => value.push('"'),
+                            Some('\n') => {}
+                            Some(other) => {
+                                value.push('\\');
+                                value.push(other);
+                            }
+                            None => return Err(DotParseError::UnterminatedString),
+                        },
+                        Some('"') => break,
+                        Some(other) => value.push(other),
+                        None => return Err(DotParseError::UnterminatedString),
+                    }
+                }
+                tokens.push(Token::Id(value));
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' => tokens.push(Token::Punctuation(c)),
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut value = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if next.is_alphanumeric() || *next == '_' || *next == '.' {
+                        value.push(*next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Id(value));
+            }
+            other => return Err(DotParseError::UnexpectedToken(other.to_string())),
+        }
+        if c != '\n' && !c.is_whitespace() {
+            line_start = false;
+        }
+    }
+    Ok(tokens)
+}
+
+/// A node of a [`DotGraph`] together with its attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotNode {
+    /// The identifier of the node.
+    pub id: String,
+    /// The attributes of the node, including the defaults that were active when the node
+    /// was first declared.
+    pub attributes: Map<String, String>,
+}
+
+impl DotNode {
+    /// Returns the value of the attribute `name`, if it is present.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(|value| value.as_str())
+    }
+
+    fn is_pseudo(&self) -> bool {
+        matches!(
+            self.attribute("shape"),
+            Some("point") | Some("none") | Some("plaintext")
+        )
+    }
+
+    fn is_initial(&self) -> bool {
+        self.attribute("initial") == Some("true")
+    }
+
+    fn is_accepting(&self) -> bool {
+        self.attribute("shape") == Some("doublecircle")
+            || self.attribute("accepting") == Some("true")
+    }
+
+    /// Returns the color of the state, which is the part of the label after the last `|`.
+    fn color(&self) -> Option<String> {
+        self.attribute("label")
+            .and_then(|label| label.rsplit_once('|'))
+            .map(|(_, color)| color.trim().to_string())
+    }
+}
+
+/// A directed edge of a [`DotGraph`] together with its attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotEdge {
+    /// The identifier of the source node.
+    pub source: String,
+    /// The identifier of the target node.
+    pub target: String,
+    /// The attributes of the edge.
+    pub attributes: Map<String, String>,
+}
+
+impl DotEdge {
+    /// Returns the value of the attribute `name`, if it is present.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(|value| value.as_str())
+    }
+
+    /// Splits the label into the symbols and the (optional) color of the edge.
+    fn symbols_and_color(&self) -> Result<(Vec<char>, Option<String>), DotParseError> {
+        let label = self.attribute("label").unwrap_or_default();
+        let (symbols, color) = match label.rsplit_once('|') {
+            Some((symbols, color)) => (symbols, Some(color.trim().to_string())),
+            None => (label, None),
+        };
+        let symbols = symbols
+            .split(',')
+            .map(|sym| sym.trim())
+            .filter(|sym| !sym.is_empty())
+            .map(|sym| {
+                let mut chars = sym.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(DotParseError::MalformedSymbol(sym.to_string())),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((symbols, color))
+    }
+}
+
+/// A directed graph in the DOT language, which can be converted into a transition system or
+/// a [`DFA`] following the conventions outlined in the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DotGraph {
+    /// The name of the graph, which is empty if none is given.
+    pub name: String,
+    /// The nodes in the order in which they are first mentioned.
+    pub nodes: Vec<DotNode>,
+    /// The edges in the order in which they appear.
+    pub edges: Vec<DotEdge>,
+}
+
+struct DotParser {
+    tokens: Vec<Token>,
+    position: usize,
+    graph: DotGraph,
+    lookup: Map<String, usize>,
+}
+
+impl DotParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, DotParseError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or(DotParseError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), DotParseError> {
+        let token = self.next()?;
+        if token != expected {
+            return Err(DotParseError::UnexpectedToken(token.to_string()));
+        }
+        Ok(())
+    }
+
+    fn id(&mut self) -> Result<String, DotParseError> {
+        match self.next()? {
+            Token::Id(id) => Ok(id),
+            other => Err(DotParseError::UnexpectedToken(other.to_string())),
+        }
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn graph(&mut self) -> Result<(), DotParseError> {
+        let mut keyword = self.id()?;
+        if keyword.eq_ignore_ascii_case("strict") {
+            keyword = self.id()?;
+        }
+        if keyword.eq_ignore_ascii_case("graph") {
+            return Err(DotParseError::UndirectedGraph);
+        }
+        if !keyword.eq_ignore_ascii_case("digraph") {
+            return Err(DotParseError::UnexpectedToken(keyword));
+        }
+        if let Some(Token::Id(_)) = self.peek() {
+            self.graph.name = self.id()?;
+        }
+        self.expect(Token::Punctuation('{'))?;
+        self.statements(Map::default(), Map::default())?;
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(DotParseError::UnexpectedToken(token.to_string())),
+        }
+    }
+
+    /// Parses a list of attributes of the form `[a=b, c=d][e=f]`.
+    fn attributes(&mut self) -> Result<Map<String, String>, DotParseError> {
+        let mut attributes = Map::default();
+        while self.eat(&Token::Punctuation('[')) {
+            loop {
+                if self.eat(&Token::Punctuation(']')) {
+                    break;
+                }
+                let key = self.id()?;
+                self.expect(Token::Punctuation('='))?;
+                let value = self.id()?;
+                attributes.insert(key, value);
+                if !self.eat(&Token::Punctuation(',')) {
+                    self.eat(&Token::Punctuation(';'));
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn add_node(&mut self, id: &str, attributes: Map<String, String>) {
+        match self.lookup.get(id) {
+            Some(i) => self.graph.nodes[*i].attributes.extend(attributes),
+            None => {
+                self.lookup.insert(id.to_string(), self.graph.nodes.len());
+                self.graph.nodes.push(DotNode {
+                    id: id.to_string(),
+                    attributes,
+                });
+            }
+        }
+    }
+
+    /// Parses statements until the closing brace of the current (sub)graph is reached.
+    fn statements(
+        &mut self,
+        mut node_defaults: Map<String, String>,
+        mut edge_defaults: Map<String, String>,
+    ) -> Result<(), DotParseError> {
+        loop {
+            match self.next()? {
+                Token::Punctuation('}') => return Ok(()),
+                Token::Punctuation(';') => continue,
+                Token::Punctuation('{') => {
+                    self.statements(node_defaults.clone(), edge_defaults.clone())?
+                }
+                Token::Id(keyword) if keyword.eq_ignore_ascii_case("subgraph") => {
+                    if let Some(Token::Id(_)) = self.peek() {
+                        self.id()?;
+                    }
+                    self.expect(Token::Punctuation('{'))?;
+                    self.statements(node_defaults.clone(), edge_defaults.clone())?;
+                }
+                Token::Id(keyword) if keyword.eq_ignore_ascii_case("node") => {
+                    node_defaults.extend(self.attributes()?)
+                }
+                Token::Id(keyword) if keyword.eq_ignore_ascii_case("edge") => {
+                    edge_defaults.extend(self.attributes()?)
+                }
+                Token::Id(keyword) if keyword.eq_ignore_ascii_case("graph") => {
+                    self.attributes()?;
+                }
+                Token::Id(id) => {
+                    if self.eat(&Token::Punctuation('=')) {
+                        // graph attribute such as rankdir=LR, which is irrelevant for us
+                        self.id()?;
+                        continue;
+                    }
+                    let mut chain = vec![id];
+                    loop {
+                        match self.peek() {
+                            Some(Token::Arrow) => {
+                                self.position += 1;
+                                chain.push(self.id()?);
+                            }
+                            Some(Token::Line) => return Err(DotParseError::UndirectedGraph),
+                            _ => break,
+                        }
+                    }
+                    let attributes = self.attributes()?;
+                    if chain.len() == 1 {
+                        let mut node_attributes = node_defaults.clone();
+                        node_attributes.extend(attributes);
+                        self.add_node(&chain[0], node_attributes);
+                    } else {
+                        for id in &chain {
+                            if !self.lookup.contains_key(id) {
+                                self.add_node(id, node_defaults.clone());
+                            }
+                        }
+                        for (source, target) in chain.into_iter().tuple_windows() {
+                            let mut edge_attributes = edge_defaults.clone();
+                            edge_attributes.extend(attributes.clone());
+                            self.graph.edges.push(DotEdge {
+                                source,
+                                target,
+                                attributes: edge_attributes,
+                            });
+                        }
+                    }
+                }
+                other => return Err(DotParseError::UnexpectedToken(other.to_string())),
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for DotGraph {
+    type Error = DotParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut parser = DotParser {
+            tokens: tokenize(value)?,
+            position: 0,
+            graph: DotGraph::default(),
+            lookup: Map::default(),
+        };
+        parser.graph()?;
+        trace!(
+            "Parsed DOT graph with {} nodes and {} edges",
+            parser.graph.nodes.len(),
+            parser.graph.edges.len()
+        );
+        Ok(parser.graph)
+    }
+}
+
+impl DotGraph {
+    /// Returns the node with the given identifier, if it exists.
+    pub fn node(&self, id: &str) -> Option<&DotNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Returns the identifier of the initial state, if there is one.
+    pub fn initial(&self) -> Result<Option<&str>, DotParseError> {
+        let pseudo: Set<_> = self
+            .nodes
+            .iter()
+            .filter(|node| node.is_pseudo())
+            .map(|node| node.id.as_str())
+            .collect();
+        let mut candidates = self
+            .edges
+            .iter()
+            .filter(|edge| pseudo.contains(edge.source.as_str()))
+            .map(|edge| edge.target.as_str())
+            .chain(
+                self.nodes
+                    .iter()
+                    .filter(|node| node.is_initial())
+                    .map(|node| node.id.as_str()),
+            )
+            .unique();
+        match (candidates.next(), candidates.next()) {
+            (Some(p), Some(q)) => Err(DotParseError::MultipleInitial(p.to_string(), q.to_string())),
+            (initial, _) => Ok(initial),
+        }
+    }
+
+    /// Returns the nodes that represent states, i.e. all nodes except the pseudo nodes that mark
+    /// the initial state. The initial state (if it exists) comes first, the remaining states
+    /// are in order of appearance.
+    pub fn states(&self) -> Result<Vec<&DotNode>, DotParseError> {
+        let initial = self.initial()?;
+        Ok(self
+            .nodes
+            .iter()
+            .filter(|node| !node.is_pseudo())
+            .sorted_by_key(|node| Some(node.id.as_str()) != initial)
+            .collect())
+    }
+
+    /// Collects all symbols that appear on edges into an alphabet.
+    pub fn alphabet(&self) -> Result<CharAlphabet, DotParseError> {
+        let mut symbols = vec![];
+        for edge in &self.edges {
+            symbols.extend(edge.symbols_and_color()?.0);
+        }
+        Ok(symbols.into_iter().sorted().dedup().collect_vec().into())
+    }
+
+    /// Converts the graph into a nondeterministic transition system where state and edge colors
+    /// are the strings given in the labels (or empty if none is given). Returns the transition
+    /// system together with the index of the initial state, if one is marked. The state with
+    /// index `i` corresponds to the `i`-th element of [`DotGraph::states`].
+    #[allow(clippy::type_complexity)]
+    pub fn into_ts(
+        &self,
+    ) -> Result<(NTS<CharAlphabet, String, String>, Option<usize>), DotParseError> {
+        let states = self.states()?;
+        let mut ts = NTS::new_for_alphabet(self.alphabet()?);
+        let mut indices = Map::default();
+        for node in &states {
+            let idx = ts.add_state(node.color().unwrap_or_default());
+            indices.insert(node.id.as_str(), idx);
+        }
+        for edge in &self.edges {
+            let (Some(source), Some(target)) = (
+                indices.get(edge.source.as_str()),
+                indices.get(edge.target.as_str()),
+            ) else {
+                continue;
+            };
+            let (symbols, color) = edge.symbols_and_color()?;
+            for sym in symbols {
+                ts.add_edge(*source, sym, *target, color.clone().unwrap_or_default());
+            }
+        }
+        let initial = self
+            .initial()?
+            .map(|initial| {
+                indices
+                    .get(initial)
+                    .copied()
+                    .ok_or_else(|| DotParseError::UnknownNode(initial.to_string()))
+            })
+            .transpose()?;
+        Ok((ts, initial))
+    }
+
+    /// Converts the graph into a [`DFA`]. This fails if no initial state is marked or if some
+    /// state has more than one transition on the same symbol. Missing transitions are simply
+    /// left out, so the resulting DFA may be incomplete.
+    pub fn into_dfa(&self) -> Result<DFA, DotParseError> {
+        let states = self.states()?;
+        if self.initial()?.is_none() {
+            return Err(DotParseError::MissingInitial);
+        }
+        let mut dfa = DFA::new_for_alphabet(self.alphabet()?);
+        let mut indices = Map::default();
+        for node in &states {
+            let idx = dfa.add_state(node.is_accepting());
+            indices.insert(node.id.as_str(), idx);
+        }
+        let mut seen = Set::default();
+        for edge in &self.edges {
+            let (Some(source), Some(target)) = (
+                indices.get(edge.source.as_str()),
+                indices.get(edge.target.as_str()),
+            ) else {
+                continue;
+            };
+            for sym in edge.symbols_and_color()?.0 {
+                if !seen.insert((*source, sym)) {
+                    return Err(DotParseError::Nondeterministic(edge.source.clone(), sym));
+                }
+                dfa.add_edge(*source, sym, *target, Void);
+            }
+        }
+        Ok(dfa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{DotGraph, DotParseError};
+
+    const EVEN_A: &str = r#"
+        // accepts all words with an even number of a's
+        digraph "even a" {
+            rankdir=LR;
+            node [shape = circle];
+            init [label="", shape=point];
+            q0 [shape = doublecircle];
+            init -> q0;
+            q0 -> q1 [label = "a"];
+            q1 -> q0 [label = "a"];
+            subgraph loops {
+                edge [label="b"];
+                q0 -> q0;
+                q1 -> q1;
+            }
+        }
+    "#;
+
+    #[test]
+    fn parse_dot_dfa() {
+        let graph = DotGraph::try_from(EVEN_A).unwrap();
+        assert_eq!(graph.name, "even a");
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 5);
+        assert_eq!(graph.initial(), Ok(Some("q0")));
+
+        let dfa = graph.into_dfa().unwrap();
+        assert_eq!(dfa.size(), 2);
+        assert!(dfa.accepts("abab"));
+        assert!(dfa.accepts(""));
+        assert!(!dfa.accepts("ab"));
+    }
+
+    #[test]
+    fn parse_dot_colors() {
+        let graph = DotGraph::try_from(
+            r#"digraph {
+                q0 [initial=true, label="q0 | 2"];
+                q1 [label="q1|0"];
+                q0 -> q1 [label="a, b | 1"];
+                q1 -> q1 -> q0 [label="c"];
+            }"#,
+        )
+        .unwrap();
+        let (ts, initial) = graph.into_ts().unwrap();
+        assert_eq!(initial, Some(0));
+        assert_eq!(ts.size(), 2);
+        assert_eq!(ts.state_color(0), Some("2".to_string()));
+        assert_eq!(ts.edges_from(0).unwrap().count(), 2);
+        assert_eq!(ts.edges_from(1).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn dot_parse_errors() {
+        assert_eq!(
+            DotGraph::try_from("graph { a -- b }"),
+            Err(DotParseError::UndirectedGraph)
+        );
+        assert_eq!(
+            DotGraph::try_from("digraph { a -> b [label=\"ab\"] }")
+                .unwrap()
+                .into_ts()
+                .map(|_| ()),
+            Err(DotParseError::MalformedSymbol("ab".to_string()))
+        );
+        assert_eq!(
+            DotGraph::try_from("digraph { a -> b }")
+                .unwrap()
+                .into_dfa()
+                .map(|_| ()),
+            Err(DotParseError::MissingInitial)
+        );
+    }
+}
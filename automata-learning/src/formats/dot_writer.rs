@@ -0,0 +1,523 @@
+//! Configurable output of transition systems in the DOT language. The output follows the
+//! conventions that are described in [`super::dot`], so it can be parsed again.
+
+use std::fmt::Write;
+
+use automata::{congruence::FORC, prelude::*, RightCongruence, Set};
+use itertools::Itertools;
+
+use crate::{metadata::StateMetadata, ordered::SortedTransitionSystem};
+
+/// The direction in which graphviz lays out the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RankDir {
+    /// Edges point from left to right, which is the default.
+    #[default]
+    LeftRight,
+    /// Edges point from top to bottom.
+    TopBottom,
+    /// Edges point from right to left.
+    RightLeft,
+    /// Edges point from bottom to top.
+    BottomTop,
+}
+
+impl std::fmt::Display for RankDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                RankDir::LeftRight => "LR",
+                RankDir::TopBottom => "TB",
+                RankDir::RightLeft => "RL",
+                RankDir::BottomTop => "BT",
+            }
+        )
+    }
+}
+
+/// A list of DOT attributes, given as key/value pairs.
+pub type DotAttributes = Vec<(String, String)>;
+
+type ExpressionOf<Ts> = <<Ts as TransitionSystem>::Alphabet as Alphabet>::Expression;
+type StateAttributeFn<'a, Ts> = Box<
+    dyn Fn(
+            <Ts as TransitionSystem>::StateIndex,
+            &<Ts as TransitionSystem>::StateColor,
+        ) -> DotAttributes
+        + 'a,
+>;
+type EdgeAttributeFn<'a, Ts> = Box<
+    dyn Fn(
+            <Ts as TransitionSystem>::StateIndex,
+            &ExpressionOf<Ts>,
+            &<Ts as TransitionSystem>::EdgeColor,
+            <Ts as TransitionSystem>::StateIndex,
+        ) -> DotAttributes
+        + 'a,
+>;
+
+/// Configures how a transition system is rendered by [`ToDot::to_dot_with`]. Options are
+/// set using the builder methods, for example
+/// `DotOptions::new().rankdir(RankDir::TopBottom).cluster_sccs(true)`.
+///
+/// Attributes are applied in order: first the defaults (state names, colors and the
+/// accepting/priority markings), then the closures in the order in which they were added.
+/// Later attributes overwrite earlier ones with the same key.
+pub struct DotOptions<'a, Ts: TransitionSystem> {
+    name: String,
+    rankdir: RankDir,
+    cluster_sccs: bool,
+    show_colors: bool,
+    accepting: Option<Box<dyn Fn(&Ts::StateColor) -> bool + 'a>>,
+    state_priority: Option<Box<dyn Fn(&Ts::StateColor) -> usize + 'a>>,
+    edge_priority: Option<Box<dyn Fn(&Ts::EdgeColor) -> usize + 'a>>,
+    state_attributes: Vec<StateAttributeFn<'a, Ts>>,
+    edge_attributes: Vec<EdgeAttributeFn<'a, Ts>>,
+    metadata: Option<&'a StateMetadata<Ts::StateIndex>>,
+}
+
+impl<'a, Ts: TransitionSystem> Default for DotOptions<'a, Ts> {
+    fn default() -> Self {
+        Self {
+            name: "A".to_string(),
+            rankdir: RankDir::default(),
+            cluster_sccs: false,
+            show_colors: true,
+            accepting: None,
+            state_priority: None,
+            edge_priority: None,
+            state_attributes: vec![],
+            edge_attributes: vec![],
+            metadata: None,
+        }
+    }
+}
+
+impl<'a, Ts: TransitionSystem> DotOptions<'a, Ts> {
+    /// Creates the default options, which lay out the graph from left to right and show
+    /// all state and edge colors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the graph.
+    pub fn name<S: ToString>(mut self, name: S) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Sets the direction of the layout.
+    pub fn rankdir(mut self, rankdir: RankDir) -> Self {
+        self.rankdir = rankdir;
+        self
+    }
+
+    /// If set to `true`, each strongly connected component is put into its own cluster.
+    pub fn cluster_sccs(mut self, cluster: bool) -> Self {
+        self.cluster_sccs = cluster;
+        self
+    }
+
+    /// Determines whether state and edge colors are added to the labels.
+    pub fn show_colors(mut self, show: bool) -> Self {
+        self.show_colors = show;
+        self
+    }
+
+    /// Draws states whose color satisfies the predicate as double circles.
+    pub fn accepting<F: Fn(&Ts::StateColor) -> bool + 'a>(mut self, predicate: F) -> Self {
+        self.accepting = Some(Box::new(predicate));
+        self
+    }
+
+    /// Shows the priority given by `priority` in the label of each state, states with an
+    /// even priority are drawn as double circles.
+    pub fn state_priorities<F: Fn(&Ts::StateColor) -> usize + 'a>(mut self, priority: F) -> Self {
+        self.state_priority = Some(Box::new(priority));
+        self
+    }
+
+    /// Shows the priority given by `priority` in the label of each edge.
+    pub fn edge_priorities<F: Fn(&Ts::EdgeColor) -> usize + 'a>(mut self, priority: F) -> Self {
+        self.edge_priority = Some(Box::new(priority));
+        self
+    }
+
+    /// Labels states with their names from `metadata` instead of their indices, and shows
+    /// their annotations as tooltips.
+    pub fn metadata(mut self, metadata: &'a StateMetadata<Ts::StateIndex>) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Adds a closure that computes additional attributes for each state.
+    pub fn state_attributes<F>(mut self, attributes: F) -> Self
+    where
+        F: Fn(Ts::StateIndex, &Ts::StateColor) -> DotAttributes + 'a,
+    {
+        self.state_attributes.push(Box::new(attributes));
+        self
+    }
+
+    /// Adds a closure that computes additional attributes for each edge. The closure is
+    /// given the source, expression, color and target of the edge.
+    pub fn edge_attributes<F>(mut self, attributes: F) -> Self
+    where
+        F: Fn(Ts::StateIndex, &ExpressionOf<Ts>, &Ts::EdgeColor, Ts::StateIndex) -> DotAttributes
+            + 'a,
+    {
+        self.edge_attributes.push(Box::new(attributes));
+        self
+    }
+}
+
+/// Escapes a string such that it can be used as a quoted DOT identifier.
+pub(crate) fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\\\""))
+}
+
+/// Writes a list of attributes, later occurrences of a key overwrite earlier ones.
+fn write_attributes(attributes: DotAttributes) -> String {
+    let mut seen = Set::default();
+    let deduplicated = attributes
+        .into_iter()
+        .rev()
+        .filter(|(key, _)| seen.insert(key.clone()))
+        .collect_vec();
+    deduplicated
+        .into_iter()
+        .rev()
+        .map(|(key, value)| format!("{key}={}", quote(&value)))
+        .join(", ")
+}
+
+/// Renders transition systems and composite structures such as FORCs in the DOT language. The
+/// output is configured by options of type `O`, which are [`DotOptions`] for transition
+/// systems.
+pub trait ToDot<O> {
+    /// Produces a DOT representation of `self` that is configured by `options`.
+    fn to_dot_with(&self, options: &O) -> String;
+
+    /// Produces a DOT representation with the default options.
+    fn to_dot(&self) -> String
+    where
+        O: Default,
+    {
+        self.to_dot_with(&O::default())
+    }
+}
+
+impl<'a, Ts> ToDot<DotOptions<'a, Ts>> for Ts
+where
+    Ts: TransitionSystem,
+    Ts::StateIndex: Show,
+    Ts::StateColor: Show,
+    Ts::EdgeColor: Show,
+    ExpressionOf<Ts>: Show + Ord,
+{
+    fn to_dot_with(&self, options: &DotOptions<'a, Self>) -> String {
+        let name = |q: Self::StateIndex| format!("q{}", q.show());
+        let states = self.sorted_state_indices();
+
+        let mut out = String::new();
+        writeln!(out, "digraph {} {{", quote(&options.name)).unwrap();
+        writeln!(out, "  rankdir={};", options.rankdir).unwrap();
+        writeln!(out, "  node [shape=circle];").unwrap();
+
+        // the node definitions, grouped into clusters if needed
+        let groups = if options.cluster_sccs {
+            // components are ordered by their smallest state
+            self.sccs()
+                .iter()
+                .map(|scc| scc.clone().into_iter().sorted().collect_vec())
+                .sorted_by_key(|group| group[0])
+                .collect_vec()
+        } else {
+            vec![states.clone()]
+        };
+
+        for (i, group) in groups.iter().enumerate() {
+            let indent = if options.cluster_sccs {
+                writeln!(out, "  subgraph cluster_{i} {{").unwrap();
+                "    "
+            } else {
+                "  "
+            };
+            for q in group {
+                let color = self.state_color(*q).expect("State must exist");
+                let mut label = name(*q);
+                let mut attributes: DotAttributes = vec![];
+                if let Some(metadata) = options.metadata {
+                    label = metadata.display_name(*q);
+                    let tooltip = metadata
+                        .annotations(*q)
+                        .map(|(key, value)| format!("{key}: {value}"))
+                        .join("; ");
+                    if !tooltip.is_empty() {
+                        attributes.push(("tooltip".into(), tooltip));
+                    }
+                }
+                if let Some(priority) = &options.state_priority {
+                    let priority = priority(&color);
+                    label = format!("{label} | {priority}");
+                    if priority % 2 == 0 {
+                        attributes.push(("shape".into(), "doublecircle".into()));
+                    }
+                } else if options.show_colors {
+                    let shown = color.show();
+                    if !shown.is_empty() {
+                        label = format!("{label} | {shown}");
+                    }
+                }
+                if let Some(accepting) = &options.accepting {
+                    if accepting(&color) {
+                        attributes.push(("shape".into(), "doublecircle".into()));
+                    }
+                }
+                attributes.insert(0, ("label".into(), label));
+                for f in &options.state_attributes {
+                    attributes.extend(f(*q, &color));
+                }
+                writeln!(
+                    out,
+                    "{indent}{} [{}];",
+                    name(*q),
+                    write_attributes(attributes)
+                )
+                .unwrap();
+            }
+            if options.cluster_sccs {
+                writeln!(out, "  }}").unwrap();
+            }
+        }
+
+        if let Some(initial) = self.maybe_initial_state() {
+            writeln!(out, "  init [label=\"\", shape=point];").unwrap();
+            writeln!(out, "  init -> {};", name(initial)).unwrap();
+        }
+
+        // edges between the same pair of states with the same color and the same custom
+        // attributes are merged into a single edge with a combined label
+        for q in &states {
+            let mut merged: Vec<(Self::StateIndex, String, DotAttributes, Vec<String>)> = vec![];
+            for (expression, color, target) in self.sorted_edges_from(*q) {
+                let suffix = if let Some(priority) = &options.edge_priority {
+                    format!(" | {}", priority(&color))
+                } else if options.show_colors && !color.show().is_empty() {
+                    format!(" | {}", color.show())
+                } else {
+                    String::new()
+                };
+                let attributes = options
+                    .edge_attributes
+                    .iter()
+                    .flat_map(|f| f(*q, &expression, &color, target))
+                    .collect_vec();
+                let symbol = expression.show();
+                match merged
+                    .iter_mut()
+                    .find(|(t, s, a, _)| *t == target && *s == suffix && *a == attributes)
+                {
+                    Some((_, _, _, symbols)) => symbols.push(symbol),
+                    None => merged.push((target, suffix, attributes, vec![symbol])),
+                }
+            }
+            for (target, suffix, attributes, mut symbols) in merged {
+                // symbols are listed in the canonical order, see `CanonicalAlphabet`
+                symbols.sort();
+                let mut all = vec![(
+                    "label".to_string(),
+                    format!("{}{suffix}", symbols.join(", ")),
+                )];
+                all.extend(attributes);
+                writeln!(
+                    out,
+                    "  {} -> {} [{}];",
+                    name(*q),
+                    name(target),
+                    write_attributes(all)
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+/// Writes the classes and transitions of a congruence, where `prefix` distinguishes the nodes
+/// of different congruences in the same graph. Transitions between the same pair of classes
+/// are merged into a single edge.
+fn write_congruence<A>(out: &mut String, cong: &RightCongruence<A>, prefix: &str, indent: &str)
+where
+    A: Alphabet,
+    A::Symbol: Show,
+    A::Expression: Show + Ord,
+{
+    let states = cong.sorted_state_indices();
+    for q in &states {
+        let class = cong.class_name(*q).expect("Class must exist").to_vec();
+        let label = if class.is_empty() {
+            "ε".to_string()
+        } else {
+            class.iter().map(|sym| sym.show()).join("")
+        };
+        writeln!(out, "{indent}{prefix}{q} [label={}];", quote(&label)).unwrap();
+    }
+    for q in &states {
+        let mut merged: Vec<(usize, Vec<String>)> = vec![];
+        for (expression, _, target) in cong.sorted_edges_from(*q) {
+            let symbol = expression.show();
+            match merged.iter_mut().find(|(t, _)| *t == target) {
+                Some((_, symbols)) => symbols.push(symbol),
+                None => merged.push((target, vec![symbol])),
+            }
+        }
+        for (target, symbols) in merged {
+            writeln!(
+                out,
+                "{indent}{prefix}{q} -> {prefix}{target} [label={}];",
+                quote(&symbols.join(", "))
+            )
+            .unwrap();
+        }
+    }
+}
+
+impl<A> ToDot<RankDir> for FORC<A>
+where
+    A: Alphabet,
+    A::Symbol: Show,
+    A::Expression: Show + Ord,
+{
+    /// Draws the leading congruence and all progress congruences into one graph, which is laid
+    /// out in the direction `rankdir`. Each congruence is put into its own cluster and every
+    /// leading class is connected to the initial class of its progress congruence with a
+    /// dashed edge.
+    fn to_dot_with(&self, rankdir: &RankDir) -> String {
+        let leading = self.leading();
+        let mut out = String::new();
+        writeln!(out, "digraph \"FORC\" {{").unwrap();
+        writeln!(out, "  rankdir={rankdir};").unwrap();
+        writeln!(out, "  node [shape=circle];").unwrap();
+        writeln!(out, "  compound=true;").unwrap();
+
+        writeln!(out, "  subgraph cluster_leading {{").unwrap();
+        writeln!(out, "    label=\"leading\";").unwrap();
+        write_congruence(&mut out, leading, "l", "    ");
+        writeln!(out, "  }}").unwrap();
+        writeln!(out, "  init [label=\"\", shape=point];").unwrap();
+        writeln!(out, "  init -> l{};", leading.initial()).unwrap();
+
+        for class in leading.state_indices().sorted() {
+            let Some(prc) = self.prc(class) else {
+                continue;
+            };
+            writeln!(out, "  subgraph cluster_{class} {{").unwrap();
+            writeln!(
+                out,
+                "    label={};",
+                quote(&format!("progress of l{class}"))
+            )
+            .unwrap();
+            write_congruence(&mut out, prc, &format!("p{class}_"), "    ");
+            writeln!(out, "  }}").unwrap();
+            writeln!(
+                out,
+                "  l{class} -> p{class}_{} [style=\"dashed\", lhead=\"cluster_{class}\"];",
+                prc.initial()
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use automata::{congruence::FORC, RightCongruence};
+
+    use super::{DotOptions, RankDir, ToDot};
+    use crate::{formats::DotGraph, metadata::StateMetadata};
+
+    fn even_a() -> DFA {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+        dfa
+    }
+
+    #[test]
+    fn dot_options() {
+        let dfa = even_a();
+        let options = DotOptions::new()
+            .name("even")
+            .rankdir(RankDir::TopBottom)
+            .cluster_sccs(true)
+            .show_colors(false)
+            .accepting(|c: &bool| *c)
+            .edge_attributes(|_, _, _, _| vec![("style".into(), "bold".into())]);
+        let dot = dfa.to_dot_with(&options);
+        assert!(dot.contains("rankdir=TB;"));
+        assert!(dot.contains("subgraph cluster_0"));
+        assert!(!dot.contains("subgraph cluster_1"));
+        assert!(dot.contains("style=\"bold\""));
+
+        // the output can be parsed back into the same automaton
+        let parsed = DotGraph::try_from(dot.as_str())
+            .unwrap()
+            .into_dfa()
+            .unwrap();
+        assert_eq!(parsed.size(), 2);
+        for word in ["", "a", "ab", "aba", "bbaab"] {
+            assert_eq!(parsed.accepts(word), dfa.accepts(word));
+        }
+    }
+
+    #[test]
+    fn metadata_dot() {
+        let dfa = even_a();
+        let mut metadata = StateMetadata::from_names([(0, "even")]);
+        metadata.annotate(0, "origin", "initial");
+        let dot = dfa.to_dot_with(&DotOptions::new().metadata(&metadata));
+        assert!(dot.contains("label=\"even | true\""));
+        assert!(dot.contains("tooltip=\"origin: initial\""));
+        assert!(dot.contains("label=\"q1 | false\""));
+    }
+
+    #[test]
+    fn forc_dot() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        let mut leading = RightCongruence::new(alphabet.clone());
+        let eps = leading.add_state((vec![], Void));
+        leading.add_edge(eps, 'a', eps, Void);
+        leading.add_edge(eps, 'b', eps, Void);
+        let mut prc = RightCongruence::new(alphabet);
+        let p0 = prc.add_state((vec![], Void));
+        let p1 = prc.add_state((vec!['a'], Void));
+        prc.add_edge(p0, 'a', p1, Void);
+        prc.add_edge(p0, 'b', p0, Void);
+        prc.add_edge(p1, 'a', p1, Void);
+        prc.add_edge(p1, 'b', p1, Void);
+        let forc = FORC::from_iter(leading, [(eps, prc)]);
+
+        let dot = forc.to_dot();
+        assert!(dot.contains("subgraph cluster_leading"));
+        assert!(dot.contains("subgraph cluster_0"));
+        assert!(dot.contains("l0 -> l0 [label=\"a, b\"];"));
+        assert!(dot.contains("p0_0 -> p0_1 [label=\"a\"];"));
+        assert!(dot.contains("l0 -> p0_0 [style=\"dashed\""));
+        assert!(dot.contains("p0_1 [label=\"a\"];"));
+    }
+}
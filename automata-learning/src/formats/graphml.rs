@@ -0,0 +1,535 @@
+//! Reading and writing of transition systems in the GraphML format, which is understood by
+//! graph tools such as yEd and Gephi. Nodes are states and edges are transitions, the data
+//! that is attached to them uses the following keys:
+//!
+//! | `attr.name`  | `for`  | `attr.type` | meaning                                             |
+//! |--------------|--------|-------------|-----------------------------------------------------|
+//! | `label`      | `node` | `string`    | the name of the state, only used for display        |
+//! | `initial`    | `node` | `boolean`   | whether the state is initial, defaults to `false`   |
+//! | `accepting`  | `node` | `boolean`   | whether the state is accepting, defaults to `false` |
+//! | `color`      | `node` | `string`    | the color of the state                              |
+//! | `expression` | `edge` | `string`    | a comma separated list of single character symbols  |
+//! | `color`      | `edge` | `string`    | the color of the transition                         |
+//!
+//! When reading, keys are resolved by their `attr.name`, so files in which the keys have
+//! different ids (as is the case after editing with external tools) can be read as well.
+//! Unknown keys are ignored. Colors are written with [`Show`] and read as strings.
+
+use std::fmt::Write;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+use tracing::trace;
+
+use crate::ordered::SortedTransitionSystem;
+
+/// Abstracts the types of errors that can occur when reading a GraphML file or converting it
+/// into a transition system.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum GraphMlError {
+    Xml(String),
+    MissingElement(&'static str),
+    MissingAttribute(&'static str),
+    UnknownNode(String),
+    MalformedSymbol(String),
+    MissingInitial,
+    MultipleInitial(String, String),
+    Nondeterministic(String, char),
+}
+
+impl std::fmt::Display for GraphMlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphMlError::Xml(err) => write!(f, "Malformed XML: {err}"),
+            GraphMlError::MissingElement(e) => write!(f, "Missing element <{e}>"),
+            GraphMlError::MissingAttribute(a) => write!(f, "Missing attribute {a}"),
+            GraphMlError::UnknownNode(id) => write!(f, "Node {id} does not exist"),
+            GraphMlError::MalformedSymbol(sym) => {
+                write!(
+                    f,
+                    "Malformed symbol {sym}, symbols must be single characters"
+                )
+            }
+            GraphMlError::MissingInitial => write!(f, "No initial state is marked"),
+            GraphMlError::MultipleInitial(p, q) => {
+                write!(f, "Both {p} and {q} are marked as initial")
+            }
+            GraphMlError::Nondeterministic(state, sym) => {
+                write!(f, "State {state} has multiple transitions on {sym}")
+            }
+        }
+    }
+}
+
+/// A node of a [`GraphMlGraph`], which represents a state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphMlNode {
+    /// The identifier of the node.
+    pub id: String,
+    /// The label of the node, if one is given.
+    pub label: Option<String>,
+    /// Whether the state is initial.
+    pub initial: bool,
+    /// Whether the state is accepting.
+    pub accepting: bool,
+    /// The color of the state, if one is given.
+    pub color: Option<String>,
+}
+
+/// An edge of a [`GraphMlGraph`], which represents one or more transitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphMlEdge {
+    /// The identifier of the source node.
+    pub source: String,
+    /// The identifier of the target node.
+    pub target: String,
+    /// The symbols on which the transition is taken.
+    pub symbols: Vec<char>,
+    /// The color of the transition, if one is given.
+    pub color: Option<String>,
+}
+
+/// A directed graph in the GraphML format, see the [module documentation](self) for the keys
+/// that are used.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GraphMlGraph {
+    /// The nodes in the order in which they appear.
+    pub nodes: Vec<GraphMlNode>,
+    /// The edges in the order in which they appear.
+    pub edges: Vec<GraphMlEdge>,
+}
+
+/// Escapes the characters that have a special meaning in XML.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn parse_symbols(expression: &str) -> Result<Vec<char>, GraphMlError> {
+    expression
+        .split(',')
+        .map(|sym| sym.trim())
+        .filter(|sym| !sym.is_empty())
+        .map(|sym| {
+            let mut chars = sym.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(GraphMlError::MalformedSymbol(sym.to_string())),
+            }
+        })
+        .collect()
+}
+
+impl TryFrom<&str> for GraphMlGraph {
+    type Error = GraphMlError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let document =
+            roxmltree::Document::parse(value).map_err(|err| GraphMlError::Xml(err.to_string()))?;
+        let root = document.root_element();
+
+        // maps (domain, key id) to the attribute name and default value
+        let mut keys = Map::default();
+        for key in root.children().filter(|c| c.has_tag_name("key")) {
+            let id = key
+                .attribute("id")
+                .ok_or(GraphMlError::MissingAttribute("id"))?;
+            let domain = key.attribute("for").unwrap_or("all");
+            let name = key.attribute("attr.name").unwrap_or(id);
+            let default = key
+                .children()
+                .find(|c| c.has_tag_name("default"))
+                .and_then(|c| c.text())
+                .map(|text| text.trim().to_string());
+            keys.insert(
+                (domain.to_string(), id.to_string()),
+                (name.to_string(), default),
+            );
+        }
+        let data = |node: roxmltree::Node, domain: &str| {
+            let mut values: Map<String, String> = keys
+                .iter()
+                .filter(|((d, _), _)| d == domain || d == "all")
+                .filter_map(|(_, (name, default))| Some((name.clone(), default.clone()?)))
+                .collect();
+            for d in node.children().filter(|c| c.has_tag_name("data")) {
+                let Some(key) = d.attribute("key") else {
+                    continue;
+                };
+                let name = keys
+                    .get(&(domain.to_string(), key.to_string()))
+                    .or_else(|| keys.get(&("all".to_string(), key.to_string())))
+                    .map(|(name, _)| name.clone())
+                    .unwrap_or_else(|| key.to_string());
+                values.insert(name, d.text().unwrap_or_default().trim().to_string());
+            }
+            values
+        };
+
+        let graph = root
+            .children()
+            .find(|c| c.has_tag_name("graph"))
+            .ok_or(GraphMlError::MissingElement("graph"))?;
+
+        let mut nodes = vec![];
+        for node in graph.children().filter(|c| c.has_tag_name("node")) {
+            let values = data(node, "node");
+            nodes.push(GraphMlNode {
+                id: node
+                    .attribute("id")
+                    .ok_or(GraphMlError::MissingAttribute("id"))?
+                    .to_string(),
+                label: values.get("label").cloned(),
+                initial: values.get("initial").map(|v| v.as_str()) == Some("true"),
+                accepting: values.get("accepting").map(|v| v.as_str()) == Some("true"),
+                color: values.get("color").cloned(),
+            });
+        }
+
+        let ids: Set<_> = nodes.iter().map(|node| node.id.clone()).collect();
+        let mut edges = vec![];
+        for edge in graph.children().filter(|c| c.has_tag_name("edge")) {
+            let source = edge
+                .attribute("source")
+                .ok_or(GraphMlError::MissingAttribute("source"))?;
+            let target = edge
+                .attribute("target")
+                .ok_or(GraphMlError::MissingAttribute("target"))?;
+            for id in [source, target] {
+                if !ids.contains(id) {
+                    return Err(GraphMlError::UnknownNode(id.to_string()));
+                }
+            }
+            let values = data(edge, "edge");
+            edges.push(GraphMlEdge {
+                source: source.to_string(),
+                target: target.to_string(),
+                symbols: parse_symbols(
+                    values
+                        .get("expression")
+                        .map(|e| e.as_str())
+                        .unwrap_or_default(),
+                )?,
+                color: values.get("color").cloned(),
+            });
+        }
+
+        trace!(
+            "Parsed GraphML graph with {} nodes and {} edges",
+            nodes.len(),
+            edges.len()
+        );
+        Ok(Self { nodes, edges })
+    }
+}
+
+impl GraphMlGraph {
+    /// Builds the graph of a transition system, state and edge colors are written with
+    /// [`Show`]. Parallel edges with the same color are merged into one edge.
+    pub fn from_ts<Ts>(ts: &Ts) -> Self
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet>,
+        Ts::StateColor: Show,
+        Ts::EdgeColor: Show,
+    {
+        Self::build(ts, |_| false)
+    }
+
+    /// Builds the graph of a transition system with boolean state colors, i.e. a DFA or NFA.
+    /// States that are colored `true` are marked as accepting and no state colors are written.
+    pub fn from_acceptor<Ts>(ts: &Ts) -> Self
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet, StateColor = bool>,
+        Ts::EdgeColor: Show,
+    {
+        let mut graph = Self::build(ts, |c| *c);
+        for node in &mut graph.nodes {
+            node.color = None;
+        }
+        graph
+    }
+
+    fn build<Ts, F>(ts: &Ts, accepting: F) -> Self
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet>,
+        Ts::StateColor: Show,
+        Ts::EdgeColor: Show,
+        F: Fn(&Ts::StateColor) -> bool,
+    {
+        let indices = ts.sorted_state_indices();
+        let name: Map<_, _> = indices
+            .iter()
+            .enumerate()
+            .map(|(i, q)| (*q, format!("q{i}")))
+            .collect();
+        let initial = ts.maybe_initial_state();
+        let nodes = indices
+            .iter()
+            .map(|q| {
+                let color = ts.state_color(*q).expect("State must exist");
+                GraphMlNode {
+                    id: name[q].clone(),
+                    label: Some(name[q].clone()),
+                    initial: Some(*q) == initial,
+                    accepting: accepting(&color),
+                    color: Some(color.show()),
+                }
+            })
+            .collect();
+
+        let mut edges: Vec<GraphMlEdge> = vec![];
+        for (source, (sym, color, target)) in ts.sorted_edges() {
+            let source = &name[&source];
+            let target = &name[&target];
+            let color = Some(color.show());
+            match edges.iter_mut().find(|edge| {
+                edge.source == *source && edge.target == *target && edge.color == color
+            }) {
+                Some(edge) => edge.symbols.push(sym),
+                None => edges.push(GraphMlEdge {
+                    source: source.clone(),
+                    target: target.clone(),
+                    symbols: vec![sym],
+                    color,
+                }),
+            }
+        }
+        Self { nodes, edges }
+    }
+
+    /// Writes the graph in the GraphML format, using the keys from the
+    /// [module documentation](self).
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+        writeln!(
+            out,
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" \
+             xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+             xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns \
+             http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">"
+        )
+        .unwrap();
+        for (id, domain, name, kind, default) in [
+            ("label", "node", "label", "string", None),
+            ("initial", "node", "initial", "boolean", Some("false")),
+            ("accepting", "node", "accepting", "boolean", Some("false")),
+            ("state_color", "node", "color", "string", None),
+            ("expression", "edge", "expression", "string", None),
+            ("edge_color", "edge", "color", "string", None),
+        ] {
+            match default {
+                Some(default) => writeln!(
+                    out,
+                    "  <key id=\"{id}\" for=\"{domain}\" attr.name=\"{name}\" \
+                     attr.type=\"{kind}\"><default>{default}</default></key>"
+                ),
+                None => writeln!(
+                    out,
+                    "  <key id=\"{id}\" for=\"{domain}\" attr.name=\"{name}\" attr.type=\"{kind}\"/>"
+                ),
+            }
+            .unwrap();
+        }
+        writeln!(out, "  <graph id=\"G\" edgedefault=\"directed\">").unwrap();
+        for node in &self.nodes {
+            writeln!(out, "    <node id=\"{}\">", escape(&node.id)).unwrap();
+            if let Some(label) = &node.label {
+                writeln!(out, "      <data key=\"label\">{}</data>", escape(label)).unwrap();
+            }
+            if node.initial {
+                writeln!(out, "      <data key=\"initial\">true</data>").unwrap();
+            }
+            if node.accepting {
+                writeln!(out, "      <data key=\"accepting\">true</data>").unwrap();
+            }
+            if let Some(color) = &node.color {
+                writeln!(
+                    out,
+                    "      <data key=\"state_color\">{}</data>",
+                    escape(color)
+                )
+                .unwrap();
+            }
+            writeln!(out, "    </node>").unwrap();
+        }
+        for edge in &self.edges {
+            writeln!(
+                out,
+                "    <edge source=\"{}\" target=\"{}\">",
+                escape(&edge.source),
+                escape(&edge.target)
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "      <data key=\"expression\">{}</data>",
+                escape(&edge.symbols.iter().join(", "))
+            )
+            .unwrap();
+            if let Some(color) = &edge.color {
+                writeln!(
+                    out,
+                    "      <data key=\"edge_color\">{}</data>",
+                    escape(color)
+                )
+                .unwrap();
+            }
+            writeln!(out, "    </edge>").unwrap();
+        }
+        writeln!(out, "  </graph>").unwrap();
+        writeln!(out, "</graphml>").unwrap();
+        out
+    }
+
+    /// Returns the identifier of the initial state, if there is one.
+    pub fn initial(&self) -> Result<Option<&str>, GraphMlError> {
+        let mut candidates = self.nodes.iter().filter(|node| node.initial);
+        match (candidates.next(), candidates.next()) {
+            (Some(p), Some(q)) => Err(GraphMlError::MultipleInitial(p.id.clone(), q.id.clone())),
+            (initial, _) => Ok(initial.map(|node| node.id.as_str())),
+        }
+    }
+
+    /// Returns the nodes, where the initial state (if it exists) comes first and the remaining
+    /// states follow in order of appearance.
+    pub fn states(&self) -> Result<Vec<&GraphMlNode>, GraphMlError> {
+        let initial = self.initial()?;
+        Ok(self
+            .nodes
+            .iter()
+            .sorted_by_key(|node| Some(node.id.as_str()) != initial)
+            .collect())
+    }
+
+    /// Collects all symbols that appear on edges into an alphabet.
+    pub fn alphabet(&self) -> CharAlphabet {
+        self.edges
+            .iter()
+            .flat_map(|edge| edge.symbols.iter().cloned())
+            .sorted()
+            .dedup()
+            .collect_vec()
+            .into()
+    }
+
+    /// Converts the graph into a nondeterministic transition system where state and edge colors
+    /// are the given strings (or empty if none is given). Returns the transition system together
+    /// with the index of the initial state, if one is marked. The state with index `i`
+    /// corresponds to the `i`-th element of [`GraphMlGraph::states`].
+    #[allow(clippy::type_complexity)]
+    pub fn into_ts(
+        &self,
+    ) -> Result<(NTS<CharAlphabet, String, String>, Option<usize>), GraphMlError> {
+        let states = self.states()?;
+        let mut ts = NTS::new_for_alphabet(self.alphabet());
+        let mut indices = Map::default();
+        for node in &states {
+            let idx = ts.add_state(node.color.clone().unwrap_or_default());
+            indices.insert(node.id.as_str(), idx);
+        }
+        for edge in &self.edges {
+            for sym in &edge.symbols {
+                ts.add_edge(
+                    indices[edge.source.as_str()],
+                    *sym,
+                    indices[edge.target.as_str()],
+                    edge.color.clone().unwrap_or_default(),
+                );
+            }
+        }
+        let initial = self.initial()?.map(|initial| indices[initial]);
+        Ok((ts, initial))
+    }
+
+    /// Converts the graph into a [`DFA`], whose accepting states are those that are marked
+    /// as accepting. This fails if no initial state is marked or if some state has more than
+    /// one transition on the same symbol.
+    pub fn into_dfa(&self) -> Result<DFA, GraphMlError> {
+        let states = self.states()?;
+        if self.initial()?.is_none() {
+            return Err(GraphMlError::MissingInitial);
+        }
+        let mut dfa = DFA::new_for_alphabet(self.alphabet());
+        let mut indices = Map::default();
+        for node in &states {
+            let idx = dfa.add_state(node.accepting);
+            indices.insert(node.id.as_str(), idx);
+        }
+        let mut seen = Set::default();
+        for edge in &self.edges {
+            let source = indices[edge.source.as_str()];
+            for sym in &edge.symbols {
+                if !seen.insert((source, *sym)) {
+                    return Err(GraphMlError::Nondeterministic(edge.source.clone(), *sym));
+                }
+                dfa.add_edge(source, *sym, indices[edge.target.as_str()], Void);
+            }
+        }
+        Ok(dfa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{GraphMlError, GraphMlGraph};
+
+    #[test]
+    fn graphml_dfa_round_trip() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+
+        let graphml = GraphMlGraph::from_acceptor(&dfa).to_graphml();
+        let parsed = GraphMlGraph::try_from(graphml.as_str()).unwrap();
+        assert_eq!(parsed.nodes.len(), 2);
+        assert_eq!(parsed.initial(), Ok(Some("q0")));
+        let restored = parsed.into_dfa().unwrap();
+        for word in ["", "a", "ab", "aba", "bbaab"] {
+            assert_eq!(restored.accepts(word), dfa.accepts(word));
+        }
+    }
+
+    #[test]
+    fn graphml_foreign_key_ids() {
+        // keys are resolved by their name, as tools like yEd assign their own ids
+        let parsed = GraphMlGraph::try_from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+                <key id="d0" for="node" attr.name="initial" attr.type="boolean"/>
+                <key id="d1" for="node" attr.name="color" attr.type="string"/>
+                <key id="d2" for="edge" attr.name="expression" attr.type="string"/>
+                <key id="d3" for="edge" attr.name="color" attr.type="string"/>
+                <key id="d4" for="node" attr.name="x" attr.type="double"/>
+                <graph edgedefault="directed">
+                    <node id="n1"><data key="d1">1</data></node>
+                    <node id="n0"><data key="d0">true</data><data key="d1">2</data><data key="d4">1.5</data></node>
+                    <edge source="n0" target="n1"><data key="d2">a, b</data><data key="d3">x</data></edge>
+                    <edge source="n1" target="n1"><data key="d2">a</data></edge>
+                </graph>
+            </graphml>"#,
+        )
+        .unwrap();
+        let (ts, initial) = parsed.into_ts().unwrap();
+        assert_eq!(initial, Some(0));
+        assert_eq!(ts.state_color(0), Some("2".to_string()));
+        assert_eq!(ts.state_color(1), Some("1".to_string()));
+
+        assert_eq!(
+            GraphMlGraph::try_from(
+                r#"<graphml><graph><node id="a"/><edge source="a" target="b"/></graph></graphml>"#
+            ),
+            Err(GraphMlError::UnknownNode("b".to_string()))
+        );
+    }
+}
@@ -0,0 +1,816 @@
+//! Export of DFAs, DBAs and DPAs in the Hanoi Omega Automata (HOA) format, which is read by
+//! tools such as Spot and Owl. The automata are converted into a [`HoaAutomaton`] of the
+//! `hoars` crate, which takes care of printing and can parse the output again.
+//!
+//! Conversely, [`hoa_to_nba`], [`hoa_to_dba`] and [`hoa_to_dpa`] import a parsed
+//! [`HoaAutomaton`]. The atomic propositions of the automaton become a
+//! [`PropositionalAlphabet`] and an edge is added for each valuation that satisfies its label.
+//! The parser already moves state-based acceptance onto the outgoing edges, so all imported
+//! automata have transition-based acceptance.
+//!
+//! The HOA format labels edges with Boolean expressions over atomic propositions, so the
+//! symbols of a [`CharAlphabet`] need to be encoded, see [`HoaEncoding`]. Each symbol also gets
+//! an `Alias` for the valuation it stands for. Acceptance is always written on the edges: DBAs
+//! and NBAs (nondeterministic transition systems with a Boolean edge color, where state 0 is
+//! initial) use the Büchi condition `Inf(0)` with their accepting edges in set 0, and a DPA
+//! uses the min even parity condition with the priority of each edge as its acceptance set.
+//!
+//! The HOA format has no finite-word semantics, so a DFA is marked with the tool-specific
+//! property `finite-word`. Its acceptance set 0 contains the edges leaving accepting states,
+//! which is why missing transitions are first directed to an added rejecting sink. The set
+//! is declared by `Acceptance: 1 Inf(0)` without an `acc-name`, but only the words that end
+//! in an accepting state are meant to be accepted.
+
+use automata::{prelude::*, Map};
+use hoars::{
+    AcceptanceCondition, AcceptanceInfo, AcceptanceName, AcceptanceSignature, AliasName, Body,
+    Edge, Header, HeaderItem, HoaAutomaton, Id, Label, LabelExpression, OmegaAcceptanceType,
+    Property, State, StateConjunction, ALPHABET, MAX_APS, VARS,
+};
+use itertools::Itertools;
+
+use crate::{
+    analysis::Completeness, ordered::SortedTransitionSystem, propositional::PropositionalAlphabet,
+};
+
+/// Abstracts the types of errors that can occur when converting an automaton into the HOA
+/// format.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum HoaExportError {
+    TooManyPropositions(usize),
+    UnknownSymbol(char),
+    MissingInitial,
+}
+
+impl std::fmt::Display for HoaExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HoaExportError::TooManyPropositions(n) => {
+                write!(
+                    f,
+                    "{n} atomic propositions are needed, at most {MAX_APS} are supported"
+                )
+            }
+            HoaExportError::UnknownSymbol(sym) => {
+                write!(
+                    f,
+                    "Symbol {sym} does not encode a valuation of the propositions"
+                )
+            }
+            HoaExportError::MissingInitial => write!(f, "The automaton has no initial state"),
+        }
+    }
+}
+
+impl std::error::Error for HoaExportError {}
+
+/// Determines how the symbols of an automaton are written as labels over atomic propositions.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum HoaEncoding<'a> {
+    /// Each symbol becomes an atomic proposition that is named by the symbol, and the edges on
+    /// a symbol are labelled with the valuation in which exactly this proposition holds.
+    #[default]
+    Symbols,
+    /// The symbols are valuations of the propositions of a [`PropositionalAlphabet`], which are
+    /// declared as the atomic propositions of the automaton.
+    Propositional(&'a PropositionalAlphabet),
+}
+
+impl<'a> HoaEncoding<'a> {
+    /// Returns the atomic propositions that are declared for the given symbols.
+    fn propositions(&self, symbols: &[char]) -> Result<Vec<String>, HoaExportError> {
+        let propositions = match self {
+            HoaEncoding::Symbols => symbols.iter().map(|sym| sym.to_string()).collect_vec(),
+            HoaEncoding::Propositional(alphabet) => alphabet.propositions().to_vec(),
+        };
+        if propositions.len() > MAX_APS {
+            return Err(HoaExportError::TooManyPropositions(propositions.len()));
+        }
+        Ok(propositions)
+    }
+
+    /// Returns the label of the edges on `sym`, which is a full conjunction over the
+    /// propositions of the valuation that `sym` stands for.
+    fn label(&self, symbols: &[char], sym: char) -> Result<LabelExpression, HoaExportError> {
+        let holds: Vec<bool> = match self {
+            HoaEncoding::Symbols => symbols.iter().map(|other| *other == sym).collect(),
+            HoaEncoding::Propositional(alphabet) => {
+                if !alphabet.symbols().contains(&sym) {
+                    return Err(HoaExportError::UnknownSymbol(sym));
+                }
+                let valuation = alphabet.valuation(sym);
+                alphabet
+                    .propositions()
+                    .iter()
+                    .map(|p| valuation.contains(p))
+                    .collect()
+            }
+        };
+        Ok(holds
+            .into_iter()
+            .enumerate()
+            .fold(ALPHABET.mk_true(), |acc, (i, holds)| {
+                if holds {
+                    acc.and(&ALPHABET.mk_var(VARS[i]))
+                } else {
+                    acc.and(&ALPHABET.mk_not_var(VARS[i]))
+                }
+            }))
+    }
+}
+
+/// Returns the name of the alias for the `i`-th symbol `sym`, which is the symbol itself if it
+/// is a valid identifier and `s` followed by `i` otherwise.
+fn alias_name(i: usize, sym: char) -> AliasName {
+    if sym.is_ascii_alphabetic() || sym == '_' {
+        AliasName::new(sym.to_string())
+    } else {
+        AliasName::new(format!("s{i}"))
+    }
+}
+
+/// Converts `ts` into a [`HoaAutomaton`] with the given acceptance and `initial` as start
+/// state, where `signature` gives the acceptance sets of an edge from its source color and its
+/// own color. States are numbered in ascending order of their indices. If `sink` gives a state
+/// and an edge color and `ts` is incomplete, missing transitions lead to an added state with
+/// this color that loops on every symbol, and all added edges get the edge color.
+fn build_hoa<Ts, F>(
+    ts: &Ts,
+    encoding: &HoaEncoding<'_>,
+    initial: Option<Ts::StateIndex>,
+    acceptance: Vec<HeaderItem>,
+    properties: Vec<Property>,
+    sink: Option<(Ts::StateColor, Ts::EdgeColor)>,
+    signature: F,
+) -> Result<HoaAutomaton, HoaExportError>
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet>,
+    F: Fn(&Ts::StateColor, &Ts::EdgeColor) -> Vec<Id>,
+{
+    let symbols = ts.alphabet().universe().sorted().collect_vec();
+    let propositions = encoding.propositions(&symbols)?;
+    let labels = symbols
+        .iter()
+        .map(|sym| encoding.label(&symbols, *sym))
+        .collect::<Result<Vec<_>, _>>()?;
+    let states = ts.sorted_state_indices();
+    let id: Map<Ts::StateIndex, Id> = states
+        .iter()
+        .enumerate()
+        .map(|(i, q)| (*q, i as Id))
+        .collect();
+    let initial = initial.ok_or(HoaExportError::MissingInitial)?;
+
+    // the state that missing transitions lead to, if the automaton is completed
+    let sink = sink.filter(|_| ts.missing_transitions().next().is_some());
+    let sink_id = states.len() as Id;
+    let edge = |color: &Ts::StateColor, sym: char, edge_color: &Ts::EdgeColor, target: Id| {
+        let position = symbols.binary_search(&sym).expect("Symbol must exist");
+        Edge::from_parts(
+            Label(labels[position].clone()),
+            StateConjunction::singleton(target),
+            AcceptanceSignature(signature(color, edge_color)),
+        )
+    };
+
+    let mut body = states
+        .iter()
+        .map(|q| {
+            let color = ts.state_color(*q).expect("State must exist");
+            let outgoing = ts.sorted_edges_from(*q);
+            let mut edges = outgoing
+                .iter()
+                .map(|(sym, edge_color, target)| edge(&color, *sym, edge_color, id[target]))
+                .collect_vec();
+            if let Some((_, edge_color)) = &sink {
+                edges.extend(
+                    symbols
+                        .iter()
+                        .filter(|sym| outgoing.iter().all(|(other, _, _)| other != *sym))
+                        .map(|sym| edge(&color, *sym, edge_color, sink_id)),
+                );
+            }
+            State::from_parts(id[q], None, edges)
+        })
+        .collect_vec();
+    if let Some((color, edge_color)) = &sink {
+        let loops = symbols
+            .iter()
+            .map(|sym| edge(color, *sym, edge_color, sink_id))
+            .collect();
+        body.push(State::from_parts(sink_id, Some("sink".to_string()), loops));
+    }
+
+    let mut header = vec![
+        HeaderItem::v1(),
+        HeaderItem::Tool("lama".to_string(), None),
+        HeaderItem::States(body.len() as Id),
+        HeaderItem::Start(StateConjunction::singleton(id[&initial])),
+        HeaderItem::AP(propositions),
+    ];
+    header.extend(
+        symbols
+            .iter()
+            .zip(&labels)
+            .enumerate()
+            .map(|(i, (sym, label))| HeaderItem::Alias(alias_name(i, *sym), label.clone())),
+    );
+    header.extend(acceptance);
+    header.push(HeaderItem::Properties(
+        [
+            Property::TransLabels,
+            Property::ExplicitLabels,
+            Property::TransitionAcceptance,
+        ]
+        .into_iter()
+        .chain(properties)
+        .collect(),
+    ));
+    Ok(HoaAutomaton::from_parts(
+        Header::from_vec(header),
+        Body::from(body),
+    ))
+}
+
+/// The header items of the Büchi condition `Inf(0)`.
+fn buchi_acceptance() -> Vec<HeaderItem> {
+    vec![
+        HeaderItem::AcceptanceName(AcceptanceName::Buchi, vec![]),
+        HeaderItem::Acceptance(1, AcceptanceCondition::buchi()),
+    ]
+}
+
+/// Conversion of automata into the HOA format, see the module documentation.
+pub trait ToHoa {
+    /// Converts `self` into a [`HoaAutomaton`], encoding symbols as given by `encoding`.
+    fn to_hoa_automaton(&self, encoding: &HoaEncoding<'_>) -> Result<HoaAutomaton, HoaExportError>;
+
+    /// Writes `self` in the HOA format, where each symbol becomes an atomic proposition.
+    fn to_hoa(&self) -> Result<String, HoaExportError> {
+        Ok(self.to_hoa_automaton(&HoaEncoding::Symbols)?.to_hoa())
+    }
+}
+
+impl ToHoa for DFA {
+    fn to_hoa_automaton(&self, encoding: &HoaEncoding<'_>) -> Result<HoaAutomaton, HoaExportError> {
+        build_hoa(
+            self,
+            encoding,
+            self.maybe_initial_state(),
+            vec![HeaderItem::Acceptance(1, AcceptanceCondition::buchi())],
+            vec![
+                Property::Deterministic,
+                Property::Complete,
+                Property::Other("finite-word".to_string()),
+            ],
+            Some((false, Void)),
+            |accepting, _| if *accepting { vec![0] } else { vec![] },
+        )
+    }
+}
+
+impl ToHoa for DBA {
+    fn to_hoa_automaton(&self, encoding: &HoaEncoding<'_>) -> Result<HoaAutomaton, HoaExportError> {
+        build_hoa(
+            self,
+            encoding,
+            self.maybe_initial_state(),
+            buchi_acceptance(),
+            vec![Property::Deterministic],
+            None,
+            |_, accepting| if *accepting { vec![0] } else { vec![] },
+        )
+    }
+}
+
+impl ToHoa for NTS<CharAlphabet, Void, bool> {
+    /// State 0 is the initial state, as for the automata produced by [`hoa_to_nba`].
+    fn to_hoa_automaton(&self, encoding: &HoaEncoding<'_>) -> Result<HoaAutomaton, HoaExportError> {
+        let initial = self.state_indices().find(|q| *q == 0);
+        build_hoa(
+            self,
+            encoding,
+            initial,
+            buchi_acceptance(),
+            vec![],
+            None,
+            |_, accepting| if *accepting { vec![0] } else { vec![] },
+        )
+    }
+}
+
+impl ToHoa for DPA {
+    /// The number of acceptance sets is one more than the greatest priority, so priorities
+    /// that do not occur on any edge are declared as well.
+    fn to_hoa_automaton(&self, encoding: &HoaEncoding<'_>) -> Result<HoaAutomaton, HoaExportError> {
+        let priorities = self
+            .sorted_edges()
+            .into_iter()
+            .map(|(_, (_, priority, _))| priority)
+            .max()
+            .map_or(1, |max| max + 1) as Id;
+        let acceptance = vec![
+            HeaderItem::AcceptanceName(
+                AcceptanceName::Parity,
+                vec![
+                    AcceptanceInfo::identifier("min"),
+                    AcceptanceInfo::identifier("even"),
+                    AcceptanceInfo::integer(priorities),
+                ],
+            ),
+            HeaderItem::Acceptance(priorities, AcceptanceCondition::parity(priorities)),
+        ];
+        build_hoa(
+            self,
+            encoding,
+            self.maybe_initial_state(),
+            acceptance,
+            vec![Property::Deterministic],
+            None,
+            |_, priority| vec![*priority as Id],
+        )
+    }
+}
+
+/// Abstracts the types of errors that can occur when converting a [`HoaAutomaton`] into an
+/// automaton.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum HoaImportError {
+    UnsupportedAcceptance(OmegaAcceptanceType),
+    Nondeterministic,
+    Alternating(Id),
+    AlternatingInitial,
+    MissingInitial,
+    UnknownState(Id),
+    InvalidSignature(Id),
+}
+
+impl std::fmt::Display for HoaImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HoaImportError::UnsupportedAcceptance(acceptance) => {
+                write!(f, "Acceptance condition {acceptance:?} is not supported")
+            }
+            HoaImportError::Nondeterministic => write!(f, "The automaton is not deterministic"),
+            HoaImportError::Alternating(q) => write!(
+                f,
+                "State {q} has an edge to a conjunction of states, alternation is not supported"
+            ),
+            HoaImportError::AlternatingInitial => write!(
+                f,
+                "The initial state is a conjunction of states, alternation is not supported"
+            ),
+            HoaImportError::MissingInitial => write!(f, "The automaton has no initial state"),
+            HoaImportError::UnknownState(q) => write!(f, "State {q} is used but not defined"),
+            HoaImportError::InvalidSignature(q) => {
+                write!(
+                    f,
+                    "State {q} has an edge with an invalid acceptance signature"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HoaImportError {}
+
+/// The transitions of an imported automaton as (source, symbol, color, target) tuples,
+/// where state 0 is initial.
+type ImportedTransitions<C> = Vec<(usize, char, C, usize)>;
+
+/// Returns the alphabet of the valuations of the atomic propositions of `hoa`.
+fn hoa_alphabet(hoa: &HoaAutomaton) -> PropositionalAlphabet {
+    PropositionalAlphabet::new(hoa.aps().iter().cloned())
+}
+
+/// Returns `true` if the valuation that `sym` encodes satisfies `label`.
+fn satisfies(alphabet: &PropositionalAlphabet, label: &Label, sym: char) -> bool {
+    let valuation = alphabet.valuation(sym);
+    let minterm =
+        alphabet
+            .propositions()
+            .iter()
+            .enumerate()
+            .fold(ALPHABET.mk_true(), |acc, (i, p)| {
+                if valuation.contains(p) {
+                    acc.and(&ALPHABET.mk_var(VARS[i]))
+                } else {
+                    acc.and(&ALPHABET.mk_not_var(VARS[i]))
+                }
+            });
+    !label.0.and(&minterm).is_false()
+}
+
+/// Collects the transitions of `hoa`, where `color` gives the color of an edge or `None` if
+/// its acceptance signature is invalid. The initial state becomes state 0 and the remaining
+/// states follow in ascending order of their ids. If there are several initial states, a
+/// fresh initial state is added instead, which gets a copy of all edges leaving them.
+fn import_transitions<C, F>(
+    hoa: &HoaAutomaton,
+    alphabet: &PropositionalAlphabet,
+    color: F,
+) -> Result<(usize, ImportedTransitions<C>), HoaImportError>
+where
+    C: Clone,
+    F: Fn(&Edge) -> Option<C>,
+{
+    let starts = hoa
+        .header()
+        .iter()
+        .filter_map(|item| match item {
+            HeaderItem::Start(conjunction) => Some(
+                conjunction
+                    .get_singleton()
+                    .ok_or(HoaImportError::AlternatingInitial),
+            ),
+            _ => None,
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let ids = hoa
+        .body()
+        .iter()
+        .map(|state| state.id())
+        .sorted()
+        .collect_vec();
+    let offset = match starts.as_slice() {
+        [] => return Err(HoaImportError::MissingInitial),
+        [_] => 0,
+        _ => 1,
+    };
+    let order = if offset == 0 {
+        std::iter::once(starts[0])
+            .chain(ids.iter().cloned().filter(|id| *id != starts[0]))
+            .collect_vec()
+    } else {
+        ids.clone()
+    };
+    let index: Map<Id, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (*id, i + offset))
+        .collect();
+    for id in &starts {
+        if !index.contains_key(id) {
+            return Err(HoaImportError::UnknownState(*id));
+        }
+    }
+
+    let symbols = alphabet.symbols().collect_vec();
+    let mut transitions = vec![];
+    for state in hoa.body() {
+        let source = index[&state.id()];
+        for edge in state.edges() {
+            let target = edge
+                .target()
+                .ok_or(HoaImportError::Alternating(state.id()))?;
+            let target = *index
+                .get(&target)
+                .ok_or(HoaImportError::UnknownState(target))?;
+            let color = color(edge).ok_or(HoaImportError::InvalidSignature(state.id()))?;
+            for sym in &symbols {
+                if satisfies(alphabet, edge.label(), *sym) {
+                    transitions.push((source, *sym, color.clone(), target));
+                }
+            }
+        }
+    }
+    if offset == 1 {
+        let copies = transitions
+            .iter()
+            .filter(|(source, ..)| starts.iter().any(|id| index[id] == *source))
+            .map(|(_, sym, color, target)| (0, *sym, color.clone(), *target))
+            .collect_vec();
+        transitions.extend(copies);
+    }
+    Ok((ids.len() + offset, transitions))
+}
+
+/// Converts `hoa` into a nondeterministic Büchi automaton with acceptance on the edges, which
+/// works for all automata with a Büchi condition. State 0 is the initial state, see the module
+/// documentation for how states and symbols are numbered.
+pub fn hoa_to_nba(
+    hoa: &HoaAutomaton,
+) -> Result<(NTS<CharAlphabet, Void, bool>, PropositionalAlphabet), HoaImportError> {
+    let acceptance = hoa.acceptance_type();
+    if !acceptance.is_buchi() {
+        return Err(HoaImportError::UnsupportedAcceptance(acceptance));
+    }
+    let alphabet = hoa_alphabet(hoa);
+    let (size, transitions) = import_transitions(hoa, &alphabet, |edge| {
+        Some(edge.acceptance_signature().contains(&0))
+    })?;
+    let mut nba = NTS::new_for_alphabet(alphabet.alphabet());
+    for _ in 0..size {
+        nba.add_state(Void);
+    }
+    for (source, sym, accepting, target) in transitions {
+        nba.add_edge(source, sym, target, accepting);
+    }
+    Ok((nba, alphabet))
+}
+
+/// Converts `hoa` into a [`DBA`], which requires a deterministic automaton with a Büchi
+/// condition. The initial state of the result is the initial state of `hoa`.
+pub fn hoa_to_dba(hoa: &HoaAutomaton) -> Result<(DBA, PropositionalAlphabet), HoaImportError> {
+    let acceptance = hoa.acceptance_type();
+    if !acceptance.is_buchi() {
+        return Err(HoaImportError::UnsupportedAcceptance(acceptance));
+    }
+    if !hoa.is_deterministic() {
+        return Err(HoaImportError::Nondeterministic);
+    }
+    let alphabet = hoa_alphabet(hoa);
+    let (size, transitions) = import_transitions(hoa, &alphabet, |edge| {
+        Some(edge.acceptance_signature().contains(&0))
+    })?;
+    let mut dba = DBA::new_for_alphabet(alphabet.alphabet());
+    for _ in 0..size {
+        dba.add_state(Void);
+    }
+    for (source, sym, accepting, target) in transitions {
+        dba.add_edge(source, sym, target, accepting);
+    }
+    Ok((dba, alphabet))
+}
+
+/// Converts `hoa` into a [`DPA`] with the min even parity condition, which requires a
+/// deterministic automaton with a Büchi, co-Büchi or parity condition. The priorities are
+/// obtained with [`OmegaAcceptanceType::min_even_priority`].
+pub fn hoa_to_dpa(hoa: &HoaAutomaton) -> Result<(DPA, PropositionalAlphabet), HoaImportError> {
+    let acceptance = hoa.acceptance_type();
+    if acceptance == OmegaAcceptanceType::Other {
+        return Err(HoaImportError::UnsupportedAcceptance(acceptance));
+    }
+    if !hoa.is_deterministic() {
+        return Err(HoaImportError::Nondeterministic);
+    }
+    let alphabet = hoa_alphabet(hoa);
+    let (size, transitions) =
+        import_transitions(hoa, &alphabet, |edge| acceptance.min_even_priority(edge))?;
+    let mut dpa = DPA::new_for_alphabet(alphabet.alphabet());
+    for _ in 0..size {
+        dpa.add_state(Void);
+    }
+    for (source, sym, priority, target) in transitions {
+        dpa.add_edge(source, sym, target, priority);
+    }
+    Ok((dpa, alphabet))
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+    use hoars::{HoaAutomaton, OmegaAcceptanceType};
+    use itertools::Itertools;
+
+    use super::{
+        hoa_to_dba, hoa_to_dpa, hoa_to_nba, HoaEncoding, HoaExportError, HoaImportError, ToHoa,
+    };
+    use crate::propositional::{PropositionalAlphabet, Valuation};
+
+    /// Parses the printed automaton and checks that it is the same as the one before printing.
+    fn round_trip(hoa: &HoaAutomaton) -> HoaAutomaton {
+        let printed = hoa.to_hoa();
+        let parsed = HoaAutomaton::try_from(printed.as_str()).expect("Output must parse");
+        assert_eq!(&parsed, hoa, "Round trip failed for\n{printed}");
+        parsed
+    }
+
+    #[test]
+    fn dpa_to_hoa() {
+        let dpa: DPA = NTS::builder()
+            .with_transitions([
+                (0, 'a', 0, 0),
+                (0, 'b', 1, 1),
+                (1, 'a', 2, 0),
+                (1, 'b', 1, 1),
+            ])
+            .into_dpa(0);
+        let hoa = round_trip(&dpa.to_hoa_automaton(&HoaEncoding::Symbols).unwrap());
+        let acceptance = hoa.acceptance_type();
+        assert_eq!(
+            acceptance,
+            OmegaAcceptanceType::Parity {
+                max: false,
+                even: true,
+                priorities: 3
+            }
+        );
+        assert!(hoa.is_deterministic());
+        let priorities = hoa.body()[1]
+            .edges()
+            .iter()
+            .map(|e| acceptance.min_even_priority(e).unwrap())
+            .collect_vec();
+        assert_eq!(priorities, vec![2, 1]);
+        assert!(dpa.to_hoa().unwrap().contains("AP: 2 \"a\" \"b\""));
+    }
+
+    #[test]
+    fn dfa_and_dba_to_hoa() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q1, Void);
+        dfa.add_edge(q1, 'b', q0, Void);
+        let hoa = round_trip(&dfa.to_hoa_automaton(&HoaEncoding::Symbols).unwrap());
+        assert!(hoa.has_state_based_acceptance());
+        assert_eq!(hoa.body().len(), 2);
+        let printed = hoa.to_hoa();
+        assert!(printed.contains("finite-word"));
+        assert!(!printed.contains("acc-name"));
+        assert!(printed.contains("Alias: @a 0 & !1"));
+
+        // the missing transition on b leads to a rejecting sink and stays accepting
+        let mut incomplete = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = incomplete.add_state(true);
+        incomplete.add_edge(q0, 'a', q0, Void);
+        let hoa = round_trip(&incomplete.to_hoa_automaton(&HoaEncoding::Symbols).unwrap());
+        assert_eq!(hoa.body().len(), 2);
+        let printed = hoa.to_hoa();
+        assert!(printed.contains("[!0 & 1] 1 {0}"));
+        assert!(printed.contains("State: 1 \"sink\""));
+
+        let mut dba = DBA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dba.add_state(Void);
+        dba.add_edge(q0, 'a', q0, true);
+        dba.add_edge(q0, 'b', q0, false);
+        let hoa = round_trip(&dba.to_hoa_automaton(&HoaEncoding::Symbols).unwrap());
+        assert!(!hoa.has_state_based_acceptance());
+        assert!(hoa.to_hoa().contains("[0 & !1] 0 {0}"));
+    }
+
+    #[test]
+    fn propositional_labels() {
+        let props = PropositionalAlphabet::new(["p", "q"]);
+        let mut dba = DBA::new_for_alphabet(props.alphabet());
+        let q0 = dba.add_state(Void);
+        for sym in props.symbols() {
+            let accepting = props.valuation(sym).contains("p");
+            dba.add_edge(q0, sym, q0, accepting);
+        }
+        let encoding = HoaEncoding::Propositional(&props);
+        let hoa = round_trip(&dba.to_hoa_automaton(&encoding).unwrap());
+        assert_eq!(hoa.aps(), &vec!["p".to_string(), "q".to_string()]);
+        assert_eq!(hoa.body()[0].edges().len(), 4);
+        assert_eq!(hoa.aliases().len(), 4);
+
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a'));
+        let q0 = dfa.add_state(true);
+        dfa.add_edge(q0, 'a', q0, Void);
+        assert_eq!(
+            dfa.to_hoa_automaton(&encoding),
+            Err(HoaExportError::UnknownSymbol('a'))
+        );
+    }
+
+    /// Returns the sorted (symbol, color, target) triples of the edges leaving `q`.
+    fn edges<Ts: TransitionSystem<Alphabet = CharAlphabet>>(
+        ts: &Ts,
+        q: Ts::StateIndex,
+    ) -> Vec<(char, Ts::EdgeColor, Ts::StateIndex)>
+    where
+        Ts::EdgeColor: Ord,
+        Ts::StateIndex: Ord,
+    {
+        ts.edges_from(q)
+            .expect("State must exist")
+            .map(|e| (*e.expression(), e.color(), e.target()))
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn nondeterministic_state_based_to_nba() {
+        // eventually always p, where acceptance is on the state
+        let hoa = HoaAutomaton::try_from(
+            r#"HOA: v1
+States: 2
+Start: 0
+AP: 1 "p"
+acc-name: Buchi
+Acceptance: 1 Inf(0)
+--BODY--
+State: 0
+[t] 0
+[0] 1
+State: 1 {0}
+[0] 1
+--END--
+"#,
+        )
+        .unwrap();
+        let (nba, alphabet) = hoa_to_nba(&hoa).unwrap();
+        let p = alphabet.symbol(&Valuation::from(["p".to_string()]));
+        let none = alphabet.symbol(&Valuation::new());
+        assert_eq!(nba.size(), 2);
+        assert_eq!(
+            edges(&nba, 0),
+            vec![(p, false, 0), (p, false, 1), (none, false, 0)]
+                .into_iter()
+                .sorted()
+                .collect_vec()
+        );
+        assert_eq!(edges(&nba, 1), vec![(p, true, 1)]);
+        assert!(matches!(
+            hoa_to_dba(&hoa),
+            Err(HoaImportError::Nondeterministic)
+        ));
+    }
+
+    #[test]
+    fn nba_export_and_import() {
+        let props = PropositionalAlphabet::new(["p"]);
+        let p = props.symbol(&Valuation::from(["p".to_string()]));
+        let none = props.symbol(&Valuation::new());
+        let mut nba = NTS::new_for_alphabet(props.alphabet());
+        let q0 = nba.add_state(Void);
+        let q1 = nba.add_state(Void);
+        nba.add_edge(q0, p, q0, false);
+        nba.add_edge(q0, none, q0, false);
+        nba.add_edge(q0, p, q1, false);
+        nba.add_edge(q1, p, q1, true);
+        let hoa = round_trip(
+            &nba.to_hoa_automaton(&HoaEncoding::Propositional(&props))
+                .unwrap(),
+        );
+        assert!(!hoa.is_deterministic());
+        assert!(!hoa.to_hoa().contains("deterministic"));
+        let (imported, alphabet) = hoa_to_nba(&hoa).unwrap();
+        assert_eq!(alphabet, props);
+        assert_eq!(edges(&imported, 0), edges(&nba, q0));
+        assert_eq!(edges(&imported, 1), edges(&nba, q1));
+    }
+
+    #[test]
+    fn parity_to_dpa() {
+        // the initial state is not the first one and the condition is max odd
+        let hoa = HoaAutomaton::try_from(
+            r#"HOA: v1
+States: 2
+Start: 1
+AP: 1 "p"
+acc-name: parity max odd 3
+Acceptance: 3 Fin(2) & (Inf(1) | Fin(0))
+--BODY--
+State: 0
+[0] 0 {1}
+[!0] 1 {2}
+State: 1
+[0] 0
+[!0] 1 {0}
+--END--
+"#,
+        )
+        .unwrap();
+        let (dpa, alphabet) = hoa_to_dpa(&hoa).unwrap();
+        let p = alphabet.symbol(&Valuation::from(["p".to_string()]));
+        let none = alphabet.symbol(&Valuation::new());
+        assert_eq!(dpa.initial(), 0);
+        // max odd priorities 0, 1, 2 and no priority become 5, 4, 3 and 6
+        assert_eq!(edges(&dpa, 0), vec![(p, 6, 1), (none, 5, 0)]);
+        assert_eq!(edges(&dpa, 1), vec![(p, 4, 1), (none, 3, 0)]);
+
+        let generalized = HoaAutomaton::try_from(
+            r#"HOA: v1
+States: 1
+Start: 0
+AP: 0
+Acceptance: 2 Inf(0) & Inf(1)
+--BODY--
+State: 0
+[t] 0 {0 1}
+--END--
+"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            hoa_to_dpa(&generalized),
+            Err(HoaImportError::UnsupportedAcceptance(_))
+        ));
+    }
+
+    #[test]
+    fn dba_export_and_import() {
+        let props = PropositionalAlphabet::new(["p", "q"]);
+        let mut dba = DBA::new_for_alphabet(props.alphabet());
+        let q0 = dba.add_state(Void);
+        let q1 = dba.add_state(Void);
+        for sym in props.symbols() {
+            let accepting = props.valuation(sym).contains("p");
+            dba.add_edge(q0, sym, q1, accepting);
+            dba.add_edge(q1, sym, q0, false);
+        }
+        let hoa = dba
+            .to_hoa_automaton(&HoaEncoding::Propositional(&props))
+            .unwrap();
+        let (imported, alphabet) = hoa_to_dba(&hoa).unwrap();
+        assert_eq!(alphabet, props);
+        assert_eq!(edges(&imported, 0), edges(&dba, q0));
+        assert_eq!(edges(&imported, 1), edges(&dba, q1));
+    }
+}
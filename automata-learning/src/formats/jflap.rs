@@ -0,0 +1,497 @@
+//! Reading and writing of JFLAP `.jff` files. Finite automata (`fa`), Mealy machines (`mealy`)
+//! and Moore machines (`moore`) are supported. Symbols must be single characters, outputs of
+//! Mealy and Moore machines are read as strings. Transitions on the empty word (lambda
+//! transitions) are rejected.
+
+use std::fmt::Write;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+
+use crate::ordered::SortedTransitionSystem;
+
+/// Abstracts the types of errors that can occur when reading a JFLAP file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum JflapError {
+    Xml(String),
+    UnsupportedType(String),
+    MissingElement(&'static str),
+    MalformedId(String),
+    UnknownState(u32),
+    MalformedSymbol(String),
+    LambdaTransition(u32),
+    MissingInitial,
+    Nondeterministic(u32, char),
+    WrongType(JflapType),
+}
+
+impl std::fmt::Display for JflapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JflapError::Xml(err) => write!(f, "Malformed XML: {err}"),
+            JflapError::UnsupportedType(t) => write!(f, "Unsupported automaton type {t}"),
+            JflapError::MissingElement(e) => write!(f, "Missing element <{e}>"),
+            JflapError::MalformedId(id) => write!(f, "Malformed state id {id}"),
+            JflapError::UnknownState(id) => write!(f, "State {id} does not exist"),
+            JflapError::MalformedSymbol(sym) => {
+                write!(
+                    f,
+                    "Malformed symbol {sym}, symbols must be single characters"
+                )
+            }
+            JflapError::LambdaTransition(id) => {
+                write!(
+                    f,
+                    "State {id} has a lambda transition, which is not supported"
+                )
+            }
+            JflapError::MissingInitial => write!(f, "No initial state is given"),
+            JflapError::Nondeterministic(id, sym) => {
+                write!(f, "State {id} has multiple transitions on {sym}")
+            }
+            JflapError::WrongType(t) => write!(f, "Automaton has unexpected type {t}"),
+        }
+    }
+}
+
+/// The type of automaton that is stored in a JFLAP file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JflapType {
+    /// A finite automaton, which may be nondeterministic.
+    FiniteAutomaton,
+    /// A Mealy machine, which has outputs on transitions.
+    Mealy,
+    /// A Moore machine, which has outputs on states.
+    Moore,
+}
+
+impl std::fmt::Display for JflapType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                JflapType::FiniteAutomaton => "fa",
+                JflapType::Mealy => "mealy",
+                JflapType::Moore => "moore",
+            }
+        )
+    }
+}
+
+/// A state of a [`JflapAutomaton`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JflapState {
+    /// The numeric id of the state.
+    pub id: u32,
+    /// The name of the state, which is shown in JFLAP.
+    pub name: String,
+    /// Whether the state is initial.
+    pub initial: bool,
+    /// Whether the state is final, only relevant for finite automata.
+    pub accepting: bool,
+    /// The output of the state, only relevant for Moore machines.
+    pub output: Option<String>,
+}
+
+/// A transition of a [`JflapAutomaton`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JflapTransition {
+    /// The id of the source state.
+    pub from: u32,
+    /// The id of the target state.
+    pub to: u32,
+    /// The symbol that is read, `None` for lambda transitions.
+    pub read: Option<char>,
+    /// The output of the transition, only relevant for Mealy machines.
+    pub output: Option<String>,
+}
+
+/// An automaton as it is stored in a JFLAP file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JflapAutomaton {
+    /// The type of the automaton.
+    pub kind: JflapType,
+    /// The states of the automaton.
+    pub states: Vec<JflapState>,
+    /// The transitions of the automaton.
+    pub transitions: Vec<JflapTransition>,
+}
+
+fn child_text<'a>(node: roxmltree::Node<'a, 'a>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .map(|child| child.text().unwrap_or_default())
+}
+
+fn parse_id(id: &str) -> Result<u32, JflapError> {
+    id.trim()
+        .parse()
+        .map_err(|_| JflapError::MalformedId(id.to_string()))
+}
+
+fn parse_symbol(sym: &str) -> Result<Option<char>, JflapError> {
+    let mut chars = sym.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => Ok(None),
+        (Some(c), None) => Ok(Some(c)),
+        _ => Err(JflapError::MalformedSymbol(sym.to_string())),
+    }
+}
+
+/// Escapes the characters that have a special meaning in XML.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl TryFrom<&str> for JflapAutomaton {
+    type Error = JflapError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let document =
+            roxmltree::Document::parse(value).map_err(|err| JflapError::Xml(err.to_string()))?;
+        let structure = document.root_element();
+        let kind = match child_text(structure, "type").map(|t| t.trim()) {
+            Some("fa") => JflapType::FiniteAutomaton,
+            Some("mealy") => JflapType::Mealy,
+            Some("moore") => JflapType::Moore,
+            Some(other) => return Err(JflapError::UnsupportedType(other.to_string())),
+            None => return Err(JflapError::MissingElement("type")),
+        };
+        // newer versions of JFLAP wrap the states and transitions in an automaton element
+        let automaton = structure
+            .children()
+            .find(|child| child.has_tag_name("automaton"))
+            .unwrap_or(structure);
+
+        let mut states = vec![];
+        for node in automaton.children().filter(|c| c.has_tag_name("state")) {
+            let id = parse_id(
+                node.attribute("id")
+                    .ok_or(JflapError::MissingElement("id"))?,
+            )?;
+            states.push(JflapState {
+                id,
+                name: node
+                    .attribute("name")
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("q{id}")),
+                initial: node.children().any(|c| c.has_tag_name("initial")),
+                accepting: node.children().any(|c| c.has_tag_name("final")),
+                output: child_text(node, "output").map(|o| o.to_string()),
+            });
+        }
+
+        let ids: Set<_> = states.iter().map(|q| q.id).collect();
+        let mut transitions = vec![];
+        for node in automaton
+            .children()
+            .filter(|c| c.has_tag_name("transition"))
+        {
+            let from =
+                parse_id(child_text(node, "from").ok_or(JflapError::MissingElement("from"))?)?;
+            let to = parse_id(child_text(node, "to").ok_or(JflapError::MissingElement("to"))?)?;
+            for id in [from, to] {
+                if !ids.contains(&id) {
+                    return Err(JflapError::UnknownState(id));
+                }
+            }
+            transitions.push(JflapTransition {
+                from,
+                to,
+                read: parse_symbol(child_text(node, "read").unwrap_or_default())?,
+                output: child_text(node, "transout").map(|o| o.to_string()),
+            });
+        }
+
+        Ok(Self {
+            kind,
+            states,
+            transitions,
+        })
+    }
+}
+
+impl JflapAutomaton {
+    /// Builds the representation of a transition system with boolean state colors, i.e. a
+    /// DFA or NFA. States that are colored `true` are final.
+    pub fn from_acceptor<Ts>(ts: &Ts) -> Self
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet, StateColor = bool>,
+    {
+        Self::from_ts(ts, JflapType::FiniteAutomaton, |c| (*c, None), |_| None)
+    }
+
+    /// Builds the representation of a Mealy machine, the outputs are obtained with [`Show`].
+    pub fn from_mealy<M>(mm: &M) -> Self
+    where
+        M: TransitionSystem<Alphabet = CharAlphabet>,
+        M::EdgeColor: Show,
+    {
+        Self::from_ts(mm, JflapType::Mealy, |_| (false, None), |c| Some(c.show()))
+    }
+
+    /// Builds the representation of a Moore machine, the outputs are obtained with [`Show`].
+    pub fn from_moore<M>(mm: &M) -> Self
+    where
+        M: TransitionSystem<Alphabet = CharAlphabet>,
+        M::StateColor: Show,
+    {
+        Self::from_ts(mm, JflapType::Moore, |c| (false, Some(c.show())), |_| None)
+    }
+
+    fn from_ts<Ts, F, G>(ts: &Ts, kind: JflapType, state: F, edge: G) -> Self
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet>,
+        F: Fn(&Ts::StateColor) -> (bool, Option<String>),
+        G: Fn(&Ts::EdgeColor) -> Option<String>,
+    {
+        let indices = ts.sorted_state_indices();
+        let position: Map<_, _> = indices
+            .iter()
+            .enumerate()
+            .map(|(i, q)| (*q, i as u32))
+            .collect();
+        let initial = ts.maybe_initial_state();
+        let states = indices
+            .iter()
+            .map(|q| {
+                let (accepting, output) = state(&ts.state_color(*q).expect("State must exist"));
+                JflapState {
+                    id: position[q],
+                    name: format!("q{}", position[q]),
+                    initial: Some(*q) == initial,
+                    accepting,
+                    output,
+                }
+            })
+            .collect();
+        let transitions = ts
+            .sorted_edges()
+            .into_iter()
+            .map(|(source, (symbol, color, target))| JflapTransition {
+                from: position[&source],
+                to: position[&target],
+                read: Some(symbol),
+                output: edge(&color),
+            })
+            .collect();
+        Self {
+            kind,
+            states,
+            transitions,
+        }
+    }
+
+    /// Writes the automaton as a JFLAP file. The states are arranged on a circle.
+    pub fn to_jff(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>"
+        )
+        .unwrap();
+        writeln!(out, "<structure>").unwrap();
+        writeln!(out, "  <type>{}</type>", self.kind).unwrap();
+        writeln!(out, "  <automaton>").unwrap();
+        let radius = 50.0 * (self.states.len().max(2) as f64);
+        for (i, q) in self.states.iter().enumerate() {
+            let angle = 2.0 * std::f64::consts::PI * (i as f64) / (self.states.len() as f64);
+            writeln!(
+                out,
+                "    <state id=\"{}\" name=\"{}\">",
+                q.id,
+                escape(&q.name)
+            )
+            .unwrap();
+            writeln!(out, "      <x>{:.1}</x>", radius * (1.0 + angle.cos())).unwrap();
+            writeln!(out, "      <y>{:.1}</y>", radius * (1.0 + angle.sin())).unwrap();
+            if q.initial {
+                writeln!(out, "      <initial/>").unwrap();
+            }
+            if q.accepting {
+                writeln!(out, "      <final/>").unwrap();
+            }
+            if let Some(output) = &q.output {
+                writeln!(out, "      <output>{}</output>", escape(output)).unwrap();
+            }
+            writeln!(out, "    </state>").unwrap();
+        }
+        for t in &self.transitions {
+            writeln!(out, "    <transition>").unwrap();
+            writeln!(out, "      <from>{}</from>", t.from).unwrap();
+            writeln!(out, "      <to>{}</to>", t.to).unwrap();
+            match t.read {
+                Some(sym) => writeln!(out, "      <read>{}</read>", escape(&sym.to_string())),
+                None => writeln!(out, "      <read/>"),
+            }
+            .unwrap();
+            if let Some(output) = &t.output {
+                writeln!(out, "      <transout>{}</transout>", escape(output)).unwrap();
+            }
+            writeln!(out, "    </transition>").unwrap();
+        }
+        writeln!(out, "  </automaton>").unwrap();
+        writeln!(out, "</structure>").unwrap();
+        out
+    }
+
+    /// Collects the symbols that appear on transitions into an alphabet.
+    pub fn alphabet(&self) -> CharAlphabet {
+        self.transitions
+            .iter()
+            .filter_map(|t| t.read)
+            .sorted()
+            .dedup()
+            .collect_vec()
+            .into()
+    }
+
+    fn expect_kind(&self, kind: JflapType) -> Result<(), JflapError> {
+        if self.kind != kind {
+            return Err(JflapError::WrongType(self.kind));
+        }
+        Ok(())
+    }
+
+    /// Builds a deterministic transition system, where the initial state is placed at index 0
+    /// and the remaining states follow in the order of their appearance.
+    fn build_dts<Q: Color, C: Color>(
+        &self,
+        state_color: impl Fn(&JflapState) -> Q,
+        edge_color: impl Fn(&JflapTransition) -> C,
+    ) -> Result<DTS<CharAlphabet, Q, C>, JflapError> {
+        let mut initial = self.states.iter().filter(|q| q.initial);
+        let Some(init) = initial.next() else {
+            return Err(JflapError::MissingInitial);
+        };
+        let mut ts = DTS::new_for_alphabet(self.alphabet());
+        let mut indices = Map::default();
+        for q in std::iter::once(init).chain(self.states.iter().filter(|q| q.id != init.id)) {
+            indices.insert(q.id, ts.add_state(state_color(q)));
+        }
+        let mut seen = Set::default();
+        for t in &self.transitions {
+            let Some(sym) = t.read else {
+                return Err(JflapError::LambdaTransition(t.from));
+            };
+            if !seen.insert((t.from, sym)) {
+                return Err(JflapError::Nondeterministic(t.from, sym));
+            }
+            ts.add_edge(indices[&t.from], sym, indices[&t.to], edge_color(t));
+        }
+        Ok(ts)
+    }
+
+    /// Converts a deterministic finite automaton into a [`DFA`].
+    pub fn into_dfa(&self) -> Result<DFA, JflapError> {
+        self.expect_kind(JflapType::FiniteAutomaton)?;
+        Ok(self
+            .build_dts(|q| q.accepting, |_| Void)?
+            .with_initial(0)
+            .into_dfa())
+    }
+
+    /// Converts a (possibly nondeterministic) finite automaton into an [`NTS`] whose states
+    /// are colored with `true` if they are final. The states appear in the same order as in
+    /// the file, the indices of the initial states are returned as well.
+    pub fn into_nfa(&self) -> Result<(NTS<CharAlphabet, bool, Void>, Vec<usize>), JflapError> {
+        self.expect_kind(JflapType::FiniteAutomaton)?;
+        let mut ts = NTS::new_for_alphabet(self.alphabet());
+        let mut indices = Map::default();
+        let mut initial = vec![];
+        for q in &self.states {
+            let idx = ts.add_state(q.accepting);
+            if q.initial {
+                initial.push(idx);
+            }
+            indices.insert(q.id, idx);
+        }
+        for t in &self.transitions {
+            let Some(sym) = t.read else {
+                return Err(JflapError::LambdaTransition(t.from));
+            };
+            ts.add_edge(indices[&t.from], sym, indices[&t.to], Void);
+        }
+        Ok((ts, initial))
+    }
+
+    /// Converts a Mealy machine, missing outputs are treated as empty strings.
+    pub fn into_mealy(&self) -> Result<MealyMachine<CharAlphabet, String>, JflapError> {
+        self.expect_kind(JflapType::Mealy)?;
+        Ok(self
+            .build_dts(|_| Void, |t| t.output.clone().unwrap_or_default())?
+            .with_initial(0)
+            .into_mealy())
+    }
+
+    /// Converts a Moore machine, missing outputs are treated as empty strings.
+    pub fn into_moore(&self) -> Result<MooreMachine<CharAlphabet, String>, JflapError> {
+        self.expect_kind(JflapType::Moore)?;
+        Ok(self
+            .build_dts(|q| q.output.clone().unwrap_or_default(), |_| Void)?
+            .with_initial(0)
+            .into_moore())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{JflapAutomaton, JflapError, JflapType};
+
+    #[test]
+    fn jflap_dfa_round_trip() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+
+        let jff = JflapAutomaton::from_acceptor(&dfa).to_jff();
+        let parsed = JflapAutomaton::try_from(jff.as_str()).unwrap();
+        assert_eq!(parsed.kind, JflapType::FiniteAutomaton);
+        let restored = parsed.into_dfa().unwrap();
+        for word in ["", "a", "ab", "aba", "bbaab"] {
+            assert_eq!(restored.accepts(word), dfa.accepts(word));
+        }
+        assert!(parsed.into_mealy().is_err());
+    }
+
+    #[test]
+    fn jflap_mealy_without_automaton_element() {
+        let parsed = JflapAutomaton::try_from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+            <structure>
+                <type>mealy</type>
+                <state id="0" name="q0"><x>0</x><y>0</y><initial/></state>
+                <state id="1" name="q1"><x>0</x><y>0</y></state>
+                <transition><from>0</from><to>1</to><read>a</read><transout>x</transout></transition>
+                <transition><from>1</from><to>0</to><read>a</read><transout>y</transout></transition>
+            </structure>"#,
+        )
+        .unwrap();
+        let mm = parsed.into_mealy().unwrap();
+        assert_eq!(mm.try_mealy_map("aa"), Some("y".to_string()));
+
+        let lambda = r#"<structure><type>fa</type>
+            <state id="0"><initial/></state>
+            <transition><from>0</from><to>0</to><read/></transition>
+        </structure>"#;
+        assert_eq!(
+            JflapAutomaton::try_from(lambda)
+                .unwrap()
+                .into_dfa()
+                .map(|_| ()),
+            Err(JflapError::LambdaTransition(0))
+        );
+    }
+}
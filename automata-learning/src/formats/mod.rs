@@ -0,0 +1,61 @@
+//! Conversion of transition systems from and to textual exchange formats, which allows
+//! interoperating with external tools such as graphviz.
+
+/// The canonical order and string form of alphabets, which exporters use for listing symbols.
+pub mod alphabet;
+pub use alphabet::{CanonicalAlphabet, CanonicalAlphabetError};
+
+/// Parsing of transition systems and DFAs from graphviz DOT files.
+pub mod dot;
+pub use dot::{DotEdge, DotGraph, DotNode, DotParseError};
+
+/// Customizable rendering of transition systems in the DOT format.
+pub mod dot_writer;
+pub use dot_writer::{DotAttributes, DotOptions, RankDir, ToDot};
+
+/// Rendering of transition systems to SVG, PNG and PDF files using graphviz.
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "render")]
+pub use render::{render_dot, render_dot_to_file, Render, RenderError, RenderFormat};
+
+/// Transition tables of deterministic transition systems for printing them in a terminal.
+pub mod table;
+pub use table::{ToTable, TransitionTable};
+
+/// Export of transition systems as TikZ pictures for inclusion in LaTeX documents.
+pub mod tikz;
+pub use tikz::{TikzAccepting, TikzOptions, TikzSide, ToTikz};
+
+/// Import and export of transition systems in the GraphML format.
+pub mod graphml;
+pub use graphml::{GraphMlEdge, GraphMlError, GraphMlGraph, GraphMlNode};
+
+/// Versioned representations of automata and samples that can be serialized with serde.
+#[cfg(feature = "serde")]
+pub mod serialize;
+#[cfg(feature = "serde")]
+pub use serialize::{
+    ClassRepr, CongruenceRepr, ForcRepr, ReprError, SampleRepr, StableCongruenceRepr,
+    StableForcRepr, TsRepr, FORMAT_VERSION,
+};
+
+/// Saving and loading of serializable values in a compact, checksummed binary format.
+#[cfg(feature = "persist")]
+pub mod persist;
+#[cfg(feature = "persist")]
+pub use persist::{load, save, Persist, PersistError};
+
+/// Export and import of automata in the HOA format.
+pub mod hoa;
+pub use hoa::{
+    hoa_to_dba, hoa_to_dpa, hoa_to_nba, HoaEncoding, HoaExportError, HoaImportError, ToHoa,
+};
+
+/// Import and export of automata in the BA format used by RABIT and GOAL.
+pub mod ba;
+pub use ba::{BaAutomaton, BaError, BaTransition};
+
+/// Import and export of JFLAP files for finite automata, Mealy and Moore machines.
+pub mod jflap;
+pub use jflap::{JflapAutomaton, JflapError, JflapType};
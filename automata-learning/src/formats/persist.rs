@@ -0,0 +1,209 @@
+//! Compact binary persistence for everything that can be serialized with serde, in particular
+//! the representations from [`super::serialize`]. Values are encoded with bincode and written
+//! into a container that consists of
+//! - the magic bytes `LAMA`,
+//! - the [`FORMAT_VERSION`] as a little endian `u32`,
+//! - the length of the payload as a little endian `u64`,
+//! - the CRC32 checksum of the payload as a little endian `u32`, and
+//! - the payload itself.
+//!
+//! When loading, the magic bytes, version, length and checksum are verified before the payload
+//! is decoded, so truncated or corrupted files are detected reliably.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::debug;
+
+use super::serialize::FORMAT_VERSION;
+
+const MAGIC: &[u8; 4] = b"LAMA";
+const HEADER_LENGTH: usize = 4 + 4 + 8 + 4;
+
+/// Abstracts the types of errors that can occur when saving or loading.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum PersistError {
+    Io(std::io::Error),
+    Encoding(bincode::Error),
+    MissingMagic,
+    UnsupportedVersion(u32),
+    LengthMismatch { expected: u64, found: u64 },
+    ChecksumMismatch { expected: u32, found: u32 },
+}
+
+impl std::fmt::Display for PersistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersistError::Io(err) => write!(f, "I/O error: {err}"),
+            PersistError::Encoding(err) => write!(f, "Could not encode or decode: {err}"),
+            PersistError::MissingMagic => write!(f, "Not a file written by lama"),
+            PersistError::UnsupportedVersion(v) => write!(
+                f,
+                "Unsupported format version {v}, at most {FORMAT_VERSION} is supported"
+            ),
+            PersistError::LengthMismatch { expected, found } => write!(
+                f,
+                "Payload should have {expected} bytes but has {found}, the file is truncated"
+            ),
+            PersistError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "Checksum mismatch, expected {expected:#010x} but found {found:#010x}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl From<std::io::Error> for PersistError {
+    fn from(value: std::io::Error) -> Self {
+        PersistError::Io(value)
+    }
+}
+
+impl From<bincode::Error> for PersistError {
+    fn from(value: bincode::Error) -> Self {
+        PersistError::Encoding(value)
+    }
+}
+
+/// Encodes `value` into the binary container format.
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, PersistError> {
+    let payload = bincode::serialize(value)?;
+    let mut out = Vec::with_capacity(HEADER_LENGTH + payload.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    out.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+/// Decodes a value from the binary container format, verifying its integrity.
+pub fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, PersistError> {
+    if bytes.len() < HEADER_LENGTH || &bytes[..4] != MAGIC {
+        return Err(PersistError::MissingMagic);
+    }
+    let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    if version > FORMAT_VERSION {
+        return Err(PersistError::UnsupportedVersion(version));
+    }
+    let expected = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+    let payload = &bytes[HEADER_LENGTH..];
+    if payload.len() as u64 != expected {
+        return Err(PersistError::LengthMismatch {
+            expected,
+            found: payload.len() as u64,
+        });
+    }
+    let checksum = u32::from_le_bytes(bytes[16..20].try_into().unwrap());
+    let found = crc32fast::hash(payload);
+    if checksum != found {
+        return Err(PersistError::ChecksumMismatch {
+            expected: checksum,
+            found,
+        });
+    }
+    Ok(bincode::deserialize(payload)?)
+}
+
+/// Saves `value` to the file at `path`, overwriting it if it exists.
+pub fn save<T: Serialize + ?Sized, P: AsRef<Path>>(value: &T, path: P) -> Result<(), PersistError> {
+    let start = std::time::Instant::now();
+    let bytes = to_bytes(value)?;
+    let mut writer = BufWriter::new(File::create(path.as_ref())?);
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    debug!(
+        "Saving {} bytes to {} took {}ms",
+        bytes.len(),
+        path.as_ref().display(),
+        start.elapsed().as_millis()
+    );
+    Ok(())
+}
+
+/// Loads a value from the file at `path`, which must have been written by [`save`].
+pub fn load<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T, PersistError> {
+    let start = std::time::Instant::now();
+    let mut bytes = vec![];
+    BufReader::new(File::open(path.as_ref())?).read_to_end(&mut bytes)?;
+    let value = from_bytes(&bytes)?;
+    debug!(
+        "Loading {} bytes from {} took {}ms",
+        bytes.len(),
+        path.as_ref().display(),
+        start.elapsed().as_millis()
+    );
+    Ok(value)
+}
+
+/// Convenience trait that allows calling [`save`] and [`load`] as methods, it is implemented
+/// for all types that can be serialized and deserialized.
+pub trait Persist: Serialize + DeserializeOwned {
+    /// Saves `self` to the file at `path`, see [`save`].
+    fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PersistError> {
+        save(self, path)
+    }
+
+    /// Loads a value from the file at `path`, see [`load`].
+    fn load<P: AsRef<Path>>(path: P) -> Result<Self, PersistError> {
+        load(path)
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Persist for T {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{from_bytes, to_bytes, Persist, PersistError};
+    use crate::formats::TsRepr;
+
+    fn chain(n: usize) -> TsRepr<bool, ()> {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let states = (0..n)
+            .map(|i| dfa.add_state(i % 3 == 0))
+            .collect::<Vec<_>>();
+        for i in 0..n {
+            dfa.add_edge(states[i], 'a', states[(i + 1) % n], Void);
+            dfa.add_edge(states[i], 'b', states[(i * 7) % n], Void);
+        }
+        TsRepr::from_dfa(&dfa)
+    }
+
+    #[test]
+    fn persist_round_trip() {
+        let repr = chain(1000);
+        let path = std::env::temp_dir().join(format!("lama-persist-{}.bin", std::process::id()));
+        repr.save(&path).unwrap();
+        let loaded = TsRepr::<bool, ()>::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(repr, loaded);
+    }
+
+    #[test]
+    fn persist_detects_corruption() {
+        let mut bytes = to_bytes(&chain(10)).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(matches!(
+            from_bytes::<TsRepr<bool, ()>>(&bytes),
+            Err(PersistError::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            from_bytes::<TsRepr<bool, ()>>(&bytes[..bytes.len() - 3]),
+            Err(PersistError::LengthMismatch { .. })
+        ));
+        assert!(matches!(
+            from_bytes::<TsRepr<bool, ()>>(b"nope"),
+            Err(PersistError::MissingMagic)
+        ));
+    }
+}
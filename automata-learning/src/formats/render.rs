@@ -0,0 +1,205 @@
+//! Rendering of DOT output to images by invoking a locally installed graphviz `dot` binary. The
+//! binary is looked up in the `PATH`, unless the environment variable `LAMA_DOT` points to a
+//! different executable.
+
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use tracing::debug;
+
+use super::ToDot;
+
+/// Abstracts the types of errors that can occur when rendering.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum RenderError {
+    DotNotFound(std::io::Error),
+    Io(std::io::Error),
+    UnsupportedFormat(String),
+    Graphviz(String),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::DotNotFound(err) => {
+                write!(f, "Could not run graphviz, is dot installed? ({err})")
+            }
+            RenderError::Io(err) => write!(f, "I/O error: {err}"),
+            RenderError::UnsupportedFormat(ext) => {
+                write!(f, "Cannot render to files with extension \"{ext}\"")
+            }
+            RenderError::Graphviz(msg) => write!(f, "graphviz failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<std::io::Error> for RenderError {
+    fn from(value: std::io::Error) -> Self {
+        RenderError::Io(value)
+    }
+}
+
+/// The output formats that are supported by [`render_dot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderFormat {
+    /// Scalable vector graphics.
+    Svg,
+    /// Portable network graphics.
+    Png,
+    /// Portable document format.
+    Pdf,
+}
+
+impl RenderFormat {
+    /// Determines the format from the extension of `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, RenderError> {
+        let ext = path
+            .as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        match ext.as_str() {
+            "svg" => Ok(RenderFormat::Svg),
+            "png" => Ok(RenderFormat::Png),
+            "pdf" => Ok(RenderFormat::Pdf),
+            _ => Err(RenderError::UnsupportedFormat(ext)),
+        }
+    }
+
+    fn flag(&self) -> &'static str {
+        match self {
+            RenderFormat::Svg => "-Tsvg",
+            RenderFormat::Png => "-Tpng",
+            RenderFormat::Pdf => "-Tpdf",
+        }
+    }
+}
+
+fn dot_binary() -> String {
+    std::env::var("LAMA_DOT").unwrap_or_else(|_| "dot".to_string())
+}
+
+/// Returns true if the `dot` binary can be executed.
+pub fn dot_available() -> bool {
+    Command::new(dot_binary())
+        .arg("-V")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Feeds `dot` into graphviz and returns the rendered bytes in the given `format`.
+pub fn render_dot(dot: &str, format: RenderFormat) -> Result<Vec<u8>, RenderError> {
+    let start = std::time::Instant::now();
+    let mut child = Command::new(dot_binary())
+        .arg(format.flag())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(RenderError::DotNotFound)?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(dot.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(RenderError::Graphviz(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    debug!(
+        "Rendering {format:?} with graphviz took {}ms",
+        start.elapsed().as_millis()
+    );
+    Ok(output.stdout)
+}
+
+/// Renders `dot` into the file at `path`, the format is determined by the extension.
+pub fn render_dot_to_file<P: AsRef<Path>>(dot: &str, path: P) -> Result<(), RenderError> {
+    let bytes = render_dot(dot, RenderFormat::from_path(path.as_ref())?)?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Renders transition systems and FORCs to images, it is implemented for everything that
+/// implements [`ToDot`] with options of type `O`.
+pub trait Render<O>: ToDot<O> {
+    /// Renders `self` with the given `options` into the file at `path`. The extension of
+    /// `path` determines the format and must be one of `svg`, `png` or `pdf`.
+    fn render_to_file_with<P: AsRef<Path>>(&self, options: &O, path: P) -> Result<(), RenderError> {
+        render_dot_to_file(&self.to_dot_with(options), path)
+    }
+
+    /// Renders `self` with the default options into the file at `path`.
+    fn render_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), RenderError>
+    where
+        O: Default,
+    {
+        self.render_to_file_with(&O::default(), path)
+    }
+
+    /// Renders `self` with the given `options` and returns the resulting SVG.
+    fn render_to_svg_string_with(&self, options: &O) -> Result<String, RenderError> {
+        let bytes = render_dot(&self.to_dot_with(options), RenderFormat::Svg)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Renders `self` with the default options and returns the resulting SVG.
+    fn render_to_svg_string(&self) -> Result<String, RenderError>
+    where
+        O: Default,
+    {
+        self.render_to_svg_string_with(&O::default())
+    }
+}
+
+impl<O, T: ToDot<O>> Render<O> for T {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{dot_available, Render, RenderError, RenderFormat};
+
+    #[test]
+    fn render_format_from_extension() {
+        assert_eq!(RenderFormat::from_path("a.svg").unwrap(), RenderFormat::Svg);
+        assert_eq!(
+            RenderFormat::from_path("a/b.PNG").unwrap(),
+            RenderFormat::Png
+        );
+        assert!(matches!(
+            RenderFormat::from_path("a.txt"),
+            Err(RenderError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn render_svg() {
+        if !dot_available() {
+            return;
+        }
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        dfa.add_edge(q0, 'a', q0, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        let svg = dfa.render_to_svg_string().unwrap();
+        assert!(svg.contains("<svg"));
+
+        let path = std::env::temp_dir().join(format!("lama-render-{}.png", std::process::id()));
+        dfa.render_to_file(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,699 @@
+//! Stable, versioned representations of transition systems, congruences and samples that
+//! can be (de)serialized with serde. As the automata themselves are defined in the `automata`
+//! crate, they are converted into the representation types defined here, which are then
+//! serialized. Only automata over a [`CharAlphabet`] are supported.
+//!
+//! Every representation carries a `version` field that is set to [`FORMAT_VERSION`]. When
+//! converting back, representations with a newer version are rejected.
+
+use automata::{prelude::*, word::OmegaWord, Map, RightCongruence, Set};
+use itertools::Itertools;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::CanonicalAlphabet;
+use crate::{
+    checked::{CheckedSproutable, CheckedTransitionSystem, TransitionSystemError},
+    congruence::ClassNavigation,
+    passive::{FiniteSample, OmegaSample, Sample},
+};
+
+/// The version of the serialized representations, which is incremented whenever the
+/// representation changes in an incompatible way.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Abstracts the types of errors that can occur when converting a representation back into
+/// an automaton or sample.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum ReprError {
+    UnsupportedVersion(u32),
+    MissingInitial,
+    Malformed(TransitionSystemError<usize, char>),
+    Nondeterministic(usize, char),
+    UnknownClass(String),
+}
+
+impl std::fmt::Display for ReprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReprError::UnsupportedVersion(v) => write!(
+                f,
+                "Unsupported format version {v}, at most {FORMAT_VERSION} is supported"
+            ),
+            ReprError::MissingInitial => write!(f, "No initial state is given"),
+            ReprError::Malformed(err) => write!(f, "{err}"),
+            ReprError::Nondeterministic(q, sym) => {
+                write!(f, "State {q} has multiple transitions on {sym}")
+            }
+            ReprError::UnknownClass(rep) => {
+                write!(f, "No class has the representative \"{rep}\"")
+            }
+        }
+    }
+}
+
+impl From<TransitionSystemError<usize, char>> for ReprError {
+    fn from(value: TransitionSystemError<usize, char>) -> Self {
+        ReprError::Malformed(value)
+    }
+}
+
+fn check_version(version: u32) -> Result<(), ReprError> {
+    if version > FORMAT_VERSION {
+        Err(ReprError::UnsupportedVersion(version))
+    } else {
+        Ok(())
+    }
+}
+
+/// A single edge of a [`TsRepr`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeRepr<C> {
+    /// The index of the source state.
+    pub source: usize,
+    /// The symbol on which the edge is taken.
+    pub symbol: char,
+    /// The color of the edge.
+    pub color: C,
+    /// The index of the target state.
+    pub target: usize,
+}
+
+/// Serializable representation of a transition system with state colors `Q` and edge colors
+/// `C`. States are numbered consecutively, state `i` has color `states[i]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TsRepr<Q, C> {
+    /// The version of the representation.
+    pub version: u32,
+    /// The symbols of the alphabet.
+    pub alphabet: Vec<char>,
+    /// The index of the initial state, if there is one.
+    pub initial: Option<usize>,
+    /// The colors of the states.
+    pub states: Vec<Q>,
+    /// The edges of the transition system.
+    pub edges: Vec<EdgeRepr<C>>,
+}
+
+impl<Q: Clone, C: Clone> TsRepr<Q, C> {
+    /// Builds the representation of the given transition system. The states are renumbered
+    /// in ascending order of their indices.
+    pub fn from_ts<Ts>(ts: &Ts) -> Self
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet, StateColor = Q, EdgeColor = C>,
+    {
+        Self::from_ts_mapped(ts, |q| q, |c| c)
+    }
+
+    /// Builds the representation of the given transition system, where state and edge colors
+    /// are transformed by the given functions. This is used for colors such as [`Void`], which
+    /// are stored as `()`.
+    pub fn from_ts_mapped<Ts, F, G>(ts: &Ts, state_color: F, edge_color: G) -> Self
+    where
+        Ts: TransitionSystem<Alphabet = CharAlphabet>,
+        F: Fn(Ts::StateColor) -> Q,
+        G: Fn(Ts::EdgeColor) -> C,
+    {
+        let states = ts.state_indices().sorted().collect_vec();
+        let position: Map<_, _> = states.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+        let edges = states
+            .iter()
+            .flat_map(|q| ts.edges_from(*q).expect("State must exist"))
+            .map(|e| EdgeRepr {
+                source: position[&e.source()],
+                symbol: *e.expression(),
+                color: edge_color(e.color()),
+                target: position[&e.target()],
+            })
+            .sorted_by_key(|e| (e.source, e.symbol, e.target))
+            .collect();
+        Self {
+            version: FORMAT_VERSION,
+            alphabet: CanonicalAlphabet::of(ts.alphabet()).symbols().to_vec(),
+            initial: ts.maybe_initial_state().map(|q| position[&q]),
+            states: states
+                .iter()
+                .map(|q| state_color(ts.state_color(*q).expect("State must exist")))
+                .collect(),
+            edges,
+        }
+    }
+
+    fn initial(&self) -> Result<usize, ReprError> {
+        self.initial.ok_or(ReprError::MissingInitial)
+    }
+
+    /// Builds a deterministic transition system from the representation, transforming the
+    /// colors with the given functions.
+    fn build_dts<P: Color, D: Color>(
+        &self,
+        state_color: impl Fn(&Q) -> P,
+        edge_color: impl Fn(&C) -> D,
+    ) -> Result<DTS<CharAlphabet, P, D>, ReprError> {
+        check_version(self.version)?;
+        let mut ts = DTS::new_for_alphabet(CharAlphabet::from_iter(self.alphabet.clone()));
+        for color in &self.states {
+            ts.add_state(state_color(color));
+        }
+        let mut seen = Set::default();
+        for e in &self.edges {
+            if !seen.insert((e.source, e.symbol)) {
+                return Err(ReprError::Nondeterministic(e.source, e.symbol));
+            }
+            ts.try_add_edge(e.source, e.symbol, e.target, edge_color(&e.color))?;
+        }
+        Ok(ts)
+    }
+}
+
+impl<Q: Color, C: Color> TsRepr<Q, C> {
+    /// Converts the representation into a nondeterministic transition system.
+    pub fn into_nts(&self) -> Result<NTS<CharAlphabet, Q, C>, ReprError> {
+        check_version(self.version)?;
+        let mut ts = NTS::new_for_alphabet(CharAlphabet::from_iter(self.alphabet.clone()));
+        for color in &self.states {
+            ts.add_state(color.clone());
+        }
+        for e in &self.edges {
+            ts.try_add_edge(e.source, e.symbol, e.target, e.color.clone())?;
+        }
+        Ok(ts)
+    }
+
+    /// Converts the representation into a deterministic transition system, which fails if
+    /// there is more than one edge leaving a state on the same symbol.
+    pub fn into_dts(&self) -> Result<DTS<CharAlphabet, Q, C>, ReprError> {
+        self.build_dts(Q::clone, C::clone)
+    }
+}
+
+impl TsRepr<bool, ()> {
+    /// Builds the representation of a [`DFA`].
+    pub fn from_dfa<D: DFALike<Alphabet = CharAlphabet>>(dfa: &D) -> Self {
+        Self::from_ts_mapped(dfa, |q| q, |_| ())
+    }
+
+    /// Converts the representation into a [`DFA`].
+    pub fn into_dfa(&self) -> Result<DFA, ReprError> {
+        let ts = self
+            .build_dts(|q| *q, |_| Void)?
+            .with_initial(self.initial()?);
+        ts.try_initial()?;
+        Ok(ts.into_dfa())
+    }
+}
+
+impl<C: Color> TsRepr<(), C> {
+    /// Builds the representation of a [`MealyMachine`].
+    pub fn from_mealy<M: MealyLike<Alphabet = CharAlphabet, EdgeColor = C>>(mm: &M) -> Self {
+        Self::from_ts_mapped(mm, |_| (), |c| c)
+    }
+
+    /// Converts the representation into a [`MealyMachine`].
+    pub fn into_mealy(&self) -> Result<MealyMachine<CharAlphabet, C>, ReprError> {
+        let ts = self
+            .build_dts(|_| Void, C::clone)?
+            .with_initial(self.initial()?);
+        ts.try_initial()?;
+        Ok(ts.into_mealy())
+    }
+}
+
+impl<Q: Color> TsRepr<Q, ()> {
+    /// Builds the representation of a [`MooreMachine`].
+    pub fn from_moore<M: MooreLike<Alphabet = CharAlphabet, StateColor = Q>>(mm: &M) -> Self {
+        Self::from_ts_mapped(mm, |q| q, |_| ())
+    }
+
+    /// Converts the representation into a [`MooreMachine`].
+    pub fn into_moore(&self) -> Result<MooreMachine<CharAlphabet, Q>, ReprError> {
+        let ts = self
+            .build_dts(Q::clone, |_| Void)?
+            .with_initial(self.initial()?);
+        ts.try_initial()?;
+        Ok(ts.into_moore())
+    }
+}
+
+/// Serializable representation of a [`RightCongruence`], where the color of each state is
+/// the name of its class.
+pub type CongruenceRepr = TsRepr<Vec<char>, ()>;
+
+impl CongruenceRepr {
+    /// Builds the representation of a right congruence.
+    pub fn from_congruence(cong: &RightCongruence<CharAlphabet>) -> Self {
+        let mut repr = TsRepr::from_ts_mapped(cong, |_| vec![], |_| ());
+        repr.states = cong
+            .state_indices()
+            .sorted()
+            .map(|q| cong.class_name(q).expect("State must exist").to_vec())
+            .collect();
+        repr
+    }
+
+    /// Converts the representation back into a right congruence. The class with index 0 is
+    /// assumed to be the class of the empty word.
+    pub fn into_congruence(&self) -> Result<RightCongruence<CharAlphabet>, ReprError> {
+        check_version(self.version)?;
+        let mut cong = RightCongruence::new(CharAlphabet::from_iter(self.alphabet.clone()));
+        for class in &self.states {
+            cong.add_state(class.clone());
+        }
+        for e in &self.edges {
+            cong.try_add_edge(e.source, e.symbol, e.target, Void)?;
+        }
+        Ok(cong)
+    }
+}
+
+/// Serializable representation of a [`FORC`], consisting of the leading congruence and one
+/// progress right congruence for each of its classes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForcRepr {
+    /// The version of the representation.
+    pub version: u32,
+    /// The leading congruence.
+    pub leading: CongruenceRepr,
+    /// Pairs of class indices of the leading congruence with their progress congruence.
+    pub progress: Vec<(usize, CongruenceRepr)>,
+}
+
+impl ForcRepr {
+    /// Builds the representation of a family of right congruences.
+    pub fn from_forc(forc: &FORC<CharAlphabet>) -> Self {
+        let leading = CongruenceRepr::from_congruence(forc.leading());
+        let progress = forc
+            .leading()
+            .state_indices()
+            .sorted()
+            .filter_map(|q| {
+                forc.prc(q)
+                    .map(|prc| (q, CongruenceRepr::from_congruence(prc)))
+            })
+            .collect();
+        Self {
+            version: FORMAT_VERSION,
+            leading,
+            progress,
+        }
+    }
+
+    /// Converts the representation back into a family of right congruences.
+    pub fn into_forc(&self) -> Result<FORC<CharAlphabet>, ReprError> {
+        check_version(self.version)?;
+        let leading = self.leading.into_congruence()?;
+        let progress: Vec<_> = self
+            .progress
+            .iter()
+            .map(|(q, prc)| prc.into_congruence().map(|prc| (*q, prc)))
+            .collect::<Result<_, _>>()?;
+        Ok(FORC::from_iter(leading, progress))
+    }
+}
+
+/// A class of a [`StableCongruenceRepr`], identified by its minimal representative.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassRepr {
+    /// The minimal representative of the class in the length-lexicographic order.
+    pub representative: String,
+    /// The representatives of the successor classes, in the order of the alphabet.
+    pub successors: Vec<(char, String)>,
+}
+
+/// Serializable representation of a [`RightCongruence`] that does not depend on the indices
+/// of its classes. Each class is stored by its minimal representative, which is computed
+/// from the transitions, and classes are sorted by these representatives. Hence two
+/// congruences that induce the same partition of the reachable words have equal
+/// representations, regardless of how their classes are numbered or labelled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StableCongruenceRepr {
+    /// The version of the representation.
+    pub version: u32,
+    /// The symbols of the alphabet.
+    pub alphabet: Vec<char>,
+    /// The classes that are reachable from the class of the empty word.
+    pub classes: Vec<ClassRepr>,
+}
+
+impl StableCongruenceRepr {
+    /// Builds the representation of a right congruence, unreachable classes are dropped.
+    pub fn from_congruence(cong: &RightCongruence<CharAlphabet>) -> Self {
+        let alphabet = CanonicalAlphabet::of(cong.alphabet());
+        let classes = cong.classes_llex();
+        let representative: Map<_, _> = classes
+            .iter()
+            .map(|(q, rep)| (*q, rep.iter().collect::<String>()))
+            .collect();
+        Self {
+            version: FORMAT_VERSION,
+            alphabet: alphabet.symbols().to_vec(),
+            classes: classes
+                .iter()
+                .map(|(q, _)| ClassRepr {
+                    representative: representative[q].clone(),
+                    successors: alphabet
+                        .symbols()
+                        .iter()
+                        .filter_map(|sym| {
+                            cong.successor_index(*q, *sym)
+                                .map(|p| (*sym, representative[&p].clone()))
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Converts the representation back into a right congruence, where the classes are
+    /// numbered in the stored order and labelled with their representatives. The class of
+    /// the empty word must be present.
+    pub fn into_congruence(&self) -> Result<RightCongruence<CharAlphabet>, ReprError> {
+        check_version(self.version)?;
+        if !self.classes.iter().any(|c| c.representative.is_empty()) {
+            return Err(ReprError::UnknownClass(String::new()));
+        }
+        let mut cong = RightCongruence::new(CharAlphabet::from_iter(self.alphabet.clone()));
+        // the class of the empty word has to be added first, so that it becomes the initial one
+        let classes = self
+            .classes
+            .iter()
+            .sorted_by_key(|c| !c.representative.is_empty())
+            .collect_vec();
+        let index: Map<_, _> = classes
+            .iter()
+            .map(|c| {
+                (
+                    c.representative.as_str(),
+                    cong.add_state((c.representative.chars().collect_vec(), Void)),
+                )
+            })
+            .collect();
+        for class in classes {
+            let mut seen = Set::default();
+            for (sym, target) in &class.successors {
+                if !seen.insert(*sym) {
+                    return Err(ReprError::Nondeterministic(
+                        index[class.representative.as_str()],
+                        *sym,
+                    ));
+                }
+                let Some(p) = index.get(target.as_str()) else {
+                    return Err(ReprError::UnknownClass(target.clone()));
+                };
+                cong.try_add_edge(index[class.representative.as_str()], *sym, *p, Void)?;
+            }
+        }
+        Ok(cong)
+    }
+}
+
+/// Serializable representation of a [`FORC`] that does not depend on class indices, see
+/// [`StableCongruenceRepr`]. Progress congruences are associated with the minimal
+/// representative of their leading class.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StableForcRepr {
+    /// The version of the representation.
+    pub version: u32,
+    /// The leading congruence.
+    pub leading: StableCongruenceRepr,
+    /// Pairs of representatives of leading classes with their progress congruence.
+    pub progress: Vec<(String, StableCongruenceRepr)>,
+}
+
+impl StableForcRepr {
+    /// Builds the representation of a family of right congruences, progress congruences of
+    /// unreachable leading classes are dropped.
+    pub fn from_forc(forc: &FORC<CharAlphabet>) -> Self {
+        let leading = forc.leading();
+        Self {
+            version: FORMAT_VERSION,
+            leading: StableCongruenceRepr::from_congruence(leading),
+            progress: leading
+                .classes_llex()
+                .into_iter()
+                .filter_map(|(q, rep)| {
+                    forc.prc(q).map(|prc| {
+                        (
+                            rep.into_iter().collect(),
+                            StableCongruenceRepr::from_congruence(prc),
+                        )
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Converts the representation back into a family of right congruences.
+    pub fn into_forc(&self) -> Result<FORC<CharAlphabet>, ReprError> {
+        check_version(self.version)?;
+        let leading = self.leading.into_congruence()?;
+        let class: Map<String, usize> = leading
+            .classes_llex()
+            .into_iter()
+            .map(|(q, rep)| (rep.into_iter().collect(), q))
+            .collect();
+        let progress: Vec<_> = self
+            .progress
+            .iter()
+            .map(|(rep, prc)| {
+                let q = class
+                    .get(rep)
+                    .ok_or_else(|| ReprError::UnknownClass(rep.clone()))?;
+                prc.into_congruence().map(|prc| (*q, prc))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(FORC::from_iter(leading, progress))
+    }
+}
+
+/// Serializable representation of a sample, where each word is stored together with its
+/// classification. Finite words are given by their symbols, ultimately periodic words by
+/// their spoke and cycle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleRepr<W, C> {
+    /// The version of the representation.
+    pub version: u32,
+    /// The symbols of the alphabet.
+    pub alphabet: Vec<char>,
+    /// The classified words of the sample.
+    pub words: Vec<(W, C)>,
+    /// The weights of all words whose weight is not one, may be omitted.
+    #[serde(default)]
+    pub weights: Vec<(W, usize)>,
+}
+
+/// Serializable representation of an ultimately periodic word.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeriodicRepr {
+    /// The finite prefix that is read before the cycle.
+    pub spoke: String,
+    /// The part that is repeated infinitely often.
+    pub cycle: String,
+}
+
+impl<C: Color + Serialize> Serialize for FiniteSample<CharAlphabet, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SampleRepr {
+            version: FORMAT_VERSION,
+            alphabet: CanonicalAlphabet::of(&self.alphabet).symbols().to_vec(),
+            words: self
+                .entries()
+                .map(|(w, c)| (w.iter().collect::<String>(), c.clone()))
+                .sorted_by(|(x, _), (y, _)| x.cmp(y))
+                .collect(),
+            weights: self
+                .weights
+                .iter()
+                .map(|(w, weight)| (w.iter().collect::<String>(), *weight))
+                .sorted()
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, C: Color + Deserialize<'de>> Deserialize<'de> for FiniteSample<CharAlphabet, C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SampleRepr::<String, C>::deserialize(deserializer)?;
+        check_version(repr.version).map_err(serde::de::Error::custom)?;
+        let mut sample = Sample::new_finite(
+            CharAlphabet::from_iter(repr.alphabet),
+            repr.words
+                .into_iter()
+                .map(|(w, c)| (w.chars().collect_vec(), c)),
+        );
+        sample.weights = repr
+            .weights
+            .into_iter()
+            .map(|(w, weight)| (w.chars().collect_vec(), weight))
+            .collect();
+        Ok(sample)
+    }
+}
+
+impl<C: Color + Serialize> Serialize for OmegaSample<CharAlphabet, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SampleRepr {
+            version: FORMAT_VERSION,
+            alphabet: CanonicalAlphabet::of(&self.alphabet).symbols().to_vec(),
+            words: self
+                .entries()
+                .map(|(w, c)| {
+                    let raw = w.raw_word();
+                    let (spoke, cycle) = raw.split_at(w.loop_index());
+                    (
+                        PeriodicRepr {
+                            spoke: spoke.iter().collect(),
+                            cycle: cycle.iter().collect(),
+                        },
+                        c.clone(),
+                    )
+                })
+                .sorted_by(|(x, _), (y, _)| (&x.spoke, &x.cycle).cmp(&(&y.spoke, &y.cycle)))
+                .collect(),
+            weights: self
+                .weights
+                .iter()
+                .map(|(w, weight)| {
+                    let raw = w.raw_word();
+                    let (spoke, cycle) = raw.split_at(w.loop_index());
+                    let repr = PeriodicRepr {
+                        spoke: spoke.iter().collect(),
+                        cycle: cycle.iter().collect(),
+                    };
+                    (repr, *weight)
+                })
+                .sorted_by(|(x, _), (y, _)| (&x.spoke, &x.cycle).cmp(&(&y.spoke, &y.cycle)))
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, C: Color + Deserialize<'de>> Deserialize<'de> for OmegaSample<CharAlphabet, C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SampleRepr::<PeriodicRepr, C>::deserialize(deserializer)?;
+        check_version(repr.version).map_err(serde::de::Error::custom)?;
+        let mut sample = Sample::new_omega(
+            CharAlphabet::from_iter(repr.alphabet),
+            repr.words
+                .into_iter()
+                .map(|(w, c)| (upw!(w.spoke.as_str(), w.cycle.as_str()), c)),
+        );
+        sample.weights = repr
+            .weights
+            .into_iter()
+            .map(|(w, weight)| (upw!(w.spoke.as_str(), w.cycle.as_str()), weight))
+            .collect();
+        Ok(sample)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use automata::{congruence::FORC, RightCongruence};
+
+    use super::{ReprError, StableCongruenceRepr, StableForcRepr, TsRepr, FORMAT_VERSION};
+    use crate::{checked::TransitionSystemError, passive::FiniteSample};
+
+    #[test]
+    fn dfa_repr_round_trip() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+
+        let repr = TsRepr::from_dfa(&dfa);
+        assert_eq!(repr.version, FORMAT_VERSION);
+        let json = serde_json::to_string(&repr).unwrap();
+        let parsed: TsRepr<bool, ()> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, repr);
+        let restored = parsed.into_dfa().unwrap();
+        for word in ["", "a", "ab", "aba", "bbaab"] {
+            assert_eq!(restored.accepts(word), dfa.accepts(word));
+        }
+
+        let mut future = parsed.clone();
+        future.version = FORMAT_VERSION + 1;
+        assert!(future.into_dfa().is_err());
+
+        let mut dangling = parsed.clone();
+        dangling.edges[0].target = 7;
+        assert_eq!(
+            dangling.into_dfa().unwrap_err(),
+            ReprError::Malformed(TransitionSystemError::MissingState(7))
+        );
+        let mut foreign = parsed.clone();
+        foreign.edges[0].symbol = 'c';
+        assert_eq!(
+            foreign.into_dfa().unwrap_err(),
+            ReprError::Malformed(TransitionSystemError::UnknownSymbol('c'))
+        );
+        let mut detached = parsed.clone();
+        detached.initial = Some(7);
+        assert_eq!(
+            detached.into_dfa().unwrap_err(),
+            ReprError::Malformed(TransitionSystemError::MissingState(7))
+        );
+    }
+
+    #[test]
+    fn sample_round_trip() {
+        let sample = FiniteSample::new_finite(
+            alphabet!(simple 'a', 'b'),
+            [("ab".chars(), true), ("ba".chars(), false)],
+        );
+        let json = serde_json::to_string(&sample).unwrap();
+        let restored: FiniteSample = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.classify(&vec!['a', 'b']), Some(true));
+        assert_eq!(restored.classify(&vec!['b', 'a']), Some(false));
+    }
+
+    #[test]
+    fn stable_congruence_repr() {
+        // the same congruence with classes added in different orders and with other labels
+        let build = |order: [usize; 3], labels: [&str; 3]| {
+            let mut cong = RightCongruence::new(alphabet!(simple 'a', 'b'));
+            let mut index = [0; 3];
+            for i in order {
+                index[i] = cong.add_state((labels[i].chars().collect::<Vec<_>>(), Void));
+            }
+            for (i, j) in [(0, 1), (1, 2), (2, 2)] {
+                cong.add_edge(index[i], 'a', index[j], Void);
+                cong.add_edge(index[i], 'b', index[i], Void);
+            }
+            cong
+        };
+        let first = StableCongruenceRepr::from_congruence(&build([0, 1, 2], ["", "a", "aa"]));
+        let second = StableCongruenceRepr::from_congruence(&build([0, 2, 1], ["", "x", "y"]));
+        assert_eq!(first, second);
+        assert_eq!(first.classes[2].representative, "aa");
+
+        let json = serde_json::to_string(&first).unwrap();
+        let parsed: StableCongruenceRepr = serde_json::from_str(&json).unwrap();
+        let restored = parsed.into_congruence().unwrap();
+        assert_eq!(restored.size(), 3);
+        assert_eq!(StableCongruenceRepr::from_congruence(&restored), first);
+
+        let forc = FORC::from_iter(
+            build([0, 1, 2], ["", "a", "aa"]),
+            [(0, build([0, 2, 1], ["", "a", "aa"]))],
+        );
+        let repr = StableForcRepr::from_forc(&forc);
+        assert_eq!(repr.progress[0].0, "");
+        let restored = repr.into_forc().unwrap();
+        assert_eq!(StableForcRepr::from_forc(&restored), repr);
+
+        let mut broken = first.clone();
+        broken.classes[1].successors[0].1 = "ab".to_string();
+        assert_eq!(
+            broken.into_congruence().unwrap_err(),
+            ReprError::UnknownClass("ab".to_string())
+        );
+    }
+}
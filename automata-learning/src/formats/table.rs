@@ -0,0 +1,113 @@
+//! Transition tables of deterministic transition systems, which have a row for each state and a
+//! column for each symbol in the order of the [`CanonicalAlphabet`]. The initial state is
+//! marked with an arrow, and state and edge colors are shown next to the state and the
+//! successor, unless they are empty as for [`Void`]. This works for every deterministic
+//! transition system over a [`CharAlphabet`], so DFAs, Moore and Mealy machines and right
+//! congruences are all printed in the same way.
+
+use automata::prelude::*;
+use itertools::Itertools;
+
+use super::CanonicalAlphabet;
+
+/// Displays the transition table of a transition system, see [`ToTable::table`].
+pub struct TransitionTable<'a, Ts>(&'a Ts);
+
+impl<'a, Ts> std::fmt::Display for TransitionTable<'a, Ts>
+where
+    Ts: Deterministic<Alphabet = CharAlphabet>,
+    Ts::StateIndex: Show,
+    Ts::StateColor: Show,
+    Ts::EdgeColor: Show,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ts = self.0;
+        let alphabet = CanonicalAlphabet::of(ts.alphabet());
+        let states = ts.state_indices().sorted().collect_vec();
+        let colors = states
+            .iter()
+            .map(|q| ts.state_color(*q).expect("State must exist").show())
+            .collect_vec();
+        let show_colors = colors.iter().any(|c| !c.is_empty());
+
+        let mut builder = tabled::builder::Builder::default();
+        let mut header = vec!["State".to_string()];
+        if show_colors {
+            header.push("Color".to_string());
+        }
+        header.extend(alphabet.symbols().iter().map(|sym| sym.to_string()));
+        builder.push_record(header);
+
+        for (q, color) in states.into_iter().zip(colors) {
+            let mut row = vec![if ts.maybe_initial_state() == Some(q) {
+                format!("→ q{}", q.show())
+            } else {
+                format!("q{}", q.show())
+            }];
+            if show_colors {
+                row.push(color);
+            }
+            for sym in alphabet.symbols() {
+                let cell = ts
+                    .edges_from(q)
+                    .expect("State must exist")
+                    .find(|e| e.expression() == sym)
+                    .map(|e| {
+                        let color = e.color().show();
+                        if color.is_empty() {
+                            format!("q{}", e.target().show())
+                        } else {
+                            format!("q{} | {color}", e.target().show())
+                        }
+                    })
+                    .unwrap_or_else(|| "-".to_string());
+                row.push(cell);
+            }
+            builder.push_record(row);
+        }
+
+        write!(f, "{}", builder.build())
+    }
+}
+
+/// Produces transition tables of deterministic transition systems.
+pub trait ToTable: Deterministic<Alphabet = CharAlphabet> + Sized {
+    /// Returns a value that displays the transition table of `self`.
+    fn table(&self) -> TransitionTable<'_, Self> {
+        TransitionTable(self)
+    }
+}
+
+impl<Ts: Deterministic<Alphabet = CharAlphabet>> ToTable for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::ToTable;
+
+    #[test]
+    fn transition_tables() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'b', 'a'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+        let table = dfa.table().to_string();
+        assert!(table.contains("Color"));
+        assert!(table.find("| a ") < table.find("| b "));
+        // q0 is initial and has no transition on b
+        let row = table.lines().find(|l| l.contains("→ q0")).unwrap();
+        assert!(row.contains("| - "));
+
+        let mealy = NTS::builder()
+            .with_transitions([(0, 'a', 1, 0), (0, 'b', 0, 0)])
+            .deterministic()
+            .with_initial(0)
+            .into_mealy();
+        let table = mealy.table().to_string();
+        assert!(!table.contains("Color"));
+        assert!(table.contains("q0 | 1"));
+    }
+}
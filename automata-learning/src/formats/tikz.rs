@@ -0,0 +1,309 @@
+//! Output of transition systems as a `tikzpicture` that uses the `automata` and `positioning`
+//! TikZ libraries, i.e. the document needs `\usetikzlibrary{automata, positioning}`. States
+//! are placed on a grid in breadth-first order starting from the initial state, which usually
+//! results in a readable picture for the small automata that appear in papers.
+
+use std::fmt::Write;
+
+use automata::prelude::*;
+use itertools::Itertools;
+
+use crate::ordered::SortedTransitionSystem;
+
+/// The side of a state on which the arrow that marks the initial state is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TikzSide {
+    /// The arrow comes in from the left, which is the default.
+    #[default]
+    Left,
+    /// The arrow comes in from above.
+    Above,
+    /// The arrow comes in from below.
+    Below,
+    /// The arrow comes in from the right.
+    Right,
+}
+
+impl std::fmt::Display for TikzSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                TikzSide::Left => "left",
+                TikzSide::Above => "above",
+                TikzSide::Below => "below",
+                TikzSide::Right => "right",
+            }
+        )
+    }
+}
+
+/// How accepting states are marked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TikzAccepting {
+    /// Accepting states are drawn with a double border, which is the default.
+    #[default]
+    Double,
+    /// Accepting states have an outgoing arrow.
+    Arrow,
+}
+
+/// Configures the output of [`ToTikz::to_tikz_with`], options are set using the builder
+/// methods, for example `TikzOptions::new().node_distance(3.0).columns(2)`.
+pub struct TikzOptions<'a, Ts: TransitionSystem> {
+    node_distance: f64,
+    columns: usize,
+    initial_side: TikzSide,
+    initial_text: String,
+    accepting_style: TikzAccepting,
+    show_colors: bool,
+    accepting: Option<Box<dyn Fn(&Ts::StateColor) -> bool + 'a>>,
+}
+
+impl<'a, Ts: TransitionSystem> Default for TikzOptions<'a, Ts> {
+    fn default() -> Self {
+        Self {
+            node_distance: 2.5,
+            columns: 4,
+            initial_side: TikzSide::default(),
+            initial_text: String::new(),
+            accepting_style: TikzAccepting::default(),
+            show_colors: true,
+            accepting: None,
+        }
+    }
+}
+
+impl<'a, Ts: TransitionSystem> TikzOptions<'a, Ts> {
+    /// Creates the default options, which place four states per row at a distance of 2.5cm
+    /// and show all state and edge colors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the distance between neighbouring states in centimeters.
+    pub fn node_distance(mut self, distance: f64) -> Self {
+        self.node_distance = distance;
+        self
+    }
+
+    /// Sets the number of states that are placed in one row, must be positive.
+    pub fn columns(mut self, columns: usize) -> Self {
+        assert!(columns > 0, "There must be at least one column");
+        self.columns = columns;
+        self
+    }
+
+    /// Sets the side from which the arrow marking the initial state comes in.
+    pub fn initial_side(mut self, side: TikzSide) -> Self {
+        self.initial_side = side;
+        self
+    }
+
+    /// Sets the text that is written next to the arrow marking the initial state.
+    pub fn initial_text<S: ToString>(mut self, text: S) -> Self {
+        self.initial_text = text.to_string();
+        self
+    }
+
+    /// Sets how accepting states are marked.
+    pub fn accepting_style(mut self, style: TikzAccepting) -> Self {
+        self.accepting_style = style;
+        self
+    }
+
+    /// Determines whether state and edge colors are added to the labels.
+    pub fn show_colors(mut self, show: bool) -> Self {
+        self.show_colors = show;
+        self
+    }
+
+    /// Marks states whose color satisfies the predicate as accepting.
+    pub fn accepting<F: Fn(&Ts::StateColor) -> bool + 'a>(mut self, predicate: F) -> Self {
+        self.accepting = Some(Box::new(predicate));
+        self
+    }
+}
+
+/// Escapes characters that have a special meaning in LaTeX math mode.
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\backslash "),
+            '_' | '#' | '{' | '}' | '&' | '%' | '$' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\sim "),
+            '^' => out.push_str("\\hat{}"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Produces TikZ code for transition systems, see [`TikzOptions`] for the available settings.
+pub trait ToTikz: TransitionSystem + Sized {
+    /// Produces a `tikzpicture` of `self` that is configured by `options`.
+    fn to_tikz_with(&self, options: &TikzOptions<'_, Self>) -> String;
+
+    /// Produces a `tikzpicture` with the default options.
+    fn to_tikz(&self) -> String {
+        self.to_tikz_with(&TikzOptions::new())
+    }
+}
+
+impl<Ts> ToTikz for Ts
+where
+    Ts: TransitionSystem,
+    Ts::StateIndex: Show,
+    Ts::StateColor: Show,
+    Ts::EdgeColor: Show,
+    <Ts::Alphabet as Alphabet>::Expression: Show + Ord,
+{
+    fn to_tikz_with(&self, options: &TikzOptions<'_, Self>) -> String {
+        let name = |q: Self::StateIndex| format!("q{}", q.show());
+
+        // breadth-first order from the initial state, unreachable states come last
+        let mut order = self
+            .maybe_initial_state()
+            .map(|initial| self.reachable_state_indices_from(initial).collect_vec())
+            .unwrap_or_default();
+        for q in self.sorted_state_indices() {
+            if !order.contains(&q) {
+                order.push(q);
+            }
+        }
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "\\begin{{tikzpicture}}[shorten >=1pt, node distance={}cm, on grid, auto, >=stealth, \
+             initial text={{{}}}, initial where={}, accepting/.style={}]",
+            options.node_distance,
+            options.initial_text,
+            options.initial_side,
+            match options.accepting_style {
+                TikzAccepting::Double => "accepting by double",
+                TikzAccepting::Arrow => "accepting by arrow",
+            }
+        )
+        .unwrap();
+
+        for (i, q) in order.iter().enumerate() {
+            let color = self.state_color(*q).expect("State must exist");
+            let mut styles = vec!["state"];
+            if self.maybe_initial_state() == Some(*q) {
+                styles.push("initial");
+            }
+            if let Some(accepting) = &options.accepting {
+                if accepting(&color) {
+                    styles.push("accepting");
+                }
+            }
+            let position = if i == 0 {
+                String::new()
+            } else if i % options.columns == 0 {
+                format!(" [below=of {}]", name(order[i - options.columns]))
+            } else {
+                format!(" [right=of {}]", name(order[i - 1]))
+            };
+            let mut label = format!("q_{{{}}}", escape(&q.show()));
+            if options.show_colors && !color.show().is_empty() {
+                label = format!("{label} \\mid {}", escape(&color.show()));
+            }
+            writeln!(
+                out,
+                "  \\node[{}] ({}){position} {{${label}$}};",
+                styles.join(", "),
+                name(*q)
+            )
+            .unwrap();
+        }
+
+        // parallel edges are merged into one edge with a combined label
+        let mut edges: Vec<(Self::StateIndex, Self::StateIndex, Vec<String>)> = vec![];
+        for q in &order {
+            for (expression, color, target) in self.sorted_edges_from(*q) {
+                let mut label = escape(&expression.show());
+                if options.show_colors && !color.show().is_empty() {
+                    label = format!("{label} \\mid {}", escape(&color.show()));
+                }
+                match edges.iter_mut().find(|(s, t, _)| *s == *q && *t == target) {
+                    Some((_, _, labels)) => labels.push(label),
+                    None => edges.push((*q, target, vec![label])),
+                }
+            }
+        }
+
+        writeln!(out, "  \\path[->]").unwrap();
+        for (source, target, labels) in &edges {
+            let bend = if source == target {
+                "[loop above] "
+            } else if edges.iter().any(|(s, t, _)| s == target && t == source) {
+                "[bend left] "
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                "    ({}) edge {bend}node {{${}$}} ({})",
+                name(*source),
+                labels.join(", "),
+                name(*target)
+            )
+            .unwrap();
+        }
+        writeln!(out, "  ;").unwrap();
+        writeln!(out, "\\end{{tikzpicture}}").unwrap();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{escape, TikzAccepting, TikzOptions, TikzSide, ToTikz};
+
+    #[test]
+    fn tikz_output() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(false);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        dfa.add_edge(q1, 'b', q2, Void);
+        dfa.add_edge(q2, 'a', q2, Void);
+        dfa.add_edge(q2, 'b', q2, Void);
+
+        let options = TikzOptions::new()
+            .node_distance(3.0)
+            .columns(2)
+            .initial_side(TikzSide::Above)
+            .accepting_style(TikzAccepting::Arrow)
+            .show_colors(false)
+            .accepting(|c: &bool| *c);
+        let tikz = dfa.to_tikz_with(&options);
+        assert!(tikz.starts_with("\\begin{tikzpicture}"));
+        assert!(tikz.contains("node distance=3cm"));
+        assert!(tikz.contains("initial where=above"));
+        assert!(tikz.contains("\\node[state, initial, accepting] (q0) {$q_{0}$};"));
+        assert!(tikz.contains("(q1) [right=of q0]"));
+        assert!(tikz.contains("(q2) [below=of q0]"));
+        assert!(tikz.contains("(q0) edge [loop above] node {$b$} (q0)"));
+        assert!(tikz.contains("(q0) edge [bend left] node {$a$} (q1)"));
+        assert!(tikz.contains("(q2) edge [loop above] node {$a, b$} (q2)"));
+        assert!(tikz.trim_end().ends_with("\\end{tikzpicture}"));
+    }
+
+    #[test]
+    fn tikz_escaping() {
+        assert_eq!(escape("a_1#"), "a\\_1\\#");
+        assert_eq!(escape("{x}"), "\\{x\\}");
+    }
+}
@@ -0,0 +1,196 @@
+//! Two-player games on transition systems. A [`GameArena`] is a transition system whose states
+//! are colored with the [`Player`] that owns them, i.e. that picks the outgoing edge when a play
+//! is in the state. A player that can not move loses the play.
+//!
+//! Attractors as well as reachability and safety games are solved in time linear in the size
+//! of the arena. Strategies are positional and map each state of the respective player in the
+//! winning region to the symbol of the edge that should be taken.
+
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map, Set};
+
+/// One of the two players of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Player {
+    /// The player that usually has the existential role, such as the controller in synthesis.
+    #[default]
+    Even,
+    /// The adversary of [`Player::Even`].
+    Odd,
+}
+
+impl Player {
+    /// Returns the other player.
+    pub fn opponent(&self) -> Player {
+        match self {
+            Player::Even => Player::Odd,
+            Player::Odd => Player::Even,
+        }
+    }
+}
+
+/// The winning region of a player together with a positional strategy that wins from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameSolution<Idx> {
+    /// The states from which the player wins.
+    pub winning: Set<Idx>,
+    /// The symbol of the edge that the player takes in each of its winning states. Winning
+    /// states in which every choice is winning, such as states in the target of a reachability
+    /// game, may be missing.
+    pub strategy: Map<Idx, char>,
+}
+
+impl<Idx: IndexType> GameSolution<Idx> {
+    /// Returns true if the player wins from `state`.
+    pub fn wins_from(&self, state: Idx) -> bool {
+        self.winning.contains(&state)
+    }
+}
+
+/// A transition system with [`Player`]s as state colors, see the module documentation.
+pub trait GameArena: TransitionSystem<Alphabet = CharAlphabet, StateColor = Player> {
+    /// Returns the player that owns `state`. Panics if the state does not exist.
+    fn owner(&self, state: Self::StateIndex) -> Player {
+        self.state_color(state).expect("State must exist")
+    }
+
+    /// Computes the attractor of `target` for `player`, i.e. the states from which the player
+    /// can force the play into `target`, together with a strategy for doing so. In states of
+    /// the player, the strategy takes an edge that decreases the distance to `target`.
+    fn attractor(
+        &self,
+        player: Player,
+        target: &Set<Self::StateIndex>,
+    ) -> GameSolution<Self::StateIndex> {
+        // for states of the opponent, we count the edges that do not lead into the attractor
+        // yet, once this reaches zero the opponent can not avoid the attractor
+        let mut predecessors: Map<Self::StateIndex, Vec<(Self::StateIndex, char)>> = Map::default();
+        let mut escapes: Map<Self::StateIndex, usize> = Map::default();
+        for q in self.state_indices() {
+            let mut count = 0;
+            for e in self.edges_from(q).expect("State must exist") {
+                predecessors
+                    .entry(e.target())
+                    .or_default()
+                    .push((q, *e.expression()));
+                count += 1;
+            }
+            escapes.insert(q, count);
+        }
+
+        let mut winning = target.clone();
+        let mut strategy = Map::default();
+        let mut queue: VecDeque<_> = target.iter().cloned().collect();
+        // states of the opponent without edges are attracted as the opponent can not move
+        for (q, count) in &escapes {
+            if *count == 0 && self.owner(*q) != player && winning.insert(*q) {
+                queue.push_back(*q);
+            }
+        }
+        while let Some(q) = queue.pop_front() {
+            for (p, sym) in predecessors.get(&q).into_iter().flatten() {
+                if winning.contains(p) {
+                    continue;
+                }
+                let attracted = if self.owner(*p) == player {
+                    strategy.insert(*p, *sym);
+                    true
+                } else {
+                    let count = escapes.get_mut(p).expect("Every state is counted");
+                    *count -= 1;
+                    *count == 0
+                };
+                if attracted {
+                    winning.insert(*p);
+                    queue.push_back(*p);
+                }
+            }
+        }
+        GameSolution { winning, strategy }
+    }
+
+    /// Solves the reachability game in which `player` wins if the play visits `target`, which
+    /// amounts to computing the attractor.
+    fn solve_reachability(
+        &self,
+        player: Player,
+        target: &Set<Self::StateIndex>,
+    ) -> GameSolution<Self::StateIndex> {
+        self.attractor(player, target)
+    }
+
+    /// Solves the safety game in which `player` wins if the play stays in `safe` forever. The
+    /// winning region is the complement of the opponent's attractor of the unsafe states, and
+    /// the strategy takes an edge that stays in the winning region.
+    fn solve_safety(
+        &self,
+        player: Player,
+        safe: &Set<Self::StateIndex>,
+    ) -> GameSolution<Self::StateIndex> {
+        let unsafe_states = self.state_indices().filter(|q| !safe.contains(q)).collect();
+        let losing = self.attractor(player.opponent(), &unsafe_states).winning;
+        let winning: Set<_> = self
+            .state_indices()
+            .filter(|q| !losing.contains(q))
+            .collect();
+        let strategy = winning
+            .iter()
+            .filter(|q| self.owner(**q) == player)
+            .filter_map(|q| {
+                self.edges_from(*q)
+                    .expect("State must exist")
+                    .find(|e| winning.contains(&e.target()))
+                    .map(|e| (*q, *e.expression()))
+            })
+            .collect();
+        GameSolution { winning, strategy }
+    }
+}
+
+impl<Ts: TransitionSystem<Alphabet = CharAlphabet, StateColor = Player>> GameArena for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, Set};
+
+    use super::{GameArena, Player};
+
+    #[test]
+    fn reachability_and_safety() {
+        // Even owns q0 and q2, Odd owns q1 and q3. From q1, Odd can escape to the sink q3 which
+        // only loops, q2 is the target.
+        let mut arena = DTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = arena.add_state(Player::Even);
+        let q1 = arena.add_state(Player::Odd);
+        let q2 = arena.add_state(Player::Even);
+        let q3 = arena.add_state(Player::Odd);
+        for (source, sym, target) in [
+            (q0, 'a', q1),
+            (q0, 'b', q2),
+            (q1, 'a', q2),
+            (q1, 'b', q3),
+            (q2, 'a', q2),
+            (q3, 'a', q3),
+        ] {
+            arena.add_edge(source, sym, target, Void);
+        }
+
+        let target: Set<_> = [q2].into_iter().collect();
+        let reach = arena.solve_reachability(Player::Even, &target);
+        assert_eq!(reach.winning, Set::from_iter([q0, q2]));
+        assert_eq!(reach.strategy.get(&q0), Some(&'b'));
+        assert!(!reach.wins_from(q1));
+
+        let odd = arena.attractor(Player::Odd, &[q3].into_iter().collect());
+        assert_eq!(odd.winning, Set::from_iter([q1, q3]));
+        assert_eq!(odd.strategy.get(&q1), Some(&'b'));
+
+        // staying out of q3 is possible from q0 and q2
+        let safe = [q0, q1, q2].into_iter().collect();
+        let safety = arena.solve_safety(Player::Even, &safe);
+        assert_eq!(safety.winning, Set::from_iter([q0, q2]));
+        assert_eq!(safety.strategy.get(&q0), Some(&'b'));
+        assert_eq!(safety.strategy.get(&q2), Some(&'a'));
+    }
+}
@@ -0,0 +1,210 @@
+//! Kripke structures and model checking of their infinite paths against Büchi automata. The
+//! states of a [`KripkeStructure`] are labelled with the atomic propositions that hold in them.
+//! Automata read valuations, i.e. sets of propositions, which are encoded as the symbols of a
+//! [`PropositionalAlphabet`], so that they can be used with the usual [`CharAlphabet`] based
+//! transition systems.
+
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+
+use crate::analysis::emptiness::accepting_lasso;
+pub use crate::propositional::{PropositionalAlphabet, Valuation};
+
+/// A finite graph whose states are labelled with sets of atomic propositions. It has no input
+/// alphabet, a path `s_0 s_1 ...` from the initial state produces the word of valuations
+/// `L(s_0) L(s_1) ...`. States without successors have no infinite paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KripkeStructure {
+    alphabet: PropositionalAlphabet,
+    labels: Vec<char>,
+    successors: Vec<Vec<usize>>,
+    initial: usize,
+}
+
+impl KripkeStructure {
+    /// Creates a structure without states over the given alphabet. The first state that is
+    /// added is initial, unless [`Self::set_initial`] is used.
+    pub fn new(alphabet: PropositionalAlphabet) -> Self {
+        Self {
+            alphabet,
+            labels: vec![],
+            successors: vec![],
+            initial: 0,
+        }
+    }
+
+    /// Adds a state in which exactly the given propositions hold and returns its index.
+    pub fn add_state<I: IntoIterator<Item = S>, S: Into<String>>(&mut self, label: I) -> usize {
+        let valuation = label.into_iter().map(Into::into).collect();
+        self.labels.push(self.alphabet.symbol(&valuation));
+        self.successors.push(vec![]);
+        self.labels.len() - 1
+    }
+
+    /// Adds a transition from `source` to `target`.
+    pub fn add_edge(&mut self, source: usize, target: usize) {
+        assert!(target < self.size(), "State {target} does not exist");
+        if !self.successors[source].contains(&target) {
+            self.successors[source].push(target);
+        }
+    }
+
+    /// Makes `state` the initial state.
+    pub fn set_initial(&mut self, state: usize) {
+        assert!(state < self.size(), "State {state} does not exist");
+        self.initial = state;
+    }
+
+    /// Returns the initial state.
+    pub fn initial(&self) -> usize {
+        self.initial
+    }
+
+    /// Returns the number of states.
+    pub fn size(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns the alphabet of valuations.
+    pub fn alphabet(&self) -> &PropositionalAlphabet {
+        &self.alphabet
+    }
+
+    /// Returns the propositions that hold in `state`.
+    pub fn label(&self, state: usize) -> Valuation {
+        self.alphabet.valuation(self.labels[state])
+    }
+
+    /// Returns the successors of `state`.
+    pub fn successors(&self, state: usize) -> &[usize] {
+        &self.successors[state]
+    }
+
+    /// Builds the product with a Büchi automaton `nba` over the alphabet of valuations, whose
+    /// acceptance is given by its edges as for [`crate::analysis::BuchiEmptiness`]. The
+    /// infinite runs of the product correspond to pairs of a path of the structure and a run
+    /// of `nba` on the word of the path, and are accepting if the run of `nba` is. Only the
+    /// part that is reachable from the pair of initial states is built.
+    pub fn product<N>(&self, nba: &N) -> KripkeProduct<N::StateIndex>
+    where
+        N: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool> + Pointed,
+    {
+        let mut ts = NTS::new_for_alphabet(self.alphabet.alphabet());
+        let mut states = vec![];
+        let mut index: Map<(usize, N::StateIndex), usize> = Map::default();
+        let pair = (self.initial, nba.initial());
+        let initial = ts.add_state(Void);
+        index.insert(pair, initial);
+        states.push(pair);
+        let mut queue = VecDeque::from([pair]);
+        while let Some((s, q)) = queue.pop_front() {
+            let source = index[&(s, q)];
+            let sym = self.labels[s];
+            for e in nba.edges_from(q).expect("State must exist") {
+                if *e.expression() != sym {
+                    continue;
+                }
+                for t in &self.successors[s] {
+                    let pair = (*t, e.target());
+                    let target = *index.entry(pair).or_insert_with(|| {
+                        states.push(pair);
+                        queue.push_back(pair);
+                        ts.add_state(Void)
+                    });
+                    ts.add_edge(source, sym, target, e.color());
+                }
+            }
+        }
+        KripkeProduct {
+            ts,
+            initial,
+            states,
+        }
+    }
+}
+
+/// The product of a [`KripkeStructure`] and a Büchi automaton, see
+/// [`KripkeStructure::product`].
+#[derive(Debug, Clone)]
+pub struct KripkeProduct<Idx> {
+    /// The transition system of the product, where every edge is labelled with the valuation
+    /// of the state of the structure it leaves.
+    pub ts: NTS<CharAlphabet, Void, bool>,
+    /// The initial state of the product.
+    pub initial: usize,
+    /// The pair of a state of the structure and a state of the automaton for each state of the
+    /// product.
+    pub states: Vec<(usize, Idx)>,
+}
+
+/// Checks whether some infinite path of `ks` from its initial state produces a word that is
+/// accepted by `nba`. As usual, `nba` recognizes the violations of a property, e.g. it is
+/// obtained from the negation of an LTL formula, so the structure satisfies the property if
+/// and only if the check succeeds. Otherwise the returned lasso `(u, v)` of states of the
+/// structure gives a violating path `u v^ω`, where `v` is not empty.
+pub fn model_check<N>(ks: &KripkeStructure, nba: &N) -> Result<(), (Vec<usize>, Vec<usize>)>
+where
+    N: TransitionSystem<Alphabet = CharAlphabet, EdgeColor = bool> + Pointed,
+{
+    let product = ks.product(nba);
+    let Some((prefix, cycle)) = accepting_lasso(&product.ts.with_initial(product.initial)) else {
+        return Ok(());
+    };
+    let path = |run: Vec<(usize, char)>| {
+        run.into_iter()
+            .map(|(q, _)| product.states[q].0)
+            .collect_vec()
+    };
+    Err((path(prefix), path(cycle)))
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{model_check, KripkeStructure, PropositionalAlphabet, Valuation};
+
+    #[test]
+    fn kripke_model_check() {
+        let alphabet = PropositionalAlphabet::new(["request", "grant"]);
+        let mut ks = KripkeStructure::new(alphabet.clone());
+        let idle = ks.add_state(Vec::<String>::new());
+        let waiting = ks.add_state(["request"]);
+        let granted = ks.add_state(["grant"]);
+        ks.add_edge(idle, idle);
+        ks.add_edge(idle, waiting);
+        ks.add_edge(waiting, granted);
+        ks.add_edge(granted, idle);
+        assert_eq!(ks.label(waiting), Valuation::from(["request".to_string()]));
+
+        // violations of "always eventually grant", i.e. eventually never grant
+        let mut never_grant = NTS::new_for_alphabet(alphabet.alphabet());
+        let q0 = never_grant.add_state(Void);
+        let q1 = never_grant.add_state(Void);
+        for sym in alphabet.symbols() {
+            never_grant.add_edge(q0, sym, q0, false);
+        }
+        for sym in alphabet.symbols_where(|v| !v.contains("grant")) {
+            never_grant.add_edge(q0, sym, q1, false);
+            never_grant.add_edge(q1, sym, q1, true);
+        }
+        let (prefix, cycle) = model_check(&ks, &never_grant.with_initial(q0))
+            .expect_err("Staying idle forever violates the property");
+        assert!(prefix.iter().chain(&cycle).all(|s| *s != granted));
+        assert_eq!(cycle, vec![idle]);
+
+        // violations of "never grant without a preceding request", i.e. a grant right away
+        let mut grant_first = NTS::new_for_alphabet(alphabet.alphabet());
+        let p0 = grant_first.add_state(Void);
+        let p1 = grant_first.add_state(Void);
+        for sym in alphabet.symbols_where(|v| v.contains("grant")) {
+            grant_first.add_edge(p0, sym, p1, false);
+        }
+        for sym in alphabet.symbols() {
+            grant_first.add_edge(p1, sym, p1, true);
+        }
+        assert_eq!(model_check(&ks, &grant_first.with_initial(p0)), Ok(()));
+    }
+}
@@ -20,5 +20,62 @@ pub mod active;
 
 pub(crate) mod prefixtree;
 
+/// Constructions and analyses of right congruences and families of right congruences.
+pub mod congruence;
+
+/// Contains analyses and reductions of transition systems, such as simulation relations.
+pub mod analysis;
+
+/// Reading and writing transition systems in formats used by other tools, such as DOT.
+pub mod formats;
+
+/// Generation of random automata and words, as well as strategies for property testing.
+pub mod random;
+
+/// Loading of benchmark corpora from local directories.
+pub mod benchmarks;
+
+/// Structured progress events and statistics of learners.
+pub mod observer;
+
+/// Limits on the time, states and queries that learners may use, and cooperative cancellation.
+pub mod budget;
+
+/// Attractors and solvers for reachability and safety games on transition systems.
+pub mod games;
+
+/// Alphabets of valuations of atomic propositions with Boolean formulas as edge labels.
+pub mod propositional;
+
+/// Products and sums of alphabets for combining automata over different alphabets.
+pub mod alphabets;
+
+/// Symbolic automata whose edges carry predicates of an effective Boolean algebra.
+pub mod symbolic;
+
+/// Words over text whose symbols are characters or grapheme clusters.
+pub mod text;
+
+/// Fallible variants of accessors and constructors of transition systems.
+pub mod checked;
+
+/// Declarative construction of automata with named states.
+pub mod builder;
+
+/// Names and annotations of states that are kept separately from their colors.
+pub mod metadata;
+
+/// Lookup of states by index, by a word that reaches them, by class or by name.
+pub mod lookup;
+
+/// A stable order of states and edges that exporters and generators iterate in.
+pub mod ordered;
+
+/// Transition systems as sequences of transitions that can be collected and rebuilt.
+pub mod transitions;
+
+/// Kripke structures and model checking against Büchi automata.
+pub mod kripke;
+
 #[cfg(test)]
 mod tests {}
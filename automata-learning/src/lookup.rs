@@ -0,0 +1,131 @@
+//! References to states in the ways one talks about them informally: by index, by a word that
+//! leads to the state from the initial state, by a class of a right congruence or by a name
+//! given in a [`StateMetadata`]. Any of these can be passed to the methods of [`StateLookup`],
+//! which saves resolving them to state indices by hand first.
+
+use automata::{prelude::*, Class, RightCongruence};
+use itertools::Itertools;
+
+use crate::{analysis::Separation, metadata::StateMetadata};
+
+/// Something that refers to a state of `Ts`, see the module documentation.
+pub trait StateRef<Ts: TransitionSystem> {
+    /// Returns the index of the state that is referred to, or `None` if there is no such state.
+    fn resolve(&self, ts: &Ts) -> Option<Ts::StateIndex>;
+}
+
+impl<Ts: TransitionSystem<StateIndex = usize>> StateRef<Ts> for usize {
+    fn resolve(&self, ts: &Ts) -> Option<usize> {
+        ts.state_color(*self).map(|_| *self)
+    }
+}
+
+/// A word refers to the state that is reached by reading it from the initial state.
+impl<Ts: Deterministic<Alphabet = CharAlphabet> + Pointed> StateRef<Ts> for [char] {
+    fn resolve(&self, ts: &Ts) -> Option<Ts::StateIndex> {
+        ts.reached_state_index(&self.to_vec())
+    }
+}
+
+impl<Ts: Deterministic<Alphabet = CharAlphabet> + Pointed> StateRef<Ts> for Vec<char> {
+    fn resolve(&self, ts: &Ts) -> Option<Ts::StateIndex> {
+        self.as_slice().resolve(ts)
+    }
+}
+
+/// A string refers to the state that is reached by reading its characters.
+impl<Ts: Deterministic<Alphabet = CharAlphabet> + Pointed> StateRef<Ts> for str {
+    fn resolve(&self, ts: &Ts) -> Option<Ts::StateIndex> {
+        self.chars().collect_vec().resolve(ts)
+    }
+}
+
+impl StateRef<RightCongruence<CharAlphabet>> for Class<char> {
+    fn resolve(&self, cong: &RightCongruence<CharAlphabet>) -> Option<usize> {
+        cong.class_to_index(self)
+    }
+}
+
+/// Refers to the state with a given name, see [`StateMetadata::named`].
+#[derive(Debug, Clone, Copy)]
+pub struct Named<'a, Idx: IndexType> {
+    metadata: &'a StateMetadata<Idx>,
+    name: &'a str,
+}
+
+impl<Idx: IndexType> StateMetadata<Idx> {
+    /// Returns a reference to the state called `name`, which can be passed to
+    /// [`StateLookup`].
+    pub fn named<'a>(&'a self, name: &'a str) -> Named<'a, Idx> {
+        Named {
+            metadata: self,
+            name,
+        }
+    }
+}
+
+impl<'a, Ts: TransitionSystem> StateRef<Ts> for Named<'a, Ts::StateIndex> {
+    fn resolve(&self, ts: &Ts) -> Option<Ts::StateIndex> {
+        let state = self.metadata.state(self.name)?;
+        ts.state_color(state).map(|_| state)
+    }
+}
+
+/// Access to states through any [`StateRef`].
+pub trait StateLookup: TransitionSystem + Sized {
+    /// Returns the index of the state that `state` refers to.
+    fn find_state<R: StateRef<Self> + ?Sized>(&self, state: &R) -> Option<Self::StateIndex> {
+        state.resolve(self)
+    }
+
+    /// Returns the color of the state that `state` refers to.
+    fn color_of<R: StateRef<Self> + ?Sized>(&self, state: &R) -> Option<Self::StateColor> {
+        self.state_color(self.find_state(state)?)
+    }
+
+    /// Returns a separating word of the states that `p` and `q` refer to, as given by
+    /// [`Separation::separating_word`]. Returns `None` if one of them does not exist or if
+    /// they are equivalent.
+    fn separate<P, Q>(&self, p: &P, q: &Q) -> Option<Vec<char>>
+    where
+        Self: Separation,
+        P: StateRef<Self> + ?Sized,
+        Q: StateRef<Self> + ?Sized,
+    {
+        self.separating_word(self.find_state(p)?, self.find_state(q)?)
+    }
+}
+
+impl<Ts: TransitionSystem> StateLookup for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::StateLookup;
+    use crate::metadata::StateMetadata;
+
+    #[test]
+    fn state_lookup() {
+        // counts a's modulo three, where only state 0 is accepting
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(false);
+        for (p, q) in [(q0, q1), (q1, q2), (q2, q0)] {
+            dfa.add_edge(p, 'a', q, Void);
+            dfa.add_edge(p, 'b', p, Void);
+        }
+        assert_eq!(dfa.find_state(&q2), Some(q2));
+        assert_eq!(dfa.find_state(&7usize), None);
+        assert_eq!(dfa.find_state("aba"), Some(q2));
+        assert_eq!(dfa.find_state(&['a', 'a', 'a'][..]), Some(q0));
+        assert_eq!(dfa.color_of("aaa"), Some(true));
+        assert_eq!(dfa.separate("ab", "ba"), None);
+        assert_eq!(dfa.separate("a", "aa"), Some(vec!['a']));
+
+        let metadata = StateMetadata::from_names([(q0, "zero"), (q1, "one")]);
+        assert_eq!(dfa.find_state(&metadata.named("one")), Some(q1));
+        assert_eq!(dfa.find_state(&metadata.named("two")), None);
+    }
+}
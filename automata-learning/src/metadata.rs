@@ -0,0 +1,201 @@
+//! Names and annotations of states that are kept next to a transition system instead of in its
+//! colors. Colors determine the semantics of an automaton, e.g. acceptance or outputs, so
+//! misusing them as display names changes the automaton. A [`StateMetadata`] instead maps
+//! state indices to a name and to arbitrary key/value annotations, and it can be carried along
+//! when the states of the transition system are renumbered, paired up in a product or merged
+//! in a quotient. Exporters such as [`ToDot`](crate::formats::ToDot) use the names
+//! as labels when the metadata is passed to them.
+
+use std::collections::BTreeMap;
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+
+/// Names and annotations of the states of a transition system, see the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateMetadata<Idx: IndexType> {
+    names: Map<Idx, String>,
+    annotations: Map<Idx, BTreeMap<String, String>>,
+}
+
+impl<Idx: IndexType> Default for StateMetadata<Idx> {
+    fn default() -> Self {
+        Self {
+            names: Map::default(),
+            annotations: Map::default(),
+        }
+    }
+}
+
+impl<Idx: IndexType> StateMetadata<Idx> {
+    /// Creates metadata without names and annotations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates metadata that gives the states the names yielded by `names`.
+    pub fn from_names<I: IntoIterator<Item = (Idx, S)>, S: Into<String>>(names: I) -> Self {
+        let mut metadata = Self::new();
+        for (q, name) in names {
+            metadata.set_name(q, name);
+        }
+        metadata
+    }
+
+    /// Sets the name of `state`, replacing a previous one.
+    pub fn set_name<S: Into<String>>(&mut self, state: Idx, name: S) {
+        self.names.insert(state, name.into());
+    }
+
+    /// Returns the name of `state`, if it has one.
+    pub fn name(&self, state: Idx) -> Option<&str> {
+        self.names.get(&state).map(|name| name.as_str())
+    }
+
+    /// Returns a state called `name`, if there is one.
+    pub fn state(&self, name: &str) -> Option<Idx> {
+        self.names
+            .iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(q, _)| *q)
+    }
+
+    /// Returns the name of `state`, or `q` followed by its index if it has none.
+    pub fn display_name(&self, state: Idx) -> String
+    where
+        Idx: Show,
+    {
+        self.name(state)
+            .map(String::from)
+            .unwrap_or_else(|| format!("q{}", state.show()))
+    }
+
+    /// Annotates `state` with `value` for `key`, replacing a previous value.
+    pub fn annotate<K: Into<String>, V: Into<String>>(&mut self, state: Idx, key: K, value: V) {
+        self.annotations
+            .entry(state)
+            .or_default()
+            .insert(key.into(), value.into());
+    }
+
+    /// Returns the value of the annotation of `state` for `key`.
+    pub fn annotation(&self, state: Idx, key: &str) -> Option<&str> {
+        self.annotations
+            .get(&state)
+            .and_then(|annotations| annotations.get(key))
+            .map(|value| value.as_str())
+    }
+
+    /// Returns the annotations of `state` ordered by key.
+    pub fn annotations(&self, state: Idx) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.annotations
+            .get(&state)
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Carries the metadata over to a copy of the transition system, where `index` maps each
+    /// state to its index in the copy, as returned for example by
+    /// [`RelabelAlphabet::relabel_alphabet`](crate::alphabets::RelabelAlphabet). States
+    /// without an index are dropped.
+    pub fn remap<J: IndexType>(&self, index: &Map<Idx, J>) -> StateMetadata<J> {
+        StateMetadata {
+            names: self
+                .names
+                .iter()
+                .filter_map(|(q, name)| Some((*index.get(q)?, name.clone())))
+                .collect(),
+            annotations: self
+                .annotations
+                .iter()
+                .filter_map(|(q, annotations)| Some((*index.get(q)?, annotations.clone())))
+                .collect(),
+        }
+    }
+
+    /// Builds the metadata of a product whose `i`-th state is the pair `states[i]`, as in
+    /// [`AlphabetProduct`](crate::alphabets::AlphabetProduct). A pair is named by
+    /// concatenating the names of its components, and it has the annotations of both, where
+    /// those of `right` take precedence.
+    pub fn product<R: IndexType>(
+        left: &Self,
+        right: &StateMetadata<R>,
+        states: &[(Idx, R)],
+    ) -> StateMetadata<usize>
+    where
+        Idx: Show,
+        R: Show,
+    {
+        let mut metadata = StateMetadata::new();
+        for (i, (p, q)) in states.iter().enumerate() {
+            if left.name(*p).is_some() || right.name(*q).is_some() {
+                let name = format!("({}, {})", left.display_name(*p), right.display_name(*q));
+                metadata.set_name(i, name);
+            }
+            for (key, value) in left.annotations(*p).chain(right.annotations(*q)) {
+                metadata.annotate(i, key, value);
+            }
+        }
+        metadata
+    }
+
+    /// Builds the metadata of a quotient whose `i`-th state is the class `classes[i]`. A class
+    /// is named by listing the names of its members, and it keeps the annotations on which all
+    /// members agree.
+    pub fn quotient(&self, classes: &[Vec<Idx>]) -> StateMetadata<usize>
+    where
+        Idx: Show,
+    {
+        let mut metadata = StateMetadata::new();
+        for (i, class) in classes.iter().enumerate() {
+            if class.iter().any(|q| self.name(*q).is_some()) {
+                let names = class.iter().map(|q| self.display_name(*q)).join(", ");
+                metadata.set_name(i, format!("{{{names}}}"));
+            }
+            let Some((first, rest)) = class.split_first() else {
+                continue;
+            };
+            for (key, value) in self.annotations(*first) {
+                if rest.iter().all(|q| self.annotation(*q, key) == Some(value)) {
+                    metadata.annotate(i, key, value);
+                }
+            }
+        }
+        metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::Map;
+
+    use super::StateMetadata;
+
+    #[test]
+    fn state_metadata() {
+        let mut left = StateMetadata::from_names([(0, "idle"), (1, "busy")]);
+        left.annotate(0, "origin", "reset");
+        left.annotate(1, "origin", "reset");
+        assert_eq!(left.display_name(1), "busy");
+        assert_eq!(left.annotation(0, "origin"), Some("reset"));
+
+        let remapped = left.remap(&Map::from_iter([(0, 5), (1, 7)]));
+        assert_eq!(remapped.name(7), Some("busy"));
+        assert_eq!(remapped.name(0), None);
+
+        let right = StateMetadata::from_names([(0, "even")]);
+        let product = StateMetadata::product(&left, &right, &[(0, 0), (1, 1)]);
+        assert_eq!(product.name(0), Some("(idle, even)"));
+        assert_eq!(product.name(1), Some("(busy, q1)"));
+        assert_eq!(product.annotation(1, "origin"), Some("reset"));
+
+        left.annotate(1, "output", "1");
+        let quotient = left.quotient(&[vec![0, 1]]);
+        assert_eq!(quotient.name(0), Some("{idle, busy}"));
+        assert_eq!(
+            quotient.annotations(0).collect::<Vec<_>>(),
+            [("origin", "reset")]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::{path::PathBuf, time::Duration};
+
+use tracing::{debug, trace, warn};
+
+/// An event that is emitted by a learner while it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LearnerEvent {
+    /// A hypothesis with the given number of states or classes was constructed.
+    Hypothesis(usize),
+    /// The given number of transitions or rows are waiting to be processed.
+    Pending(usize),
+    /// Redirecting a transition to an existing state or class, i.e. a merge, was attempted.
+    MergeAttempted {
+        /// Whether the result was consistent.
+        consistent: bool,
+    },
+    /// A new state or class was added, resulting in the given number of states or classes.
+    StateAdded(usize),
+    /// A conflict with the data was found, such as a counterexample of the given length.
+    Conflict(usize),
+    /// The given number of membership queries has been posed so far.
+    Queries(usize),
+    /// The given number of equivalence queries has been posed so far.
+    EquivalenceQueries(usize),
+    /// The given number of symbols has been sent in membership queries so far.
+    Symbols(usize),
+    /// The phase with the given name finished after the given time.
+    Phase(&'static str, Duration),
+}
+
+/// A format in which learners can export their intermediate data structures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// A markdown table, e.g. of an observation table.
+    Markdown,
+    /// Comma separated values with a header row.
+    Csv,
+    /// A graph in the DOT language, e.g. of a discrimination tree.
+    Dot,
+}
+
+impl ExportFormat {
+    /// Returns the usual file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Dot => "dot",
+        }
+    }
+}
+
+/// Receives the events emitted by a learner, see [`LearnerEvent`].
+pub trait LearnerObserver {
+    /// Is called for every event in the order in which they occur.
+    fn observe(&mut self, event: LearnerEvent);
+
+    /// Returns the formats in which the learner should export its intermediate data
+    /// structures after each round. Rendering them can be expensive, so nothing is exported
+    /// by default.
+    fn export_formats(&self) -> &[ExportFormat] {
+        &[]
+    }
+
+    /// Receives the rendering of an intermediate data structure in the given format after the
+    /// given round.
+    fn export(&mut self, round: usize, format: ExportFormat, content: &str) {}
+}
+
+/// Logs all events via `tracing`, which is what the learners do if no other observer is given.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingObserver;
+
+impl LearnerObserver for TracingObserver {
+    fn observe(&mut self, event: LearnerEvent) {
+        match event {
+            LearnerEvent::Phase(name, duration) => {
+                debug!("Phase {name} took {}μs", duration.as_micros())
+            }
+            LearnerEvent::Hypothesis(size) => debug!("Constructed hypothesis of size {size}"),
+            event => trace!("{event:?}"),
+        }
+    }
+}
+
+/// Logs all events like [`TracingObserver`] and additionally logs the intermediate data
+/// structures of the learner in the given formats after each round. If a directory is set, the
+/// exports are also written to files named `round-<round>.<extension>` in it.
+#[derive(Debug, Clone, Default)]
+pub struct ExportingObserver {
+    formats: Vec<ExportFormat>,
+    directory: Option<PathBuf>,
+}
+
+impl ExportingObserver {
+    /// Creates an observer that exports in the given formats.
+    pub fn new<I: IntoIterator<Item = ExportFormat>>(formats: I) -> Self {
+        Self {
+            formats: formats.into_iter().collect(),
+            directory: None,
+        }
+    }
+
+    /// Writes the exports into `directory`, which must exist.
+    pub fn directory<P: Into<PathBuf>>(mut self, directory: P) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+}
+
+impl LearnerObserver for ExportingObserver {
+    fn observe(&mut self, event: LearnerEvent) {
+        TracingObserver.observe(event)
+    }
+
+    fn export_formats(&self) -> &[ExportFormat] {
+        &self.formats
+    }
+
+    fn export(&mut self, round: usize, format: ExportFormat, content: &str) {
+        debug!("State after round {round}:\n{content}");
+        if let Some(directory) = &self.directory {
+            let path = directory.join(format!("round-{round}.{}", format.extension()));
+            if let Err(err) = std::fs::write(&path, content) {
+                warn!("Could not write {}: {err}", path.display());
+            }
+        }
+    }
+}
+
+/// Accumulates statistics over the events of a learner.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LearnerStatistics {
+    /// The sizes of all hypotheses in the order in which they were constructed.
+    pub hypotheses: Vec<usize>,
+    /// The maximal number of pending transitions or rows.
+    pub max_pending: usize,
+    /// The number of attempted merges.
+    pub merges_attempted: usize,
+    /// The number of attempted merges that were inconsistent.
+    pub merges_rejected: usize,
+    /// The number of states or classes that were added.
+    pub states_added: usize,
+    /// The number of conflicts that were found.
+    pub conflicts: usize,
+    /// The number of membership queries.
+    pub queries: usize,
+    /// The number of equivalence queries.
+    pub equivalence_queries: usize,
+    /// The number of symbols sent in membership queries.
+    pub symbols: usize,
+    /// The finished phases together with the time they took.
+    pub phases: Vec<(&'static str, Duration)>,
+}
+
+impl LearnerStatistics {
+    /// Returns the total time spent in the phase with the given name.
+    pub fn time_in(&self, phase: &str) -> Duration {
+        self.phases
+            .iter()
+            .filter(|(name, _)| *name == phase)
+            .map(|(_, duration)| *duration)
+            .sum()
+    }
+}
+
+impl LearnerObserver for LearnerStatistics {
+    fn observe(&mut self, event: LearnerEvent) {
+        match event {
+            LearnerEvent::Hypothesis(size) => self.hypotheses.push(size),
+            LearnerEvent::Pending(length) => self.max_pending = self.max_pending.max(length),
+            LearnerEvent::MergeAttempted { consistent } => {
+                self.merges_attempted += 1;
+                if !consistent {
+                    self.merges_rejected += 1;
+                }
+            }
+            LearnerEvent::StateAdded(_) => self.states_added += 1,
+            LearnerEvent::Conflict(_) => self.conflicts += 1,
+            LearnerEvent::Queries(count) => self.queries = count,
+            LearnerEvent::EquivalenceQueries(count) => self.equivalence_queries = count,
+            LearnerEvent::Symbols(count) => self.symbols = count,
+            LearnerEvent::Phase(name, duration) => self.phases.push((name, duration)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{LearnerEvent, LearnerObserver, LearnerStatistics};
+
+    #[test]
+    fn accumulate_statistics() {
+        let mut statistics = LearnerStatistics::default();
+        for event in [
+            LearnerEvent::Pending(2),
+            LearnerEvent::MergeAttempted { consistent: false },
+            LearnerEvent::StateAdded(2),
+            LearnerEvent::Pending(3),
+            LearnerEvent::MergeAttempted { consistent: true },
+            LearnerEvent::Pending(1),
+            LearnerEvent::Hypothesis(2),
+            LearnerEvent::Phase("sprout", Duration::from_millis(3)),
+            LearnerEvent::Phase("sprout", Duration::from_millis(2)),
+        ] {
+            statistics.observe(event);
+        }
+        assert_eq!(statistics.max_pending, 3);
+        assert_eq!(statistics.merges_attempted, 2);
+        assert_eq!(statistics.merges_rejected, 1);
+        assert_eq!(statistics.states_added, 1);
+        assert_eq!(statistics.hypotheses, vec![2]);
+        assert_eq!(statistics.time_in("sprout"), Duration::from_millis(5));
+    }
+}
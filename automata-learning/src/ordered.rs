@@ -0,0 +1,81 @@
+//! A stable order of states and edges. The backends of the `automata` crate iterate edges in
+//! an order that depends on how they are stored, which may be the order of a hash map, so
+//! anything that is produced by iterating over them can differ between runs. The exporters in
+//! [`formats`](crate::formats) and the generators in [`random`](crate::random) instead use the
+//! order given here: states by ascending index, and the edges of a state by ascending
+//! expression and then by ascending target. Parallel edges with the same expression and target
+//! keep the order of the backend.
+
+use automata::prelude::*;
+use itertools::Itertools;
+
+type ExpressionOf<Ts> = <<Ts as TransitionSystem>::Alphabet as Alphabet>::Expression;
+
+/// An edge as returned by [`SortedTransitionSystem::sorted_edges_from`], consisting of the
+/// expression, the color and the target.
+pub type SortedEdge<Ts> = (
+    ExpressionOf<Ts>,
+    <Ts as TransitionSystem>::EdgeColor,
+    <Ts as TransitionSystem>::StateIndex,
+);
+
+/// Iteration over states and edges in the stable order that is described in the module
+/// documentation.
+pub trait SortedTransitionSystem: TransitionSystem + Sized {
+    /// Returns the indices of all states in ascending order.
+    fn sorted_state_indices(&self) -> Vec<Self::StateIndex> {
+        self.state_indices().sorted().collect()
+    }
+
+    /// Returns the edges from `state` sorted by expression and then by target.
+    fn sorted_edges_from(&self, state: Self::StateIndex) -> Vec<SortedEdge<Self>>
+    where
+        ExpressionOf<Self>: Ord,
+    {
+        self.edges_from(state)
+            .expect("State must exist")
+            .map(|e| (e.expression().clone(), e.color(), e.target()))
+            .sorted_by(|(a, _, p), (b, _, q)| a.cmp(b).then_with(|| p.cmp(q)))
+            .collect()
+    }
+
+    /// Returns the edges of all states together with their source, ordered by source and then
+    /// as in [`Self::sorted_edges_from`].
+    #[allow(clippy::type_complexity)]
+    fn sorted_edges(&self) -> Vec<(Self::StateIndex, SortedEdge<Self>)>
+    where
+        ExpressionOf<Self>: Ord,
+    {
+        self.sorted_state_indices()
+            .into_iter()
+            .flat_map(|q| self.sorted_edges_from(q).into_iter().map(move |e| (q, e)))
+            .collect()
+    }
+}
+
+impl<Ts: TransitionSystem> SortedTransitionSystem for Ts {}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::SortedTransitionSystem;
+
+    #[test]
+    fn sorted_edges() {
+        let mut nfa = NTS::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_edge(q1, 'a', q0, Void);
+        nfa.add_edge(q0, 'b', q0, Void);
+        nfa.add_edge(q0, 'a', q1, Void);
+        nfa.add_edge(q0, 'a', q0, Void);
+        assert_eq!(nfa.sorted_state_indices(), vec![q0, q1]);
+        assert_eq!(
+            nfa.sorted_edges_from(q0),
+            vec![('a', Void, q0), ('a', Void, q1), ('b', Void, q0)]
+        );
+        let sources = nfa.sorted_edges().into_iter().map(|(q, _)| q);
+        assert_eq!(sources.collect::<Vec<_>>(), vec![q0, q0, q0, q1]);
+    }
+}
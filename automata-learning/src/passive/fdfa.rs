@@ -0,0 +1,126 @@
+//! Passive learning of periodic FDFAs from omega samples. The pipeline consists of three steps:
+//! - the leading congruence is inferred from the sample with sprout, using prefix consistency
+//!   as in [`OmegaSample::infer_right_congruence`],
+//! - for each class `u` of the leading congruence, a finite sample is derived from the
+//!   normalized decompositions of the sample words, which classifies a period `v` according
+//!   to the word `uv^ω`, see [`progress_samples`],
+//! - a progress DFA is inferred from each of these finite samples in the same way as
+//!   [`super::dfa_rpni`] does, and the results are combined into an [`FDFA`].
+
+use automata::{congruence::FORC, prelude::*, Map, RightCongruence, Set};
+use tracing::{debug, trace};
+
+use crate::congruence::{FdfaKind, ForcMembership, FDFA};
+
+use super::{sprout::sprout, FiniteSample, OmegaSample, Sample};
+
+/// Derives the finite samples for the progress DFAs of each class of `leading`. For every
+/// normalized decomposition `(u, v)` of a sample word, the period `v` is added to the sample of
+/// the class of `u` with the classification of the word. The square of `v` is added as well,
+/// since it describes the same word and a periodic FDFA must classify both equally. Classes
+/// that are not reached by any decomposition receive an empty sample.
+///
+/// Panics if `leading` is not complete or if the sample is not prefix consistent with it.
+pub fn progress_samples<A: Alphabet>(
+    sample: &OmegaSample<A, bool>,
+    leading: &RightCongruence<A>,
+) -> Map<usize, FiniteSample<A, bool>> {
+    // the decompositions only depend on the leading congruence
+    let forc = FORC::from_iter(leading.clone(), Vec::<(usize, RightCongruence<A>)>::new());
+    let mut samples: Map<usize, FiniteSample<A, bool>> = leading
+        .state_indices()
+        .map(|class| {
+            let empty = Sample {
+                alphabet: leading.alphabet().clone(),
+                words: Map::default(),
+                weights: Map::default(),
+            };
+            (class, empty)
+        })
+        .collect();
+    for (word, classification) in sample.entries() {
+        let decompositions = forc
+            .normalized_decompositions(word)
+            .expect("Leading congruence must be complete");
+        for (class, period) in decompositions {
+            let square = [period.as_slice(), period.as_slice()].concat();
+            let words = &mut samples.get_mut(&class).expect("Class must exist").words;
+            for v in [period, square] {
+                if let Some(previous) = words.insert(v, *classification) {
+                    assert_eq!(
+                        previous, *classification,
+                        "Sample is not prefix consistent with the leading congruence"
+                    );
+                }
+            }
+        }
+    }
+    for (class, progress) in &samples {
+        trace!(
+            "Sample for class {class} has {} words",
+            progress.words.len()
+        );
+    }
+    samples
+}
+
+/// Infers a periodic FDFA from the given sample, see the module documentation for the steps
+/// that are taken.
+pub fn infer_fdfa<A: Alphabet>(sample: &OmegaSample<A, bool>) -> FDFA<A> {
+    let leading = sample.infer_right_congruence();
+    debug!(
+        "Inferred leading congruence with {} classes",
+        leading.size()
+    );
+
+    let mut progress = vec![];
+    let mut accepting = Map::default();
+    for (class, progress_sample) in progress_samples(sample, &leading) {
+        let prc = sprout(&progress_sample, vec![], true);
+        let finals: Set<usize> = progress_sample
+            .positive_words()
+            .map(|w| {
+                prc.reached_state_index(w)
+                    .expect("Every positive word must induce a successful run!")
+            })
+            .collect();
+        debug!(
+            "Inferred progress congruence with {} classes for class {class}",
+            prc.size()
+        );
+        progress.push((class, prc));
+        accepting.insert(class, finals);
+    }
+    FDFA::new(
+        FdfaKind::Periodic,
+        FORC::from_iter(leading, progress),
+        accepting,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::infer_fdfa;
+    use crate::{congruence::FdfaKind, passive::OmegaSample};
+
+    #[test]
+    fn passive_fdfa() {
+        // infinitely many a's
+        let sample = OmegaSample::new_omega(
+            alphabet!(simple 'a', 'b'),
+            vec![
+                (upw!("a"), true),
+                (upw!("b", "a"), true),
+                (upw!("b"), false),
+                (upw!("a", "b"), false),
+            ],
+        );
+        let fdfa = infer_fdfa(&sample);
+        assert_eq!(fdfa.kind(), FdfaKind::Periodic);
+        for (word, classification) in sample.entries() {
+            assert_eq!(fdfa.accepts(word), *classification, "{word:?}");
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Incremental passive learning of DFAs. Instead of running RPNI on the whole sample whenever
+//! new words arrive, an [`IncrementalRpni`] keeps its current hypothesis and only revises the
+//! parts of it that contradict the new words:
+//! - all transitions of the spanning tree, i.e. those leading from the class of `u` to the
+//!   class whose minimal representative is `ua`, are kept, as a tree never merges two words,
+//! - the remaining transitions are added back in length-lexicographic order of their source
+//!   as long as the congruence stays consistent with the extended sample,
+//! - the transitions that are dropped this way are filled in by sprout, which may split off
+//!   new classes for them.
+
+use automata::{prelude::*, Map, RightCongruence};
+use tracing::debug;
+
+use crate::congruence::{ClassNavigation, SproutableExt};
+
+use super::{
+    label_positive_classes,
+    sprout::{sprout, sprout_resume, ConsistencyCheck},
+    FiniteSample,
+};
+
+/// Returned by [`IncrementalRpni::extend_with`] if a new word is already contained in the
+/// sample with a different classification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelConflict<S>(pub Vec<S>);
+
+/// Maintains a DFA that is consistent with a growing sample, see the module documentation.
+#[derive(Clone)]
+pub struct IncrementalRpni<A: Alphabet> {
+    sample: FiniteSample<A, bool>,
+    cong: RightCongruence<A>,
+}
+
+impl<A: Alphabet> IncrementalRpni<A> {
+    /// Learns an initial hypothesis from the given sample, in the same way as
+    /// [`super::dfa_rpni`] does.
+    pub fn new(sample: FiniteSample<A, bool>) -> Self {
+        let cong = sprout(&sample, vec![], true);
+        Self { sample, cong }
+    }
+
+    /// Returns all words that were seen so far.
+    pub fn sample(&self) -> &FiniteSample<A, bool> {
+        &self.sample
+    }
+
+    /// Returns the right congruence underlying the current hypothesis.
+    pub fn congruence(&self) -> &RightCongruence<A> {
+        &self.cong
+    }
+
+    /// Returns the current hypothesis.
+    pub fn hypothesis(&self) -> DFA<A> {
+        label_positive_classes(&self.sample, self.cong.clone())
+    }
+
+    /// Incorporates the words of `sample` and revises the hypothesis if it has become
+    /// inconsistent. Returns the number of transitions that had to be redirected, which is zero
+    /// if the hypothesis was kept. If a word is already known with a different classification,
+    /// the sample and hypothesis are left unchanged and the word is returned.
+    pub fn extend_with(
+        &mut self,
+        sample: &FiniteSample<A, bool>,
+    ) -> Result<usize, LabelConflict<A::Symbol>> {
+        if let Some((word, _)) = sample
+            .entries()
+            .find(|(w, c)| self.sample.words.get(*w).is_some_and(|known| *known != **c))
+        {
+            return Err(LabelConflict(word.clone()));
+        }
+        self.sample
+            .words
+            .extend(sample.entries().map(|(w, c)| (w.clone(), *c)));
+
+        if self.sample.consistent(&self.cong) {
+            debug!("Hypothesis is consistent with the new words");
+            return Ok(0);
+        }
+
+        let (cong, pending) = self.consistent_part();
+        let reopened = pending.len();
+        debug!("Hypothesis is inconsistent, redirecting {reopened} transitions");
+        self.cong = sprout_resume(&self.sample, cong, pending);
+        Ok(reopened)
+    }
+
+    /// Rebuilds the spanning tree of the current congruence and adds back all other
+    /// transitions that keep it consistent with the sample. Returns the result together with
+    /// the transitions that were left out.
+    fn consistent_part(&self) -> (RightCongruence<A>, Vec<(usize, A::Symbol)>) {
+        let classes = self.cong.classes_llex();
+        let mut cong = RightCongruence::new(self.cong.alphabet().clone());
+        let index: Map<usize, usize> = classes
+            .iter()
+            .map(|(q, rep)| (*q, cong.add_state((rep.clone(), Void))))
+            .collect();
+        let representatives: Map<usize, &Vec<A::Symbol>> =
+            classes.iter().map(|(q, rep)| (*q, rep)).collect();
+
+        let mut back_edges = vec![];
+        for (q, rep) in &classes {
+            for sym in self.cong.alphabet().universe() {
+                let p = self
+                    .cong
+                    .successor_index(*q, sym)
+                    .expect("Hypothesis must be complete");
+                let mut word = rep.clone();
+                word.push(sym);
+                if representatives[&p] == &word {
+                    cong.add_edge(index[q], A::expression(sym), index[&p], Void);
+                } else {
+                    back_edges.push((index[q], sym, index[&p]));
+                }
+            }
+        }
+
+        let mut pending = vec![];
+        for (source, sym, target) in back_edges {
+            let mut transaction = cong.transaction();
+            transaction.add_edge(source, sym, target, Void);
+            let consistent = self.sample.consistent(transaction.ts());
+            transaction.rollback();
+            if consistent {
+                cong.add_edge(source, A::expression(sym), target, Void);
+            } else {
+                pending.push((source, sym));
+            }
+        }
+        (cong, pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{IncrementalRpni, LabelConflict};
+    use crate::passive::FiniteSample;
+
+    fn sample(words: &[(&str, bool)]) -> FiniteSample {
+        FiniteSample::new_finite(
+            alphabet!(simple 'a', 'b'),
+            words.iter().map(|(w, c)| (w.chars(), *c)),
+        )
+    }
+
+    #[test]
+    fn incremental_rpni() {
+        // words ending with an a
+        let mut learner = IncrementalRpni::new(sample(&[("a", true), ("", false)]));
+        assert_eq!(learner.hypothesis().size(), 2);
+
+        // already classified correctly
+        assert_eq!(learner.extend_with(&sample(&[("ba", true)])), Ok(0));
+
+        // the a-transition of the class of a has to be redirected
+        let new = sample(&[("aa", true), ("ab", false), ("b", false)]);
+        assert_eq!(learner.extend_with(&new), Ok(1));
+        let dfa = learner.hypothesis();
+        assert_eq!(dfa.size(), 2);
+        assert!(learner.sample().consistency_report(&dfa).is_consistent());
+
+        let conflicting = sample(&[("a", false)]);
+        assert_eq!(
+            learner.extend_with(&conflicting),
+            Err(LabelConflict(vec!['a']))
+        );
+        assert_eq!(learner.sample().words.len(), 6);
+    }
+}
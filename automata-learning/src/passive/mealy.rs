@@ -0,0 +1,186 @@
+use automata::{prelude::*, Map};
+use tracing::{debug, trace};
+
+use super::{sprout::ConsistencyCheck, FiniteSample};
+
+/// Errors that can occur when converting input/output traces into a sample.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TraceError<S> {
+    /// The trace with the given index has a different number of inputs and outputs.
+    LengthMismatch(usize),
+    /// The given input word is mapped to different outputs by two traces.
+    Inconsistent(Vec<S>),
+}
+
+impl<S: std::fmt::Debug> std::fmt::Display for TraceError<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceError::LengthMismatch(trace) => {
+                write!(
+                    f,
+                    "Trace {trace} has different numbers of inputs and outputs"
+                )
+            }
+            TraceError::Inconsistent(word) => {
+                write!(f, "Input {word:?} is mapped to different outputs")
+            }
+        }
+    }
+}
+
+impl<A: Alphabet, O: Color> FiniteSample<A, O> {
+    /// Builds a sample from input/output traces of a reactive system, each of which consists of
+    /// a word of inputs and the word of outputs that is produced on it. A trace of length `n`
+    /// yields `n` entries, mapping each non-empty prefix of the inputs to the output of its
+    /// last symbol, which is how the output of a Mealy machine is queried.
+    pub fn from_io_traces<I>(alphabet: A, traces: I) -> Result<Self, TraceError<A::Symbol>>
+    where
+        I: IntoIterator<Item = (Vec<A::Symbol>, Vec<O>)>,
+    {
+        let mut words: Map<Vec<A::Symbol>, O> = Map::default();
+        for (i, (inputs, outputs)) in traces.into_iter().enumerate() {
+            if inputs.len() != outputs.len() {
+                return Err(TraceError::LengthMismatch(i));
+            }
+            for (length, output) in (1..=inputs.len()).zip(outputs) {
+                let prefix = inputs[..length].to_vec();
+                match words.get(&prefix) {
+                    Some(known) if *known != output => {
+                        return Err(TraceError::Inconsistent(prefix));
+                    }
+                    Some(_) => {}
+                    None => {
+                        words.insert(prefix, output);
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            alphabet,
+            words,
+            weights: Map::default(),
+        })
+    }
+}
+
+/// Requires that transitions of the congruence produce a unique output. The output of a
+/// sample word `ua` is the output of the `a`-transition from the class of `u`, so two such
+/// words that use the same transition must have the same output.
+#[derive(Clone, Debug)]
+pub struct OutputConsistency<'a, A: Alphabet, O: Color> {
+    sample: &'a FiniteSample<A, O>,
+}
+
+impl<'a, A: Alphabet, O: Color> OutputConsistency<'a, A, O> {
+    /// Creates a new instance of the constraint.
+    pub fn new(sample: &'a FiniteSample<A, O>) -> Self {
+        Self { sample }
+    }
+
+    /// Collects the outputs of all transitions that are used by sample words, returns `None`
+    /// if a transition has to produce two different outputs.
+    fn outputs(&self, cong: &RightCongruence<A>) -> Option<Map<(usize, A::Symbol), O>> {
+        let mut outputs: Map<(usize, A::Symbol), O> = Map::default();
+        for (word, output) in self.sample.entries() {
+            let Some((sym, prefix)) = word.split_last() else {
+                continue;
+            };
+            let Some(q) = cong.reached_state_index(prefix) else {
+                continue;
+            };
+            match outputs.get(&(q, *sym)) {
+                Some(known) if known != output => {
+                    trace!("\t\tTransition from {q} on {} has two outputs", sym.show());
+                    return None;
+                }
+                Some(_) => {}
+                None => {
+                    outputs.insert((q, *sym), output.clone());
+                }
+            }
+        }
+        Some(outputs)
+    }
+}
+
+impl<'a, A: Alphabet, O: Color> ConsistencyCheck<A> for OutputConsistency<'a, A, O> {
+    fn consistent(&self, cong: &RightCongruence<A>) -> bool {
+        self.outputs(cong).is_some()
+    }
+
+    fn threshold(&self) -> usize {
+        // the prefix tree of the sample is always consistent
+        self.sample.words().map(|w| w.len()).sum::<usize>() + 1
+    }
+
+    fn alphabet(&self) -> &A {
+        self.sample.alphabet()
+    }
+}
+
+/// Infers a Mealy machine from a sample that maps input words to the output of their last
+/// symbol, e.g. one obtained with [`FiniteSample::from_io_traces`]. The transition structure
+/// is learned with sprout using [`OutputConsistency`], transitions that are not used by any
+/// sample word produce the default output.
+pub fn mealy_rpni<A: Alphabet, O: Color + Default>(
+    sample: &FiniteSample<A, O>,
+) -> MealyMachine<A, O> {
+    let constraint = OutputConsistency::new(sample);
+    let cong = super::sprout::sprout(&constraint, vec![], true);
+    let outputs = constraint
+        .outputs(&cong)
+        .expect("Sprout produces consistent congruences");
+
+    let mut ts: DTS<A, Void, O> = DTS::new_for_alphabet(cong.alphabet().clone());
+    let states: Map<usize, usize> = cong
+        .state_indices()
+        .map(|q| (q, ts.add_state(Void)))
+        .collect();
+    for (q, state) in &states {
+        for sym in cong.alphabet().universe() {
+            let p = cong
+                .successor_index(*q, sym)
+                .expect("Sprout produces complete congruences");
+            let output = outputs.get(&(*q, sym)).cloned().unwrap_or_default();
+            ts.add_edge(*state, A::expression(sym), states[&p], output);
+        }
+    }
+    debug!("Inferred Mealy machine with {} states", ts.size());
+    ts.with_initial(states[&cong.initial()]).into_mealy()
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{mealy_rpni, TraceError};
+    use crate::passive::FiniteSample;
+
+    #[test]
+    fn mealy_from_traces() {
+        // a toggles the parity of the number of a's, every symbol outputs the current parity
+        let traces = [
+            ("aab", [1, 0, 0].as_slice()),
+            ("ba", &[0, 1]),
+            ("abb", &[1, 1, 1]),
+            ("bab", &[0, 1, 1]),
+            ("bb", &[0, 0]),
+        ]
+        .map(|(inputs, outputs)| (inputs.chars().collect(), outputs.to_vec()));
+        let sample = FiniteSample::from_io_traces(alphabet!(simple 'a', 'b'), traces).unwrap();
+        assert_eq!(sample.words.len(), 9);
+
+        let mm = mealy_rpni(&sample);
+        assert_eq!(mm.size(), 2);
+        assert_eq!(mm.last_edge_color("abab"), Some(0));
+        assert_eq!(mm.last_edge_color("aaba"), Some(1));
+
+        assert_eq!(
+            FiniteSample::from_io_traces(
+                alphabet!(simple 'a', 'b'),
+                [(vec!['a'], vec![0]), (vec!['a', 'b'], vec![1, 1])]
+            ),
+            Err(TraceError::Inconsistent(vec!['a']))
+        );
+    }
+}
@@ -14,6 +14,8 @@ pub use sample::{ClassOmegaSample, PeriodicOmegaSample, Sample, SplitOmegaSample
 
 use crate::{
     active::{LStar, MealyOracle},
+    budget::Budget,
+    observer::LearnerStatistics,
     passive::fwpm::FWPM,
     prefixtree::prefix_tree,
     AnnotatedCongruence,
@@ -21,7 +23,10 @@ use crate::{
 
 use self::precise::PreciseDPA;
 
-pub use self::sample::{FiniteSample, OmegaSample};
+pub use self::sample::{
+    AbbadingoParseError, ConsistencyReport, Evaluation, FiniteSample, KFolds, LabeledSample,
+    Misclassification, OmegaSample, SampleFileError, WordLabel,
+};
 
 /// Module containing the implementations of the sprout/glerc algorithm.
 pub mod sprout;
@@ -32,17 +37,88 @@ pub mod fwpm;
 /// Defines the precise DPA.
 pub mod precise;
 
+/// Exact identification of minimal consistent DFAs with a SAT solver.
+pub mod sat;
+
+/// Passive inference of Mealy machines from input/output traces.
+pub mod mealy;
+
+/// Passive learning of FDFAs from omega samples.
+pub mod fdfa;
+
+/// Incremental passive learning of DFAs from growing samples.
+pub mod incremental;
+
 /// Executes the RPNI algorithm on the given sample. This returns a DFA that is
 /// composed of a right congruence as well as an acceptance condition, which marks
 /// a classes as accepting if it is reached by a positive sample word.
 pub fn dfa_rpni<A: Alphabet>(sample: &FiniteSample<A, bool>) -> DFA<A> {
     let cong = sprout::sprout(sample, vec![], true);
+    label_positive_classes(sample, cong)
+}
+
+/// Executes RPNI like [`dfa_rpni`], where consistency with the sample is checked using the
+/// given representation of its words, see [`sprout::finite_consistency_conflicts`].
+pub fn dfa_rpni_with<A: Alphabet>(
+    sample: &FiniteSample<A, bool>,
+    representation: sprout::PrefixRepresentation,
+) -> DFA<A> {
+    let conflicts = sprout::finite_consistency_conflicts(sample, representation);
+    let cong = sprout::sprout(conflicts, vec![], true);
+    label_positive_classes(sample, cong)
+}
+
+/// Executes RPNI like [`dfa_rpni`] and additionally returns statistics about the run.
+pub fn dfa_rpni_with_statistics<A: Alphabet>(
+    sample: &FiniteSample<A, bool>,
+) -> (DFA<A>, LearnerStatistics) {
+    let mut statistics = LearnerStatistics::default();
+    let cong = sprout::sprout_observed(
+        sample,
+        vec![],
+        true,
+        &sprout::SproutStrategy::default(),
+        &mut statistics,
+    )
+    .unwrap_or_else(|err| panic!("{err}"));
+    (label_positive_classes(sample, cong), statistics)
+}
+
+/// Executes RPNI on a sample that may also label words as prefixes or factors of the language,
+/// see [`LabeledSample`]. The classes that are reached by a member are accepting.
+pub fn dfa_rpni_labeled<A: Alphabet>(sample: &LabeledSample<A>) -> DFA<A> {
+    let cong = sprout::sprout(sample, vec![], true);
+    label_positive_classes(sample.members(), cong)
+}
+
+/// Executes RPNI like [`dfa_rpni`], but stops once `budget` is exhausted, see
+/// [`sprout::sprout_budgeted`]. In that case, the DFA in the returned error is built from the
+/// partial congruence that was found so far and rejects all words whose run leaves it. An
+/// inconsistent sample is reported as [`sprout::SproutError::InconsistentSample`].
+pub fn dfa_rpni_budgeted<A: Alphabet>(
+    sample: &FiniteSample<A, bool>,
+    budget: &Budget,
+) -> Result<DFA<A>, sprout::SproutError<DFA<A>>> {
+    sprout::sprout_budgeted(
+        sample,
+        vec![],
+        true,
+        &sprout::SproutStrategy::default(),
+        budget,
+    )
+    .map(|cong| label_positive_classes(sample, cong))
+    .map_err(|err| err.map_best(|best| label_positive_classes(sample, best)))
+}
+
+/// Turns `cong` into a DFA in which the classes that are reached by a positive word of
+/// `sample` are accepting. Positive words whose run leaves a partial congruence are ignored.
+fn label_positive_classes<A: Alphabet>(
+    sample: &FiniteSample<A, bool>,
+    cong: RightCongruence<A>,
+) -> DFA<A> {
     let accepting: automata::Set<_> = sample
         .positive_words()
-        .map(|w| {
-            cong.reached_state_index(w)
-                .expect("Every positive word must induce a successful run!")
-        })
+        .filter_map(|w| cong.reached_state_index(w))
         .collect();
     (&cong)
         .map_state_colors(|q| {
@@ -56,6 +132,34 @@ pub fn dfa_rpni<A: Alphabet>(sample: &FiniteSample<A, bool>) -> DFA<A> {
         .into_dfa()
 }
 
+/// Executes RPNI on a noisy sample, where up to `budget` words may be misclassified by the
+/// result, see [`sprout::Tolerant`]. A class is accepting if the positive sample words reaching
+/// it outweigh the negative ones, where every word counts with its [weight](Sample::weight).
+pub fn dfa_rpni_tolerant<A: Alphabet>(
+    sample: &FiniteSample<A, bool>,
+    budget: sprout::NoiseBudget,
+) -> DFA<A> {
+    let cong = sprout::sprout(sprout::Tolerant::new(sample, budget), vec![], true);
+    let mut balance: automata::Map<usize, isize> = automata::Map::default();
+    for (w, c) in sample.entries() {
+        let idx = cong
+            .reached_state_index(w)
+            .expect("Every sample word must induce a successful run!");
+        let weight = sample.weight(w) as isize;
+        *balance.entry(idx).or_default() += if *c { weight } else { -weight };
+    }
+    (&cong)
+        .map_state_colors(|q| {
+            let idx = cong
+                .class_to_index(q.class())
+                .expect("Class must be in the congruence!");
+            balance.get(&idx).is_some_and(|b| *b > 0)
+        })
+        .collect_pointed()
+        .0
+        .into_dfa()
+}
+
 /// Executes a variant of the RPNI algorithm for omega-words, producing a DBA.
 pub fn dba_rpni<A: Alphabet>(sample: &OmegaSample<A, bool>) -> DBA<A> {
     todo!()
@@ -134,7 +238,16 @@ mod tests {
     use automata::prelude::*;
     use tracing::info;
 
-    use crate::passive::dpa_rpni;
+    use crate::{
+        budget::{Budget, CancellationToken, Exhausted},
+        formats::ToDot,
+        passive::{
+            dfa_rpni, dfa_rpni_budgeted, dfa_rpni_tolerant, dfa_rpni_with,
+            dfa_rpni_with_statistics, dpa_rpni,
+            sprout::{NoiseBudget, PrefixRepresentation, SproutError},
+            FiniteSample,
+        },
+    };
 
     use super::{sample, OmegaSample};
 
@@ -172,4 +285,129 @@ mod tests {
         //     assert_eq!(b, c, "{:?} is classified {b}, expected {c}", w);
         // }
     }
+
+    #[test]
+    fn noise_tolerant_rpni() {
+        // words ending with an a, where "ba" is mislabelled
+        let sample = FiniteSample::new_finite(
+            alphabet!(simple 'a', 'b'),
+            [
+                ("a", true),
+                ("aa", true),
+                ("ba", false),
+                ("bba", true),
+                ("aba", true),
+                ("", false),
+                ("b", false),
+                ("ab", false),
+                ("bb", false),
+                ("abb", false),
+            ]
+            .map(|(w, c)| (w.chars(), c)),
+        );
+        let dfa = dfa_rpni_tolerant(&sample, NoiseBudget::Absolute(1));
+        let report = sample.consistency_report(&dfa);
+        assert!(report.misclassified_count() <= 1);
+
+        let strict = dfa_rpni_tolerant(&sample, NoiseBudget::Absolute(0));
+        assert!(sample.consistency_report(&strict).is_consistent());
+    }
+
+    #[test]
+    fn noise_tolerant_rpni_weighted() {
+        // the budget allows a single class, in which the heavy positive word outweighs the two
+        // light negative ones
+        let sample = FiniteSample::new_weighted(
+            alphabet!(simple 'a', 'b'),
+            [("a", true, 5), ("b", false, 1), ("aa", false, 1)].map(|(w, c, n)| (w.chars(), c, n)),
+        );
+        let dfa = dfa_rpni_tolerant(&sample, NoiseBudget::Absolute(2));
+        assert_eq!(dfa.size(), 1);
+        assert!(dfa.accepts("b".chars()));
+        assert!(dfa.accepts("aa".chars()));
+    }
+
+    #[test]
+    fn rpni_with_dawg() {
+        let sample = FiniteSample::new_finite(
+            alphabet!(simple 'a', 'b'),
+            [
+                ("a", true),
+                ("ba", true),
+                ("aba", true),
+                ("bba", true),
+                ("", false),
+                ("b", false),
+                ("ab", false),
+                ("bb", false),
+                ("abb", false),
+            ]
+            .map(|(w, c)| (w.chars(), c)),
+        );
+        for representation in [PrefixRepresentation::Tree, PrefixRepresentation::Dawg] {
+            let dfa = dfa_rpni_with(&sample, representation);
+            assert!(sample.consistency_report(&dfa).is_consistent());
+        }
+
+        let (dfa, statistics) = dfa_rpni_with_statistics(&sample);
+        assert_eq!(statistics.hypotheses, vec![dfa.size()]);
+        assert_eq!(statistics.states_added + 1, dfa.size());
+        assert!(statistics.merges_attempted >= statistics.merges_rejected);
+    }
+
+    #[test]
+    fn budgeted_rpni() {
+        let sample = FiniteSample::new_finite(
+            alphabet!(simple 'a', 'b'),
+            [
+                ("a", true),
+                ("ba", true),
+                ("", false),
+                ("b", false),
+                ("ab", false),
+            ]
+            .map(|(w, c)| (w.chars(), c)),
+        );
+        let dfa = dfa_rpni_budgeted(&sample, &Budget::unlimited()).unwrap();
+        assert!(sample.consistency_report(&dfa).is_consistent());
+
+        let Err(SproutError::BudgetExhausted(exceeded)) =
+            dfa_rpni_budgeted(&sample, &Budget::unlimited().max_states(1))
+        else {
+            panic!("The budget should be exhausted")
+        };
+        assert_eq!(exceeded.reason, Exhausted::States(1));
+        assert_eq!(exceeded.best.size(), 2);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let budget = Budget::unlimited().cancellation(token);
+        let Err(SproutError::BudgetExhausted(exceeded)) = dfa_rpni_budgeted(&sample, &budget)
+        else {
+            panic!("The run should be cancelled")
+        };
+        assert_eq!(exceeded.reason, Exhausted::Cancelled);
+        assert_eq!(exceeded.best.size(), 1);
+    }
+
+    #[test]
+    fn rpni_is_deterministic() {
+        let learn = || {
+            let sample = FiniteSample::new_finite(
+                alphabet!(simple 'a', 'b', 'c'),
+                [
+                    ("a", true),
+                    ("ca", true),
+                    ("bca", true),
+                    ("", false),
+                    ("b", false),
+                    ("ac", false),
+                    ("cb", false),
+                ]
+                .map(|(w, c)| (w.chars(), c)),
+            );
+            dfa_rpni(&sample).to_dot()
+        };
+        assert_eq!(learn(), learn());
+    }
 }
@@ -0,0 +1,300 @@
+use std::path::Path;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+use tracing::{debug, trace};
+
+use super::{FiniteSample, Sample};
+
+/// Abstracts the types of errors that can occur when parsing a sample in the Abbadingo or
+/// StaMinA format.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum AbbadingoParseError {
+    MissingHeader,
+    MalformedHeader(String),
+    MalformedLine(usize),
+    MalformedLabel(usize, String),
+    LengthMismatch(usize),
+    SymbolOutOfRange(usize, String),
+    CountMismatch { expected: usize, found: usize },
+    Inconsistent(usize),
+    Io(String),
+}
+
+impl std::fmt::Display for AbbadingoParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbbadingoParseError::MissingHeader => write!(f, "Missing header"),
+            AbbadingoParseError::MalformedHeader(header) => {
+                write!(f, "Malformed header \"{header}\"")
+            }
+            AbbadingoParseError::MalformedLine(line) => write!(f, "Malformed line {line}"),
+            AbbadingoParseError::MalformedLabel(line, label) => {
+                write!(f, "Malformed label {label} in line {line}")
+            }
+            AbbadingoParseError::LengthMismatch(line) => {
+                write!(
+                    f,
+                    "Length in line {line} does not match the number of symbols"
+                )
+            }
+            AbbadingoParseError::SymbolOutOfRange(line, sym) => {
+                write!(f, "Symbol {sym} in line {line} is not in the alphabet")
+            }
+            AbbadingoParseError::CountMismatch { expected, found } => {
+                write!(f, "Expected {expected} words but found {found}")
+            }
+            AbbadingoParseError::Inconsistent(line) => write!(
+                f,
+                "Inconsistent sample, the word in line {line} is both positive and negative"
+            ),
+            AbbadingoParseError::Io(err) => write!(f, "Could not read file: {err}"),
+        }
+    }
+}
+
+/// Maps the symbol with the given index to a character. The first 26 symbols are mapped to
+/// the lowercase letters, the next 26 to the uppercase letters and the remaining ones to
+/// characters starting at `U+0100`.
+pub fn symbol_for_index(index: usize) -> char {
+    let index = index as u32;
+    match index {
+        0..=25 => char::from_u32('a' as u32 + index),
+        26..=51 => char::from_u32('A' as u32 + index - 26),
+        _ => char::from_u32(0x100 + index - 52),
+    }
+    .expect("Symbol index is too large")
+}
+
+/// The inverse of [`symbol_for_index`].
+pub fn index_for_symbol(sym: char) -> Option<usize> {
+    match sym {
+        'a'..='z' => Some(sym as usize - 'a' as usize),
+        'A'..='Z' => Some(sym as usize - 'A' as usize + 26),
+        _ if (sym as usize) >= 0x100 => Some(sym as usize - 0x100 + 52),
+        _ => None,
+    }
+}
+
+/// Parses a file in the Abbadingo format. If `allow_unlabeled` is set, words whose label is
+/// neither `0` nor `1` (StaMinA uses `?` and `-1` for test words) are skipped instead of
+/// resulting in an error.
+fn parse_abbadingo_like(
+    input: &str,
+    allow_unlabeled: bool,
+) -> Result<FiniteSample<CharAlphabet, bool>, AbbadingoParseError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (_, header) = lines.next().ok_or(AbbadingoParseError::MissingHeader)?;
+    let (count, alphabet_size) = header
+        .split_whitespace()
+        .map(|n| n.parse::<usize>())
+        .collect_tuple()
+        .and_then(|(count, size)| Some((count.ok()?, size.ok()?)))
+        .ok_or_else(|| AbbadingoParseError::MalformedHeader(header.to_string()))?;
+    trace!("Parsing sample with {count} words over {alphabet_size} symbols");
+
+    let mut words = Map::default();
+    let mut weights = Map::default();
+    let mut inferred = Set::default();
+    let mut found = 0;
+    for (number, line) in lines {
+        let mut tokens = line.split_whitespace();
+        let label = tokens
+            .next()
+            .ok_or(AbbadingoParseError::MalformedLine(number))?;
+        let length = tokens
+            .next()
+            .and_then(|length| length.parse::<usize>().ok())
+            .ok_or(AbbadingoParseError::MalformedLine(number))?;
+        let word = tokens
+            .map(|sym| {
+                if let Ok(index) = sym.parse::<usize>() {
+                    if index < alphabet_size {
+                        return Ok(symbol_for_index(index));
+                    }
+                } else {
+                    // some benchmarks use letters instead of indices
+                    let mut chars = sym.chars();
+                    if let (Some(c), None) = (chars.next(), chars.next()) {
+                        inferred.insert(c);
+                        return Ok(c);
+                    }
+                }
+                Err(AbbadingoParseError::SymbolOutOfRange(
+                    number,
+                    sym.to_string(),
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if word.len() != length {
+            return Err(AbbadingoParseError::LengthMismatch(number));
+        }
+        found += 1;
+
+        let classification = match label {
+            "1" => true,
+            "0" => false,
+            _ if allow_unlabeled => {
+                debug!("Skipping unlabeled word in line {number}");
+                continue;
+            }
+            other => {
+                return Err(AbbadingoParseError::MalformedLabel(
+                    number,
+                    other.to_string(),
+                ))
+            }
+        };
+        match words.get(&word) {
+            Some(old) if *old != classification => {
+                return Err(AbbadingoParseError::Inconsistent(number));
+            }
+            Some(_) => {
+                debug!("Duplicate word found in line {number}");
+                *weights.entry(word).or_insert(1) += 1;
+            }
+            None => {
+                words.insert(word, classification);
+            }
+        }
+    }
+
+    if found != count {
+        return Err(AbbadingoParseError::CountMismatch {
+            expected: count,
+            found,
+        });
+    }
+
+    // the alphabet consists of the symbols declared in the header and those that were given
+    // as letters, the latter may overlap with the former
+    let alphabet = CharAlphabet::from_iter(
+        (0..alphabet_size)
+            .map(symbol_for_index)
+            .chain(inferred)
+            .sorted()
+            .dedup(),
+    );
+    Ok(Sample {
+        alphabet,
+        words,
+        weights,
+    })
+}
+
+impl FiniteSample<CharAlphabet, bool> {
+    /// Parses a sample in the format of the Abbadingo One competition. The first line contains
+    /// the number of words and the size of the alphabet, each subsequent line contains the
+    /// label (`1` for positive and `0` for negative), the length of the word and its symbols,
+    /// which are given as indices into the alphabet. Symbols are converted to characters with
+    /// [`symbol_for_index`], so an alphabet of size 2 becomes `{a, b}`. Symbols that are given
+    /// as single letters instead of indices are used directly and added to the alphabet. Words
+    /// that occur multiple times are counted in their weight, see [`Sample::weight`].
+    pub fn from_abbadingo(input: &str) -> Result<Self, AbbadingoParseError> {
+        parse_abbadingo_like(input, false)
+    }
+
+    /// Parses a sample from the StaMinA competition, which uses the Abbadingo format but may
+    /// contain unlabeled words (labeled with `?` or `-1`). Unlabeled words are skipped.
+    pub fn from_stamina(input: &str) -> Result<Self, AbbadingoParseError> {
+        parse_abbadingo_like(input, true)
+    }
+
+    /// Reads a sample in the Abbadingo format from the file at `path`.
+    pub fn load_abbadingo<P: AsRef<Path>>(path: P) -> Result<Self, AbbadingoParseError> {
+        let input = std::fs::read_to_string(path)
+            .map_err(|err| AbbadingoParseError::Io(err.to_string()))?;
+        Self::from_abbadingo(&input)
+    }
+
+    /// Reads a sample in the StaMinA format from the file at `path`.
+    pub fn load_stamina<P: AsRef<Path>>(path: P) -> Result<Self, AbbadingoParseError> {
+        let input = std::fs::read_to_string(path)
+            .map_err(|err| AbbadingoParseError::Io(err.to_string()))?;
+        Self::from_stamina(&input)
+    }
+
+    /// Writes the sample in the Abbadingo format, which is also understood by StaMinA tools.
+    /// Symbols are written as their position in the alphabet, words are ordered by length and
+    /// then lexicographically. Each word is repeated as often as its weight says.
+    pub fn to_abbadingo(&self) -> String {
+        let symbols = self.alphabet.universe().collect_vec();
+        let mut out = format!("{} {}\n", self.total_weight(), symbols.len());
+        for (word, classification) in self
+            .entries()
+            .sorted_by(|(x, _), (y, _)| x.len().cmp(&y.len()).then(x.cmp(y)))
+        {
+            let indices = word
+                .iter()
+                .map(|sym| {
+                    symbols
+                        .iter()
+                        .position(|s| s == sym)
+                        .expect("Symbol must be in the alphabet")
+                        .to_string()
+                })
+                .collect_vec();
+            for _ in 0..self.weight(word) {
+                out.push_str(&format!(
+                    "{} {}",
+                    if *classification { 1 } else { 0 },
+                    word.len()
+                ));
+                for index in &indices {
+                    out.push(' ');
+                    out.push_str(index);
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{index_for_symbol, symbol_for_index, AbbadingoParseError};
+    use crate::passive::FiniteSample;
+
+    #[test]
+    fn parse_abbadingo() {
+        let input = "5 2\n1 0\n0 1 1\n1 3 0 1 0\n0 2 1 1\n0 1 1\n";
+        let sample = FiniteSample::from_abbadingo(input).unwrap();
+        assert_eq!(sample.weight(&vec!['b']), 2);
+        assert_eq!(sample.alphabet, alphabet!(simple 'a', 'b'));
+        assert_eq!(sample.positive_words().count(), 2);
+        assert_eq!(sample.classify(&vec!['a', 'b', 'a']), Some(true));
+        assert_eq!(sample.classify(&vec!['b']), Some(false));
+        assert_eq!(sample.classify(&vec![]), Some(true));
+
+        let written = sample.to_abbadingo();
+        assert_eq!(FiniteSample::from_abbadingo(&written).unwrap(), sample);
+    }
+
+    #[test]
+    fn parse_stamina() {
+        let input = "3 3\n1 2 0 2\n? 1 1\n-1 1 2\n";
+        assert!(FiniteSample::from_abbadingo(input).is_err());
+        let sample = FiniteSample::from_stamina(input).unwrap();
+        assert_eq!(sample.words.len(), 1);
+        assert_eq!(sample.classify(&vec!['a', 'c']), Some(true));
+
+        assert_eq!(
+            FiniteSample::from_abbadingo("1 2\n1 1 2\n"),
+            Err(AbbadingoParseError::SymbolOutOfRange(2, "2".to_string()))
+        );
+        let letters = FiniteSample::from_abbadingo("2 0\n1 2 x y\n0 1 y\n").unwrap();
+        assert_eq!(letters.alphabet, alphabet!(simple 'x', 'y'));
+        for i in [0, 25, 26, 51, 52, 100] {
+            assert_eq!(index_for_symbol(symbol_for_index(i)), Some(i));
+        }
+    }
+}
@@ -19,7 +19,10 @@ use itertools::Itertools;
 use tracing::{debug, info, trace};
 
 use crate::{
-    active::{oracle::MealyOracle, LStar},
+    active::{
+        oracle::{DFAOracle, MealyOracle},
+        LStar,
+    },
     passive::fwpm::FWPM,
     priority_mapping::{CongruentPriorityMapping, PriorityMapping},
 };
@@ -364,24 +367,31 @@ where
     todo!()
 }
 
-pub fn actively_exchanged_words_dfa<D: DFALike>(dfa: D) -> DFASample<D> {
-    todo!()
+/// Learns `dfa` with L* and returns all membership queries that were posed on the way.
+pub fn actively_exchanged_words_dfa<D>(dfa: D) -> DFASample<D>
+where
+    D: DFALike<Alphabet = CharAlphabet> + Clone,
+{
+    let mut lstar = LStar::for_dfa(dfa.alphabet().clone(), DFAOracle::new(dfa));
+    lstar.infer();
+    lstar.queries()
 }
 
 type MealySample<D> =
     Sample<<D as TransitionSystem>::Alphabet, Vec<SymbolOf<D>>, <D as TransitionSystem>::EdgeColor>;
 
+/// Learns `mm` with L* and returns all output queries that were posed on the way, each with the
+/// last output that it produces.
 pub fn actively_exchanged_words_mealy<D>(mm: D) -> MealySample<D>
 where
-    D: Congruence,
-    EdgeColor<D>: Color,
+    D: Congruence<Alphabet = CharAlphabet>,
+    EdgeColor<D>: Color + Default,
 {
     let alphabet = mm.alphabet().clone();
     let oracle = MealyOracle::new(mm, None);
-    // let mut lstar = LStar::logged(oracle, alphabet);
-    // let learned = lstar.learn();
-    // let mut sample = MealySample::new(alphabet);
-    todo!()
+    let mut lstar = LStar::for_mealy(alphabet, oracle);
+    lstar.infer();
+    lstar.queries()
 }
 
 #[cfg(test)]
@@ -0,0 +1,286 @@
+use std::hash::Hash;
+
+use automata::{prelude::*, word::LinearWord, Map};
+use itertools::Itertools;
+
+use crate::random::Rng;
+
+use super::{ConsistencyReport, FiniteSample, OmegaSample, Sample};
+
+/// Shuffles `items` in place with the Fisher-Yates algorithm.
+fn shuffle<T>(items: &mut [T], rng: &mut Rng) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, rng.below(i + 1));
+    }
+}
+
+impl<A: Alphabet, W: LinearWord<A::Symbol> + Hash + Ord + Clone, C: Color> Sample<A, W, C> {
+    /// Returns the entries in a random order that only depends on `seed`. Entries are sorted
+    /// before shuffling, so the order does not depend on the iteration order of the sample.
+    fn shuffled(&self, seed: u64) -> Vec<(W, C)> {
+        let mut entries = self
+            .entries()
+            .map(|(w, c)| (w.clone(), c.clone()))
+            .sorted_by(|(x, _), (y, _)| x.cmp(y))
+            .collect_vec();
+        shuffle(&mut entries, &mut Rng::seeded(seed));
+        entries
+    }
+
+    fn with_entries(&self, entries: impl IntoIterator<Item = (W, C)>) -> Self {
+        let words: Map<W, C> = entries.into_iter().collect();
+        let weights = self
+            .weights
+            .iter()
+            .filter(|(w, _)| words.contains_key(*w))
+            .map(|(w, weight)| (w.clone(), *weight))
+            .collect();
+        Sample {
+            alphabet: self.alphabet.clone(),
+            words,
+            weights,
+        }
+    }
+
+    /// Randomly splits the sample into a training and a test sample, where the test sample
+    /// receives the given fraction of the words (rounded down). The split only depends on
+    /// `seed`, so it is reproducible.
+    pub fn train_test_split(&self, test_fraction: f64, seed: u64) -> (Self, Self) {
+        assert!(
+            (0.0..=1.0).contains(&test_fraction),
+            "The test fraction must lie in [0, 1]"
+        );
+        let entries = self.shuffled(seed);
+        let test_size = (entries.len() as f64 * test_fraction) as usize;
+        let (test, train) = entries.split_at(test_size);
+        (
+            self.with_entries(train.iter().cloned()),
+            self.with_entries(test.iter().cloned()),
+        )
+    }
+
+    /// Like [`Self::train_test_split`], but splits the words of each color separately, such
+    /// that the proportions of the colors are the same in both samples up to rounding.
+    pub fn stratified_split(&self, test_fraction: f64, seed: u64) -> (Self, Self) {
+        let colors = self
+            .entries()
+            .map(|(_, c)| c.clone())
+            .unique()
+            .collect_vec();
+        let (mut train, mut test) = (self.with_entries([]), self.with_entries([]));
+        for (i, color) in colors.into_iter().enumerate() {
+            let stratum = self.with_entries(
+                self.entries()
+                    .filter(|(_, c)| **c == color)
+                    .map(|(w, c)| (w.clone(), c.clone())),
+            );
+            let (stratum_train, stratum_test) =
+                stratum.train_test_split(test_fraction, seed.wrapping_add(i as u64));
+            train.append(stratum_train);
+            test.append(stratum_test);
+        }
+        (train, test)
+    }
+
+    /// Returns an iterator over the `k` folds of a cross-validation. The words are randomly
+    /// distributed into `k` parts of almost equal size, and the `i`-th item consists of the
+    /// training sample containing all parts but the `i`-th and the test sample containing the
+    /// `i`-th part.
+    pub fn k_folds(&self, k: usize, seed: u64) -> KFolds<'_, A, W, C> {
+        assert!(k >= 2, "Cross-validation needs at least two folds");
+        let parts = self
+            .shuffled(seed)
+            .into_iter()
+            .enumerate()
+            .into_group_map_by(|(i, _)| i % k);
+        KFolds {
+            sample: self,
+            parts: (0..k)
+                .map(|i| {
+                    parts
+                        .get(&i)
+                        .into_iter()
+                        .flatten()
+                        .map(|(_, entry)| entry.clone())
+                        .collect()
+                })
+                .collect(),
+            current: 0,
+        }
+    }
+}
+
+/// Iterator over the folds of a cross-validation, created by [`Sample::k_folds`]. Yields pairs
+/// of a training and a test sample.
+pub struct KFolds<'a, A: Alphabet, W: LinearWord<A::Symbol> + Hash, C: Color> {
+    sample: &'a Sample<A, W, C>,
+    parts: Vec<Vec<(W, C)>>,
+    current: usize,
+}
+
+impl<'a, A, W, C> Iterator for KFolds<'a, A, W, C>
+where
+    A: Alphabet,
+    W: LinearWord<A::Symbol> + Hash + Ord + Clone,
+    C: Color,
+{
+    type Item = (Sample<A, W, C>, Sample<A, W, C>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let test = self.parts.get(self.current)?;
+        let train = self
+            .parts
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.current)
+            .flat_map(|(_, part)| part.iter().cloned());
+        let fold = (
+            self.sample.with_entries(train),
+            self.sample.with_entries(test.iter().cloned()),
+        );
+        self.current += 1;
+        Some(fold)
+    }
+}
+
+/// The confusion matrix of a hypothesis on a sample, from which the usual quality measures
+/// are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Evaluation {
+    /// Positive words that are accepted.
+    pub true_positives: usize,
+    /// Negative words that are accepted.
+    pub false_positives: usize,
+    /// Negative words that are rejected.
+    pub true_negatives: usize,
+    /// Positive words that are rejected.
+    pub false_negatives: usize,
+}
+
+impl Evaluation {
+    fn from_report<W, Q>(
+        report: &ConsistencyReport<W, Q>,
+        positive: usize,
+        negative: usize,
+    ) -> Self {
+        let false_negatives = report.false_negatives().len();
+        let false_positives = report.false_positives().len();
+        Self {
+            true_positives: positive - false_negatives,
+            false_positives,
+            true_negatives: negative - false_positives,
+            false_negatives,
+        }
+    }
+
+    /// Returns the number of evaluated words.
+    pub fn total(&self) -> usize {
+        self.true_positives + self.false_positives + self.true_negatives + self.false_negatives
+    }
+
+    /// Returns the fraction of correctly classified words, or `1` if no word was evaluated.
+    pub fn accuracy(&self) -> f64 {
+        ratio(self.true_positives + self.true_negatives, self.total())
+    }
+
+    /// Returns the fraction of positive words among the accepted ones, or `1` if no word is
+    /// accepted.
+    pub fn precision(&self) -> f64 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+        )
+    }
+
+    /// Returns the fraction of accepted words among the positive ones, or `1` if there are no
+    /// positive words.
+    pub fn recall(&self) -> f64 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_negatives,
+        )
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        1.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+impl<A: Alphabet> FiniteSample<A> {
+    /// Evaluates the given DFA on the sample, which is usually a held-out test sample.
+    pub fn evaluate<D: DFALike<Alphabet = A>>(&self, hypothesis: &D) -> Evaluation {
+        let report = self.consistency_report(hypothesis);
+        Evaluation::from_report(
+            &report,
+            self.positive_words().count(),
+            self.negative_words().count(),
+        )
+    }
+}
+
+impl<A: Alphabet> OmegaSample<A> {
+    /// Evaluates the given DPA on the sample, which is usually a held-out test sample.
+    pub fn evaluate<D: DPALike<Alphabet = A>>(&self, hypothesis: &D) -> Evaluation {
+        let report = self.consistency_report(hypothesis);
+        Evaluation::from_report(&report, self.positive_size(), self.negative_size())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+    use itertools::Itertools;
+
+    use crate::passive::{dfa_rpni, FiniteSample};
+
+    #[test]
+    fn splitting_and_evaluation() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        // words that end with an a, in which every word up to length 3 occurs
+        let words = (0..=3)
+            .flat_map(|n| {
+                (0..n)
+                    .map(|_| ['a', 'b'])
+                    .multi_cartesian_product()
+                    .collect_vec()
+            })
+            .chain([vec![]])
+            .unique()
+            .map(|w| {
+                let c = w.last() == Some(&'a');
+                (w, c)
+            })
+            .collect_vec();
+        let sample = FiniteSample::new_finite(alphabet, words);
+        assert_eq!(sample.words.len(), 15);
+
+        let (train, test) = sample.train_test_split(0.2, 7);
+        assert_eq!((train.words.len(), test.words.len()), (12, 3));
+        assert!(test.words().all(|w| !train.contains(w)));
+        assert_eq!(sample.train_test_split(0.2, 7), (train, test));
+
+        let (train, test) = sample.stratified_split(0.5, 3);
+        assert_eq!(test.positive_words().count(), 3);
+        assert_eq!(test.negative_words().count(), 4);
+        assert_eq!(train.words.len() + test.words.len(), 15);
+
+        let folds = sample.k_folds(5, 1).collect_vec();
+        assert_eq!(folds.len(), 5);
+        for (train, test) in &folds {
+            assert_eq!(test.words.len(), 3);
+            assert_eq!(train.words.len(), 12);
+        }
+        let tested = folds.iter().flat_map(|(_, test)| test.words()).unique();
+        assert_eq!(tested.count(), 15);
+
+        let evaluation = sample.evaluate(&dfa_rpni(&sample));
+        assert_eq!(evaluation.total(), 15);
+        assert_eq!(evaluation.accuracy(), 1.0);
+        assert_eq!(evaluation.precision(), 1.0);
+        assert_eq!(evaluation.recall(), 1.0);
+    }
+}
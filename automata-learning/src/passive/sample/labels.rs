@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Set};
+
+use crate::passive::sprout::ConsistencyCheck;
+
+use super::{FiniteSample, Sample};
+
+/// The information that a labeled word carries about the language `L` that is learned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WordLabel {
+    /// The word is in `L`.
+    Member,
+    /// The word is not in `L`.
+    NonMember,
+    /// The word is a prefix of some word in `L`.
+    Prefix,
+    /// The word is a factor of some word in `L`.
+    Factor,
+}
+
+/// A sample of finite words, which may be labeled as prefixes or factors of the language in
+/// addition to members and non-members, see [`WordLabel`].
+///
+/// When used as a [`ConsistencyCheck`], the accepting classes are those that are reached by a
+/// member, as in [`crate::passive::dfa_rpni`]. A prefix must then reach a class from which an
+/// accepting class is reachable, and a factor must lead from some class to such a class. As
+/// sprout builds the congruence step by step, a label is only considered violated once every
+/// class that could still make it hold has all of its transitions.
+#[derive(Clone, Debug)]
+pub struct LabeledSample<A: Alphabet> {
+    members: FiniteSample<A, bool>,
+    prefixes: Set<Vec<A::Symbol>>,
+    factors: Set<Vec<A::Symbol>>,
+}
+
+impl<A: Alphabet> LabeledSample<A> {
+    /// Creates a labeled sample from the given words, the labels of words that occur multiple
+    /// times are combined.
+    pub fn new<I, J>(alphabet: A, words: J) -> Self
+    where
+        I: IntoIterator<Item = A::Symbol>,
+        J: IntoIterator<Item = (I, WordLabel)>,
+    {
+        let mut out = Self::from_sample(Sample::new_finite(
+            alphabet,
+            std::iter::empty::<(Vec<_>, bool)>(),
+        ));
+        for (word, label) in words {
+            out.add(word.into_iter().collect(), label);
+        }
+        out
+    }
+
+    /// Creates a labeled sample that consists of the members and non-members of `sample`.
+    pub fn from_sample(sample: FiniteSample<A, bool>) -> Self {
+        Self {
+            members: sample,
+            prefixes: Set::default(),
+            factors: Set::default(),
+        }
+    }
+
+    /// Adds `word` with the given label.
+    pub fn add(&mut self, word: Vec<A::Symbol>, label: WordLabel) {
+        match label {
+            WordLabel::Member => {
+                self.members.words.insert(word, true);
+            }
+            WordLabel::NonMember => {
+                self.members.words.insert(word, false);
+            }
+            WordLabel::Prefix => {
+                self.prefixes.insert(word);
+            }
+            WordLabel::Factor => {
+                self.factors.insert(word);
+            }
+        }
+    }
+
+    /// Returns the sample of members and non-members.
+    pub fn members(&self) -> &FiniteSample<A, bool> {
+        &self.members
+    }
+
+    /// Returns the words that are labeled as prefixes.
+    pub fn prefixes(&self) -> impl Iterator<Item = &'_ Vec<A::Symbol>> + '_ {
+        self.prefixes.iter()
+    }
+
+    /// Returns the words that are labeled as factors.
+    pub fn factors(&self) -> impl Iterator<Item = &'_ Vec<A::Symbol>> + '_ {
+        self.factors.iter()
+    }
+}
+
+/// Determines whether a class in `accepting` is reachable from `source`. Gives `None` if no such
+/// class is reachable so far, but some reachable class still misses a transition.
+fn reaches_accepting<A: Alphabet>(
+    cong: &RightCongruence<A>,
+    source: usize,
+    accepting: &Set<usize>,
+) -> Option<bool> {
+    let mut seen = Set::from_iter([source]);
+    let mut queue = VecDeque::from([source]);
+    let mut complete = true;
+    while let Some(q) = queue.pop_front() {
+        if accepting.contains(&q) {
+            return Some(true);
+        }
+        for sym in cong.alphabet().universe() {
+            match cong.successor_index(q, sym) {
+                Some(p) if seen.insert(p) => queue.push_back(p),
+                Some(_) => {}
+                None => complete = false,
+            }
+        }
+    }
+    complete.then_some(false)
+}
+
+impl<A: Alphabet> ConsistencyCheck<A> for LabeledSample<A> {
+    fn consistent(&self, cong: &RightCongruence<A>) -> bool {
+        if !self.members.consistent(cong) {
+            return false;
+        }
+        let accepting: Set<_> = self
+            .members
+            .positive_words()
+            .filter_map(|w| cong.reached_state_index(w))
+            .collect();
+        // a class that is not reached yet may still become viable
+        let viable = |q: Option<usize>| {
+            q.and_then(|q| reaches_accepting(cong, q, &accepting)) != Some(false)
+        };
+
+        let prefixes = self
+            .prefixes
+            .iter()
+            .all(|u| viable(cong.reached_state_index(u)));
+        let factors = self.factors.iter().all(|u| {
+            cong.state_indices()
+                .any(|q| viable(u.iter().try_fold(q, |p, sym| cong.successor_index(p, *sym))))
+        });
+        prefixes && factors
+    }
+
+    fn threshold(&self) -> usize {
+        let longest = self
+            .prefixes
+            .iter()
+            .chain(&self.factors)
+            .map(|w| w.len())
+            .max()
+            .unwrap_or(0);
+        self.members.max_word_len().max(longest) * 2
+    }
+
+    fn alphabet(&self) -> &A {
+        self.members.alphabet()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, RightCongruence};
+
+    use super::{LabeledSample, WordLabel};
+    use crate::passive::{dfa_rpni_labeled, sprout::ConsistencyCheck};
+
+    #[test]
+    fn prefix_and_factor_labels() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        // words starting with an a, where the class of b is a rejecting sink
+        let mut cong = RightCongruence::new(alphabet.clone());
+        let e = cong.add_state((vec![], Void));
+        let a = cong.add_state((vec!['a'], Void));
+        let b = cong.add_state((vec!['b'], Void));
+        for (source, sym, target) in [(e, 'a', a), (e, 'b', b), (a, 'a', a), (a, 'b', a)] {
+            cong.add_edge(source, sym, target, Void);
+        }
+        let members = [("a", WordLabel::Member), ("b", WordLabel::NonMember)];
+        let labeled = |extra: (&str, WordLabel)| {
+            LabeledSample::new(
+                alphabet.clone(),
+                members
+                    .into_iter()
+                    .chain([extra])
+                    .map(|(w, l)| (w.chars(), l)),
+            )
+        };
+
+        // the sink is still missing its transitions
+        assert!(labeled(("ba", WordLabel::Prefix)).consistent(&cong));
+        cong.add_edge(b, 'a', b, Void);
+        cong.add_edge(b, 'b', b, Void);
+        assert!(!labeled(("ba", WordLabel::Prefix)).consistent(&cong));
+        assert!(labeled(("ba", WordLabel::Factor)).consistent(&cong));
+        assert!(labeled(("ab", WordLabel::Prefix)).consistent(&cong));
+
+        let sample = labeled(("bb", WordLabel::Prefix));
+        // the prefixes of members are prefixes of the language as well
+        assert_eq!(sample.members().positive_prefixes().len(), 2);
+        assert_eq!(sample.members().negative_prefixes().len(), 2);
+        let dfa = dfa_rpni_labeled(&sample);
+        assert!(sample.members().consistency_report(&dfa).is_consistent());
+        assert!(["", "a", "b", "aa", "ab", "ba"]
+            .into_iter()
+            .any(|suffix| dfa.accepts(format!("bb{suffix}").as_str())));
+    }
+}
@@ -6,7 +6,7 @@ use std::{
     hash::Hash,
 };
 
-use automata::{prelude::*, word::LinearWord, Map};
+use automata::{prelude::*, word::LinearWord, Map, Set};
 use itertools::Itertools;
 use tracing::{debug, trace};
 
@@ -22,14 +22,33 @@ pub use omega::{OmegaSampleParseError, PeriodicOmegaSample};
 
 mod canonic_coloring;
 
+mod abbadingo;
+pub use abbadingo::{index_for_symbol, symbol_for_index, AbbadingoParseError};
+
 mod characterize;
 
-/// Represents a finite sample, which is a pair of positive and negative instances.
+mod text;
+pub use text::SampleFileError;
+
+mod report;
+pub use report::{ConsistencyReport, Misclassification};
+
+mod evaluation;
+pub use evaluation::{Evaluation, KFolds};
+
+mod labels;
+pub use labels::{LabeledSample, WordLabel};
+
+/// Represents a finite sample, which is a pair of positive and negative instances. Each word
+/// may carry a weight, such as the number of times it occurred in a log, which is used by the
+/// noise tolerant learners and by [`super::sprout::evidence_score`]. Words without an entry in
+/// `weights` have weight one.
 #[derive(Clone, Eq, PartialEq)]
 #[allow(missing_docs)]
 pub struct Sample<A: Alphabet, W: LinearWord<A::Symbol> + Hash, C: Color = bool> {
     pub alphabet: A,
     pub words: Map<W, C>,
+    pub weights: Map<W, usize>,
 }
 
 /// Type alias for samples over the alphabet `A`, containing finite words which are classified with color `C`,
@@ -59,32 +78,62 @@ impl<A: Alphabet, W: LinearWord<A::Symbol>> Sample<A, W, bool> {
 }
 
 impl<A: Alphabet, W: LinearWord<A::Symbol>, C: Color> Sample<A, W, C> {
-    pub fn into_joined(self, other: Sample<A, W, C>) -> Sample<A, W, C> {
-        let words = self.words.into_iter().chain(other.words).collect();
-        Sample {
-            alphabet: self.alphabet,
-            words,
-        }
+    /// Joins two samples, words that occur in both are classified as in `other` and their
+    /// weights are added up.
+    pub fn into_joined(mut self, other: Sample<A, W, C>) -> Sample<A, W, C>
+    where
+        W: Clone,
+    {
+        self.append(other);
+        self
     }
 
-    pub fn append(&mut self, other: Sample<A, W, C>) {
-        self.words.extend(other.words);
+    /// Adds the words of `other` like [`Self::into_joined`].
+    pub fn append(&mut self, other: Sample<A, W, C>)
+    where
+        W: Clone,
+    {
+        for (word, color) in other.words {
+            let weight = other.weights.get(&word).copied().unwrap_or(1);
+            self.add_weighted(word, color, weight);
+        }
     }
 
+    /// Joins two samples like [`Self::into_joined`] without consuming them.
     pub fn as_joined(&self, other: &Sample<A, W, C>) -> Sample<A, W, C>
     where
         W: Clone,
     {
-        let words = self
-            .words
-            .iter()
-            .chain(other.words.iter())
-            .map(|(w, c)| (w.clone(), c.clone()))
-            .collect();
-        Sample {
-            alphabet: self.alphabet.clone(),
-            words,
+        self.clone().into_joined(other.clone())
+    }
+
+    /// Adds `word` with the given classification, which occurred `weight` many times. If the
+    /// word is already present, its classification is replaced and the weights are added up.
+    pub fn add_weighted(&mut self, word: W, color: C, weight: usize)
+    where
+        W: Clone,
+    {
+        let total = if self.words.contains_key(&word) {
+            self.weight(&word) + weight
+        } else {
+            weight
+        };
+        if total == 1 {
+            self.weights.remove(&word);
+        } else {
+            self.weights.insert(word.clone(), total);
         }
+        self.words.insert(word, color);
+    }
+
+    /// Returns the weight of `word`, which is one unless specified otherwise.
+    pub fn weight(&self, word: &W) -> usize {
+        self.weights.get(word).copied().unwrap_or(1)
+    }
+
+    /// Returns the sum of the weights of all words in the sample.
+    pub fn total_weight(&self) -> usize {
+        self.words().map(|w| self.weight(w)).sum()
     }
 
     /// Returns a reference to the underlying alphabet.
@@ -136,13 +185,55 @@ impl<A: Alphabet, C: Color> FiniteSample<A, C> {
             .into_iter()
             .map(|(word, color)| (word.into_iter().collect(), color))
             .collect();
-        Self { alphabet, words }
+        Self {
+            alphabet,
+            words,
+            weights: Map::default(),
+        }
+    }
+
+    /// Creates a sample of finite words like [`Self::new_finite`], where each word is given
+    /// together with its weight. Words that are given multiple times are joined as in
+    /// [`Self::add_weighted`].
+    pub fn new_weighted<
+        I: IntoIterator<Item = A::Symbol>,
+        J: IntoIterator<Item = (I, C, usize)>,
+    >(
+        alphabet: A,
+        words: J,
+    ) -> Self {
+        let mut sample = Self::new_finite(alphabet, std::iter::empty::<(Vec<_>, C)>());
+        for (word, color, weight) in words {
+            sample.add_weighted(word.into_iter().collect(), color, weight);
+        }
+        sample
     }
 
     /// Returns the maximum length of any finite word in the sample. Gives back `0` if no word exists in the sample.
     pub fn max_word_len(&self) -> usize {
         self.words().map(|w| w.len()).max().unwrap_or(0)
     }
+
+    /// Returns all prefixes of the words with the given color, including the empty word and
+    /// the words themselves.
+    pub fn prefixes_with_color(&self, color: C) -> Set<Vec<A::Symbol>> {
+        self.words_with_color(color)
+            .flat_map(|w| (0..=w.len()).map(|i| w[..i].to_vec()))
+            .collect()
+    }
+}
+
+impl<A: Alphabet> FiniteSample<A, bool> {
+    /// Returns all prefixes of positive words, each of which is a prefix of the language, see
+    /// [`WordLabel::Prefix`].
+    pub fn positive_prefixes(&self) -> Set<Vec<A::Symbol>> {
+        self.prefixes_with_color(true)
+    }
+
+    /// Returns all prefixes of negative words.
+    pub fn negative_prefixes(&self) -> Set<Vec<A::Symbol>> {
+        self.prefixes_with_color(false)
+    }
 }
 
 impl<A, W, C> Debug for Sample<A, W, C>
@@ -180,10 +271,31 @@ mod tests {
     use itertools::Itertools;
     use tracing::info;
 
-    use crate::passive::Sample;
+    use crate::passive::{FiniteSample, Sample};
 
     use super::ReducedOmegaWord;
 
+    #[test]
+    fn weighted_sample() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        let sample = FiniteSample::new_weighted(
+            alphabet.clone(),
+            [("a", true, 2), ("b", false, 1), ("a", true, 3)].map(|(w, c, n)| (w.chars(), c, n)),
+        );
+        assert_eq!(sample.words.len(), 2);
+        assert_eq!(sample.weight(&vec!['a']), 5);
+        assert_eq!(sample.weight(&vec!['b']), 1);
+        assert_eq!(sample.total_weight(), 6);
+
+        let other = FiniteSample::new_finite(
+            alphabet,
+            [("b", false), ("ab", true)].map(|(w, c)| (w.chars(), c)),
+        );
+        let joined = sample.as_joined(&other);
+        assert_eq!(joined.weight(&vec!['b']), 2);
+        assert_eq!(joined.total_weight(), 8);
+    }
+
     #[test]
     fn parse_sample() {
         let sample_str = r#"omega
@@ -6,7 +6,8 @@ use tracing::{debug, trace};
 
 use crate::passive::{
     sprout::{
-        iteration_consistency_conflicts, prefix_consistency_conflicts, sprout, SeparatesIdempotents,
+        iteration_consistency_conflicts, prefix_consistency_conflicts, sprout, NoiseBudget,
+        SeparatesIdempotents, Tolerant,
     },
     ClassOmegaSample, Sample,
 };
@@ -174,6 +175,13 @@ impl<A: Alphabet> OmegaSample<A, bool> {
         sprout(prefix_consistency_conflicts(self), vec![], true)
     }
 
+    /// Computes a [`RightCongruence`] for noisy samples. Instead of prefix consistency, this only
+    /// requires that words which visit the same classes infinitely often are classified equally,
+    /// where up to `budget` words may be misclassified, see [`Tolerant`].
+    pub fn infer_right_congruence_tolerant(&self, budget: NoiseBudget) -> RightCongruence<A> {
+        sprout(Tolerant::new(self, budget), vec![], true)
+    }
+
     /// Returns the positive size, i.e. the number of positive words.
     pub fn positive_size(&self) -> usize {
         self.words_with_color(true).count()
@@ -253,7 +261,11 @@ impl<A: Alphabet, C: Color> OmegaSample<A, C> {
             .map(|(word, color)| (word.into(), color))
             .collect();
 
-        Self { alphabet, words }
+        Self {
+            alphabet,
+            words,
+            weights: Map::default(),
+        }
     }
 
     /// Splits the sample into a map of [`ClassOmegaSample`]s, one for each class of the underlying [`RightCongruence`].
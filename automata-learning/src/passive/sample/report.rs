@@ -0,0 +1,204 @@
+use automata::{prelude::*, Map};
+use tracing::debug;
+
+use super::{FiniteSample, OmegaSample};
+
+/// A sample word that is classified differently by a hypothesis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misclassification<W, Q = usize> {
+    /// The misclassified word.
+    pub word: W,
+    /// The classification of the word in the sample.
+    pub expected: bool,
+    /// The states of the hypothesis that are visited while reading the word, starting with the
+    /// initial state. For omega words, the spoke and a single iteration of the cycle are read.
+    /// The run ends early if a transition is missing.
+    pub run: Vec<Q>,
+}
+
+/// The result of checking a hypothesis against a sample, see e.g.
+/// [`FiniteSample::consistency_report`]. The misclassified words are grouped by their
+/// classification in the sample.
+#[derive(Debug, Clone)]
+pub struct ConsistencyReport<W, Q = usize> {
+    checked: usize,
+    misclassified: Map<bool, Vec<Misclassification<W, Q>>>,
+}
+
+impl<W, Q> ConsistencyReport<W, Q> {
+    fn new() -> Self {
+        Self {
+            checked: 0,
+            misclassified: Map::default(),
+        }
+    }
+
+    fn record(&mut self, word: W, expected: bool, found: bool, run: Vec<Q>) {
+        self.checked += 1;
+        if expected != found {
+            self.misclassified
+                .entry(expected)
+                .or_default()
+                .push(Misclassification {
+                    word,
+                    expected,
+                    run,
+                });
+        }
+    }
+
+    /// Returns `true` if the hypothesis classifies all words of the sample correctly.
+    pub fn is_consistent(&self) -> bool {
+        self.misclassified.is_empty()
+    }
+
+    /// Returns the number of sample words that were checked.
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Returns the number of misclassified words.
+    pub fn misclassified_count(&self) -> usize {
+        self.misclassified.values().map(Vec::len).sum()
+    }
+
+    /// Returns the misclassified words that have the classification `label` in the sample.
+    pub fn misclassified(&self, label: bool) -> &[Misclassification<W, Q>] {
+        self.misclassified
+            .get(&label)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the positive sample words that are rejected by the hypothesis.
+    pub fn false_negatives(&self) -> &[Misclassification<W, Q>] {
+        self.misclassified(true)
+    }
+
+    /// Returns the negative sample words that are accepted by the hypothesis.
+    pub fn false_positives(&self) -> &[Misclassification<W, Q>] {
+        self.misclassified(false)
+    }
+
+    /// Gives an iterator over all misclassified words.
+    pub fn iter(&self) -> impl Iterator<Item = &'_ Misclassification<W, Q>> + '_ {
+        self.misclassified.values().flatten()
+    }
+}
+
+/// Collects the states that are visited on `word` from the initial state of `ts`.
+fn run<D, S>(ts: &D, word: &[S]) -> Vec<D::StateIndex>
+where
+    D: Deterministic + Pointed,
+    D::Alphabet: Alphabet<Symbol = S>,
+    S: Symbol,
+{
+    let mut state = ts.initial();
+    let mut states = vec![state];
+    for sym in word {
+        let Some(next) = ts.successor_index(state, *sym) else {
+            break;
+        };
+        states.push(next);
+        state = next;
+    }
+    states
+}
+
+impl<A: Alphabet> FiniteSample<A> {
+    /// Runs every word of the sample through the given DFA and reports the words that it
+    /// classifies differently from the sample.
+    pub fn consistency_report<D>(
+        &self,
+        hypothesis: &D,
+    ) -> ConsistencyReport<Vec<A::Symbol>, D::StateIndex>
+    where
+        D: DFALike<Alphabet = A>,
+    {
+        let dfa = hypothesis.into_dfa();
+        let mut report = ConsistencyReport::new();
+        for (word, expected) in self.entries() {
+            report.record(
+                word.clone(),
+                *expected,
+                dfa.accepts(word),
+                run(hypothesis, word),
+            );
+        }
+        debug!(
+            "Hypothesis misclassifies {} of {} words",
+            report.misclassified_count(),
+            report.checked()
+        );
+        report
+    }
+}
+
+impl<A: Alphabet> OmegaSample<A> {
+    /// Runs every word of the sample through the given DPA and reports the words that it
+    /// classifies differently from the sample.
+    pub fn consistency_report<D>(
+        &self,
+        hypothesis: &D,
+    ) -> ConsistencyReport<ReducedOmegaWord<A::Symbol>, D::StateIndex>
+    where
+        D: DPALike<Alphabet = A>,
+    {
+        let dpa = hypothesis.into_dpa();
+        let mut report = ConsistencyReport::new();
+        for (word, expected) in self.entries() {
+            report.record(
+                word.clone(),
+                *expected,
+                dpa.accepts(word),
+                run(hypothesis, word.raw_word()),
+            );
+        }
+        debug!(
+            "Hypothesis misclassifies {} of {} words",
+            report.misclassified_count(),
+            report.checked()
+        );
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use crate::passive::FiniteSample;
+
+    #[test]
+    fn consistency_report() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        // accepts the words that contain an a
+        let mut dfa = DFA::new_for_alphabet(alphabet.clone());
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q1, 'a', q1, Void);
+        dfa.add_edge(q1, 'b', q1, Void);
+
+        let sample = FiniteSample::new_finite(
+            alphabet,
+            [("ba", true), ("b", false), ("bb", true), ("ab", false)].map(|(w, c)| (w.chars(), c)),
+        );
+        let report = sample.consistency_report(&dfa);
+        assert!(!report.is_consistent());
+        assert_eq!(report.checked(), 4);
+        assert_eq!(report.misclassified_count(), 2);
+
+        let [negative] = report.false_negatives() else {
+            panic!("Expected exactly one false negative");
+        };
+        assert_eq!(negative.word, vec!['b', 'b']);
+        assert_eq!(negative.run, vec![q0, q0, q0]);
+        let [positive] = report.false_positives() else {
+            panic!("Expected exactly one false positive");
+        };
+        assert_eq!(positive.word, vec!['a', 'b']);
+        assert_eq!(positive.run, vec![q0, q1, q1]);
+    }
+}
@@ -62,6 +62,7 @@ impl<'a, A: Alphabet, C: Color> ClassOmegaSample<'a, A, C> {
             sample: Sample {
                 alphabet,
                 words: Map::default(),
+                weights: Map::default(),
             },
         }
     }
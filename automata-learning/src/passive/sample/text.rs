@@ -0,0 +1,360 @@
+//! A simple line-based text format for finite and omega samples over characters. Empty lines
+//! and lines starting with `#` are ignored. The first line is the header `finite` or `omega`,
+//! optionally followed by a line `alphabet: a, b, c`. If the alphabet is not given, it is
+//! inferred from the symbols that occur in the words. Every further line contains one
+//! classified word, where the label is `1` or `+` for positive and `0` or `-` for negative
+//! words:
+//! - finite samples: `<word> <label>`, e.g. `abba 1`,
+//! - omega samples: `<spoke> <period> <label>`, e.g. `ab ba 0` for `ab(ba)^ω`.
+//!
+//! Each symbol of a word is a single character and the empty word is written as `ε`. The
+//! period of an omega word must not be empty.
+//!
+//! ```text
+//! # words that end with an a
+//! finite
+//! alphabet: a, b
+//! ε 0
+//! ba 1
+//! ab 0
+//! ```
+
+use std::path::Path;
+
+use automata::{prelude::*, Map, Set};
+use itertools::Itertools;
+use tracing::debug;
+
+use super::{FiniteSample, OmegaSample, Sample};
+
+/// The representation of the empty word.
+const EPSILON: &str = "ε";
+
+/// Abstracts the types of errors that can occur when reading or writing a sample in the text
+/// format.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum SampleFileError {
+    MissingHeader,
+    WrongKind { expected: String, found: String },
+    MalformedAlphabet(usize),
+    MalformedLine(usize),
+    MalformedLabel(usize, String),
+    SymbolNotInAlphabet(usize, char),
+    EmptyPeriod(usize),
+    Inconsistent(usize),
+    Io(String),
+}
+
+impl std::fmt::Display for SampleFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SampleFileError::MissingHeader => write!(f, "Missing header"),
+            SampleFileError::WrongKind { expected, found } => {
+                write!(f, "Expected a {expected} sample but found a {found} sample")
+            }
+            SampleFileError::MalformedAlphabet(line) => {
+                write!(f, "Malformed alphabet in line {line}")
+            }
+            SampleFileError::MalformedLine(line) => write!(f, "Malformed line {line}"),
+            SampleFileError::MalformedLabel(line, label) => {
+                write!(f, "Malformed label {label} in line {line}")
+            }
+            SampleFileError::SymbolNotInAlphabet(line, sym) => {
+                write!(f, "Symbol {sym} in line {line} is not in the alphabet")
+            }
+            SampleFileError::EmptyPeriod(line) => write!(f, "Empty period in line {line}"),
+            SampleFileError::Inconsistent(line) => write!(
+                f,
+                "Inconsistent sample, the word in line {line} is both positive and negative"
+            ),
+            SampleFileError::Io(err) => write!(f, "Could not access file: {err}"),
+        }
+    }
+}
+
+fn parse_word(token: &str) -> Vec<char> {
+    if token == EPSILON {
+        vec![]
+    } else {
+        token.chars().collect()
+    }
+}
+
+fn write_word<'a, I: IntoIterator<Item = &'a char>>(word: I) -> String {
+    let word: String = word.into_iter().collect();
+    if word.is_empty() {
+        EPSILON.to_string()
+    } else {
+        word
+    }
+}
+
+fn parse_label(line: usize, token: &str) -> Result<bool, SampleFileError> {
+    match token {
+        "1" | "+" => Ok(true),
+        "0" | "-" => Ok(false),
+        other => Err(SampleFileError::MalformedLabel(line, other.to_string())),
+    }
+}
+
+/// Parses the header, the optional alphabet and all remaining lines, which are split into
+/// whitespace separated fields and handed to `parse_line`. It returns the entry, the symbols
+/// that are used and the label, the symbols are used to infer the alphabet.
+#[allow(clippy::type_complexity)]
+fn parse<T, F>(
+    input: &str,
+    kind: &str,
+    mut parse_line: F,
+) -> Result<(CharAlphabet, Vec<(T, bool)>), SampleFileError>
+where
+    F: FnMut(usize, &[&str]) -> Result<(T, Vec<char>, bool), SampleFileError>,
+{
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .peekable();
+
+    let (_, header) = lines.next().ok_or(SampleFileError::MissingHeader)?;
+    match header {
+        "finite" | "omega" if header != kind => {
+            return Err(SampleFileError::WrongKind {
+                expected: kind.to_string(),
+                found: header.to_string(),
+            })
+        }
+        "finite" | "omega" => {}
+        _ => return Err(SampleFileError::MissingHeader),
+    }
+
+    let mut declared: Option<Set<char>> = None;
+    if let Some((number, line)) = lines.next_if(|(_, line)| line.starts_with("alphabet")) {
+        let (_, symbols) = line
+            .split_once(':')
+            .ok_or(SampleFileError::MalformedAlphabet(number))?;
+        let symbols = symbols
+            .split(',')
+            .map(|sym| sym.trim())
+            .filter(|sym| !sym.is_empty())
+            .map(|sym| {
+                let mut chars = sym.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(SampleFileError::MalformedAlphabet(number)),
+                }
+            })
+            .collect::<Result<Set<_>, _>>()?;
+        declared = Some(symbols);
+    }
+
+    let mut symbols = declared.clone().unwrap_or_default();
+    let mut entries = vec![];
+    for (number, line) in lines {
+        let fields = line.split_whitespace().collect_vec();
+        let (word, used, label) = parse_line(number, &fields)?;
+        for sym in used {
+            match &declared {
+                Some(declared) if !declared.contains(&sym) => {
+                    return Err(SampleFileError::SymbolNotInAlphabet(number, sym))
+                }
+                _ => {
+                    symbols.insert(sym);
+                }
+            }
+        }
+        entries.push((word, label));
+    }
+    debug!(
+        "Parsed {kind} sample with {} words over {} symbols",
+        entries.len(),
+        symbols.len()
+    );
+    Ok((
+        CharAlphabet::from_iter(symbols.into_iter().sorted()),
+        entries,
+    ))
+}
+
+/// Inserts the entries into a map, fails if a word occurs with both labels.
+fn collect_words<W: Eq + std::hash::Hash>(
+    entries: Vec<((usize, W), bool)>,
+) -> Result<Map<W, bool>, SampleFileError> {
+    let mut words = Map::default();
+    for ((number, word), label) in entries {
+        if let Some(old) = words.insert(word, label) {
+            if old != label {
+                return Err(SampleFileError::Inconsistent(number));
+            }
+        }
+    }
+    Ok(words)
+}
+
+fn read(path: &Path) -> Result<String, SampleFileError> {
+    std::fs::read_to_string(path).map_err(|err| SampleFileError::Io(err.to_string()))
+}
+
+fn write(path: &Path, contents: String) -> Result<(), SampleFileError> {
+    std::fs::write(path, contents).map_err(|err| SampleFileError::Io(err.to_string()))
+}
+
+impl FiniteSample<CharAlphabet, bool> {
+    /// Parses a finite sample in the text format, which starts with the header `finite` and
+    /// an optional line `alphabet: a, b` and contains a line `<word> <label>` for each word.
+    /// The label is `1` or `+` for positive and `0` or `-` for negative words, the empty word
+    /// is written as `ε`. Without an alphabet line, the alphabet is inferred from the words.
+    pub fn from_text(input: &str) -> Result<Self, SampleFileError> {
+        let (alphabet, entries) = parse(input, "finite", |number, fields| match fields {
+            [word, label] => {
+                let word = parse_word(word);
+                Ok(((number, word.clone()), word, parse_label(number, label)?))
+            }
+            _ => Err(SampleFileError::MalformedLine(number)),
+        })?;
+        Ok(Sample {
+            alphabet,
+            words: collect_words(entries)?,
+            weights: Map::default(),
+        })
+    }
+
+    /// Writes the sample in the text format, words are ordered by length and then
+    /// lexicographically.
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "finite\nalphabet: {}\n",
+            self.alphabet.universe().join(", ")
+        );
+        for (word, label) in self
+            .entries()
+            .sorted_by(|(x, _), (y, _)| x.len().cmp(&y.len()).then(x.cmp(y)))
+        {
+            out.push_str(&format!("{} {}\n", write_word(word), u8::from(*label)));
+        }
+        out
+    }
+
+    /// Reads a finite sample in the text format from the file at `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SampleFileError> {
+        Self::from_text(&read(path.as_ref())?)
+    }
+
+    /// Writes the sample in the text format to the file at `path`.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), SampleFileError> {
+        write(path.as_ref(), self.to_text())
+    }
+}
+
+impl OmegaSample<CharAlphabet, bool> {
+    /// Parses an omega sample in the text format, which is the same as for finite samples,
+    /// see [`FiniteSample::from_text`], except that the header is `omega` and each word is
+    /// given as `<spoke> <period> <label>`. The period must not be empty.
+    pub fn from_text(input: &str) -> Result<Self, SampleFileError> {
+        let (alphabet, entries) = parse(input, "omega", |number, fields| match fields {
+            [spoke, period, label] => {
+                let (spoke, period) = (parse_word(spoke), parse_word(period));
+                if period.is_empty() {
+                    return Err(SampleFileError::EmptyPeriod(number));
+                }
+                let used = spoke.iter().chain(&period).cloned().collect();
+                let word = upw!(
+                    spoke.iter().collect::<String>().as_str(),
+                    period.iter().collect::<String>().as_str()
+                );
+                Ok(((number, word), used, parse_label(number, label)?))
+            }
+            _ => Err(SampleFileError::MalformedLine(number)),
+        })?;
+        Ok(Sample {
+            alphabet,
+            words: collect_words(entries)?,
+            weights: Map::default(),
+        })
+    }
+
+    /// Writes the sample in the text format, words are ordered by their spoke and period.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("omega\nalphabet: {}\n", self.alphabet.universe().join(", "));
+        for (spoke, period, label) in self
+            .entries()
+            .map(|(w, label)| {
+                let raw = w.raw_word();
+                let (spoke, period) = raw.split_at(w.loop_index());
+                (write_word(spoke), write_word(period), *label)
+            })
+            .sorted()
+        {
+            out.push_str(&format!("{spoke} {period} {}\n", u8::from(label)));
+        }
+        out
+    }
+
+    /// Reads an omega sample in the text format from the file at `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SampleFileError> {
+        Self::from_text(&read(path.as_ref())?)
+    }
+
+    /// Writes the sample in the text format to the file at `path`.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), SampleFileError> {
+        write(path.as_ref(), self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::SampleFileError;
+    use crate::passive::{FiniteSample, OmegaSample};
+
+    #[test]
+    fn text_format() {
+        let finite = FiniteSample::from_text(
+            "# words that end with an a
+            finite
+            ε 0
+            ba +
+            ab 0",
+        )
+        .unwrap();
+        assert_eq!(finite.alphabet, alphabet!(simple 'a', 'b'));
+        assert_eq!(finite.classify(&vec!['b', 'a']), Some(true));
+        assert_eq!(finite.classify(&vec![]), Some(false));
+        assert_eq!(FiniteSample::from_text(&finite.to_text()).unwrap(), finite);
+
+        let omega = OmegaSample::from_text(
+            "omega
+            alphabet: a, b, c
+            ε a 1
+            ab ba 0",
+        )
+        .unwrap();
+        assert_eq!(omega.alphabet, alphabet!(simple 'a', 'b', 'c'));
+        assert_eq!(omega.classify(&upw!("a")), Some(true));
+        assert_eq!(omega.classify(&upw!("ab", "ba")), Some(false));
+        assert_eq!(OmegaSample::from_text(&omega.to_text()).unwrap(), omega);
+
+        let path = std::env::temp_dir().join("lama_sample_text_format.txt");
+        omega.to_file(&path).unwrap();
+        assert_eq!(OmegaSample::from_file(&path).unwrap(), omega);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            FiniteSample::from_text("omega\nε a 1"),
+            Err(SampleFileError::WrongKind {
+                expected: "finite".to_string(),
+                found: "omega".to_string()
+            })
+        );
+        assert_eq!(
+            OmegaSample::from_text("omega\nalphabet: a\nb a 1"),
+            Err(SampleFileError::SymbolNotInAlphabet(3, 'b'))
+        );
+        assert_eq!(
+            FiniteSample::from_text("finite\na 1\na 0"),
+            Err(SampleFileError::Inconsistent(3))
+        );
+    }
+}
@@ -0,0 +1,451 @@
+//! Exact identification of a minimal DFA that is consistent with a finite sample, following
+//! the SAT encoding of Heule and Verwer, see
+//! [this paper](https://doi.org/10.1007/978-3-642-15488-1_7). The question whether a DFA with
+//! `k` states exists is encoded as a graph coloring problem on the augmented prefix tree
+//! acceptor (APTA) of the sample, and `k` is increased until the formula becomes satisfiable.
+
+use std::collections::VecDeque;
+
+use automata::{prelude::*, Map};
+use itertools::Itertools;
+use tracing::{debug, trace};
+
+use super::FiniteSample;
+
+/// A formula in conjunctive normal form. Variables are numbered from `1` and literals are
+/// given in the DIMACS convention, i.e. `-v` is the negation of variable `v`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cnf {
+    variables: usize,
+    clauses: Vec<Vec<i32>>,
+}
+
+impl Cnf {
+    /// Creates an empty formula, which is trivially satisfiable.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocates a fresh variable and returns it.
+    pub fn new_var(&mut self) -> i32 {
+        self.variables += 1;
+        self.variables as i32
+    }
+
+    /// Adds a clause, i.e. a disjunction of literals.
+    pub fn add_clause<I: IntoIterator<Item = i32>>(&mut self, clause: I) {
+        let clause = clause.into_iter().collect_vec();
+        debug_assert!(clause
+            .iter()
+            .all(|lit| *lit != 0 && lit.unsigned_abs() as usize <= self.variables));
+        self.clauses.push(clause);
+    }
+
+    /// Returns the number of variables.
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
+
+    /// Returns the clauses.
+    pub fn clauses(&self) -> &[Vec<i32>] {
+        &self.clauses
+    }
+
+    /// Writes the formula in the DIMACS format, which is understood by all common SAT solvers.
+    pub fn to_dimacs(&self) -> String {
+        let mut out = format!("p cnf {} {}\n", self.variables, self.clauses.len());
+        for clause in &self.clauses {
+            out.push_str(&clause.iter().map(|lit| lit.to_string()).join(" "));
+            out.push_str(" 0\n");
+        }
+        out
+    }
+}
+
+/// A backend that decides satisfiability of a [`Cnf`].
+pub trait SatSolver {
+    /// Returns a satisfying assignment if the formula is satisfiable, where the value of
+    /// variable `v` is stored at position `v - 1`.
+    fn solve(&mut self, formula: &Cnf) -> Option<Vec<bool>>;
+}
+
+/// A simple solver based on the DPLL procedure with unit propagation. It needs no further
+/// dependencies, but is only suitable for small samples.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dpll;
+
+impl Dpll {
+    fn value(assignment: &[Option<bool>], lit: i32) -> Option<bool> {
+        assignment[lit.unsigned_abs() as usize - 1].map(|v| v == (lit > 0))
+    }
+
+    /// Propagates unit clauses, assigned variables are pushed onto `trail`. Returns `false`
+    /// if a clause is falsified.
+    fn propagate(
+        clauses: &[Vec<i32>],
+        assignment: &mut [Option<bool>],
+        trail: &mut Vec<usize>,
+    ) -> bool {
+        loop {
+            let mut changed = false;
+            for clause in clauses {
+                let mut unassigned = None;
+                let mut open = 0;
+                let mut satisfied = false;
+                for lit in clause {
+                    match Self::value(assignment, *lit) {
+                        Some(true) => {
+                            satisfied = true;
+                            break;
+                        }
+                        Some(false) => {}
+                        None => {
+                            open += 1;
+                            unassigned = Some(*lit);
+                        }
+                    }
+                }
+                if satisfied {
+                    continue;
+                }
+                match (open, unassigned) {
+                    (0, _) => return false,
+                    (1, Some(lit)) => {
+                        let var = lit.unsigned_abs() as usize - 1;
+                        assignment[var] = Some(lit > 0);
+                        trail.push(var);
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+            if !changed {
+                return true;
+            }
+        }
+    }
+
+    fn search(clauses: &[Vec<i32>], assignment: &mut [Option<bool>]) -> bool {
+        let mut trail = vec![];
+        if Self::propagate(clauses, assignment, &mut trail) {
+            let Some(var) = assignment.iter().position(|v| v.is_none()) else {
+                return true;
+            };
+            for value in [true, false] {
+                assignment[var] = Some(value);
+                if Self::search(clauses, assignment) {
+                    return true;
+                }
+            }
+            assignment[var] = None;
+        }
+        for var in trail {
+            assignment[var] = None;
+        }
+        false
+    }
+}
+
+impl SatSolver for Dpll {
+    fn solve(&mut self, formula: &Cnf) -> Option<Vec<bool>> {
+        let mut assignment = vec![None; formula.variables()];
+        Self::search(formula.clauses(), &mut assignment)
+            .then(|| assignment.into_iter().map(|v| v.unwrap_or(false)).collect())
+    }
+}
+
+/// A backend using the [varisat](https://crates.io/crates/varisat) CDCL solver.
+#[cfg(feature = "varisat")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Varisat;
+
+#[cfg(feature = "varisat")]
+impl SatSolver for Varisat {
+    fn solve(&mut self, formula: &Cnf) -> Option<Vec<bool>> {
+        use varisat::ExtendFormula;
+
+        let mut solver = varisat::Solver::new();
+        for clause in formula.clauses() {
+            let lits = clause
+                .iter()
+                .map(|lit| varisat::Lit::from_dimacs(*lit as isize))
+                .collect_vec();
+            solver.add_clause(&lits);
+        }
+        if !solver.solve().expect("Solver must not fail") {
+            return None;
+        }
+        let mut assignment = vec![false; formula.variables()];
+        for lit in solver
+            .model()
+            .expect("Satisfiable formula must have a model")
+        {
+            if lit.index() < assignment.len() {
+                assignment[lit.index()] = lit.is_positive();
+            }
+        }
+        Some(assignment)
+    }
+}
+
+/// Witnesses that no DFA with a certain number of states is consistent with the sample.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnsatCertificate<S> {
+    /// Words that must pairwise lead to different states, since for each pair some suffix
+    /// leads to a positive and a negative sample word. There are more such words than states.
+    Clique(Vec<Vec<S>>),
+    /// The formula that the solver found to be unsatisfiable, which can be checked
+    /// independently with any other solver.
+    Formula(Cnf),
+}
+
+/// The result of [`sat_identify`].
+#[derive(Clone)]
+pub struct SatIdentification<A: Alphabet> {
+    /// A consistent DFA with the least possible number of states.
+    pub dfa: DFA<A>,
+    /// For each smaller number of states, a certificate that no consistent DFA exists.
+    pub refuted: Vec<(usize, UnsatCertificate<A::Symbol>)>,
+}
+
+/// The augmented prefix tree acceptor of a sample.
+struct Apta<S> {
+    children: Vec<Vec<(S, usize)>>,
+    label: Vec<Option<bool>>,
+    access: Vec<Vec<S>>,
+}
+
+impl<S: Symbol> Apta<S> {
+    fn new<'a, I: IntoIterator<Item = (&'a Vec<S>, bool)>>(words: I) -> Self {
+        let mut apta = Self {
+            children: vec![vec![]],
+            label: vec![None],
+            access: vec![vec![]],
+        };
+        for (word, label) in words {
+            let mut node = 0;
+            for sym in word {
+                node = match apta.children[node].iter().find(|(s, _)| s == sym) {
+                    Some((_, child)) => *child,
+                    None => {
+                        let child = apta.label.len();
+                        let mut access = apta.access[node].clone();
+                        access.push(*sym);
+                        apta.children.push(vec![]);
+                        apta.label.push(None);
+                        apta.access.push(access);
+                        apta.children[node].push((*sym, child));
+                        child
+                    }
+                };
+            }
+            apta.label[node] = Some(label);
+        }
+        apta
+    }
+
+    fn size(&self) -> usize {
+        self.label.len()
+    }
+
+    /// Returns `true` if the two nodes can not be merged, since reading the same suffix leads
+    /// to nodes with different labels.
+    fn conflicting(&self, v: usize, w: usize) -> bool {
+        if matches!((self.label[v], self.label[w]), (Some(x), Some(y)) if x != y) {
+            return true;
+        }
+        self.children[v].iter().any(|(sym, c)| {
+            self.children[w]
+                .iter()
+                .find(|(s, _)| s == sym)
+                .is_some_and(|(_, d)| self.conflicting(*c, *d))
+        })
+    }
+
+    /// Greedily computes a set of pairwise conflicting nodes, which are used for symmetry
+    /// breaking.
+    fn clique(&self) -> Vec<usize> {
+        let mut clique: Vec<usize> = vec![];
+        for v in 0..self.size() {
+            if clique.iter().all(|w| self.conflicting(v, *w)) {
+                clique.push(v);
+            }
+        }
+        clique
+    }
+}
+
+/// Encodes the existence of a consistent DFA with `k` states, returns the formula together
+/// with the functions that give the variables `x(v, i)` (node `v` has color `i`), `y(a, i, j)`
+/// (color `i` moves to color `j` on the `a`-th symbol) and `z(i)` (color `i` is accepting).
+fn encode<S: Symbol>(
+    apta: &Apta<S>,
+    symbols: &[S],
+    clique: &[usize],
+    k: usize,
+) -> (
+    Cnf,
+    impl Fn(usize, usize) -> i32,
+    impl Fn(usize, usize, usize) -> i32,
+    impl Fn(usize) -> i32,
+) {
+    let n = apta.size();
+    let m = symbols.len();
+    let mut cnf = Cnf::new();
+    for _ in 0..(n * k + m * k * k + k) {
+        cnf.new_var();
+    }
+    let x = move |v: usize, i: usize| (1 + v * k + i) as i32;
+    let y = move |a: usize, i: usize, j: usize| (1 + n * k + (a * k + i) * k + j) as i32;
+    let z = move |i: usize| (1 + n * k + m * k * k + i) as i32;
+
+    // symmetry breaking, the nodes of the clique get fixed, distinct colors
+    for (i, v) in clique.iter().enumerate() {
+        cnf.add_clause([x(*v, i)]);
+    }
+    for v in 0..n {
+        cnf.add_clause((0..k).map(|i| x(v, i)));
+        for (i, j) in (0..k).tuple_combinations() {
+            cnf.add_clause([-x(v, i), -x(v, j)]);
+        }
+        if let Some(label) = apta.label[v] {
+            for i in 0..k {
+                cnf.add_clause([-x(v, i), if label { z(i) } else { -z(i) }]);
+            }
+        }
+        for (sym, w) in &apta.children[v] {
+            let a = symbols
+                .iter()
+                .position(|s| s == sym)
+                .expect("Symbol must be in the alphabet");
+            for i in 0..k {
+                for j in 0..k {
+                    cnf.add_clause([-x(v, i), -x(*w, j), y(a, i, j)]);
+                    cnf.add_clause([-y(a, i, j), -x(v, i), x(*w, j)]);
+                }
+            }
+        }
+    }
+    for a in 0..m {
+        for i in 0..k {
+            cnf.add_clause((0..k).map(|j| y(a, i, j)));
+            for (j, h) in (0..k).tuple_combinations() {
+                cnf.add_clause([-y(a, i, j), -y(a, i, h)]);
+            }
+        }
+    }
+    (cnf, x, y, z)
+}
+
+/// Computes a DFA with the least number of states that is consistent with the sample, i.e.
+/// it accepts all positive and rejects all negative words. Starting from one state, the
+/// number of states is increased until `solver` finds the encoding satisfiable, which happens
+/// at the latest when there are as many states as the prefix tree of the sample has nodes.
+/// For every number of states that is too small, a certificate is returned.
+pub fn sat_identify<A, S>(sample: &FiniteSample<A, bool>, solver: &mut S) -> SatIdentification<A>
+where
+    A: Alphabet,
+    S: SatSolver,
+{
+    let apta = Apta::new(sample.entries().map(|(w, c)| (w, *c)));
+    let symbols = sample.alphabet().universe().collect_vec();
+    let clique = apta.clique();
+    debug!(
+        "Prefix tree has {} nodes, symmetry breaking clique has size {}",
+        apta.size(),
+        clique.len()
+    );
+
+    let mut refuted = vec![];
+    for k in 1.. {
+        if k < clique.len() {
+            let words = clique.iter().map(|v| apta.access[*v].clone()).collect();
+            refuted.push((k, UnsatCertificate::Clique(words)));
+            continue;
+        }
+        let (cnf, x, y, z) = encode(&apta, &symbols, &clique, k);
+        trace!(
+            "Encoding for {k} states has {} variables and {} clauses",
+            cnf.variables(),
+            cnf.clauses().len()
+        );
+        let Some(model) = solver.solve(&cnf) else {
+            refuted.push((k, UnsatCertificate::Formula(cnf)));
+            continue;
+        };
+        let holds = |var: i32| model[var as usize - 1];
+
+        // the states of the DFA are the colors that are reachable from the color of the root
+        let root = (0..k)
+            .find(|i| holds(x(0, *i)))
+            .expect("Root must be colored");
+        let mut dfa = DFA::new_for_alphabet(sample.alphabet().clone());
+        let mut index: Map<usize, usize> = Map::default();
+        index.insert(root, dfa.add_state(holds(z(root))));
+        let mut queue = VecDeque::from([root]);
+        while let Some(i) = queue.pop_front() {
+            for (a, sym) in symbols.iter().enumerate() {
+                let j = (0..k)
+                    .find(|j| holds(y(a, i, *j)))
+                    .expect("Every color must have a successor");
+                if !index.contains_key(&j) {
+                    index.insert(j, dfa.add_state(holds(z(j))));
+                    queue.push_back(j);
+                }
+                dfa.add_edge(index[&i], A::expression(*sym), index[&j], Void);
+            }
+        }
+        debug!("Found consistent DFA with {} states", dfa.size());
+        return SatIdentification { dfa, refuted };
+    }
+    unreachable!("The prefix tree itself is a consistent DFA")
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{sat_identify, Cnf, Dpll, SatSolver, UnsatCertificate};
+    use crate::passive::FiniteSample;
+
+    #[test]
+    fn dpll() {
+        let mut cnf = Cnf::new();
+        let (a, b) = (cnf.new_var(), cnf.new_var());
+        cnf.add_clause([a, b]);
+        cnf.add_clause([-a, b]);
+        assert_eq!(Dpll.solve(&cnf), Some(vec![true, true]));
+        cnf.add_clause([-b]);
+        assert_eq!(Dpll.solve(&cnf), None);
+        assert!(cnf.to_dimacs().starts_with("p cnf 2 3\n"));
+    }
+
+    #[test]
+    fn minimal_consistent_dfa() {
+        // words that end with an a
+        let sample = FiniteSample::new_finite(
+            alphabet!(simple 'a', 'b'),
+            [
+                ("a", true),
+                ("ba", true),
+                ("aa", true),
+                ("bba", true),
+                ("aba", true),
+                ("", false),
+                ("b", false),
+                ("ab", false),
+                ("bb", false),
+                ("abb", false),
+                ("bab", false),
+            ]
+            .map(|(w, c)| (w.chars(), c)),
+        );
+        let result = sat_identify(&sample, &mut Dpll);
+        assert_eq!(result.dfa.size(), 2);
+        assert!(sample.consistency_report(&result.dfa).is_consistent());
+        assert!(matches!(
+            result.refuted.as_slice(),
+            [(1, UnsatCertificate::Clique(words))] if words.len() == 2
+        ));
+    }
+}
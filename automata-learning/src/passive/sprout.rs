@@ -1,4 +1,5 @@
 use std::{
+    cell::RefCell,
     collections::{BTreeSet, VecDeque},
     fmt::Display,
 };
@@ -8,8 +9,12 @@ use itertools::Itertools;
 use tracing::trace;
 
 use crate::{
+    analysis::Recurrence,
+    budget::{Budget, BudgetExceeded, Exhausted},
+    congruence::{ClassNavigation, SproutableExt},
+    observer::{LearnerEvent, LearnerObserver, TracingObserver},
     passive::{ClassOmegaSample, FiniteSample, OmegaSample, Sample, SplitOmegaSample},
-    prefixtree::prefix_tree,
+    prefixtree::{dawg, finite_prefix_tree, prefix_tree},
 };
 
 use owo_colors::OwoColorize;
@@ -113,6 +118,25 @@ impl<A: Alphabet> std::fmt::Debug for ConflictRelation<A> {
 }
 
 impl<A: Alphabet> ConflictRelation<A> {
+    /// Creates a conflict relation from two congruences and pairs of their classes that must not
+    /// be reached on the same class of a consistent congruence. This allows building conflict
+    /// relations other than the ones for prefix and iteration consistency.
+    pub fn new(
+        left: RightCongruence<A>,
+        right: RightCongruence<A>,
+        conflicts: Set<(usize, usize)>,
+    ) -> Self {
+        assert_eq!(
+            left.alphabet().universe().collect_vec(),
+            right.alphabet().universe().collect_vec(),
+            "Both congruences must use the same alphabet"
+        );
+        Self {
+            dfas: [left, right],
+            conflicts,
+        }
+    }
+
     /// Returns a reference to the underlying alphabet of one of the DFAs. We assure that both DFAs use the same
     /// alphabet.
     pub fn alphabet(&self) -> &A {
@@ -217,18 +241,7 @@ pub fn prefix_consistency_conflicts<A: Alphabet, S: std::borrow::Borrow<OmegaSam
 
     let dfa = (&left_pta).ts_product(&right_pta);
 
-    let sccs = dfa.sccs();
-    let states_with_infinite_run: Vec<(usize, usize)> = sccs
-        .iter()
-        .filter_map(|scc| {
-            if !scc.is_transient() {
-                Some(scc.clone().into_iter().map(Into::into))
-            } else {
-                None
-            }
-        })
-        .flatten()
-        .collect();
+    let states_with_infinite_run = dfa.recurrent_states();
 
     let mut conflicts = Set::default();
     for ProductIndex(l, r) in dfa.state_indices() {
@@ -237,7 +250,7 @@ pub fn prefix_consistency_conflicts<A: Alphabet, S: std::borrow::Borrow<OmegaSam
             .collect_vec();
         if reachable
             .iter()
-            .any(|ProductIndex(p, q)| states_with_infinite_run.contains(&(*p, *q)))
+            .any(|q| states_with_infinite_run.contains(q))
         {
             conflicts.insert((l, r));
         }
@@ -249,6 +262,54 @@ pub fn prefix_consistency_conflicts<A: Alphabet, S: std::borrow::Borrow<OmegaSam
     }
 }
 
+/// Determines how the positive and negative words of a finite sample are represented in the
+/// conflict relation computed by [`finite_consistency_conflicts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PrefixRepresentation {
+    /// The prefix tree acceptor, which has one state for each prefix of a word.
+    #[default]
+    Tree,
+    /// The minimal acyclic DFA, which also shares common suffixes and is therefore much smaller
+    /// for large samples.
+    Dawg,
+}
+
+/// Computes a conflict relation encoding consistency with a finite sample, i.e. no class may be
+/// reached by a positive and a negative word. The positive and negative words are represented
+/// by acyclic DFAs as determined by `representation`, and all pairs of their accepting states
+/// are in conflict.
+pub fn finite_consistency_conflicts<A: Alphabet>(
+    sample: &FiniteSample<A, bool>,
+    representation: PrefixRepresentation,
+) -> ConflictRelation<A> {
+    let build = |words: Vec<Vec<A::Symbol>>| match representation {
+        PrefixRepresentation::Tree => finite_prefix_tree(sample.alphabet.clone(), words),
+        PrefixRepresentation::Dawg => dawg(sample.alphabet.clone(), words),
+    };
+    let left_dfa = build(sample.positive_words().cloned().collect());
+    let right_dfa = build(sample.negative_words().cloned().collect());
+    trace!(
+        "Representing the sample with automata of sizes {} and {}",
+        left_dfa.size(),
+        right_dfa.size()
+    );
+
+    let conflicts = left_dfa
+        .accepting_states()
+        .cartesian_product(right_dfa.accepting_states().collect_vec())
+        .collect_vec();
+    let (left, left_map) = left_dfa.build_right_congruence();
+    let (right, right_map) = right_dfa.build_right_congruence();
+
+    ConflictRelation {
+        dfas: [left, right],
+        conflicts: conflicts
+            .into_iter()
+            .map(|(l, r)| (left_map[&l], right_map[&r]))
+            .collect(),
+    }
+}
+
 impl<A: Alphabet> ConsistencyCheck<A> for () {
     fn consistent(&self, cong: &RightCongruence<A>) -> bool {
         true
@@ -290,74 +351,594 @@ impl<'a, A: Alphabet> ConsistencyCheck<A> for SeparatesIdempotents<'a, A> {
     }
 }
 
-/// Runs the omega-sprout algorithm on a given conflict relation.
-pub fn sprout<A, C>(
-    conflicts: C,
-    additional_constraints: Vec<Box<dyn ConsistencyCheck<A>>>,
-    allow_transitions_into_epsilon: bool,
-) -> RightCongruence<A>
-where
-    A: Alphabet,
-    C: ConsistencyCheck<A>,
-{
-    let mut cong = RightCongruence::new(conflicts.alphabet().clone());
-    let initial = cong.add_state((vec![], Void));
-    let threshold = conflicts.threshold();
-
-    // We maintain a set of missing transitions and go through them in order of creation for the states and in order
-    // give by alphabet for the symbols for one state (this amouts to BFS).
-    let mut queue: VecDeque<_> = conflicts
-        .alphabet()
-        .universe()
-        .map(|sym| (initial, sym))
-        .collect();
-    'outer: while let Some((source, sym)) = queue.pop_front() {
+/// Limits the number of classes, a congruence with more than `max` classes is inconsistent.
+/// Used as an additional constraint, exceeding the limit makes [`sprout_with`] return
+/// [`SproutError::ConstraintViolated`], while a backtracking search first tries to stay
+/// within the limit by choosing other targets.
+#[derive(Clone, Debug)]
+pub struct MaxClasses<A: Alphabet> {
+    alphabet: A,
+    max: usize,
+}
+
+impl<A: Alphabet> MaxClasses<A> {
+    /// Creates a new instance of the constraint.
+    pub fn new(alphabet: A, max: usize) -> Self {
+        Self { alphabet, max }
+    }
+}
+
+impl<A: Alphabet> ConsistencyCheck<A> for MaxClasses<A> {
+    fn consistent(&self, cong: &RightCongruence<A>) -> bool {
+        cong.size() <= self.max
+    }
+
+    fn threshold(&self) -> usize {
+        self.max
+    }
+
+    fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+}
+
+/// Requires the congruence to be closed under the given symmetries, each of which is a
+/// permutation of the alphabet that is extended to words symbol by symbol. Symbols that are
+/// not mentioned in a permutation are mapped to themselves. A congruence `~` is closed under a
+/// permutation `π` if `u ~ v` implies `π(u) ~ π(v)`.
+///
+/// As the congruence may be partial during sprouting, only pairs of words whose images both
+/// reach a class are compared. It suffices to check the pairs `ua ~ v`, where `u` and `v`
+/// are the minimal representatives of a class and the target of its `a`-transition, as
+/// they generate the whole congruence.
+#[derive(Clone, Debug)]
+pub struct SymmetryClosed<A: Alphabet> {
+    alphabet: A,
+    permutations: Vec<Map<A::Symbol, A::Symbol>>,
+}
+
+impl<A: Alphabet> SymmetryClosed<A> {
+    /// Creates a new instance of the constraint.
+    pub fn new(alphabet: A, permutations: Vec<Map<A::Symbol, A::Symbol>>) -> Self {
+        Self {
+            alphabet,
+            permutations,
+        }
+    }
+}
+
+impl<A: Alphabet> ConsistencyCheck<A> for SymmetryClosed<A> {
+    fn consistent(&self, cong: &RightCongruence<A>) -> bool {
+        let classes: Map<_, _> = cong.classes_llex().into_iter().collect();
+        self.permutations.iter().all(|permutation| {
+            let image = |word: &[A::Symbol]| {
+                let mapped = word
+                    .iter()
+                    .map(|sym| *permutation.get(sym).unwrap_or(sym))
+                    .collect_vec();
+                cong.reached_state_index(&mapped)
+            };
+            classes.iter().all(|(q, rep)| {
+                self.alphabet.universe().all(|sym| {
+                    let Some(p) = cong.successor_index(*q, sym) else {
+                        return true;
+                    };
+                    let mut word = rep.clone();
+                    word.push(sym);
+                    match (image(&word), image(&classes[&p])) {
+                        (Some(left), Some(right)) if left != right => {
+                            trace!(
+                                "\t\tSymmetry violated, images of {word:?} and {:?} differ",
+                                classes[&p]
+                            );
+                            false
+                        }
+                        _ => true,
+                    }
+                })
+            })
+        })
+    }
+
+    fn threshold(&self) -> usize {
+        usize::MAX
+    }
+
+    fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+}
+
+/// Bounds the number of sample words that a learned congruence may misclassify, which allows
+/// learning from noisy data, see [`Tolerant`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseBudget {
+    /// At most the given number of words may be misclassified.
+    Absolute(usize),
+    /// At most the given fraction of all words may be misclassified.
+    Fraction(f64),
+}
+
+impl NoiseBudget {
+    /// Checks whether `misclassified` out of `total` words are within the budget.
+    pub fn allows(&self, misclassified: usize, total: usize) -> bool {
+        match self {
+            NoiseBudget::Absolute(max) => misclassified <= *max,
+            NoiseBudget::Fraction(fraction) => misclassified as f64 <= fraction * total as f64,
+        }
+    }
+}
+
+/// Data which can determine how many of its words a congruence misclassifies. Words that are
+/// reached on the same class, or for omega words that visit the same set of classes infinitely
+/// often, must be classified equally, so all but the majority of them count as misclassified.
+/// Words whose run leaves the (partial) congruence are not counted. Every word counts with its
+/// weight, see [`Sample::weight`].
+pub trait CountsMisclassifications<A: Alphabet>: ConsistencyCheck<A> {
+    /// Returns the total weight of the words that `cong` misclassifies.
+    fn misclassified(&self, cong: &RightCongruence<A>) -> usize;
+    /// Returns the total weight of all words.
+    fn size(&self) -> usize;
+}
+
+/// Sums up the weights of the minorities among the labels of each group.
+fn minorities<K: std::hash::Hash + Eq>(labelled: impl Iterator<Item = (K, bool, usize)>) -> usize {
+    let mut counts: Map<K, (usize, usize)> = Map::default();
+    for (group, label, weight) in labelled {
+        let (positive, negative) = counts.entry(group).or_default();
+        if label {
+            *positive += weight;
+        } else {
+            *negative += weight;
+        }
+    }
+    counts
+        .values()
+        .map(|(positive, negative)| *positive.min(negative))
+        .sum()
+}
+
+impl<A: Alphabet> CountsMisclassifications<A> for FiniteSample<A, bool> {
+    fn misclassified(&self, cong: &RightCongruence<A>) -> usize {
+        minorities(
+            self.entries()
+                .filter_map(|(w, c)| Some((cong.reached_state_index(w)?, *c, self.weight(w)))),
+        )
+    }
+
+    fn size(&self) -> usize {
+        self.total_weight()
+    }
+}
+
+/// Computes the classes that are visited infinitely often on `word`, if the run does not
+/// leave the congruence.
+fn infinity_set<A: Alphabet>(
+    cong: &RightCongruence<A>,
+    word: &ReducedOmegaWord<A::Symbol>,
+) -> Option<BTreeSet<usize>> {
+    let raw = word.raw_word();
+    let (spoke, cycle) = raw.split_at(word.loop_index());
+    // we read the cycle until the class in which an iteration starts repeats
+    let mut starts = vec![cong.reached_state_index(spoke)?];
+    let mut visited: Vec<BTreeSet<usize>> = vec![];
+    loop {
+        let mut state = *starts.last().unwrap();
+        let mut seen = BTreeSet::new();
+        for sym in cycle {
+            state = cong.successor_index(state, *sym)?;
+            seen.insert(state);
+        }
+        visited.push(seen);
+        if let Some(position) = starts.iter().position(|q| *q == state) {
+            return Some(visited[position..].iter().flatten().copied().collect());
+        }
+        starts.push(state);
+    }
+}
+
+impl<A: Alphabet> ConsistencyCheck<A> for OmegaSample<A, bool> {
+    fn consistent(&self, cong: &RightCongruence<A>) -> bool {
+        self.misclassified(cong) == 0
+    }
+
+    fn threshold(&self) -> usize {
+        self.words().map(|w| w.raw_word().len()).max().unwrap_or(0) * 2
+    }
+
+    fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+}
+
+impl<A: Alphabet> CountsMisclassifications<A> for OmegaSample<A, bool> {
+    fn misclassified(&self, cong: &RightCongruence<A>) -> usize {
+        minorities(
+            self.entries()
+                .filter_map(|(w, c)| Some((infinity_set(cong, w)?, *c, self.weight(w)))),
+        )
+    }
+
+    fn size(&self) -> usize {
+        self.total_weight()
+    }
+}
+
+/// A decision that was made by a [`Tolerant`] consistency check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoiseDecision {
+    /// The number of classes of the congruence that was checked.
+    pub classes: usize,
+    /// The total weight of the words that the congruence misclassifies.
+    pub misclassified: usize,
+    /// Whether the congruence was within the budget.
+    pub accepted: bool,
+}
+
+/// Relaxes the consistency with a sample such that a congruence is consistent as long as it
+/// misclassifies no more words than the [`NoiseBudget`] allows. Every check is recorded, so the
+/// misclassifications caused by each transition that sprout adds can be inspected afterwards
+/// with [`Self::decisions`].
+pub struct Tolerant<S> {
+    sample: S,
+    budget: NoiseBudget,
+    decisions: RefCell<Vec<NoiseDecision>>,
+}
+
+impl<S> Tolerant<S> {
+    /// Creates a new instance of the constraint.
+    pub fn new(sample: S, budget: NoiseBudget) -> Self {
+        Self {
+            sample,
+            budget,
+            decisions: RefCell::new(vec![]),
+        }
+    }
+
+    /// Returns the decisions that were made so far, in the order in which they were made.
+    pub fn decisions(&self) -> Vec<NoiseDecision> {
+        self.decisions.borrow().clone()
+    }
+}
+
+impl<A: Alphabet, S: CountsMisclassifications<A>> ConsistencyCheck<A> for Tolerant<S> {
+    fn consistent(&self, cong: &RightCongruence<A>) -> bool {
+        let misclassified = self.sample.misclassified(cong);
+        let accepted = self.budget.allows(misclassified, self.sample.size());
         trace!(
-            "Trying to add transition from {} on {}, cong size is {}",
-            cong.state_color(source)
-                .expect("Every state must be colored!")
-                .blue(),
-            sym.show().blue(),
-            cong.size(),
+            "\t\t{misclassified} of {} words misclassified, {}",
+            self.sample.size(),
+            if accepted {
+                "within budget"
+            } else {
+                "exceeding budget"
+            }
         );
+        self.decisions.borrow_mut().push(NoiseDecision {
+            classes: cong.size(),
+            misclassified,
+            accepted,
+        });
+        accepted
+    }
 
-        // FIXME: This is a hack to avoid lifetime issues, find a better way...
-        // TODO: figure out if this is the best way, we just take the upper bound on the number and assume that all states have sequential ids...
-        for target in (0..cong.size()) {
-            if !allow_transitions_into_epsilon && target == initial {
-                continue;
+    fn threshold(&self) -> usize {
+        self.sample.threshold()
+    }
+
+    fn alphabet(&self) -> &A {
+        self.sample.alphabet()
+    }
+}
+
+/// Determines the order in which sprout fills in missing transitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum InsertionOrder {
+    /// Missing transitions are handled in the order in which they arise, so classes are
+    /// completed in the order of their creation.
+    #[default]
+    Bfs,
+    /// The missing transitions of the most recently created class are handled first.
+    Dfs,
+    /// The missing transitions of the class with the shortest label are handled first.
+    ShortestClass,
+}
+
+impl InsertionOrder {
+    /// Returns the position of the pending transition that is handled next.
+    fn pick<A: Alphabet>(
+        &self,
+        pending: &[(usize, A::Symbol)],
+        cong: &RightCongruence<A>,
+    ) -> usize {
+        let positions = 0..pending.len();
+        match self {
+            InsertionOrder::Bfs => 0,
+            InsertionOrder::Dfs => positions
+                .rev()
+                .max_by_key(|i| pending[*i].0)
+                .expect("There must be a pending transition"),
+            InsertionOrder::ShortestClass => positions
+                .min_by_key(|i| {
+                    cong.class_name(pending[*i].0)
+                        .expect("Class must exist")
+                        .to_vec()
+                        .len()
+                })
+                .expect("There must be a pending transition"),
+        }
+    }
+}
+
+/// Scores a candidate transition, given the congruence, source, symbol and target.
+pub type TargetScore<'a, A> =
+    Box<dyn Fn(&RightCongruence<A>, usize, <A as Alphabet>::Symbol, usize) -> i64 + 'a>;
+
+/// Scores candidate transitions by the evidence for them in `sample`, in the spirit of the
+/// EDSM heuristic. Redirecting the `a`-transition of the class of `u` to the class of `v`
+/// identifies the words `uax` and `vx`, so the score is the total weight of the pairs of such
+/// words in the sample that have the same classification. Pairs that are classified
+/// differently are not penalized, since they make the transition inconsistent anyway.
+pub fn evidence_score<'a, A: Alphabet>(sample: &'a FiniteSample<A, bool>) -> TargetScore<'a, A> {
+    Box::new(move |cong, source, sym, target| {
+        let mut prefix = cong.class_name(source).expect("Class must exist").to_vec();
+        prefix.push(sym);
+        let representative = cong.class_name(target).expect("Class must exist").to_vec();
+        sample
+            .entries()
+            .filter_map(|(word, classification)| {
+                let suffix = word.strip_prefix(prefix.as_slice())?;
+                let identified = [representative.as_slice(), suffix].concat();
+                (sample.classify(&identified)? == *classification)
+                    .then(|| sample.weight(word) + sample.weight(&identified))
+            })
+            .sum::<usize>() as i64
+    })
+}
+
+/// Determines the order in which the existing classes are tried as the target of a missing
+/// transition.
+pub enum TargetPreference<'a, A: Alphabet> {
+    /// Classes are tried in the order of their creation.
+    Oldest,
+    /// The most recently created classes are tried first.
+    Newest,
+    /// Classes with a higher score are tried first, ties are broken by age.
+    Scored(TargetScore<'a, A>),
+}
+
+/// Configures the search that is performed by [`sprout_with`]. The default strategy, which is
+/// used by [`sprout`], handles missing transitions in breadth-first order, adds each one to the
+/// oldest class for which the congruence stays consistent and never revises a decision.
+///
+/// With backtracking enabled, all consistent targets and finally a new class are tried for
+/// each transition, and the search backtracks if the threshold of the conflicts is exceeded or
+/// a new class violates the additional constraints. This may take exponential time, but finds
+/// a consistent congruence whenever one exists within the threshold.
+pub struct SproutStrategy<'a, A: Alphabet> {
+    insertion: InsertionOrder,
+    target: TargetPreference<'a, A>,
+    backtracking: bool,
+}
+
+impl<'a, A: Alphabet> Default for TargetPreference<'a, A> {
+    fn default() -> Self {
+        TargetPreference::Oldest
+    }
+}
+
+impl<'a, A: Alphabet> Default for SproutStrategy<'a, A> {
+    fn default() -> Self {
+        Self {
+            insertion: InsertionOrder::default(),
+            target: TargetPreference::default(),
+            backtracking: false,
+        }
+    }
+}
+
+impl<'a, A: Alphabet> SproutStrategy<'a, A> {
+    /// Creates the default strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the order in which missing transitions are handled.
+    pub fn insertion(mut self, insertion: InsertionOrder) -> Self {
+        self.insertion = insertion;
+        self
+    }
+
+    /// Sets the order in which targets are tried.
+    pub fn target(mut self, target: TargetPreference<'a, A>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Enables or disables backtracking.
+    pub fn backtracking(mut self, backtracking: bool) -> Self {
+        self.backtracking = backtracking;
+        self
+    }
+
+    /// Orders the candidate targets according to the preference.
+    fn order_targets(
+        &self,
+        mut targets: Vec<usize>,
+        cong: &RightCongruence<A>,
+        source: usize,
+        sym: A::Symbol,
+    ) -> Vec<usize> {
+        match &self.target {
+            TargetPreference::Oldest => {}
+            TargetPreference::Newest => targets.reverse(),
+            TargetPreference::Scored(score) => {
+                targets.sort_by_key(|target| std::cmp::Reverse(score(cong, source, sym, *target)))
             }
-            let old_edge = cong.add_edge(source, A::expression(sym), target, Void);
+        }
+        targets
+    }
+}
 
-            if conflicts.consistent(&cong)
-                && additional_constraints.iter().all(|c| c.consistent(&cong))
-            {
-                trace!(
-                    "\tTransition {}--{}-->{} is consistent",
-                    cong.state_color(source)
-                        .expect("We expect every state to be colored")
-                        .green(),
-                    sym.show(),
-                    cong.state_color(target)
-                        .expect("We expect every state to be colored")
-                        .green()
-                );
-                continue 'outer;
-            } else {
-                trace!(
-                    "\tTransition {}--{}-->{} is not consistent",
-                    cong.state_color(source)
-                        .expect("We expect every state to be colored")
-                        .red(),
-                    sym.show(),
-                    cong.state_color(target)
-                        .expect("We expect every state to be colored")
-                        .red()
-                );
-                cong.remove_edges(source, A::expression(sym));
+/// Holds everything that stays fixed during a run of [`sprout_with`].
+struct SproutSearch<'s, 'a, A: Alphabet, C> {
+    conflicts: &'s C,
+    additional_constraints: &'s [Box<dyn ConsistencyCheck<A>>],
+    allow_transitions_into_epsilon: bool,
+    strategy: &'s SproutStrategy<'a, A>,
+    initial: usize,
+    threshold: usize,
+    observer: RefCell<&'s mut dyn LearnerObserver>,
+    batch: Option<&'s BatchCheck<'s, A>>,
+    budget: Option<&'s Budget>,
+}
+
+/// Explains why the omega-sprout algorithm did not produce a hypothesis of type `H`. The
+/// variants other than [`SproutError::BudgetExhausted`] make a backtracking search try the
+/// next candidate, so they are only returned once all candidates failed.
+#[derive(Debug, Clone)]
+pub enum SproutError<H> {
+    /// The budget was exhausted, contains the hypothesis built so far.
+    BudgetExhausted(BudgetExceeded<H>),
+    /// A new class would exceed the given threshold of the conflicts, which happens if the
+    /// sample is inconsistent, i.e. if no congruence separates all conflicting words.
+    InconsistentSample(usize),
+    /// Creating the given number of classes violates one of the additional constraints, e.g.
+    /// a limit imposed by [`MaxClasses`].
+    ConstraintViolated(usize),
+}
+
+impl<H> SproutError<H> {
+    /// Converts the hypothesis contained in [`SproutError::BudgetExhausted`] using `f`.
+    pub fn map_best<G>(self, f: impl FnOnce(H) -> G) -> SproutError<G> {
+        match self {
+            SproutError::BudgetExhausted(BudgetExceeded { best, reason }) => {
+                SproutError::BudgetExhausted(BudgetExceeded {
+                    best: f(best),
+                    reason,
+                })
             }
+            SproutError::InconsistentSample(threshold) => {
+                SproutError::InconsistentSample(threshold)
+            }
+            SproutError::ConstraintViolated(classes) => SproutError::ConstraintViolated(classes),
+        }
+    }
+}
+
+impl<H> std::fmt::Display for SproutError<H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SproutError::BudgetExhausted(exceeded) => {
+                write!(f, "Budget exhausted: {}", exceeded.reason)
+            }
+            SproutError::InconsistentSample(threshold) => write!(
+                f,
+                "More than {threshold} classes are needed, the sample is inconsistent"
+            ),
+            SproutError::ConstraintViolated(classes) => write!(
+                f,
+                "No congruence with {classes} classes satisfies the additional constraints"
+            ),
         }
+    }
+}
+
+impl<H: std::fmt::Debug> std::error::Error for SproutError<H> {}
+
+/// Candidate targets of a transition, each with the verdict whether adding the transition to
+/// it is consistent with the conflicts.
+type Verdicts = Vec<(usize, bool)>;
+
+/// Checks the conflicts for a batch of candidate targets of a transition at once, giving the
+/// [`Verdicts`] in the order of the candidates. This allows evaluating the candidates in
+/// parallel.
+type BatchCheck<'s, A> =
+    dyn Fn(&RightCongruence<A>, usize, <A as Alphabet>::Symbol, &[usize]) -> Verdicts + 's;
+
+/// A decision of the backtracking search, consisting of the congruence and the pending
+/// transitions from before the transition from `source` on `sym` was added, and the targets
+/// that remain to be tried. Adding a new class is the last alternative.
+struct ChoicePoint<A: Alphabet> {
+    cong: RightCongruence<A>,
+    pending: Vec<(usize, A::Symbol)>,
+    source: usize,
+    sym: A::Symbol,
+    targets: std::vec::IntoIter<usize>,
+}
+
+impl<'s, 'a, A: Alphabet, C: ConsistencyCheck<A>> SproutSearch<'s, 'a, A, C> {
+    fn emit(&self, event: LearnerEvent) {
+        self.observer.borrow_mut().observe(event);
+    }
+
+    /// Checks whether adding the transition keeps the congruence consistent. If the conflicts
+    /// were already checked, e.g. by a [`BatchCheck`], their verdict is passed as `checked`
+    /// and only the additional constraints are verified.
+    fn consistent(
+        &self,
+        cong: &mut RightCongruence<A>,
+        source: usize,
+        sym: A::Symbol,
+        target: usize,
+        checked: Option<bool>,
+    ) -> bool {
+        let consistent = checked != Some(false) && {
+            let mut transaction = cong.transaction();
+            transaction.add_edge(source, sym, target, Void);
+            let consistent = checked.unwrap_or_else(|| self.conflicts.consistent(transaction.ts()))
+                && self
+                    .additional_constraints
+                    .iter()
+                    .all(|c| c.consistent(transaction.ts()));
+            transaction.rollback();
+            consistent
+        };
+        self.emit(LearnerEvent::MergeAttempted { consistent });
+        if consistent {
+            trace!(
+                "\tTransition {}--{}-->{} is consistent",
+                cong.state_color(source)
+                    .expect("We expect every state to be colored")
+                    .green(),
+                sym.show(),
+                cong.state_color(target)
+                    .expect("We expect every state to be colored")
+                    .green()
+            );
+        } else {
+            trace!(
+                "\tTransition {}--{}-->{} is not consistent",
+                cong.state_color(source)
+                    .expect("We expect every state to be colored")
+                    .red(),
+                sym.show(),
+                cong.state_color(target)
+                    .expect("We expect every state to be colored")
+                    .red()
+            );
+        }
+        consistent
+    }
+
+    /// Returns the existing classes that the transition may lead to, in order of preference.
+    fn candidates(&self, cong: &RightCongruence<A>, source: usize, sym: A::Symbol) -> Vec<usize> {
+        // TODO: figure out if this is the best way, we just take the upper bound on the number and assume that all states have sequential ids...
+        let targets = (0..cong.size())
+            .filter(|target| self.allow_transitions_into_epsilon || *target != self.initial)
+            .collect_vec();
+        self.strategy.order_targets(targets, cong, source, sym)
+    }
 
+    /// Adds a new class that is reached from `source` on `sym`. Returns an error if the
+    /// threshold is exceeded or the additional constraints are violated.
+    fn add_class(
+        &self,
+        cong: &mut RightCongruence<A>,
+        pending: &mut Vec<(usize, A::Symbol)>,
+        source: usize,
+        sym: A::Symbol,
+    ) -> Result<(), SproutError<RightCongruence<A>>> {
         let mut new_state_label = cong
             .state_color(source)
             .expect("We expect every state to be colored")
@@ -374,14 +955,380 @@ where
         );
 
         let new_state = cong.add_state(new_state_label);
-        if new_state > threshold {
-            panic!("TOO MANY STATES")
+        self.emit(LearnerEvent::StateAdded(cong.size()));
+        if new_state > self.threshold {
+            return Err(SproutError::InconsistentSample(self.threshold));
         }
         cong.add_edge(source, A::expression(sym), new_state, Void);
-        queue.extend(std::iter::repeat(new_state).zip(conflicts.alphabet().universe()))
+        pending.extend(
+            std::iter::repeat(new_state).zip(self.conflicts.alphabet().universe().sorted()),
+        );
+        if self
+            .additional_constraints
+            .iter()
+            .all(|c| c.consistent(cong))
+        {
+            Ok(())
+        } else {
+            Err(SproutError::ConstraintViolated(cong.size()))
+        }
+    }
+
+    /// Checks the budget and removes the next pending transition according to the strategy.
+    /// Returns `None` once all transitions have been added.
+    fn next_pending(
+        &self,
+        cong: &RightCongruence<A>,
+        pending: &mut Vec<(usize, A::Symbol)>,
+    ) -> Result<Option<(usize, A::Symbol)>, Exhausted> {
+        if pending.is_empty() {
+            return Ok(None);
+        }
+        if let Some(Err(reason)) = self.budget.map(|b| b.check(cong.size(), 0)) {
+            trace!("Budget exhausted: {reason}");
+            return Err(reason);
+        }
+        self.emit(LearnerEvent::Pending(pending.len()));
+        let (source, sym) = pending.remove(self.strategy.insertion.pick(pending, cong));
+        trace!(
+            "Trying to add transition from {} on {}, cong size is {}",
+            cong.state_color(source)
+                .expect("Every state must be colored!")
+                .blue(),
+            sym.show().blue(),
+            cong.size(),
+        );
+        Ok(Some((source, sym)))
+    }
+
+    /// Fills in the pending transitions, returns an error if the search fails or the budget is
+    /// exhausted.
+    fn run(
+        &self,
+        mut cong: RightCongruence<A>,
+        mut pending: Vec<(usize, A::Symbol)>,
+    ) -> Result<RightCongruence<A>, SproutError<RightCongruence<A>>> {
+        if self.strategy.backtracking {
+            return self.run_backtracking(cong, pending);
+        }
+        loop {
+            let (source, sym) = match self.next_pending(&cong, &mut pending) {
+                Ok(Some(next)) => next,
+                Ok(None) => return Ok(cong),
+                Err(reason) => {
+                    return Err(SproutError::BudgetExhausted(BudgetExceeded {
+                        best: cong,
+                        reason,
+                    }))
+                }
+            };
+            let candidates = self.candidates(&cong, source, sym);
+            let target = match self.batch {
+                // the additional constraints are only checked for candidates whose verdict is
+                // positive, so the chosen target is the same as in the sequential search
+                Some(batch) => batch(&cong, source, sym, &candidates)
+                    .into_iter()
+                    .find(|(target, checked)| {
+                        self.consistent(&mut cong, source, sym, *target, Some(*checked))
+                    })
+                    .map(|(target, _)| target),
+                None => candidates
+                    .into_iter()
+                    .find(|target| self.consistent(&mut cong, source, sym, *target, None)),
+            };
+            match target {
+                Some(target) => {
+                    cong.add_edge(source, A::expression(sym), target, Void);
+                }
+                None => self.add_class(&mut cong, &mut pending, source, sym)?,
+            }
+        }
+    }
+
+    /// Fills in the pending transitions like [`Self::run`], but keeps the decisions that were
+    /// made on a stack. If a new class fails, the search returns to the most recent decision
+    /// that has alternatives left.
+    fn run_backtracking(
+        &self,
+        mut cong: RightCongruence<A>,
+        mut pending: Vec<(usize, A::Symbol)>,
+    ) -> Result<RightCongruence<A>, SproutError<RightCongruence<A>>> {
+        let mut stack = vec![];
+        loop {
+            let (source, sym) = match self.next_pending(&cong, &mut pending) {
+                Ok(Some(next)) => next,
+                Ok(None) => return Ok(cong),
+                Err(reason) => {
+                    return Err(SproutError::BudgetExhausted(BudgetExceeded {
+                        best: cong,
+                        reason,
+                    }))
+                }
+            };
+            stack.push(ChoicePoint {
+                targets: self.candidates(&cong, source, sym).into_iter(),
+                cong,
+                pending,
+                source,
+                sym,
+            });
+            (cong, pending) = self.backtrack(&mut stack)?;
+        }
+    }
+
+    /// Takes the next alternative of the topmost decision on the stack, decisions without
+    /// alternatives are removed. Fails if the new class of the first decision fails.
+    #[allow(clippy::type_complexity)]
+    fn backtrack(
+        &self,
+        stack: &mut Vec<ChoicePoint<A>>,
+    ) -> Result<(RightCongruence<A>, Vec<(usize, A::Symbol)>), SproutError<RightCongruence<A>>>
+    {
+        loop {
+            let mut point = stack
+                .pop()
+                .expect("The search only backtracks to decisions it made");
+            let (source, sym) = (point.source, point.sym);
+            if let Some(target) = point
+                .targets
+                .find(|target| self.consistent(&mut point.cong, source, sym, *target, None))
+            {
+                let mut next = point.cong.clone();
+                next.add_edge(source, A::expression(sym), target, Void);
+                let pending = point.pending.clone();
+                stack.push(point);
+                return Ok((next, pending));
+            }
+            // the new class is the last alternative, so the decision is dropped
+            let ChoicePoint {
+                mut cong,
+                mut pending,
+                ..
+            } = point;
+            match self.add_class(&mut cong, &mut pending, source, sym) {
+                Ok(()) => return Ok((cong, pending)),
+                Err(err) if stack.is_empty() => return Err(err),
+                Err(err) => trace!("Backtracking from {source} on {}: {err}", sym.show()),
+            }
+        }
     }
+}
+
+/// Runs the omega-sprout algorithm on a given conflict relation. A transition is only added
+/// if the resulting congruence is consistent with `conflicts` and all `additional_constraints`,
+/// which allows plugging in user-defined constraints such as [`MaxClasses`] or
+/// [`SymmetryClosed`]. Panics if a new class has to be created that exceeds the threshold or
+/// violates the additional constraints, [`sprout_with`] returns a [`SproutError`] instead.
+/// This uses the default [`SproutStrategy`], see [`sprout_with`] for other strategies.
+pub fn sprout<A, C>(
+    conflicts: C,
+    additional_constraints: Vec<Box<dyn ConsistencyCheck<A>>>,
+    allow_transitions_into_epsilon: bool,
+) -> RightCongruence<A>
+where
+    A: Alphabet,
+    C: ConsistencyCheck<A>,
+{
+    sprout_with(
+        conflicts,
+        additional_constraints,
+        allow_transitions_into_epsilon,
+        &SproutStrategy::default(),
+    )
+    .unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Runs the omega-sprout algorithm like [`sprout`], but searches according to `strategy`.
+/// Returns an error if no consistent congruence is found, that is if the threshold of
+/// `conflicts` is exceeded or the additional constraints can not be satisfied.
+pub fn sprout_with<A, C>(
+    conflicts: C,
+    additional_constraints: Vec<Box<dyn ConsistencyCheck<A>>>,
+    allow_transitions_into_epsilon: bool,
+    strategy: &SproutStrategy<'_, A>,
+) -> Result<RightCongruence<A>, SproutError<RightCongruence<A>>>
+where
+    A: Alphabet,
+    C: ConsistencyCheck<A>,
+{
+    sprout_observed(
+        conflicts,
+        additional_constraints,
+        allow_transitions_into_epsilon,
+        strategy,
+        &mut TracingObserver,
+    )
+}
+
+/// Runs the omega-sprout algorithm like [`sprout_with`] and reports its progress to `observer`.
+pub fn sprout_observed<A, C>(
+    conflicts: C,
+    additional_constraints: Vec<Box<dyn ConsistencyCheck<A>>>,
+    allow_transitions_into_epsilon: bool,
+    strategy: &SproutStrategy<'_, A>,
+    observer: &mut dyn LearnerObserver,
+) -> Result<RightCongruence<A>, SproutError<RightCongruence<A>>>
+where
+    A: Alphabet,
+    C: ConsistencyCheck<A>,
+{
+    search(
+        &conflicts,
+        &additional_constraints,
+        allow_transitions_into_epsilon,
+        strategy,
+        None,
+        None,
+        None,
+        observer,
+    )
+}
+
+/// Runs the omega-sprout algorithm like [`sprout_with`], but stops once `budget` is exhausted.
+/// The budget is checked before each transition is added, where the number of classes counts
+/// as states and no queries are posed. If it is exhausted, [`SproutError::BudgetExhausted`]
+/// contains the partial congruence that was built so far together with the reason. The other
+/// errors are returned as for [`sprout_with`].
+pub fn sprout_budgeted<A, C>(
+    conflicts: C,
+    additional_constraints: Vec<Box<dyn ConsistencyCheck<A>>>,
+    allow_transitions_into_epsilon: bool,
+    strategy: &SproutStrategy<'_, A>,
+    budget: &Budget,
+) -> Result<RightCongruence<A>, SproutError<RightCongruence<A>>>
+where
+    A: Alphabet,
+    C: ConsistencyCheck<A>,
+{
+    search(
+        &conflicts,
+        &additional_constraints,
+        allow_transitions_into_epsilon,
+        strategy,
+        None,
+        None,
+        Some(budget),
+        &mut TracingObserver,
+    )
+}
 
-    cong
+/// Runs the omega-sprout algorithm like [`sprout_with`], but checks the candidate targets of
+/// each transition against `conflicts` in parallel. The candidates are still chosen in the
+/// order given by the strategy, so the result is the same as that of [`sprout_with`]. This
+/// pays off if the consistency check is expensive, e.g. for conflict relations of large
+/// samples. Backtracking searches are not parallelized.
+#[cfg(feature = "parallel")]
+pub fn sprout_parallel<A, C>(
+    conflicts: C,
+    additional_constraints: Vec<Box<dyn ConsistencyCheck<A>>>,
+    allow_transitions_into_epsilon: bool,
+    strategy: &SproutStrategy<'_, A>,
+) -> Result<RightCongruence<A>, SproutError<RightCongruence<A>>>
+where
+    A: Alphabet + Sync,
+    A::Symbol: Sync,
+    C: ConsistencyCheck<A> + Sync,
+    RightCongruence<A>: Sync,
+{
+    use rayon::prelude::*;
+
+    let batch = |cong: &RightCongruence<A>, source: usize, sym: A::Symbol, targets: &[usize]| {
+        targets
+            .par_iter()
+            .map(|target| {
+                let mut candidate = cong.clone();
+                candidate.add_edge(source, A::expression(sym), *target, Void);
+                (*target, conflicts.consistent(&candidate))
+            })
+            .collect::<Vec<_>>()
+    };
+    search(
+        &conflicts,
+        &additional_constraints,
+        allow_transitions_into_epsilon,
+        strategy,
+        None,
+        Some(&batch),
+        None,
+        &mut TracingObserver,
+    )
+}
+
+/// Fills in the pending transitions of a partial congruence, which is used to continue from a
+/// hypothesis that became inconsistent after new data arrived. Only the pending transitions
+/// and those of newly created classes are added, all others are kept as they are. Panics if
+/// the threshold of `conflicts` is exceeded.
+pub(crate) fn sprout_resume<A, C>(
+    conflicts: C,
+    cong: RightCongruence<A>,
+    pending: Vec<(usize, A::Symbol)>,
+) -> RightCongruence<A>
+where
+    A: Alphabet,
+    C: ConsistencyCheck<A>,
+{
+    search(
+        &conflicts,
+        &[],
+        true,
+        &SproutStrategy::default(),
+        Some((cong, pending)),
+        None,
+        None,
+        &mut TracingObserver,
+    )
+    .unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Runs the search from `start`, which consists of a partial congruence and its missing
+/// transitions, or from the congruence with only the class of the empty word.
+#[allow(clippy::too_many_arguments)]
+fn search<'s, A, C>(
+    conflicts: &'s C,
+    additional_constraints: &'s [Box<dyn ConsistencyCheck<A>>],
+    allow_transitions_into_epsilon: bool,
+    strategy: &'s SproutStrategy<'_, A>,
+    start: Option<(RightCongruence<A>, Vec<(usize, A::Symbol)>)>,
+    batch: Option<&'s BatchCheck<'s, A>>,
+    budget: Option<&'s Budget>,
+    observer: &'s mut dyn LearnerObserver,
+) -> Result<RightCongruence<A>, SproutError<RightCongruence<A>>>
+where
+    A: Alphabet,
+    C: ConsistencyCheck<A>,
+{
+    let (cong, pending) = start.unwrap_or_else(|| {
+        let mut cong = RightCongruence::new(conflicts.alphabet().clone());
+        let initial = cong.add_state((vec![], Void));
+        // symbols are sorted, so the result does not depend on how the alphabet was built
+        let pending = conflicts
+            .alphabet()
+            .universe()
+            .sorted()
+            .map(|sym| (initial, sym))
+            .collect_vec();
+        (cong, pending)
+    });
+    let initial = cong.initial();
+    let start = std::time::Instant::now();
+    let search = SproutSearch {
+        conflicts,
+        additional_constraints,
+        allow_transitions_into_epsilon,
+        strategy,
+        initial,
+        threshold: conflicts.threshold(),
+        observer: RefCell::new(observer),
+        batch,
+        budget,
+    };
+    let result = search.run(cong, pending);
+    match &result {
+        Ok(cong) => search.emit(LearnerEvent::Hypothesis(cong.size())),
+        Err(err) => trace!("Sprout failed: {err}"),
+    }
+    search.emit(LearnerEvent::Phase("sprout", start.elapsed()));
+    result
 }
 
 #[cfg(test)]
@@ -396,7 +1343,19 @@ pub(crate) mod tests {
     };
     use itertools::Itertools;
 
-    use crate::passive::{sample::OmegaSample, sprout::ConflictRelation, Sample};
+    use crate::{
+        analysis::Isomorphism,
+        budget::{Budget, Exhausted},
+        passive::{
+            sample::OmegaSample,
+            sprout::{
+                evidence_score, ConflictRelation, ConsistencyCheck, CountsMisclassifications,
+                InsertionOrder, MaxClasses, SproutError, SproutStrategy, SymmetryClosed,
+                TargetPreference,
+            },
+            FiniteSample, Sample,
+        },
+    };
 
     pub fn inf_aba_sample() -> (CharAlphabet, OmegaSample<CharAlphabet, bool>) {
         let Ok(sample) = OmegaSample::try_from(
@@ -582,7 +1541,7 @@ pub(crate) mod tests {
 
         let cong = super::sprout(conflicts, vec![], true);
 
-        assert_eq!(cong.size(), expected_cong.size());
+        assert!(cong.is_isomorphic(&expected_cong).is_some());
         for word in ["aba", "abbabb", "baabaaba", "bababaaba", "b", "a", ""] {
             let reached = cong.reached_state_color(word).unwrap();
             let expected = expected_cong.reached_state_color(word).unwrap();
@@ -631,4 +1590,199 @@ pub(crate) mod tests {
             assert!(cong.contains_state_color(&class.into()))
         }
     }
+
+    #[test]
+    fn user_defined_constraints() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        // counts the a's modulo two and the length modulo two, respectively
+        let parity = |counted: &[char]| {
+            let mut cong = RightCongruence::new(alphabet.clone());
+            let even = cong.add_state((vec![], Void));
+            let odd = cong.add_state((vec![counted[0]], Void));
+            for sym in ['a', 'b'] {
+                if counted.contains(&sym) {
+                    cong.add_edge(even, sym, odd, Void);
+                    cong.add_edge(odd, sym, even, Void);
+                } else {
+                    cong.add_edge(even, sym, even, Void);
+                    cong.add_edge(odd, sym, odd, Void);
+                }
+            }
+            cong
+        };
+        let swap = SymmetryClosed::new(
+            alphabet.clone(),
+            vec![[('a', 'b'), ('b', 'a')].into_iter().collect()],
+        );
+        assert!(!swap.consistent(&parity(&['a'])));
+        assert!(swap.consistent(&parity(&['a', 'b'])));
+
+        assert!(MaxClasses::new(alphabet.clone(), 2).consistent(&parity(&['a'])));
+        assert!(!MaxClasses::new(alphabet, 1).consistent(&parity(&['a'])));
+    }
+
+    #[test]
+    fn sprout_strategies() {
+        // words that end with an a
+        let sample = FiniteSample::new_finite(
+            alphabet!(simple 'a', 'b'),
+            [
+                ("a", true),
+                ("ba", true),
+                ("aa", true),
+                ("bba", true),
+                ("", false),
+                ("b", false),
+                ("ab", false),
+                ("bab", false),
+            ]
+            .map(|(w, c)| (w.chars(), c)),
+        );
+        for strategy in [
+            SproutStrategy::new().insertion(InsertionOrder::Dfs),
+            SproutStrategy::new().insertion(InsertionOrder::ShortestClass),
+            SproutStrategy::new().target(TargetPreference::Newest),
+            SproutStrategy::new().target(TargetPreference::Scored(Box::new(
+                |_: &RightCongruence<CharAlphabet>, _: usize, _: char, t: usize| t as i64,
+            ))),
+            SproutStrategy::new().target(TargetPreference::Scored(evidence_score(&sample))),
+        ] {
+            let cong = super::sprout_with(&sample, vec![], true, &strategy).unwrap();
+            assert!(sample.consistent(&cong));
+        }
+
+        let cong = super::sprout_with(
+            &sample,
+            vec![Box::new(MaxClasses::new(sample.alphabet.clone(), 2))],
+            true,
+            &SproutStrategy::new()
+                .target(TargetPreference::Newest)
+                .backtracking(true),
+        )
+        .unwrap();
+        assert!(sample.consistent(&cong));
+        assert!(cong.size() <= 2);
+    }
+
+    /// Rejects every congruence and allows only a single class.
+    struct RejectAll(CharAlphabet);
+
+    impl ConsistencyCheck<CharAlphabet> for RejectAll {
+        fn consistent(&self, _cong: &RightCongruence<CharAlphabet>) -> bool {
+            false
+        }
+
+        fn threshold(&self) -> usize {
+            1
+        }
+
+        fn alphabet(&self) -> &CharAlphabet {
+            &self.0
+        }
+    }
+
+    #[test]
+    fn sprout_errors() {
+        // words that end with an a, which needs two classes
+        let alphabet = alphabet!(simple 'a', 'b');
+        let sample = FiniteSample::new_finite(
+            alphabet.clone(),
+            [
+                ("a", true),
+                ("ba", true),
+                ("", false),
+                ("b", false),
+                ("ab", false),
+            ]
+            .map(|(w, c)| (w.chars(), c)),
+        );
+        let strategy = SproutStrategy::default();
+
+        let budget = Budget::unlimited().max_states(1);
+        let Err(SproutError::BudgetExhausted(exceeded)) =
+            super::sprout_budgeted(&sample, vec![], true, &strategy, &budget)
+        else {
+            panic!("The budget should be exhausted")
+        };
+        assert_eq!(exceeded.reason, Exhausted::States(1));
+        assert_eq!(exceeded.best.size(), 2);
+
+        let Err(err) = super::sprout_budgeted(
+            RejectAll(alphabet.clone()),
+            vec![],
+            true,
+            &strategy,
+            &Budget::unlimited(),
+        ) else {
+            panic!("No congruence should be consistent")
+        };
+        assert!(matches!(err, SproutError::InconsistentSample(1)));
+        assert_eq!(
+            err.to_string(),
+            "More than 1 classes are needed, the sample is inconsistent"
+        );
+    }
+
+    #[test]
+    fn max_classes_violated() {
+        // words that end with an a, which can not be separated by a single class
+        let alphabet = alphabet!(simple 'a', 'b');
+        let sample = FiniteSample::new_finite(
+            alphabet.clone(),
+            [("a", true), ("ba", true), ("", false), ("ab", false)].map(|(w, c)| (w.chars(), c)),
+        );
+        for strategy in [
+            SproutStrategy::default(),
+            SproutStrategy::new().backtracking(true),
+        ] {
+            let result = super::sprout_with(
+                &sample,
+                vec![Box::new(MaxClasses::new(alphabet.clone(), 1))],
+                true,
+                &strategy,
+            );
+            assert!(matches!(result, Err(SproutError::ConstraintViolated(2))));
+        }
+
+        let cong = super::sprout_with(
+            &sample,
+            vec![Box::new(MaxClasses::new(alphabet, 2))],
+            true,
+            &SproutStrategy::default(),
+        )
+        .unwrap();
+        assert_eq!(cong.size(), 2);
+    }
+
+    #[test]
+    fn weighted_misclassifications() {
+        let alphabet = alphabet!(simple 'a', 'b');
+        let sample = FiniteSample::new_weighted(
+            alphabet.clone(),
+            [("a", true, 3), ("b", false, 1), ("", false, 1)].map(|(w, c, n)| (w.chars(), c, n)),
+        );
+        assert_eq!(sample.size(), 5);
+        // a single class identifies all words, so the negative ones are in the minority
+        let cong = super::sprout(MaxClasses::new(alphabet, 1), vec![], true);
+        assert_eq!(sample.misclassified(&cong), 2);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_sprout() {
+        let (_, sample) = inf_aba_sample();
+        let conflicts = super::prefix_consistency_conflicts(&sample);
+        let strategy = SproutStrategy::default();
+        let sequential = super::sprout_with(&conflicts, vec![], true, &strategy).unwrap();
+        let parallel = super::sprout_parallel(&conflicts, vec![], true, &strategy).unwrap();
+        assert_eq!(sequential.size(), parallel.size());
+        for q in sequential.state_indices() {
+            for sym in sample.alphabet.universe() {
+                assert_eq!(
+                    sequential.successor_index(q, sym),
+                    parallel.successor_index(q, sym)
+                );
+            }
+        }
+    }
 }
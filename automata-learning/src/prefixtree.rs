@@ -1,8 +1,8 @@
 use std::collections::VecDeque;
 
 use automata::{
-    transition_system::Sproutable, word::OmegaWord, Alphabet, Map, Pointed, RightCongruence, Set,
-    Void,
+    prelude::*, transition_system::Sproutable, word::OmegaWord, Alphabet, Map, Pointed,
+    RightCongruence, Set, Void,
 };
 use itertools::Itertools;
 use tracing::trace;
@@ -79,6 +79,131 @@ pub fn prefix_tree<A: Alphabet, W: Into<ReducedOmegaWord<A::Symbol>>, I: IntoIte
     tree
 }
 
+/// A node of an acyclic automaton under construction, whose edges are sorted by symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Node<S> {
+    accepting: bool,
+    edges: Vec<(S, usize)>,
+}
+
+/// Builds acyclic automata from lexicographically sorted words. If `minimize` is set, this is
+/// the incremental construction of Daciuk et al., which merges the states of the previous
+/// word into equivalent registered states as soon as the next word diverges from it, so the
+/// automaton is minimal at all times apart from the path of the last word.
+struct Acyclic<S> {
+    nodes: Vec<Node<S>>,
+    register: Map<Node<S>, usize>,
+    minimize: bool,
+}
+
+impl<S: Symbol> Acyclic<S> {
+    fn build<I: IntoIterator<Item = Vec<S>>>(words: I, minimize: bool) -> Self {
+        let mut out = Self {
+            nodes: vec![Node {
+                accepting: false,
+                edges: vec![],
+            }],
+            register: Map::default(),
+            minimize,
+        };
+        for word in words.into_iter().sorted().dedup() {
+            out.insert(&word);
+        }
+        if out.minimize && !out.nodes[0].edges.is_empty() {
+            out.replace_or_register(0);
+        }
+        out
+    }
+
+    fn insert(&mut self, word: &[S]) {
+        // as the words are sorted, the common prefix with the previous word is the only path
+        // that contains edges matching the word
+        let mut state = 0;
+        let mut length = 0;
+        while let Some((_, target)) = word.get(length).and_then(|sym| {
+            self.nodes[state]
+                .edges
+                .iter()
+                .find(|(label, _)| label == sym)
+        }) {
+            state = *target;
+            length += 1;
+        }
+        if self.minimize && !self.nodes[state].edges.is_empty() {
+            self.replace_or_register(state);
+        }
+        for sym in &word[length..] {
+            let next = self.nodes.len();
+            self.nodes.push(Node {
+                accepting: false,
+                edges: vec![],
+            });
+            self.nodes[state].edges.push((*sym, next));
+            state = next;
+        }
+        self.nodes[state].accepting = true;
+    }
+
+    fn replace_or_register(&mut self, state: usize) {
+        let (_, child) = *self.nodes[state]
+            .edges
+            .last()
+            .expect("State must have edges");
+        if !self.nodes[child].edges.is_empty() {
+            self.replace_or_register(child);
+        }
+        match self.register.get(&self.nodes[child]) {
+            Some(equivalent) => {
+                trace!("Merging state {child} into {equivalent}");
+                self.nodes[state].edges.last_mut().unwrap().1 = *equivalent;
+            }
+            None => {
+                self.register.insert(self.nodes[child].clone(), child);
+            }
+        }
+    }
+
+    /// Collects the nodes that are reachable from the root into a DFA, whose initial state is
+    /// `0` and whose states are numbered in breadth-first order.
+    fn into_dfa<A: Alphabet<Symbol = S>>(self, alphabet: A) -> DFA<A> {
+        let mut dfa = DFA::new_for_alphabet(alphabet);
+        let mut index: Map<usize, usize> = Map::default();
+        index.insert(0, dfa.add_state(self.nodes[0].accepting));
+        let mut queue = VecDeque::from([0]);
+        while let Some(node) = queue.pop_front() {
+            for (sym, target) in &self.nodes[node].edges {
+                let successor = match index.get(target) {
+                    Some(successor) => *successor,
+                    None => {
+                        let successor = dfa.add_state(self.nodes[*target].accepting);
+                        index.insert(*target, successor);
+                        queue.push_back(*target);
+                        successor
+                    }
+                };
+                dfa.add_edge(index[&node], A::expression(*sym), successor, Void);
+            }
+        }
+        dfa
+    }
+}
+
+/// Builds the prefix tree acceptor of a set of finite words, i.e. the tree-shaped DFA that
+/// accepts exactly the given words. Its size is the number of distinct prefixes of the words.
+pub fn finite_prefix_tree<A: Alphabet, I: IntoIterator<Item = Vec<A::Symbol>>>(
+    alphabet: A,
+    words: I,
+) -> DFA<A> {
+    Acyclic::build(words, false).into_dfa(alphabet)
+}
+
+/// Builds the minimal DFA accepting exactly the given finite words, which is acyclic apart from
+/// the missing transitions into a sink (a DAWG). In contrast to [`finite_prefix_tree`], common
+/// suffixes are shared, so the construction stays small for large samples.
+pub fn dawg<A: Alphabet, I: IntoIterator<Item = Vec<A::Symbol>>>(alphabet: A, words: I) -> DFA<A> {
+    Acyclic::build(words, true).into_dfa(alphabet)
+}
+
 #[cfg(test)]
 mod tests {
     use automata::{
@@ -89,7 +214,9 @@ mod tests {
         TransitionSystem, Void,
     };
 
-    use super::prefix_tree;
+    use automata::prelude::*;
+
+    use super::{dawg, finite_prefix_tree, prefix_tree};
 
     #[test]
     fn build_prefix_tree() {
@@ -109,4 +236,23 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn build_dawg() {
+        let alphabet = CharAlphabet::from_iter(['a', 'b']);
+        let words = ["aa", "ab", "ba", "bb", "bab", ""].map(|w| w.chars().collect::<Vec<_>>());
+
+        let tree = finite_prefix_tree(alphabet.clone(), words.clone());
+        let minimal = dawg(alphabet, words.clone());
+        assert_eq!(tree.size(), 8);
+        assert_eq!(minimal.size(), 5);
+        for dfa in [&tree, &minimal] {
+            for word in &words {
+                assert!(dfa.accepts(word));
+            }
+            for word in ["a", "b", "aab", "baa", "abab"] {
+                assert!(!dfa.accepts(word));
+            }
+        }
+    }
 }
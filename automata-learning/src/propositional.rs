@@ -0,0 +1,512 @@
+//! Alphabets whose symbols are valuations of atomic propositions, and Boolean formulas over
+//! them that serve as edge labels, as in the HOA format. Valuations are encoded as the symbols
+//! of a [`CharAlphabet`], so automata over a [`PropositionalAlphabet`] are ordinary transition
+//! systems, and an edge labelled with a [`Formula`] becomes one edge for each symbol that
+//! satisfies it, see [`PropositionalAlphabet::symbols_matching`].
+
+use std::collections::BTreeSet;
+
+use automata::prelude::*;
+use itertools::Itertools;
+
+/// The first symbol that is used for encoding valuations, which lies in a private use area of
+/// unicode, so it does not clash with symbols that are used elsewhere.
+const FIRST_VALUATION_SYMBOL: u32 = 0xE000;
+/// The maximal number of propositions, which keeps the alphabet within the private use area.
+const MAX_PROPOSITIONS: usize = 12;
+
+/// A valuation of atomic propositions, given by the set of propositions that hold.
+pub type Valuation = BTreeSet<String>;
+
+/// The alphabet of all valuations of a fixed list of atomic propositions. The valuation in
+/// which exactly the propositions with positions in a bitmask `m` hold is encoded as the symbol
+/// with code point `0xE000 + m`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropositionalAlphabet {
+    propositions: Vec<String>,
+}
+
+impl PropositionalAlphabet {
+    /// Creates the alphabet for the given propositions. Panics if there are more than twelve.
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(propositions: I) -> Self {
+        let propositions = propositions.into_iter().map(Into::into).collect_vec();
+        assert!(
+            propositions.len() <= MAX_PROPOSITIONS,
+            "At most {MAX_PROPOSITIONS} propositions are supported"
+        );
+        Self { propositions }
+    }
+
+    /// Returns the atomic propositions.
+    pub fn propositions(&self) -> &[String] {
+        &self.propositions
+    }
+
+    /// Returns the symbol that encodes `valuation`. Panics if it contains an unknown
+    /// proposition.
+    pub fn symbol(&self, valuation: &Valuation) -> char {
+        let mask = valuation
+            .iter()
+            .map(|p| {
+                let position = self
+                    .propositions
+                    .iter()
+                    .position(|q| q == p)
+                    .unwrap_or_else(|| panic!("Unknown proposition {p}"));
+                1 << position
+            })
+            .sum::<u32>();
+        char::from_u32(FIRST_VALUATION_SYMBOL + mask).expect("Symbol must be valid")
+    }
+
+    /// Returns the valuation that is encoded by `symbol`.
+    pub fn valuation(&self, symbol: char) -> Valuation {
+        let mask = u32::from(symbol) - FIRST_VALUATION_SYMBOL;
+        self.propositions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask & (1 << i) != 0)
+            .map(|(_, p)| p.clone())
+            .collect()
+    }
+
+    /// Returns the symbols of all valuations.
+    pub fn symbols(&self) -> impl Iterator<Item = char> + '_ {
+        (0..1u32 << self.propositions.len()).map(|mask| {
+            char::from_u32(FIRST_VALUATION_SYMBOL + mask).expect("Symbol must be valid")
+        })
+    }
+
+    /// Returns the symbols of all valuations that satisfy `predicate`, which is useful for
+    /// labelling the edges of an automaton with a propositional formula.
+    pub fn symbols_where<F: Fn(&Valuation) -> bool>(&self, predicate: F) -> Vec<char> {
+        self.symbols()
+            .filter(|sym| predicate(&self.valuation(*sym)))
+            .collect()
+    }
+
+    /// Returns the alphabet that consists of the symbols of all valuations.
+    pub fn alphabet(&self) -> CharAlphabet {
+        CharAlphabet::from_iter(self.symbols())
+    }
+
+    /// Returns the formula that consists of the proposition with the given name. Panics if the
+    /// proposition is unknown.
+    pub fn atom(&self, proposition: &str) -> Formula {
+        let position = self
+            .propositions
+            .iter()
+            .position(|p| p == proposition)
+            .unwrap_or_else(|| panic!("Unknown proposition {proposition}"));
+        Formula::Atom(position)
+    }
+
+    /// Returns true if the valuation encoded by `symbol` satisfies `formula`.
+    pub fn matches(&self, formula: &Formula, symbol: char) -> bool {
+        let mask = u32::from(symbol) - FIRST_VALUATION_SYMBOL;
+        formula.evaluate(&|i| i < self.propositions.len() && mask & (1 << i) != 0)
+    }
+
+    /// Returns the symbols of all valuations that satisfy `formula`. An edge that is labelled
+    /// with `formula` corresponds to one edge for each of these symbols.
+    pub fn symbols_matching(&self, formula: &Formula) -> Vec<char> {
+        self.symbols()
+            .filter(|sym| self.matches(formula, *sym))
+            .collect()
+    }
+
+    /// Parses a label expression in the syntax of the HOA format, e.g. `0 & !(1 | t)`, where
+    /// propositions are referenced by their position. Propositions may also be referenced by
+    /// their name in double quotes, e.g. `"request" & !"grant"`.
+    pub fn parse(&self, label: &str) -> Result<Formula, FormulaError> {
+        let mut parser = FormulaParser {
+            alphabet: self,
+            input: label.chars().collect(),
+            position: 0,
+        };
+        let formula = parser.disjunction()?;
+        match parser.peek() {
+            None => Ok(formula),
+            Some(c) => Err(FormulaError::UnexpectedCharacter(parser.position, c)),
+        }
+    }
+}
+
+/// Abstracts the types of errors that can occur when parsing a [`PropositionalAlphabet`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum PropositionsError {
+    MissingCount,
+    CountMismatch(usize, usize),
+    TooMany(usize),
+    Malformed(String),
+}
+
+impl std::fmt::Display for PropositionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropositionsError::MissingCount => write!(f, "The number of propositions is missing"),
+            PropositionsError::CountMismatch(declared, found) => {
+                write!(f, "Declared {declared} propositions, but found {found}")
+            }
+            PropositionsError::TooMany(count) => {
+                write!(
+                    f,
+                    "At most {MAX_PROPOSITIONS} propositions are supported, got {count}"
+                )
+            }
+            PropositionsError::Malformed(rest) => write!(f, "Malformed proposition at {rest}"),
+        }
+    }
+}
+
+impl std::error::Error for PropositionsError {}
+
+/// Writes the propositions in the canonical form of the `AP` header of the HOA format, i.e.
+/// their number followed by their names in double quotes, e.g. `2 "request" "grant"`. The
+/// position of a proposition in this list is the index that formulas use.
+impl std::fmt::Display for PropositionalAlphabet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.propositions.len())?;
+        for p in &self.propositions {
+            write!(f, " \"{}\"", p.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for PropositionalAlphabet {
+    type Err = PropositionsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+        let declared: usize = s[..digits]
+            .parse()
+            .map_err(|_| PropositionsError::MissingCount)?;
+        let mut propositions = vec![];
+        let mut rest = s[digits..].trim_start();
+        while !rest.is_empty() {
+            let Some(quoted) = rest.strip_prefix('"') else {
+                return Err(PropositionsError::Malformed(rest.to_string()));
+            };
+            let mut name = String::new();
+            let mut chars = quoted.char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((i, '"')) => break i,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, c)) => name.push(c),
+                        None => return Err(PropositionsError::Malformed(rest.to_string())),
+                    },
+                    Some((_, c)) => name.push(c),
+                    None => return Err(PropositionsError::Malformed(rest.to_string())),
+                }
+            };
+            propositions.push(name);
+            rest = quoted[end + 1..].trim_start();
+        }
+        if propositions.len() != declared {
+            return Err(PropositionsError::CountMismatch(
+                declared,
+                propositions.len(),
+            ));
+        }
+        if declared > MAX_PROPOSITIONS {
+            return Err(PropositionsError::TooMany(declared));
+        }
+        Ok(Self { propositions })
+    }
+}
+
+/// A Boolean formula over atomic propositions, which are referenced by their position in a
+/// [`PropositionalAlphabet`]. It is displayed in the syntax of label expressions of the HOA
+/// format.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Formula {
+    /// The formula that always holds.
+    True,
+    /// The formula that never holds.
+    False,
+    /// The proposition at the given position.
+    Atom(usize),
+    /// The negation of a formula.
+    Not(Box<Formula>),
+    /// The conjunction of two formulas.
+    And(Box<Formula>, Box<Formula>),
+    /// The disjunction of two formulas.
+    Or(Box<Formula>, Box<Formula>),
+}
+
+impl Formula {
+    /// Evaluates the formula, where `holds` decides whether the proposition at a position
+    /// holds.
+    pub fn evaluate(&self, holds: &impl Fn(usize) -> bool) -> bool {
+        match self {
+            Formula::True => true,
+            Formula::False => false,
+            Formula::Atom(i) => holds(*i),
+            Formula::Not(f) => !f.evaluate(holds),
+            Formula::And(f, g) => f.evaluate(holds) && g.evaluate(holds),
+            Formula::Or(f, g) => f.evaluate(holds) || g.evaluate(holds),
+        }
+    }
+
+    /// Writes the formula, where subformulas whose operator binds less tightly than
+    /// `precedence` are put in parentheses.
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, precedence: u8) -> std::fmt::Result {
+        let own = match self {
+            Formula::Or(..) => 0,
+            Formula::And(..) => 1,
+            _ => 2,
+        };
+        if own < precedence {
+            write!(f, "(")?;
+        }
+        match self {
+            Formula::True => write!(f, "t")?,
+            Formula::False => write!(f, "f")?,
+            Formula::Atom(i) => write!(f, "{i}")?,
+            Formula::Not(g) => {
+                write!(f, "!")?;
+                g.write(f, 2)?;
+            }
+            Formula::And(g, h) => {
+                g.write(f, 1)?;
+                write!(f, " & ")?;
+                h.write(f, 2)?;
+            }
+            Formula::Or(g, h) => {
+                g.write(f, 0)?;
+                write!(f, " | ")?;
+                h.write(f, 1)?;
+            }
+        }
+        if own < precedence {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Formula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl std::ops::Not for Formula {
+    type Output = Formula;
+
+    fn not(self) -> Self::Output {
+        Formula::Not(Box::new(self))
+    }
+}
+
+impl std::ops::BitAnd for Formula {
+    type Output = Formula;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Formula::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl std::ops::BitOr for Formula {
+    type Output = Formula;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Formula::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+/// Abstracts the types of errors that can occur when parsing a [`Formula`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum FormulaError {
+    UnexpectedEnd,
+    UnexpectedCharacter(usize, char),
+    UnknownProposition(String),
+}
+
+impl std::fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormulaError::UnexpectedEnd => write!(f, "Unexpected end of formula"),
+            FormulaError::UnexpectedCharacter(position, c) => {
+                write!(f, "Unexpected character {c} at position {position}")
+            }
+            FormulaError::UnknownProposition(p) => write!(f, "Unknown proposition {p}"),
+        }
+    }
+}
+
+impl std::error::Error for FormulaError {}
+
+/// A recursive descent parser for formulas, where negation binds tighter than conjunction,
+/// which binds tighter than disjunction.
+struct FormulaParser<'a> {
+    alphabet: &'a PropositionalAlphabet,
+    input: Vec<char>,
+    position: usize,
+}
+
+impl FormulaParser<'_> {
+    /// Returns the next character that is not whitespace without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        while self
+            .input
+            .get(self.position)
+            .is_some_and(|c| c.is_whitespace())
+        {
+            self.position += 1;
+        }
+        self.input.get(self.position).cloned()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), FormulaError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(c) => Err(FormulaError::UnexpectedCharacter(self.position, c)),
+            None => Err(FormulaError::UnexpectedEnd),
+        }
+    }
+
+    fn disjunction(&mut self) -> Result<Formula, FormulaError> {
+        let mut formula = self.conjunction()?;
+        while self.peek() == Some('|') {
+            self.position += 1;
+            formula = formula | self.conjunction()?;
+        }
+        Ok(formula)
+    }
+
+    fn conjunction(&mut self) -> Result<Formula, FormulaError> {
+        let mut formula = self.negation()?;
+        while self.peek() == Some('&') {
+            self.position += 1;
+            formula = formula & self.negation()?;
+        }
+        Ok(formula)
+    }
+
+    fn negation(&mut self) -> Result<Formula, FormulaError> {
+        match self.peek() {
+            Some('!') => {
+                self.position += 1;
+                Ok(!self.negation()?)
+            }
+            Some('(') => {
+                self.position += 1;
+                let formula = self.disjunction()?;
+                self.expect(')')?;
+                Ok(formula)
+            }
+            Some('t') => {
+                self.position += 1;
+                Ok(Formula::True)
+            }
+            Some('f') => {
+                self.position += 1;
+                Ok(Formula::False)
+            }
+            Some('"') => {
+                self.position += 1;
+                let length = self.input[self.position..]
+                    .iter()
+                    .position(|c| *c == '"')
+                    .ok_or(FormulaError::UnexpectedEnd)?;
+                let name: String = self.input[self.position..self.position + length]
+                    .iter()
+                    .collect();
+                self.position += length + 1;
+                let position = self
+                    .alphabet
+                    .propositions()
+                    .iter()
+                    .position(|p| *p == name)
+                    .ok_or(FormulaError::UnknownProposition(name))?;
+                Ok(Formula::Atom(position))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let digits: String = self.input[self.position..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                self.position += digits.len();
+                let position: usize = digits
+                    .parse()
+                    .map_err(|_| FormulaError::UnknownProposition(digits.clone()))?;
+                if position >= self.alphabet.propositions().len() {
+                    return Err(FormulaError::UnknownProposition(digits));
+                }
+                Ok(Formula::Atom(position))
+            }
+            Some(c) => Err(FormulaError::UnexpectedCharacter(self.position, c)),
+            None => Err(FormulaError::UnexpectedEnd),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Formula, FormulaError, PropositionalAlphabet, PropositionsError};
+
+    #[test]
+    fn formula_labels() {
+        let alphabet = PropositionalAlphabet::new(["request", "grant"]);
+        let formula = alphabet.parse("0 & !(1 | f)").unwrap();
+        assert_eq!(
+            formula,
+            alphabet.atom("request") & !(alphabet.atom("grant") | Formula::False)
+        );
+        assert_eq!(formula.to_string(), "0 & !(1 | f)");
+        assert_eq!(
+            alphabet
+                .parse(r#""request" & !"grant""#)
+                .unwrap()
+                .to_string(),
+            "0 & !1"
+        );
+        assert_eq!(
+            alphabet.parse("(0 | 1) & t").unwrap().to_string(),
+            "(0 | 1) & t"
+        );
+
+        let matching = alphabet.symbols_matching(&formula);
+        assert_eq!(matching.len(), 1);
+        assert_eq!(
+            alphabet.valuation(matching[0]),
+            ["request".to_string()].into_iter().collect()
+        );
+        assert_eq!(alphabet.symbols_matching(&Formula::True).len(), 4);
+
+        assert_eq!(alphabet.parse("0 &"), Err(FormulaError::UnexpectedEnd));
+        assert_eq!(
+            alphabet.parse("2"),
+            Err(FormulaError::UnknownProposition("2".to_string()))
+        );
+        assert_eq!(
+            alphabet.parse("0 1"),
+            Err(FormulaError::UnexpectedCharacter(2, '1'))
+        );
+    }
+
+    #[test]
+    fn canonical_propositions() {
+        let alphabet = PropositionalAlphabet::new(["request", "say \"hi\""]);
+        let written = alphabet.to_string();
+        assert_eq!(written, r#"2 "request" "say \"hi\"""#);
+        assert_eq!(written.parse(), Ok(alphabet));
+        assert_eq!(
+            "2 \"a\"".parse::<PropositionalAlphabet>(),
+            Err(PropositionsError::CountMismatch(2, 1))
+        );
+        assert_eq!(
+            "\"a\"".parse::<PropositionalAlphabet>(),
+            Err(PropositionsError::MissingCount)
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Generation of random automata and words, mainly intended for testing. Automata are generated
+//! following the model of Tabakov and Vardi: for each symbol, the number of transitions is the
+//! transition density times the number of states, and the number of accepting states is the
+//! acceptance density times the number of states. All generators are seeded, so the same
+//! configuration always produces the same automaton.
+//!
+//! With the `proptest` feature, the [`strategies`] module provides strategies for words,
+//! alphabets, DFAs and Mealy machines that can be used for property testing.
+
+use automata::{prelude::*, Set};
+use itertools::Itertools;
+
+/// A small and fast pseudo random number generator (SplitMix64). We do not need cryptographic
+/// quality, but we want generated automata to be reproducible across platforms.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a new generator from the given seed.
+    pub fn seeded(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number in `0..bound`, `bound` must not be zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "Bound must be positive");
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Returns a random number in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Picks `k` distinct elements from `0..n` uniformly at random, returned in ascending order.
+    pub fn choose(&mut self, n: usize, k: usize) -> Vec<usize> {
+        let mut pool = (0..n).collect_vec();
+        let k = k.min(n);
+        for i in 0..k {
+            let j = i + self.below(n - i);
+            pool.swap(i, j);
+        }
+        pool.truncate(k);
+        pool.sort();
+        pool
+    }
+}
+
+/// Configures the generation of random automata, see the module documentation for the model
+/// that is used. Densities are clamped so that the result is always well-defined.
+#[derive(Debug, Clone)]
+pub struct RandomAutomaton {
+    states: usize,
+    alphabet: CharAlphabet,
+    transition_density: f64,
+    acceptance_density: f64,
+    seed: u64,
+}
+
+impl RandomAutomaton {
+    /// Creates a configuration for automata with `states` states over the given alphabet. By
+    /// default, the transition density is 1.25, the acceptance density is 0.5 and the seed is 0.
+    pub fn new(states: usize, alphabet: CharAlphabet) -> Self {
+        assert!(states > 0, "Automata must have at least one state");
+        Self {
+            states,
+            alphabet,
+            transition_density: 1.25,
+            acceptance_density: 0.5,
+            seed: 0,
+        }
+    }
+
+    /// Sets the transition density, i.e. the expected number of transitions per symbol
+    /// divided by the number of states.
+    pub fn transition_density(mut self, density: f64) -> Self {
+        self.transition_density = density.max(0.0);
+        self
+    }
+
+    /// Sets the fraction of states that are accepting.
+    pub fn acceptance_density(mut self, density: f64) -> Self {
+        self.acceptance_density = density.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the seed of the random number generator.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    fn accepting(&self, rng: &mut Rng) -> Set<usize> {
+        let count = (self.acceptance_density * self.states as f64).round() as usize;
+        rng.choose(self.states, count).into_iter().collect()
+    }
+
+    /// Generates a nondeterministic automaton. For each symbol, `round(r * n)` distinct pairs of
+    /// states are connected, where `r` is the transition density and `n` the number of states.
+    /// State 0 is the initial one.
+    pub fn nfa(&self) -> NTS<CharAlphabet, bool, Void> {
+        let mut rng = Rng::seeded(self.seed);
+        let accepting = self.accepting(&mut rng);
+        let mut nfa = NTS::new_for_alphabet(self.alphabet.clone());
+        let indices = (0..self.states)
+            .map(|q| nfa.add_state(accepting.contains(&q)))
+            .collect_vec();
+        let pairs = self.states * self.states;
+        let per_symbol = (self.transition_density * self.states as f64).round() as usize;
+        for sym in self.alphabet.universe().sorted() {
+            for pair in rng.choose(pairs, per_symbol) {
+                nfa.add_edge(
+                    indices[pair / self.states],
+                    sym,
+                    indices[pair % self.states],
+                    Void,
+                );
+            }
+        }
+        nfa
+    }
+
+    /// Generates a deterministic automaton, which may be incomplete. Each state has a
+    /// transition on a symbol with probability `r / n`, where `r` is the transition density
+    /// and `n` the number of states, clamped to at most 1, so that on average each symbol has
+    /// as many transitions as in [`RandomAutomaton::nfa`]. State 0 is the initial one.
+    pub fn dfa(&self) -> DFA {
+        let mut rng = Rng::seeded(self.seed);
+        let accepting = self.accepting(&mut rng);
+        let mut dfa = DFA::new_for_alphabet(self.alphabet.clone());
+        let indices = (0..self.states)
+            .map(|q| dfa.add_state(accepting.contains(&q)))
+            .collect_vec();
+        let probability = self.transition_density.min(1.0);
+        for q in 0..self.states {
+            for sym in self.alphabet.universe().sorted() {
+                if rng.unit() < probability {
+                    let p = rng.below(self.states);
+                    dfa.add_edge(indices[q], sym, indices[p], Void);
+                }
+            }
+        }
+        dfa
+    }
+
+    /// Generates a complete deterministic automaton, i.e. a DFA where every state has a
+    /// transition on every symbol. The transition density is ignored.
+    pub fn complete_dfa(&self) -> DFA {
+        self.clone().transition_density(1.0).dfa()
+    }
+
+    /// Generates a complete Mealy machine whose outputs are drawn uniformly from `0..outputs`.
+    /// Densities are ignored.
+    pub fn mealy(&self, outputs: usize) -> MealyMachine<CharAlphabet, usize> {
+        assert!(outputs > 0, "There must be at least one output");
+        let mut rng = Rng::seeded(self.seed);
+        let mut transitions = vec![];
+        for q in 0..self.states {
+            for sym in self.alphabet.universe().sorted() {
+                transitions.push((q, sym, rng.below(outputs), rng.below(self.states)));
+            }
+        }
+        NTS::builder()
+            .default_color(())
+            .with_transitions(transitions)
+            .deterministic()
+            .with_initial(0)
+            .into_mealy()
+    }
+}
+
+/// Generates a random word of the given length over `alphabet`.
+pub fn random_word(alphabet: &CharAlphabet, length: usize, rng: &mut Rng) -> Vec<char> {
+    let symbols = alphabet.universe().sorted().collect_vec();
+    (0..length)
+        .map(|_| symbols[rng.below(symbols.len())])
+        .collect()
+}
+
+/// Generates `count` random words whose lengths are uniformly distributed in
+/// `0..=max_length`.
+pub fn random_words(
+    alphabet: &CharAlphabet,
+    count: usize,
+    max_length: usize,
+    seed: u64,
+) -> Vec<Vec<char>> {
+    let mut rng = Rng::seeded(seed);
+    (0..count)
+        .map(|_| {
+            let length = rng.below(max_length + 1);
+            random_word(alphabet, length, &mut rng)
+        })
+        .collect()
+}
+
+/// Strategies for property testing with `proptest`. Since the word and automaton types are
+/// defined in the `automata` crate, we can not implement [`proptest::arbitrary::Arbitrary`] for
+/// them directly. Instead, the wrappers [`ArbitraryDfa`], [`ArbitraryMealy`] and
+/// [`ArbitraryWord`] implement it with reasonable defaults, and the `arb_*` functions give
+/// control over the sizes.
+#[cfg(feature = "proptest")]
+pub mod strategies {
+    use automata::prelude::*;
+    use proptest::prelude::*;
+
+    use super::RandomAutomaton;
+
+    /// Generates alphabets of the letters `a`, `b`, ... with between 1 and `max_size` symbols.
+    pub fn arb_alphabet(max_size: usize) -> impl Strategy<Value = CharAlphabet> {
+        assert!(
+            (1..=26).contains(&max_size),
+            "Alphabets have 1 to 26 symbols"
+        );
+        (1..=max_size).prop_map(|size| ('a'..='z').take(size).collect::<Vec<_>>().into())
+    }
+
+    /// Generates words over `alphabet` of length at most `max_length`.
+    pub fn arb_word(alphabet: CharAlphabet, max_length: usize) -> impl Strategy<Value = Vec<char>> {
+        let symbols: Vec<char> = alphabet.universe().collect();
+        proptest::collection::vec(proptest::sample::select(symbols), 0..=max_length)
+    }
+
+    /// Generates DFAs over `alphabet` with between 1 and `max_states` states. If `complete`
+    /// is set, all generated DFAs are complete.
+    pub fn arb_dfa(
+        alphabet: CharAlphabet,
+        max_states: usize,
+        complete: bool,
+    ) -> impl Strategy<Value = DFA> {
+        (1..=max_states, 0.0..=1.0f64, 0.5..=2.0f64, any::<u64>()).prop_map(
+            move |(states, acceptance, transitions, seed)| {
+                let config = RandomAutomaton::new(states, alphabet.clone())
+                    .acceptance_density(acceptance)
+                    .transition_density(transitions)
+                    .seed(seed);
+                if complete {
+                    config.complete_dfa()
+                } else {
+                    config.dfa()
+                }
+            },
+        )
+    }
+
+    /// Generates complete Mealy machines over `alphabet` with between 1 and `max_states`
+    /// states and outputs in `0..outputs`.
+    pub fn arb_mealy(
+        alphabet: CharAlphabet,
+        max_states: usize,
+        outputs: usize,
+    ) -> impl Strategy<Value = MealyMachine<CharAlphabet, usize>> {
+        (1..=max_states, any::<u64>()).prop_map(move |(states, seed)| {
+            RandomAutomaton::new(states, alphabet.clone())
+                .seed(seed)
+                .mealy(outputs)
+        })
+    }
+
+    /// A complete DFA with at most 8 states over an alphabet of at most 3 symbols.
+    #[derive(Debug, Clone)]
+    pub struct ArbitraryDfa(pub DFA);
+
+    impl Arbitrary for ArbitraryDfa {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            arb_alphabet(3)
+                .prop_flat_map(|alphabet| arb_dfa(alphabet, 8, true))
+                .prop_map(ArbitraryDfa)
+                .boxed()
+        }
+    }
+
+    /// A complete Mealy machine with at most 8 states over an alphabet of at most 3 symbols
+    /// and with outputs in `0..3`.
+    #[derive(Debug, Clone)]
+    pub struct ArbitraryMealy(pub MealyMachine<CharAlphabet, usize>);
+
+    impl Arbitrary for ArbitraryMealy {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            arb_alphabet(3)
+                .prop_flat_map(|alphabet| arb_mealy(alphabet, 8, 3))
+                .prop_map(ArbitraryMealy)
+                .boxed()
+        }
+    }
+
+    /// A word of length at most 12 over the alphabet `{a, b, c}`.
+    #[derive(Debug, Clone)]
+    pub struct ArbitraryWord(pub Vec<char>);
+
+    impl Arbitrary for ArbitraryWord {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            arb_word(alphabet!(simple 'a', 'b', 'c'), 12)
+                .prop_map(ArbitraryWord)
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{random_words, RandomAutomaton, Rng};
+
+    #[test]
+    fn random_generation_is_reproducible() {
+        let config = RandomAutomaton::new(10, alphabet!(simple 'a', 'b')).seed(42);
+        let (first, second) = (config.complete_dfa(), config.complete_dfa());
+        assert_eq!(first.size(), 10);
+        for word in random_words(&alphabet!(simple 'a', 'b'), 50, 10, 7) {
+            assert_eq!(first.accepts(&word), second.accepts(&word));
+        }
+        // all states have a successor on every symbol
+        for q in first.state_indices() {
+            assert_eq!(first.edges_from(q).unwrap().count(), 2);
+        }
+        // a different seed eventually gives a different automaton
+        assert!((0..10).any(|seed| {
+            let other = config.clone().seed(seed).complete_dfa();
+            random_words(&alphabet!(simple 'a', 'b'), 50, 10, 7)
+                .iter()
+                .any(|w| other.accepts(w) != first.accepts(w))
+        }));
+    }
+
+    #[test]
+    fn random_nfa_densities() {
+        let nfa = RandomAutomaton::new(8, alphabet!(simple 'a', 'b'))
+            .transition_density(1.5)
+            .acceptance_density(0.25)
+            .seed(3)
+            .nfa();
+        let edges: usize = nfa
+            .state_indices()
+            .map(|q| nfa.edges_from(q).unwrap().count())
+            .sum();
+        assert_eq!(edges, 2 * 12);
+        assert_eq!(
+            nfa.state_indices()
+                .filter(|q| nfa.state_color(*q) == Some(true))
+                .count(),
+            2
+        );
+
+        let mut rng = Rng::seeded(1);
+        let chosen = rng.choose(10, 4);
+        assert_eq!(chosen.len(), 4);
+        assert!(chosen.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,480 @@
+//! Symbolic automata, whose edges are labelled with predicates of an effective Boolean algebra
+//! instead of single symbols. This handles alphabets that are too large to enumerate, such as
+//! all unicode characters in string analysis, since the constructions only ever combine
+//! predicates and ask whether they are satisfiable. [`CharSet`] provides such an algebra for
+//! sets of characters that are given by ranges.
+
+use std::{collections::VecDeque, fmt::Debug, hash::Hash};
+
+use automata::{Map, Set};
+use itertools::Itertools;
+
+/// An effective Boolean algebra, whose predicates denote sets of elements. All operations
+/// must be computable, in particular whether a predicate is satisfiable, i.e. denotes a
+/// non-empty set.
+pub trait BooleanAlgebra {
+    /// The elements, i.e. the symbols of the alphabet.
+    type Element: Clone + Debug;
+    /// The predicates, i.e. the labels of edges.
+    type Predicate: Clone + Debug + Eq + Hash;
+
+    /// Returns the predicate that holds for every element.
+    fn top(&self) -> Self::Predicate;
+    /// Returns the conjunction of `left` and `right`.
+    fn and(&self, left: &Self::Predicate, right: &Self::Predicate) -> Self::Predicate;
+    /// Returns the disjunction of `left` and `right`.
+    fn or(&self, left: &Self::Predicate, right: &Self::Predicate) -> Self::Predicate;
+    /// Returns the negation of `predicate`.
+    fn not(&self, predicate: &Self::Predicate) -> Self::Predicate;
+    /// Returns an element that satisfies `predicate`, or `None` if it is unsatisfiable.
+    fn witness(&self, predicate: &Self::Predicate) -> Option<Self::Element>;
+    /// Returns true if `element` satisfies `predicate`.
+    fn contains(&self, predicate: &Self::Predicate, element: &Self::Element) -> bool;
+
+    /// Returns true if some element satisfies `predicate`.
+    fn is_satisfiable(&self, predicate: &Self::Predicate) -> bool {
+        self.witness(predicate).is_some()
+    }
+
+    /// Returns the satisfiable conjunctions of every predicate in `predicates` or its negation,
+    /// which partition the elements such that each predicate is constant on every part.
+    fn minterms(&self, predicates: &[Self::Predicate]) -> Vec<Self::Predicate> {
+        predicates.iter().fold(vec![self.top()], |minterms, p| {
+            let negated = self.not(p);
+            minterms
+                .iter()
+                .flat_map(|m| [self.and(m, p), self.and(m, &negated)])
+                .filter(|m| self.is_satisfiable(m))
+                .collect()
+        })
+    }
+}
+
+/// The code points of all characters, which excludes the surrogates.
+const CHARACTERS: [(u32, u32); 2] = [(0, 0xD7FF), (0xE000, 0x10FFFF)];
+
+/// A set of characters, given by disjoint and non-adjacent ranges of code points in ascending
+/// order. The sets of characters form an effective Boolean algebra, where the algebra itself is
+/// the unit [`CharAlgebra`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CharSet {
+    ranges: Vec<(u32, u32)>,
+}
+
+impl CharSet {
+    /// Brings arbitrary ranges into normal form by sorting and merging them, and removes the
+    /// surrogates.
+    fn normalized(mut ranges: Vec<(u32, u32)>) -> Self {
+        ranges.sort();
+        let mut merged: Vec<(u32, u32)> = vec![];
+        for (from, to) in ranges {
+            match merged.last_mut() {
+                Some((_, end)) if from <= end.saturating_add(1) => *end = (*end).max(to),
+                _ => merged.push((from, to)),
+            }
+        }
+        Self { ranges: merged }.intersection(&Self::all())
+    }
+
+    /// Returns the set of all characters.
+    pub fn all() -> Self {
+        Self {
+            ranges: CHARACTERS.to_vec(),
+        }
+    }
+
+    /// Returns the characters from `from` to `to`, both inclusive.
+    pub fn range(from: char, to: char) -> Self {
+        Self::normalized(vec![(from.into(), to.into())])
+    }
+
+    /// Returns the set that only contains `c`.
+    pub fn single(c: char) -> Self {
+        Self::range(c, c)
+    }
+
+    /// Returns true if the set contains `c`.
+    pub fn contains(&self, c: char) -> bool {
+        let c = u32::from(c);
+        self.ranges.iter().any(|(from, to)| *from <= c && c <= *to)
+    }
+
+    /// Returns true if the set contains no character.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns the smallest character in the set.
+    pub fn first(&self) -> Option<char> {
+        self.ranges
+            .first()
+            .and_then(|(from, _)| char::from_u32(*from))
+    }
+
+    /// Returns the characters that are contained in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = self.ranges[i];
+            let (c, d) = other.ranges[j];
+            if a.max(c) <= b.min(d) {
+                ranges.push((a.max(c), b.min(d)));
+            }
+            if b < d {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Returns the characters that are contained in one of the sets.
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized(self.ranges.iter().chain(&other.ranges).cloned().collect())
+    }
+
+    /// Returns the characters that are not contained in the set.
+    pub fn complement(&self) -> Self {
+        let mut gaps = vec![];
+        let mut next = 0u32;
+        for (from, to) in &self.ranges {
+            if *from > next {
+                gaps.push((next, from - 1));
+            }
+            next = to + 1;
+        }
+        if next <= 0x10FFFF {
+            gaps.push((next, 0x10FFFF));
+        }
+        Self { ranges: gaps }.intersection(&Self::all())
+    }
+}
+
+/// The effective Boolean algebra of [`CharSet`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CharAlgebra;
+
+impl BooleanAlgebra for CharAlgebra {
+    type Element = char;
+    type Predicate = CharSet;
+
+    fn top(&self) -> CharSet {
+        CharSet::all()
+    }
+
+    fn and(&self, left: &CharSet, right: &CharSet) -> CharSet {
+        left.intersection(right)
+    }
+
+    fn or(&self, left: &CharSet, right: &CharSet) -> CharSet {
+        left.union(right)
+    }
+
+    fn not(&self, predicate: &CharSet) -> CharSet {
+        predicate.complement()
+    }
+
+    fn witness(&self, predicate: &CharSet) -> Option<char> {
+        predicate.first()
+    }
+
+    fn contains(&self, predicate: &CharSet, element: &char) -> bool {
+        predicate.contains(*element)
+    }
+
+    fn is_satisfiable(&self, predicate: &CharSet) -> bool {
+        !predicate.is_empty()
+    }
+}
+
+/// A finite automaton over an effective Boolean algebra `B`, whose edges are labelled with
+/// predicates. A word is read by taking an edge whose predicate is satisfied by the next
+/// element, and is accepted if some run ends in an accepting state. The automaton is
+/// deterministic if the predicates of the edges leaving each state are pairwise disjoint.
+#[derive(Debug, Clone)]
+pub struct SymbolicAutomaton<B: BooleanAlgebra> {
+    algebra: B,
+    accepting: Vec<bool>,
+    edges: Vec<Vec<(B::Predicate, usize)>>,
+    initial: usize,
+}
+
+impl<B: BooleanAlgebra + Clone> SymbolicAutomaton<B> {
+    /// Creates an automaton without states. The first state that is added is initial, unless
+    /// [`Self::set_initial`] is used.
+    pub fn new(algebra: B) -> Self {
+        Self {
+            algebra,
+            accepting: vec![],
+            edges: vec![],
+            initial: 0,
+        }
+    }
+
+    /// Returns the Boolean algebra of the predicates.
+    pub fn algebra(&self) -> &B {
+        &self.algebra
+    }
+
+    /// Adds a state and returns its index.
+    pub fn add_state(&mut self, accepting: bool) -> usize {
+        self.accepting.push(accepting);
+        self.edges.push(vec![]);
+        self.accepting.len() - 1
+    }
+
+    /// Adds an edge from `source` to `target` that is labelled with `predicate`. If there
+    /// already is an edge between the two states, its predicate is extended instead. Edges
+    /// with unsatisfiable predicates are dropped.
+    pub fn add_edge(&mut self, source: usize, predicate: B::Predicate, target: usize) {
+        assert!(target < self.size(), "State {target} does not exist");
+        if !self.algebra.is_satisfiable(&predicate) {
+            return;
+        }
+        match self.edges[source].iter_mut().find(|(_, t)| *t == target) {
+            Some((existing, _)) => *existing = self.algebra.or(existing, &predicate),
+            None => self.edges[source].push((predicate, target)),
+        }
+    }
+
+    /// Makes `state` the initial state.
+    pub fn set_initial(&mut self, state: usize) {
+        assert!(state < self.size(), "State {state} does not exist");
+        self.initial = state;
+    }
+
+    /// Returns the initial state.
+    pub fn initial(&self) -> usize {
+        self.initial
+    }
+
+    /// Returns the number of states.
+    pub fn size(&self) -> usize {
+        self.accepting.len()
+    }
+
+    /// Returns true if `state` is accepting.
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting[state]
+    }
+
+    /// Returns the edges leaving `state` as pairs of a predicate and a target.
+    pub fn edges_from(&self, state: usize) -> &[(B::Predicate, usize)] {
+        &self.edges[state]
+    }
+
+    /// Returns the states that can be reached from one of `states` on `element`.
+    fn successors(&self, states: &Set<usize>, element: &B::Element) -> Set<usize> {
+        states
+            .iter()
+            .flat_map(|q| &self.edges[*q])
+            .filter(|(p, _)| self.algebra.contains(p, element))
+            .map(|(_, t)| *t)
+            .collect()
+    }
+
+    /// Returns true if the automaton accepts `word`.
+    pub fn accepts<I: IntoIterator<Item = B::Element>>(&self, word: I) -> bool {
+        if self.size() == 0 {
+            return false;
+        }
+        let initial: Set<usize> = [self.initial].into_iter().collect();
+        word.into_iter()
+            .fold(initial, |states, element| {
+                self.successors(&states, &element)
+            })
+            .iter()
+            .any(|q| self.accepting[*q])
+    }
+
+    /// Returns true if the predicates of the edges leaving each state are pairwise disjoint.
+    pub fn is_deterministic(&self) -> bool {
+        self.edges.iter().all(|edges| {
+            edges
+                .iter()
+                .tuple_combinations()
+                .all(|((p, _), (q, _))| !self.algebra.is_satisfiable(&self.algebra.and(p, q)))
+        })
+    }
+
+    /// Returns a shortest accepted word, whose elements are witnesses of the predicates along
+    /// a shortest accepting path, or `None` if the language is empty.
+    pub fn accepted_word(&self) -> Option<Vec<B::Element>> {
+        if self.size() == 0 {
+            return None;
+        }
+        let mut words: Map<usize, Vec<B::Element>> = [(self.initial, vec![])].into_iter().collect();
+        let mut queue = VecDeque::from([self.initial]);
+        while let Some(q) = queue.pop_front() {
+            if self.accepting[q] {
+                return words.remove(&q);
+            }
+            for (predicate, target) in &self.edges[q] {
+                if words.contains_key(target) {
+                    continue;
+                }
+                let Some(element) = self.algebra.witness(predicate) else {
+                    continue;
+                };
+                let mut word = words[&q].clone();
+                word.push(element);
+                words.insert(*target, word);
+                queue.push_back(*target);
+            }
+        }
+        None
+    }
+
+    /// Returns true if no word is accepted.
+    pub fn is_empty(&self) -> bool {
+        self.accepted_word().is_none()
+    }
+
+    /// Builds an equivalent deterministic and complete automaton with the subset construction.
+    /// The edges leaving a set of states are given by the minterms of the predicates of their
+    /// edges, so each minterm leads to a single set. The empty set is a rejecting sink.
+    pub fn determinize(&self) -> Self {
+        let mut out = Self::new(self.algebra.clone());
+        if self.size() == 0 {
+            let sink = out.add_state(false);
+            out.add_edge(sink, self.algebra.top(), sink);
+            return out;
+        }
+        let start: Vec<usize> = vec![self.initial];
+        let mut index: Map<Vec<usize>, usize> = Map::default();
+        index.insert(start.clone(), out.add_state(self.accepting[self.initial]));
+        let mut queue = VecDeque::from([start]);
+        while let Some(subset) = queue.pop_front() {
+            let source = index[&subset];
+            let edges = subset.iter().flat_map(|q| &self.edges[*q]).collect_vec();
+            let predicates = edges.iter().map(|(p, _)| p.clone()).unique().collect_vec();
+            for minterm in self.algebra.minterms(&predicates) {
+                // every predicate either contains the minterm or is disjoint from it
+                let target = edges
+                    .iter()
+                    .filter(|(p, _)| self.algebra.is_satisfiable(&self.algebra.and(p, &minterm)))
+                    .map(|(_, t)| *t)
+                    .sorted()
+                    .dedup()
+                    .collect_vec();
+                let target = match index.get(&target) {
+                    Some(q) => *q,
+                    None => {
+                        let accepting = target.iter().any(|q| self.accepting[*q]);
+                        let q = out.add_state(accepting);
+                        index.insert(target.clone(), q);
+                        queue.push_back(target);
+                        q
+                    }
+                };
+                out.add_edge(source, minterm, target);
+            }
+        }
+        out
+    }
+
+    /// Returns an automaton that accepts exactly the words that are rejected by this one. It
+    /// is obtained by swapping the accepting states of the [determinization](Self::determinize).
+    pub fn complement(&self) -> Self {
+        let mut out = self.determinize();
+        out.accepting.iter_mut().for_each(|a| *a = !*a);
+        out
+    }
+
+    /// Returns the product automaton that accepts the intersection of both languages. Its
+    /// edges are labelled with the conjunctions of the predicates of the components, and only
+    /// satisfiable conjunctions are kept. Only the part that is reachable from the pair of
+    /// initial states is built.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut out = Self::new(self.algebra.clone());
+        if self.size() == 0 || other.size() == 0 {
+            return out;
+        }
+        let start = (self.initial, other.initial);
+        let mut index: Map<(usize, usize), usize> = Map::default();
+        index.insert(
+            start,
+            out.add_state(self.accepting[start.0] && other.accepting[start.1]),
+        );
+        let mut queue = VecDeque::from([start]);
+        while let Some((p, q)) = queue.pop_front() {
+            let source = index[&(p, q)];
+            for ((left, s), (right, t)) in self.edges[p].iter().cartesian_product(&other.edges[q]) {
+                let predicate = self.algebra.and(left, right);
+                if !self.algebra.is_satisfiable(&predicate) {
+                    continue;
+                }
+                let target = *index.entry((*s, *t)).or_insert_with(|| {
+                    queue.push_back((*s, *t));
+                    out.add_state(self.accepting[*s] && other.accepting[*t])
+                });
+                out.add_edge(source, predicate, target);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BooleanAlgebra, CharAlgebra, CharSet, SymbolicAutomaton};
+
+    #[test]
+    fn char_sets() {
+        let digits = CharSet::range('0', '9');
+        let letters = CharSet::range('a', 'z').union(&CharSet::range('A', 'Z'));
+        assert!(digits.intersection(&letters).is_empty());
+        assert!(digits.complement().contains('a'));
+        assert!(!digits.complement().contains('5'));
+        assert_eq!(digits.complement().complement(), digits);
+        assert_eq!(
+            CharSet::range('a', 'c').union(&CharSet::range('d', 'f')),
+            CharSet::range('a', 'f')
+        );
+        // the surrogates are not characters
+        assert_eq!(
+            CharSet::all().complement(),
+            CharSet::default(),
+            "The complement of all characters is empty"
+        );
+        let minterms = CharAlgebra.minterms(&[digits.clone(), CharSet::range('5', 'z')]);
+        assert_eq!(minterms.len(), 4);
+    }
+
+    #[test]
+    fn symbolic_automata() {
+        // non-empty sequences of digits
+        let mut numbers = SymbolicAutomaton::new(CharAlgebra);
+        let q0 = numbers.add_state(false);
+        let q1 = numbers.add_state(true);
+        numbers.add_edge(q0, CharSet::range('0', '9'), q1);
+        numbers.add_edge(q1, CharSet::range('0', '9'), q1);
+        assert!(numbers.is_deterministic());
+        assert!(numbers.accepts("2024".chars()));
+        assert!(!numbers.accepts("20x4".chars()));
+        assert_eq!(numbers.accepted_word(), Some(vec!['0']));
+
+        // words that start with 1 and end with 0, over all characters
+        let mut ends = SymbolicAutomaton::new(CharAlgebra);
+        let p0 = ends.add_state(false);
+        let p1 = ends.add_state(false);
+        let p2 = ends.add_state(true);
+        ends.add_edge(p0, CharSet::single('1'), p1);
+        ends.add_edge(p1, CharSet::all(), p1);
+        ends.add_edge(p1, CharSet::single('0'), p2);
+        assert!(!ends.is_deterministic());
+
+        let both = numbers.intersection(&ends);
+        assert!(both.accepts("1230".chars()));
+        assert!(!both.accepts("1a0".chars()));
+        assert_eq!(both.accepted_word(), Some(vec!['1', '0']));
+
+        let complement = ends.complement();
+        assert!(complement.is_deterministic());
+        assert!(complement.accepts("1a".chars()));
+        assert!(!complement.accepts("1a0".chars()));
+        assert!(numbers.intersection(&numbers.complement()).is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+//! Words over text, so that automata can be run over real-world strings without tokenizing
+//! them by hand. A [`TextWord`] splits a string into segments, which are either its characters
+//! or, with the `graphemes` feature, its extended grapheme clusters, i.e. what a reader
+//! perceives as a single character such as `é` written as `e` followed by a combining accent.
+//! The segments that occur in a set of texts form a [`TextAlphabet`], which encodes each of
+//! them as a symbol of a [`CharAlphabet`].
+
+use automata::prelude::*;
+use itertools::Itertools;
+
+/// The first symbol that is used for encoding segments that consist of several characters,
+/// which lies in an unassigned plane of unicode.
+const FIRST_CLUSTER_SYMBOL: u32 = 0x40000;
+
+/// Determines how a text is split into the symbols of a [`TextWord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Segmentation {
+    /// Every character is a symbol.
+    #[default]
+    Chars,
+    /// Every extended grapheme cluster is a symbol.
+    #[cfg(feature = "graphemes")]
+    Graphemes,
+}
+
+/// A finite word that borrows a text and whose symbols are its segments, see the module
+/// documentation. Lengths and positions refer to segments, not to bytes or characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextWord<'a> {
+    text: &'a str,
+    /// The byte offsets at which segments start, followed by the length of the text.
+    boundaries: Vec<usize>,
+}
+
+impl<'a> TextWord<'a> {
+    /// Splits `text` into segments according to `segmentation`.
+    pub fn new(text: &'a str, segmentation: Segmentation) -> Self {
+        let mut boundaries = match segmentation {
+            Segmentation::Chars => text.char_indices().map(|(i, _)| i).collect_vec(),
+            #[cfg(feature = "graphemes")]
+            Segmentation::Graphemes => {
+                use unicode_segmentation::UnicodeSegmentation;
+                text.grapheme_indices(true).map(|(i, _)| i).collect_vec()
+            }
+        };
+        boundaries.push(text.len());
+        Self { text, boundaries }
+    }
+
+    /// Returns the text.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.boundaries.len() - 1
+    }
+
+    /// Returns true if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the segment at position `n`, if there is one.
+    pub fn nth(&self, n: usize) -> Option<&'a str> {
+        let (from, to) = (*self.boundaries.get(n)?, *self.boundaries.get(n + 1)?);
+        Some(&self.text[from..to])
+    }
+
+    /// Returns the segments in order.
+    pub fn segments(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.boundaries
+            .iter()
+            .tuple_windows()
+            .map(|(from, to)| &self.text[*from..*to])
+    }
+
+    /// Returns the alphabet of the segments that occur in the text.
+    pub fn alphabet(&self) -> TextAlphabet {
+        TextAlphabet::new(self.segments())
+    }
+
+    /// Returns the word of symbols that encode the segments in `alphabet`, or `None` if a
+    /// segment is not contained in it.
+    pub fn symbols(&self, alphabet: &TextAlphabet) -> Option<Vec<char>> {
+        self.segments().map(|s| alphabet.symbol(s)).collect()
+    }
+}
+
+/// A finite set of segments of texts, each of which is encoded as a symbol. A segment that
+/// consists of a single character is its own symbol, so for [`Segmentation::Chars`] the
+/// symbols are just the characters. The `i`-th segment in sorted order that consists of
+/// several characters is encoded as the symbol with code point `0x40000 + i`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TextAlphabet {
+    segments: Vec<String>,
+    clusters: Vec<String>,
+}
+
+impl TextAlphabet {
+    /// Creates the alphabet of the given segments, which may contain repetitions.
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(segments: I) -> Self {
+        let segments = segments
+            .into_iter()
+            .map(Into::into)
+            .sorted()
+            .dedup()
+            .collect_vec();
+        let clusters = segments
+            .iter()
+            .filter(|s| s.chars().count() > 1)
+            .cloned()
+            .collect();
+        Self { segments, clusters }
+    }
+
+    /// Returns the alphabet of all segments that occur in one of `words`.
+    pub fn of_words<'a, I: IntoIterator<Item = &'a TextWord<'a>>>(words: I) -> Self {
+        Self::new(words.into_iter().flat_map(|w| w.segments()))
+    }
+
+    /// Returns the segments in sorted order.
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Returns the symbol that encodes `segment`, or `None` if it is not contained.
+    pub fn symbol(&self, segment: &str) -> Option<char> {
+        let mut chars = segment.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => self
+                .segments
+                .binary_search_by(|s| s.as_str().cmp(segment))
+                .ok()
+                .map(|_| c),
+            _ => {
+                let index = self
+                    .clusters
+                    .binary_search_by(|s| s.as_str().cmp(segment))
+                    .ok()?;
+                char::from_u32(FIRST_CLUSTER_SYMBOL + index as u32)
+            }
+        }
+    }
+
+    /// Returns the segment that is encoded by `symbol`, if there is one.
+    pub fn segment(&self, symbol: char) -> Option<&str> {
+        match u32::from(symbol).checked_sub(FIRST_CLUSTER_SYMBOL) {
+            Some(index) if (index as usize) < self.clusters.len() => {
+                Some(&self.clusters[index as usize])
+            }
+            _ => {
+                let segment = symbol.to_string();
+                self.segments
+                    .iter()
+                    .find(|s| **s == segment)
+                    .map(|s| s.as_str())
+            }
+        }
+    }
+
+    /// Returns the alphabet of the symbols of all segments.
+    pub fn alphabet(&self) -> CharAlphabet {
+        CharAlphabet::from_iter(
+            self.segments
+                .iter()
+                .map(|s| self.symbol(s).expect("Segment must be contained")),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::prelude::*;
+
+    use super::{Segmentation, TextAlphabet, TextWord};
+
+    #[test]
+    fn text_words() {
+        // an e followed by a combining acute accent, which are two characters
+        let text = "cafe\u{301}!";
+        let word = TextWord::new(text, Segmentation::Chars);
+        assert_eq!(word.len(), 6);
+        assert_eq!(word.nth(4), Some("\u{301}"));
+        assert_eq!(word.nth(6), None);
+        let alphabet = word.alphabet();
+        assert_eq!(alphabet.segments().len(), 6);
+        assert_eq!(word.symbols(&alphabet), Some(text.chars().collect()));
+
+        // words ending with an exclamation mark
+        let mut dfa = DFA::new_for_alphabet(alphabet.alphabet());
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        for sym in alphabet.alphabet().universe() {
+            let target = if sym == '!' { q1 } else { q0 };
+            dfa.add_edge(q0, sym, target, Void);
+            dfa.add_edge(q1, sym, target, Void);
+        }
+        let symbols = word.symbols(&alphabet).unwrap();
+        assert_eq!(dfa.reached_state_index(&symbols), Some(q1));
+
+        let clusters = TextAlphabet::new(["a", "e\u{301}"]);
+        let encoded = clusters.symbol("e\u{301}").unwrap();
+        assert_eq!(clusters.segment(encoded), Some("e\u{301}"));
+        assert_eq!(clusters.symbol("a"), Some('a'));
+        assert_eq!(clusters.symbol("b"), None);
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn grapheme_words() {
+        let word = TextWord::new("cafe\u{301}!", Segmentation::Graphemes);
+        assert_eq!(word.len(), 5);
+        assert_eq!(word.nth(3), Some("e\u{301}"));
+        let alphabet = word.alphabet();
+        let symbols = word.symbols(&alphabet).unwrap();
+        assert_eq!(symbols.len(), 5);
+        assert_eq!(alphabet.segment(symbols[3]), Some("e\u{301}"));
+    }
+}
@@ -0,0 +1,116 @@
+//! Transition systems as plain sequences of transitions, i.e. quadruples of source, expression,
+//! edge color and target. This allows collecting, comparing and rebuilding automata with
+//! iterator adapters without knowing the backend that stores them. Transitions are yielded in
+//! the stable order of [`SortedTransitionSystem`], so two transition systems with the same
+//! transitions yield them in the same order.
+
+use automata::{prelude::*, Map};
+
+use crate::ordered::SortedTransitionSystem;
+
+type ExpressionOf<Ts> = <<Ts as TransitionSystem>::Alphabet as Alphabet>::Expression;
+
+/// A transition of `Ts`, consisting of source, expression, edge color and target.
+pub type Transition<Ts> = (
+    <Ts as TransitionSystem>::StateIndex,
+    ExpressionOf<Ts>,
+    <Ts as TransitionSystem>::EdgeColor,
+    <Ts as TransitionSystem>::StateIndex,
+);
+
+/// Iteration over all transitions, see the module documentation.
+pub trait Transitions: TransitionSystem + Sized {
+    /// Returns an iterator over the transitions of `self`.
+    fn transitions(&self) -> impl Iterator<Item = Transition<Self>> + '_
+    where
+        ExpressionOf<Self>: Ord,
+    {
+        self.sorted_edges()
+            .into_iter()
+            .map(|(source, (expression, color, target))| (source, expression, color, target))
+    }
+
+    /// Consumes `self` and returns its transitions.
+    fn into_transitions(self) -> std::vec::IntoIter<Transition<Self>>
+    where
+        ExpressionOf<Self>: Ord,
+    {
+        self.transitions().collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl<Ts: TransitionSystem> Transitions for Ts {}
+
+/// Builds a transition system over `alphabet` with the given colored states and transitions,
+/// which refer to states by arbitrary indices. States are added in the order in which they are
+/// given, and the index of each of them in the result is returned as well. Panics if a
+/// transition uses a state that is not given.
+#[allow(clippy::type_complexity)]
+pub fn from_transitions<Idx, Q, C, S, T>(
+    alphabet: CharAlphabet,
+    states: S,
+    transitions: T,
+) -> (NTS<CharAlphabet, Q, C>, Map<Idx, usize>)
+where
+    Idx: IndexType,
+    Q: Color,
+    C: Color,
+    S: IntoIterator<Item = (Idx, Q)>,
+    T: IntoIterator<Item = (Idx, char, C, Idx)>,
+{
+    let mut ts = NTS::new_for_alphabet(alphabet);
+    let mut index = Map::default();
+    for (q, color) in states {
+        index.insert(q, ts.add_state(color));
+    }
+    for (source, sym, color, target) in transitions {
+        let (p, q) = (
+            *index.get(&source).expect("Source must be given"),
+            *index.get(&target).expect("Target must be given"),
+        );
+        ts.add_edge(p, sym, q, color);
+    }
+    (ts, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::{prelude::*, Set};
+
+    use super::{from_transitions, Transitions};
+
+    #[test]
+    fn transitions() {
+        let mut dfa = DFA::new_for_alphabet(alphabet!(simple 'a', 'b'));
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_edge(q0, 'b', q0, Void);
+        dfa.add_edge(q0, 'a', q1, Void);
+        dfa.add_edge(q1, 'a', q0, Void);
+        assert_eq!(
+            dfa.transitions().collect::<Vec<_>>(),
+            vec![
+                (q0, 'a', Void, q1),
+                (q0, 'b', Void, q0),
+                (q1, 'a', Void, q0)
+            ]
+        );
+
+        // rebuild the automaton with a b-loop on q1 and compare the transitions
+        let states = dfa
+            .state_indices()
+            .map(|q| (q, dfa.state_color(q).unwrap()));
+        let transitions = dfa.transitions().chain([(q1, 'b', Void, q1)]);
+        let (rebuilt, index) = from_transitions(dfa.alphabet().clone(), states, transitions);
+        let old: Set<_> = dfa
+            .into_transitions()
+            .map(|(p, a, _, q)| (index[&p], a, index[&q]))
+            .collect();
+        let new: Set<_> = rebuilt
+            .transitions()
+            .map(|(p, a, _, q)| (p, a, q))
+            .collect();
+        let added = new.difference(&old).collect::<Vec<_>>();
+        assert_eq!(added, vec![&(index[&q1], 'b', index[&q1])]);
+    }
+}
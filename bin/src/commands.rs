@@ -0,0 +1,250 @@
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, Context};
+use automata::{prelude::*, Map};
+use automata_learning::{
+    analysis::{Bisimulation, Completeness},
+    formats::{DotOptions, GraphMlGraph, HoaEncoding, Render, ToDot, ToHoa, ToTikz},
+    passive::{dfa_rpni, dpa_rpni, FiniteSample, OmegaSample},
+};
+use clap::ArgMatches;
+use tracing::{debug, info};
+
+use crate::io::{
+    from_file_or_stdin, read_dfa, read_hoa, to_file_or_stdout, write_dfa, Format, HoaInput,
+};
+
+/// Determines the input format of the subcommand, which defaults to DOT.
+fn input_format(matches: &ArgMatches, input: &str) -> anyhow::Result<Format> {
+    match Format::determine(
+        matches.get_one(&format!("{input}-format")),
+        matches.get_one(input),
+    ) {
+        Some(format) => Ok(format),
+        None if matches
+            .get_one::<String>(&format!("{input}-format"))
+            .is_some() =>
+        {
+            Err(anyhow!("Unknown input format"))
+        }
+        None => Ok(Format::Dot),
+    }
+}
+
+/// Determines the output format of the subcommand, which defaults to DOT.
+fn output_format(matches: &ArgMatches) -> anyhow::Result<Format> {
+    match Format::determine(matches.get_one("to"), matches.get_one("output")) {
+        Some(format) => Ok(format),
+        None if matches.get_one::<String>("to").is_some() => Err(anyhow!("Unknown output format")),
+        None => Ok(Format::Dot),
+    }
+}
+
+fn read_input_dfa(matches: &ArgMatches, input: &str) -> anyhow::Result<DFA> {
+    let format = input_format(matches, input)?;
+    let dfa = read_dfa(&from_file_or_stdin(matches.get_one(input)), format)?;
+    debug!("Read DFA with {} states in {format:?} format", dfa.size());
+    Ok(dfa)
+}
+
+fn write_output_dfa(matches: &ArgMatches, dfa: &DFA) -> anyhow::Result<()> {
+    let output = write_dfa(dfa, output_format(matches)?)?;
+    to_file_or_stdout(matches.get_one("output"), &output)
+}
+
+/// Copies the part of `ts` that is reachable from `initial` into a new DFA, whose initial state
+/// is 0. The transition system must be deterministic.
+fn reachable_dfa<Ts>(ts: &Ts, initial: Ts::StateIndex) -> DFA
+where
+    Ts: TransitionSystem<Alphabet = CharAlphabet, StateColor = bool>,
+{
+    let mut dfa = DFA::new_for_alphabet(ts.alphabet().clone());
+    let mut indices = Map::default();
+    indices.insert(
+        initial,
+        dfa.add_state(ts.state_color(initial).expect("State must exist")),
+    );
+    let mut queue = VecDeque::from([initial]);
+    while let Some(q) = queue.pop_front() {
+        for e in ts.edges_from(q).expect("State must exist") {
+            let target = match indices.get(&e.target()) {
+                Some(idx) => *idx,
+                None => {
+                    let idx = dfa.add_state(ts.state_color(e.target()).expect("State must exist"));
+                    indices.insert(e.target(), idx);
+                    queue.push_back(e.target());
+                    idx
+                }
+            };
+            dfa.add_edge(indices[&q], *e.expression(), target, Void);
+        }
+    }
+    dfa
+}
+
+/// Returns a copy of `dfa` in which all missing transitions lead to a new rejecting sink. This
+/// does not change the language, but negating the acceptance of an incomplete DFA would not
+/// complement it, as the words that get stuck would stay rejected.
+fn complete_dfa(dfa: &DFA) -> DFA {
+    let missing = dfa.missing_transitions().collect::<Vec<_>>();
+    let mut complete = dfa.clone();
+    if !missing.is_empty() {
+        let sink = complete.add_state(false);
+        for (q, sym) in missing {
+            complete.add_edge(q, sym, sink, Void);
+        }
+        for sym in dfa.alphabet().universe() {
+            complete.add_edge(sink, sym, sink, Void);
+        }
+    }
+    complete
+}
+
+/// Converts an automaton from one format into another. HOA input is read as a DPA if it is
+/// deterministic and as an NBA otherwise, and it can be written as DOT, TikZ or HOA.
+pub fn convert(matches: &ArgMatches) -> anyhow::Result<()> {
+    let from = input_format(matches, "input")?;
+    let to = output_format(matches)?;
+    if from == Format::Hoa {
+        let aut = read_hoa(matches)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Input contains no HOA automaton"))?;
+        let output = match (aut, to) {
+            (HoaInput::Dpa(dpa, _), Format::Dot) => dpa.to_dot(),
+            (HoaInput::Dpa(dpa, _), Format::Tikz) => dpa.to_tikz(),
+            (HoaInput::Dpa(dpa, alphabet), Format::Hoa) => dpa
+                .to_hoa_automaton(&HoaEncoding::Propositional(&alphabet))
+                .map_err(|err| anyhow!("{err}"))?
+                .to_hoa(),
+            (HoaInput::Nba(nba, _), Format::Dot) => nba.with_initial(0).to_dot(),
+            (HoaInput::Nba(nba, _), Format::Tikz) => nba.with_initial(0).to_tikz(),
+            (HoaInput::Nba(nba, alphabet), Format::Hoa) => nba
+                .to_hoa_automaton(&HoaEncoding::Propositional(&alphabet))
+                .map_err(|err| anyhow!("{err}"))?
+                .to_hoa(),
+            (_, other) => {
+                return Err(anyhow!(
+                    "HOA automata can not be written in {other:?} format"
+                ))
+            }
+        };
+        return to_file_or_stdout(matches.get_one("output"), &output);
+    }
+    let dfa = read_input_dfa(matches, "input")?;
+    write_output_dfa(matches, &dfa)
+}
+
+/// Minimizes a DFA by computing the bisimulation quotient of its reachable part. For complete
+/// DFAs, this is the minimal DFA.
+pub fn minimize(matches: &ArgMatches) -> anyhow::Result<()> {
+    let dfa = read_input_dfa(matches, "input")?;
+    let reachable = reachable_dfa(&dfa, dfa.initial());
+    let (quotient, _) = reachable.bisimulation_quotient();
+    let minimized = reachable_dfa(&quotient, quotient.initial());
+    info!(
+        "Minimized DFA from {} to {} states",
+        dfa.size(),
+        minimized.size()
+    );
+    write_output_dfa(matches, &minimized)
+}
+
+/// Computes the product of two DFAs, which accepts the intersection of their languages or, if
+/// `--union` is given, the union. For the union, both DFAs are completed before they are
+/// negated.
+pub fn product(matches: &ArgMatches) -> anyhow::Result<()> {
+    let left = read_input_dfa(matches, "input")?;
+    let right = read_input_dfa(matches, "other")?;
+    if left.alphabet() != right.alphabet() {
+        return Err(anyhow!("Both DFAs must have the same alphabet"));
+    }
+    let product = if matches.get_flag("union") {
+        let left = complete_dfa(&left).negation().collect_dfa();
+        let right = complete_dfa(&right).negation().collect_dfa();
+        (&left)
+            .intersection(&right)
+            .into_dfa()
+            .negation()
+            .collect_dfa()
+    } else {
+        (&left).intersection(&right).into_dfa()
+    };
+    write_output_dfa(matches, &reachable_dfa(&product, product.initial()))
+}
+
+/// Complements a DFA, which is first completed with a rejecting sink if necessary.
+pub fn complement(matches: &ArgMatches) -> anyhow::Result<()> {
+    let dfa = read_input_dfa(matches, "input")?;
+    if !dfa.is_complete() {
+        debug!("Completing DFA with a rejecting sink before complementing it");
+    }
+    write_output_dfa(matches, &complete_dfa(&dfa).negation().collect_dfa())
+}
+
+/// Checks the language of a DFA for emptiness. Prints `empty` or a shortest accepted word and
+/// returns whether the language is empty.
+pub fn emptiness(matches: &ArgMatches) -> anyhow::Result<bool> {
+    let dfa = read_input_dfa(matches, "input")?;
+    match dfa.give_word() {
+        Some(word) => {
+            println!(
+                "nonempty, accepts \"{}\"",
+                word.into_iter().collect::<String>()
+            );
+            Ok(false)
+        }
+        None => {
+            println!("empty");
+            Ok(true)
+        }
+    }
+}
+
+/// Learns a DFA with RPNI from a sample in the Abbadingo (or StaMinA) format.
+pub fn learn_dfa(matches: &ArgMatches) -> anyhow::Result<()> {
+    let input = from_file_or_stdin(matches.get_one("input"));
+    let sample = if matches.get_flag("stamina") {
+        FiniteSample::from_stamina(&input)
+    } else {
+        FiniteSample::from_abbadingo(&input)
+    }
+    .map_err(|err| anyhow!("Could not parse sample: {err}"))?;
+    info!("Learning DFA from sample with {} words", sample.words.len());
+    write_output_dfa(matches, &dfa_rpni(&sample))
+}
+
+/// Learns a DPA from a sample of omega words, which is given in the format that is used by
+/// the `passive` subcommand. The result can be written as DOT, GraphML or TikZ.
+pub fn learn_omega(matches: &ArgMatches) -> anyhow::Result<()> {
+    let lines = from_file_or_stdin(matches.get_one("input"))
+        .lines()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    let sample =
+        OmegaSample::try_from(lines).map_err(|err| anyhow!("Could not parse sample: {err}"))?;
+    let dpa = dpa_rpni(&sample);
+    info!("Learned DPA with {} states", dpa.size());
+    let output = match output_format(matches)? {
+        Format::Dot => dpa.to_dot(),
+        Format::GraphMl => GraphMlGraph::from_ts(&dpa).to_graphml(),
+        Format::Tikz => dpa.to_tikz(),
+        other => return Err(anyhow!("DPAs can not be written in {other:?} format")),
+    };
+    to_file_or_stdout(matches.get_one("output"), &output)
+}
+
+/// Renders a DFA to an SVG, PNG or PDF file using graphviz.
+pub fn render(matches: &ArgMatches) -> anyhow::Result<()> {
+    let dfa = read_input_dfa(matches, "input")?;
+    let output = matches
+        .get_one::<String>("output")
+        .context("An output file is required for rendering")?;
+    dfa.render_to_file_with(
+        &DotOptions::new()
+            .show_colors(false)
+            .accepting(|c: &bool| *c),
+        output,
+    )
+    .map_err(|err| anyhow!("{err}"))
+}
@@ -1,6 +1,14 @@
-use std::io::Read;
+use std::{io::Read, path::Path};
 
-use automata::{automaton::DeterministicOmegaAutomaton, hoa::HoaAlphabet, TransitionSystem};
+use anyhow::anyhow;
+use automata::{prelude::*, TransitionSystem};
+use automata_learning::{
+    formats::{
+        hoa_to_dpa, hoa_to_nba, BaAutomaton, DotGraph, DotOptions, GraphMlGraph, HoaImportError,
+        JflapAutomaton, TikzOptions, ToDot, ToHoa, ToTikz,
+    },
+    propositional::PropositionalAlphabet,
+};
 use clap::ArgMatches;
 
 pub fn to_file_or_stdout(maybe_file_name: Option<&String>, output: &str) -> anyhow::Result<()> {
@@ -13,20 +21,37 @@ pub fn to_file_or_stdout(maybe_file_name: Option<&String>, output: &str) -> anyh
     }
 }
 
-pub fn hoa_deterministic(matches: &ArgMatches) -> Vec<DeterministicOmegaAutomaton<HoaAlphabet>> {
+/// An automaton that was read from HOA input, together with the alphabet of valuations of its
+/// atomic propositions.
+pub enum HoaInput {
+    /// A deterministic automaton with a Büchi, co-Büchi or parity condition.
+    Dpa(DPA, PropositionalAlphabet),
+    /// A nondeterministic Büchi automaton, whose initial state is 0.
+    Nba(NTS<CharAlphabet, Void, bool>, PropositionalAlphabet),
+}
+
+/// Reads all automata from the HOA input of the subcommand. Deterministic automata are read as
+/// DPAs and nondeterministic Büchi automata as NBAs, other automata are rejected.
+pub fn read_hoa(matches: &ArgMatches) -> anyhow::Result<Vec<HoaInput>> {
     let hoa = from_file_or_stdin(matches.get_one("input"));
-    let parsed_auts = automata::hoa::input::hoa_to_ts(&hoa);
     let mut auts = vec![];
-    for nondet_aut in &parsed_auts {
-        if let Some(aut) = nondet_aut.to_deterministic() {
-            tracing::debug!("Parsed HOA automaton with {} states", aut.size());
-            auts.push(aut);
-        } else {
-            tracing::error!("Input is not deterministic!");
+    for parsed in hoars::parse_hoa_automata(&hoa) {
+        let aut = match hoa_to_dpa(&parsed) {
+            Ok((dpa, alphabet)) => Ok(HoaInput::Dpa(dpa, alphabet)),
+            Err(HoaImportError::Nondeterministic) => {
+                hoa_to_nba(&parsed).map(|(nba, alphabet)| HoaInput::Nba(nba, alphabet))
+            }
+            Err(err) => Err(err),
+        }
+        .map_err(|err| anyhow!("Could not read HOA automaton: {err}"))?;
+        match &aut {
+            HoaInput::Dpa(dpa, _) => tracing::debug!("Parsed DPA with {} states", dpa.size()),
+            HoaInput::Nba(nba, _) => tracing::debug!("Parsed NBA with {} states", nba.size()),
         }
+        auts.push(aut);
     }
     tracing::info!("Parsed {} HOA automata", auts.len());
-    auts
+    Ok(auts)
 }
 
 pub fn from_file_or_stdin(maybe_file_name: Option<&String>) -> String {
@@ -42,3 +67,81 @@ pub fn from_file_or_stdin(maybe_file_name: Option<&String>) -> String {
         }
     }
 }
+
+/// The file formats that automata can be read from and written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Dot,
+    Jff,
+    GraphMl,
+    Ba,
+    Tikz,
+    Hoa,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 6] = ["dot", "jff", "graphml", "ba", "tikz", "hoa"];
+
+    pub fn from_name(name: &str) -> anyhow::Result<Self> {
+        match name.to_lowercase().as_str() {
+            "dot" | "gv" => Ok(Format::Dot),
+            "jff" | "jflap" => Ok(Format::Jff),
+            "graphml" => Ok(Format::GraphMl),
+            "ba" => Ok(Format::Ba),
+            "tikz" | "tex" => Ok(Format::Tikz),
+            "hoa" => Ok(Format::Hoa),
+            other => Err(anyhow!("Unknown format {other}")),
+        }
+    }
+
+    /// Determines the format from an explicitly given name or, if none is given, from the
+    /// extension of the file.
+    pub fn determine(name: Option<&String>, file: Option<&String>) -> Option<Self> {
+        match (name, file) {
+            (Some(name), _) => Format::from_name(name).ok(),
+            (None, Some(file)) => Path::new(file)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(|ext| Format::from_name(ext).ok()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Reads a DFA in the given format.
+pub fn read_dfa(input: &str, format: Format) -> anyhow::Result<DFA> {
+    match format {
+        Format::Dot => DotGraph::try_from(input)
+            .and_then(|graph| graph.into_dfa())
+            .map_err(|err| anyhow!("{err}")),
+        Format::Jff => JflapAutomaton::try_from(input)
+            .and_then(|aut| aut.into_dfa())
+            .map_err(|err| anyhow!("{err}")),
+        Format::GraphMl => GraphMlGraph::try_from(input)
+            .and_then(|graph| graph.into_dfa())
+            .map_err(|err| anyhow!("{err}")),
+        Format::Ba => BaAutomaton::try_from(input)
+            .and_then(|aut| aut.into_dfa())
+            .map_err(|err| anyhow!("{err}")),
+        Format::Tikz | Format::Hoa => Err(anyhow!("{format:?} is not supported for DFAs")),
+    }
+    .map_err(|err| anyhow!("Could not read DFA: {err}"))
+}
+
+/// Writes a DFA in the given format.
+pub fn write_dfa(dfa: &DFA, format: Format) -> anyhow::Result<String> {
+    Ok(match format {
+        Format::Dot => dfa.to_dot_with(
+            &DotOptions::new()
+                .show_colors(false)
+                .accepting(|c: &bool| *c),
+        ),
+        Format::Jff => JflapAutomaton::from_acceptor(dfa).to_jff(),
+        Format::GraphMl => GraphMlGraph::from_acceptor(dfa).to_graphml(),
+        Format::Ba => BaAutomaton::from_acceptor(dfa)
+            .ok_or_else(|| anyhow!("DFA has no initial state"))?
+            .to_ba(),
+        Format::Tikz => dfa.to_tikz_with(&TikzOptions::new().show_colors(false).accepting(|c| *c)),
+        Format::Hoa => dfa.to_hoa().map_err(|err| anyhow!("{err}"))?,
+    })
+}
@@ -4,11 +4,10 @@ use automata_learning::passive::{
     OmegaSample,
 };
 use clap::{command, Arg, ArgAction, Command};
-use io::{hoa_deterministic, to_file_or_stdout};
+use io::{read_hoa, to_file_or_stdout};
 use tracing::{debug, error, Level};
 
-use crate::io::from_file_or_stdin;
-
+mod commands;
 mod io;
 
 fn conflicts_arg() -> Arg {
@@ -18,6 +17,48 @@ fn conflicts_arg() -> Arg {
         .help("filename to output information on conflict relation")
 }
 
+fn input_arg() -> Arg {
+    Arg::new("input")
+        .short('i')
+        .long("input")
+        .help("Input file, stdin is used if none is given")
+}
+
+fn output_arg() -> Arg {
+    Arg::new("output")
+        .short('o')
+        .long("output")
+        .help("Output file, stdout is used if none is given")
+}
+
+fn from_arg() -> Arg {
+    Arg::new("input-format")
+        .short('f')
+        .long("from")
+        .help(format!(
+            "Format of the input, one of {}. Inferred from the file extension if not given and defaults to dot",
+            io::Format::NAMES.join(", ")
+        ))
+}
+
+fn to_arg() -> Arg {
+    Arg::new("to")
+        .short('t')
+        .long("to")
+        .help(format!(
+            "Format of the output, one of {}. Inferred from the file extension if not given and defaults to dot",
+            io::Format::NAMES.join(", ")
+        ))
+}
+
+/// Exits with a non-zero exit code if the subcommand failed.
+fn exit_on_error(result: anyhow::Result<()>) {
+    if let Err(err) = result {
+        error!("{err}");
+        std::process::exit(1);
+    }
+}
+
 fn main() {
     let matches = command!()
         .arg(
@@ -60,6 +101,69 @@ fn main() {
             Command::new("nop")
                 .arg(Arg::new("input").short('i').long("input"))
         )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert an automaton between the hoa, dot, ba, jff, graphml and tikz formats")
+                .arg(input_arg())
+                .arg(output_arg())
+                .arg(from_arg())
+                .arg(to_arg()),
+        )
+        .subcommand(
+            Command::new("minimize")
+                .about("Minimize a DFA")
+                .arg(input_arg())
+                .arg(output_arg())
+                .arg(from_arg())
+                .arg(to_arg()),
+        )
+        .subcommand(
+            Command::new("product")
+                .about("Compute the product of two DFAs, which accepts the intersection of their languages")
+                .arg(input_arg())
+                .arg(Arg::new("other").short('s').long("second").required(true).help("The second DFA"))
+                .arg(Arg::new("other-format").long("second-from").help("Format of the second DFA"))
+                .arg(Arg::new("union").short('u').long("union").action(ArgAction::SetTrue).help("Accept the union instead of the intersection"))
+                .arg(output_arg())
+                .arg(from_arg())
+                .arg(to_arg()),
+        )
+        .subcommand(
+            Command::new("complement")
+                .about("Complement a DFA")
+                .arg(input_arg())
+                .arg(output_arg())
+                .arg(from_arg())
+                .arg(to_arg()),
+        )
+        .subcommand(
+            Command::new("emptiness")
+                .about("Check whether the language of a DFA is empty, the exit code is 0 if it is and 2 otherwise")
+                .arg(input_arg())
+                .arg(from_arg()),
+        )
+        .subcommand(
+            Command::new("learn-dfa")
+                .about("Learn a DFA with RPNI from a sample in the Abbadingo format")
+                .arg(input_arg())
+                .arg(output_arg())
+                .arg(to_arg())
+                .arg(Arg::new("stamina").long("stamina").action(ArgAction::SetTrue).help("Read the sample in the StaMinA format")),
+        )
+        .subcommand(
+            Command::new("learn-omega")
+                .about("Learn a DPA from a sample of omega words")
+                .arg(input_arg())
+                .arg(output_arg())
+                .arg(to_arg()),
+        )
+        .subcommand(
+            Command::new("render")
+                .about("Render a DFA to an svg, png or pdf file using graphviz")
+                .arg(input_arg())
+                .arg(output_arg().required(true))
+                .arg(from_arg()),
+        )
         .subcommand_required(true)
         .get_matches();
 
@@ -78,28 +182,42 @@ fn main() {
     tracing::subscriber::set_global_default(subscriber).unwrap();
 
     match matches.subcommand() {
+        Some(("convert", sub_matches)) => exit_on_error(commands::convert(sub_matches)),
+        Some(("minimize", sub_matches)) => exit_on_error(commands::minimize(sub_matches)),
+        Some(("product", sub_matches)) => exit_on_error(commands::product(sub_matches)),
+        Some(("complement", sub_matches)) => exit_on_error(commands::complement(sub_matches)),
+        Some(("emptiness", sub_matches)) => match commands::emptiness(sub_matches) {
+            Ok(true) => std::process::exit(0),
+            Ok(false) => std::process::exit(2),
+            Err(err) => {
+                error!("{err}");
+                std::process::exit(1);
+            }
+        },
+        Some(("learn-dfa", sub_matches)) => exit_on_error(commands::learn_dfa(sub_matches)),
+        Some(("learn-omega", sub_matches)) => exit_on_error(commands::learn_omega(sub_matches)),
+        Some(("render", sub_matches)) => exit_on_error(commands::render(sub_matches)),
         Some(("tosample", sample_matches)) => {
-            let auts = hoa_deterministic(sample_matches);
+            let auts = match read_hoa(sample_matches) {
+                Ok(auts) => auts,
+                Err(err) => {
+                    error!("{err}");
+                    std::process::exit(1);
+                }
+            };
             if auts.len() > 1 {
                 tracing::error!("This operation supports only single inputs");
                 std::process::exit(-1);
             }
             std::process::exit(0)
         }
-        Some(("nop", nop_matches)) => {
-            let hoa = from_file_or_stdin(nop_matches.get_one("input"));
-            let parsed_auts = automata::hoa::input::hoa_to_ts(&hoa);
-            let mut auts = vec![];
-            for nondet_aut in &parsed_auts {
-                if let Some(aut) = nondet_aut.to_deterministic() {
-                    tracing::debug!("Parsed HOA automaton with {} states", aut.size());
-                    auts.push(aut);
-                } else {
-                    tracing::error!("Input is not deterministic!");
-                }
+        Some(("nop", nop_matches)) => match read_hoa(nop_matches) {
+            Ok(auts) => println!("Successfully parsed {} HOA automata", auts.len()),
+            Err(err) => {
+                error!("{err}");
+                std::process::exit(1);
             }
-            println!("Successfully parsed {} HOA automata", auts.len());
-        }
+        },
 
         Some(("passive", passive_matches)) => {
             let sample_lines: Vec<String> = match passive_matches.get_one::<String>("input") {
@@ -0,0 +1,281 @@
+use itertools::Itertools;
+
+use crate::{
+    AcceptanceAtom, AcceptanceCondition, AcceptanceInfo, AcceptanceName, Edge, HeaderItem,
+    HoaAutomaton, Id,
+};
+
+/// Classifies the acceptance condition of a [`HoaAutomaton`] into one of the types that
+/// can be represented by the omega automata in the `automata` crate. Note that state-based
+/// acceptance is pushed onto the outgoing edges during parsing, so the acceptance is always
+/// interpreted as transition-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OmegaAcceptanceType {
+    /// Büchi acceptance with the single acceptance set 0.
+    Buchi,
+    /// Co-Büchi acceptance with the single acceptance set 0.
+    CoBuchi,
+    /// A parity condition, `max` determines whether the least or greatest priority is
+    /// relevant and `even` whether even or odd priorities are good.
+    Parity {
+        /// Whether the maximal (instead of the minimal) priority is relevant.
+        max: bool,
+        /// Whether even (instead of odd) priorities are accepting.
+        even: bool,
+        /// The number of priorities.
+        priorities: usize,
+    },
+    /// Any other acceptance condition, which can currently not be imported.
+    Other,
+}
+
+impl OmegaAcceptanceType {
+    /// Returns `true` if the condition is a Büchi condition.
+    pub fn is_buchi(&self) -> bool {
+        matches!(self, OmegaAcceptanceType::Buchi)
+    }
+
+    /// Returns `true` if the condition is a parity condition.
+    pub fn is_parity(&self) -> bool {
+        matches!(self, OmegaAcceptanceType::Parity { .. })
+    }
+
+    /// Converts the acceptance signature of an edge into a priority in the min even parity
+    /// condition, which is what the `automata` crate uses. Büchi conditions are converted by
+    /// mapping edges in the acceptance set to 0 and all others to 1, co-Büchi conditions map
+    /// edges in the set to 1 and all others to 2. Returns `None` if the condition is not
+    /// supported or the signature is ill-formed, i.e. it contains more than one priority or a
+    /// priority that is not below the number of priorities of a parity condition.
+    pub fn min_even_priority(&self, edge: &Edge) -> Option<usize> {
+        let signature = edge.acceptance_signature();
+        match self {
+            OmegaAcceptanceType::Buchi => Some(if signature.contains(&0) { 0 } else { 1 }),
+            OmegaAcceptanceType::CoBuchi => Some(if signature.contains(&0) { 1 } else { 2 }),
+            OmegaAcceptanceType::Parity {
+                max,
+                even,
+                priorities,
+            } => {
+                // an edge without a priority is treated as the least significant priority, i.e.
+                // as `priorities` for min conditions and as -1 for max conditions
+                let priority = match signature.get_singleton()? {
+                    Some(p) if (p as usize) < *priorities => Some(p as usize),
+                    Some(_) => return None,
+                    None => None,
+                };
+                // for max conditions, we reverse the order by subtracting from an even upper
+                // bound, which preserves the parity of each priority
+                let bound = priorities.checked_add(priorities % 2)?;
+                let normalized = match (priority, *max) {
+                    (Some(p), true) => bound.checked_sub(p)?,
+                    (None, true) => bound.checked_add(1)?,
+                    (Some(p), false) => p,
+                    (None, false) => *priorities,
+                };
+                if *even {
+                    Some(normalized)
+                } else {
+                    normalized.checked_add(1)
+                }
+            }
+            OmegaAcceptanceType::Other => None,
+        }
+    }
+}
+
+/// Checks whether the condition is `Inf(0)`.
+fn is_buchi_condition(condition: &AcceptanceCondition) -> bool {
+    matches!(
+        condition,
+        AcceptanceCondition::Inf(AcceptanceAtom::Positive(0))
+    )
+}
+
+/// Checks whether the condition is `Fin(0)`.
+fn is_co_buchi_condition(condition: &AcceptanceCondition) -> bool {
+    matches!(
+        condition,
+        AcceptanceCondition::Fin(AcceptanceAtom::Positive(0))
+    )
+}
+
+impl HoaAutomaton {
+    /// Determines the [`OmegaAcceptanceType`] of the automaton. If an `acc-name` header is
+    /// present, it is used to determine the type. Otherwise, we try to recognize the
+    /// acceptance condition syntactically.
+    pub fn acceptance_type(&self) -> OmegaAcceptanceType {
+        let (sets, condition) = self.acceptance();
+        let name = self
+            .header()
+            .iter()
+            .find_map(|item| item.try_acceptance_name());
+
+        match name {
+            Some((AcceptanceName::Buchi, _)) => OmegaAcceptanceType::Buchi,
+            Some((AcceptanceName::CoBuchi, _)) => OmegaAcceptanceType::CoBuchi,
+            Some((AcceptanceName::Parity, info)) => {
+                let identifiers = info
+                    .iter()
+                    .filter_map(|i| match i {
+                        AcceptanceInfo::Identifier(ident) => Some(ident.as_str()),
+                        _ => None,
+                    })
+                    .collect_vec();
+                let priorities = info
+                    .iter()
+                    .find_map(|i| match i {
+                        AcceptanceInfo::Int(n) => Some(*n as usize),
+                        _ => None,
+                    })
+                    .unwrap_or(sets);
+                OmegaAcceptanceType::Parity {
+                    max: identifiers.contains(&"max"),
+                    even: !identifiers.contains(&"odd"),
+                    priorities,
+                }
+            }
+            Some(_) => OmegaAcceptanceType::Other,
+            None if sets == 1 && is_buchi_condition(&condition) => OmegaAcceptanceType::Buchi,
+            None if sets == 1 && is_co_buchi_condition(&condition) => OmegaAcceptanceType::CoBuchi,
+            None if condition == AcceptanceCondition::parity(sets as Id) => {
+                OmegaAcceptanceType::Parity {
+                    max: false,
+                    even: true,
+                    priorities: sets,
+                }
+            }
+            None => OmegaAcceptanceType::Other,
+        }
+    }
+
+    /// Returns `true` if the automaton is deterministic, meaning it has a single initial state,
+    /// every edge has a single target and the labels of the edges leaving a state are pairwise
+    /// disjoint.
+    pub fn is_deterministic(&self) -> bool {
+        let start = self
+            .header()
+            .iter()
+            .filter_map(|item| match item {
+                HeaderItem::Start(conjunction) => Some(conjunction),
+                _ => None,
+            })
+            .collect_vec();
+        if start.len() != 1 || start[0].get_singleton().is_none() {
+            return false;
+        }
+
+        self.body().iter().all(|state| {
+            state.edges().iter().all(|edge| edge.target().is_some())
+                && state
+                    .edges()
+                    .iter()
+                    .tuple_combinations()
+                    .all(|(left, right)| left.label().0.and(&right.label().0).is_false())
+        })
+    }
+
+    /// Returns `true` if the acceptance is state-based, i.e. all edges leaving a state have the
+    /// same acceptance signature. In this case the automaton can be imported with acceptance
+    /// on states.
+    pub fn has_state_based_acceptance(&self) -> bool {
+        self.body().iter().all(|state| {
+            state
+                .edges()
+                .iter()
+                .map(|edge| edge.acceptance_signature())
+                .all_equal()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HoaAutomaton;
+
+    use super::OmegaAcceptanceType;
+
+    #[test]
+    fn classify_buchi() {
+        let hoa = HoaAutomaton::try_from(
+            r#"HOA: v1
+            AP: 1 "a"
+            States: 2
+            Start: 0
+            acc-name: Buchi
+            Acceptance: 1 Inf(0)
+            --BODY--
+            State: 0 {0}
+             [0] 1
+             [!0] 0
+            State: 1
+             [0] 1
+             [!0] 0
+            --END--
+            "#,
+        )
+        .unwrap();
+        assert_eq!(hoa.acceptance_type(), OmegaAcceptanceType::Buchi);
+        assert!(hoa.is_deterministic());
+        assert!(hoa.has_state_based_acceptance());
+
+        let edge = &hoa.body()[0].edges()[0];
+        assert_eq!(hoa.acceptance_type().min_even_priority(edge), Some(0));
+    }
+
+    #[test]
+    fn classify_nondeterministic_parity() {
+        let hoa = HoaAutomaton::try_from(
+            r#"HOA: v1
+            AP: 1 "a"
+            States: 2
+            Start: 0
+            Acceptance: 3 Inf(0) | (Fin(1) & Inf(2))
+            --BODY--
+            State: 0
+             [0] 1 {1}
+             [t] 0 {2}
+            State: 1
+             [0] 1 {0}
+            --END--
+            "#,
+        )
+        .unwrap();
+        assert!(hoa.acceptance_type().is_parity());
+        assert!(!hoa.is_deterministic());
+        assert!(!hoa.has_state_based_acceptance());
+    }
+
+    #[test]
+    fn out_of_range_priorities() {
+        let hoa = HoaAutomaton::try_from(
+            r#"HOA: v1
+            AP: 1 "a"
+            States: 1
+            Start: 0
+            Acceptance: 2 Inf(0) | Fin(1)
+            --BODY--
+            State: 0
+             [0] 0 {1}
+             [!0] 0 {5}
+            --END--
+            "#,
+        )
+        .unwrap();
+        let edges = hoa.body()[0].edges();
+        let max_even = OmegaAcceptanceType::Parity {
+            max: true,
+            even: true,
+            priorities: 2,
+        };
+        assert_eq!(max_even.min_even_priority(&edges[0]), Some(1));
+        assert_eq!(max_even.min_even_priority(&edges[1]), None);
+
+        let min_odd = OmegaAcceptanceType::Parity {
+            max: false,
+            even: false,
+            priorities: 2,
+        };
+        assert_eq!(min_odd.min_even_priority(&edges[0]), Some(2));
+        assert_eq!(min_odd.min_even_priority(&edges[1]), None);
+    }
+}
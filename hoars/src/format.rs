@@ -36,6 +36,13 @@ pub type AtomicProposition = String;
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct AliasName(pub(crate) String);
 
+impl AliasName {
+    /// Creates the alias with the given name, which is written without the leading `@`.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self(name.into())
+    }
+}
+
 /// An acceptance atom can be used to build an acceptance condition,
 /// each atom is either a positive or a negative acceptance set
 /// identifier.
@@ -236,6 +243,8 @@ impl TryFrom<String> for AcceptanceName {
 
 /// Represents properties of an automaton. For more information
 /// see the documentation of the [HOA format](https://adl.github.io/hoaf/#properties).
+/// Properties that the format does not define, e.g. tool-specific ones, are kept as
+/// [`Property::Other`].
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[allow(missing_docs)]
 pub enum Property {
@@ -257,6 +266,7 @@ pub enum Property {
     Terminal,
     Tight,
     Colored,
+    Other(String),
 }
 
 impl TryFrom<String> for Property {
@@ -279,10 +289,10 @@ impl TryFrom<String> for Property {
             "weak" => Ok(Property::Weak),
             "very-weak" => Ok(Property::VeryWeak),
             "inherently-weak" => Ok(Property::InherentlyWeak),
-            "terminatl" => Ok(Property::Terminal),
+            "terminal" => Ok(Property::Terminal),
             "tight" => Ok(Property::Tight),
             "colored" => Ok(Property::Colored),
-            unknown => Err(format!("{} is not a valid property", unknown)),
+            _ => Ok(Property::Other(value)),
         }
     }
 }
@@ -1,11 +1,15 @@
 //! This crate provides a parser for the HOA format.
 // #![warn(missing_docs)]
 mod body;
+mod classify;
 mod format;
 mod header;
 pub mod input;
 mod lexer;
+pub mod ltl;
 pub mod output;
+#[cfg(feature = "spot")]
+pub mod spot;
 mod value;
 
 use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet};
@@ -51,7 +55,9 @@ pub use format::{
 };
 
 pub use body::{Body, Edge, Label, State};
+pub use classify::OmegaAcceptanceType;
 pub use header::{Header, HeaderItem};
+pub use ltl::{Ltl, LtlError};
 
 use itertools::Itertools;
 use lexer::Token;
@@ -298,6 +304,11 @@ impl HoaAutomaton {
     pub fn add_header_item(&mut self, item: HeaderItem) {
         self.header.push(item);
     }
+
+    /// Serializes the automaton into the HOA format, see [`output::to_hoa`].
+    pub fn to_hoa(&self) -> String {
+        output::to_hoa(self)
+    }
 }
 
 impl Default for HoaAutomaton {
@@ -0,0 +1,863 @@
+//! Parsing of LTL formulas and their translation into (generalized) Büchi automata.
+//!
+//! The syntax follows the one used by SPOT:
+//! - `true`, `false`, `1` and `0` are constants,
+//! - atomic propositions start with a lowercase letter or `_`, followed by lowercase letters,
+//!   digits and `_`. Other names can be given in double quotes, e.g. `"Req"`,
+//! - `!`/`~` is negation, `&`/`&&`/`/\` conjunction, `|`/`||`/`\/` disjunction, `->`/`=>`
+//!   implication and `<->`/`<=>` equivalence,
+//! - `X`, `F`/`<>` and `G`/`[]` are the unary temporal operators and `U`, `R`, `W` and `M` are
+//!   the (strong) until, (weak) release, weak until and strong release.
+//!
+//! Temporal operators are single uppercase letters, so `GFa` is read as `G F a`. Unary operators
+//! bind strongest, followed by the binary temporal operators, conjunction, disjunction,
+//! implication and equivalence. Implication and the binary temporal operators are right
+//! associative.
+//!
+//! The translation follows the tableau construction of Couvreur, which directly produces a
+//! transition-based generalized Büchi automaton with one acceptance set for each until
+//! subformula. The states of the automaton are sets of formulas in negation normal form that
+//! need to be satisfied by the remaining suffix of the word.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use itertools::Itertools;
+use tracing::trace;
+
+use crate::{
+    AcceptanceCondition, AcceptanceInfo, AcceptanceName, AcceptanceSignature, Body, Edge, Header,
+    HeaderItem, HoaAutomaton, Id, Label, Property, State, StateConjunction, ALPHABET, MAX_APS,
+    VARS,
+};
+
+/// Represents a formula of linear temporal logic.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(missing_docs)]
+pub enum Ltl {
+    True,
+    False,
+    Atom(String),
+    Not(Box<Ltl>),
+    And(Box<Ltl>, Box<Ltl>),
+    Or(Box<Ltl>, Box<Ltl>),
+    Implies(Box<Ltl>, Box<Ltl>),
+    Iff(Box<Ltl>, Box<Ltl>),
+    Next(Box<Ltl>),
+    Finally(Box<Ltl>),
+    Globally(Box<Ltl>),
+    Until(Box<Ltl>, Box<Ltl>),
+    Release(Box<Ltl>, Box<Ltl>),
+    WeakUntil(Box<Ltl>, Box<Ltl>),
+    StrongRelease(Box<Ltl>, Box<Ltl>),
+}
+
+/// Represents the errors that can occur when parsing or translating an LTL formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LtlError {
+    /// The input ended unexpectedly.
+    UnexpectedEnd,
+    /// An unexpected character was found at the given position.
+    UnexpectedCharacter(char, usize),
+    /// An unexpected token was found at the given position.
+    UnexpectedToken(String, usize),
+    /// The formula uses more atomic propositions than are supported by [`MAX_APS`].
+    TooManyPropositions(usize),
+}
+
+impl std::fmt::Display for LtlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LtlError::UnexpectedEnd => write!(f, "Unexpected end of formula"),
+            LtlError::UnexpectedCharacter(c, pos) => {
+                write!(f, "Unexpected character {c} at position {pos}")
+            }
+            LtlError::UnexpectedToken(token, pos) => {
+                write!(f, "Unexpected token {token} at position {pos}")
+            }
+            LtlError::TooManyPropositions(n) => write!(
+                f,
+                "Formula uses {n} atomic propositions, at most {MAX_APS} are supported"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LtlError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LtlToken {
+    LParen,
+    RParen,
+    Not,
+    And,
+    Or,
+    Implies,
+    Iff,
+    Unary(char),
+    Binary(char),
+    True,
+    False,
+    Atom(String),
+}
+
+impl std::fmt::Display for LtlToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LtlToken::LParen => write!(f, "("),
+            LtlToken::RParen => write!(f, ")"),
+            LtlToken::Not => write!(f, "!"),
+            LtlToken::And => write!(f, "&"),
+            LtlToken::Or => write!(f, "|"),
+            LtlToken::Implies => write!(f, "->"),
+            LtlToken::Iff => write!(f, "<->"),
+            LtlToken::Unary(op) | LtlToken::Binary(op) => write!(f, "{op}"),
+            LtlToken::True => write!(f, "true"),
+            LtlToken::False => write!(f, "false"),
+            LtlToken::Atom(name) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(LtlToken, usize)>, LtlError> {
+    let chars = input.chars().collect_vec();
+    let mut tokens = vec![];
+    let mut i = 0;
+    let starts_with = |i: usize, pattern: &str| {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(j, c)| chars.get(i + j) == Some(&c))
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let (token, length) = if let Some((token, pattern)) = [
+            (LtlToken::Iff, "<->"),
+            (LtlToken::Iff, "<=>"),
+            (LtlToken::Unary('F'), "<>"),
+            (LtlToken::Unary('G'), "[]"),
+            (LtlToken::Implies, "->"),
+            (LtlToken::Implies, "=>"),
+            (LtlToken::And, "&&"),
+            (LtlToken::And, "/\\"),
+            (LtlToken::Or, "||"),
+            (LtlToken::Or, "\\/"),
+        ]
+        .into_iter()
+        .find(|(_, pattern)| starts_with(i, pattern))
+        {
+            (token, pattern.len())
+        } else {
+            match c {
+                '(' => (LtlToken::LParen, 1),
+                ')' => (LtlToken::RParen, 1),
+                '!' | '~' => (LtlToken::Not, 1),
+                '&' => (LtlToken::And, 1),
+                '|' => (LtlToken::Or, 1),
+                '1' => (LtlToken::True, 1),
+                '0' => (LtlToken::False, 1),
+                'X' | 'F' | 'G' => (LtlToken::Unary(c), 1),
+                'U' | 'R' | 'W' | 'M' => (LtlToken::Binary(c), 1),
+                '"' => {
+                    let length = chars[i + 1..]
+                        .iter()
+                        .position(|c| *c == '"')
+                        .ok_or(LtlError::UnexpectedEnd)?;
+                    let name: String = chars[i + 1..i + 1 + length].iter().collect();
+                    (LtlToken::Atom(name), length + 2)
+                }
+                c if c.is_ascii_lowercase() || c == '_' => {
+                    let length = chars[i..]
+                        .iter()
+                        .take_while(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || **c == '_')
+                        .count();
+                    let name: String = chars[i..i + length].iter().collect();
+                    let token = match name.as_str() {
+                        "true" => LtlToken::True,
+                        "false" => LtlToken::False,
+                        _ => LtlToken::Atom(name),
+                    };
+                    (token, length)
+                }
+                c => return Err(LtlError::UnexpectedCharacter(c, i)),
+            }
+        };
+        tokens.push((token, i));
+        i += length;
+    }
+    Ok(tokens)
+}
+
+struct LtlParser {
+    tokens: Vec<(LtlToken, usize)>,
+    position: usize,
+}
+
+impl LtlParser {
+    fn peek(&self) -> Option<&LtlToken> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<LtlToken, LtlError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(token, _)| token.clone())
+            .ok_or(LtlError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn unexpected(&self) -> LtlError {
+        match self.tokens.get(self.position) {
+            Some((token, pos)) => LtlError::UnexpectedToken(token.to_string(), *pos),
+            None => LtlError::UnexpectedEnd,
+        }
+    }
+
+    fn iff(&mut self) -> Result<Ltl, LtlError> {
+        let mut left = self.implies()?;
+        while self.peek() == Some(&LtlToken::Iff) {
+            self.position += 1;
+            left = Ltl::Iff(Box::new(left), Box::new(self.implies()?));
+        }
+        Ok(left)
+    }
+
+    fn implies(&mut self) -> Result<Ltl, LtlError> {
+        let left = self.or()?;
+        if self.peek() == Some(&LtlToken::Implies) {
+            self.position += 1;
+            return Ok(Ltl::Implies(Box::new(left), Box::new(self.implies()?)));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Ltl, LtlError> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&LtlToken::Or) {
+            self.position += 1;
+            left = Ltl::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Ltl, LtlError> {
+        let mut left = self.binary()?;
+        while self.peek() == Some(&LtlToken::And) {
+            self.position += 1;
+            left = Ltl::And(Box::new(left), Box::new(self.binary()?));
+        }
+        Ok(left)
+    }
+
+    fn binary(&mut self) -> Result<Ltl, LtlError> {
+        let left = self.unary()?;
+        if let Some(LtlToken::Binary(op)) = self.peek().cloned() {
+            self.position += 1;
+            let (left, right) = (Box::new(left), Box::new(self.binary()?));
+            return Ok(match op {
+                'U' => Ltl::Until(left, right),
+                'R' => Ltl::Release(left, right),
+                'W' => Ltl::WeakUntil(left, right),
+                'M' => Ltl::StrongRelease(left, right),
+                _ => unreachable!("Binary operators are U, R, W and M"),
+            });
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Ltl, LtlError> {
+        match self.peek() {
+            Some(LtlToken::Not) => {
+                self.position += 1;
+                Ok(Ltl::Not(Box::new(self.unary()?)))
+            }
+            Some(LtlToken::Unary(op)) => {
+                let op = *op;
+                self.position += 1;
+                let inner = Box::new(self.unary()?);
+                Ok(match op {
+                    'X' => Ltl::Next(inner),
+                    'F' => Ltl::Finally(inner),
+                    'G' => Ltl::Globally(inner),
+                    _ => unreachable!("Unary operators are X, F and G"),
+                })
+            }
+            _ => self.atomic(),
+        }
+    }
+
+    fn atomic(&mut self) -> Result<Ltl, LtlError> {
+        if self.peek().is_none() {
+            return Err(LtlError::UnexpectedEnd);
+        }
+        let error = self.unexpected();
+        match self.next()? {
+            LtlToken::True => Ok(Ltl::True),
+            LtlToken::False => Ok(Ltl::False),
+            LtlToken::Atom(name) => Ok(Ltl::Atom(name)),
+            LtlToken::LParen => {
+                let inner = self.iff()?;
+                match self.next() {
+                    Ok(LtlToken::RParen) => Ok(inner),
+                    Ok(_) => {
+                        self.position -= 1;
+                        Err(self.unexpected())
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            _ => Err(error),
+        }
+    }
+}
+
+impl TryFrom<&str> for Ltl {
+    type Error = LtlError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut parser = LtlParser {
+            tokens: tokenize(value)?,
+            position: 0,
+        };
+        let formula = parser.iff()?;
+        if parser.peek().is_some() {
+            return Err(parser.unexpected());
+        }
+        Ok(formula)
+    }
+}
+
+impl std::str::FromStr for Ltl {
+    type Err = LtlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ltl::try_from(s)
+    }
+}
+
+impl std::fmt::Display for Ltl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Ltl::True => write!(f, "true"),
+            Ltl::False => write!(f, "false"),
+            Ltl::Atom(name) => {
+                let plain = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+                    && name != "true"
+                    && name != "false";
+                if plain {
+                    write!(f, "{name}")
+                } else {
+                    write!(f, "\"{name}\"")
+                }
+            }
+            Ltl::Not(inner) => write!(f, "!{inner}"),
+            Ltl::Next(inner) => write!(f, "X{inner}"),
+            Ltl::Finally(inner) => write!(f, "F{inner}"),
+            Ltl::Globally(inner) => write!(f, "G{inner}"),
+            Ltl::And(l, r) => write!(f, "({l} & {r})"),
+            Ltl::Or(l, r) => write!(f, "({l} | {r})"),
+            Ltl::Implies(l, r) => write!(f, "({l} -> {r})"),
+            Ltl::Iff(l, r) => write!(f, "({l} <-> {r})"),
+            Ltl::Until(l, r) => write!(f, "({l} U {r})"),
+            Ltl::Release(l, r) => write!(f, "({l} R {r})"),
+            Ltl::WeakUntil(l, r) => write!(f, "({l} W {r})"),
+            Ltl::StrongRelease(l, r) => write!(f, "({l} M {r})"),
+        }
+    }
+}
+
+/// A successor of a set of formulas, as computed by [`expand`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Branch {
+    literals: BTreeMap<String, bool>,
+    next: BTreeSet<Ltl>,
+    pending: BTreeSet<Ltl>,
+}
+
+/// Expands the formulas in `todo` (which must be in negation normal form) into the possible
+/// ways of satisfying them, each of which consists of the literals that must hold now, the
+/// formulas that must hold from the next position on and the until formulas whose right side
+/// was postponed.
+fn expand(mut todo: Vec<Ltl>, mut branch: Branch, out: &mut Vec<Branch>) {
+    let Some(formula) = todo.pop() else {
+        out.push(branch);
+        return;
+    };
+    match &formula {
+        Ltl::True => expand(todo, branch, out),
+        Ltl::False => {}
+        Ltl::Atom(name) => {
+            if branch.literals.insert(name.clone(), true) != Some(false) {
+                expand(todo, branch, out)
+            }
+        }
+        Ltl::Not(inner) => {
+            let Ltl::Atom(name) = inner.as_ref() else {
+                unreachable!("Formula must be in negation normal form")
+            };
+            if branch.literals.insert(name.clone(), false) != Some(true) {
+                expand(todo, branch, out)
+            }
+        }
+        Ltl::And(l, r) => {
+            todo.push(*l.clone());
+            todo.push(*r.clone());
+            expand(todo, branch, out)
+        }
+        Ltl::Or(l, r) => {
+            let mut other = todo.clone();
+            other.push(*r.clone());
+            expand(other, branch.clone(), out);
+            todo.push(*l.clone());
+            expand(todo, branch, out)
+        }
+        Ltl::Next(inner) => {
+            branch.next.insert(*inner.clone());
+            expand(todo, branch, out)
+        }
+        Ltl::Until(l, r) => {
+            let mut fulfilled = todo.clone();
+            fulfilled.push(*r.clone());
+            expand(fulfilled, branch.clone(), out);
+            todo.push(*l.clone());
+            branch.next.insert(formula.clone());
+            branch.pending.insert(formula);
+            expand(todo, branch, out)
+        }
+        Ltl::Release(l, r) => {
+            let mut released = todo.clone();
+            released.push(*l.clone());
+            released.push(*r.clone());
+            expand(released, branch.clone(), out);
+            todo.push(*r.clone());
+            branch.next.insert(formula.clone());
+            expand(todo, branch, out)
+        }
+        _ => unreachable!("Formula must be in negation normal form"),
+    }
+}
+
+/// A generalized Büchi automaton as it is produced by the translation, before it is turned
+/// into a [`HoaAutomaton`]. State 0 is initial.
+struct Gba {
+    edges: Vec<Vec<(BTreeMap<String, bool>, usize, Vec<Id>)>>,
+    sets: usize,
+}
+
+impl Ltl {
+    /// Parses a formula, see the [module documentation](self) for the syntax.
+    pub fn parse(input: &str) -> Result<Self, LtlError> {
+        Ltl::try_from(input)
+    }
+
+    /// Returns the atomic propositions in the order of their first occurrence.
+    pub fn atoms(&self) -> Vec<String> {
+        let mut atoms = vec![];
+        self.collect_atoms(&mut atoms);
+        atoms
+    }
+
+    fn collect_atoms(&self, atoms: &mut Vec<String>) {
+        match self {
+            Ltl::True | Ltl::False => {}
+            Ltl::Atom(name) => {
+                if !atoms.contains(name) {
+                    atoms.push(name.clone());
+                }
+            }
+            Ltl::Not(inner) | Ltl::Next(inner) | Ltl::Finally(inner) | Ltl::Globally(inner) => {
+                inner.collect_atoms(atoms)
+            }
+            Ltl::And(l, r)
+            | Ltl::Or(l, r)
+            | Ltl::Implies(l, r)
+            | Ltl::Iff(l, r)
+            | Ltl::Until(l, r)
+            | Ltl::Release(l, r)
+            | Ltl::WeakUntil(l, r)
+            | Ltl::StrongRelease(l, r) => {
+                l.collect_atoms(atoms);
+                r.collect_atoms(atoms);
+            }
+        }
+    }
+
+    /// Transforms the formula into negation normal form, which only uses the constants, (negated)
+    /// atomic propositions, conjunction, disjunction, next, until and release. Negations are
+    /// pushed inwards until they are directly in front of atomic propositions.
+    pub fn nnf(&self) -> Ltl {
+        self.nnf_with(false)
+    }
+
+    fn nnf_with(&self, negate: bool) -> Ltl {
+        let b = |f: Ltl| Box::new(f);
+        match (self, negate) {
+            (Ltl::True, false) | (Ltl::False, true) => Ltl::True,
+            (Ltl::True, true) | (Ltl::False, false) => Ltl::False,
+            (Ltl::Atom(_), false) => self.clone(),
+            (Ltl::Atom(_), true) => Ltl::Not(b(self.clone())),
+            (Ltl::Not(inner), _) => inner.nnf_with(!negate),
+            (Ltl::And(l, r), false) | (Ltl::Or(l, r), true) => {
+                Ltl::And(b(l.nnf_with(negate)), b(r.nnf_with(negate)))
+            }
+            (Ltl::Or(l, r), false) | (Ltl::And(l, r), true) => {
+                Ltl::Or(b(l.nnf_with(negate)), b(r.nnf_with(negate)))
+            }
+            (Ltl::Implies(l, r), _) => Ltl::Or(b(Ltl::Not(l.clone())), r.clone()).nnf_with(negate),
+            (Ltl::Iff(l, r), _) => Ltl::Or(
+                b(Ltl::And(l.clone(), r.clone())),
+                b(Ltl::And(b(Ltl::Not(l.clone())), b(Ltl::Not(r.clone())))),
+            )
+            .nnf_with(negate),
+            (Ltl::Next(inner), _) => Ltl::Next(b(inner.nnf_with(negate))),
+            (Ltl::Finally(inner), _) => Ltl::Until(b(Ltl::True), inner.clone()).nnf_with(negate),
+            (Ltl::Globally(inner), _) => {
+                Ltl::Release(b(Ltl::False), inner.clone()).nnf_with(negate)
+            }
+            (Ltl::Until(l, r), false) | (Ltl::Release(l, r), true) => {
+                Ltl::Until(b(l.nnf_with(negate)), b(r.nnf_with(negate)))
+            }
+            (Ltl::Release(l, r), false) | (Ltl::Until(l, r), true) => {
+                Ltl::Release(b(l.nnf_with(negate)), b(r.nnf_with(negate)))
+            }
+            // l W r is equivalent to r R (r | l)
+            (Ltl::WeakUntil(l, r), _) => {
+                Ltl::Release(r.clone(), b(Ltl::Or(r.clone(), l.clone()))).nnf_with(negate)
+            }
+            // l M r is equivalent to r U (l & r)
+            (Ltl::StrongRelease(l, r), _) => {
+                Ltl::Until(r.clone(), b(Ltl::And(l.clone(), r.clone()))).nnf_with(negate)
+            }
+        }
+    }
+
+    fn collect_untils(&self, untils: &mut Vec<Ltl>) {
+        match self {
+            Ltl::Until(l, r) => {
+                if !untils.contains(self) {
+                    untils.push(self.clone());
+                }
+                l.collect_untils(untils);
+                r.collect_untils(untils);
+            }
+            Ltl::And(l, r) | Ltl::Or(l, r) | Ltl::Release(l, r) => {
+                l.collect_untils(untils);
+                r.collect_untils(untils);
+            }
+            Ltl::Not(inner) | Ltl::Next(inner) => inner.collect_untils(untils),
+            _ => {}
+        }
+    }
+
+    fn gba(&self) -> Gba {
+        let nnf = self.nnf();
+        let mut untils = vec![];
+        nnf.collect_untils(&mut untils);
+
+        let initial: BTreeSet<Ltl> = [nnf].into_iter().collect();
+        let mut states = vec![initial.clone()];
+        let mut indices: BTreeMap<BTreeSet<Ltl>, usize> = [(initial, 0)].into_iter().collect();
+        let mut queue: VecDeque<usize> = [0].into_iter().collect();
+        let mut edges = vec![];
+        while let Some(source) = queue.pop_front() {
+            let mut branches = vec![];
+            expand(
+                states[source].iter().cloned().collect(),
+                Branch {
+                    literals: BTreeMap::new(),
+                    next: BTreeSet::new(),
+                    pending: BTreeSet::new(),
+                },
+                &mut branches,
+            );
+            let mut outgoing = vec![];
+            for branch in branches.into_iter().sorted().dedup() {
+                let target = *indices.entry(branch.next.clone()).or_insert_with(|| {
+                    states.push(branch.next.clone());
+                    queue.push_back(states.len() - 1);
+                    states.len() - 1
+                });
+                let accepting = untils
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, u)| !branch.pending.contains(u))
+                    .map(|(i, _)| i as Id)
+                    .collect_vec();
+                outgoing.push((branch.literals, target, accepting));
+            }
+            // states are processed in the order of their indices
+            edges.push(outgoing);
+        }
+        trace!(
+            "Translated {} into GBA with {} states and {} acceptance sets",
+            self,
+            states.len(),
+            untils.len()
+        );
+        Gba {
+            edges,
+            sets: untils.len(),
+        }
+    }
+
+    /// Translates the formula into a transition-based generalized Büchi automaton, which has
+    /// one acceptance set for each until subformula of the negation normal form. If there is at
+    /// most one such subformula, the automaton uses a Büchi condition instead.
+    pub fn to_gba(&self) -> Result<HoaAutomaton, LtlError> {
+        let gba = self.gba();
+        self.to_hoa_automaton(gba)
+    }
+
+    /// Translates the formula into a transition-based Büchi automaton by degeneralizing the
+    /// automaton computed by [`Ltl::to_gba`] with a counter that tracks the next acceptance set
+    /// that needs to be visited.
+    pub fn to_nba(&self) -> Result<HoaAutomaton, LtlError> {
+        let gba = self.gba();
+        if gba.sets <= 1 {
+            return self.to_hoa_automaton(gba);
+        }
+
+        let mut states = vec![(0, 0)];
+        let mut indices: BTreeMap<(usize, usize), usize> = [((0, 0), 0)].into_iter().collect();
+        let mut edges = vec![];
+        let mut position = 0;
+        while position < states.len() {
+            let (q, level) = states[position];
+            let mut outgoing = vec![];
+            for (literals, target, accepting) in &gba.edges[q] {
+                let mut next = level;
+                while next < gba.sets && accepting.contains(&(next as Id)) {
+                    next += 1;
+                }
+                let (next, signature) = if next == gba.sets {
+                    (0, vec![0])
+                } else {
+                    (next, vec![])
+                };
+                let target = *indices.entry((*target, next)).or_insert_with(|| {
+                    states.push((*target, next));
+                    states.len() - 1
+                });
+                outgoing.push((literals.clone(), target, signature));
+            }
+            edges.push(outgoing);
+            position += 1;
+        }
+        self.to_hoa_automaton(Gba { edges, sets: 1 })
+    }
+
+    fn to_hoa_automaton(&self, gba: Gba) -> Result<HoaAutomaton, LtlError> {
+        let atoms = self.atoms();
+        if atoms.len() > MAX_APS {
+            return Err(LtlError::TooManyPropositions(atoms.len()));
+        }
+        let (name, infos, sets, condition) = match gba.sets {
+            0 | 1 => (
+                AcceptanceName::Buchi,
+                vec![],
+                1,
+                AcceptanceCondition::buchi(),
+            ),
+            n => (
+                AcceptanceName::GeneralizedBuchi,
+                vec![AcceptanceInfo::Int(n as Id)],
+                n,
+                (1..n as Id).fold(AcceptanceCondition::id_inf(0), |acc, i| {
+                    acc.and(AcceptanceCondition::id_inf(i))
+                }),
+            ),
+        };
+        let header = Header::from_vec(vec![
+            HeaderItem::v1(),
+            // quoted propositions would end the quoted name early
+            HeaderItem::Name(self.to_string().replace('"', "'")),
+            HeaderItem::Tool("lama".to_string(), None),
+            HeaderItem::States(gba.edges.len() as Id),
+            HeaderItem::Start(StateConjunction::singleton(0)),
+            HeaderItem::AP(atoms.clone()),
+            HeaderItem::AcceptanceName(name, infos),
+            HeaderItem::Acceptance(sets as Id, condition),
+            HeaderItem::Properties(vec![
+                Property::TransLabels,
+                Property::ExplicitLabels,
+                Property::TransitionAcceptance,
+            ]),
+        ]);
+        let body = gba
+            .edges
+            .into_iter()
+            .enumerate()
+            .map(|(q, outgoing)| {
+                let edges = outgoing
+                    .into_iter()
+                    .map(|(literals, target, accepting)| {
+                        let label = literals.iter().fold(ALPHABET.mk_true(), |acc, (ap, val)| {
+                            let var = VARS[atoms.iter().position(|a| a == ap).unwrap()];
+                            if *val {
+                                acc.and(&ALPHABET.mk_var(var))
+                            } else {
+                                acc.and(&ALPHABET.mk_not_var(var))
+                            }
+                        });
+                        // without until subformulas every transition is accepting
+                        let signature = if gba.sets == 0 { vec![0] } else { accepting };
+                        Edge::from_parts(
+                            Label(label),
+                            StateConjunction::singleton(target as Id),
+                            AcceptanceSignature(signature),
+                        )
+                    })
+                    .collect();
+                State::from_parts(q as Id, None, edges)
+            })
+            .collect_vec();
+        Ok(HoaAutomaton::from_parts(header, Body::from(body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use biodivine_lib_bdd::BddValuation;
+    use itertools::Itertools;
+
+    use super::{Ltl, LtlError};
+    use crate::{HoaAutomaton, MAX_APS};
+
+    /// Checks whether the automaton accepts the word `prefix` followed by `cycle` repeated
+    /// infinitely often, where each letter gives the set of atomic propositions that hold.
+    fn accepts_lasso(aut: &HoaAutomaton, prefix: &[&[usize]], cycle: &[&[usize]]) -> bool {
+        let word = prefix.iter().chain(cycle.iter()).collect_vec();
+        let sets = aut
+            .header()
+            .iter()
+            .find_map(|item| item.count_acceptance_sets())
+            .unwrap();
+        let successors = |(q, pos): (u32, usize)| {
+            let mut valuation = vec![false; MAX_APS];
+            for ap in word[pos].iter() {
+                valuation[*ap] = true;
+            }
+            let valuation = BddValuation::new(valuation);
+            let next = if pos + 1 < word.len() {
+                pos + 1
+            } else {
+                prefix.len()
+            };
+            aut.body()
+                .iter()
+                .find(|state| state.id() == q)
+                .unwrap()
+                .edges()
+                .iter()
+                .filter(|edge| edge.label().eval_in(&valuation))
+                .map(|edge| {
+                    (
+                        (edge.target().unwrap(), next),
+                        edge.acceptance_signature().0.clone(),
+                    )
+                })
+                .collect_vec()
+        };
+        let reach = |from: (u32, usize)| {
+            let mut seen = vec![];
+            let mut stack = successors(from).into_iter().map(|(n, _)| n).collect_vec();
+            while let Some(node) = stack.pop() {
+                if !seen.contains(&node) {
+                    seen.push(node);
+                    stack.extend(successors(node).into_iter().map(|(n, _)| n));
+                }
+            }
+            seen
+        };
+        let mut candidates = reach((0, 0));
+        candidates.push((0, 0));
+        candidates.into_iter().any(|node| {
+            let reachable = reach(node);
+            if !reachable.contains(&node) {
+                return false;
+            }
+            let scc = reachable
+                .into_iter()
+                .filter(|other| reach(*other).contains(&node))
+                .collect_vec();
+            let visited = scc
+                .iter()
+                .flat_map(|n| {
+                    successors(*n)
+                        .into_iter()
+                        .filter(|(target, _)| scc.contains(target))
+                        .flat_map(|(_, signature)| signature)
+                })
+                .collect_vec();
+            (0..sets as u32).all(|set| visited.contains(&set))
+        })
+    }
+
+    #[test]
+    fn ltl_parsing() {
+        let formula = Ltl::parse("GFa -> (b U !c) & X\"Req\"").unwrap();
+        assert_eq!(formula.to_string(), "(GFa -> ((b U !c) & X\"Req\"))");
+        assert_eq!(Ltl::parse(&formula.to_string()), Ok(formula.clone()));
+        assert_eq!(formula.atoms(), vec!["a", "b", "c", "Req"]);
+        assert_eq!(
+            Ltl::parse("[]<>a_1 || true").unwrap(),
+            Ltl::parse("(G F a_1) | 1").unwrap()
+        );
+        assert_eq!(Ltl::parse("a U"), Err(LtlError::UnexpectedEnd));
+        assert_eq!(
+            Ltl::parse("a b"),
+            Err(LtlError::UnexpectedToken("\"b\"".to_string(), 2))
+        );
+        assert_eq!(
+            Ltl::parse("a # b"),
+            Err(LtlError::UnexpectedCharacter('#', 2))
+        );
+    }
+
+    #[test]
+    fn ltl_nnf() {
+        let nnf = Ltl::parse("!(a U Gb)").unwrap().nnf();
+        assert_eq!(nnf.to_string(), "(!a R (true U !b))");
+        let nnf = Ltl::parse("!(a -> Xb)").unwrap().nnf();
+        assert_eq!(nnf.to_string(), "(a & X!b)");
+    }
+
+    #[test]
+    fn ltl_to_automata() {
+        let gfa = Ltl::parse("GFa").unwrap().to_nba().unwrap();
+        assert!(gfa.acceptance_type().is_buchi());
+        assert!(accepts_lasso(&gfa, &[], &[&[0], &[]]));
+        assert!(!accepts_lasso(&gfa, &[&[0]], &[&[]]));
+
+        let formula = Ltl::parse("GFa & GFb").unwrap();
+        let gba = formula.to_gba().unwrap();
+        let nba = formula.to_nba().unwrap();
+        assert!(nba.acceptance_type().is_buchi());
+        for aut in [&gba, &nba] {
+            assert!(accepts_lasso(aut, &[], &[&[0], &[1]]));
+            assert!(accepts_lasso(aut, &[&[]], &[&[0, 1]]));
+            assert!(!accepts_lasso(aut, &[&[1]], &[&[0]]));
+        }
+
+        let until = Ltl::parse("a U (b & X!a)").unwrap().to_nba().unwrap();
+        assert!(accepts_lasso(&until, &[&[0], &[1]], &[&[]]));
+        assert!(!accepts_lasso(&until, &[&[0], &[0, 1]], &[&[0]]));
+        assert!(!accepts_lasso(&until, &[], &[&[0]]));
+
+        let invariant = Ltl::parse("G(a -> X b)").unwrap().to_nba().unwrap();
+        assert!(accepts_lasso(&invariant, &[], &[&[0, 1]]));
+        assert!(!accepts_lasso(&invariant, &[&[0], &[]], &[&[1]]));
+
+        // the translation can be fed back into the parser
+        let reparsed = HoaAutomaton::try_from(nba.to_hoa().as_str()).unwrap();
+        assert!(accepts_lasso(&reparsed, &[], &[&[0], &[1]]));
+    }
+}
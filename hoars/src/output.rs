@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use biodivine_lib_bdd::Bdd;
 use itertools::Itertools;
 
 use crate::{
@@ -17,6 +18,47 @@ pub fn to_hoa(aut: &HoaAutomaton) -> String {
         .join("\n")
 }
 
+/// Writes a label expression in the syntax of the HOA format, which is a disjunctive normal
+/// form over the indices of the atomic propositions. The variables of the [`crate::ALPHABET`]
+/// correspond to the atomic propositions, so the `i`-th variable is printed as `i`.
+fn label_expression_to_hoa(expression: &Bdd) -> String {
+    if expression.is_true() {
+        return "t".to_string();
+    }
+    if expression.is_false() {
+        return "f".to_string();
+    }
+    let clauses = expression
+        .sat_clauses()
+        .map(|clause| {
+            clause
+                .to_values()
+                .into_iter()
+                .map(|(var, value)| {
+                    if value {
+                        format!("{}", var.to_index())
+                    } else {
+                        format!("!{}", var.to_index())
+                    }
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+    if clauses.len() == 1 {
+        return clauses[0].join(" & ");
+    }
+    clauses
+        .into_iter()
+        .map(|literals| {
+            if literals.len() == 1 {
+                literals[0].clone()
+            } else {
+                format!("({})", literals.join(" & "))
+            }
+        })
+        .join(" | ")
+}
+
 impl Display for HeaderItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -30,18 +72,31 @@ impl Display for HeaderItem {
                 aps.iter().map(|ap| format!("\"{}\"", ap)).join(" ")
             ),
             HeaderItem::Alias(alias_name, alias_expression) => {
-                write!(f, "Alias: {} {}", alias_name, alias_expression)
+                write!(
+                    f,
+                    "Alias: {} {}",
+                    alias_name,
+                    label_expression_to_hoa(alias_expression)
+                )
             }
             HeaderItem::Acceptance(number_sets, condition) => {
                 write!(f, "Acceptance: {} {}", number_sets, condition)
             }
             HeaderItem::AcceptanceName(identifier, vec_info) => {
-                write!(f, "acc-name: {} {}", identifier, vec_info.iter().join(" "))
+                write!(f, "acc-name: {}", identifier)?;
+                for info in vec_info {
+                    write!(f, " {}", info)?;
+                }
+                Ok(())
             }
-            HeaderItem::Tool(name, options) => {
-                write!(f, "tool: {} {}", name, options.iter().join(" "))
+            HeaderItem::Tool(name, version) => {
+                write!(f, "tool: \"{}\"", name)?;
+                if let Some(version) = version {
+                    write!(f, " \"{}\"", version)?;
+                }
+                Ok(())
             }
-            HeaderItem::Name(name) => write!(f, "name: {}", name),
+            HeaderItem::Name(name) => write!(f, "name: \"{}\"", name),
             HeaderItem::Properties(properties) => {
                 write!(f, "properties: {}", properties.iter().join(" "))
             }
@@ -74,7 +129,7 @@ impl Display for Property {
                 Property::NoUniversalBranching => "no-univ-branch",
                 Property::Deterministic => "deterministic",
                 Property::Complete => "complete",
-                Property::Unambiguous => "unabmiguous",
+                Property::Unambiguous => "unambiguous",
                 Property::StutterInvariant => "stutter-invariant",
                 Property::Weak => "weak",
                 Property::VeryWeak => "very-weak",
@@ -82,6 +137,7 @@ impl Display for Property {
                 Property::Terminal => "terminal",
                 Property::Tight => "tight",
                 Property::Colored => "colored",
+                Property::Other(name) => name.as_str(),
             }
         )
     }
@@ -149,7 +205,7 @@ impl Display for StateConjunction {
 
 impl Display for Label {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}]", self.0)
+        write!(f, "[{}]", label_expression_to_hoa(&self.0))
     }
 }
 
@@ -164,7 +220,11 @@ impl Display for AcceptanceSignature {
 
 impl Display for Edge {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {} {}", self.0, self.1, self.2)
+        if self.2.is_empty() {
+            write!(f, "{} {}", self.0, self.1)
+        } else {
+            write!(f, "{} {} {}", self.0, self.1, self.2)
+        }
     }
 }
 
@@ -181,3 +241,102 @@ impl Display for State {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::HoaAutomaton;
+
+    /// Parses the given automaton, prints it and parses the output again. Both parsed
+    /// automata must coincide.
+    fn round_trip(input: &str) {
+        let parsed = HoaAutomaton::try_from(input).unwrap();
+        let printed = parsed.to_hoa();
+        let reparsed = match HoaAutomaton::try_from(printed.as_str()) {
+            Ok(aut) => aut,
+            Err(e) => panic!("Could not parse output\n{}\n{}", printed, e),
+        };
+        assert_eq!(parsed, reparsed, "Round trip failed for\n{}", printed);
+    }
+
+    #[test]
+    fn round_trip_buchi() {
+        round_trip(
+            r#"HOA: v1
+            name: "GFa"
+            tool: "ltl2tgba" "2.10"
+            AP: 1 "a"
+            States: 2
+            Start: 0
+            acc-name: Buchi
+            Acceptance: 1 Inf(0)
+            properties: trans-labels explicit-labels state-acc
+            --BODY--
+            State: 0 "initial"
+             [0] 1
+             [!0] 0
+            State: 1 {0}
+             [0] 1
+             [!0] 0
+            --END--
+            "#,
+        );
+    }
+
+    #[test]
+    fn round_trip_parity_with_aliases() {
+        round_trip(
+            r#"HOA: v1
+            AP: 3 "a" "b" "c"
+            Alias: @ab 0 & 1
+            States: 2
+            Start: 0
+            acc-name: parity min even 3
+            Acceptance: 3 Inf(0) | (Fin(1) & Inf(2))
+            --BODY--
+            State: 0
+             [0 & !1 | 2] 1 {1}
+             [!0 & 1 & !2] 0 {2}
+             [t] 0
+            State: 1
+             [(0 | 1) & !2] 1 {0}
+             [f] 0
+            --END--
+            "#,
+        );
+    }
+
+    #[test]
+    fn labels_are_printed_in_hoa_syntax() {
+        let aut = HoaAutomaton::try_from(
+            r#"HOA: v1
+            AP: 2 "a" "b"
+            States: 1
+            Start: 0
+            Acceptance: 0 t
+            --BODY--
+            State: 0
+             [0 & 1] 0
+            --END--
+            "#,
+        )
+        .unwrap();
+        assert!(aut.to_hoa().contains("[0 & 1] 0"));
+    }
+
+    #[test]
+    fn round_trip_tool_specific_properties() {
+        round_trip(
+            r#"HOA: v1
+            AP: 1 "a"
+            States: 1
+            Start: 0
+            Acceptance: 1 Inf(0)
+            properties: deterministic finite-word unambiguous terminal
+            --BODY--
+            State: 0
+             [0] 0 {0}
+            --END--
+            "#,
+        );
+    }
+}
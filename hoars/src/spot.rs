@@ -0,0 +1,198 @@
+//! Typed wrappers around the command line tools of [SPOT](https://spot.lre.epita.fr/), namely
+//! `ltl2tgba` and `autfilt`. Automata are exchanged in the HOA format, so every function takes
+//! and returns [`HoaAutomaton`]s. The tools are looked up in the `PATH`, unless the environment
+//! variable `SPOT_BIN` is set, in which case it is used as the directory containing them.
+//!
+//! Parity automata that are produced by these functions use the `min even` parity condition,
+//! which is the one that is used throughout the `automata` crate.
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use tracing::debug;
+
+use crate::{FromHoaError, HoaAutomaton};
+
+/// Represents the errors that can occur when invoking one of the SPOT tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpotError {
+    /// The tool could not be started, most likely because SPOT is not installed.
+    NotInstalled(String),
+    /// Communicating with the tool failed.
+    Io(String),
+    /// The tool exited with an error, contains the name of the tool and its error output.
+    Tool(String, String),
+    /// The output of the tool could not be parsed.
+    Parse(FromHoaError),
+}
+
+impl std::fmt::Display for SpotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpotError::NotInstalled(tool) => write!(f, "Could not run {tool}, is SPOT installed?"),
+            SpotError::Io(err) => write!(f, "I/O error: {err}"),
+            SpotError::Tool(tool, err) => write!(f, "{tool} failed: {err}"),
+            SpotError::Parse(err) => write!(f, "Could not parse output of SPOT: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SpotError {}
+
+fn tool(name: &str) -> PathBuf {
+    match std::env::var_os("SPOT_BIN") {
+        Some(dir) => PathBuf::from(dir).join(name),
+        None => PathBuf::from(name),
+    }
+}
+
+/// Runs the tool `name` with the given arguments, feeding `input` to its standard input, and
+/// returns its standard output. Exit code 1 is not treated as an error, since SPOT uses it to
+/// signal that no automaton was output (for example by `autfilt --is-empty`).
+fn run(name: &str, args: &[&str], input: Option<&str>) -> Result<String, SpotError> {
+    let start = std::time::Instant::now();
+    let mut child = Command::new(tool(name))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|_| SpotError::NotInstalled(name.to_string()))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    if let Some(input) = input {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|err| SpotError::Io(err.to_string()))?;
+    }
+    drop(stdin);
+    let output = child
+        .wait_with_output()
+        .map_err(|err| SpotError::Io(err.to_string()))?;
+    if !matches!(output.status.code(), Some(0) | Some(1)) {
+        return Err(SpotError::Tool(
+            name.to_string(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    debug!(
+        "Running {name} {} took {}ms",
+        args.join(" "),
+        start.elapsed().as_millis()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn parse(name: &str, output: &str) -> Result<HoaAutomaton, SpotError> {
+    if !output.contains("--BODY--") {
+        return Err(SpotError::Tool(
+            name.to_string(),
+            "no automaton was produced".to_string(),
+        ));
+    }
+    HoaAutomaton::try_from(output).map_err(SpotError::Parse)
+}
+
+/// Returns `true` if `ltl2tgba` and `autfilt` can be executed.
+pub fn spot_available() -> bool {
+    ["ltl2tgba", "autfilt"]
+        .iter()
+        .all(|name| run(name, &["--version"], None).is_ok())
+}
+
+/// Runs `ltl2tgba` on `formula` with the given additional arguments and parses the result.
+pub fn ltl2tgba(formula: &str, args: &[&str]) -> Result<HoaAutomaton, SpotError> {
+    let mut all = vec!["-H", "-f", formula];
+    all.extend_from_slice(args);
+    parse("ltl2tgba", &run("ltl2tgba", &all, None)?)
+}
+
+/// Runs `autfilt` on `aut` with the given additional arguments. Returns `None` if `autfilt`
+/// filtered out the automaton, which happens for example if `--is-empty` is given and the
+/// automaton is not empty.
+pub fn autfilt(aut: &HoaAutomaton, args: &[&str]) -> Result<Option<HoaAutomaton>, SpotError> {
+    let mut all = vec!["-H"];
+    all.extend_from_slice(args);
+    let output = run("autfilt", &all, Some(&aut.to_hoa()))?;
+    if output.trim().is_empty() {
+        return Ok(None);
+    }
+    parse("autfilt", &output).map(Some)
+}
+
+/// Translates an LTL formula in SPOT syntax into a nondeterministic Büchi automaton with
+/// state-based acceptance.
+pub fn ltl_to_nba(formula: &str) -> Result<HoaAutomaton, SpotError> {
+    ltl2tgba(formula, &["-B", "--small"])
+}
+
+/// Translates an LTL formula in SPOT syntax into a deterministic parity automaton.
+pub fn ltl_to_dpa(formula: &str) -> Result<HoaAutomaton, SpotError> {
+    ltl2tgba(formula, &["-D", "-P", "--parity=min even", "--small"])
+}
+
+/// Minimizes a deterministic parity automaton as far as SPOT is able to, the result is again
+/// a deterministic parity automaton.
+pub fn spot_minimize(dpa: &HoaAutomaton) -> Result<HoaAutomaton, SpotError> {
+    autfilt(
+        dpa,
+        &["-D", "-P", "--parity=min even", "--small", "--tight"],
+    )?
+    .ok_or_else(|| {
+        SpotError::Tool(
+            "autfilt".to_string(),
+            "no automaton was produced".to_string(),
+        )
+    })
+}
+
+/// Determinizes an automaton into a deterministic parity automaton.
+pub fn spot_determinize(aut: &HoaAutomaton) -> Result<HoaAutomaton, SpotError> {
+    autfilt(aut, &["-D", "-P", "--parity=min even", "--generic"])?.ok_or_else(|| {
+        SpotError::Tool(
+            "autfilt".to_string(),
+            "no automaton was produced".to_string(),
+        )
+    })
+}
+
+/// Computes an automaton for the complement of the language of `aut`.
+pub fn spot_complement(aut: &HoaAutomaton) -> Result<HoaAutomaton, SpotError> {
+    autfilt(aut, &["--complement"])?.ok_or_else(|| {
+        SpotError::Tool(
+            "autfilt".to_string(),
+            "no automaton was produced".to_string(),
+        )
+    })
+}
+
+/// Checks whether the language of `aut` is empty.
+pub fn spot_is_empty(aut: &HoaAutomaton) -> Result<bool, SpotError> {
+    Ok(autfilt(aut, &["--is-empty"])?.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OmegaAcceptanceType;
+
+    #[test]
+    fn spot_round_trip() {
+        if !spot_available() {
+            return;
+        }
+        let nba = ltl_to_nba("GFa").unwrap();
+        assert!(nba.acceptance_type().is_buchi());
+        assert!(!spot_is_empty(&nba).unwrap());
+
+        let dpa = spot_minimize(&ltl_to_dpa("FGa | GFb").unwrap()).unwrap();
+        assert!(dpa.is_deterministic());
+        assert_ne!(dpa.acceptance_type(), OmegaAcceptanceType::Other);
+
+        let complement = spot_complement(&ltl_to_nba("G a").unwrap()).unwrap();
+        assert!(!spot_is_empty(&complement).unwrap());
+        assert!(spot_is_empty(&ltl_to_nba("a & !a").unwrap()).unwrap());
+    }
+}